@@ -2,8 +2,11 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::any::Any;
+use std::ops::Range;
 
 use crate::event::PointerButton;
+use crate::gesture::Gesture;
+use crate::widget::{CanvasPointerEvent, DockLayout, SortDirection};
 
 // TODO - Refactor - See issue https://github.com/linebender/xilem/issues/335
 
@@ -18,6 +21,20 @@ pub enum Action {
     TextChanged(String),
     TextEntered(String),
     CheckboxChecked(bool),
+    SplitPointChanged(f64),
+    TabSelected(usize),
+    TabCloseRequested(usize),
+    TabMoved(usize, usize),
+    TreeItemExpanded(bool),
+    TreeItemSelected(bool),
+    TableSortChanged(usize, SortDirection),
+    TableColumnResized(usize, f64),
+    TableColumnMoved(usize, usize),
+    TableRowSelected(usize),
+    TableRowsRequested(Range<usize>),
+    DockLayoutChanged(DockLayout),
+    CanvasPointer(CanvasPointerEvent),
+    Gesture(Gesture),
     // FIXME - This is a huge hack
     Other(Box<dyn Any + Send>),
 }
@@ -29,6 +46,26 @@ impl PartialEq for Action {
             (Self::TextChanged(l0), Self::TextChanged(r0)) => l0 == r0,
             (Self::TextEntered(l0), Self::TextEntered(r0)) => l0 == r0,
             (Self::CheckboxChecked(l0), Self::CheckboxChecked(r0)) => l0 == r0,
+            (Self::SplitPointChanged(l0), Self::SplitPointChanged(r0)) => l0 == r0,
+            (Self::TabSelected(l0), Self::TabSelected(r0)) => l0 == r0,
+            (Self::TabCloseRequested(l0), Self::TabCloseRequested(r0)) => l0 == r0,
+            (Self::TabMoved(l0, l1), Self::TabMoved(r0, r1)) => l0 == r0 && l1 == r1,
+            (Self::TreeItemExpanded(l0), Self::TreeItemExpanded(r0)) => l0 == r0,
+            (Self::TreeItemSelected(l0), Self::TreeItemSelected(r0)) => l0 == r0,
+            (Self::TableSortChanged(l0, l1), Self::TableSortChanged(r0, r1)) => {
+                l0 == r0 && l1 == r1
+            }
+            (Self::TableColumnResized(l0, l1), Self::TableColumnResized(r0, r1)) => {
+                l0 == r0 && l1 == r1
+            }
+            (Self::TableColumnMoved(l0, l1), Self::TableColumnMoved(r0, r1)) => {
+                l0 == r0 && l1 == r1
+            }
+            (Self::TableRowSelected(l0), Self::TableRowSelected(r0)) => l0 == r0,
+            (Self::TableRowsRequested(l0), Self::TableRowsRequested(r0)) => l0 == r0,
+            (Self::DockLayoutChanged(l0), Self::DockLayoutChanged(r0)) => l0 == r0,
+            (Self::CanvasPointer(l0), Self::CanvasPointer(r0)) => l0 == r0,
+            (Self::Gesture(l0), Self::Gesture(r0)) => l0 == r0,
             // FIXME
             // (Self::Other(val_l), Self::Other(val_r)) => false,
             _ => false,
@@ -43,6 +80,41 @@ impl std::fmt::Debug for Action {
             Self::TextChanged(text) => f.debug_tuple("TextChanged").field(text).finish(),
             Self::TextEntered(text) => f.debug_tuple("TextEntered").field(text).finish(),
             Self::CheckboxChecked(b) => f.debug_tuple("CheckboxChecked").field(b).finish(),
+            Self::SplitPointChanged(split_point) => f
+                .debug_tuple("SplitPointChanged")
+                .field(split_point)
+                .finish(),
+            Self::TabSelected(index) => f.debug_tuple("TabSelected").field(index).finish(),
+            Self::TabCloseRequested(index) => {
+                f.debug_tuple("TabCloseRequested").field(index).finish()
+            }
+            Self::TabMoved(from, to) => f.debug_tuple("TabMoved").field(from).field(to).finish(),
+            Self::TreeItemExpanded(b) => f.debug_tuple("TreeItemExpanded").field(b).finish(),
+            Self::TreeItemSelected(b) => f.debug_tuple("TreeItemSelected").field(b).finish(),
+            Self::TableSortChanged(column, direction) => f
+                .debug_tuple("TableSortChanged")
+                .field(column)
+                .field(direction)
+                .finish(),
+            Self::TableColumnResized(column, width) => f
+                .debug_tuple("TableColumnResized")
+                .field(column)
+                .field(width)
+                .finish(),
+            Self::TableColumnMoved(from, to) => f
+                .debug_tuple("TableColumnMoved")
+                .field(from)
+                .field(to)
+                .finish(),
+            Self::TableRowSelected(row) => f.debug_tuple("TableRowSelected").field(row).finish(),
+            Self::TableRowsRequested(rows) => {
+                f.debug_tuple("TableRowsRequested").field(rows).finish()
+            }
+            Self::DockLayoutChanged(layout) => {
+                f.debug_tuple("DockLayoutChanged").field(layout).finish()
+            }
+            Self::CanvasPointer(event) => f.debug_tuple("CanvasPointer").field(event).finish(),
+            Self::Gesture(gesture) => f.debug_tuple("Gesture").field(gesture).finish(),
             Self::Other(_) => write!(f, "Other(...)"),
         }
     }
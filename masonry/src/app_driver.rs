@@ -25,6 +25,12 @@ pub trait AppDriver {
     ///
     /// Use cases include loading fonts.
     fn on_start(&mut self, state: &mut MasonryState) {}
+
+    #[allow(unused_variables)]
+    /// A hook which will be executed when the application is about to exit.
+    ///
+    /// Use cases include saving the application's state.
+    fn on_exit(&mut self, state: &mut MasonryState) {}
 }
 
 impl<'a> DriverCtx<'a> {
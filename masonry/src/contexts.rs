@@ -3,20 +3,33 @@
 
 //! The context types that are passed into various widget methods.
 
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 use std::time::Duration;
 
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+#[cfg(target_arch = "wasm32")]
+use web_time::Instant;
+
 use accesskit::{NodeBuilder, TreeUpdate};
 use parley::{FontContext, LayoutContext};
 use tracing::{trace, warn};
-use vello::kurbo::Vec2;
+use vello::kurbo::{Affine, Vec2};
+use vello::peniko::BlendMode;
 
 use crate::action::Action;
-use crate::render_root::{MutateCallback, RenderRootSignal, RenderRootState};
+use crate::focus::FocusEntry;
+use crate::render_root::{MutateCallback, RenderRootSignal, RenderRootState, Timer, WakeQueue};
 use crate::text::TextBrush;
 use crate::text_helpers::{ImeChangeSignal, TextFieldRegistration};
 use crate::tree_arena::ArenaMutChildren;
 use crate::widget::{WidgetMut, WidgetState};
-use crate::{AllowRawMut, CursorIcon, Insets, Point, Rect, Size, Widget, WidgetId, WidgetPod};
+use crate::{
+    AllowRawMut, CursorIcon, FocusScopeOptions, Insets, Point, PointerId, Rect, Size, Widget,
+    WidgetId, WidgetPod,
+};
 
 /// A macro for implementing methods on multiple contexts.
 ///
@@ -58,6 +71,8 @@ pub struct EventCtx<'a> {
     pub(crate) widget_state_children: ArenaMutChildren<'a, WidgetState>,
     pub(crate) widget_children: ArenaMutChildren<'a, Box<dyn Widget>>,
     pub(crate) allow_pointer_capture: bool,
+    /// The pointer the current event comes from, if it is a pointer event.
+    pub(crate) pointer_id: Option<PointerId>,
     pub(crate) is_handled: bool,
     pub(crate) request_pan_to_child: Option<Rect>,
 }
@@ -128,6 +143,15 @@ impl_context_method!(
             self.widget_state.id
         }
 
+        /// The ratio between physical and logical pixels of the window.
+        ///
+        /// Layout and painting are done in logical pixels, and the scene is scaled by this
+        /// factor when it is rendered. Widgets can use it to lay out text or rasterize
+        /// images at the resolution of the screen.
+        pub fn scale_factor(&self) -> f64 {
+            self.global_state.scale_factor
+        }
+
         /// Skip iterating over the given child.
         ///
         /// Normally, container widgets are supposed to iterate over each of their
@@ -221,11 +245,24 @@ impl_context_method!(
             self.widget_state.window_origin()
         }
 
+        /// The transform from the widget's coordinate space to the window's.
+        ///
+        /// This includes the layer transforms of the widget and its ancestors, set with
+        /// [`ComposeCtx::set_child_transform`].
+        pub fn window_transform(&self) -> Affine {
+            self.widget_state.window_transform
+        }
+
         /// Convert a point from the widget's coordinate space to the window's.
         ///
         /// The returned point is relative to the content area; it excludes window chrome.
         pub fn to_window(&self, widget_point: Point) -> Point {
-            self.window_origin() + widget_point.to_vec2()
+            self.window_transform() * widget_point
+        }
+
+        /// Convert a point from the window's coordinate space to the widget's.
+        pub fn to_local(&self, window_point: Point) -> Point {
+            self.window_transform().inverse() * window_point
         }
     }
 );
@@ -260,8 +297,13 @@ impl_context_method!(
         }
 
         // TODO - remove
+        /// Whether the widget has captured any pointer.
         pub fn is_active(&self) -> bool {
-            self.global_state.pointer_capture_target == Some(self.widget_id())
+            let id = self.widget_id();
+            self.global_state
+                .pointer_captures
+                .values()
+                .any(|target| *target == id)
         }
 
         /// The focus status of a widget.
@@ -463,10 +505,14 @@ impl_context_method!(MutateCtx<'_>, EventCtx<'_>, LifeCycleCtx<'_>, {
     pub fn remove_child(&mut self, child: WidgetPod<impl Widget>) {
         // TODO - Send recursive event to child
         let id = child.id().to_raw();
-        let _ = self
+        let state = self
             .widget_state_children
             .remove_child(id)
             .expect("remove_child: child not found");
+        // The area the child was painted in has to be redrawn without it.
+        if let Some(painted_rect) = state.painted_rect {
+            self.global_state.damage.add_rect(painted_rect);
+        }
         let _ = self
             .widget_children
             .remove_child(id)
@@ -487,6 +533,31 @@ impl_context_method!(MutateCtx<'_>, EventCtx<'_>, LifeCycleCtx<'_>, {
         self.widget_state.is_explicitly_disabled = disabled;
     }
 
+    /// Set the position of this widget in tab order.
+    ///
+    /// Widgets with a positive tab index are reached first, in increasing order, then
+    /// widgets with a tab index of zero, which is the default, in tree order.
+    /// If this widget is a focus scope, this is the position of the whole scope.
+    ///
+    /// Tab indices are only compared between widgets of the same focus scope.
+    pub fn set_tab_index(&mut self, tab_index: u32) {
+        if self.widget_state.tab_index != tab_index {
+            self.widget_state.tab_index = tab_index;
+            self.widget_state.update_focus_chain = true;
+        }
+    }
+
+    /// Make this widget a focus scope, or stop it from being one if `scope` is `None`.
+    ///
+    /// The focusable descendants of a focus scope are grouped together in tab order.
+    /// Scopes can also trap focus or enable arrow key navigation, see [`FocusScopeOptions`].
+    pub fn set_focus_scope(&mut self, scope: Option<FocusScopeOptions>) {
+        if self.widget_state.focus_scope != scope {
+            self.widget_state.focus_scope = scope;
+            self.widget_state.update_focus_chain = true;
+        }
+    }
+
     #[allow(unused)]
     /// Indicate that text input state has changed.
     ///
@@ -549,10 +620,40 @@ impl_context_method!(
 
         /// Request a timer event.
         ///
-        /// The return value is a token, which can be used to associate the
-        /// request with the event.
-        pub fn request_timer(&mut self, _deadline: Duration) -> TimerToken {
-            todo!("request_timer");
+        /// Once `delay` has elapsed, the widget's [`on_timer`](Widget::on_timer) method
+        /// is called with the returned token, which can be used to tell timers apart.
+        pub fn request_timer(&mut self, delay: Duration) -> TimerToken {
+            trace!("request_timer {:?}", delay);
+            let token = TimerToken::next();
+            let deadline = self.global_state.now() + delay;
+            self.global_state.timers.push(Timer {
+                token,
+                widget_id: self.widget_state.id,
+                deadline,
+            });
+            token
+        }
+
+        /// Request a [`WidgetWaker`], which wakes the widget from any thread.
+        ///
+        /// Once the waker is woken, the widget's [`on_timer`](Widget::on_timer) method is
+        /// called with the waker's [token](WidgetWaker::token), as if a timer had expired.
+        /// This lets widgets wait for background work without polling.
+        pub fn request_waker(&mut self) -> WidgetWaker {
+            trace!("request_waker");
+            WidgetWaker {
+                token: TimerToken::next(),
+                widget_id: self.widget_state.id,
+                queue: self.global_state.wake_queue.clone(),
+            }
+        }
+
+        /// The current time, as seen by timers and animations.
+        ///
+        /// In tests, this is the clock of the [`TestHarness`](crate::testing::TestHarness),
+        /// which only moves forward when the test moves timers forward.
+        pub fn now(&self) -> Instant {
+            self.global_state.now()
         }
 
         /// Mark child widget as stashed.
@@ -573,13 +674,58 @@ impl_context_method!(
     }
 );
 
-// FIXME - Remove
-pub struct TimerToken;
+/// A token identifying a timer, returned by [`EventCtx::request_timer`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TimerToken(u64);
+
+impl TimerToken {
+    fn next() -> TimerToken {
+        static TIMER_TOKEN_COUNTER: AtomicU64 = AtomicU64::new(1);
+        TimerToken(TIMER_TOKEN_COUNTER.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+/// A handle which wakes a widget from any thread, returned by [`EventCtx::request_waker`].
+///
+/// Waking the widget calls its [`on_timer`](Widget::on_timer) method with the waker's token,
+/// on the event loop's thread. The waker can be woken several times.
+#[derive(Clone)]
+pub struct WidgetWaker {
+    token: TimerToken,
+    widget_id: WidgetId,
+    queue: Arc<WakeQueue>,
+}
+
+impl WidgetWaker {
+    /// The token the widget's [`on_timer`](Widget::on_timer) method is called with.
+    pub fn token(&self) -> TimerToken {
+        self.token
+    }
+
+    /// Wake the widget.
+    pub fn wake(&self) {
+        self.queue.wake(self.widget_id, self.token);
+    }
+}
+
+impl fmt::Debug for WidgetWaker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WidgetWaker")
+            .field("token", &self.token)
+            .field("widget_id", &self.widget_id)
+            .finish_non_exhaustive()
+    }
+}
 
 impl EventCtx<'_> {
-    // TODO - Document
     // TODO - Figure out cases where widget should be notified of pointer capture
     // loss
+    /// Capture the pointer of the current event, so that its events are sent to this widget
+    /// until it is released.
+    ///
+    /// This can only be called while handling a pointer down or pointer move. Capturing
+    /// during a move takes the pointer from whichever widget captured it, which lets
+    /// a scrolling container take over a drag which started on one of its children.
     #[track_caller]
     pub fn capture_pointer(&mut self) {
         debug_assert!(
@@ -588,15 +734,70 @@ impl EventCtx<'_> {
             self.widget_id().to_raw(),
         );
         // TODO: plumb pointer capture through to platform (through winit)
-        self.global_state.pointer_capture_target = Some(self.widget_state.id);
+        let pointer = self.pointer_id.unwrap_or(PointerId::Mouse);
+        self.global_state
+            .pointer_captures
+            .insert(pointer, self.widget_state.id);
     }
 
+    /// Release the capture of the pointer of the current event.
+    ///
+    /// Outside of pointer events, this releases every pointer captured by the widget.
     pub fn release_pointer(&mut self) {
-        self.global_state.pointer_capture_target = None;
+        match self.pointer_id {
+            Some(pointer) => {
+                self.global_state.pointer_captures.remove(&pointer);
+            }
+            None => {
+                let id = self.widget_state.id;
+                self.global_state
+                    .pointer_captures
+                    .retain(|_, target| *target != id);
+            }
+        }
     }
 
+    /// Whether the widget has captured the pointer of the current event.
+    ///
+    /// Outside of pointer events, this is the same as [`is_active`](Self::is_active).
     pub fn has_pointer_capture(&self) -> bool {
-        self.global_state.pointer_capture_target == Some(self.widget_state.id)
+        if self.pointer_id.is_some() {
+            self.pointer_capture_target() == Some(self.widget_state.id)
+        } else {
+            self.is_active()
+        }
+    }
+
+    /// The widget which captured the pointer of the current event, if any.
+    pub(crate) fn pointer_capture_target(&self) -> Option<WidgetId> {
+        self.global_state
+            .pointer_capture_target(self.pointer_id.unwrap_or(PointerId::Mouse))
+    }
+
+    /// Submit an [`Action`] on behalf of one of this widget's descendants.
+    ///
+    /// This is for containers which handle the input of their descendants,
+    /// such as [`Tree`](crate::widget::Tree) handling the selection of its items.
+    pub(crate) fn submit_descendant_action(&mut self, descendant: WidgetId, action: Action) {
+        trace!("submit_descendant_action descendant={:?}", descendant);
+        self.global_state
+            .signal_queue
+            .push_back(RenderRootSignal::Action(action, descendant));
+    }
+
+    /// Queue a callback that will be called with a [`WidgetMut`] for one of this widget's descendants.
+    ///
+    /// The callbacks will be run in the order they were submitted during the mutate pass.
+    pub(crate) fn mutate_descendant_later(
+        &mut self,
+        descendant: WidgetId,
+        f: impl FnOnce(WidgetMut<'_, Box<dyn Widget>>) + Send + 'static,
+    ) {
+        let callback = MutateCallback {
+            id: descendant,
+            callback: Box::new(f),
+        };
+        self.global_state.mutate_callbacks.push(callback);
     }
 
     /// Send a signal to parent widgets to scroll this widget into view.
@@ -607,9 +808,12 @@ impl EventCtx<'_> {
     // TODO - Remove
     pub fn set_active(&mut self, active: bool) {
         if active {
-            self.global_state.pointer_capture_target = Some(self.widget_state.id);
+            let pointer = self.pointer_id.unwrap_or(PointerId::Mouse);
+            self.global_state
+                .pointer_captures
+                .insert(pointer, self.widget_state.id);
         } else {
-            self.global_state.pointer_capture_target = None;
+            self.release_pointer();
         }
     }
 
@@ -679,7 +883,10 @@ impl LifeCycleCtx<'_> {
     /// [`LifeCycle::BuildFocusChain`]: crate::LifeCycle::BuildFocusChain
     pub fn register_for_focus(&mut self) {
         trace!("register_for_focus");
-        self.widget_state.focus_chain.push(self.widget_id());
+        self.widget_state.focus_chain.push(FocusEntry {
+            id: self.widget_id(),
+            tab_index: self.widget_state.tab_index,
+        });
     }
 
     /// Register this widget as accepting text input.
@@ -785,6 +992,17 @@ impl LayoutCtx<'_> {
         self.widget_state.baseline_offset = baseline;
     }
 
+    /// Declare a point, in this widget's coordinates, which the viewport of the
+    /// enclosing [`Portal`](crate::widget::Portal) can snap to.
+    ///
+    /// Once a scroll comes to rest, the portal aligns the top left corner of its viewport
+    /// with the nearest snap point, e.g. with the top of a list item.
+    /// Snap points must be declared again on every layout.
+    pub fn add_scroll_snap_point(&mut self, point: Point) {
+        trace!("add_scroll_snap_point {:?}", point);
+        self.widget_state.scroll_snap_points.push(point);
+    }
+
     /// Returns whether this widget needs to call [`WidgetPod::layout`]
     pub fn needs_layout(&self) -> bool {
         self.widget_state.needs_layout
@@ -813,6 +1031,22 @@ impl LayoutCtx<'_> {
         self.get_child_state(child).baseline_offset
     }
 
+    /// The scroll snap points declared by the given child and its descendants,
+    /// in the child's coordinates.
+    ///
+    /// This is meant for scrolling containers; other containers pass the snap points
+    /// of their children up to their own parent when placing them.
+    ///
+    /// ## Panics
+    ///
+    /// This method will panic if [`WidgetPod::layout`] has not been called yet for
+    /// the child.
+    #[track_caller]
+    pub fn child_scroll_snap_points(&self, child: &WidgetPod<impl Widget>) -> &[Point] {
+        self.assert_layout_done(child, "child_scroll_snap_points");
+        &self.get_child_state(child).scroll_snap_points
+    }
+
     /// Get the given child's layout rect.
     ///
     /// ## Panics
@@ -889,6 +1123,15 @@ impl LayoutCtx<'_> {
         self.widget_state.needs_paint = true;
     }
 
+    /// Request an animation frame.
+    ///
+    /// This is useful for widgets which start an animation in layout, such as an
+    /// animated image which was decoded at the size it is laid out at.
+    pub fn request_anim_frame(&mut self) {
+        trace!("request_anim_frame");
+        self.widget_state.request_anim = true;
+    }
+
     /// Set the position of a child widget, in the parent's coordinate space. This
     /// will also implicitly change "hot" status and affect the parent's display rect.
     ///
@@ -904,7 +1147,7 @@ impl LayoutCtx<'_> {
         self.assert_layout_done(child, "place_child");
         if origin != self.get_child_state_mut(child).origin {
             self.get_child_state_mut(child).origin = origin;
-            self.get_child_state_mut(child).translation_changed = true;
+            self.get_child_state_mut(child).layer_changed = true;
         }
         self.get_child_state_mut(child)
             .is_expecting_place_child_call = false;
@@ -913,6 +1156,20 @@ impl LayoutCtx<'_> {
             .widget_state
             .local_paint_rect
             .union(self.get_child_state(child).paint_rect());
+
+        // Portals snap their own viewport to the points of their descendants.
+        let child_state = self.get_child_state(child);
+        if !self.widget_state.is_portal
+            && !child_state.is_stashed
+            && !child_state.scroll_snap_points.is_empty()
+        {
+            let points: Vec<Point> = child_state
+                .scroll_snap_points
+                .iter()
+                .map(|point| *point + origin.to_vec2())
+                .collect();
+            self.widget_state.scroll_snap_points.extend(points);
+        }
     }
 }
 
@@ -932,7 +1189,52 @@ impl ComposeCtx<'_> {
         let child = self.get_child_state_mut(child);
         if translation != child.translation {
             child.translation = translation;
-            child.translation_changed = true;
+            child.layer_changed = true;
+        }
+    }
+
+    /// Set the transform of the child widget's layer.
+    ///
+    /// The transform is applied in the child's coordinate space, on top of its position
+    /// and translation, so it scales and rotates the child and its descendants around
+    /// the child's top left corner. Pointer events are hit-tested against the transformed
+    /// child.
+    ///
+    /// Unlike a change of layout, this doesn't repaint the child, so it is cheap to
+    /// animate.
+    pub fn set_child_transform<W: Widget>(&mut self, child: &mut WidgetPod<W>, transform: Affine) {
+        let child = self.get_child_state_mut(child);
+        if transform != child.transform {
+            child.transform = transform;
+            child.layer_changed = true;
+        }
+    }
+
+    /// Set the opacity the child widget and its descendants are composited with.
+    ///
+    /// `opacity` is clamped between 0 and 1. This doesn't repaint the child.
+    pub fn set_child_opacity<W: Widget>(&mut self, child: &mut WidgetPod<W>, opacity: f32) {
+        let opacity = opacity.clamp(0.0, 1.0);
+        let child = self.get_child_state_mut(child);
+        if opacity != child.opacity {
+            child.opacity = opacity;
+            child.layer_changed = true;
+        }
+    }
+
+    /// Set the blend mode the child widget and its descendants are composited with.
+    ///
+    /// This doesn't repaint the child.
+    pub fn set_child_blend_mode<W: Widget>(
+        &mut self,
+        child: &mut WidgetPod<W>,
+        blend_mode: impl Into<BlendMode>,
+    ) {
+        let blend_mode = blend_mode.into();
+        let child = self.get_child_state_mut(child);
+        if blend_mode != child.blend_mode {
+            child.blend_mode = blend_mode;
+            child.layer_changed = true;
         }
     }
 }
@@ -962,6 +1264,24 @@ impl AccessCtx<'_> {
     pub fn current_node(&mut self) -> &mut NodeBuilder {
         &mut self.current_node
     }
+
+    /// Add an accessibility node for a part of the widget which isn't a widget itself,
+    /// such as a [hit region](crate::widget::HitRegion) of a canvas.
+    ///
+    /// The node is added as a child of the widget's node. `bounds` is in the widget's
+    /// coordinate space, and `id` must not be used by any other node, so it should be
+    /// created with [`WidgetId::next`].
+    pub fn push_child_node(&mut self, id: WidgetId, mut node: NodeBuilder, bounds: Rect) {
+        let bounds = self
+            .window_transform()
+            .transform_rect_bbox(bounds)
+            .scale_from_origin(self.scale_factor);
+        node.set_bounds(accesskit::Rect::new(
+            bounds.x0, bounds.y0, bounds.x1, bounds.y1,
+        ));
+        self.current_node.push_child(id.into());
+        self.tree_update.nodes.push((id.into(), node.build()));
+    }
 }
 
 // --- MARK: RAW WRAPPERS ---
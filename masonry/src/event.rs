@@ -10,7 +10,7 @@ use crate::WidgetId;
 
 use std::path::PathBuf;
 
-use winit::event::{Force, Ime, KeyEvent, Modifiers};
+use winit::event::{Force, Ime, KeyEvent, Modifiers, TouchPhase};
 use winit::keyboard::ModifiersState;
 
 // TODO - Occluded(bool) event
@@ -217,6 +217,32 @@ pub struct AccessEvent {
     pub data: Option<accesskit::ActionData>,
 }
 
+/// Identifies one of the pointers interacting with the window.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PointerId {
+    /// The mouse, or the touchpad driving the mouse cursor.
+    Mouse,
+    /// A finger or pen touching the screen, with the id the platform gave to the touch.
+    Touch(u64),
+}
+
+/// The kind of device a pointer comes from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PointerType {
+    Mouse,
+    Touch,
+    Pen,
+}
+
+/// How the delta of a [`PointerEvent::MouseWheel`] event is measured.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ScrollUnit {
+    /// Lines of text, as reported by mouse wheels.
+    Line,
+    /// Logical pixels, as reported by touchpads.
+    Pixel,
+}
+
 #[derive(Debug, Clone)]
 pub struct PointerState {
     // TODO
@@ -228,6 +254,23 @@ pub struct PointerState {
     pub count: u8,
     pub focus: bool,
     pub force: Option<Force>,
+    pub pointer_id: PointerId,
+    pub pointer_type: PointerType,
+    /// Whether this is the primary pointer: the mouse, or the first finger touching the
+    /// screen when no other finger was.
+    ///
+    /// Events from other pointers are only sent to widgets which
+    /// [accept multiple pointers](crate::Widget::accepts_multi_pointer), so that other
+    /// widgets can treat touches as if they came from a mouse.
+    pub is_primary: bool,
+    /// For [`MouseWheel`](PointerEvent::MouseWheel) events, whether the delta is in lines
+    /// or in pixels.
+    pub scroll_unit: ScrollUnit,
+    /// For [`MouseWheel`](PointerEvent::MouseWheel) events, the phase of the touchpad gesture.
+    ///
+    /// [`TouchPhase::Ended`] means the fingers were lifted from the touchpad, which is
+    /// when kinetic scrolling starts. Mouse wheels always report [`TouchPhase::Moved`].
+    pub scroll_phase: TouchPhase,
 }
 
 #[derive(Debug, Clone)]
@@ -487,6 +530,99 @@ impl PointerState {
             count: 0,
             focus: false,
             force: None,
+            pointer_id: PointerId::Mouse,
+            pointer_type: PointerType::Mouse,
+            is_primary: true,
+            scroll_unit: ScrollUnit::Line,
+            scroll_phase: TouchPhase::Moved,
+        }
+    }
+
+    /// The pressure of the pointer, from 0 to 1, if the device reports it.
+    ///
+    /// Touch screens and pens usually report pressure, mice never do.
+    pub fn pressure(&self) -> Option<f64> {
+        self.force.map(|force| force.normalized())
+    }
+}
+
+/// Tracks the fingers touching the screen, and turns touches into pointer events.
+///
+/// Each touch is its own pointer. The first finger to touch the screen is the primary pointer
+/// until every finger is lifted, and is sent as a press, moves and release of the primary
+/// button, so that widgets which only handle the mouse also work with touch screens.
+#[derive(Debug, Default)]
+pub(crate) struct TouchTracker {
+    touches: Vec<u64>,
+    primary: Option<u64>,
+}
+
+impl TouchTracker {
+    /// The pointer events for a touch event.
+    ///
+    /// `state` is the state of the mouse pointer, from which the touch pointer inherits
+    /// its modifiers.
+    pub(crate) fn pointer_events(
+        &mut self,
+        id: u64,
+        phase: TouchPhase,
+        position: LogicalPosition<f64>,
+        physical_position: PhysicalPosition<f64>,
+        force: Option<Force>,
+        state: &PointerState,
+    ) -> Vec<PointerEvent> {
+        if phase == TouchPhase::Started && !self.touches.contains(&id) {
+            if self.touches.is_empty() {
+                self.primary = Some(id);
+            }
+            self.touches.push(id);
+        }
+        let is_primary = self.primary == Some(id);
+        if matches!(phase, TouchPhase::Ended | TouchPhase::Cancelled) {
+            self.touches.retain(|touch| *touch != id);
+            if self.touches.is_empty() {
+                self.primary = None;
+            }
+        }
+
+        // Pens are only told apart from fingers by their angle, on platforms which report it.
+        let pointer_type = match force {
+            Some(Force::Calibrated {
+                altitude_angle: Some(_),
+                ..
+            }) => PointerType::Pen,
+            _ => PointerType::Touch,
+        };
+        let state = PointerState {
+            physical_position,
+            position,
+            buttons: PointerButtons::new(),
+            mods: state.mods,
+            count: 0,
+            focus: false,
+            force,
+            pointer_id: PointerId::Touch(id),
+            pointer_type,
+            is_primary,
+            scroll_unit: ScrollUnit::Line,
+            scroll_phase: TouchPhase::Moved,
+        };
+        let pressed = PointerState {
+            buttons: PointerButton::Primary.into(),
+            ..state.clone()
+        };
+        match phase {
+            TouchPhase::Started => vec![
+                PointerEvent::PointerMove(state),
+                PointerEvent::PointerDown(PointerButton::Primary, pressed),
+            ],
+            TouchPhase::Moved => vec![PointerEvent::PointerMove(pressed)],
+            TouchPhase::Ended => vec![
+                PointerEvent::PointerUp(PointerButton::Primary, state.clone()),
+                PointerEvent::PointerLeave(state),
+            ],
+            // Widgets treat the pointer leaving as the end of any interaction.
+            TouchPhase::Cancelled => vec![PointerEvent::PointerLeave(state)],
         }
     }
 }
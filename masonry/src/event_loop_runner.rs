@@ -16,13 +16,13 @@ use winit::event::{
     DeviceEvent as WinitDeviceEvent, DeviceId, MouseButton as WinitMouseButton,
     WindowEvent as WinitWindowEvent,
 };
-use winit::event_loop::ActiveEventLoop;
+use winit::event_loop::{ActiveEventLoop, ControlFlow};
 use winit::window::{Window, WindowAttributes, WindowId};
 
 use crate::app_driver::{AppDriver, DriverCtx};
 use crate::dpi::LogicalPosition;
-use crate::event::{PointerButton, PointerState, WindowEvent};
-use crate::render_root::{self, RenderRoot, WindowSizePolicy};
+use crate::event::{PointerButton, PointerState, ScrollUnit, TouchTracker, WindowEvent};
+use crate::render_root::{self, Damage, RenderRoot, WindowSizePolicy};
 use crate::{PointerEvent, TextEvent, Widget, WidgetId};
 
 #[derive(Debug)]
@@ -30,6 +30,8 @@ pub enum MasonryUserEvent {
     AccessKit(accesskit_winit::Event),
     // TODO: A more considered design here
     Action(crate::Action, WidgetId),
+    /// A widget was woken by a [`WidgetWaker`](crate::WidgetWaker).
+    Wake,
 }
 
 impl From<accesskit_winit::Event> for MasonryUserEvent {
@@ -74,6 +76,7 @@ pub struct MasonryState<'a> {
     render_cx: RenderContext,
     render_root: RenderRoot,
     pointer_state: PointerState,
+    touch_tracker: TouchTracker,
     renderer: Option<Renderer>,
     // TODO: Winit doesn't seem to let us create these proxies from within the loop
     // The reasons for this are unclear
@@ -83,6 +86,9 @@ pub struct MasonryState<'a> {
     // In future, this will support multiple windows
     window: WindowState<'a>,
     background_color: Color,
+    /// The surface doesn't show the current scene, e.g. because it was just created or
+    /// resized, so the next frame has to be rendered even if nothing was damaged.
+    surface_outdated: bool,
 }
 
 struct MainState<'a> {
@@ -194,7 +200,8 @@ impl ApplicationHandler<MasonryUserEvent> for MainState<'_> {
     // external event loops can let masonry handle these callbacks.
 
     fn about_to_wait(&mut self, event_loop: &winit::event_loop::ActiveEventLoop) {
-        self.masonry_state.handle_about_to_wait(event_loop);
+        self.masonry_state
+            .handle_about_to_wait(event_loop, self.app_driver.as_mut());
     }
 
     fn new_events(
@@ -206,6 +213,7 @@ impl ApplicationHandler<MasonryUserEvent> for MainState<'_> {
     }
 
     fn exiting(&mut self, event_loop: &winit::event_loop::ActiveEventLoop) {
+        self.app_driver.on_exit(&mut self.masonry_state);
         self.masonry_state.handle_exiting(event_loop);
     }
 
@@ -222,25 +230,35 @@ impl MasonryState<'_> {
         background_color: Color,
     ) -> Self {
         let render_cx = RenderContext::new();
-        // TODO: We can't know this scale factor until later?
+        // The scale factor isn't known until the window is created; it is set in
+        // `handle_resumed`.
         let scale_factor = 1.0;
 
+        let mut render_root = RenderRoot::new(
+            root_widget,
+            render_root::RenderRootOptions {
+                use_system_fonts: true,
+                size_policy: WindowSizePolicy::User,
+                scale_factor,
+            },
+        );
+        let proxy = event_loop.create_proxy();
+        render_root.set_wake_callback(move || {
+            // This only fails if the event loop has exited, in which case there's nothing to wake.
+            let _ = proxy.send_event(MasonryUserEvent::Wake);
+        });
+
         MasonryState {
             render_cx,
-            render_root: RenderRoot::new(
-                root_widget,
-                render_root::RenderRootOptions {
-                    use_system_fonts: true,
-                    size_policy: WindowSizePolicy::User,
-                    scale_factor,
-                },
-            ),
+            render_root,
             renderer: None,
             pointer_state: PointerState::empty(),
+            touch_tracker: TouchTracker::default(),
             proxy: event_loop.create_proxy(),
 
             window: WindowState::Uninitialized(window),
             background_color,
+            surface_outdated: true,
         }
     }
 
@@ -278,6 +296,7 @@ impl MasonryState<'_> {
                     surface,
                     accesskit_adapter: adapter,
                 };
+                self.surface_outdated = true;
                 self.render_root
                     .handle_window_event(WindowEvent::Rescale(scale_factor));
             }
@@ -297,11 +316,16 @@ impl MasonryState<'_> {
                     PresentMode::AutoVsync,
                 ))
                 .unwrap();
+                // The window might have moved to another monitor while suspended.
+                let scale_factor = window.scale_factor();
                 self.window = WindowState::Rendering {
                     window,
                     surface,
                     accesskit_adapter,
-                }
+                };
+                self.surface_outdated = true;
+                self.render_root
+                    .handle_window_event(WindowEvent::Rescale(scale_factor));
             }
             _ => {
                 // We have received a redundant resumed event. That's allowed by winit
@@ -334,7 +358,7 @@ impl MasonryState<'_> {
     }
 
     // --- MARK: RENDER ---
-    fn render(&mut self, scene: Scene) {
+    fn render(&mut self, scene: Scene, damage: &Damage) {
         let WindowState::Rendering {
             window, surface, ..
         } = &mut self.window
@@ -353,8 +377,20 @@ impl MasonryState<'_> {
 
         if surface.config.width != width || surface.config.height != height {
             self.render_cx.resize_surface(surface, width, height);
+            self.surface_outdated = true;
         }
 
+        // If nothing changed, the surface still shows the scene, so we don't need to use
+        // the GPU at all.
+        if damage.is_empty() && !self.surface_outdated {
+            tracing::trace!("Skipping render, nothing was damaged");
+            return;
+        }
+        self.surface_outdated = false;
+        // TODO - wgpu doesn't support presenting only the damaged area of the surface yet
+        // (e.g. with `VK_KHR_incremental_present` or `eglSwapBuffersWithDamageKHR`), and
+        // Vello always renders the whole surface, so damaged frames are rendered in full.
+
         let transformed_scene = if scale_factor == 1.0 {
             None
         } else {
@@ -424,13 +460,14 @@ impl MasonryState<'_> {
 
         match event {
             WinitWindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                self.surface_outdated = true;
                 self.render_root
                     .handle_window_event(WindowEvent::Rescale(scale_factor));
             }
             WinitWindowEvent::RedrawRequested => {
                 self.render_root.handle_window_event(WindowEvent::AnimFrame);
-                let (scene, tree_update) = self.render_root.redraw();
-                self.render(scene);
+                let (scene, tree_update, damage) = self.render_root.redraw();
+                self.render(scene, &damage);
                 let WindowState::Rendering {
                     accesskit_adapter, ..
                 } = &mut self.window
@@ -497,15 +534,17 @@ impl MasonryState<'_> {
                         ));
                 }
             },
-            WinitWindowEvent::MouseWheel { delta, .. } => {
-                let delta = match delta {
+            WinitWindowEvent::MouseWheel { delta, phase, .. } => {
+                let (delta, unit) = match delta {
                     winit::event::MouseScrollDelta::LineDelta(x, y) => {
-                        LogicalPosition::new(x as f64, y as f64)
+                        (LogicalPosition::new(x as f64, y as f64), ScrollUnit::Line)
                     }
                     winit::event::MouseScrollDelta::PixelDelta(delta) => {
-                        delta.to_logical(window.scale_factor())
+                        (delta.to_logical(window.scale_factor()), ScrollUnit::Pixel)
                     }
                 };
+                self.pointer_state.scroll_unit = unit;
+                self.pointer_state.scroll_phase = phase;
                 self.render_root
                     .handle_pointer_event(PointerEvent::MouseWheel(
                         delta,
@@ -516,44 +555,19 @@ impl MasonryState<'_> {
                 location,
                 phase,
                 force,
+                id,
                 ..
             }) => {
-                // FIXME: This is naïve and should be refined for actual use.
-                //        It will also interact with gesture discrimination.
-                self.pointer_state.physical_position = location;
-                self.pointer_state.position = location.to_logical(window.scale_factor());
-                self.pointer_state.force = force;
-                match phase {
-                    winit::event::TouchPhase::Started => {
-                        self.render_root
-                            .handle_pointer_event(PointerEvent::PointerMove(
-                                self.pointer_state.clone(),
-                            ));
-                        self.render_root
-                            .handle_pointer_event(PointerEvent::PointerDown(
-                                PointerButton::Primary,
-                                self.pointer_state.clone(),
-                            ));
-                    }
-                    winit::event::TouchPhase::Ended => {
-                        self.render_root
-                            .handle_pointer_event(PointerEvent::PointerUp(
-                                PointerButton::Primary,
-                                self.pointer_state.clone(),
-                            ));
-                    }
-                    winit::event::TouchPhase::Moved => {
-                        self.render_root
-                            .handle_pointer_event(PointerEvent::PointerMove(
-                                self.pointer_state.clone(),
-                            ));
-                    }
-                    winit::event::TouchPhase::Cancelled => {
-                        self.render_root
-                            .handle_pointer_event(PointerEvent::PointerLeave(
-                                self.pointer_state.clone(),
-                            ));
-                    }
+                let events = self.touch_tracker.pointer_events(
+                    id,
+                    phase,
+                    location.to_logical(window.scale_factor()),
+                    location,
+                    force,
+                    &self.pointer_state,
+                );
+                for event in events {
+                    self.render_root.handle_pointer_event(event);
                 }
             }
             WinitWindowEvent::PinchGesture { delta, .. } => {
@@ -603,13 +617,28 @@ impl MasonryState<'_> {
                 .state
                 .signal_queue
                 .push_back(render_root::RenderRootSignal::Action(action, widget)),
+            MasonryUserEvent::Wake => self.render_root.handle_timers(),
         }
 
         self.handle_signals(event_loop, app_driver);
     }
 
     // --- MARK: EMPTY WINIT HANDLERS ---
-    pub fn handle_about_to_wait(&mut self, _: &ActiveEventLoop) {}
+    pub fn handle_about_to_wait(
+        &mut self,
+        event_loop: &ActiveEventLoop,
+        app_driver: &mut dyn AppDriver,
+    ) {
+        // Timers are handled once the pending window events have been processed.
+        if let WindowState::Rendering { .. } = self.window {
+            self.render_root.handle_timers();
+            self.handle_signals(event_loop, app_driver);
+        }
+        match self.render_root.next_timer_deadline() {
+            Some(deadline) => event_loop.set_control_flow(ControlFlow::WaitUntil(deadline)),
+            None => event_loop.set_control_flow(ControlFlow::Wait),
+        }
+    }
 
     pub fn handle_new_events(&mut self, _: &ActiveEventLoop, _: winit::event::StartCause) {}
 
@@ -641,6 +670,7 @@ impl MasonryState<'_> {
                     window.set_ime_allowed(false);
                 }
                 render_root::RenderRootSignal::ImeMoved(position, size) => {
+                    // TODO - Report the text around the caret once winit supports it.
                     window.set_ime_cursor_area(position, size);
                 }
                 render_root::RenderRootSignal::RequestRedraw => {
@@ -0,0 +1,118 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Keyboard focus navigation.
+
+use vello::kurbo::Rect;
+
+use crate::WidgetId;
+
+/// A direction keyboard focus can be moved in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FocusDirection {
+    /// The next widget in tab order, usually reached with Tab.
+    Next,
+    /// The previous widget in tab order, usually reached with Shift+Tab.
+    Previous,
+    /// The closest widget to the left, in a directional focus scope.
+    Left,
+    /// The closest widget to the right, in a directional focus scope.
+    Right,
+    /// The closest widget above, in a directional focus scope.
+    Up,
+    /// The closest widget below, in a directional focus scope.
+    Down,
+}
+
+/// How a focus scope affects keyboard navigation.
+///
+/// A focus scope groups the focusable widgets of a subtree: they are kept together
+/// in tab order, at the position given by the scope's tab index.
+///
+/// See [`set_focus_scope`](crate::EventCtx::set_focus_scope).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FocusScopeOptions {
+    /// Whether the scope traps focus, like a modal dialog.
+    ///
+    /// When a trap appears, focus moves to its first widget, and Tab navigation stays
+    /// inside the most recent trap. When the trap goes away, focus goes back to the widget
+    /// that had it before.
+    pub trap: bool,
+    /// Whether arrow keys move focus between the widgets of the scope, based on their
+    /// position on screen.
+    pub directional: bool,
+}
+
+/// A focusable widget, with the tab index it is sorted by.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct FocusEntry {
+    pub(crate) id: WidgetId,
+    pub(crate) tab_index: u32,
+}
+
+/// Sort a focus chain in tab order.
+///
+/// Widgets with a positive tab index come first, in increasing order, then widgets with a
+/// tab index of zero. Widgets with the same tab index stay in tree order.
+pub(crate) fn sort_focus_chain(chain: &mut [FocusEntry]) {
+    chain.sort_by_key(|entry| (entry.tab_index == 0, entry.tab_index));
+}
+
+/// Find the candidate closest to `from` in the given direction.
+///
+/// Candidates are scored by their distance along the direction, plus twice their distance
+/// on the other axis, so that widgets in the same row or column are preferred.
+pub(crate) fn closest_in_direction(
+    from: Rect,
+    direction: FocusDirection,
+    candidates: impl IntoIterator<Item = (WidgetId, Rect)>,
+) -> Option<WidgetId> {
+    let from_center = from.center();
+    let mut best: Option<(f64, f64, WidgetId)> = None;
+    for (id, rect) in candidates {
+        let center = rect.center();
+        let (is_ahead, distance, cross_gap, cross_offset) = match direction {
+            FocusDirection::Left => (
+                center.x < from_center.x,
+                from.x0 - rect.x1,
+                range_gap(from.y0, from.y1, rect.y0, rect.y1),
+                center.y - from_center.y,
+            ),
+            FocusDirection::Right => (
+                center.x > from_center.x,
+                rect.x0 - from.x1,
+                range_gap(from.y0, from.y1, rect.y0, rect.y1),
+                center.y - from_center.y,
+            ),
+            FocusDirection::Up => (
+                center.y < from_center.y,
+                from.y0 - rect.y1,
+                range_gap(from.x0, from.x1, rect.x0, rect.x1),
+                center.x - from_center.x,
+            ),
+            FocusDirection::Down => (
+                center.y > from_center.y,
+                rect.y0 - from.y1,
+                range_gap(from.x0, from.x1, rect.x0, rect.x1),
+                center.x - from_center.x,
+            ),
+            FocusDirection::Next | FocusDirection::Previous => return None,
+        };
+        if !is_ahead {
+            continue;
+        }
+        let score = distance.max(0.) + 2. * cross_gap;
+        let cross_offset = cross_offset.abs();
+        if best.map_or(true, |(best_score, best_offset, _)| {
+            (score, cross_offset) < (best_score, best_offset)
+        }) {
+            best = Some((score, cross_offset, id));
+        }
+    }
+    best.map(|(_, _, id)| id)
+}
+
+/// The distance between two ranges, or zero if they overlap.
+fn range_gap(a0: f64, a1: f64, b0: f64, b1: f64) -> f64 {
+    (b0 - a1).max(a0 - b1).max(0.)
+}
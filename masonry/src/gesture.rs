@@ -0,0 +1,504 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Reusable pointer gesture recognizers.
+//!
+//! Each recognizer is a small state machine which is fed the pointer events a widget
+//! receives, and returns a gesture when one is recognized. Widgets can combine several
+//! recognizers; see [`GestureDetector`](crate::widget::GestureDetector) for an example.
+//!
+//! All positions in the emitted gestures are in the coordinate space of the widget
+//! which owns the recognizer.
+
+use std::time::Duration;
+
+use vello::kurbo::{Point, Vec2};
+
+use crate::{
+    EventCtx, PointerButton, PointerEvent, PointerId, PointerState, PointerType, TimerToken,
+};
+
+/// The maximum delay between two presses for them to count as a multi-click.
+pub const MULTI_CLICK_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The maximum distance between two presses for them to count as a multi-click.
+pub const MULTI_CLICK_SLOP: f64 = 4.0;
+
+/// The distance the pointer must move while pressed before a drag starts.
+pub const DRAG_SLOP: f64 = 4.0;
+
+/// How long the pointer must stay pressed before a long press is recognized.
+pub const LONG_PRESS_DELAY: Duration = Duration::from_millis(500);
+
+/// A gesture recognized by one of the recognizers of this module.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Gesture {
+    Click(Click),
+    LongPress(LongPress),
+    Drag(Drag),
+    PanZoom(PanZoom),
+}
+
+/// A press and release of a pointer button on the same widget.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Click {
+    pub button: PointerButton,
+    /// The number of clicks in quick succession, e.g. 2 for a double-click.
+    pub count: u8,
+    pub position: Point,
+}
+
+/// A press of the primary button held in place for [`LONG_PRESS_DELAY`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LongPress {
+    pub position: Point,
+}
+
+/// The phase of a [`Drag`] gesture.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DragPhase {
+    /// The pointer moved further than [`DRAG_SLOP`] while pressed.
+    Start,
+    Move,
+    /// The button was released, or the pointer left the window.
+    End,
+}
+
+/// A pointer movement with a button pressed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Drag {
+    pub phase: DragPhase,
+    pub button: PointerButton,
+    /// Where the button was pressed.
+    pub start: Point,
+    pub position: Point,
+    /// The movement since the previous drag event, or since the press for [`DragPhase::Start`].
+    pub delta: Vec2,
+}
+
+/// A two-finger gesture on a touchpad.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PanZoom {
+    pub pan: Vec2,
+    /// The zoom factor to apply, where `1.0` means no change.
+    pub zoom: f64,
+    /// The position of the pointer, which zooming should keep in place.
+    pub center: Point,
+}
+
+fn window_pos(state: &PointerState) -> Point {
+    Point::new(state.position.x, state.position.y)
+}
+
+fn pointer_id(event: &PointerEvent) -> PointerId {
+    event.pointer_state().pointer_id
+}
+
+/// Capture the pointer, unless another widget (e.g. a descendant) already did.
+fn capture_if_free(ctx: &mut EventCtx) {
+    if ctx.pointer_capture_target().is_none() {
+        ctx.capture_pointer();
+    }
+}
+
+fn release_if_captured(ctx: &mut EventCtx) {
+    if ctx.has_pointer_capture() {
+        ctx.release_pointer();
+    }
+}
+
+// --- MARK: CLICK ---
+/// Recognizes clicks, including double and triple clicks.
+///
+/// A click is emitted when a button is released over the widget it was pressed on.
+/// Only one pointer is tracked at a time.
+#[derive(Debug, Default)]
+pub struct ClickRecognizer {
+    pressed: Option<(PointerId, PointerButton)>,
+}
+
+impl ClickRecognizer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether a button was pressed and not yet released.
+    pub fn is_pressed(&self) -> bool {
+        self.pressed.is_some()
+    }
+
+    /// Forget the current press, so that releasing the button doesn't emit a click.
+    pub fn cancel(&mut self) {
+        self.pressed = None;
+    }
+
+    pub fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) -> Option<Click> {
+        let pointer = pointer_id(event);
+        if self
+            .pressed
+            .is_some_and(|(pressed_pointer, _)| pressed_pointer != pointer)
+        {
+            return None;
+        }
+        match event {
+            PointerEvent::PointerDown(button, _) => {
+                if !ctx.is_disabled() {
+                    self.pressed = Some((pointer, *button));
+                    capture_if_free(ctx);
+                }
+                None
+            }
+            PointerEvent::PointerUp(button, state) => {
+                if self.pressed != Some((pointer, *button)) {
+                    return None;
+                }
+                self.pressed = None;
+                release_if_captured(ctx);
+                (ctx.is_hot() && !ctx.is_disabled()).then(|| Click {
+                    button: *button,
+                    count: state.count,
+                    position: ctx.to_local(window_pos(state)),
+                })
+            }
+            PointerEvent::PointerLeave(_) => {
+                // If the screen was locked whilst holding down the mouse button, we don't get
+                // a `PointerUp` event.
+                if self.pressed.take().is_some() {
+                    release_if_captured(ctx);
+                }
+                None
+            }
+            _ => None,
+        }
+    }
+}
+
+// --- MARK: LONG PRESS ---
+/// Recognizes long presses of the primary button.
+///
+/// The widget must forward its [`on_timer`](crate::Widget::on_timer) calls to
+/// [`LongPressRecognizer::on_timer`]. Pressing with another pointer, e.g. a second
+/// finger, cancels the long press.
+#[derive(Debug, Default)]
+pub struct LongPressRecognizer {
+    pending: Option<(TimerToken, PointerId, Point)>,
+}
+
+impl LongPressRecognizer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forget the current press, so that no long press is emitted for it.
+    pub fn cancel(&mut self) {
+        self.pending = None;
+    }
+
+    pub fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        let pointer = pointer_id(event);
+        if let Some((_, pending_pointer, start)) = self.pending {
+            if pending_pointer != pointer {
+                if let PointerEvent::PointerDown(..) = event {
+                    self.pending = None;
+                }
+                return;
+            }
+            if let PointerEvent::PointerMove(state) = event {
+                if start.distance(window_pos(state)) > DRAG_SLOP {
+                    self.pending = None;
+                }
+                return;
+            }
+        }
+        match event {
+            PointerEvent::PointerDown(PointerButton::Primary, state) if !ctx.is_disabled() => {
+                let token = ctx.request_timer(LONG_PRESS_DELAY);
+                self.pending = Some((token, pointer, window_pos(state)));
+            }
+            PointerEvent::PointerDown(_, _)
+            | PointerEvent::PointerUp(_, _)
+            | PointerEvent::PointerLeave(_) => self.pending = None,
+            _ => {}
+        }
+    }
+
+    pub fn on_timer(&mut self, ctx: &mut EventCtx, token: TimerToken) -> Option<LongPress> {
+        match self.pending {
+            Some((pending_token, _, start)) if pending_token == token => {
+                self.pending = None;
+                Some(LongPress {
+                    position: ctx.to_local(start),
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+// --- MARK: DRAG ---
+#[derive(Debug, Default)]
+enum DragState {
+    #[default]
+    Idle,
+    Pressed {
+        pointer: PointerId,
+        button: PointerButton,
+        start: Point,
+    },
+    Dragging {
+        pointer: PointerId,
+        button: PointerButton,
+        start: Point,
+        last: Point,
+    },
+}
+
+/// Recognizes drags, once the pointer moved further than [`DRAG_SLOP`] with a button pressed.
+///
+/// Only one pointer is tracked at a time.
+#[derive(Debug, Default)]
+pub struct DragRecognizer {
+    state: DragState,
+}
+
+impl DragRecognizer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether a drag started and hasn't ended yet.
+    pub fn is_dragging(&self) -> bool {
+        matches!(self.state, DragState::Dragging { .. })
+    }
+
+    /// Forget the current press or drag, without emitting a [`DragPhase::End`].
+    pub fn cancel(&mut self) {
+        self.state = DragState::Idle;
+    }
+
+    /// End the current drag early, e.g. because another gesture took over.
+    ///
+    /// Returns the [`DragPhase::End`] event if a drag was in progress. The pointer isn't
+    /// released, as the gesture which took over usually tracks it.
+    pub fn finish(&mut self, ctx: &EventCtx) -> Option<Drag> {
+        match std::mem::take(&mut self.state) {
+            DragState::Dragging {
+                button,
+                start,
+                last,
+                ..
+            } => Some(self.drag(ctx, DragPhase::End, button, start, last, last)),
+            _ => None,
+        }
+    }
+
+    pub fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) -> Option<Drag> {
+        let event_pointer = pointer_id(event);
+        match (&self.state, event) {
+            (DragState::Idle, PointerEvent::PointerDown(button, state)) => {
+                if !ctx.is_disabled() {
+                    self.state = DragState::Pressed {
+                        pointer: event_pointer,
+                        button: *button,
+                        start: window_pos(state),
+                    };
+                    capture_if_free(ctx);
+                }
+                None
+            }
+            (DragState::Idle, _) => None,
+            (DragState::Pressed { pointer, .. } | DragState::Dragging { pointer, .. }, _)
+                if *pointer != event_pointer =>
+            {
+                None
+            }
+            (
+                DragState::Pressed {
+                    pointer,
+                    button,
+                    start,
+                },
+                PointerEvent::PointerMove(state),
+            ) => {
+                let (pointer, button, start) = (*pointer, *button, *start);
+                let position = window_pos(state);
+                if start.distance(position) <= DRAG_SLOP {
+                    return None;
+                }
+                self.state = DragState::Dragging {
+                    pointer,
+                    button,
+                    start,
+                    last: position,
+                };
+                Some(self.drag(ctx, DragPhase::Start, button, start, start, position))
+            }
+            (
+                DragState::Dragging {
+                    pointer,
+                    button,
+                    start,
+                    last,
+                },
+                PointerEvent::PointerMove(state),
+            ) => {
+                let (pointer, button, start, last) = (*pointer, *button, *start, *last);
+                let position = window_pos(state);
+                self.state = DragState::Dragging {
+                    pointer,
+                    button,
+                    start,
+                    last: position,
+                };
+                Some(self.drag(ctx, DragPhase::Move, button, start, last, position))
+            }
+            (DragState::Pressed { button, .. }, PointerEvent::PointerUp(released, _))
+                if button == released =>
+            {
+                self.state = DragState::Idle;
+                release_if_captured(ctx);
+                None
+            }
+            (DragState::Pressed { .. }, PointerEvent::PointerLeave(_)) => {
+                self.state = DragState::Idle;
+                release_if_captured(ctx);
+                None
+            }
+            (
+                DragState::Dragging {
+                    button,
+                    start,
+                    last,
+                    ..
+                },
+                PointerEvent::PointerUp(released, state),
+            ) if button == released => {
+                let (button, start, last) = (*button, *start, *last);
+                self.state = DragState::Idle;
+                release_if_captured(ctx);
+                Some(self.drag(ctx, DragPhase::End, button, start, last, window_pos(state)))
+            }
+            (
+                DragState::Dragging {
+                    button,
+                    start,
+                    last,
+                    ..
+                },
+                PointerEvent::PointerLeave(_),
+            ) => {
+                let (button, start, last) = (*button, *start, *last);
+                self.state = DragState::Idle;
+                release_if_captured(ctx);
+                Some(self.drag(ctx, DragPhase::End, button, start, last, last))
+            }
+            _ => None,
+        }
+    }
+
+    fn drag(
+        &self,
+        ctx: &EventCtx,
+        phase: DragPhase,
+        button: PointerButton,
+        start: Point,
+        last: Point,
+        position: Point,
+    ) -> Drag {
+        let position = ctx.to_local(position);
+        Drag {
+            phase,
+            button,
+            start: ctx.to_local(start),
+            position,
+            delta: position - ctx.to_local(last),
+        }
+    }
+}
+
+// --- MARK: PAN ZOOM ---
+/// Recognizes two-finger pans and pinches, on touchpads and touch screens.
+///
+/// Touchpads report two-finger pans as [`MouseWheel`](PointerEvent::MouseWheel) events, so a
+/// widget using this recognizer will also pan when the mouse wheel is used.
+///
+/// On touch screens, the widget must [accept multiple pointers](crate::Widget::accepts_multi_pointer)
+/// for the recognizer to see the second finger.
+#[derive(Debug, Default)]
+pub struct PanZoomRecognizer {
+    /// The touches on the widget, in the order they started.
+    touches: Vec<(PointerId, Point)>,
+}
+
+impl PanZoomRecognizer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether two fingers are touching the widget, in which case other gestures
+    /// should be cancelled.
+    pub fn is_pinching(&self) -> bool {
+        self.touches.len() >= 2
+    }
+
+    pub fn on_pointer_event(
+        &mut self,
+        ctx: &mut EventCtx,
+        event: &PointerEvent,
+    ) -> Option<PanZoom> {
+        let state = event.pointer_state();
+        let pointer = state.pointer_id;
+        let touch_index = self.touches.iter().position(|(id, _)| *id == pointer);
+        match (event, touch_index) {
+            (PointerEvent::Pinch(delta, state), _) => Some(PanZoom {
+                pan: Vec2::ZERO,
+                zoom: 1. + delta,
+                center: ctx.to_local(window_pos(state)),
+            }),
+            (PointerEvent::MouseWheel(delta, state), _) => Some(PanZoom {
+                pan: Vec2::new(delta.x, delta.y),
+                zoom: 1.,
+                center: ctx.to_local(window_pos(state)),
+            }),
+            (PointerEvent::PointerDown(_, state), None)
+                if state.pointer_type != PointerType::Mouse && !ctx.is_disabled() =>
+            {
+                self.touches.push((pointer, window_pos(state)));
+                capture_if_free(ctx);
+                None
+            }
+            (PointerEvent::PointerMove(state), Some(index)) => {
+                let before = self.two_finger_span();
+                self.touches[index].1 = window_pos(state);
+                let (before, after) = (before?, self.two_finger_span()?);
+                // Only the first two fingers count.
+                if index >= 2 {
+                    return None;
+                }
+                Some(PanZoom {
+                    pan: after.0 - before.0,
+                    zoom: if before.1 > 0. {
+                        after.1 / before.1
+                    } else {
+                        1.
+                    },
+                    center: ctx.to_local(after.0),
+                })
+            }
+            (PointerEvent::PointerUp(..) | PointerEvent::PointerLeave(_), Some(index)) => {
+                self.touches.remove(index);
+                release_if_captured(ctx);
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// The center of the first two fingers, and the distance between them.
+    fn two_finger_span(&self) -> Option<(Point, f64)> {
+        let [(_, first), (_, second), ..] = self.touches[..] else {
+            return None;
+        };
+        Some((first.midpoint(second), first.distance(second)))
+    }
+}
@@ -0,0 +1,996 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Loading and decoding images in the background, for the [`Image`](crate::widget::Image) widget.
+//!
+//! Images are described by an [`ImageSource`], and decoded on a small pool of background
+//! threads. Decoded images are kept in the global [`ImageCache`], so that several widgets
+//! showing the same image only decode it once.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::io::Cursor;
+use std::panic::AssertUnwindSafe;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::{mpsc, Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+use std::time::Duration;
+
+use image::codecs::gif::GifDecoder;
+use image::codecs::png::PngDecoder;
+use image::imageops::FilterType;
+use image::{AnimationDecoder, DynamicImage, Frames, ImageFormat, RgbaImage};
+use once_cell::sync::Lazy;
+use vello::peniko::{Blob, Format, Image as ImageBuf};
+
+/// The error type of the future returned by an [`ImageLoader`].
+pub type LoadError = Box<dyn std::error::Error + Send + Sync>;
+
+/// The future returned by an [`ImageLoader`], which resolves to the encoded image.
+pub type LoadFuture = Pin<Box<dyn Future<Output = Result<Vec<u8>, LoadError>> + Send>>;
+
+/// A job which loads and decodes an image, run by the executor of an [`ImageCache`].
+pub type DecodeJob = Box<dyn FnOnce() + Send>;
+
+/// The default memory limit of the [global cache](ImageCache::global), in bytes.
+pub const DEFAULT_CACHE_MEMORY_LIMIT: usize = 256 * 1024 * 1024;
+
+/// The maximum number of threads decoding images for caches using the default executor.
+const MAX_DECODER_THREADS: usize = 4;
+
+/// Frames which are shown for less than this are shown for [`DEFAULT_FRAME_DURATION`] instead,
+/// which is how browsers treat them.
+const MIN_FRAME_DURATION: Duration = Duration::from_millis(20);
+const DEFAULT_FRAME_DURATION: Duration = Duration::from_millis(100);
+
+// --- MARK: SOURCES ---
+
+/// Where the encoded data of an image comes from.
+///
+/// Sources compare equal if they refer to the same image: the same [`Blob`], the same path,
+/// or loaders with the same key.
+#[derive(Clone)]
+pub enum ImageSource {
+    /// The encoded image is in memory.
+    Bytes(Blob<u8>),
+    /// The encoded image is read from a file.
+    Path(PathBuf),
+    /// The encoded image is produced by a user-provided loader, e.g. from the network.
+    Loader(ImageLoader),
+}
+
+/// A user-provided asynchronous loader of encoded image data.
+///
+/// The loader is identified by its key, such as a URL, which is used for caching.
+/// Two loaders with the same key should load the same image.
+#[derive(Clone)]
+pub struct ImageLoader {
+    key: Arc<str>,
+    load: Arc<dyn Fn() -> LoadFuture + Send + Sync>,
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+enum SourceKey {
+    Bytes(u64),
+    Path(PathBuf),
+    Loader(Arc<str>),
+}
+
+impl ImageSource {
+    /// An image source from the encoded image `data`.
+    pub fn bytes(data: impl Into<Vec<u8>>) -> Self {
+        Self::Bytes(Blob::new(Arc::new(data.into())))
+    }
+
+    /// An image source reading the encoded image from the file at `path`.
+    pub fn path(path: impl Into<PathBuf>) -> Self {
+        Self::Path(path.into())
+    }
+
+    /// An image source loading the encoded image with `load`, identified by `key`.
+    ///
+    /// See [`ImageLoader::new`] for details.
+    pub fn loader<F, Fut>(key: impl Into<Arc<str>>, load: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Vec<u8>, LoadError>> + Send + 'static,
+    {
+        Self::Loader(ImageLoader::new(key, load))
+    }
+
+    fn key(&self) -> SourceKey {
+        match self {
+            Self::Bytes(blob) => SourceKey::Bytes(blob.id()),
+            Self::Path(path) => SourceKey::Path(path.clone()),
+            Self::Loader(loader) => SourceKey::Loader(loader.key.clone()),
+        }
+    }
+
+    /// Read the encoded image, if it is in memory or in a file.
+    ///
+    /// Loaders are run by a [`LoadTask`] instead, so this returns `None` for them.
+    fn read(&self) -> Option<Result<Vec<u8>, ImageError>> {
+        match self {
+            Self::Bytes(blob) => Some(Ok(blob.data().to_vec())),
+            Self::Path(path) => {
+                Some(std::fs::read(path).map_err(|err| ImageError::Io(Arc::new(err))))
+            }
+            Self::Loader(_) => None,
+        }
+    }
+}
+
+impl PartialEq for ImageSource {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl fmt::Debug for ImageSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bytes(blob) => f.debug_tuple("Bytes").field(&blob.id()).finish(),
+            Self::Path(path) => f.debug_tuple("Path").field(path).finish(),
+            Self::Loader(loader) => f.debug_tuple("Loader").field(&loader.key).finish(),
+        }
+    }
+}
+
+impl ImageLoader {
+    /// Create a loader identified by `key`, which loads the encoded image with `load`.
+    ///
+    /// The future returned by `load` is polled by the executor of the [`ImageCache`], without
+    /// an async runtime. It is only polled again once it is woken, so waiting for it doesn't
+    /// hold up decoding other images, but it shouldn't block while it is polled.
+    /// Futures which need a runtime, such as most network requests, should be spawned on
+    /// that runtime and awaited in the future given here.
+    pub fn new<F, Fut>(key: impl Into<Arc<str>>, load: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Vec<u8>, LoadError>> + Send + 'static,
+    {
+        Self {
+            key: key.into(),
+            load: Arc::new(move || Box::pin(load())),
+        }
+    }
+
+    /// The key identifying the loaded image.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Start loading the encoded image.
+    pub fn load(&self) -> LoadFuture {
+        (self.load)()
+    }
+}
+
+// --- MARK: ERRORS ---
+
+/// An error which occurred while loading or decoding an image.
+#[derive(Clone, Debug)]
+pub enum ImageError {
+    /// The image file couldn't be read.
+    Io(Arc<std::io::Error>),
+    /// The [`ImageLoader`] failed.
+    Load(Arc<dyn std::error::Error + Send + Sync>),
+    /// The encoded image couldn't be decoded.
+    Decode(Arc<image::ImageError>),
+    /// The [`ImageLoader`] or the decoder panicked.
+    Panicked,
+}
+
+impl fmt::Display for ImageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "failed to read image: {err}"),
+            Self::Load(err) => write!(f, "failed to load image: {err}"),
+            Self::Decode(err) => write!(f, "failed to decode image: {err}"),
+            Self::Panicked => write!(f, "loading image panicked"),
+        }
+    }
+}
+
+impl std::error::Error for ImageError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(&**err),
+            Self::Load(err) => Some(&**err),
+            Self::Decode(err) => Some(&**err),
+            Self::Panicked => None,
+        }
+    }
+}
+
+impl From<image::ImageError> for ImageError {
+    fn from(err: image::ImageError) -> Self {
+        Self::Decode(Arc::new(err))
+    }
+}
+
+// --- MARK: DECODED IMAGES ---
+
+/// A decoded image, with one frame, or several frames if it is animated.
+#[derive(Clone, Debug)]
+pub struct DecodedImage {
+    frames: Vec<ImageFrame>,
+    width: u32,
+    height: u32,
+}
+
+/// A frame of a [`DecodedImage`].
+#[derive(Clone, Debug)]
+pub struct ImageFrame {
+    /// The pixels of the frame, covering the whole image.
+    pub image: ImageBuf,
+    /// How long the frame is shown before the next one.
+    pub duration: Duration,
+}
+
+impl DecodedImage {
+    /// A still image consisting of `image`.
+    pub fn still(image: ImageBuf) -> Self {
+        Self {
+            width: image.width,
+            height: image.height,
+            frames: vec![ImageFrame {
+                image,
+                duration: Duration::ZERO,
+            }],
+        }
+    }
+
+    /// The width of the decoded image, in pixels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// The height of the decoded image, in pixels.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The frames of the image. Still images have a single frame.
+    pub fn frames(&self) -> &[ImageFrame] {
+        &self.frames
+    }
+
+    /// Whether the image has more than one frame.
+    pub fn is_animated(&self) -> bool {
+        self.frames.len() > 1
+    }
+
+    /// The memory used by the pixels of the image, in bytes.
+    pub fn byte_size(&self) -> usize {
+        self.frames
+            .iter()
+            .map(|frame| frame.image.data.data().len())
+            .sum()
+    }
+
+    fn from_frames(frames: Vec<(RgbaImage, Duration)>) -> Self {
+        let (width, height) = frames
+            .first()
+            .map_or((0, 0), |(image, _)| image.dimensions());
+        let frames = frames
+            .into_iter()
+            .map(|(image, duration)| {
+                let (width, height) = image.dimensions();
+                ImageFrame {
+                    image: ImageBuf::new(
+                        Blob::new(Arc::new(image.into_raw())),
+                        Format::Rgba8,
+                        width,
+                        height,
+                    ),
+                    duration,
+                }
+            })
+            .collect();
+        Self {
+            frames,
+            width,
+            height,
+        }
+    }
+
+    fn to_rgba_frames(&self) -> Vec<(RgbaImage, Duration)> {
+        self.frames
+            .iter()
+            .filter_map(|frame| {
+                let image = RgbaImage::from_raw(
+                    frame.image.width,
+                    frame.image.height,
+                    frame.image.data.data().to_vec(),
+                )?;
+                Some((image, frame.duration))
+            })
+            .collect()
+    }
+}
+
+/// Decode the encoded image `data`, with all its frames if it is an animated GIF or PNG.
+pub fn decode(data: &[u8]) -> Result<DecodedImage, ImageError> {
+    let frames = match image::guess_format(data)? {
+        ImageFormat::Gif => collect_frames(GifDecoder::new(Cursor::new(data))?.into_frames())?,
+        ImageFormat::Png => {
+            let decoder = PngDecoder::new(Cursor::new(data))?;
+            if decoder.is_apng()? {
+                collect_frames(decoder.apng()?.into_frames())?
+            } else {
+                vec![(
+                    DynamicImage::from_decoder(decoder)?.into_rgba8(),
+                    Duration::ZERO,
+                )]
+            }
+        }
+        format => vec![(
+            image::load_from_memory_with_format(data, format)?.into_rgba8(),
+            Duration::ZERO,
+        )],
+    };
+    Ok(DecodedImage::from_frames(frames))
+}
+
+fn collect_frames(frames: Frames<'_>) -> Result<Vec<(RgbaImage, Duration)>, ImageError> {
+    let frames = frames.collect_frames()?;
+    Ok(frames
+        .into_iter()
+        .map(|frame| {
+            let (numer, denom) = frame.delay().numer_denom_ms();
+            let mut duration = Duration::from_secs_f64(numer as f64 / denom.max(1) as f64 / 1e3);
+            if duration < MIN_FRAME_DURATION {
+                duration = DEFAULT_FRAME_DURATION;
+            }
+            (frame.into_buffer(), duration)
+        })
+        .collect())
+}
+
+/// Downscale `frames` to fit within `max_size`, keeping their aspect ratio.
+///
+/// Frames which already fit are returned unchanged.
+fn downscale(
+    frames: Vec<(RgbaImage, Duration)>,
+    (max_width, max_height): (u32, u32),
+) -> Vec<(RgbaImage, Duration)> {
+    frames
+        .into_iter()
+        .map(|(image, duration)| {
+            let (width, height) = image.dimensions();
+            if width <= max_width && height <= max_height {
+                return (image, duration);
+            }
+            let scale = (max_width as f64 / width as f64).min(max_height as f64 / height as f64);
+            let new_width = ((width as f64 * scale).round() as u32).max(1);
+            let new_height = ((height as f64 * scale).round() as u32).max(1);
+            let image =
+                image::imageops::resize(&image, new_width, new_height, FilterType::Triangle);
+            (image, duration)
+        })
+        .collect()
+}
+
+// --- MARK: CACHE ---
+
+type LoadResult = Result<Arc<DecodedImage>, ImageError>;
+
+/// A pending or finished image load, returned by [`ImageCache::load`].
+#[derive(Clone)]
+pub struct ImageRequest {
+    state: Arc<Mutex<RequestState>>,
+}
+
+#[derive(Default)]
+struct RequestState {
+    result: Option<LoadResult>,
+    on_finish: Vec<Box<dyn FnOnce() + Send>>,
+}
+
+impl ImageRequest {
+    fn new() -> Self {
+        Self {
+            state: Arc::default(),
+        }
+    }
+
+    /// The decoded image or the error, if the load has finished.
+    pub fn poll(&self) -> Option<Result<Arc<DecodedImage>, ImageError>> {
+        self.state.lock().unwrap().result.clone()
+    }
+
+    /// Whether the load has finished.
+    pub fn is_finished(&self) -> bool {
+        self.state.lock().unwrap().result.is_some()
+    }
+
+    /// Call `f` once the load has finished, or right away if it already has.
+    ///
+    /// `f` is called on the thread which finished the load, e.g. to wake a widget with a
+    /// [`WidgetWaker`](crate::WidgetWaker).
+    pub fn on_finish(&self, f: impl FnOnce() + Send + 'static) {
+        let mut state = self.state.lock().unwrap();
+        if state.result.is_none() {
+            state.on_finish.push(Box::new(f));
+            return;
+        }
+        drop(state);
+        f();
+    }
+
+    fn finish(&self, result: LoadResult) {
+        let on_finish = {
+            let mut state = self.state.lock().unwrap();
+            state.result = Some(result);
+            std::mem::take(&mut state.on_finish)
+        };
+        for f in on_finish {
+            f();
+        }
+    }
+}
+
+impl fmt::Debug for ImageRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ImageRequest")
+            .field("finished", &self.is_finished())
+            .finish()
+    }
+}
+
+/// A cache of decoded images, which evicts the least recently used images
+/// when the decoded images use more memory than its limit.
+///
+/// Images are cached per source and size, since images may be
+/// [downscaled](ImageCache::load) to the size they are shown at.
+/// Cloning the cache gives a handle to the same cache.
+///
+/// Images are loaded and decoded by the cache's executor. By default this is a pool of
+/// up to four threads shared by all caches, so that showing many images at once doesn't
+/// start a thread for each of them.
+#[derive(Clone)]
+pub struct ImageCache {
+    state: Arc<Mutex<CacheState>>,
+    executor: Arc<dyn Fn(DecodeJob) + Send + Sync>,
+}
+
+struct CacheState {
+    entries: HashMap<CacheKey, CacheEntry>,
+    memory_usage: usize,
+    memory_limit: usize,
+    /// Incremented on each access, used to find the least recently used entry.
+    clock: u64,
+}
+
+type CacheKey = (SourceKey, Option<(u32, u32)>);
+
+struct CacheEntry {
+    request: ImageRequest,
+    /// The memory used by the image, or `None` while it is being decoded.
+    byte_size: Option<usize>,
+    last_used: u64,
+}
+
+static GLOBAL_CACHE: Lazy<ImageCache> = Lazy::new(|| ImageCache::new(DEFAULT_CACHE_MEMORY_LIMIT));
+
+static DECODER_POOL: Lazy<DecoderPool> = Lazy::new(|| {
+    let threads = std::thread::available_parallelism().map_or(1, |threads| threads.get());
+    DecoderPool::new(threads.min(MAX_DECODER_THREADS))
+});
+
+impl ImageCache {
+    /// Create an empty cache which holds up to `memory_limit` bytes of decoded images.
+    ///
+    /// Images are decoded by the pool of threads shared by all caches.
+    pub fn new(memory_limit: usize) -> Self {
+        Self::with_executor(memory_limit, |job| DECODER_POOL.execute(job))
+    }
+
+    /// Create an empty cache which holds up to `memory_limit` bytes of decoded images,
+    /// and runs the jobs loading them with `executor`.
+    ///
+    /// The executor is called on the thread which [loads](Self::load) the image, or which
+    /// wakes the future of an [`ImageLoader`], and should run the job in the background.
+    /// Running it right away makes loads synchronous, which is useful in tests.
+    pub fn with_executor(
+        memory_limit: usize,
+        executor: impl Fn(DecodeJob) + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(CacheState {
+                entries: HashMap::new(),
+                memory_usage: 0,
+                memory_limit,
+                clock: 0,
+            })),
+            executor: Arc::new(executor),
+        }
+    }
+
+    /// The cache used by the [`Image`](crate::widget::Image) widget.
+    ///
+    /// Its memory limit is [`DEFAULT_CACHE_MEMORY_LIMIT`] unless it is
+    /// [changed](Self::set_memory_limit).
+    pub fn global() -> &'static ImageCache {
+        &GLOBAL_CACHE
+    }
+
+    /// The maximum memory used by the cached images, in bytes.
+    pub fn memory_limit(&self) -> usize {
+        self.state.lock().unwrap().memory_limit
+    }
+
+    /// Set the maximum memory used by the cached images, in bytes, evicting images if needed.
+    ///
+    /// Images which are still shown by a widget are kept alive by that widget, even if
+    /// they are evicted from the cache.
+    pub fn set_memory_limit(&self, memory_limit: usize) {
+        let mut state = self.state.lock().unwrap();
+        state.memory_limit = memory_limit;
+        state.evict();
+    }
+
+    /// The memory currently used by the cached images, in bytes.
+    pub fn memory_usage(&self) -> usize {
+        self.state.lock().unwrap().memory_usage
+    }
+
+    /// Remove all finished images from the cache.
+    pub fn clear(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.entries.retain(|_, entry| entry.byte_size.is_none());
+        state.memory_usage = 0;
+    }
+
+    /// Load the image from `source`, downscaled to fit within `max_size` if it is given.
+    ///
+    /// If the image is cached or already being loaded, this returns the existing request.
+    /// Otherwise the image is read and decoded by the cache's executor.
+    pub fn load(&self, source: &ImageSource, max_size: Option<(u32, u32)>) -> ImageRequest {
+        let key = (source.key(), max_size);
+        let request = ImageRequest::new();
+        let full_image = {
+            let mut state = self.state.lock().unwrap();
+            state.clock += 1;
+            let clock = state.clock;
+            if let Some(entry) = state.entries.get_mut(&key) {
+                entry.last_used = clock;
+                return entry.request.clone();
+            }
+            state.entries.insert(
+                key.clone(),
+                CacheEntry {
+                    request: request.clone(),
+                    byte_size: None,
+                    last_used: clock,
+                },
+            );
+            // Downscaling can start from the full image, if it has already been decoded.
+            max_size
+                .and_then(|_| state.entries.get(&(key.0.clone(), None)))
+                .and_then(|entry| entry.request.poll())
+                .and_then(Result::ok)
+        };
+
+        let cache = self.clone();
+        let job_request = request.clone();
+        let process = move |input: Result<JobInput, ImageError>| {
+            let result = catch_panic(|| {
+                let frames = match input? {
+                    JobInput::Encoded(data) => decode(&data)?.to_rgba_frames(),
+                    JobInput::Decoded(image) => image.to_rgba_frames(),
+                };
+                let frames = match max_size {
+                    Some(max_size) => downscale(frames, max_size),
+                    None => frames,
+                };
+                Ok(Arc::new(DecodedImage::from_frames(frames)))
+            });
+            cache.finish(&key, &job_request, result);
+        };
+        if let Some(image) = full_image {
+            (self.executor)(Box::new(move || process(Ok(JobInput::Decoded(image)))));
+        } else if let ImageSource::Loader(loader) = source {
+            LoadTask::spawn(self.executor.clone(), loader.load(), move |data| {
+                process(data.map(JobInput::Encoded));
+            });
+        } else {
+            let source = source.clone();
+            (self.executor)(Box::new(move || {
+                let data = source.read().expect("loaders are run by a `LoadTask`");
+                process(data.map(JobInput::Encoded));
+            }));
+        }
+        request
+    }
+
+    /// Store the result of a finished load.
+    fn finish(&self, key: &CacheKey, request: &ImageRequest, result: LoadResult) {
+        {
+            let mut state = self.state.lock().unwrap();
+            match &result {
+                Ok(image) => {
+                    let byte_size = image.byte_size();
+                    // The entry may have been removed by `clear`, in which case it isn't re-added.
+                    if let Some(entry) = state.entries.get_mut(key) {
+                        entry.byte_size = Some(byte_size);
+                        state.memory_usage += byte_size;
+                    }
+                }
+                Err(err) => {
+                    tracing::warn!("Loading image failed: {err}");
+                    // Failed loads aren't cached, so that they can be retried.
+                    state.entries.remove(key);
+                }
+            }
+            state.evict();
+        }
+        // The cache is unlocked first, since the request's callbacks may load other images.
+        request.finish(result);
+    }
+}
+
+impl CacheState {
+    fn evict(&mut self) {
+        while self.memory_usage > self.memory_limit {
+            let Some(key) = self
+                .entries
+                .iter()
+                .filter(|(_, entry)| entry.byte_size.is_some())
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            let entry = self.entries.remove(&key).unwrap();
+            self.memory_usage -= entry.byte_size.unwrap();
+        }
+    }
+}
+
+/// What a load job turns into a cached image.
+enum JobInput {
+    /// The encoded image, which is decoded.
+    Encoded(Vec<u8>),
+    /// The full image, which is downscaled.
+    Decoded(Arc<DecodedImage>),
+}
+
+/// Run `f`, turning a panic into an error so that the request still finishes.
+fn catch_panic<T>(f: impl FnOnce() -> Result<T, ImageError>) -> Result<T, ImageError> {
+    std::panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or(Err(ImageError::Panicked))
+}
+
+// --- MARK: LOAD TASKS ---
+
+type LoadCallback = Box<dyn FnOnce(Result<Vec<u8>, ImageError>) + Send>;
+
+/// The future of an [`ImageLoader`], which is polled by a job of the cache's executor
+/// each time it is woken, instead of blocking a thread until it is done.
+struct LoadTask {
+    state: Mutex<TaskState>,
+    on_done: Mutex<Option<LoadCallback>>,
+    executor: Arc<dyn Fn(DecodeJob) + Send + Sync>,
+}
+
+enum TaskState {
+    /// The future is waiting to be polled.
+    Idle(LoadFuture),
+    /// The future is being polled.
+    Polling,
+    /// The future was woken while it was being polled, so it is polled again.
+    PollingWoken,
+    Done,
+}
+
+impl LoadTask {
+    fn spawn(
+        executor: Arc<dyn Fn(DecodeJob) + Send + Sync>,
+        future: LoadFuture,
+        on_done: impl FnOnce(Result<Vec<u8>, ImageError>) + Send + 'static,
+    ) {
+        let task = Arc::new(Self {
+            state: Mutex::new(TaskState::Idle(future)),
+            on_done: Mutex::new(Some(Box::new(on_done))),
+            executor,
+        });
+        task.schedule();
+    }
+
+    fn schedule(self: Arc<Self>) {
+        let executor = self.executor.clone();
+        executor(Box::new(move || self.poll()));
+    }
+
+    fn poll(self: Arc<Self>) {
+        let mut future = {
+            let mut state = self.state.lock().unwrap();
+            // Several polls may be scheduled, of which only the first one finds the future.
+            match std::mem::replace(&mut *state, TaskState::Polling) {
+                TaskState::Idle(future) => future,
+                other => {
+                    *state = other;
+                    return;
+                }
+            }
+        };
+        let waker = Waker::from(self.clone());
+        let mut cx = Context::from_waker(&waker);
+        let result = loop {
+            // The state isn't locked while polling, since the future may wake itself.
+            let poll = std::panic::catch_unwind(AssertUnwindSafe(|| future.as_mut().poll(&mut cx)));
+            let mut state = self.state.lock().unwrap();
+            match poll {
+                Ok(Poll::Pending) => {
+                    if let TaskState::PollingWoken = *state {
+                        *state = TaskState::Polling;
+                        continue;
+                    }
+                    *state = TaskState::Idle(future);
+                    return;
+                }
+                Ok(Poll::Ready(result)) => {
+                    *state = TaskState::Done;
+                    break result.map_err(|err| ImageError::Load(err.into()));
+                }
+                Err(_) => {
+                    *state = TaskState::Done;
+                    break Err(ImageError::Panicked);
+                }
+            }
+        };
+        if let Some(on_done) = self.on_done.lock().unwrap().take() {
+            on_done(result);
+        }
+    }
+}
+
+impl Wake for LoadTask {
+    fn wake(self: Arc<Self>) {
+        let mut state = self.state.lock().unwrap();
+        match *state {
+            TaskState::Idle(_) => {
+                // The executor may run the poll right away, so the state is unlocked first.
+                drop(state);
+                self.schedule();
+            }
+            TaskState::Polling => *state = TaskState::PollingWoken,
+            TaskState::PollingWoken | TaskState::Done => {}
+        }
+    }
+}
+
+// --- MARK: DECODER POOL ---
+
+/// A pool of threads running [`DecodeJob`]s, which are started as jobs arrive.
+struct DecoderPool {
+    sender: Mutex<mpsc::Sender<DecodeJob>>,
+    receiver: Arc<Mutex<mpsc::Receiver<DecodeJob>>>,
+    threads: Mutex<usize>,
+    max_threads: usize,
+}
+
+impl DecoderPool {
+    fn new(max_threads: usize) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            sender: Mutex::new(sender),
+            receiver: Arc::new(Mutex::new(receiver)),
+            threads: Mutex::new(0),
+            max_threads,
+        }
+    }
+
+    fn execute(&self, job: DecodeJob) {
+        // The pool keeps the receiver alive, so sending can't fail.
+        self.sender.lock().unwrap().send(job).unwrap();
+
+        let mut threads = self.threads.lock().unwrap();
+        if *threads >= self.max_threads {
+            return;
+        }
+        let receiver = self.receiver.clone();
+        let spawned = std::thread::Builder::new()
+            .name("masonry image decoder".into())
+            .spawn(move || loop {
+                // The lock is only held while waiting for a job, not while running it.
+                let job = receiver.lock().unwrap().recv();
+                match job {
+                    // A panicking job mustn't take the thread down with it, since the
+                    // thread would still be counted and its jobs would never run.
+                    Ok(job) => {
+                        if std::panic::catch_unwind(AssertUnwindSafe(job)).is_err() {
+                            tracing::error!("Image decoding job panicked");
+                        }
+                    }
+                    Err(mpsc::RecvError) => return,
+                }
+            });
+        match spawned {
+            Ok(_) => *threads += 1,
+            Err(err) if *threads == 0 => {
+                // Without any thread the job would never run, so it is run here instead.
+                tracing::warn!("Starting image decoder thread failed: {err}");
+                drop(threads);
+                if let Ok(job) = self.receiver.lock().unwrap().try_recv() {
+                    job();
+                }
+            }
+            Err(err) => tracing::warn!("Starting image decoder thread failed: {err}"),
+        }
+    }
+}
+
+impl fmt::Debug for ImageCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.state.lock().unwrap();
+        f.debug_struct("ImageCache")
+            .field("entries", &state.entries.len())
+            .field("memory_usage", &state.memory_usage)
+            .field("memory_limit", &state.memory_limit)
+            .finish_non_exhaustive()
+    }
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use image::Rgba;
+
+    use super::*;
+
+    fn encode_png(width: u32, height: u32) -> Vec<u8> {
+        let image = RgbaImage::from_pixel(width, height, Rgba([0, 255, 0, 255]));
+        let mut data = Vec::new();
+        image
+            .write_to(&mut Cursor::new(&mut data), ImageFormat::Png)
+            .unwrap();
+        data
+    }
+
+    fn wait(request: &ImageRequest) -> LoadResult {
+        let (sender, receiver) = mpsc::channel();
+        request.on_finish(move || sender.send(()).unwrap());
+        receiver
+            .recv_timeout(Duration::from_secs(10))
+            .expect("image took too long to load");
+        request.poll().unwrap()
+    }
+
+    #[test]
+    fn shared_requests() {
+        let cache = ImageCache::new(DEFAULT_CACHE_MEMORY_LIMIT);
+        let source = ImageSource::bytes(encode_png(4, 4));
+        let first = cache.load(&source, None);
+        let second = cache.load(&source.clone(), None);
+        assert!(Arc::ptr_eq(&first.state, &second.state));
+
+        let image = wait(&first).unwrap();
+        assert_eq!(image.byte_size(), 4 * 4 * 4);
+        assert_eq!(cache.memory_usage(), 4 * 4 * 4);
+    }
+
+    #[test]
+    fn downscale_keeps_aspect_ratio() {
+        let cache = ImageCache::new(DEFAULT_CACHE_MEMORY_LIMIT);
+        let source = ImageSource::bytes(encode_png(40, 20));
+        let image = wait(&cache.load(&source, Some((10, 10)))).unwrap();
+        assert_eq!((image.width(), image.height()), (10, 5));
+
+        // Images aren't upscaled.
+        let image = wait(&cache.load(&source, Some((80, 80)))).unwrap();
+        assert_eq!((image.width(), image.height()), (40, 20));
+    }
+
+    #[test]
+    fn evict_least_recently_used() {
+        let image_size = 4 * 4 * 4;
+        let cache = ImageCache::new(2 * image_size);
+        let sources: Vec<_> = (0..3)
+            .map(|_| ImageSource::bytes(encode_png(4, 4)))
+            .collect();
+        for source in &sources[..2] {
+            wait(&cache.load(source, None)).unwrap();
+        }
+        assert_eq!(cache.memory_usage(), 2 * image_size);
+
+        // Use the first image, so that the second one is the least recently used.
+        assert!(cache.load(&sources[0], None).is_finished());
+        wait(&cache.load(&sources[2], None)).unwrap();
+        assert_eq!(cache.memory_usage(), 2 * image_size);
+        assert!(cache.load(&sources[0], None).is_finished());
+        assert!(cache.load(&sources[2], None).is_finished());
+
+        cache.set_memory_limit(image_size);
+        assert_eq!(cache.memory_usage(), image_size);
+        cache.clear();
+        assert_eq!(cache.memory_usage(), 0);
+    }
+
+    #[test]
+    fn loader_source() {
+        let cache = ImageCache::new(DEFAULT_CACHE_MEMORY_LIMIT);
+        let data = encode_png(3, 2);
+        let source = ImageSource::loader("test-image", move || {
+            let data = data.clone();
+            async move { Ok(data) }
+        });
+        let image = wait(&cache.load(&source, None)).unwrap();
+        assert_eq!((image.width(), image.height()), (3, 2));
+
+        let failing = ImageSource::loader("failing-image", || async {
+            Err::<Vec<u8>, LoadError>("not found".into())
+        });
+        let result = wait(&cache.load(&failing, None));
+        assert!(matches!(result, Err(ImageError::Load(_))));
+    }
+
+    #[test]
+    fn panicking_loader_finishes_request() {
+        let cache = ImageCache::new(DEFAULT_CACHE_MEMORY_LIMIT);
+        let source = ImageSource::loader("panicking-image", || async {
+            panic!("the loader panicked");
+        });
+        let result = wait(&cache.load(&source, None));
+        assert!(matches!(result, Err(ImageError::Panicked)));
+    }
+
+    #[test]
+    fn pool_survives_panicking_job() {
+        let pool = DecoderPool::new(1);
+        pool.execute(Box::new(|| panic!("the job panicked")));
+        let (sender, receiver) = mpsc::channel();
+        pool.execute(Box::new(move || sender.send(()).unwrap()));
+        receiver
+            .recv_timeout(Duration::from_secs(10))
+            .expect("the pool stopped running jobs");
+        assert_eq!(*pool.threads.lock().unwrap(), 1);
+    }
+
+    #[test]
+    fn pending_loader_doesnt_block_decoding() {
+        let pool = Arc::new(DecoderPool::new(1));
+        let cache = ImageCache::with_executor(DEFAULT_CACHE_MEMORY_LIMIT, move |job| {
+            pool.execute(job);
+        });
+        let (sender, receiver) = futures_intrusive::channel::shared::oneshot_channel();
+        let receiver = Arc::new(receiver);
+        let source = ImageSource::loader("slow-image", move || {
+            let receiver = receiver.clone();
+            async move { receiver.receive().await.ok_or_else(|| "closed".into()) }
+        });
+        let slow = cache.load(&source, None);
+
+        // The only decoder thread isn't taken up by waiting for the loader.
+        let image = wait(&cache.load(&ImageSource::bytes(encode_png(2, 2)), None)).unwrap();
+        assert_eq!(image.width(), 2);
+        assert!(!slow.is_finished());
+
+        sender.send(encode_png(3, 3)).unwrap();
+        assert_eq!(wait(&slow).unwrap().width(), 3);
+    }
+
+    #[test]
+    fn custom_executor() {
+        let jobs = Arc::new(Mutex::new(Vec::<DecodeJob>::new()));
+        let queue = jobs.clone();
+        let cache = ImageCache::with_executor(DEFAULT_CACHE_MEMORY_LIMIT, move |job| {
+            queue.lock().unwrap().push(job);
+        });
+        let request = cache.load(&ImageSource::bytes(encode_png(2, 2)), None);
+        let (sender, receiver) = mpsc::channel();
+        request.on_finish(move || sender.send(()).unwrap());
+        assert!(!request.is_finished());
+
+        let pending: Vec<_> = jobs.lock().unwrap().drain(..).collect();
+        assert_eq!(pending.len(), 1);
+        for job in pending {
+            job();
+        }
+        assert!(receiver.try_recv().is_ok());
+        assert!(request.poll().unwrap().is_ok());
+    }
+}
@@ -109,6 +109,9 @@ mod action;
 mod box_constraints;
 mod contexts;
 mod event;
+mod focus;
+pub mod gesture;
+pub mod image_loading;
 pub mod paint_scene_helpers;
 pub mod render_root;
 pub mod testing;
@@ -131,12 +134,13 @@ pub use action::Action;
 pub use box_constraints::BoxConstraints;
 pub use contexts::{
     AccessCtx, ComposeCtx, EventCtx, IsContext, LayoutCtx, LifeCycleCtx, MutateCtx, PaintCtx,
-    RawWrapper, RawWrapperMut,
+    RawWrapper, RawWrapperMut, TimerToken, WidgetWaker,
 };
 pub use event::{
-    AccessEvent, InternalLifeCycle, LifeCycle, PointerButton, PointerEvent, PointerState,
-    StatusChange, TextEvent, WindowEvent, WindowTheme,
+    AccessEvent, InternalLifeCycle, LifeCycle, PointerButton, PointerEvent, PointerId,
+    PointerState, PointerType, ScrollUnit, StatusChange, TextEvent, WindowEvent, WindowTheme,
 };
+pub use focus::{FocusDirection, FocusScopeOptions};
 pub use kurbo::{Affine, Insets, Point, Rect, Size, Vec2};
 pub use parley::layout::Alignment as TextAlignment;
 pub use util::{AsAny, Handled};
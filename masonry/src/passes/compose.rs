@@ -2,30 +2,40 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use tracing::info_span;
-use vello::kurbo::Vec2;
+use vello::kurbo::Affine;
 
 use crate::passes::recurse_on_children;
 use crate::render_root::{RenderRoot, RenderRootState};
 use crate::tree_arena::ArenaMut;
-use crate::{ComposeCtx, Widget, WidgetState};
+use crate::{ComposeCtx, Point, Widget, WidgetState};
 
 fn compose_widget(
     global_state: &mut RenderRootState,
     mut widget: ArenaMut<'_, Box<dyn Widget>>,
     mut state: ArenaMut<'_, WidgetState>,
     parent_moved: bool,
-    parent_translation: Vec2,
+    parent_transform: Affine,
 ) {
     let _span = widget.item.make_trace_span().entered();
 
-    let moved = parent_moved || state.item.translation_changed;
-    let translation = parent_translation + state.item.translation + state.item.origin.to_vec2();
-    state.item.window_origin = translation.to_point();
+    let moved = parent_moved || state.item.layer_changed;
+    let translation = state.item.translation + state.item.origin.to_vec2();
+    let transform = parent_transform * Affine::translate(translation) * state.item.transform;
+    state.item.window_transform = transform;
+    state.item.window_origin = transform * Point::ORIGIN;
 
-    if !moved && !state.item.translation_changed && !state.item.needs_compose {
+    if !moved && !state.item.needs_compose {
         return;
     }
 
+    // A change of opacity or blend mode doesn't move the widget, so the paint pass
+    // won't see it; the area it was painted in has to be redrawn.
+    if state.item.layer_changed {
+        if let Some(painted_rect) = state.item.painted_rect {
+            global_state.damage.add_rect(painted_rect);
+        }
+    }
+
     let mut ctx = ComposeCtx {
         global_state,
         widget_state: state.item,
@@ -43,7 +53,7 @@ fn compose_widget(
 
     state.item.needs_compose = false;
     state.item.request_compose = false;
-    state.item.translation_changed = false;
+    state.item.layer_changed = false;
 
     let id = state.item.id;
     let parent_state = state.item;
@@ -52,13 +62,7 @@ fn compose_widget(
         widget.reborrow_mut(),
         state.children,
         |widget, mut state| {
-            compose_widget(
-                global_state,
-                widget,
-                state.reborrow_mut(),
-                moved,
-                translation,
-            );
+            compose_widget(global_state, widget, state.reborrow_mut(), moved, transform);
             parent_state.merge_up(state.item);
         },
     );
@@ -70,7 +74,13 @@ pub(crate) fn root_compose(root: &mut RenderRoot, global_root_state: &mut Widget
     let _span = info_span!("compose").entered();
 
     let (root_widget, root_state) = root.widget_arena.get_pair_mut(root.root.id());
-    compose_widget(&mut root.state, root_widget, root_state, false, Vec2::ZERO);
+    compose_widget(
+        &mut root.state,
+        root_widget,
+        root_state,
+        false,
+        Affine::IDENTITY,
+    );
 
     global_root_state.merge_up(root.widget_arena.get_state_mut(root.root.id()).item);
 }
@@ -6,16 +6,18 @@ use tracing::{debug, info_span, trace};
 use winit::keyboard::{KeyCode, PhysicalKey};
 
 use crate::passes::merge_state_up;
-use crate::render_root::RenderRoot;
+use crate::render_root::{RenderRoot, Timer};
 use crate::{
-    AccessEvent, EventCtx, Handled, PointerEvent, TextEvent, Widget, WidgetId, WidgetState,
+    AccessEvent, EventCtx, FocusDirection, Handled, PointerEvent, PointerId, TextEvent, Widget,
+    WidgetId, WidgetState,
 };
 
 fn get_target_widget(
     root: &RenderRoot,
+    pointer: PointerId,
     pointer_pos: Option<LogicalPosition<f64>>,
 ) -> Option<WidgetId> {
-    if let Some(capture_target) = root.state.pointer_capture_target {
+    if let Some(capture_target) = root.state.pointer_capture_target(pointer) {
         return Some(capture_target);
     }
 
@@ -37,6 +39,7 @@ fn run_event_pass<E>(
     target: Option<WidgetId>,
     event: &E,
     allow_pointer_capture: bool,
+    pointer_id: Option<PointerId>,
     pass_fn: impl FnMut(&mut dyn Widget, &mut EventCtx, &E),
 ) -> Handled {
     let mut pass_fn = pass_fn;
@@ -53,6 +56,7 @@ fn run_event_pass<E>(
             widget_state_children: state_mut.children,
             widget_children: widget_mut.children,
             allow_pointer_capture,
+            pointer_id,
             is_handled: false,
             request_pan_to_child: None,
         };
@@ -93,21 +97,47 @@ pub(crate) fn root_on_pointer_event(
         debug!("Running ON_POINTER_EVENT pass with {}", event.short_name());
     }
 
-    root.last_mouse_pos = event.position();
+    let pointer_state = event.pointer_state();
+    let pointer = pointer_state.pointer_id;
+    let is_primary = pointer_state.is_primary;
+    match event.position() {
+        Some(position) => root.pointer_positions.insert(pointer, position),
+        None => root.pointer_positions.remove(&pointer),
+    };
+    if is_primary {
+        root.last_mouse_pos = event.position();
+    }
+    if matches!(event, PointerEvent::PointerDown(..)) {
+        // The focus ring is only drawn for keyboard navigation.
+        root.state.focus_visible = false;
+    }
 
-    let target_widget_id = get_target_widget(root, event.position());
+    let target_widget_id = get_target_widget(root, pointer, event.position());
 
     let handled = run_event_pass(
         root,
         root_state,
         target_widget_id,
         event,
-        matches!(event, PointerEvent::PointerDown(..)),
+        // Moves can capture too, so that a container can take over a drag from a child.
+        matches!(
+            event,
+            PointerEvent::PointerDown(..) | PointerEvent::PointerMove(..)
+        ),
+        Some(pointer),
         |widget, ctx, event| {
-            widget.on_pointer_event(ctx, event);
+            // Widgets which only handle one pointer see touches as a mouse.
+            if is_primary || widget.accepts_multi_pointer() {
+                widget.on_pointer_event(ctx, event);
+            }
         },
     );
 
+    // A touch which left the screen is gone for good, and can't keep its capture.
+    if let (PointerId::Touch(_), PointerEvent::PointerLeave(_)) = (pointer, event) {
+        root.state.pointer_captures.remove(&pointer);
+    }
+
     if !event.is_high_density() {
         debug!(
             focused_widget = root.state.focused_widget.map(|id| id.0),
@@ -137,20 +167,34 @@ pub(crate) fn root_on_text_event(
         target,
         event,
         false,
+        None,
         |widget, ctx, event| {
             widget.on_text_event(ctx, event);
         },
     );
 
-    // Handle Tab focus
+    // Handle Tab and arrow key focus
     if let TextEvent::KeyboardKey(key, mods) = event {
-        if handled == Handled::No && key.physical_key == PhysicalKey::Code(KeyCode::Tab) {
-            if !mods.shift_key() {
-                root.state.next_focused_widget = root.widget_from_focus_chain(true);
-            } else {
-                root.state.next_focused_widget = root.widget_from_focus_chain(false);
+        if handled == Handled::No && key.state.is_pressed() {
+            let no_mods = mods.is_empty();
+            let direction = match key.physical_key {
+                PhysicalKey::Code(KeyCode::Tab) if mods.shift_key() => {
+                    Some(FocusDirection::Previous)
+                }
+                PhysicalKey::Code(KeyCode::Tab) => Some(FocusDirection::Next),
+                PhysicalKey::Code(KeyCode::ArrowLeft) if no_mods => Some(FocusDirection::Left),
+                PhysicalKey::Code(KeyCode::ArrowRight) if no_mods => Some(FocusDirection::Right),
+                PhysicalKey::Code(KeyCode::ArrowUp) if no_mods => Some(FocusDirection::Up),
+                PhysicalKey::Code(KeyCode::ArrowDown) if no_mods => Some(FocusDirection::Down),
+                _ => None,
+            };
+            if let Some(direction) = direction {
+                let moved = root.move_focus(direction);
+                // Tab is always consumed, so that it never ends up typed in a text field.
+                if moved || matches!(direction, FocusDirection::Next | FocusDirection::Previous) {
+                    handled = Handled::Yes;
+                }
             }
-            handled = Handled::Yes;
         }
     }
 
@@ -165,6 +209,31 @@ pub(crate) fn root_on_text_event(
     handled
 }
 
+pub(crate) fn root_on_timer(root: &mut RenderRoot, root_state: &mut WidgetState, timer: &Timer) {
+    let _span = info_span!("timer").entered();
+    debug!(
+        "Running ON_TIMER pass for widget #{}",
+        timer.widget_id.to_raw()
+    );
+
+    let target = timer.widget_id;
+    run_event_pass(
+        root,
+        root_state,
+        Some(target),
+        &timer.token,
+        false,
+        None,
+        |widget, ctx, token| {
+            // Timers are only sent to the widget which requested them.
+            if ctx.widget_id() == target {
+                widget.on_timer(ctx, *token);
+                ctx.set_handled();
+            }
+        },
+    );
+}
+
 pub(crate) fn root_on_access_event(
     root: &mut RenderRoot,
     root_state: &mut WidgetState,
@@ -181,6 +250,7 @@ pub(crate) fn root_on_access_event(
         target,
         event,
         false,
+        None,
         |widget, ctx, event| {
             widget.on_access_event(ctx, event);
         },
@@ -4,16 +4,17 @@
 use std::collections::HashMap;
 
 use tracing::{info_span, trace};
-use vello::kurbo::{Affine, Stroke};
+use vello::kurbo::{Affine, Rect, RoundedRect, Stroke};
 use vello::peniko::Mix;
 use vello::Scene;
 
 use crate::passes::recurse_on_children;
-use crate::render_root::{RenderRoot, RenderRootState};
-use crate::theme::get_debug_color;
+use crate::render_root::{Damage, RenderRoot, RenderRootState};
+use crate::theme::{self, get_debug_color};
 use crate::tree_arena::ArenaMut;
 use crate::{PaintCtx, Widget, WidgetId, WidgetState};
 
+#[allow(clippy::too_many_arguments)]
 fn paint_widget(
     global_state: &mut RenderRootState,
     complete_scene: &mut Scene,
@@ -21,11 +22,38 @@ fn paint_widget(
     mut widget: ArenaMut<'_, Box<dyn Widget>>,
     mut state: ArenaMut<'_, WidgetState>,
     debug_paint: bool,
+    damage: &mut Damage,
+    parent_clip: Option<Rect>,
 ) {
     let _span = widget.item.make_trace_span().entered();
     let id = state.item.id;
 
-    // TODO - Handle invalidation regions
+    // The clip of the widget and its ancestors, in window coordinates.
+    let transform = state.item.window_transform;
+    let window_clip = state
+        .item
+        .clip
+        .map(|clip| transform.transform_rect_bbox(clip));
+    let clip = match (parent_clip, window_clip) {
+        (Some(parent_clip), Some(clip)) => Some(parent_clip.intersect(clip)),
+        (parent_clip, clip) => parent_clip.or(clip),
+    };
+    let paint_rect = transform.transform_rect_bbox(state.item.local_paint_rect);
+    let visible_rect = clip.map_or(paint_rect, |clip| paint_rect.intersect(clip));
+
+    // If the widget is repainted or moved, the area it was painted in before has
+    // to be redrawn, as well as the area it is painted in now.
+    let moved =
+        state.item.painted_transform != transform || state.item.painted_rect != Some(visible_rect);
+    if state.item.request_paint || moved {
+        if let Some(painted_rect) = state.item.painted_rect {
+            damage.add_rect(painted_rect);
+        }
+        damage.add_rect(visible_rect);
+    }
+    state.item.painted_rect = Some(visible_rect);
+    state.item.painted_transform = transform;
+
     let mut ctx = PaintCtx {
         global_state,
         widget_state: state.item,
@@ -50,13 +78,24 @@ fn paint_widget(
     state.item.request_paint = false;
     state.item.needs_paint = false;
 
-    let clip = state.item.clip;
-    let has_clip = clip.is_some();
-    let transform = Affine::translate(state.item.window_origin.to_vec2());
+    // The widget and its children are composited together in their own layer, so that
+    // changing the opacity or blend mode doesn't require repainting them.
+    let has_layer = state.item.has_layer();
+    if has_layer {
+        complete_scene.push_layer(
+            state.item.blend_mode,
+            state.item.opacity,
+            transform,
+            &state.item.local_paint_rect,
+        );
+    }
+
+    let own_clip = state.item.clip;
+    let has_clip = own_clip.is_some();
     let scene = scenes.get(&id).unwrap();
 
-    if let Some(clip) = clip {
-        complete_scene.push_layer(Mix::Clip, 1., transform, &clip);
+    if let Some(own_clip) = own_clip {
+        complete_scene.push_layer(Mix::Clip, 1., transform, &own_clip);
     }
 
     complete_scene.append(scene, Some(transform));
@@ -72,12 +111,29 @@ fn paint_widget(
             // TODO - We skip painting stashed items.
             // This may have knock-on effects we'd need to document.
             if state.item.is_stashed {
+                if let Some(painted_rect) = state.item.painted_rect.take() {
+                    damage.add_rect(painted_rect);
+                }
                 return;
             }
-            // TODO: We could skip painting children outside the parent clip path.
-            // There's a few things to consider if we do:
-            // - Some widgets can paint outside of their layout box.
-            // - Once we implement compositor layers, we may want to paint outside of the clip path anyway in anticipation of user scrolling.
+            // Children completely outside of the clip can't be seen, so we skip painting them.
+            // Their paint rect includes anything they paint outside of their layout box.
+            // TODO - Scrolling only changes layer transforms, so we may want to paint
+            // outside of the clip path anyway to avoid repainting while the user scrolls.
+            if let Some(clip) = clip {
+                let child_rect = state
+                    .item
+                    .window_transform
+                    .transform_rect_bbox(state.item.local_paint_rect);
+                if child_rect.intersect(clip).is_empty() {
+                    if let Some(painted_rect) = state.item.painted_rect.take() {
+                        damage.add_rect(painted_rect);
+                    }
+                    // The child keeps its paint request, so that it is painted once it is visible.
+                    state.item.needs_paint = false;
+                    return;
+                }
+            }
             paint_widget(
                 global_state,
                 complete_scene,
@@ -85,6 +141,8 @@ fn paint_widget(
                 widget,
                 state.reborrow_mut(),
                 debug_paint,
+                damage,
+                clip,
             );
             parent_state.merge_up(state.item);
         },
@@ -100,15 +158,73 @@ fn paint_widget(
     if has_clip {
         complete_scene.pop_layer();
     }
+    if has_layer {
+        complete_scene.pop_layer();
+    }
 }
 
 // ----------------
 
-pub(crate) fn root_paint(root: &mut RenderRoot) -> Scene {
+/// The focus ring around the focused widget, in window coordinates, if it should be drawn.
+fn focus_ring(root: &RenderRoot) -> Option<(Affine, RoundedRect)> {
+    if !root.state.focus_visible {
+        return None;
+    }
+    let focused = root.state.focused_widget?;
+    let state = root.widget_arena.widget_states.find(focused.to_raw())?.item;
+    // Widgets which are stashed or scrolled out of view don't get a ring.
+    state.painted_rect?;
+    let offset = theme::FOCUS_RING_OFFSET + theme::FOCUS_RING_WIDTH / 2.;
+    let shape = state
+        .size
+        .to_rect()
+        .inflate(offset, offset)
+        .to_rounded_rect(theme::FOCUS_RING_RADIUS);
+    Some((state.window_transform, shape))
+}
+
+/// Damage the area of the focus ring if it changed since it was last drawn.
+fn update_focus_ring(root: &mut RenderRoot, damage: &mut Damage) -> Option<(Affine, RoundedRect)> {
+    let focus_ring = focus_ring(root);
+    let focus_ring_rect = focus_ring.map(|(transform, shape)| {
+        let width = theme::FOCUS_RING_WIDTH;
+        transform
+            .transform_rect_bbox(shape.rect())
+            .inflate(width, width)
+    });
+    if focus_ring_rect != root.state.focus_ring_rect {
+        for rect in [root.state.focus_ring_rect, focus_ring_rect]
+            .into_iter()
+            .flatten()
+        {
+            damage.add_rect(rect);
+        }
+        root.state.focus_ring_rect = focus_ring_rect;
+    }
+    focus_ring
+}
+
+pub(crate) fn root_paint(root: &mut RenderRoot) -> (Scene, Damage) {
     let _span = info_span!("paint").entered();
 
     let debug_paint = std::env::var("MASONRY_DEBUG_PAINT").is_ok_and(|it| !it.is_empty());
 
+    let mut damage = std::mem::take(&mut root.state.damage);
+
+    update_focus_ring(root, &mut damage);
+
+    // If no widget needs to be painted or has moved, the previous scene can be reused.
+    let needs_paint = root
+        .widget_arena
+        .get_state_mut(root.root.id())
+        .item
+        .needs_paint;
+    if !needs_paint && damage.is_empty() {
+        if let Some(scene) = &root.last_scene {
+            return (scene.clone(), damage);
+        }
+    }
+
     // TODO - Reserve scene
     // https://github.com/linebender/xilem/issues/524
     let mut complete_scene = Scene::new();
@@ -139,8 +255,23 @@ pub(crate) fn root_paint(root: &mut RenderRoot) -> Scene {
         root_widget,
         root_state,
         debug_paint,
+        &mut damage,
+        None,
     );
     root.state.scenes = scenes;
 
-    complete_scene
+    // The focus ring is drawn on top of every widget, so that it is never hidden by siblings.
+    // It is updated again, because painting may have culled or uncovered the focused widget.
+    if let Some((transform, shape)) = update_focus_ring(root, &mut damage) {
+        complete_scene.stroke(
+            &Stroke::new(theme::FOCUS_RING_WIDTH),
+            transform,
+            theme::FOCUS_RING_COLOR,
+            None,
+            &shape,
+        );
+    }
+    root.last_scene = Some(complete_scene.clone());
+
+    (complete_scene, damage)
 }
@@ -1,7 +1,7 @@
 // Copyright 2024 the Xilem Authors
 // SPDX-License-Identifier: Apache-2.0
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 
 use cursor_icon::CursorIcon;
 use tracing::{info_span, trace};
@@ -9,7 +9,9 @@ use tracing::{info_span, trace};
 use crate::passes::{merge_state_up, recurse_on_children};
 use crate::render_root::{RenderRoot, RenderRootSignal, RenderRootState};
 use crate::tree_arena::ArenaMut;
-use crate::{LifeCycle, LifeCycleCtx, StatusChange, Widget, WidgetId, WidgetState};
+use crate::{
+    LifeCycle, LifeCycleCtx, Point, PointerId, StatusChange, Widget, WidgetId, WidgetState,
+};
 
 fn get_id_path(root: &RenderRoot, widget_id: Option<WidgetId>) -> Vec<WidgetId> {
     let Some(widget_id) = widget_id else {
@@ -49,36 +51,46 @@ fn run_targeted_update_pass(
 }
 
 pub(crate) fn run_update_pointer_pass(root: &mut RenderRoot, root_state: &mut WidgetState) {
-    let pointer_pos = root.last_mouse_pos.map(|pos| (pos.x, pos.y).into());
-
     // -- UPDATE HOVERED WIDGETS --
 
-    let mut next_hovered_widget = if let Some(pos) = pointer_pos {
+    // Each pointer hovers a widget.
+    let pointer_positions: Vec<_> = root
+        .pointer_positions
+        .iter()
+        .map(|(pointer, pos)| (*pointer, Point::new(pos.x, pos.y)))
+        .collect();
+
+    let mut next_hovered_paths = BTreeMap::new();
+    for (pointer, pos) in pointer_positions {
         // TODO - Apply scale?
-        root.get_root_widget()
+        let mut next_hovered_widget = root
+            .get_root_widget()
             .find_widget_at_pos(pos)
-            .map(|widget| widget.id())
-    } else {
-        None
-    };
-    // If the pointer is captured, it can either hover its capture target or nothing.
-    if let Some(capture_target) = root.state.pointer_capture_target {
-        if next_hovered_widget != Some(capture_target) {
-            next_hovered_widget = None;
+            .map(|widget| widget.id());
+        // If the pointer is captured, it can either hover its capture target or nothing.
+        // Without a capture, hovering a widget makes all its ancestors hot, so hovering one of
+        // the capture target's descendants also counts as hovering the capture target.
+        // Otherwise, e.g. a button with an icon would stop being hot whilst pressed on the icon.
+        if let Some(capture_target) = root.state.pointer_capture_target(pointer) {
+            if get_id_path(root, next_hovered_widget).contains(&capture_target) {
+                next_hovered_widget = Some(capture_target);
+            } else {
+                next_hovered_widget = None;
+            }
         }
+        next_hovered_paths.insert(pointer, get_id_path(root, next_hovered_widget));
     }
 
     // "Hovered path" means the widget which is considered hovered, and all its parents.
-    let prev_hovered_path = std::mem::take(&mut root.state.hovered_path);
-    let next_hovered_path = get_id_path(root, next_hovered_widget);
+    // A widget is hot if any pointer hovers it.
+    let prev_hovered_paths = std::mem::take(&mut root.state.hovered_paths);
+    let prev_hovered: Vec<WidgetId> = prev_hovered_paths.values().flatten().copied().collect();
+    let next_hovered: Vec<WidgetId> = next_hovered_paths.values().flatten().copied().collect();
 
-    let mut hovered_set = HashSet::new();
-    for widget_id in &next_hovered_path {
-        hovered_set.insert(*widget_id);
-    }
+    let hovered_set: HashSet<WidgetId> = next_hovered.iter().copied().collect();
 
-    trace!("prev_hovered_path: {:?}", prev_hovered_path);
-    trace!("next_hovered_path: {:?}", next_hovered_path);
+    trace!("prev_hovered_paths: {:?}", prev_hovered_paths);
+    trace!("next_hovered_paths: {:?}", next_hovered_paths);
 
     // This algorithm is written to be resilient to future changes like reparenting and multiple
     // cursors. In theory it's O(Depth² * CursorCount) in the worst case, which isn't too bad
@@ -104,7 +116,7 @@ pub(crate) fn run_update_pointer_pass(root: &mut RenderRoot, root_state: &mut Wi
 
     // TODO - Make sure widgets are iterated from the bottom up.
     // TODO - Document the iteration order for update_pointer pass.
-    for widget_id in prev_hovered_path.iter().copied() {
+    for widget_id in prev_hovered.iter().copied() {
         if root.widget_arena.has(widget_id)
             && root.widget_arena.get_state_mut(widget_id).item.is_hot
                 != hovered_set.contains(&widget_id)
@@ -112,7 +124,7 @@ pub(crate) fn run_update_pointer_pass(root: &mut RenderRoot, root_state: &mut Wi
             update_hovered_status_of(root, widget_id, &hovered_set);
         }
     }
-    for widget_id in next_hovered_path.iter().copied() {
+    for widget_id in next_hovered.iter().copied() {
         if root.widget_arena.has(widget_id)
             && root.widget_arena.get_state_mut(widget_id).item.is_hot
                 != hovered_set.contains(&widget_id)
@@ -123,9 +135,15 @@ pub(crate) fn run_update_pointer_pass(root: &mut RenderRoot, root_state: &mut Wi
 
     // -- UPDATE CURSOR --
 
-    // If the pointer is captured, its cursor always reflects the
-    // capture target, even when not hovered.
-    let cursor_source = root.state.pointer_capture_target.or(next_hovered_widget);
+    // Only the mouse has a cursor. If the mouse is captured, its cursor always
+    // reflects the capture target, even when not hovered.
+    let mouse_hovered_widget = next_hovered_paths
+        .get(&PointerId::Mouse)
+        .and_then(|path| path.first().copied());
+    let cursor_source = root
+        .state
+        .pointer_capture_target(PointerId::Mouse)
+        .or(mouse_hovered_widget);
 
     let new_cursor = if let Some(cursor_source) = cursor_source {
         let (widget, state) = root.widget_arena.get_pair(cursor_source);
@@ -141,7 +159,7 @@ pub(crate) fn run_update_pointer_pass(root: &mut RenderRoot, root_state: &mut Wi
     }
 
     root.state.cursor_icon = new_cursor;
-    root.state.hovered_path = next_hovered_path;
+    root.state.hovered_paths = next_hovered_paths;
 
     // Pass root widget state to synthetic state create at beginning of pass
     root_state.merge_up(root.widget_arena.get_state_mut(root.root.id()).item);
@@ -174,6 +192,8 @@ fn update_disabled_for_widget(
             .item
             .lifecycle(&mut ctx, &LifeCycle::DisabledChanged(disabled));
         state.item.is_disabled = disabled;
+        // Disabled widgets are removed from the focus chain.
+        state.item.update_focus_chain = true;
     }
 
     state.item.needs_update_disabled = false;
@@ -1,13 +1,14 @@
 // Copyright 2019 the Xilem Authors and the Druid Authors
 // SPDX-License-Identifier: Apache-2.0
 
-use std::collections::{HashMap, VecDeque};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
 
 use accesskit::{ActionRequest, Tree, TreeUpdate};
 use parley::fontique::{self, Collection, CollectionOptions};
 use parley::{FontContext, LayoutContext};
 use tracing::{info_span, warn};
-use vello::kurbo::{self, Point};
+use vello::kurbo::{self, Point, Rect};
 use vello::Scene;
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -18,20 +19,25 @@ use web_time::Instant;
 use crate::contexts::{LayoutCtx, LifeCycleCtx};
 use crate::debug_logger::DebugLogger;
 use crate::dpi::{LogicalPosition, LogicalSize, PhysicalSize};
-use crate::event::{PointerEvent, TextEvent, WindowEvent};
+use crate::event::{PointerButton, PointerEvent, PointerId, TextEvent, WindowEvent};
+use crate::focus::{closest_in_direction, sort_focus_chain};
+use crate::gesture::{MULTI_CLICK_INTERVAL, MULTI_CLICK_SLOP};
 use crate::passes::accessibility::root_accessibility;
 use crate::passes::compose::root_compose;
-use crate::passes::event::{root_on_access_event, root_on_pointer_event, root_on_text_event};
+use crate::passes::event::{
+    root_on_access_event, root_on_pointer_event, root_on_text_event, root_on_timer,
+};
 use crate::passes::mutate::{mutate_widget, run_mutate_pass};
 use crate::passes::paint::root_paint;
+use crate::passes::recurse_on_children;
 use crate::passes::update::{run_update_disabled_pass, run_update_pointer_pass};
 use crate::text::TextBrush;
-use crate::tree_arena::TreeArena;
+use crate::tree_arena::{ArenaMut, TreeArena};
 use crate::widget::WidgetArena;
 use crate::widget::{WidgetMut, WidgetRef, WidgetState};
 use crate::{
-    AccessEvent, Action, BoxConstraints, CursorIcon, Handled, InternalLifeCycle, LifeCycle, Widget,
-    WidgetId, WidgetPod,
+    AccessEvent, Action, BoxConstraints, CursorIcon, FocusDirection, Handled, InternalLifeCycle,
+    LifeCycle, TimerToken, Widget, WidgetId, WidgetPod,
 };
 
 // --- MARK: STRUCTS ---
@@ -41,18 +47,20 @@ pub struct RenderRoot {
     pub(crate) root: WidgetPod<Box<dyn Widget>>,
     pub(crate) size_policy: WindowSizePolicy,
     pub(crate) size: PhysicalSize<u32>,
-    // TODO - Currently this is always 1.0
-    // kurbo coordinates are assumed to be in logical pixels
-    pub(crate) scale_factor: f64,
     /// Is `Some` if the most recently displayed frame was an animation frame.
     pub(crate) last_anim: Option<Instant>,
+    /// The position of the primary pointer.
     pub(crate) last_mouse_pos: Option<LogicalPosition<f64>>,
+    /// The position of every pointer over the window.
+    pub(crate) pointer_positions: BTreeMap<PointerId, LogicalPosition<f64>>,
     pub(crate) cursor_icon: CursorIcon,
     pub(crate) state: RenderRootState,
     // TODO - Add "access_tree_active" to detect when you don't need to update the
     // access tree
     pub(crate) rebuild_access_tree: bool,
     pub(crate) widget_arena: WidgetArena,
+    /// The scene painted by the last paint pass, which is reused if nothing changed.
+    pub(crate) last_scene: Option<Scene>,
 }
 
 pub(crate) struct RenderRootState {
@@ -60,13 +68,86 @@ pub(crate) struct RenderRootState {
     pub(crate) signal_queue: VecDeque<RenderRootSignal>,
     pub(crate) focused_widget: Option<WidgetId>,
     pub(crate) next_focused_widget: Option<WidgetId>,
-    pub(crate) hovered_path: Vec<WidgetId>,
-    pub(crate) pointer_capture_target: Option<WidgetId>,
+    /// Whether focus was last moved with the keyboard, in which case the focus ring is drawn.
+    pub(crate) focus_visible: bool,
+    /// The area covered by the focus ring during the last paint pass.
+    pub(crate) focus_ring_rect: Option<Rect>,
+    /// The active focus traps, most recent last, with the widget focused before each of them.
+    pub(crate) focus_trap_stack: Vec<(WidgetId, Option<WidgetId>)>,
+    /// For each pointer, the widget it hovers and all its parents.
+    pub(crate) hovered_paths: BTreeMap<PointerId, Vec<WidgetId>>,
+    pub(crate) pointer_captures: HashMap<PointerId, WidgetId>,
     pub(crate) cursor_icon: CursorIcon,
+    /// The ratio between physical and logical pixels of the window.
+    ///
+    /// Kurbo coordinates are in logical pixels; the scene is scaled by this factor
+    /// when it is rendered.
+    pub(crate) scale_factor: f64,
     pub(crate) font_context: FontContext,
     pub(crate) text_layout_context: LayoutContext<TextBrush>,
     pub(crate) mutate_callbacks: Vec<MutateCallback>,
     pub(crate) scenes: HashMap<WidgetId, Scene>,
+    /// Areas which were damaged outside of the paint pass, e.g. by removing a widget.
+    pub(crate) damage: Damage,
+    pub(crate) timers: Vec<Timer>,
+    /// Widgets woken by a [`WidgetWaker`](crate::WidgetWaker), possibly from another thread.
+    pub(crate) wake_queue: Arc<WakeQueue>,
+    /// The current time in tests, which only moves forward when the test harness says so.
+    pub(crate) test_clock: Option<Instant>,
+    /// The last pointer button press, used to count repeated clicks.
+    pub(crate) last_pointer_down: Option<PointerDownRecord>,
+}
+
+/// A timer requested by a widget.
+pub(crate) struct Timer {
+    pub(crate) token: TimerToken,
+    pub(crate) widget_id: WidgetId,
+    pub(crate) deadline: Instant,
+}
+
+/// The widgets woken by a [`WidgetWaker`](crate::WidgetWaker), which are handled
+/// like expired timers.
+#[derive(Default)]
+pub(crate) struct WakeQueue {
+    woken: Mutex<Vec<(WidgetId, TimerToken)>>,
+    /// Tells the event loop that a widget was woken.
+    notify: Mutex<Option<Box<dyn Fn() + Send + Sync>>>,
+}
+
+impl WakeQueue {
+    pub(crate) fn wake(&self, widget_id: WidgetId, token: TimerToken) {
+        self.woken.lock().unwrap().push((widget_id, token));
+        if let Some(notify) = &*self.notify.lock().unwrap() {
+            notify();
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.woken.lock().unwrap().is_empty()
+    }
+
+    fn take(&self) -> Vec<(WidgetId, TimerToken)> {
+        std::mem::take(&mut *self.woken.lock().unwrap())
+    }
+}
+
+pub(crate) struct PointerDownRecord {
+    pub(crate) button: PointerButton,
+    pub(crate) position: LogicalPosition<f64>,
+    pub(crate) time: Instant,
+    pub(crate) count: u8,
+}
+
+impl RenderRootState {
+    /// The current time, as seen by timers and gesture recognizers.
+    pub(crate) fn now(&self) -> Instant {
+        self.test_clock.unwrap_or_else(Instant::now)
+    }
+
+    /// The widget which captured the given pointer, if any.
+    pub(crate) fn pointer_capture_target(&self, pointer: PointerId) -> Option<WidgetId> {
+        self.pointer_captures.get(&pointer).copied()
+    }
 }
 
 #[allow(clippy::type_complexity)]
@@ -110,6 +191,74 @@ pub enum RenderRootSignal {
     SetTitle(String),
 }
 
+/// The areas of the window which changed since the previous redraw, in logical pixels.
+///
+/// Returned by [`RenderRoot::redraw`]. Overlapping areas are merged, and if there are many
+/// separate areas, they are merged into their bounding box.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Damage {
+    rects: Vec<Rect>,
+}
+
+impl Damage {
+    /// The number of separate areas above which they are merged into one.
+    const MAX_RECTS: usize = 8;
+
+    /// No damage.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self.rects.is_empty()
+    }
+
+    /// The damaged areas, which don't overlap.
+    pub fn rects(&self) -> &[Rect] {
+        &self.rects
+    }
+
+    /// The bounding box of all damaged areas.
+    pub fn bounds(&self) -> Option<Rect> {
+        self.rects.iter().copied().reduce(|a, b| a.union(b))
+    }
+
+    /// Whether `rect` overlaps a damaged area.
+    pub fn intersects(&self, rect: Rect) -> bool {
+        self.rects
+            .iter()
+            .any(|damaged| !damaged.intersect(rect).is_empty())
+    }
+
+    /// Mark `rect` as damaged.
+    pub fn add_rect(&mut self, mut rect: Rect) {
+        if rect.is_empty() || !rect.is_finite() {
+            return;
+        }
+        // Merging two areas can make the result overlap other areas, so repeat until it
+        // doesn't overlap any.
+        while let Some(index) = self
+            .rects
+            .iter()
+            .position(|damaged| !damaged.intersect(rect).is_empty())
+        {
+            rect = rect.union(self.rects.swap_remove(index));
+        }
+        self.rects.push(rect);
+        if self.rects.len() > Self::MAX_RECTS {
+            self.rects = self.bounds().into_iter().collect();
+        }
+    }
+
+    /// Mark all areas damaged in `other` as damaged.
+    pub fn union(&mut self, other: &Damage) {
+        for rect in &other.rects {
+            self.add_rect(*rect);
+        }
+    }
+}
+
 impl RenderRoot {
     pub fn new(
         root_widget: impl Widget,
@@ -123,18 +272,22 @@ impl RenderRoot {
             root: WidgetPod::new(root_widget).boxed(),
             size_policy,
             size: PhysicalSize::new(0, 0),
-            scale_factor,
             last_anim: None,
             last_mouse_pos: None,
+            pointer_positions: BTreeMap::new(),
             cursor_icon: CursorIcon::Default,
             state: RenderRootState {
                 debug_logger: DebugLogger::new(false),
                 signal_queue: VecDeque::new(),
                 focused_widget: None,
                 next_focused_widget: None,
-                hovered_path: Vec::new(),
-                pointer_capture_target: None,
+                focus_visible: false,
+                focus_ring_rect: None,
+                focus_trap_stack: Vec::new(),
+                hovered_paths: BTreeMap::new(),
+                pointer_captures: HashMap::new(),
                 cursor_icon: CursorIcon::Default,
+                scale_factor,
                 font_context: FontContext {
                     collection: Collection::new(CollectionOptions {
                         system_fonts: use_system_fonts,
@@ -145,12 +298,18 @@ impl RenderRoot {
                 text_layout_context: LayoutContext::new(),
                 mutate_callbacks: Vec::new(),
                 scenes: HashMap::new(),
+                damage: Damage::new(),
+                timers: Vec::new(),
+                wake_queue: Arc::default(),
+                test_clock: None,
+                last_pointer_down: None,
             },
             widget_arena: WidgetArena {
                 widgets: TreeArena::new(),
                 widget_states: TreeArena::new(),
             },
             rebuild_access_tree: true,
+            last_scene: None,
         };
 
         // We send WidgetAdded to all widgets right away
@@ -164,7 +323,7 @@ impl RenderRoot {
         root
     }
 
-    fn root_state(&mut self) -> &mut WidgetState {
+    pub(crate) fn root_state(&mut self) -> &mut WidgetState {
         self.widget_arena
             .widget_states
             .root_token_mut()
@@ -173,14 +332,45 @@ impl RenderRoot {
             .item
     }
 
+    /// Request a layout, paint and accessibility update of every widget.
+    fn request_render_all(&mut self) {
+        fn request_render_all_in(
+            mut widget: ArenaMut<'_, Box<dyn Widget>>,
+            state: ArenaMut<'_, WidgetState>,
+        ) {
+            state.item.request_layout = true;
+            state.item.needs_layout = true;
+            state.item.request_paint = true;
+            state.item.needs_paint = true;
+            state.item.request_accessibility = true;
+            state.item.needs_accessibility = true;
+
+            let id = state.item.id;
+            recurse_on_children(
+                id,
+                widget.reborrow_mut(),
+                state.children,
+                |widget, mut state| {
+                    request_render_all_in(widget, state.reborrow_mut());
+                },
+            );
+        }
+
+        let (root_widget, root_state) = self.widget_arena.get_pair_mut(self.root.id());
+        request_render_all_in(root_widget, root_state);
+        self.rebuild_access_tree = true;
+    }
+
     // --- MARK: WINDOW_EVENT ---
     pub fn handle_window_event(&mut self, event: WindowEvent) -> Handled {
         match event {
             WindowEvent::Rescale(scale_factor) => {
-                self.scale_factor = scale_factor;
-                // TODO - What we'd really like is to request a repaint and an accessibility
-                // pass for every single widget.
-                self.root_state().needs_layout = true;
+                if scale_factor != self.state.scale_factor {
+                    self.state.scale_factor = scale_factor;
+                    // Text is laid out in physical pixels, and accessibility bounds are
+                    // in physical pixels, so every widget is affected.
+                    self.request_render_all();
+                }
                 self.state
                     .signal_queue
                     .push_back(RenderRootSignal::RequestRedraw);
@@ -195,7 +385,7 @@ impl RenderRoot {
                 Handled::Yes
             }
             WindowEvent::AnimFrame => {
-                let now = Instant::now();
+                let now = self.state.now();
                 // TODO: this calculation uses wall-clock time of the paint call, which
                 // potentially has jitter.
                 //
@@ -229,6 +419,20 @@ impl RenderRoot {
         self.root_on_text_event(event)
     }
 
+    /// Move the focus as if the user pressed Tab, Shift+Tab or an arrow key, and no
+    /// widget handled the key.
+    ///
+    /// Returns `false` if there is no widget to move to in that direction.
+    pub fn handle_focus_navigation(&mut self, direction: FocusDirection) -> bool {
+        let mut dummy_state = WidgetState::synthetic(self.root.id(), self.get_kurbo_size());
+        self.state.next_focused_widget = self.state.focused_widget;
+
+        let moved = self.move_focus(direction);
+
+        self.post_event_processing(&mut dummy_state);
+        moved
+    }
+
     /// Registers all fonts that exist in the given data.
     ///
     /// Returns a list of pairs each containing the family identifier and fonts
@@ -262,7 +466,13 @@ impl RenderRoot {
         families
     }
 
-    pub fn redraw(&mut self) -> (Scene, TreeUpdate) {
+    /// Run the layout, paint and accessibility passes as needed, and return the scene to
+    /// render, the accessibility tree update, and the areas of the window which changed
+    /// since the previous redraw.
+    ///
+    /// If the damage is empty, the scene is the same as the one of the previous redraw,
+    /// so it doesn't need to be rendered again.
+    pub fn redraw(&mut self) -> (Scene, TreeUpdate, Damage) {
         // TODO - Xilem's reconciliation logic will have to be called
         // by the function that calls this
 
@@ -278,8 +488,8 @@ impl RenderRoot {
                 .push_back(RenderRootSignal::RequestRedraw);
         }
 
-        // TODO - Improve caching of scenes.
-        (self.root_paint(), self.root_accessibility())
+        let (scene, damage) = self.root_paint();
+        (scene, self.root_accessibility(), damage)
     }
 
     pub fn pop_signal(&mut self) -> Option<RenderRootSignal> {
@@ -378,9 +588,11 @@ impl RenderRoot {
     }
 
     // --- MARK: POINTER_EVENT ---
-    fn root_on_pointer_event(&mut self, event: PointerEvent) -> Handled {
+    fn root_on_pointer_event(&mut self, mut event: PointerEvent) -> Handled {
         let mut dummy_state = WidgetState::synthetic(self.root.id(), self.get_kurbo_size());
 
+        self.count_clicks(&mut event);
+
         // TODO - Factor out into a "pre-event" function?
         self.state.next_focused_widget = self.state.focused_widget;
 
@@ -393,6 +605,102 @@ impl RenderRoot {
         handled
     }
 
+    /// Set the click count of button events, by comparing presses with the previous one.
+    ///
+    /// Only presses of the primary pointer are counted; other pointers always have a count of 1.
+    fn count_clicks(&mut self, event: &mut PointerEvent) {
+        if !event.pointer_state().is_primary {
+            if let PointerEvent::PointerDown(_, state) | PointerEvent::PointerUp(_, state) = event {
+                state.count = 1;
+            }
+            return;
+        }
+        match event {
+            PointerEvent::PointerDown(button, state) => {
+                let now = self.state.now();
+                let count = match &self.state.last_pointer_down {
+                    Some(last)
+                        if last.button == *button
+                            && now.duration_since(last.time) <= MULTI_CLICK_INTERVAL
+                            && (last.position.x - state.position.x)
+                                .hypot(last.position.y - state.position.y)
+                                <= MULTI_CLICK_SLOP =>
+                    {
+                        last.count.saturating_add(1)
+                    }
+                    _ => 1,
+                };
+                state.count = count;
+                self.state.last_pointer_down = Some(PointerDownRecord {
+                    button: *button,
+                    position: state.position,
+                    time: now,
+                    count,
+                });
+            }
+            PointerEvent::PointerUp(button, state) => {
+                state.count = match &self.state.last_pointer_down {
+                    Some(last) if last.button == *button => last.count,
+                    _ => 1,
+                };
+            }
+            _ => {}
+        }
+    }
+
+    // --- MARK: TIMERS ---
+    /// The time at which the next timer expires, if any.
+    ///
+    /// The event loop should call [`handle_timers`](Self::handle_timers) at that time.
+    pub fn next_timer_deadline(&self) -> Option<Instant> {
+        // Woken widgets are handled like timers which expire right away.
+        if !self.state.wake_queue.is_empty() {
+            return Some(self.state.now());
+        }
+        self.state.timers.iter().map(|timer| timer.deadline).min()
+    }
+
+    /// Set the function called when a widget is woken by a [`WidgetWaker`](crate::WidgetWaker).
+    ///
+    /// The function may be called from any thread. It should make the event loop call
+    /// [`handle_timers`](Self::handle_timers), which handles the woken widgets.
+    pub fn set_wake_callback(&mut self, callback: impl Fn() + Send + Sync + 'static) {
+        *self.state.wake_queue.notify.lock().unwrap() = Some(Box::new(callback));
+    }
+
+    /// Call [`Widget::on_timer`] for every timer which expired, and every woken widget.
+    pub fn handle_timers(&mut self) {
+        let now = self.state.now();
+        for (widget_id, token) in self.state.wake_queue.take() {
+            self.state.timers.push(Timer {
+                token,
+                widget_id,
+                deadline: now,
+            });
+        }
+        let (mut expired, pending): (Vec<_>, Vec<_>) = std::mem::take(&mut self.state.timers)
+            .into_iter()
+            .partition(|timer| timer.deadline <= now);
+        self.state.timers = pending;
+        if expired.is_empty() {
+            return;
+        }
+        expired.sort_by_key(|timer| timer.deadline);
+
+        let mut dummy_state = WidgetState::synthetic(self.root.id(), self.get_kurbo_size());
+        self.state.next_focused_widget = self.state.focused_widget;
+
+        for timer in expired {
+            // The widget may have been removed since it requested the timer.
+            if self.widget_arena.has(timer.widget_id) {
+                root_on_timer(self, &mut dummy_state, &timer);
+            }
+        }
+
+        self.post_event_processing(&mut dummy_state);
+        self.get_root_widget().debug_validate(false);
+    }
+
     // --- MARK: TEXT_EVENT ---
     fn root_on_text_event(&mut self, event: TextEvent) -> Handled {
         let mut dummy_state = WidgetState::synthetic(self.root.id(), self.get_kurbo_size());
@@ -491,7 +799,8 @@ impl RenderRoot {
         layout_ctx.global_state.debug_logger.pop_span();
 
         if let WindowSizePolicy::Content = self.size_policy {
-            let new_size = LogicalSize::new(size.width, size.height).to_physical(self.scale_factor);
+            let new_size = LogicalSize::new(size.width, size.height)
+                .to_physical(layout_ctx.global_state.scale_factor);
             if self.size != new_size {
                 self.size = new_size;
                 layout_ctx
@@ -507,14 +816,15 @@ impl RenderRoot {
     }
 
     // --- MARK: PAINT ---
-    fn root_paint(&mut self) -> Scene {
+    fn root_paint(&mut self) -> (Scene, Damage) {
         root_paint(self)
     }
 
     // --- MARK: ACCESSIBILITY ---
     // TODO - Integrate in unit tests?
     fn root_accessibility(&mut self) -> TreeUpdate {
-        let mut tree_update = root_accessibility(self, self.rebuild_access_tree, self.scale_factor);
+        let mut tree_update =
+            root_accessibility(self, self.rebuild_access_tree, self.state.scale_factor);
         self.rebuild_access_tree = false;
 
         tree_update.tree = Some(Tree {
@@ -528,7 +838,7 @@ impl RenderRoot {
     }
 
     pub(crate) fn get_kurbo_size(&self) -> kurbo::Size {
-        let size = self.size.to_logical(self.scale_factor);
+        let size = self.size.to_logical(self.state.scale_factor);
         kurbo::Size::new(size.width, size.height)
     }
 
@@ -562,6 +872,8 @@ impl RenderRoot {
         if self.root_state().update_focus_chain {
             let event = LifeCycle::BuildFocusChain;
             self.root_lifecycle(event);
+            sort_focus_chain(&mut self.root_state().focus_chain);
+            self.update_focus_traps();
         }
 
         self.update_focus();
@@ -605,45 +917,170 @@ impl RenderRoot {
             self.root_lifecycle(event);
 
             // TODO: discriminate between text focus, and non-text focus.
-            self.state.signal_queue.push_back(if new.is_some() {
-                RenderRootSignal::StartIme
-            } else {
-                RenderRootSignal::EndIme
-            });
+            if old.is_some() {
+                // Restarting the input method discards any composition which belonged
+                // to the previously focused widget.
+                self.state.signal_queue.push_back(RenderRootSignal::EndIme);
+            }
+            if new.is_some() {
+                self.state
+                    .signal_queue
+                    .push_back(RenderRootSignal::StartIme);
+            }
         }
     }
 
-    pub(crate) fn widget_from_focus_chain(&mut self, forward: bool) -> Option<WidgetId> {
-        self.state.focused_widget.and_then(|focus| {
-            self.focus_chain()
+    /// Activate the focus traps which appeared since the last focus chain update, and
+    /// restore the focus taken by the ones which went away.
+    fn update_focus_traps(&mut self) {
+        let traps = self.root_state().focus_traps.clone();
+
+        while let Some(index) = self
+            .state
+            .focus_trap_stack
+            .iter()
+            .rposition(|(trap, _)| !traps.contains(trap))
+        {
+            let (_, previous_focus) = self.state.focus_trap_stack.remove(index);
+            let focus = self.state.next_focused_widget;
+            if !focus.is_some_and(|focus| self.is_in_focus_chain(focus)) {
+                self.state.next_focused_widget =
+                    previous_focus.filter(|previous| self.is_in_focus_chain(*previous));
+            }
+        }
+
+        // Traps are listed innermost first, so outer traps are activated first.
+        for trap in traps.into_iter().rev() {
+            if self
+                .state
+                .focus_trap_stack
                 .iter()
-                // Find where the focused widget is in the focus chain
-                .position(|id| id == &focus)
-                .map(|idx| {
-                    // Return the id that's next to it in the focus chain
-                    let len = self.focus_chain().len();
-                    let new_idx = if forward {
-                        (idx + 1) % len
-                    } else {
-                        (idx + len - 1) % len
-                    };
-                    self.focus_chain()[new_idx]
-                })
-                .or_else(|| {
-                    // If the currently focused widget isn't in the focus chain,
-                    // then we'll just return the first/last entry of the chain, if any.
-                    if forward {
-                        self.focus_chain().first().copied()
-                    } else {
-                        self.focus_chain().last().copied()
-                    }
-                })
-        })
+                .any(|(active, _)| *active == trap)
+            {
+                continue;
+            }
+            let previous_focus = self.state.next_focused_widget;
+            self.state.focus_trap_stack.push((trap, previous_focus));
+            let trap_chain = self.focus_chain_of(trap);
+            if !previous_focus.is_some_and(|focus| trap_chain.contains(&focus)) {
+                self.state.next_focused_widget = trap_chain.first().copied();
+            }
+        }
+    }
+
+    /// Move the focus to the next widget in the given direction.
+    ///
+    /// Returns `false` if there is no widget to move to.
+    pub(crate) fn move_focus(&mut self, direction: FocusDirection) -> bool {
+        let next = match direction {
+            FocusDirection::Next => self.widget_from_focus_chain(true),
+            FocusDirection::Previous => self.widget_from_focus_chain(false),
+            _ => self.widget_in_direction(direction),
+        };
+        let Some(next) = next else {
+            return false;
+        };
+        self.state.next_focused_widget = Some(next);
+        self.state.focus_visible = true;
+        true
     }
 
-    // TODO - Store in RenderRootState
-    pub(crate) fn focus_chain(&mut self) -> &[WidgetId] {
-        &self.root_state().focus_chain
+    pub(crate) fn widget_from_focus_chain(&mut self, forward: bool) -> Option<WidgetId> {
+        let focus_chain = self.focus_chain();
+        let Some(focus) = self.state.focused_widget else {
+            // If nothing is focused, we start from either end of the chain.
+            return if forward {
+                focus_chain.first().copied()
+            } else {
+                focus_chain.last().copied()
+            };
+        };
+        focus_chain
+            .iter()
+            // Find where the focused widget is in the focus chain
+            .position(|id| id == &focus)
+            .map(|idx| {
+                // Return the id that's next to it in the focus chain
+                let len = focus_chain.len();
+                let new_idx = if forward {
+                    (idx + 1) % len
+                } else {
+                    (idx + len - 1) % len
+                };
+                focus_chain[new_idx]
+            })
+            .or_else(|| {
+                // If the currently focused widget isn't in the focus chain,
+                // then we'll just return the first/last entry of the chain, if any.
+                if forward {
+                    focus_chain.first().copied()
+                } else {
+                    focus_chain.last().copied()
+                }
+            })
+    }
+
+    /// Find the closest widget in the given direction, among the widgets of the innermost
+    /// directional focus scope containing the focused widget.
+    fn widget_in_direction(&mut self, direction: FocusDirection) -> Option<WidgetId> {
+        let focus = self.state.focused_widget?;
+        let states = &self.widget_arena.widget_states;
+        let scope = states
+            .get_id_path(focus.to_raw())
+            .into_iter()
+            .filter_map(|id| states.find(id))
+            .find(|state| state.item.focus_scope.is_some())?;
+        if !scope
+            .item
+            .focus_scope
+            .is_some_and(|scope| scope.directional)
+        {
+            return None;
+        }
+        let candidates: Vec<_> = self
+            .focus_chain_of(scope.item.id)
+            .into_iter()
+            .filter(|id| *id != focus)
+            .filter_map(|id| {
+                let state = self.widget_arena.widget_states.find(id.to_raw())?;
+                Some((id, state.item.window_layout_rect()))
+            })
+            .collect();
+        let focus_rect = states.find(focus.to_raw())?.item.window_layout_rect();
+        closest_in_direction(focus_rect, direction, candidates)
+    }
+
+    /// The focus chain that Tab navigation moves through.
+    ///
+    /// This is the chain of the most recent focus trap, or of the whole window if there
+    /// is no trap.
+    pub(crate) fn focus_chain(&mut self) -> Vec<WidgetId> {
+        match self.state.focus_trap_stack.last() {
+            Some((trap, _)) => self.focus_chain_of(*trap),
+            None => self.focus_chain_of(self.root.id()),
+        }
+    }
+
+    fn focus_chain_of(&self, id: WidgetId) -> Vec<WidgetId> {
+        self.widget_arena
+            .widget_states
+            .find(id.to_raw())
+            .map(|state| {
+                state
+                    .item
+                    .focus_chain
+                    .iter()
+                    .map(|entry| entry.id)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn is_in_focus_chain(&self, id: WidgetId) -> bool {
+        self.widget_arena
+            .widget_states
+            .find(self.root.id().to_raw())
+            .is_some_and(|root| root.item.focus_chain.iter().any(|entry| entry.id == id))
     }
 }
 
@@ -3,27 +3,36 @@
 
 //! Tools and infrastructure for testing widgets.
 
+use std::collections::HashMap;
 use std::num::NonZeroUsize;
+use std::time::Duration;
 
+use accesskit::TreeUpdate;
 use image::{DynamicImage, ImageReader, Rgba, RgbaImage};
 use tracing::debug;
 use vello::util::RenderContext;
-use vello::{block_on_wgpu, RendererOptions};
+use vello::{block_on_wgpu, RendererOptions, Scene};
 use wgpu::{
     BufferDescriptor, BufferUsages, CommandEncoderDescriptor, Extent3d, ImageCopyBuffer,
     TextureDescriptor, TextureFormat, TextureUsages,
 };
-use winit::event::Ime;
+use winit::event::{Ime, TouchPhase};
 
 use super::screenshots::get_image_diff;
 use super::snapshot_utils::get_cargo_workspace;
 use crate::action::Action;
-use crate::dpi::{LogicalPosition, PhysicalPosition, PhysicalSize};
-use crate::event::{PointerButton, PointerEvent, PointerState, TextEvent, WindowEvent};
-use crate::render_root::{RenderRoot, RenderRootOptions, RenderRootSignal, WindowSizePolicy};
+use crate::app_driver::{AppDriver, DriverCtx};
+use crate::dpi::{LogicalPosition, LogicalSize, PhysicalSize};
+use crate::event::{
+    PointerButton, PointerEvent, PointerId, PointerState, ScrollUnit, TextEvent, TouchTracker,
+    WindowEvent,
+};
+use crate::render_root::{
+    Damage, RenderRoot, RenderRootOptions, RenderRootSignal, WindowSizePolicy,
+};
 use crate::tracing_backend::try_init_test_tracing;
 use crate::widget::{WidgetMut, WidgetRef};
-use crate::{Color, Handled, Point, Size, Vec2, Widget, WidgetId};
+use crate::{Affine, Color, FocusDirection, Handled, Point, Rect, Size, Vec2, Widget, WidgetId};
 
 // TODO - Get shorter names
 // TODO - Make them associated consts
@@ -33,6 +42,9 @@ pub const HARNESS_DEFAULT_SIZE: Size = Size::new(400., 400.);
 /// Default background color for tests.
 pub const HARNESS_DEFAULT_BACKGROUND_COLOR: Color = Color::rgb8(0x29, 0x29, 0x29);
 
+/// Time between animation frames in tests, as on a 60Hz display.
+pub const ANIM_FRAME_INTERVAL: Duration = Duration::from_nanos(16_666_667);
+
 /// A safe headless environment to test widgets in.
 ///
 /// `TestHarness` is a type that simulates an [`AppRoot`](crate::AppRoot)
@@ -62,8 +74,8 @@ pub const HARNESS_DEFAULT_BACKGROUND_COLOR: Color = Color::rgb8(0x29, 0x29, 0x29
 ///
 /// `TestHarness` tries to act like the normal masonry environment. For instance, it will dispatch every `Command` sent during event handling, handle lifecycle methods, etc.
 ///
-/// The passage of time is simulated with the [`move_timers_forward`](Self::move_timers_forward) methods. **(TODO -
-/// Doesn't move animations forward.)**
+/// The passage of time is simulated with the [`move_timers_forward`](Self::move_timers_forward) methods,
+/// which also run animations.
 ///
 /// **(TODO - Painting invalidation might not be accurate.)**
 ///
@@ -119,8 +131,13 @@ pub const HARNESS_DEFAULT_BACKGROUND_COLOR: Color = Color::rgb8(0x29, 0x29, 0x29
 pub struct TestHarness {
     render_root: RenderRoot,
     mouse_state: PointerState,
+    touch_tracker: TouchTracker,
+    /// The position of the fingers touching the screen.
+    touch_positions: HashMap<u64, Point>,
     window_size: PhysicalSize<u32>,
+    scale_factor: f64,
     background_color: Color,
+    ime_cursor_area: Option<Rect>,
 }
 
 /// Assert a snapshot of a rendered frame of your app.
@@ -164,6 +181,21 @@ impl TestHarness {
         Self::create_with(root_widget, window_size, HARNESS_DEFAULT_BACKGROUND_COLOR)
     }
 
+    /// Builds harness with given root widget, window size in logical pixels and scale factor.
+    ///
+    /// The window is rendered at `window_size * scale_factor` physical pixels, so the same
+    /// widget can be snapshotted at different scale factors.
+    pub fn create_with_scale(
+        root_widget: impl Widget,
+        window_size: Size,
+        scale_factor: f64,
+    ) -> Self {
+        let mut harness =
+            Self::create_with(root_widget, window_size, HARNESS_DEFAULT_BACKGROUND_COLOR);
+        harness.set_scale_factor(scale_factor);
+        harness
+    }
+
     /// Builds harness with given root widget, canvas size and background color.
     pub fn create_with(
         root_widget: impl Widget,
@@ -190,9 +222,15 @@ impl TestHarness {
                 },
             ),
             mouse_state,
+            touch_tracker: TouchTracker::default(),
+            touch_positions: HashMap::new(),
             window_size,
+            scale_factor: 1.0,
             background_color,
+            ime_cursor_area: None,
         };
+        // Time only moves forward when the test says so.
+        harness.render_root.state.test_clock = Some(harness.render_root.state.now());
         const ROBOTO: &[u8] = include_bytes!(concat!(
             env!("CARGO_MANIFEST_DIR"),
             "/resources/fonts/roboto/Roboto-Regular.ttf"
@@ -247,11 +285,38 @@ impl TestHarness {
     }
 
     // --- MARK: RENDER ---
+    /// Run the paint pass without rendering, and return the areas of the window which
+    /// changed since the last paint pass.
+    ///
+    /// This doesn't need a GPU, so it can be used to check that widgets request
+    /// repaints correctly.
+    pub fn paint(&mut self) -> Damage {
+        let (_scene, _tree_update, damage) = self.render_root.redraw();
+        damage
+    }
+
+    /// Run the accessibility pass, along with the paint pass, and return the
+    /// accessibility tree update.
+    ///
+    /// The first update contains the whole tree; later ones only contain the nodes
+    /// which changed.
+    pub fn access_tree_update(&mut self) -> TreeUpdate {
+        let (_scene, tree_update, _damage) = self.render_root.redraw();
+        tree_update
+    }
+
     // TODO - We add way too many dependencies in this code
     // TODO - Should be async?
     /// Create a bitmap (an array of pixels), paint the window and return the bitmap as an 8-bits-per-channel RGB image.
     pub fn render(&mut self) -> RgbaImage {
-        let (scene, _tree_update) = self.render_root.redraw();
+        let (scene, _tree_update, _damage) = self.render_root.redraw();
+        let scene = if self.scale_factor == 1.0 {
+            scene
+        } else {
+            let mut scaled_scene = Scene::new();
+            scaled_scene.append(&scene, Some(Affine::scale(self.scale_factor)));
+            scaled_scene
+        };
         if std::env::var("SKIP_RENDER_TESTS").is_ok_and(|it| !it.is_empty()) {
             return RgbaImage::from_pixel(1, 1, Rgba([255, 255, 255, 255]));
         }
@@ -275,7 +340,6 @@ impl TestHarness {
         )
         .expect("Got non-Send/Sync error from creating renderer");
 
-        // TODO - fix window_size
         let (width, height) = (self.window_size.width, self.window_size.height);
         let render_params = vello::RenderParams {
             // TODO - Parameterize
@@ -350,18 +414,16 @@ impl TestHarness {
     // --- MARK: EVENT HELPERS ---
 
     /// Move an internal mouse state, and send a [`PointerMove`](PointerEvent::PointerMove) event to the window.
+    ///
+    /// `pos` is in logical pixels.
     pub fn mouse_move(&mut self, pos: impl Into<Point>) {
-        // FIXME - Account for scaling
         let pos = pos.into();
-        let pos = PhysicalPosition::new(pos.x, pos.y);
-        self.mouse_state.physical_position = pos;
+        let pos = LogicalPosition::new(pos.x, pos.y);
+        self.mouse_state.position = pos;
+        self.mouse_state.physical_position = pos.to_physical(self.scale_factor);
 
         debug!("Harness mouse moved to {}, {}", pos.x, pos.y);
 
-        // TODO: may want to support testing with non-unity scale factors.
-        let scale_factor = 1.0;
-        self.mouse_state.position = pos.to_logical(scale_factor);
-
         self.process_pointer_event(PointerEvent::PointerMove(self.mouse_state.clone()));
     }
 
@@ -377,15 +439,77 @@ impl TestHarness {
         self.process_pointer_event(PointerEvent::PointerUp(button, self.mouse_state.clone()));
     }
 
-    /// Send a [`MouseWheel`](PointerEvent::MouseWheel) event to the window.
+    /// Send a [`MouseWheel`](PointerEvent::MouseWheel) event to the window, with a delta in lines.
     pub fn mouse_wheel(&mut self, wheel_delta: Vec2) {
-        let pixel_delta = LogicalPosition::new(wheel_delta.x, wheel_delta.y);
+        let line_delta = LogicalPosition::new(wheel_delta.x, wheel_delta.y);
+        self.mouse_state.scroll_unit = ScrollUnit::Line;
+        self.mouse_state.scroll_phase = TouchPhase::Moved;
+        self.process_pointer_event(PointerEvent::MouseWheel(
+            line_delta,
+            self.mouse_state.clone(),
+        ));
+    }
+
+    /// Send a [`MouseWheel`](PointerEvent::MouseWheel) event to the window, with a delta in
+    /// pixels, as a touchpad would.
+    ///
+    /// Touchpads send a last event with [`TouchPhase::Ended`] when the fingers are lifted.
+    pub fn touchpad_scroll(&mut self, pixel_delta: Vec2, phase: TouchPhase) {
+        let pixel_delta = LogicalPosition::new(pixel_delta.x, pixel_delta.y);
+        self.mouse_state.scroll_unit = ScrollUnit::Pixel;
+        self.mouse_state.scroll_phase = phase;
         self.process_pointer_event(PointerEvent::MouseWheel(
             pixel_delta,
             self.mouse_state.clone(),
         ));
     }
 
+    /// Send the events of a finger touching the screen at `pos`.
+    ///
+    /// `touch_id` tells fingers apart, so that several fingers can touch the screen at once.
+    /// The first finger to touch the screen is the primary pointer, whose touches
+    /// are sent as if they were mouse clicks, until every finger is lifted.
+    pub fn touch_down(&mut self, touch_id: u64, pos: impl Into<Point>) {
+        let pos = pos.into();
+        self.touch_positions.insert(touch_id, pos);
+        debug!("Harness touch {} down at {}, {}", touch_id, pos.x, pos.y);
+        self.process_touch_event(touch_id, TouchPhase::Started, pos);
+    }
+
+    /// Send the events of a finger moving to `pos`.
+    pub fn touch_move(&mut self, touch_id: u64, pos: impl Into<Point>) {
+        let pos = pos.into();
+        self.touch_positions.insert(touch_id, pos);
+        self.process_touch_event(touch_id, TouchPhase::Moved, pos);
+    }
+
+    /// Send the events of a finger being lifted from the screen.
+    pub fn touch_up(&mut self, touch_id: u64) {
+        let pos = self.touch_positions.remove(&touch_id).unwrap_or_default();
+        self.process_touch_event(touch_id, TouchPhase::Ended, pos);
+    }
+
+    /// Send the events of a touch being cancelled, e.g. by the platform recognizing a gesture.
+    pub fn touch_cancel(&mut self, touch_id: u64) {
+        let pos = self.touch_positions.remove(&touch_id).unwrap_or_default();
+        self.process_touch_event(touch_id, TouchPhase::Cancelled, pos);
+    }
+
+    fn process_touch_event(&mut self, touch_id: u64, phase: TouchPhase, pos: Point) {
+        let pos = LogicalPosition::new(pos.x, pos.y);
+        let events = self.touch_tracker.pointer_events(
+            touch_id,
+            phase,
+            pos,
+            pos.to_physical(self.scale_factor),
+            None,
+            &self.mouse_state,
+        );
+        for event in events {
+            self.process_pointer_event(event);
+        }
+    }
+
     /// Send events that lead to a given widget being clicked.
     ///
     /// Combines [`mouse_move`](Self::mouse_move), [`mouse_button_press`](Self::mouse_button_press), and [`mouse_button_release`](Self::mouse_button_release).
@@ -398,6 +522,23 @@ impl TestHarness {
         self.mouse_button_release(PointerButton::Primary);
     }
 
+    /// Send events that lead to a given widget being double-clicked.
+    ///
+    /// Like [`mouse_click_on`](Self::mouse_click_on), but clicks twice without moving the
+    /// timers forward, so the second click has a count of 2.
+    pub fn mouse_double_click_on(&mut self, id: WidgetId) {
+        self.mouse_click_on(id);
+        self.mouse_button_press(PointerButton::Primary);
+        self.mouse_button_release(PointerButton::Primary);
+    }
+
+    /// Send a [`Pinch`](PointerEvent::Pinch) event to the window, as sent by touchpads.
+    ///
+    /// `delta` is the change in zoom, e.g. `0.1` to zoom in by 10%.
+    pub fn pinch(&mut self, delta: f64) {
+        self.process_pointer_event(PointerEvent::Pinch(delta, self.mouse_state.clone()));
+    }
+
     /// Use [`mouse_move`](Self::mouse_move) to set the internal mouse pos to the center of the given widget.
     pub fn mouse_move_to(&mut self, id: WidgetId) {
         // FIXME - handle case where the widget isn't visible
@@ -408,7 +549,6 @@ impl TestHarness {
         self.mouse_move(widget_center);
     }
 
-    // TODO - Handle complicated IME
     // TODO - Mock Winit keyboard events
     pub fn keyboard_type_chars(&mut self, text: &str) {
         // For each character
@@ -419,26 +559,103 @@ impl TestHarness {
         self.process_state_after_event();
     }
 
-    #[cfg(FALSE)]
+    /// Send the text an input method is composing to the focused widget.
+    ///
+    /// `cursor` is the byte range of `text` which the input method is working on,
+    /// or `None` to hide the caret. An empty `text` clears the composition, which is
+    /// how input methods cancel it.
+    pub fn ime_preedit(&mut self, text: &str, cursor: Option<(usize, usize)>) {
+        self.process_text_event(TextEvent::Ime(Ime::Preedit(text.to_string(), cursor)));
+    }
+
+    /// Commit text from an input method to the focused widget.
+    ///
+    /// Like winit, this clears the composition before committing the text.
+    pub fn ime_commit(&mut self, text: &str) {
+        self.ime_preedit("", None);
+        self.process_text_event(TextEvent::Ime(Ime::Commit(text.to_string())));
+    }
+
+    /// The area around the caret which the candidate window of the input method
+    /// should avoid covering, in logical pixels.
+    ///
+    /// This is the last area reported by a widget, as of the last paint pass.
+    pub fn ime_cursor_area(&mut self) -> Option<Rect> {
+        while let Some(signal) = self
+            .render_root
+            .pop_signal_matching(|signal| matches!(signal, RenderRootSignal::ImeMoved(..)))
+        {
+            if let RenderRootSignal::ImeMoved(position, size) = signal {
+                self.ime_cursor_area = Some(Rect::from_origin_size(
+                    (position.x, position.y),
+                    (size.width, size.height),
+                ));
+            }
+        }
+        self.ime_cursor_area
+    }
+
+    /// Move the focus as if the user pressed Tab, Shift+Tab or an arrow key, and no
+    /// widget handled the key.
+    ///
+    /// Returns `false` if there is no widget to move to in that direction.
+    pub fn move_focus(&mut self, direction: FocusDirection) -> bool {
+        let moved = self.render_root.handle_focus_navigation(direction);
+        self.process_state_after_event();
+        moved
+    }
+
+    /// Change the scale factor of the window, as if it was moved to a screen with a
+    /// different pixel density.
+    ///
+    /// The window keeps its size in logical pixels, so its size in physical pixels changes.
+    pub fn set_scale_factor(&mut self, scale_factor: f64) {
+        let logical_size: LogicalSize<f64> = self.window_size.to_logical(self.scale_factor);
+        self.scale_factor = scale_factor;
+        self.window_size = logical_size.to_physical(scale_factor);
+        self.process_window_event(WindowEvent::Rescale(scale_factor));
+        self.process_window_event(WindowEvent::Resize(self.window_size));
+    }
+
     /// Simulate the passage of time.
     ///
     /// If you create any timer in a widget, this method is the only way to trigger
     /// them in unit tests. The testing model assumes that everything else executes
     /// instantly, and timers are never triggered "spontaneously".
     ///
-    /// **(TODO - Doesn't move animations forward.)**
+    /// Timers fire in order, each at its own deadline, so timers requested while
+    /// handling a timer also fire if their deadline is within `duration`.
+    ///
+    /// While widgets request animation frames, an [`AnimFrame`](WindowEvent::AnimFrame)
+    /// is sent every [`ANIM_FRAME_INTERVAL`], as a display would.
     pub fn move_timers_forward(&mut self, duration: Duration) {
-        // TODO - handle animations
-        let tokens = self
-            .mock_app
-            .window
-            .mock_timer_queue
-            .as_mut()
-            .unwrap()
-            .move_forward(duration);
-        for token in tokens {
-            self.process_event(Event::Timer(token));
+        let end = self.render_root.state.now() + duration;
+        loop {
+            let now = self.render_root.state.now();
+            let next_timer = self
+                .render_root
+                .next_timer_deadline()
+                .filter(|deadline| *deadline <= end);
+            let next_frame = self
+                .render_root
+                .root_state()
+                .request_anim
+                .then(|| now + ANIM_FRAME_INTERVAL)
+                .filter(|frame| *frame <= end);
+            if let Some(deadline) =
+                next_timer.filter(|deadline| next_frame.map_or(true, |frame| *deadline <= frame))
+            {
+                self.render_root.state.test_clock = Some(deadline);
+                self.render_root.handle_timers();
+                self.process_state_after_event();
+            } else if let Some(frame) = next_frame {
+                self.render_root.state.test_clock = Some(frame);
+                self.process_window_event(WindowEvent::AnimFrame);
+            } else {
+                break;
+            }
         }
+        self.render_root.state.test_clock = Some(end);
     }
 
     // --- MARK: GETTERS ---
@@ -473,16 +690,21 @@ impl TestHarness {
             .find_widget_by_id(self.render_root.state.focused_widget?)
     }
 
-    // TODO - Multiple pointers
+    /// Return the widget which captured the mouse.
     pub fn pointer_capture_target(&self) -> Option<WidgetRef<'_, dyn Widget>> {
         self.render_root
             .widget_arena
-            .try_get_widget_ref(self.render_root.state.pointer_capture_target?)
+            .try_get_widget_ref(self.pointer_capture_target_id()?)
     }
 
     // TODO - This is kinda redundant with the above
     pub fn pointer_capture_target_id(&self) -> Option<WidgetId> {
-        self.render_root.state.pointer_capture_target
+        self.capture_target_id_of(PointerId::Mouse)
+    }
+
+    /// Return the id of the widget which captured the given pointer, e.g. a finger.
+    pub fn capture_target_id_of(&self, pointer: PointerId) -> Option<WidgetId> {
+        self.render_root.state.pointer_capture_target(pointer)
     }
 
     /// Call the provided visitor on every widget in the widget tree.
@@ -539,6 +761,23 @@ impl TestHarness {
         }
     }
 
+    /// Run `f` with a [`DriverCtx`] for the root widget, as the event loop does when
+    /// calling an [`AppDriver`].
+    pub fn with_driver_ctx<R>(&mut self, f: impl FnOnce(&mut DriverCtx<'_>) -> R) -> R {
+        self.edit_root_widget(|root| {
+            f(&mut DriverCtx {
+                main_root_widget: root,
+            })
+        })
+    }
+
+    /// Pass the queued actions to `driver`, as the event loop does.
+    pub fn handle_actions(&mut self, driver: &mut impl AppDriver) {
+        while let Some((action, widget_id)) = self.pop_action() {
+            self.with_driver_ctx(|ctx| driver.on_action(ctx, widget_id, action));
+        }
+    }
+
     // --- MARK: SNAPSHOT ---
 
     /// Method used by [`assert_render_snapshot`]. Use the macro instead.
@@ -27,6 +27,7 @@ use crate::*;
 pub type PointerEventFn<S> = dyn FnMut(&mut S, &mut EventCtx, &PointerEvent);
 pub type TextEventFn<S> = dyn FnMut(&mut S, &mut EventCtx, &TextEvent);
 pub type AccessEventFn<S> = dyn FnMut(&mut S, &mut EventCtx, &AccessEvent);
+pub type TimerFn<S> = dyn FnMut(&mut S, &mut EventCtx, TimerToken);
 pub type StatusChangeFn<S> = dyn FnMut(&mut S, &mut LifeCycleCtx, &StatusChange);
 pub type LifeCycleFn<S> = dyn FnMut(&mut S, &mut LifeCycleCtx, &LifeCycle);
 pub type LayoutFn<S> = dyn FnMut(&mut S, &mut LayoutCtx, &BoxConstraints) -> Size;
@@ -47,6 +48,7 @@ pub struct ModularWidget<S> {
     on_pointer_event: Option<Box<PointerEventFn<S>>>,
     on_text_event: Option<Box<TextEventFn<S>>>,
     on_access_event: Option<Box<AccessEventFn<S>>>,
+    on_timer: Option<Box<TimerFn<S>>>,
     on_status_change: Option<Box<StatusChangeFn<S>>>,
     lifecycle: Option<Box<LifeCycleFn<S>>>,
     layout: Option<Box<LayoutFn<S>>>,
@@ -95,6 +97,7 @@ pub enum Record {
     PE(PointerEvent),
     TE(TextEvent),
     AE(AccessEvent),
+    Timer(TimerToken),
     SC(StatusChange),
     L(LifeCycle),
     Layout(Size),
@@ -126,6 +129,7 @@ impl<S> ModularWidget<S> {
             on_pointer_event: None,
             on_text_event: None,
             on_access_event: None,
+            on_timer: None,
             on_status_change: None,
             lifecycle: None,
             layout: None,
@@ -161,6 +165,11 @@ impl<S> ModularWidget<S> {
         self
     }
 
+    pub fn timer_fn(mut self, f: impl FnMut(&mut S, &mut EventCtx, TimerToken) + 'static) -> Self {
+        self.on_timer = Some(Box::new(f));
+        self
+    }
+
     pub fn status_change_fn(
         mut self,
         f: impl FnMut(&mut S, &mut LifeCycleCtx, &StatusChange) + 'static,
@@ -235,6 +244,12 @@ impl<S: 'static> Widget for ModularWidget<S> {
         }
     }
 
+    fn on_timer(&mut self, ctx: &mut EventCtx, token: TimerToken) {
+        if let Some(f) = self.on_timer.as_mut() {
+            f(&mut self.state, ctx, token);
+        }
+    }
+
     fn on_status_change(&mut self, ctx: &mut LifeCycleCtx, event: &StatusChange) {
         if let Some(f) = self.on_status_change.as_mut() {
             f(&mut self.state, ctx, event);
@@ -396,6 +411,11 @@ impl<W: Widget> Widget for Recorder<W> {
         self.child.on_access_event(ctx, event);
     }
 
+    fn on_timer(&mut self, ctx: &mut EventCtx, token: TimerToken) {
+        self.recording.push(Record::Timer(token));
+        self.child.on_timer(ctx, token);
+    }
+
     fn on_status_change(&mut self, ctx: &mut LifeCycleCtx, event: &StatusChange) {
         self.recording.push(Record::SC(event.clone()));
         self.child.on_status_change(ctx, event);
@@ -14,7 +14,9 @@ mod screenshots;
 #[cfg(not(tarpaulin_include))]
 mod snapshot_utils;
 
-pub use harness::{TestHarness, HARNESS_DEFAULT_BACKGROUND_COLOR, HARNESS_DEFAULT_SIZE};
+pub use harness::{
+    TestHarness, ANIM_FRAME_INTERVAL, HARNESS_DEFAULT_BACKGROUND_COLOR, HARNESS_DEFAULT_SIZE,
+};
 pub use helper_widgets::{ModularWidget, Record, Recorder, Recording, ReplaceChild, TestWidgetExt};
 
 use crate::WidgetId;
@@ -5,7 +5,7 @@ use std::ops::{Deref, DerefMut, Range};
 
 use parley::{FontContext, LayoutContext};
 use tracing::warn;
-use vello::kurbo::Point;
+use vello::kurbo::{Affine, BezPath, Line, Point, Rect, Stroke};
 use vello::Scene;
 use winit::{
     event::Ime,
@@ -20,9 +20,13 @@ use crate::{
 use super::{
     offset_for_delete_backwards,
     selection::{Affinity, Selection},
-    Selectable, TextBrush, TextWithSelection,
+    Misspelling, Selectable, Spellchecker, TextBrush, TextChange, TextWithSelection,
 };
 
+/// The thickness of the underline below the part of the preedit which the input method is
+/// working on, such as the clause being converted.
+const PREEDIT_CURSOR_UNDERLINE: f32 = 2.0;
+
 /// Text which can be edited
 pub trait EditableText: Selectable {
     /// Replace range with new text.
@@ -60,6 +64,15 @@ pub struct TextEditor<T: EditableText> {
     inner: TextWithSelection<T>,
     /// The range of the preedit region in the text
     preedit_range: Option<Range<usize>>,
+    /// The range of the preedit which the input method is working on, in the whole text.
+    ///
+    /// If this is `None` while composing, the caret is hidden.
+    preedit_cursor: Option<Range<usize>>,
+    spellchecker: Option<Box<dyn Spellchecker>>,
+    /// The misspellings found by the spellchecker.
+    misspellings: Vec<Misspelling>,
+    /// The length of the text last given to the spellchecker.
+    spellchecked_len: usize,
 }
 
 impl<T: EditableText> TextEditor<T> {
@@ -67,11 +80,115 @@ impl<T: EditableText> TextEditor<T> {
         Self {
             inner: TextWithSelection::new(text, text_size),
             preedit_range: None,
+            preedit_cursor: None,
+            spellchecker: None,
+            misspellings: Vec::new(),
+            spellchecked_len: 0,
         }
     }
 
+    /// Set the spellchecker, which finds the misspelled words of the text.
+    ///
+    /// The whole text is checked straight away.
+    pub fn set_spellchecker(&mut self, spellchecker: Option<Box<dyn Spellchecker>>) {
+        self.spellchecker = spellchecker;
+        self.spellchecked_len = 0;
+        self.misspellings.clear();
+        self.recheck_spelling();
+    }
+
+    /// Check the whole text again.
+    ///
+    /// This must be called after the text is changed by something other than the editor,
+    /// such as [`TextWithSelection::set_text`].
+    pub fn recheck_spelling(&mut self) {
+        let len = self.text().as_str().len();
+        let change = TextChange::replace_all(self.spellchecked_len, len);
+        self.spellcheck(change);
+    }
+
+    /// The misspelled ranges of the text, in order.
+    pub fn misspellings(&self) -> &[Misspelling] {
+        &self.misspellings
+    }
+
+    /// The suggestions of the spellchecker for the misspelled word at `range`.
+    pub fn spelling_suggestions(&mut self, range: Range<usize>) -> Vec<String> {
+        let Some(spellchecker) = self.spellchecker.as_mut() else {
+            return Vec::new();
+        };
+        spellchecker.suggest(&self.inner.text().as_str()[range])
+    }
+
+    /// The misspelling at or next to a position in the text.
+    pub fn misspelling_at(&self, text_pos: usize) -> Option<&Misspelling> {
+        self.misspellings.iter().find(|misspelling| {
+            misspelling.range.contains(&text_pos) || misspelling.range.end == text_pos
+        })
+    }
+
+    /// Replace a range of the text, usually a misspelled word, by a suggestion.
+    ///
+    /// The caret is placed after the suggestion.
+    pub fn apply_suggestion(&mut self, range: Range<usize>, suggestion: &str) {
+        self.reset_preedit();
+        self.edit(range.clone(), suggestion);
+        self.selection = Some(Selection::caret(
+            range.start + suggestion.len(),
+            Affinity::Upstream,
+        ));
+    }
+
+    /// Forget the preedit region, keeping its text.
     pub fn reset_preedit(&mut self) {
-        self.preedit_range = None;
+        self.preedit_cursor = None;
+        if let Some(preedit) = self.preedit_range.take() {
+            // The text which was being composed is final now, so it is checked.
+            self.spellcheck(TextChange {
+                old_range: preedit.clone(),
+                new_range: preedit,
+            });
+        }
+    }
+
+    /// Whether the user is composing text with an input method.
+    ///
+    /// The text being composed is part of [`text`](super::TextLayout::text), but it
+    /// only becomes final once the input method commits it.
+    pub fn is_composing(&self) -> bool {
+        self.preedit_range.is_some()
+    }
+
+    /// Call when another widget becomes focused.
+    ///
+    /// The text being composed is kept as if it had been committed.
+    /// Returns `true` if this changed the committed text.
+    pub fn focus_lost(&mut self) -> bool {
+        let was_composing = self.is_composing();
+        self.reset_preedit();
+        self.inner.focus_lost();
+        was_composing
+    }
+
+    /// The area of the caret, relative to the origin of the text.
+    ///
+    /// While composing, this is the start of the part of the preedit which the input method
+    /// is working on. Input methods place their candidate window next to this area.
+    ///
+    /// This is not meaningful until [`Self::rebuild`] has been called.
+    pub fn ime_cursor_area(&self) -> Option<Rect> {
+        let (position, affinity) = match (&self.preedit_cursor, &self.preedit_range) {
+            (Some(cursor), _) => (cursor.start, Affinity::Downstream),
+            (None, Some(preedit)) => (preedit.start, Affinity::Downstream),
+            (None, None) => {
+                let selection = self.inner.selection?;
+                (selection.active, selection.active_affinity)
+            }
+        };
+        let (line, _) = self
+            .layout
+            .caret_lines_for_text_position(position, affinity);
+        Some(Rect::from_points(line.p0, line.p1))
     }
 
     /// Rebuild the text.
@@ -89,27 +206,63 @@ impl<T: EditableText> TextEditor<T> {
                         &parley::style::StyleProperty::Underline(true),
                         range.clone(),
                     );
+                    // The part the input method is working on gets a thicker underline.
+                    if let Some(cursor) = self.preedit_cursor.clone().filter(|c| !c.is_empty()) {
+                        builder.push(
+                            &parley::style::StyleProperty::UnderlineSize(Some(
+                                PREEDIT_CURSOR_UNDERLINE,
+                            )),
+                            cursor,
+                        );
+                    }
                 }
                 builder
             });
     }
 
     pub fn draw(&mut self, scene: &mut Scene, point: impl Into<Point>) {
-        self.inner.draw(scene, point);
+        let point: Point = point.into();
+        if self.is_composing() && self.preedit_cursor.is_none() {
+            // The input method asked us to hide the caret.
+            self.inner.layout.draw(scene, point);
+        } else {
+            self.inner.draw(scene, point);
+        }
+        let transform = Affine::translate(point.to_vec2());
+        for misspelling in &self.misspellings {
+            for (line, thickness) in self
+                .inner
+                .layout
+                .underlines_for_range(misspelling.range.clone())
+            {
+                scene.stroke(
+                    &Stroke::new(thickness),
+                    transform,
+                    crate::theme::SPELLING_ERROR_COLOR,
+                    None,
+                    &squiggle(line, thickness),
+                );
+            }
+        }
     }
 
+    /// Handle a pointer button being pressed at `position`, relative to the text.
     pub fn pointer_down(
         &mut self,
-        origin: Point,
+        position: Point,
         state: &PointerState,
         button: PointerButton,
     ) -> bool {
         // TODO: If we have a selection and we're hovering over it,
         // implement (optional?) click and drag
-        self.inner.pointer_down(origin, state, button)
+        self.inner.pointer_down(position, state, button)
     }
 
     pub fn text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) -> Handled {
+        if self.is_composing() && matches!(event, TextEvent::KeyboardKey(..)) {
+            // The input method owns the keyboard until it commits or cancels the preedit.
+            return Handled::Yes;
+        }
         let inner_handled = self.inner.text_event(event);
         if inner_handled.is_handled() {
             return inner_handled;
@@ -122,7 +275,7 @@ impl<T: EditableText> TextEditor<T> {
                         Key::Named(NamedKey::Backspace) => {
                             if let Some(selection) = self.inner.selection {
                                 if !selection.is_caret() {
-                                    self.text_mut().edit(selection.range(), "");
+                                    self.edit(selection.range(), "");
                                     self.inner.selection =
                                         Some(Selection::caret(selection.min(), Affinity::Upstream));
 
@@ -136,7 +289,7 @@ impl<T: EditableText> TextEditor<T> {
                                     let text = self.text_mut();
                                     let offset =
                                         offset_for_delete_backwards(selection.active, text);
-                                    self.text_mut().edit(offset..selection.active, "");
+                                    self.edit(offset..selection.active, "");
                                     self.inner.selection =
                                         Some(Selection::caret(offset, selection.active_affinity));
 
@@ -151,7 +304,7 @@ impl<T: EditableText> TextEditor<T> {
                         Key::Named(NamedKey::Delete) => {
                             if let Some(selection) = self.inner.selection {
                                 if !selection.is_caret() {
-                                    self.text_mut().edit(selection.range(), "");
+                                    self.edit(selection.range(), "");
                                     self.inner.selection = Some(Selection::caret(
                                         selection.min(),
                                         Affinity::Downstream,
@@ -162,7 +315,7 @@ impl<T: EditableText> TextEditor<T> {
                                 } else if let Some(offset) =
                                     self.text().next_grapheme_offset(selection.active)
                                 {
-                                    self.text_mut().edit(selection.min()..offset, "");
+                                    self.edit(selection.min()..offset, "");
                                     self.inner.selection = Some(Selection::caret(
                                         selection.min(),
                                         selection.active_affinity,
@@ -183,10 +336,10 @@ impl<T: EditableText> TextEditor<T> {
                                 active_affinity: Affinity::Downstream,
                                 h_pos: None,
                             });
-                            let c = ' ';
-                            self.text_mut().edit(selection.range(), c);
+                            let c = " ";
+                            self.edit(selection.range(), c);
                             self.inner.selection = Some(Selection::caret(
-                                selection.min() + c.len_utf8(),
+                                selection.min() + c.len(),
                                 // We have just added this character, so we are "affined" with it
                                 Affinity::Downstream,
                             ));
@@ -219,13 +372,13 @@ impl<T: EditableText> TextEditor<T> {
                         Key::Named(NamedKey::Backspace) => {
                             if let Some(selection) = self.inner.selection {
                                 if !selection.is_caret() {
-                                    self.text_mut().edit(selection.range(), "");
+                                    self.edit(selection.range(), "");
                                     self.inner.selection =
                                         Some(Selection::caret(selection.min(), Affinity::Upstream));
                                 }
                                 let offset =
                                     self.text().prev_word_offset(selection.active).unwrap_or(0);
-                                self.text_mut().edit(offset..selection.active, "");
+                                self.edit(offset..selection.active, "");
                                 self.inner.selection =
                                     Some(Selection::caret(offset, Affinity::Upstream));
 
@@ -239,7 +392,7 @@ impl<T: EditableText> TextEditor<T> {
                         Key::Named(NamedKey::Delete) => {
                             if let Some(selection) = self.inner.selection {
                                 if !selection.is_caret() {
-                                    self.text_mut().edit(selection.range(), "");
+                                    self.edit(selection.range(), "");
                                     self.inner.selection = Some(Selection::caret(
                                         selection.min(),
                                         Affinity::Downstream,
@@ -247,7 +400,7 @@ impl<T: EditableText> TextEditor<T> {
                                 } else if let Some(offset) =
                                     self.text().next_word_offset(selection.active)
                                 {
-                                    self.text_mut().edit(selection.active..offset, "");
+                                    self.edit(selection.active..offset, "");
                                     self.inner.selection =
                                         Some(Selection::caret(selection.min(), Affinity::Upstream));
                                 }
@@ -267,88 +420,49 @@ impl<T: EditableText> TextEditor<T> {
             TextEvent::KeyboardKey(_, _) => Handled::No,
             TextEvent::Ime(ime) => match ime {
                 Ime::Commit(text) => {
-                    if let Some(selection_range) = self.selection.map(|x| x.range()) {
-                        self.text_mut().edit(selection_range.clone(), text);
-                        self.selection = Some(Selection::caret(
-                            selection_range.start + text.len(),
-                            Affinity::Upstream,
-                        ));
-                    }
-                    let contents = self.text().as_str().to_string();
-                    ctx.submit_action(Action::TextChanged(contents));
-                    Handled::Yes
+                    // The platform usually clears the preedit first, but if it didn't,
+                    // the committed text replaces it.
+                    self.clear_preedit();
+                    self.insert_text(text, ctx)
                 }
-                Ime::Preedit(preedit_string, preedit_sel) => {
-                    if let Some(preedit) = self.preedit_range.clone() {
-                        // TODO: Handle the case where this is the same value, to avoid some potential infinite loops
-                        self.text_mut().edit(preedit.clone(), preedit_string);
-                        let np = preedit.start..(preedit.start + preedit_string.len());
-                        self.preedit_range = if preedit_string.is_empty() {
-                            None
-                        } else {
-                            Some(np.clone())
-                        };
-                        self.selection = if let Some(pec) = preedit_sel {
-                            Some(Selection::new(
-                                np.start + pec.0,
-                                np.start + pec.1,
-                                Affinity::Upstream,
-                            ))
-                        } else {
-                            Some(Selection::caret(np.end, Affinity::Upstream))
-                        };
+                Ime::Preedit(preedit_string, preedit_cursor) => {
+                    // An empty preedit is sent by some environments when the context of
+                    // a text input has changed, even if there was no preedit; this also
+                    // avoids some potential infinite loops.
+                    if preedit_string.is_empty() && !self.is_composing() {
+                        return Handled::Yes;
+                    }
+                    // The preedit replaces the previous preedit, or the selection when
+                    // composition starts.
+                    let range = self
+                        .preedit_range
+                        .clone()
+                        .unwrap_or_else(|| self.selection.map(|x| x.range()).unwrap_or(0..0));
+                    let start = range.start;
+                    // The preedit range is updated before the edit, so that the text being
+                    // composed isn't spellchecked.
+                    self.preedit_range =
+                        (!preedit_string.is_empty()).then(|| start..start + preedit_string.len());
+                    self.edit(range, preedit_string);
+                    if preedit_string.is_empty() {
+                        self.reset_preedit();
+                        self.selection = Some(Selection::caret(start, Affinity::Upstream));
                     } else {
-                        // If we've been sent an event to clear the preedit,
-                        // but there was no existing pre-edit, there's nothing to do
-                        // so we report that the event has been handled
-                        // An empty preedit is sent by some environments when the
-                        // context of a text input has changed, even if the contents
-                        // haven't; this also avoids some potential infinite loops
-                        if preedit_string.is_empty() {
-                            return Handled::Yes;
-                        }
-                        let sr = self.selection.map(|x| x.range()).unwrap_or(0..0);
-                        self.text_mut().edit(sr.clone(), preedit_string);
-                        let np = sr.start..(sr.start + preedit_string.len());
-                        self.preedit_range = if preedit_string.is_empty() {
-                            None
-                        } else {
-                            Some(np.clone())
-                        };
-                        self.selection = if let Some(pec) = preedit_sel {
-                            Some(Selection::new(
-                                np.start + pec.0,
-                                np.start + pec.1,
-                                Affinity::Upstream,
-                            ))
-                        } else {
-                            Some(Selection::caret(np.start, Affinity::Upstream))
-                        };
+                        self.preedit_cursor =
+                            preedit_cursor.map(|(begin, end)| start + begin..start + end);
+                        let caret = preedit_cursor.map_or(preedit_string.len(), |(_, end)| end);
+                        self.selection = Some(Selection::caret(start + caret, Affinity::Upstream));
                     }
                     Handled::Yes
                 }
                 Ime::Enabled => {
                     // Generally this shouldn't happen, but I can't prove it won't.
-                    if let Some(preedit) = self.preedit_range.clone() {
-                        self.text_mut().edit(preedit.clone(), "");
-                        self.selection = Some(
-                            self.selection
-                                .unwrap_or(Selection::caret(0, Affinity::Upstream)),
-                        );
-                        self.preedit_range = None;
-                    }
+                    self.clear_preedit();
                     Handled::Yes
                 }
                 Ime::Disabled => {
-                    if let Some(preedit) = self.preedit_range.clone() {
-                        self.text_mut().edit(preedit.clone(), "");
-                        self.preedit_range = None;
-                        let sm = self.selection.map(|x| x.min()).unwrap_or(0);
-                        if preedit.contains(&sm) {
-                            self.selection =
-                                Some(Selection::caret(preedit.start, Affinity::Upstream));
-                        }
-                    }
+                    // Composition was cancelled.
+                    self.clear_preedit();
                     Handled::Yes
                 }
             },
@@ -357,14 +471,47 @@ impl<T: EditableText> TextEditor<T> {
         }
     }
 
-    fn insert_text(&mut self, c: &winit::keyboard::SmolStr, ctx: &mut EventCtx) -> Handled {
+    /// Replace `range` of the text by `new`, and tell the spellchecker.
+    fn edit(&mut self, range: Range<usize>, new: &str) {
+        let change = TextChange {
+            old_range: range.clone(),
+            new_range: range.start..range.start + new.len(),
+        };
+        self.text_mut().edit(range, new);
+        self.spellcheck(change);
+    }
+
+    fn spellcheck(&mut self, change: TextChange) {
+        if let Some(spellchecker) = self.spellchecker.as_mut() {
+            let text = self.inner.text().as_str();
+            self.misspellings = spellchecker.text_changed(text, &change);
+            self.spellchecked_len = text.len();
+            // The text being composed isn't final, so it is checked once it is committed.
+            if let Some(preedit) = &self.preedit_range {
+                self.misspellings.retain(|misspelling| {
+                    misspelling.range.end < preedit.start || preedit.end < misspelling.range.start
+                });
+            }
+        }
+    }
+
+    /// Remove the text being composed, if any, leaving the caret where it was.
+    fn clear_preedit(&mut self) {
+        if let Some(preedit) = self.preedit_range.take() {
+            self.preedit_cursor = None;
+            self.edit(preedit.clone(), "");
+            self.selection = Some(Selection::caret(preedit.start, Affinity::Upstream));
+        }
+    }
+
+    fn insert_text(&mut self, c: &str, ctx: &mut EventCtx) -> Handled {
         let selection = self.inner.selection.unwrap_or(Selection {
             anchor: 0,
             active: 0,
             active_affinity: Affinity::Downstream,
             h_pos: None,
         });
-        self.text_mut().edit(selection.range(), &**c);
+        self.edit(selection.range(), c);
         self.inner.selection = Some(Selection::caret(
             selection.min() + c.len(),
             // We have just added this character, so we are "affined" with it
@@ -376,6 +523,22 @@ impl<T: EditableText> TextEditor<T> {
     }
 }
 
+/// A zigzag along a horizontal `line`, to underline misspelled words.
+fn squiggle(line: Line, thickness: f64) -> BezPath {
+    let amplitude = thickness.max(1.);
+    let y = line.p0.y;
+    let mut path = BezPath::new();
+    path.move_to((line.p0.x, y));
+    let mut x = line.p0.x;
+    let mut up = true;
+    while x < line.p1.x {
+        x = (x + 2. * amplitude).min(line.p1.x);
+        path.line_to((x, if up { y - amplitude } else { y + amplitude }));
+        up = !up;
+    }
+    path
+}
+
 impl<T: EditableText> Deref for TextEditor<T> {
     type Target = TextWithSelection<T>;
 
@@ -3,17 +3,21 @@
 
 //! A type for laying out, drawing, and interacting with text.
 
+use std::ops::Range;
 use std::rc::Rc;
 
 use parley::context::RangedBuilder;
 use parley::fontique::{Style, Weight};
 use parley::layout::{Alignment, Cursor};
 use parley::style::{Brush as BrushTrait, FontFamily, FontStack, GenericFamily, StyleProperty};
+use parley::swash::text::{BidiClass, Codepoint as _};
 use parley::{FontContext, Layout, LayoutContext};
 use vello::kurbo::{Affine, Line, Point, Rect, Size};
 use vello::peniko::{self, Color, Gradient};
 use vello::Scene;
 
+use super::movement::WritingDirection;
+use super::selection::Affinity;
 use super::{Link, TextStorage};
 
 /// A component for displaying text on screen.
@@ -50,6 +54,7 @@ pub struct TextLayout<T> {
 
     alignment: Alignment,
     max_advance: Option<f32>,
+    text_is_rtl: bool,
 
     links: Rc<[(Rect, usize)]>,
 
@@ -155,6 +160,7 @@ impl<T> TextLayout<T> {
 
             max_advance: None,
             alignment: Default::default(),
+            text_is_rtl: false,
 
             links: Rc::new([]),
 
@@ -173,7 +179,17 @@ impl<T> TextLayout<T> {
         self.needs_line_breaks = true;
     }
 
-    /// Set the scaling factor
+    /// Set the scale factor of the window the text is displayed in.
+    ///
+    /// The text is laid out at this scale, so that glyphs are hinted for the
+    /// physical pixels of the screen. All the measurements returned by this type
+    /// are still in logical pixels, but the inner [`Layout`] returned by
+    /// [`Self::layout`] is in physical pixels.
+    ///
+    /// Widgets should call this with [`LayoutCtx::scale_factor`] before rebuilding
+    /// the layout.
+    ///
+    /// [`LayoutCtx::scale_factor`]: crate::LayoutCtx::scale_factor
     pub fn set_scale(&mut self, scale: f32) {
         if scale != self.scale {
             self.scale = scale;
@@ -265,15 +281,16 @@ impl<T> TextLayout<T> {
         self.needs_layout || self.needs_line_breaks
     }
 
-    // TODO: What are the valid use cases for this, where we shouldn't use a run-specific check instead?
-    // /// Returns `true` if this layout's text appears to be right-to-left.
-    // ///
-    // /// See [`piet::util::first_strong_rtl`] for more information.
-    // ///
-    // /// [`piet::util::first_strong_rtl`]: crate::piet::util::first_strong_rtl
-    // pub fn text_is_rtl(&self) -> bool {
-    //     self.text_is_rtl
-    // }
+    /// Returns `true` if this layout's text is in a right-to-left paragraph.
+    ///
+    /// The direction is that of the first strong directional character of the text,
+    /// ignoring isolated sections. [`Alignment::Start`] and [`Alignment::End`] are
+    /// relative to this direction.
+    ///
+    /// This is not meaningful until [`Self::rebuild`] has been called.
+    pub fn text_is_rtl(&self) -> bool {
+        self.text_is_rtl
+    }
 }
 
 impl<T: TextStorage> TextLayout<T> {
@@ -309,6 +326,9 @@ impl<T: TextStorage> TextLayout<T> {
     }
 
     /// Returns the inner Parley [`Layout`] value.
+    ///
+    /// Its measurements are in physical pixels; divide them by the scale set with
+    /// [`Self::set_scale`] to get logical pixels.
     pub fn layout(&self) -> &Layout<TextBrush> {
         self.assert_rebuilt("layout");
         &self.layout
@@ -319,7 +339,7 @@ impl<T: TextStorage> TextLayout<T> {
     /// This is not meaningful until [`Self::rebuild`] has been called.
     pub fn size(&self) -> Size {
         self.assert_rebuilt("size");
-        Size::new(self.layout.width().into(), self.layout.height().into())
+        self.logical_size(self.layout.width(), self.layout.height())
     }
 
     /// The size of the laid-out text, including any trailing whitespace.
@@ -327,7 +347,7 @@ impl<T: TextStorage> TextLayout<T> {
     /// This is not meaningful until [`Self::rebuild`] has been called.
     pub fn full_size(&self) -> Size {
         self.assert_rebuilt("full_size");
-        Size::new(self.layout.full_width().into(), self.layout.height().into())
+        self.logical_size(self.layout.full_width(), self.layout.height())
     }
 
     /// Return the text's [`LayoutMetrics`].
@@ -336,15 +356,19 @@ impl<T: TextStorage> TextLayout<T> {
     pub fn layout_metrics(&self) -> LayoutMetrics {
         self.assert_rebuilt("layout_metrics");
 
-        let first_baseline = self.layout.get(0).unwrap().metrics().baseline;
-        let size = Size::new(self.layout.width().into(), self.layout.height().into());
+        let first_baseline = self.layout.get(0).unwrap().metrics().baseline / self.scale;
+        let size = self.logical_size(self.layout.width(), self.layout.height());
         LayoutMetrics {
             size,
             first_baseline,
-            trailing_whitespace_width: self.layout.full_width(),
+            trailing_whitespace_width: self.layout.full_width() / self.scale,
         }
     }
 
+    fn logical_size(&self, width: f32, height: f32) -> Size {
+        Size::new((width / self.scale).into(), (height / self.scale).into())
+    }
+
     /// For a given `Point` (relative to this object's origin), returns index
     /// into the underlying text of the nearest grapheme boundary.
     ///
@@ -355,12 +379,18 @@ impl<T: TextStorage> TextLayout<T> {
         // TODO: This is a mostly good first pass, but doesn't handle cursor positions in
         // grapheme clusters within a parley cluster.
         // We can also try
-        Cursor::from_point(&self.layout, point.x as f32, point.y as f32)
+        let scale = self.scale as f64;
+        Cursor::from_point(
+            &self.layout,
+            (point.x * scale) as f32,
+            (point.y * scale) as f32,
+        )
     }
 
     /// Given the utf-8 position of a character boundary in the underlying text,
-    /// return the `Point` (relative to this object's origin) representing the
-    /// boundary of the containing grapheme.
+    /// return the [`Cursor`] at the boundary of the containing grapheme.
+    ///
+    /// Like the inner [`Layout`], the cursor's measurements are in physical pixels.
     ///
     /// # Panics
     ///
@@ -391,41 +421,412 @@ impl<T: TextStorage> TextLayout<T> {
     pub fn point_for_text_position(&self, text_pos: usize) -> Point {
         let cursor = self.cursor_for_text_position(text_pos);
         Point::new(
-            cursor.advance as f64,
-            (cursor.baseline + cursor.offset) as f64,
+            (cursor.advance / self.scale) as f64,
+            ((cursor.baseline + cursor.offset) / self.scale) as f64,
         )
     }
 
-    // TODO: needed for text selection
-    // /// Given a utf-8 range in the underlying text, return a `Vec` of `Rect`s
-    // /// representing the nominal bounding boxes of the text in that range.
-    // ///
-    // /// # Panics
-    // ///
-    // /// Panics if the range start or end is not a character boundary.
-    // pub fn rects_for_range(&self, range: Range<usize>) -> Vec<Rect> {
-    //     self.layout.rects_for_range(range)
-    // }
+    /// Given a utf-8 range in the underlying text, return a `Vec` of `Rect`s
+    /// representing the nominal bounding boxes of the text in that range.
+    ///
+    /// Within a line, a range of mixed left-to-right and right-to-left text can be
+    /// displayed in several separate places, so there may be several rectangles per line.
+    ///
+    /// This is not meaningful until [`Self::rebuild`] has been called.
+    pub fn rects_for_range(&self, range: Range<usize>) -> Vec<Rect> {
+        self.assert_rebuilt("rects_for_range");
+
+        let mut rects = Vec::new();
+        for (line_index, line) in self.layout.lines().enumerate() {
+            let metrics = line.metrics();
+            let y1 = metrics.baseline + metrics.descent;
+            let y0 = y1 - metrics.size();
+            for (x0, x1) in self.spans_for_range(line_index, &range) {
+                rects.push(self.logical_rect(x0, y0, x1, y1));
+            }
+        }
+        rects
+    }
+
+    /// Given a utf-8 range in the underlying text, return the `Line`s where the text
+    /// in that range would be underlined, with their thickness.
+    ///
+    /// The position and thickness of the underlines come from the font of each glyph run.
+    ///
+    /// This is not meaningful until [`Self::rebuild`] has been called.
+    pub fn underlines_for_range(&self, range: Range<usize>) -> Vec<(Line, f64)> {
+        self.assert_rebuilt("underlines_for_range");
+
+        let mut underlines = Vec::new();
+        for (line_index, line) in self.layout.lines().enumerate() {
+            let spans = self.spans_for_range(line_index, &range);
+            if spans.is_empty() {
+                continue;
+            }
+            for glyph_run in line.glyph_runs() {
+                let metrics = glyph_run.run().metrics();
+                let run_x0 = glyph_run.offset();
+                let run_x1 = run_x0 + glyph_run.advance();
+                // The offset is the distance from the baseline to the top of the underline.
+                let y =
+                    glyph_run.baseline() - metrics.underline_offset + metrics.underline_size / 2.;
+                for &(x0, x1) in &spans {
+                    let (x0, x1) = (x0.max(run_x0), x1.min(run_x1));
+                    if x0 < x1 {
+                        let scale = self.scale as f64;
+                        underlines.push((
+                            Line::new(
+                                (x0 as f64 / scale, y as f64 / scale),
+                                (x1 as f64 / scale, y as f64 / scale),
+                            ),
+                            metrics.underline_size as f64 / scale,
+                        ));
+                    }
+                }
+            }
+        }
+        underlines
+    }
 
     /// Given the utf-8 position of a character boundary in the underlying text,
     /// return a `Line` suitable for drawing a vertical cursor at that boundary.
     ///
+    /// This is the caret with [`Affinity::Downstream`]; see
+    /// [`Self::caret_lines_for_text_position`] for the general case.
+    ///
+    /// This is not meaningful until [`Self::rebuild`] has been called.
+    pub fn cursor_line_for_text_position(&self, text_pos: usize) -> Line {
+        self.caret_lines_for_text_position(text_pos, Affinity::Downstream)
+            .0
+    }
+
+    /// Given the utf-8 position of a character boundary in the underlying text and the
+    /// affinity of a caret there, return the `Line`s for drawing the caret.
+    ///
+    /// At the boundary between left-to-right and right-to-left text, a position is
+    /// displayed in two places, and the caret is split. The first line is where the
+    /// caret's affinity places it, next to the text it is attached to; the second line,
+    /// if any, is the other place, where text in the other direction would be inserted.
+    ///
     /// This is not meaningful until [`Self::rebuild`] has been called.
     // TODO: This is too simplistic. See https://raphlinus.github.io/text/2020/10/26/text-layout.html#shaping-cluster
     // for example. This would break in a `fi` ligature
-    pub fn cursor_line_for_text_position(&self, text_pos: usize) -> Line {
-        let from_position = self.cursor_for_text_position(text_pos);
+    pub fn caret_lines_for_text_position(
+        &self,
+        text_pos: usize,
+        affinity: Affinity,
+    ) -> (Line, Option<Line>) {
+        self.assert_rebuilt("caret_lines_for_text_position");
+
+        let (line_index, x) = self.caret_location(text_pos, affinity);
+        let other_affinity = match affinity {
+            Affinity::Upstream => Affinity::Downstream,
+            Affinity::Downstream => Affinity::Upstream,
+        };
+        let (other_line_index, other_x) = self.caret_location(text_pos, other_affinity);
+        // Soft line breaks also give a position two places, but on different lines.
+        let secondary = (other_line_index == line_index && (other_x - x).abs() >= 0.5)
+            .then(|| self.caret_line(line_index, other_x));
+        (self.caret_line(line_index, x), secondary)
+    }
+
+    /// For a given `Point` (relative to this object's origin), return the nearest
+    /// position between clusters where a caret can be placed, and the affinity which
+    /// places the caret at that point.
+    ///
+    /// This is not meaningful until [`Self::rebuild`] has been called.
+    pub fn caret_for_point(&self, point: Point) -> (usize, Affinity) {
+        self.assert_rebuilt("caret_for_point");
+
+        let y = (point.y * self.scale as f64) as f32;
+        let line_index = self
+            .layout
+            .lines()
+            .position(|line| {
+                let metrics = line.metrics();
+                y <= metrics.baseline + metrics.descent + metrics.leading * 0.5
+            })
+            .unwrap_or(self.layout.len().saturating_sub(1));
+        self.caret_for_line_and_x(line_index, (point.x * self.scale as f64) as f32)
+    }
+
+    /// The direction of the text at a caret position.
+    ///
+    /// This is the direction of the text the caret is attached to, which may differ
+    /// from the direction of the paragraph.
+    ///
+    /// This is not meaningful until [`Self::rebuild`] has been called.
+    pub fn writing_direction_at(&self, text_pos: usize, affinity: Affinity) -> WritingDirection {
+        let is_rtl = match self.attached_cluster(text_pos, affinity) {
+            Some((_, cluster)) => cluster.is_rtl,
+            None => self.text_is_rtl,
+        };
+        if is_rtl {
+            WritingDirection::RightToLeft
+        } else {
+            WritingDirection::LeftToRight
+        }
+    }
+
+    /// The caret position one cluster to the left or right of the given caret
+    /// position, as displayed on screen.
+    ///
+    /// Past the ends of a line, the caret moves to the next or previous line in the
+    /// direction of the paragraph.
+    pub(crate) fn caret_moved_visually(
+        &self,
+        text_pos: usize,
+        affinity: Affinity,
+        rightwards: bool,
+    ) -> (usize, Affinity) {
+        let (line_index, _) = self.caret_location(text_pos, affinity);
+        // Carets don't stop on either side of hard line breaks.
+        let clusters: Vec<_> = self
+            .visual_clusters(line_index)
+            .into_iter()
+            .filter(|cluster| !cluster.is_hard_line_break)
+            .collect();
+        let attached = self
+            .attached_cluster(text_pos, affinity)
+            .and_then(|(_, attached)| {
+                let index = clusters
+                    .iter()
+                    .position(|cluster| cluster.text_range == attached.text_range)?;
+                Some((index, text_pos == attached.left_position()))
+            });
+        // Otherwise, the caret is at the end of the line, next to a line break.
+        let current =
+            attached.or_else(|| clusters.len().checked_sub(1).map(|index| (index, false)));
+        if let Some((index, at_left_edge)) = current {
+            let target = match (rightwards, at_left_edge) {
+                (true, true) | (false, false) => Some(&clusters[index]),
+                (true, false) => clusters.get(index + 1),
+                (false, true) => index.checked_sub(1).map(|index| &clusters[index]),
+            };
+            if let Some(target) = target {
+                let position = if rightwards {
+                    target.right_position()
+                } else {
+                    target.left_position()
+                };
+                return (position, target.affinity_at(position));
+            }
+        }
+
+        let forwards = rightwards != self.text_is_rtl;
+        if forwards {
+            match self.layout.get(line_index + 1) {
+                Some(next_line) => (next_line.text_range().start, Affinity::Downstream),
+                None => (text_pos, affinity),
+            }
+        } else {
+            match line_index.checked_sub(1) {
+                Some(previous_line) => {
+                    let end = self
+                        .visual_clusters(previous_line)
+                        .into_iter()
+                        .filter(|cluster| !cluster.is_hard_line_break)
+                        .map(|cluster| cluster.text_range.end)
+                        .max();
+                    match end {
+                        Some(end) => (end, Affinity::Upstream),
+                        None => (
+                            self.layout.get(previous_line).unwrap().text_range().start,
+                            Affinity::Downstream,
+                        ),
+                    }
+                }
+                None => (text_pos, affinity),
+            }
+        }
+    }
 
-        let line = from_position.path.line(&self.layout).unwrap();
-        let line_metrics = line.metrics();
+    /// The index of the line where a caret is displayed, and the range of text of that line.
+    pub(crate) fn line_for_text_position(
+        &self,
+        text_pos: usize,
+        affinity: Affinity,
+    ) -> (usize, Range<usize>) {
+        let (line_index, _) = self.caret_location(text_pos, affinity);
+        let range = self
+            .layout
+            .get(line_index)
+            .map(|line| line.text_range())
+            .unwrap_or(0..0);
+        (
+            line_index,
+            range.start..range.end.min(self.text.as_str().len()),
+        )
+    }
 
+    /// The horizontal position of a caret, in logical pixels.
+    pub(crate) fn caret_x(&self, text_pos: usize, affinity: Affinity) -> f64 {
+        (self.caret_location(text_pos, affinity).1 / self.scale) as f64
+    }
+
+    /// The caret position nearest to `x` (in logical pixels) on the given line.
+    pub(crate) fn caret_for_line(&self, line_index: usize, x: f64) -> (usize, Affinity) {
+        self.caret_for_line_and_x(line_index, (x * self.scale as f64) as f32)
+    }
+
+    /// The number of lines in the layout.
+    pub(crate) fn line_count(&self) -> usize {
+        self.layout.len()
+    }
+
+    /// The horizontal extents of the text in `range` on a line, from left to right.
+    fn spans_for_range(&self, line_index: usize, range: &Range<usize>) -> Vec<(f32, f32)> {
+        let mut spans = Vec::new();
+        let mut current: Option<(f32, f32)> = None;
+        for cluster in self.visual_clusters(line_index) {
+            let selected =
+                cluster.text_range.start < range.end && range.start < cluster.text_range.end;
+            match (selected, &mut current) {
+                // Clusters next to each other on screen are merged.
+                (true, Some((_, x1))) => *x1 = cluster.x1,
+                (true, None) => current = Some((cluster.x0, cluster.x1)),
+                (false, _) => spans.extend(current.take()),
+            }
+        }
+        spans.extend(current);
+        spans
+    }
+
+    /// The clusters of a line, in the order they are displayed from left to right.
+    fn visual_clusters(&self, line_index: usize) -> Vec<VisualCluster> {
+        let Some(line) = self.layout.get(line_index) else {
+            return Vec::new();
+        };
+        // Parley lays out a single space for empty text.
+        if self.text.as_str().is_empty() {
+            return Vec::new();
+        }
+        let mut x = line.metrics().offset;
+        let mut clusters = Vec::new();
+        for run in line.runs() {
+            for cluster in run.visual_clusters() {
+                let x0 = x;
+                x += cluster.advance();
+                clusters.push(VisualCluster {
+                    text_range: cluster.text_range(),
+                    x0,
+                    x1: x,
+                    is_rtl: run.is_rtl(),
+                    is_hard_line_break: cluster.is_hard_line_break(),
+                });
+            }
+        }
+        clusters
+    }
+
+    /// The line and cluster which a caret is drawn next to.
+    ///
+    /// A caret is attached to the cluster after it with [`Affinity::Downstream`] and to
+    /// the cluster before it with [`Affinity::Upstream`], if there is one.
+    fn attached_cluster(
+        &self,
+        text_pos: usize,
+        affinity: Affinity,
+    ) -> Option<(usize, VisualCluster)> {
+        let mut other = None;
+        let mut containing = None;
+        for line_index in 0..self.layout.len() {
+            for cluster in self.visual_clusters(line_index) {
+                let range = &cluster.text_range;
+                let (preferred, fallback) = match affinity {
+                    Affinity::Downstream => (range.start == text_pos, range.end == text_pos),
+                    Affinity::Upstream => (range.end == text_pos, range.start == text_pos),
+                };
+                if preferred {
+                    return Some((line_index, cluster));
+                } else if fallback && other.is_none() {
+                    other = Some((line_index, cluster));
+                } else if range.contains(&text_pos) && containing.is_none() {
+                    containing = Some((line_index, cluster));
+                }
+            }
+        }
+        other.or(containing)
+    }
+
+    /// The line where a caret is displayed, and its horizontal position in physical pixels.
+    fn caret_location(&self, text_pos: usize, affinity: Affinity) -> (usize, f32) {
+        match self.attached_cluster(text_pos, affinity) {
+            // After a hard line break, the caret is at the start of the next line.
+            Some((line_index, cluster))
+                if cluster.is_hard_line_break
+                    && text_pos >= cluster.text_range.end
+                    && line_index + 1 < self.layout.len() =>
+            {
+                let next_line = line_index + 1;
+                let x = match self.visual_clusters(next_line).first() {
+                    Some(first) => first.x_for_position(first.left_position()),
+                    None => self.layout.get(next_line).unwrap().metrics().offset,
+                };
+                (next_line, x)
+            }
+            Some((line_index, cluster)) => {
+                let position = if cluster.text_range.contains(&text_pos) {
+                    // The position is inside a ligature; we use its leading edge.
+                    cluster.text_range.start
+                } else {
+                    text_pos
+                };
+                (line_index, cluster.x_for_position(position))
+            }
+            None => {
+                let line_index = self.layout.len().saturating_sub(1);
+                let x = self
+                    .layout
+                    .get(line_index)
+                    .map_or(0., |line| line.metrics().offset);
+                (line_index, x)
+            }
+        }
+    }
+
+    fn caret_for_line_and_x(&self, line_index: usize, x: f32) -> (usize, Affinity) {
+        let clusters: Vec<_> = self
+            .visual_clusters(line_index)
+            .into_iter()
+            .filter(|cluster| !cluster.is_hard_line_break)
+            .collect();
+        let (Some(first), Some(last)) = (clusters.first(), clusters.last()) else {
+            let start = self
+                .layout
+                .get(line_index)
+                .map_or(0, |line| line.text_range().start);
+            return (start.min(self.text.as_str().len()), Affinity::Downstream);
+        };
+        let (cluster, position) = if x < first.x0 {
+            (first, first.left_position())
+        } else if x >= last.x1 {
+            (last, last.right_position())
+        } else {
+            let cluster = clusters
+                .iter()
+                .find(|cluster| x < cluster.x1)
+                .unwrap_or(last);
+            if x < (cluster.x0 + cluster.x1) * 0.5 {
+                (cluster, cluster.left_position())
+            } else {
+                (cluster, cluster.right_position())
+            }
+        };
+        (position, cluster.affinity_at(position))
+    }
+
+    fn caret_line(&self, line_index: usize, x: f32) -> Line {
+        let line_metrics = *self.layout.get(line_index).unwrap().metrics();
         let baseline = line_metrics.baseline + line_metrics.descent;
-        let p1 = (from_position.offset as f64, baseline as f64);
-        let p2 = (
-            from_position.offset as f64,
-            (baseline - line_metrics.size()) as f64,
-        );
-        Line::new(p1, p2)
+        let p1 = (x as f64, baseline as f64);
+        let p2 = (x as f64, (baseline - line_metrics.size()) as f64);
+        Affine::scale(1.0 / self.scale as f64) * Line::new(p1, p2)
+    }
+
+    fn logical_rect(&self, x0: f32, y0: f32, x1: f32, y1: f32) -> Rect {
+        Affine::scale(1.0 / self.scale as f64)
+            .transform_rect_bbox(Rect::new(x0 as f64, y0 as f64, x1 as f64, y1 as f64))
     }
 
     /// Returns the [`Link`] at the provided point (relative to the layout's origin) if one exists.
@@ -474,6 +875,7 @@ impl<T: TextStorage> TextLayout<T> {
     ) {
         if self.needs_layout {
             self.needs_layout = false;
+            self.text_is_rtl = first_strong_is_rtl(self.text.as_str());
 
             let mut builder = layout_ctx.ranged_builder(font_ctx, self.text.as_str(), self.scale);
             builder.push_default(&StyleProperty::Brush(self.brush.clone()));
@@ -491,8 +893,16 @@ impl<T: TextStorage> TextLayout<T> {
         }
         if self.needs_line_breaks {
             self.needs_line_breaks = false;
-            self.layout
-                .break_all_lines(self.max_advance, self.alignment);
+            // Parley's alignments are always relative to the left edge.
+            let alignment = match (self.alignment, self.text_is_rtl) {
+                (Alignment::Start, true) => Alignment::End,
+                (Alignment::End, true) => Alignment::Start,
+                (alignment, _) => alignment,
+            };
+            self.layout.break_all_lines(
+                self.max_advance.map(|max_advance| max_advance * self.scale),
+                alignment,
+            );
 
             // TODO:
             // self.links = text
@@ -514,12 +924,80 @@ impl<T: TextStorage> TextLayout<T> {
         crate::text_helpers::render_text(
             scene,
             &mut self.scratch_scene,
-            Affine::translate((p.x, p.y)),
+            Affine::translate((p.x, p.y)) * Affine::scale(1.0 / self.scale as f64),
             &self.layout,
         );
     }
 }
 
+/// A cluster of a line, with the position of its edges on screen.
+#[derive(Clone, Debug)]
+struct VisualCluster {
+    text_range: Range<usize>,
+    /// The left edge of the cluster, in physical pixels.
+    x0: f32,
+    /// The right edge of the cluster, in physical pixels.
+    x1: f32,
+    is_rtl: bool,
+    is_hard_line_break: bool,
+}
+
+impl VisualCluster {
+    /// The text position at the left edge of the cluster.
+    fn left_position(&self) -> usize {
+        if self.is_rtl {
+            self.text_range.end
+        } else {
+            self.text_range.start
+        }
+    }
+
+    /// The text position at the right edge of the cluster.
+    fn right_position(&self) -> usize {
+        if self.is_rtl {
+            self.text_range.start
+        } else {
+            self.text_range.end
+        }
+    }
+
+    /// The horizontal position of the edge of the cluster at `position`.
+    fn x_for_position(&self, position: usize) -> f32 {
+        if position == self.left_position() {
+            self.x0
+        } else {
+            self.x1
+        }
+    }
+
+    /// The affinity which attaches a caret at one of the edges of this cluster to it.
+    fn affinity_at(&self, position: usize) -> Affinity {
+        if position == self.text_range.start {
+            Affinity::Downstream
+        } else {
+            Affinity::Upstream
+        }
+    }
+}
+
+/// Whether the first strong directional character of `text` is right-to-left,
+/// ignoring the characters inside directional isolates.
+///
+/// This is rule P2 of the Unicode Bidirectional Algorithm.
+fn first_strong_is_rtl(text: &str) -> bool {
+    let mut isolates = 0_usize;
+    for c in text.chars() {
+        match c.bidi_class() {
+            BidiClass::RLI | BidiClass::LRI | BidiClass::FSI => isolates += 1,
+            BidiClass::PDI => isolates = isolates.saturating_sub(1),
+            BidiClass::L if isolates == 0 => return false,
+            BidiClass::R | BidiClass::AL if isolates == 0 => return true,
+            _ => {}
+        }
+    }
+    false
+}
+
 impl<T: TextStorage> std::fmt::Debug for TextLayout<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         f.debug_struct("TextLayout")
@@ -545,3 +1023,140 @@ impl<T: TextStorage + Default> Default for TextLayout<T> {
         Self::new(Default::default(), crate::theme::TEXT_SIZE_NORMAL as f32)
     }
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use parley::fontique::{Collection, CollectionOptions};
+
+    use super::*;
+
+    /// Text with a right-to-left override in the middle: `abc` `fed` `ghi` on screen.
+    pub(crate) const MIXED: &str = "abc\u{202E}def\u{202C}ghi";
+
+    /// A laid out text using only the test font, so that results are consistent.
+    pub(crate) fn layout(text: &str) -> TextLayout<String> {
+        layout_with(text, |_| {})
+    }
+
+    pub(crate) fn layout_with(
+        text: &str,
+        f: impl FnOnce(&mut TextLayout<String>),
+    ) -> TextLayout<String> {
+        const ROBOTO: &[u8] = include_bytes!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/resources/fonts/roboto/Roboto-Regular.ttf"
+        ));
+        let mut font_ctx = FontContext {
+            collection: Collection::new(CollectionOptions {
+                system_fonts: false,
+                ..Default::default()
+            }),
+            source_cache: Default::default(),
+        };
+        let families = font_ctx.collection.register_fonts(ROBOTO.to_vec());
+        font_ctx
+            .collection
+            .append_fallbacks(*b"Latn", families.iter().map(|(family, _)| *family));
+        let mut layout = TextLayout::new(text.to_string(), 20.);
+        f(&mut layout);
+        layout.rebuild(&mut font_ctx, &mut LayoutContext::new());
+        layout
+    }
+
+    #[test]
+    fn paragraph_direction() {
+        assert!(!layout("abc").text_is_rtl());
+        assert!(layout("\u{200F}abc").text_is_rtl());
+        // Isolated text doesn't count.
+        assert!(!layout("\u{2067}\u{200F}\u{2069}abc").text_is_rtl());
+    }
+
+    #[test]
+    fn start_alignment_follows_direction() {
+        let align_start = |text| {
+            layout_with(text, |layout| {
+                layout.set_max_advance(Some(200.));
+                layout.set_text_alignment(Alignment::Start);
+            })
+        };
+        let ltr = align_start("abc");
+        let rtl = align_start("\u{200F}abc");
+        assert!(ltr.cursor_line_for_text_position(0).p0.x < 1.);
+        let end = rtl.text().len();
+        assert!(rtl.cursor_line_for_text_position(end).p0.x > 150.);
+        assert_eq!(rtl.line_count(), 1);
+    }
+
+    #[test]
+    fn caret_is_split_at_direction_boundary() {
+        let layout = layout(MIXED);
+        let x = |line: Line| line.p0.x;
+        // Where `c` ends and `f` starts, and where `d` ends and `g` starts.
+        let (c_end, _) = layout.caret_lines_for_text_position(3, Affinity::Upstream);
+        let (g_start, _) = layout.caret_lines_for_text_position(12, Affinity::Downstream);
+
+        // Before `d`, the caret is both after `c` and on the right of `d`.
+        let (primary, secondary) = layout.caret_lines_for_text_position(6, Affinity::Downstream);
+        assert!((x(primary) - x(g_start)).abs() < 0.5);
+        assert!((x(secondary.unwrap()) - x(c_end)).abs() < 0.5);
+        let (primary, secondary) = layout.caret_lines_for_text_position(6, Affinity::Upstream);
+        assert!((x(primary) - x(c_end)).abs() < 0.5);
+        assert!((x(secondary.unwrap()) - x(g_start)).abs() < 0.5);
+
+        // Within a run, there is a single caret.
+        let (_, secondary) = layout.caret_lines_for_text_position(1, Affinity::Downstream);
+        assert_eq!(secondary, None);
+    }
+
+    #[test]
+    fn rects_across_mixed_runs() {
+        let layout = layout(MIXED);
+        // `ef` are on the left of `d`, and `g` on its right.
+        let rects = layout.rects_for_range(7..13);
+        assert_eq!(rects.len(), 2);
+        let d_rect = layout.rects_for_range(6..7)[0];
+        assert!(rects[0].x1 <= d_rect.x0 + 0.5);
+        assert!(rects[1].x0 >= d_rect.x1 - 0.5);
+
+        // In a single run, the range is a single rectangle.
+        assert_eq!(layout.rects_for_range(0..2).len(), 1);
+        assert_eq!(layout.rects_for_range(6..9).len(), 1);
+    }
+
+    #[test]
+    fn underlines_below_range() {
+        let hello = layout("hello world");
+        let underlines = hello.underlines_for_range(6..11);
+        assert_eq!(underlines.len(), 1);
+        let (line, thickness) = underlines[0];
+        let rect = hello.rects_for_range(6..11)[0];
+        assert!((line.p0.x - rect.x0).abs() < 0.5);
+        assert!((line.p1.x - rect.x1).abs() < 0.5);
+        // Below the baseline, but within the line.
+        let baseline = hello.layout().lines().next().unwrap().metrics().baseline as f64;
+        assert!(line.p0.y > baseline && line.p0.y < rect.y1);
+        assert!(thickness > 0.);
+
+        // Like the selection, the underlines of mixed runs are split.
+        assert_eq!(layout(MIXED).underlines_for_range(7..13).len(), 2);
+    }
+
+    #[test]
+    fn caret_for_point_in_rtl_run() {
+        let layout = layout(MIXED);
+        let f_rect = layout.rects_for_range(8..9)[0];
+        // The left of `f` is after it, in the right-to-left run.
+        let left_of_f = Point::new(f_rect.x0 + 1., f_rect.center().y);
+        assert_eq!(layout.caret_for_point(left_of_f).0, 9);
+        let right_of_f = Point::new(f_rect.x1 - 1., f_rect.center().y);
+        assert_eq!(layout.caret_for_point(right_of_f).0, 8);
+        assert_eq!(
+            layout.writing_direction_at(7, Affinity::Downstream),
+            WritingDirection::RightToLeft
+        );
+        assert_eq!(
+            layout.writing_direction_at(1, Affinity::Downstream),
+            WritingDirection::LeftToRight
+        );
+    }
+}
@@ -18,13 +18,18 @@ pub use layout::{Hinting, LayoutMetrics, TextBrush, TextLayout};
 
 mod selection;
 pub use selection::{
-    len_utf8_from_first_byte, EditableTextCursor, Selectable, StringCursor, TextWithSelection,
+    len_utf8_from_first_byte, Affinity, EditableTextCursor, Selectable, Selection, StringCursor,
+    TextWithSelection,
 };
 
-// mod movement;
+mod movement;
+pub use movement::{movement, Direction, Movement, VerticalMovement, WritingDirection};
 
 mod edit;
 pub use edit::{EditableText, TextEditor};
 
+mod spellcheck;
+pub use spellcheck::{DictionarySpellchecker, Misspelling, Spellchecker, TextChange};
+
 mod backspace;
 pub use backspace::offset_for_delete_backwards;
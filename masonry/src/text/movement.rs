@@ -7,9 +7,8 @@ use std::ops::Range;
 
 use unicode_segmentation::UnicodeSegmentation;
 
-use crate::kurbo::Point;
-
-use super::{layout::TextLayout, Selectable, TextStorage};
+use super::selection::{Affinity, Selection};
+use super::{layout::TextLayout, Selectable};
 
 /// Compute the result of a [`Movement`] on a [`Selection`].
 ///
@@ -18,7 +17,7 @@ use super::{layout::TextLayout, Selectable, TextStorage};
 /// If `modify` is true, only the 'active' edge (the `end`) of the selection
 /// should be changed; this is the case when the user moves with the shift
 /// key pressed.
-pub fn movement<T: Selectable + TextStorage>(
+pub fn movement<T: Selectable>(
     m: Movement,
     s: Selection,
     layout: &TextLayout<T>,
@@ -29,91 +28,85 @@ pub fn movement<T: Selectable + TextStorage>(
         return s;
     }
     let text = layout.text();
-    let parley_layout = layout.layout();
 
-    let writing_direction = || {
-        if layout
-            .cursor_for_text_position(s.active, s.active_affinity)
-            .is_rtl
-        {
-            WritingDirection::RightToLeft
-        } else {
-            WritingDirection::LeftToRight
-        }
+    let writing_direction = || layout.writing_direction_at(s.active, s.active_affinity);
+    let paragraph_direction = if layout.text_is_rtl() {
+        WritingDirection::RightToLeft
+    } else {
+        WritingDirection::LeftToRight
     };
 
-    let (offset, h_pos) = match m {
+    let (offset, affinity, h_pos) = match m {
+        Movement::Grapheme(d @ (Direction::Left | Direction::Right)) => {
+            if s.is_caret() || modify {
+                let (offset, affinity) =
+                    layout.caret_moved_visually(s.active, s.active_affinity, d == Direction::Right);
+                (offset, affinity, None)
+            } else if d.is_upstream_for_direction(paragraph_direction) {
+                (s.min(), Affinity::Downstream, None)
+            } else {
+                (s.max(), Affinity::Upstream, None)
+            }
+        }
         Movement::Grapheme(d) => {
-            let direction = writing_direction();
-            if d.is_upstream_for_direction(direction) {
+            if d.is_upstream_for_direction(paragraph_direction) {
                 if s.is_caret() || modify {
-                    text.prev_grapheme_offset(s.active)
-                        .map(|off| (off, None))
-                        .unwrap_or((0, s.h_pos))
+                    let offset = text.prev_grapheme_offset(s.active).unwrap_or(0);
+                    (offset, Affinity::Downstream, None)
                 } else {
-                    (s.min(), None)
+                    (s.min(), Affinity::Downstream, None)
                 }
+            } else if s.is_caret() || modify {
+                let offset = text.next_grapheme_offset(s.active).unwrap_or(s.active);
+                (offset, Affinity::Upstream, None)
             } else {
-                if s.is_caret() || modify {
-                    text.next_grapheme_offset(s.active)
-                        .map(|off| (off, None))
-                        .unwrap_or((s.active, s.h_pos))
-                } else {
-                    (s.max(), None)
-                }
+                (s.max(), Affinity::Upstream, None)
             }
         }
         Movement::Vertical(VerticalMovement::LineUp) => {
-            let cur_pos = layout.cursor_for_text_position(s.active, s.active_affinity);
-            let h_pos = s.h_pos.unwrap_or(cur_pos.advance);
-            if cur_pos.path.line_index == 0 {
-                (0, Some(h_pos))
+            let (line, _) = layout.line_for_text_position(s.active, s.active_affinity);
+            let h_pos = s
+                .h_pos
+                .unwrap_or(layout.caret_x(s.active, s.active_affinity) as f32);
+            if line == 0 {
+                (0, Affinity::Downstream, Some(h_pos))
             } else {
-                let lm = cur_pos.path.line(&parley_layout).unwrap();
-                let point_above = Point::new(h_pos, cur_pos.point.y - lm.height);
-                let up_pos = layout.hit_test_point(point_above);
-                if up_pos.is_inside {
-                    (up_pos.idx, Some(h_pos))
-                } else {
-                    // because we can't specify affinity, moving up when h_pos
-                    // is wider than both the current line and the previous line
-                    // can result in a cursor position at the visual start of the
-                    // current line; so we handle this as a special-case.
-                    let lm_prev = layout.line_metric(cur_pos.line.saturating_sub(1)).unwrap();
-                    let up_pos = lm_prev.end_offset - lm_prev.trailing_whitespace;
-                    (up_pos, Some(h_pos))
-                }
+                let (offset, affinity) = layout.caret_for_line(line - 1, h_pos.into());
+                (offset, affinity, Some(h_pos))
             }
         }
         Movement::Vertical(VerticalMovement::LineDown) => {
-            let cur_pos = layout.hit_test_text_position(s.active);
-            let h_pos = s.h_pos.unwrap_or(cur_pos.point.x);
-            if cur_pos.line == layout.line_count() - 1 {
-                (text.len(), Some(h_pos))
+            let (line, _) = layout.line_for_text_position(s.active, s.active_affinity);
+            let h_pos = s
+                .h_pos
+                .unwrap_or(layout.caret_x(s.active, s.active_affinity) as f32);
+            if line + 1 >= layout.line_count() {
+                (text.len(), Affinity::Upstream, Some(h_pos))
             } else {
-                let lm = layout.line_metric(cur_pos.line).unwrap();
-                // may not work correctly for point sizes below 1.0
-                let y_below = lm.y_offset + lm.height + 1.0;
-                let point_below = Point::new(h_pos, y_below);
-                let up_pos = layout.hit_test_point(point_below);
-                (up_pos.idx, Some(point_below.x))
+                let (offset, affinity) = layout.caret_for_line(line + 1, h_pos.into());
+                (offset, affinity, Some(h_pos))
             }
         }
-        Movement::Vertical(VerticalMovement::DocumentStart) => (0, None),
-        Movement::Vertical(VerticalMovement::DocumentEnd) => (text.len(), None),
+        Movement::Vertical(VerticalMovement::DocumentStart) => (0, Affinity::Downstream, None),
+        Movement::Vertical(VerticalMovement::DocumentEnd) => (text.len(), Affinity::Upstream, None),
 
-        Movement::ParagraphStart => (text.preceding_line_break(s.active), None),
-        Movement::ParagraphEnd => (text.next_line_break(s.active), None),
+        Movement::ParagraphStart => (
+            text.preceding_line_break(s.active),
+            Affinity::Downstream,
+            None,
+        ),
+        Movement::ParagraphEnd => (text.next_line_break(s.active), Affinity::Upstream, None),
 
         Movement::Line(d) => {
-            let hit = layout.hit_test_text_position(s.active);
-            let lm = layout.line_metric(hit.line).unwrap();
-            let offset = if d.is_upstream_for_direction(writing_direction) {
-                lm.start_offset
+            let (_, range) = layout.line_for_text_position(s.active, s.active_affinity);
+            if d.is_upstream_for_direction(paragraph_direction) {
+                (range.start, Affinity::Downstream, None)
             } else {
-                lm.end_offset - lm.trailing_whitespace
-            };
-            (offset, None)
+                // The end of the line is before its hard line break, if any.
+                let line = text.slice(range.clone()).unwrap_or_default();
+                let end = range.start + line.trim_end_matches(['\n', '\r']).len();
+                (end, Affinity::Upstream, None)
+            }
         }
         Movement::Word(d) => {
             if d.is_upstream_for_direction(writing_direction()) {
@@ -122,29 +115,25 @@ pub fn movement<T: Selectable + TextStorage>(
                 } else {
                     s.min()
                 };
-                (offset, None)
+                (offset, Affinity::Downstream, None)
             } else {
                 let offset = if s.is_caret() || modify {
                     text.next_word_offset(s.active).unwrap_or(s.active)
                 } else {
                     s.max()
                 };
-                (offset, None)
+                (offset, Affinity::Upstream, None)
             }
         }
 
         // These two are not handled; they require knowledge of the size
         // of the viewport.
         Movement::Vertical(VerticalMovement::PageDown)
-        | Movement::Vertical(VerticalMovement::PageUp) => (s.active, s.h_pos),
-        other => {
-            tracing::warn!("unhandled movement {:?}", other);
-            (s.anchor, s.h_pos)
-        }
+        | Movement::Vertical(VerticalMovement::PageUp) => (s.active, s.active_affinity, s.h_pos),
     };
 
     let start = if modify { s.anchor } else { offset };
-    Selection::new(start, offset).with_h_pos(h_pos)
+    Selection::new(start, offset, affinity).with_h_pos(h_pos)
 }
 
 /// Indicates a movement that transforms a particular text position in a
@@ -240,52 +229,12 @@ impl Direction {
     }
 }
 
-/// Distinguishes between two visually distinct locations with the same byte
-/// index.
-///
-/// Sometimes, a byte location in a document has two visual locations. For
-/// example, the end of a soft-wrapped line and the start of the subsequent line
-/// have different visual locations (and we want to be able to place an input
-/// caret in either place!) but the same byte-wise location. This also shows up
-/// in bidirectional text contexts. Affinity allows us to disambiguate between
-/// these two visual locations.
-///
-/// Note that in scenarios where soft line breaks interact with bidi text, this gets
-/// more complicated.
-#[derive(Copy, Clone, Debug, Hash, PartialEq)]
-pub enum Affinity {
-    /// The position which has an apparent position "earlier" in the text.
-    /// For soft line breaks, this is the position at the end of the first line.
-    ///
-    /// For positions in-between bidi contexts, this is the position which is
-    /// related to the "outgoing" text section. E.g. for the string "abcDEF" (rendered `abcFED`),
-    /// with the cursor at "abc|DEF" with upstream affinity, the cursor would be rendered at the
-    /// position `abc|DEF`
-    Upstream,
-    /// The position which has a higher apparent position in the text.
-    /// For soft line breaks, this is the position at the beginning of the second line.
-    ///
-    /// For positions in-between bidi contexts, this is the position which is
-    /// related to the "incoming" text section. E.g. for the string "abcDEF" (rendered `abcFED`),
-    /// with the cursor at "abc|DEF" with downstream affinity, the cursor would be rendered at the
-    /// position `abcDEF|`
-    Downstream,
-}
-
-impl Affinity {
-    /// Convert into the `parley` form of "leading"
-    pub fn is_leading(&self) -> bool {
-        match self {
-            Affinity::Upstream => false,
-            Affinity::Downstream => true,
-        }
-    }
-}
-
 /// Indicates a horizontal direction for writing text.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum WritingDirection {
+    /// Text written from left to right, such as English.
     LeftToRight,
+    /// Text written from right to left, such as Arabic and Hebrew.
     RightToLeft,
     // /// Indicates writing direction should be automatically detected based on
     // /// the text contents.
@@ -316,6 +265,8 @@ pub enum VerticalMovement {
 /// This uses Unicode word boundaries, as defined in [UAX#29].
 ///
 /// [UAX#29]: http://www.unicode.org/reports/tr29/
+// TODO: Select words on double-click
+#[allow(unused)]
 pub(crate) fn word_range_for_pos(text: &str, pos: usize) -> Range<usize> {
     text.split_word_bound_indices()
         .map(|(ix, word)| ix..(ix + word.len()))
@@ -326,6 +277,63 @@ pub(crate) fn word_range_for_pos(text: &str, pos: usize) -> Range<usize> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::text::layout::tests::{layout, MIXED};
+    use crate::text::Affinity;
+
+    /// The caret positions reached by repeating a movement from the start of the text.
+    fn positions(text: &str, m: Movement) -> Vec<usize> {
+        let layout = layout(text);
+        let mut selection = Selection::caret(0, Affinity::Downstream);
+        let mut positions = vec![0];
+        loop {
+            selection = movement(m, selection, &layout, false);
+            if positions.last() == Some(&selection.active) {
+                return positions;
+            }
+            positions.push(selection.active);
+        }
+    }
+
+    #[test]
+    fn grapheme_movement_is_visual() {
+        // The caret goes through `fed` from left to right on screen. The zero-width
+        // override characters are clusters of their own, so they take a step each.
+        assert_eq!(
+            positions(MIXED, Movement::Grapheme(Direction::Right)),
+            [0, 1, 2, 3, 6, 9, 8, 7, 6, 13, 14, 15]
+        );
+    }
+
+    #[test]
+    fn upstream_movement_is_logical() {
+        let positions = positions(MIXED, Movement::Grapheme(Direction::Downstream));
+        assert!(positions.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(positions.last(), Some(&MIXED.len()));
+    }
+
+    #[test]
+    fn shift_extends_selection() {
+        let layout = layout(MIXED);
+        let selection = Selection::caret(1, Affinity::Downstream);
+        let selection = movement(
+            Movement::Grapheme(Direction::Right),
+            selection,
+            &layout,
+            true,
+        );
+        assert_eq!(selection.anchor, 1);
+        assert_eq!(selection.active, 2);
+        let selection = movement(
+            Movement::Line(Direction::Downstream),
+            selection,
+            &layout,
+            false,
+        );
+        assert_eq!(
+            (selection.anchor, selection.active),
+            (MIXED.len(), MIXED.len())
+        );
+    }
 
     #[test]
     fn word_range_simple() {
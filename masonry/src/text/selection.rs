@@ -11,13 +11,14 @@ use parley::{FontContext, LayoutContext};
 use tracing::debug;
 use unicode_segmentation::{GraphemeCursor, UnicodeSegmentation};
 use vello::kurbo::{Affine, Line, Point, Stroke};
-use vello::peniko::{Brush, Color};
+use vello::peniko::{Brush, Color, Fill};
 use vello::Scene;
 use winit::keyboard::NamedKey;
 
 use crate::event::{PointerButton, PointerState};
 use crate::{Handled, TextEvent};
 
+use super::movement::{movement, Direction, Movement, VerticalMovement};
 use super::{TextBrush, TextLayout, TextStorage};
 
 pub struct TextWithSelection<T: Selectable> {
@@ -25,11 +26,15 @@ pub struct TextWithSelection<T: Selectable> {
     /// The current selection within this widget
     // TODO: Allow multiple selections (i.e. by holding down control)
     pub selection: Option<Selection>,
+    /// The brush of the selected text.
     highlight_brush: TextBrush,
+    /// The background of the selected text.
+    highlight_fill: Brush,
     needs_selection_update: bool,
     selecting_with_mouse: bool,
-    // TODO: Cache cursor line, selection boxes
-    cursor_line: Option<Line>,
+    // TODO: Cache selection boxes
+    /// The lines of the caret, which is split at direction boundaries.
+    cursor_lines: Option<(Line, Option<Line>)>,
 }
 
 impl<T: Selectable> TextWithSelection<T> {
@@ -39,12 +44,9 @@ impl<T: Selectable> TextWithSelection<T> {
             selection: None,
             needs_selection_update: false,
             selecting_with_mouse: false,
-            cursor_line: None,
-            highlight_brush: TextBrush::Highlight {
-                text: Color::WHITE.into(),
-                fill: Color::LIGHT_BLUE.into(),
-                hinting: Default::default(),
-            },
+            cursor_lines: None,
+            highlight_brush: Color::WHITE.into(),
+            highlight_fill: Color::LIGHT_BLUE.into(),
         }
     }
 
@@ -58,9 +60,10 @@ impl<T: Selectable> TextWithSelection<T> {
         self.layout.needs_rebuild() || self.needs_selection_update
     }
 
+    /// Handle a pointer button being pressed at `position`, relative to the text.
     pub fn pointer_down(
         &mut self,
-        origin: Point,
+        position: Point,
         state: &PointerState,
         button: PointerButton,
     ) -> bool {
@@ -68,46 +71,35 @@ impl<T: Selectable> TextWithSelection<T> {
         if button == PointerButton::Primary {
             self.selecting_with_mouse = true;
             self.needs_selection_update = true;
-            // TODO: Much of this juggling seems unnecessary
-            let position = Point::new(state.position.x, state.position.y) - origin;
-            let position = self
-                .layout
-                .cursor_for_point(Point::new(position.x, position.y));
-            tracing::warn!("Got cursor point without getting affinity");
+            let (position, affinity) = self.layout.caret_for_point(position);
             if state.mods.state().shift_key() {
                 if let Some(selection) = self.selection.as_mut() {
-                    selection.active = position.insert_point;
-                    selection.active_affinity = Affinity::Downstream;
+                    selection.active = position;
+                    selection.active_affinity = affinity;
                     return true;
                 }
             }
-            self.selection = Some(Selection::caret(
-                position.insert_point,
-                Affinity::Downstream,
-            ));
+            self.selection = Some(Selection::caret(position, affinity));
             true
         } else {
             false
         }
     }
 
-    pub fn pointer_up(&mut self, _origin: Point, _state: &PointerState, button: PointerButton) {
+    pub fn pointer_up(&mut self, _position: Point, _state: &PointerState, button: PointerButton) {
         if button == PointerButton::Primary {
             self.selecting_with_mouse = false;
         }
     }
 
-    pub fn pointer_move(&mut self, origin: Point, state: &PointerState) -> bool {
+    /// Handle the pointer moving to `position`, relative to the text.
+    pub fn pointer_move(&mut self, position: Point, _state: &PointerState) -> bool {
         if self.selecting_with_mouse {
             self.needs_selection_update = true;
-            let position = Point::new(state.position.x, state.position.y) - origin;
-            let position = self
-                .layout
-                .cursor_for_point(Point::new(position.x, position.y));
-            tracing::warn!("Got cursor point without getting affinity");
+            let (position, affinity) = self.layout.caret_for_point(position);
             if let Some(selection) = self.selection.as_mut() {
-                selection.active = position.insert_point;
-                selection.active_affinity = Affinity::Downstream;
+                selection.active = position;
+                selection.active_affinity = affinity;
             } else {
                 debug_panic!("No selection set whilst still dragging");
             }
@@ -121,40 +113,33 @@ impl<T: Selectable> TextWithSelection<T> {
         match event {
             TextEvent::KeyboardKey(key, mods) if key.state.is_pressed() => {
                 match shortcut_key(key) {
-                    winit::keyboard::Key::Named(NamedKey::ArrowLeft) => {
-                        if mods.shift_key() {
-                        } else {
-                            let t = self.text();
-                            if let Some(selection) = self.selection {
-                                if mods.control_key() {
-                                    let offset = t.prev_word_offset(selection.active).unwrap_or(0);
-                                    self.selection =
-                                        Some(Selection::caret(offset, Affinity::Downstream));
-                                } else {
-                                    let offset =
-                                        t.prev_grapheme_offset(selection.active).unwrap_or(0);
-                                    self.selection =
-                                        Some(Selection::caret(offset, Affinity::Downstream));
-                                };
+                    winit::keyboard::Key::Named(
+                        key @ (NamedKey::ArrowLeft
+                        | NamedKey::ArrowRight
+                        | NamedKey::Home
+                        | NamedKey::End),
+                    ) => {
+                        // Arrows move visually, so that the caret follows them in
+                        // right-to-left text.
+                        let word = mods.control_key() || mods.alt_key();
+                        let m = match key {
+                            NamedKey::ArrowLeft if word => Movement::Word(Direction::Left),
+                            NamedKey::ArrowLeft => Movement::Grapheme(Direction::Left),
+                            NamedKey::ArrowRight if word => Movement::Word(Direction::Right),
+                            NamedKey::ArrowRight => Movement::Grapheme(Direction::Right),
+                            NamedKey::Home if mods.control_key() => {
+                                Movement::Vertical(VerticalMovement::DocumentStart)
                             }
-                        }
-                        Handled::Yes
-                    }
-                    winit::keyboard::Key::Named(NamedKey::ArrowRight) => {
-                        if mods.shift_key() {
-                            // TODO: Expand selection
-                        } else {
-                            let t = self.text();
-                            if let Some(selection) = self.selection {
-                                if mods.control_key() {
-                                    if let Some(o) = t.next_word_offset(selection.active) {
-                                        self.selection =
-                                            Some(Selection::caret(o, Affinity::Upstream));
-                                    }
-                                } else if let Some(o) = t.next_grapheme_offset(selection.active) {
-                                    self.selection = Some(Selection::caret(o, Affinity::Upstream));
-                                };
+                            NamedKey::Home => Movement::Line(Direction::Upstream),
+                            NamedKey::End if mods.control_key() => {
+                                Movement::Vertical(VerticalMovement::DocumentEnd)
                             }
+                            _ => Movement::Line(Direction::Downstream),
+                        };
+                        if let Some(selection) = self.selection {
+                            self.selection =
+                                Some(movement(m, selection, &self.layout, mods.shift_key()));
+                            self.needs_selection_update = true;
                         }
                         Handled::Yes
                     }
@@ -256,21 +241,27 @@ impl<T: Selectable> TextWithSelection<T> {
     }
 
     pub fn draw(&mut self, scene: &mut Scene, point: impl Into<Point>) {
+        let point: Point = point.into();
+        let transform = Affine::translate((point.x, point.y));
         // TODO: Calculate the location for this in layout lazily?
         if let Some(selection) = self.selection {
-            self.cursor_line = Some(self.layout.cursor_line_for_text_position(selection.active));
+            for rect in self.layout.rects_for_range(selection.range()) {
+                scene.fill(Fill::NonZero, transform, &self.highlight_fill, None, &rect);
+            }
+            self.cursor_lines = Some(
+                self.layout
+                    .caret_lines_for_text_position(selection.active, selection.active_affinity),
+            );
         } else {
-            self.cursor_line = None;
+            self.cursor_lines = None;
         }
-        let point: Point = point.into();
-        if let Some(line) = self.cursor_line {
-            scene.stroke(
-                &Stroke::new(2.),
-                Affine::translate((point.x, point.y)),
-                &Brush::Solid(Color::WHITE),
-                None,
-                &line,
-            );
+        if let Some((line, split_line)) = self.cursor_lines {
+            let brush = Brush::Solid(Color::WHITE);
+            scene.stroke(&Stroke::new(2.), transform, &brush, None, &line);
+            // Where text in the other direction would be inserted.
+            if let Some(split_line) = split_line {
+                scene.stroke(&Stroke::new(1.), transform, &brush, None, &split_line);
+            }
         }
         self.layout.draw(scene, point);
     }
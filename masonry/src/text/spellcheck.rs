@@ -0,0 +1,336 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Spellchecking of editable text.
+
+use std::collections::HashSet;
+use std::ops::Range;
+use std::path::Path;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+/// The maximum number of suggestions given by [`DictionarySpellchecker`].
+const MAX_SUGGESTIONS: usize = 5;
+
+/// The maximum edit distance between a misspelled word and its suggestions.
+const MAX_SUGGESTION_DISTANCE: usize = 2;
+
+/// A spellchecker for editable text, such as in a [`Textbox`](crate::widget::Textbox).
+///
+/// The spellchecker is told about every change to the text, and returns the misspelled
+/// ranges of the whole text. Suggestions are only asked for when they are shown.
+pub trait Spellchecker {
+    /// Find the misspellings in `text`, which was just changed.
+    ///
+    /// `change` is where `text` differs from the text of the previous call, so that
+    /// implementations can avoid checking the unchanged parts again. For the first call,
+    /// it covers the whole text.
+    ///
+    /// The returned misspellings should be sorted and must not overlap.
+    fn text_changed(&mut self, text: &str, change: &TextChange) -> Vec<Misspelling>;
+
+    /// The words which could replace the misspelled `word`, most likely first.
+    ///
+    /// This is called when the user asks for suggestions, e.g. by right-clicking a
+    /// misspelled word in a textbox. By default there are no suggestions.
+    fn suggest(&mut self, word: &str) -> Vec<String> {
+        let _ = word;
+        Vec::new()
+    }
+}
+
+/// The replacement of a range of text by another text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TextChange {
+    /// The range which was replaced, in the previous text.
+    pub old_range: Range<usize>,
+    /// The range of the replacement, in the new text.
+    pub new_range: Range<usize>,
+}
+
+/// A range of misspelled text.
+///
+/// The possible corrections are given by [`Spellchecker::suggest`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Misspelling {
+    /// The utf-8 range of the misspelled word.
+    pub range: Range<usize>,
+}
+
+impl TextChange {
+    /// The change which replaces all of a text of length `old_len` by a text of length `new_len`.
+    pub fn replace_all(old_len: usize, new_len: usize) -> Self {
+        TextChange {
+            old_range: 0..old_len,
+            new_range: 0..new_len,
+        }
+    }
+
+    /// The change from `old` to `new`, without the prefix and suffix they have in common.
+    pub fn between(old: &str, new: &str) -> Self {
+        let prefix = old
+            .char_indices()
+            .zip(new.chars())
+            .find(|((_, old), new)| old != new)
+            .map_or(old.len().min(new.len()), |((i, _), _)| i);
+        let suffix = old[prefix..]
+            .chars()
+            .rev()
+            .zip(new[prefix..].chars().rev())
+            .take_while(|(old, new)| old == new)
+            .map(|(c, _)| c.len_utf8())
+            .sum::<usize>();
+        TextChange {
+            old_range: prefix..old.len() - suffix,
+            new_range: prefix..new.len() - suffix,
+        }
+    }
+}
+
+impl Misspelling {
+    /// The misspelling of the word at the utf-8 `range` of the text.
+    pub fn new(range: Range<usize>) -> Self {
+        Misspelling { range }
+    }
+}
+
+// --- MARK: DICTIONARY ---
+
+/// A [`Spellchecker`] which accepts the words of a list.
+///
+/// Words are compared without regard to case. Words containing digits are never
+/// misspelled. Suggestions are the closest words of the list.
+#[derive(Clone, Debug, Default)]
+pub struct DictionarySpellchecker {
+    /// The lowercase words of the dictionary.
+    words: HashSet<String>,
+    /// The misspellings of the last checked text.
+    misspellings: Vec<Misspelling>,
+}
+
+impl DictionarySpellchecker {
+    /// Create a spellchecker which accepts the given words.
+    pub fn new<S: AsRef<str>>(words: impl IntoIterator<Item = S>) -> Self {
+        DictionarySpellchecker {
+            words: words
+                .into_iter()
+                .map(|word| word.as_ref().trim().to_lowercase())
+                .filter(|word| !word.is_empty())
+                .collect(),
+            misspellings: Vec::new(),
+        }
+    }
+
+    /// Load a dictionary file, with one word per line.
+    ///
+    /// Empty lines, and lines starting with `#`, are ignored.
+    pub fn from_file(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        Ok(Self::new(
+            contents.lines().filter(|line| !line.starts_with('#')),
+        ))
+    }
+
+    /// Whether `word` is in the dictionary.
+    pub fn contains(&self, word: &str) -> bool {
+        self.words.contains(&word.to_lowercase())
+    }
+
+    /// The words of the dictionary which are closest to `word`, closest first.
+    ///
+    /// Suggestions for a capitalized word are capitalized.
+    pub fn suggestions(&self, word: &str) -> Vec<String> {
+        let lowercase: Vec<char> = word.to_lowercase().chars().collect();
+        let mut suggestions: Vec<(usize, &String)> = self
+            .words
+            .iter()
+            .filter(|candidate| {
+                candidate.chars().count().abs_diff(lowercase.len()) <= MAX_SUGGESTION_DISTANCE
+            })
+            .filter_map(|candidate| {
+                let candidate_chars: Vec<char> = candidate.chars().collect();
+                let distance = edit_distance(&lowercase, &candidate_chars);
+                (distance <= MAX_SUGGESTION_DISTANCE).then_some((distance, candidate))
+            })
+            .collect();
+        suggestions.sort();
+        let capitalized = word.chars().next().is_some_and(char::is_uppercase);
+        suggestions
+            .into_iter()
+            .take(MAX_SUGGESTIONS)
+            .map(|(_, suggestion)| {
+                if capitalized {
+                    let mut chars = suggestion.chars();
+                    chars
+                        .next()
+                        .map(|first| first.to_uppercase().chain(chars).collect())
+                        .unwrap_or_default()
+                } else {
+                    suggestion.clone()
+                }
+            })
+            .collect()
+    }
+
+    fn check_word(&self, word: &str, start: usize) -> Option<Misspelling> {
+        if word.chars().any(|c| c.is_numeric()) || self.contains(word) {
+            return None;
+        }
+        Some(Misspelling::new(start..start + word.len()))
+    }
+}
+
+impl Spellchecker for DictionarySpellchecker {
+    fn text_changed(&mut self, text: &str, change: &TextChange) -> Vec<Misspelling> {
+        // The words around the change are checked again, from the whitespace before
+        // it to the whitespace after it.
+        let start = text[..change.new_range.start]
+            .char_indices()
+            .rev()
+            .find(|(_, c)| c.is_whitespace())
+            .map_or(0, |(i, c)| i + c.len_utf8());
+        let end = text[change.new_range.end..]
+            .find(char::is_whitespace)
+            .map_or(text.len(), |i| change.new_range.end + i);
+
+        let shift = |range: &Range<usize>| {
+            range.start + change.new_range.end - change.old_range.end
+                ..range.end + change.new_range.end - change.old_range.end
+        };
+        let mut misspellings = Vec::new();
+        for misspelling in std::mem::take(&mut self.misspellings) {
+            let range = if misspelling.range.end < change.old_range.start {
+                misspelling.range
+            } else if misspelling.range.start > change.old_range.end {
+                shift(&misspelling.range)
+            } else {
+                continue;
+            };
+            if range.end <= start || range.start >= end {
+                misspellings.push(Misspelling::new(range));
+            }
+        }
+        misspellings.extend(
+            text[start..end]
+                .unicode_word_indices()
+                .filter_map(|(offset, word)| self.check_word(word, start + offset)),
+        );
+        misspellings.sort_by_key(|misspelling| misspelling.range.start);
+        self.misspellings = misspellings.clone();
+        misspellings
+    }
+
+    fn suggest(&mut self, word: &str) -> Vec<String> {
+        self.suggestions(word)
+    }
+}
+
+/// The number of insertions, deletions, substitutions and transpositions of adjacent
+/// characters needed to turn `a` into `b`.
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    // The rows of the distances between prefixes of `a` and `b`.
+    let mut before_previous: Vec<usize> = vec![0; b.len() + 1];
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        let mut current = vec![i; b.len() + 1];
+        for j in 1..=b.len() {
+            let substitution = previous[j - 1] + usize::from(a[i - 1] != b[j - 1]);
+            current[j] = substitution.min(previous[j] + 1).min(current[j - 1] + 1);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                current[j] = current[j].min(before_previous[j - 2] + 1);
+            }
+        }
+        before_previous = std::mem::replace(&mut previous, current);
+    }
+    previous[b.len()]
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checker() -> DictionarySpellchecker {
+        DictionarySpellchecker::new(["the", "then", "they", "quick", "brown", "fox"])
+    }
+
+    fn ranges(misspellings: &[Misspelling]) -> Vec<Range<usize>> {
+        misspellings.iter().map(|m| m.range.clone()).collect()
+    }
+
+    #[test]
+    fn finds_misspelled_words() {
+        let mut checker = checker();
+        let text = "The qiuck brown fax, 42 times";
+        let misspellings = checker.text_changed(text, &TextChange::replace_all(0, text.len()));
+        assert_eq!(ranges(&misspellings), [4..9, 16..19, 24..29]);
+        assert_eq!(checker.suggest(&text[4..9]), ["quick"]);
+        assert_eq!(checker.suggest(&text[16..19]), ["fox"]);
+    }
+
+    #[test]
+    fn suggestions_are_sorted_and_capitalized() {
+        let checker = checker();
+        assert_eq!(checker.suggestions("Thn"), ["The", "Then", "They"]);
+        assert_eq!(checker.suggestions("xyzzy"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn changes_are_checked_incrementally() {
+        let mut checker = checker();
+        let text = "teh quick fax";
+        checker.text_changed(text, &TextChange::replace_all(0, text.len()));
+
+        // Fixing the first word moves the misspelling after it.
+        let new_text = "the quick brown fax";
+        let change = TextChange::between(text, new_text);
+        assert_eq!(change.old_range, 1..9);
+        let misspellings = checker.text_changed(new_text, &change);
+        assert_eq!(misspellings.len(), 1);
+        assert_eq!(misspellings[0].range, 16..19);
+
+        // Joining two words makes them misspelled.
+        let text = new_text;
+        let new_text = "the quickbrown fax";
+        let misspellings = checker.text_changed(new_text, &TextChange::between(text, new_text));
+        assert_eq!(ranges(&misspellings), [4..14, 15..18]);
+    }
+
+    #[test]
+    fn text_change_between() {
+        assert_eq!(
+            TextChange::between("abc", "abXc"),
+            TextChange {
+                old_range: 2..2,
+                new_range: 2..3
+            }
+        );
+        assert_eq!(
+            TextChange::between("aaa", "aa"),
+            TextChange {
+                old_range: 2..3,
+                new_range: 2..2
+            }
+        );
+        assert_eq!(
+            TextChange::between("ab", "ab"),
+            TextChange {
+                old_range: 2..2,
+                new_range: 2..2
+            }
+        );
+        assert_eq!(TextChange::between("é", "è"), TextChange::replace_all(2, 2));
+    }
+
+    #[test]
+    fn dictionary_file() {
+        let path = std::env::temp_dir().join("masonry_spellcheck_dictionary.txt");
+        std::fs::write(&path, "# Animals\ncat\n\nDog\n").unwrap();
+        let checker = DictionarySpellchecker::from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(checker.contains("Cat"));
+        assert!(checker.contains("dog"));
+        assert!(!checker.contains("# Animals"));
+        assert!(!checker.contains(""));
+    }
+}
@@ -37,6 +37,11 @@ pub const SELECTED_TEXT_BACKGROUND_COLOR: Color = Color::rgb8(0x43, 0x70, 0xA8);
 pub const SELECTED_TEXT_INACTIVE_BACKGROUND_COLOR: Color = Color::rgb8(0x74, 0x74, 0x74);
 pub const SELECTION_TEXT_COLOR: Color = Color::rgb8(0x00, 0x00, 0x00);
 pub const CURSOR_COLOR: Color = Color::WHITE;
+pub const SPELLING_ERROR_COLOR: Color = Color::rgb8(0xf0, 0x40, 0x40);
+pub const FOCUS_RING_COLOR: Color = PRIMARY_LIGHT;
+pub const FOCUS_RING_WIDTH: f64 = 2.;
+pub const FOCUS_RING_OFFSET: f64 = 1.;
+pub const FOCUS_RING_RADIUS: f64 = 4.;
 pub const TEXT_SIZE_NORMAL: f64 = 15.0;
 pub const TEXT_SIZE_LARGE: f64 = 24.0;
 pub const BASIC_WIDGET_HEIGHT: f64 = 18.0;
@@ -15,7 +15,7 @@ use vello::Scene;
 
 use crate::contexts::AccessCtx;
 use crate::paint_scene_helpers::UnitPoint;
-use crate::widget::WidgetPod;
+use crate::widget::{WidgetMut, WidgetPod};
 use crate::{
     AccessEvent, BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
     PointerEvent, Rect, Size, StatusChange, TextEvent, Widget, WidgetId,
@@ -39,9 +39,14 @@ impl Align {
     /// terms of left and right. This is inadequate for bidi-aware layout
     /// and thus the API will change when Masonry gains bidi capability.
     pub fn new(align: UnitPoint, child: impl Widget + 'static) -> Align {
+        Align::new_pod(align, WidgetPod::new(child).boxed())
+    }
+
+    /// Create widget with alignment from a widget pod.
+    pub fn new_pod(align: UnitPoint, child: WidgetPod<Box<dyn Widget>>) -> Align {
         Align {
             align,
-            child: WidgetPod::new(child).boxed(),
+            child,
             width_factor: None,
             height_factor: None,
         }
@@ -83,6 +88,19 @@ impl Align {
     }
 }
 
+// --- MARK: WIDGETMUT ---
+impl WidgetMut<'_, Align> {
+    /// Set the alignment of the child.
+    pub fn set_align(&mut self, align: UnitPoint) {
+        self.widget.align = align;
+        self.ctx.request_layout();
+    }
+
+    pub fn child_mut(&mut self) -> WidgetMut<'_, Box<dyn Widget>> {
+        self.ctx.get_mut(&mut self.widget.child)
+    }
+}
+
 // --- MARK: IMPL WIDGET ---
 impl Widget for Align {
     fn on_pointer_event(&mut self, _ctx: &mut EventCtx, _event: &PointerEvent) {}
@@ -10,6 +10,7 @@ use vello::Scene;
 
 use crate::action::Action;
 use crate::event::PointerButton;
+use crate::gesture::ClickRecognizer;
 use crate::paint_scene_helpers::{fill_lin_gradient, stroke, UnitPoint};
 use crate::text::TextStorage;
 use crate::widget::{Label, WidgetMut, WidgetPod};
@@ -23,11 +24,12 @@ use crate::{
 // should be reevaluated at some point.
 const LABEL_INSETS: Insets = Insets::uniform_xy(8., 2.);
 
-/// A button with a text label.
+/// A button with a text label, or any other child widget.
 ///
 /// Emits [`Action::ButtonPressed`] when pressed.
-pub struct Button {
-    label: WidgetPod<Label>,
+pub struct Button<W: Widget = Label> {
+    child: WidgetPod<W>,
+    click: ClickRecognizer,
 }
 
 // --- MARK: BUILDERS ---
@@ -57,8 +59,34 @@ impl Button {
     /// let button = Button::from_label(label);
     /// ```
     pub fn from_label(label: Label) -> Button {
+        Button::from_child(label.with_skip_pointer(true))
+    }
+}
+
+impl<W: Widget> Button<W> {
+    /// Create a new button with the provided child widget, e.g. an icon.
+    ///
+    /// The child is drawn on top of the button's background.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use masonry::widget::{Button, Flex, Label};
+    ///
+    /// let content = Flex::row()
+    ///     .with_child(Label::new("+"))
+    ///     .with_child(Label::new("Increment"));
+    /// let button = Button::from_child(content);
+    /// ```
+    pub fn from_child(child: W) -> Self {
+        Self::from_pod(WidgetPod::new(child))
+    }
+
+    /// Create a new button with the provided child [`WidgetPod`].
+    pub fn from_pod(child: WidgetPod<W>) -> Self {
         Button {
-            label: WidgetPod::new(label.with_skip_pointer(true)),
+            child,
+            click: ClickRecognizer::new(),
         }
     }
 }
@@ -71,35 +99,26 @@ impl WidgetMut<'_, Button> {
     }
 
     pub fn label_mut(&mut self) -> WidgetMut<'_, Label> {
-        self.ctx.get_mut(&mut self.widget.label)
+        self.child_mut()
+    }
+}
+
+impl<W: Widget> WidgetMut<'_, Button<W>> {
+    pub fn child_mut(&mut self) -> WidgetMut<'_, W> {
+        self.ctx.get_mut(&mut self.widget.child)
     }
 }
 
 // --- MARK: IMPL WIDGET ---
-impl Widget for Button {
+impl<W: Widget> Widget for Button<W> {
     fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
-        match event {
-            PointerEvent::PointerDown(_, _) => {
-                if !ctx.is_disabled() {
-                    ctx.set_active(true);
-                    ctx.request_paint();
-                    trace!("Button {:?} pressed", ctx.widget_id());
-                }
-            }
-            PointerEvent::PointerUp(button, _) => {
-                if ctx.is_active() && ctx.is_hot() && !ctx.is_disabled() {
-                    ctx.submit_action(Action::ButtonPressed(*button));
-                    trace!("Button {:?} released", ctx.widget_id());
-                }
-                ctx.request_paint();
-                ctx.set_active(false);
-            }
-            PointerEvent::PointerLeave(_) => {
-                // If the screen was locked whilst holding down the mouse button, we don't get a `PointerUp`
-                // event, but should no longer be active
-                ctx.set_active(false);
-            }
-            _ => (),
+        let was_pressed = self.click.is_pressed();
+        if let Some(click) = self.click.on_pointer_event(ctx, event) {
+            ctx.submit_action(Action::ButtonPressed(click.button));
+            trace!("Button {:?} released", ctx.widget_id());
+        }
+        if self.click.is_pressed() != was_pressed {
+            ctx.request_paint();
         }
     }
 
@@ -122,16 +141,16 @@ impl Widget for Button {
     }
 
     fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
-        self.label.lifecycle(ctx, event);
+        self.child.lifecycle(ctx, event);
     }
 
     fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
         let padding = Size::new(LABEL_INSETS.x_value(), LABEL_INSETS.y_value());
-        let label_bc = bc.shrink(padding).loosen();
+        let child_bc = bc.shrink(padding).loosen();
 
-        let label_size = self.label.layout(ctx, &label_bc);
+        let child_size = self.child.layout(ctx, &child_bc);
 
-        let baseline = ctx.child_baseline_offset(&self.label);
+        let baseline = ctx.child_baseline_offset(&self.child);
         ctx.set_baseline_offset(baseline + LABEL_INSETS.y1);
 
         // HACK: to make sure we look okay at default sizes when beside a textbox,
@@ -139,12 +158,12 @@ impl Widget for Button {
         let min_height = theme::BORDERED_WIDGET_HEIGHT;
 
         let button_size = bc.constrain(Size::new(
-            label_size.width + padding.width,
-            (label_size.height + padding.height).max(min_height),
+            child_size.width + padding.width,
+            (child_size.height + padding.height).max(min_height),
         ));
 
-        let label_offset = (button_size.to_vec2() - label_size.to_vec2()) / 2.0;
-        ctx.place_child(&mut self.label, label_offset.to_point());
+        let child_offset = (button_size.to_vec2() - child_size.to_vec2()) / 2.0;
+        ctx.place_child(&mut self.child, child_offset.to_point());
 
         trace!("Computed button size: {}", button_size);
         button_size
@@ -194,16 +213,18 @@ impl Widget for Button {
         // the child label already has a 'name' property.
         // This is more of a proof of concept of `get_raw_ref()`.
         if false {
-            let label = ctx.get_raw_ref(&self.label);
-            let name = label.widget().text().as_str().to_string();
-            ctx.current_node().set_name(name);
+            let child = ctx.get_raw_ref(&self.child);
+            if let Some(label) = child.widget().as_any().downcast_ref::<Label>() {
+                let name = label.text().as_str().to_string();
+                ctx.current_node().set_name(name);
+            }
         }
         ctx.current_node()
             .set_default_action_verb(DefaultActionVerb::Click);
     }
 
     fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
-        smallvec![self.label.id()]
+        smallvec![self.child.id()]
     }
 
     fn make_trace_span(&self) -> Span {
@@ -213,7 +234,7 @@ impl Widget for Button {
     // FIXME
     #[cfg(FALSE)]
     fn get_debug_text(&self) -> Option<String> {
-        Some(self.label.as_ref().text().as_str().to_string())
+        Some(self.child.as_ref().text().as_str().to_string())
     }
 }
 
@@ -226,6 +247,7 @@ mod tests {
     use crate::assert_render_snapshot;
     use crate::testing::{widget_ids, TestHarness, TestWidgetExt};
     use crate::theme::PRIMARY_LIGHT;
+    use crate::widget::Flex;
 
     #[test]
     fn simple_button() {
@@ -246,6 +268,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn button_with_child() {
+        let [button_id, icon_id] = widget_ids();
+        let content = Flex::row()
+            .with_child_id(Label::new("+"), icon_id)
+            .with_child(Label::new("Add"));
+        let widget = Button::from_child(content).with_id(button_id);
+
+        let mut harness = TestHarness::create(widget);
+
+        // Clicking on the content should press the button
+        harness.mouse_click_on(icon_id);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::ButtonPressed(PointerButton::Primary), button_id))
+        );
+
+        harness.edit_widget(button_id, |mut button| {
+            let mut button = button.downcast::<Button<Flex>>();
+            button.child_mut().add_child(Label::new("!"));
+        });
+        assert_eq!(
+            harness.get_widget(button_id).children()[0].children().len(),
+            3
+        );
+    }
+
     #[test]
     fn edit_button() {
         let image_1 = {
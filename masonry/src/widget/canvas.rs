@@ -0,0 +1,410 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A widget for custom 2D drawing.
+
+use accesskit::{NodeBuilder, Role};
+use smallvec::SmallVec;
+use tracing::{trace, trace_span, Span};
+use vello::kurbo::{BezPath, Point, Shape};
+use vello::peniko::BlendMode;
+use vello::Scene;
+
+use crate::widget::WidgetMut;
+use crate::{
+    theme, AccessCtx, AccessEvent, Action, Affine, ArcStr, BoxConstraints, EventCtx, LayoutCtx,
+    LifeCycle, LifeCycleCtx, PaintCtx, PointerButton, PointerEvent, Size, StatusChange, TextEvent,
+    Widget, WidgetId,
+};
+
+type PaintFn = Box<dyn Fn(&mut Scene, Size)>;
+type HitRegionsFn = Box<dyn Fn(Size) -> Vec<HitRegion>>;
+
+/// A region of a [`Canvas`] which pointer events report, and which is exposed
+/// to accessibility, such as a bar of a chart.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HitRegion {
+    shape: BezPath,
+    label: Option<ArcStr>,
+    role: Role,
+}
+
+/// A pointer event on a [`Canvas`], which is submitted as an [`Action::CanvasPointer`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CanvasPointerEvent {
+    /// What happened.
+    pub kind: CanvasPointerKind,
+    /// The position of the pointer, in the canvas's coordinate space.
+    pub position: Point,
+    /// The index of the topmost [`HitRegion`] under the pointer, if any.
+    pub region: Option<usize>,
+}
+
+/// The kind of a [`CanvasPointerEvent`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CanvasPointerKind {
+    /// A pointer button was pressed on the canvas.
+    Down(PointerButton),
+    /// A pointer button pressed on the canvas was released.
+    Up(PointerButton),
+    /// The pointer moved over the canvas, or while a button pressed on it is held.
+    Move,
+    /// The pointer left the canvas.
+    Leave,
+}
+
+/// A widget which draws custom 2D graphics with a paint function.
+///
+/// The paint function is called with the scene to draw into and the size of the canvas,
+/// with the origin at the top left of the canvas. Drawing is clipped to the canvas.
+///
+/// The canvas can have [`HitRegion`]s, which are computed from its size, to report which
+/// part of the drawing the pointer is over, and to describe the drawing to accessibility.
+/// Pointer events are only submitted as actions if they are [enabled](Self::with_pointer_events).
+pub struct Canvas {
+    paint: PaintFn,
+    hit_regions_fn: Option<HitRegionsFn>,
+    hit_regions: Vec<HitRegion>,
+    /// The accessibility node ids of the hit regions, which are kept when the regions change.
+    region_ids: Vec<WidgetId>,
+    report_buttons: bool,
+    report_moves: bool,
+}
+
+// --- MARK: HIT REGION ---
+impl HitRegion {
+    /// A hit region covering `shape`, in the canvas's coordinate space.
+    pub fn new(shape: impl Shape) -> Self {
+        Self {
+            shape: shape.into_path(0.1),
+            label: None,
+            role: Role::GraphicsObject,
+        }
+    }
+
+    /// Builder-style method to set the label describing the region to accessibility.
+    pub fn label(mut self, label: impl Into<ArcStr>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Builder-style method to set the accessibility role of the region.
+    ///
+    /// The default is [`Role::GraphicsObject`].
+    pub fn role(mut self, role: Role) -> Self {
+        self.role = role;
+        self
+    }
+
+    /// The shape of the region.
+    pub fn shape(&self) -> &BezPath {
+        &self.shape
+    }
+
+    /// Whether `point`, in the canvas's coordinate space, is in the region.
+    pub fn contains(&self, point: Point) -> bool {
+        self.shape.contains(point)
+    }
+}
+
+// --- MARK: BUILDERS ---
+impl Canvas {
+    /// Create a canvas which draws with `paint`.
+    pub fn new(paint: impl Fn(&mut Scene, Size) + 'static) -> Self {
+        Self {
+            paint: Box::new(paint),
+            hit_regions_fn: None,
+            hit_regions: Vec::new(),
+            region_ids: Vec::new(),
+            report_buttons: false,
+            report_moves: false,
+        }
+    }
+
+    /// Builder-style method to set the function computing the hit regions for a given size.
+    ///
+    /// Regions which come later are on top of earlier regions.
+    pub fn with_hit_regions(
+        mut self,
+        hit_regions: impl Fn(Size) -> Vec<HitRegion> + 'static,
+    ) -> Self {
+        self.hit_regions_fn = Some(Box::new(hit_regions));
+        self
+    }
+
+    /// Builder-style method to enable submitting pointer events as actions.
+    ///
+    /// `buttons` enables [`Down`](CanvasPointerKind::Down) and [`Up`](CanvasPointerKind::Up)
+    /// events, and `moves` enables [`Move`](CanvasPointerKind::Move) and
+    /// [`Leave`](CanvasPointerKind::Leave) events.
+    pub fn with_pointer_events(mut self, buttons: bool, moves: bool) -> Self {
+        self.report_buttons = buttons;
+        self.report_moves = moves;
+        self
+    }
+
+    /// The current hit regions.
+    pub fn hit_regions(&self) -> &[HitRegion] {
+        &self.hit_regions
+    }
+
+    /// The index of the topmost hit region containing `point`.
+    pub fn region_at(&self, point: Point) -> Option<usize> {
+        self.hit_regions
+            .iter()
+            .rposition(|region| region.contains(point))
+    }
+
+    fn update_hit_regions(&mut self, size: Size) {
+        self.hit_regions = match &self.hit_regions_fn {
+            Some(hit_regions_fn) => hit_regions_fn(size),
+            None => Vec::new(),
+        };
+        let missing_ids = self.hit_regions.len().saturating_sub(self.region_ids.len());
+        self.region_ids
+            .extend(std::iter::repeat_with(WidgetId::next).take(missing_ids));
+    }
+}
+
+// --- MARK: WIDGETMUT ---
+impl WidgetMut<'_, Canvas> {
+    /// Set the paint function, and repaint the canvas.
+    pub fn set_paint(&mut self, paint: impl Fn(&mut Scene, Size) + 'static) {
+        self.widget.paint = Box::new(paint);
+        self.ctx.request_paint();
+    }
+
+    /// Set the function computing the hit regions, or remove the hit regions.
+    pub fn set_hit_regions(&mut self, hit_regions: Option<Box<dyn Fn(Size) -> Vec<HitRegion>>>) {
+        self.widget.hit_regions_fn = hit_regions;
+        let size = self.ctx.size();
+        self.widget.update_hit_regions(size);
+        self.ctx.request_accessibility_update();
+    }
+
+    /// Set which pointer events are submitted as actions.
+    ///
+    /// See [`Canvas::with_pointer_events`] for details.
+    pub fn set_pointer_events(&mut self, buttons: bool, moves: bool) {
+        self.widget.report_buttons = buttons;
+        self.widget.report_moves = moves;
+    }
+}
+
+// --- MARK: IMPL WIDGET ---
+impl Widget for Canvas {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        let (kind, state) = match event {
+            PointerEvent::PointerDown(button, state) => {
+                if !ctx.is_disabled() {
+                    ctx.capture_pointer();
+                }
+                (CanvasPointerKind::Down(*button), state)
+            }
+            PointerEvent::PointerUp(button, state) => {
+                if !ctx.has_pointer_capture() {
+                    return;
+                }
+                ctx.release_pointer();
+                (CanvasPointerKind::Up(*button), state)
+            }
+            PointerEvent::PointerMove(state) => (CanvasPointerKind::Move, state),
+            PointerEvent::PointerLeave(state) => (CanvasPointerKind::Leave, state),
+            _ => return,
+        };
+        let report = match kind {
+            CanvasPointerKind::Down(_) | CanvasPointerKind::Up(_) => self.report_buttons,
+            CanvasPointerKind::Move | CanvasPointerKind::Leave => self.report_moves,
+        };
+        if !report || ctx.is_disabled() {
+            return;
+        }
+        let position = ctx.to_local(Point::new(state.position.x, state.position.y));
+        let region = match kind {
+            CanvasPointerKind::Leave => None,
+            _ => self.region_at(position),
+        };
+        trace!("Canvas {:?} pointer event {:?}", ctx.widget_id(), kind);
+        ctx.submit_action(Action::CanvasPointer(CanvasPointerEvent {
+            kind,
+            position,
+            region,
+        }));
+    }
+
+    fn on_text_event(&mut self, _ctx: &mut EventCtx, _event: &TextEvent) {}
+
+    fn on_access_event(&mut self, _ctx: &mut EventCtx, _event: &AccessEvent) {}
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle) {}
+
+    fn layout(&mut self, _ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        // A canvas takes all the space it is given, with a default size if it is unbounded.
+        let size = if bc.is_width_bounded() && bc.is_height_bounded() {
+            bc.max()
+        } else {
+            bc.constrain(Size::new(
+                theme::WIDE_WIDGET_WIDTH,
+                theme::WIDE_WIDGET_WIDTH,
+            ))
+        };
+        self.update_hit_regions(size);
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let size = ctx.size();
+        scene.push_layer(BlendMode::default(), 1., Affine::IDENTITY, &size.to_rect());
+        (self.paint)(scene, size);
+        scene.pop_layer();
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::Canvas
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        for (region, id) in self.hit_regions.iter().zip(&self.region_ids) {
+            let mut node = NodeBuilder::new(region.role);
+            if let Some(label) = &region.label {
+                node.set_name(label.to_string());
+            }
+            ctx.push_child_node(*id, node, region.shape.bounding_box());
+        }
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        SmallVec::new()
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("Canvas")
+    }
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use vello::kurbo::Rect;
+
+    use super::*;
+    use crate::testing::{widget_ids, TestHarness, TestWidgetExt};
+
+    fn two_halves(size: Size) -> Vec<HitRegion> {
+        vec![
+            HitRegion::new(Rect::new(0.0, 0.0, size.width / 2.0, size.height)).label("Left"),
+            HitRegion::new(Rect::new(size.width / 2.0, 0.0, size.width, size.height))
+                .label("Right"),
+        ]
+    }
+
+    #[test]
+    fn hit_regions() {
+        let canvas = Canvas::new(|_, _| {}).with_hit_regions(two_halves);
+        let harness = TestHarness::create_with_size(canvas, Size::new(40.0, 30.0));
+        let root = harness.root_widget();
+        let canvas = root.downcast::<Canvas>().unwrap();
+        assert_eq!(canvas.hit_regions().len(), 2);
+        assert_eq!(canvas.region_at(Point::new(5.0, 5.0)), Some(0));
+        assert_eq!(canvas.region_at(Point::new(35.0, 5.0)), Some(1));
+        assert_eq!(canvas.region_at(Point::new(50.0, 5.0)), None);
+    }
+
+    #[test]
+    fn remove_hit_regions() {
+        let canvas = Canvas::new(|_, _| {}).with_hit_regions(two_halves);
+        let mut harness = TestHarness::create_with_size(canvas, Size::new(40.0, 30.0));
+        harness.edit_root_widget(|mut root| {
+            let mut canvas = root.downcast::<Canvas>();
+            canvas.set_hit_regions(None);
+        });
+        let root = harness.root_widget();
+        let canvas = root.downcast::<Canvas>().unwrap();
+        assert!(canvas.hit_regions().is_empty());
+        assert_eq!(canvas.region_at(Point::new(5.0, 5.0)), None);
+    }
+
+    #[test]
+    fn pointer_events() {
+        let [canvas_id] = widget_ids();
+        let canvas = Canvas::new(|_, _| {})
+            .with_hit_regions(two_halves)
+            .with_pointer_events(true, false);
+        let mut harness =
+            TestHarness::create_with_size(canvas.with_id(canvas_id), Size::new(40.0, 30.0));
+
+        harness.mouse_move((30.0, 10.0));
+        assert_eq!(harness.pop_action(), None);
+
+        harness.mouse_button_press(PointerButton::Primary);
+        assert_eq!(
+            harness.pop_action(),
+            Some((
+                Action::CanvasPointer(CanvasPointerEvent {
+                    kind: CanvasPointerKind::Down(PointerButton::Primary),
+                    position: Point::new(30.0, 10.0),
+                    region: Some(1),
+                }),
+                canvas_id
+            ))
+        );
+
+        harness.edit_widget(canvas_id, |mut canvas| {
+            let mut canvas = canvas.downcast::<Canvas>();
+            canvas.set_pointer_events(true, true);
+        });
+        harness.mouse_move((5.0, 10.0));
+        assert_eq!(
+            harness.pop_action(),
+            Some((
+                Action::CanvasPointer(CanvasPointerEvent {
+                    kind: CanvasPointerKind::Move,
+                    position: Point::new(5.0, 10.0),
+                    region: Some(0),
+                }),
+                canvas_id
+            ))
+        );
+    }
+
+    #[test]
+    fn hit_region_access_nodes() {
+        let [canvas_id] = widget_ids();
+        let canvas = Canvas::new(|_, _| {}).with_hit_regions(two_halves);
+        let mut harness =
+            TestHarness::create_with_size(canvas.with_id(canvas_id), Size::new(40.0, 30.0));
+        let tree_update = harness.access_tree_update();
+        let node = |id: accesskit::NodeId| {
+            tree_update
+                .nodes
+                .iter()
+                .find(|(node_id, _)| *node_id == id)
+                .map(|(_, node)| node)
+                .unwrap()
+        };
+
+        let region_ids: Vec<accesskit::NodeId> = {
+            let canvas = harness.get_widget(canvas_id);
+            let canvas = canvas.downcast::<Canvas>().unwrap();
+            canvas.region_ids.iter().map(|id| (*id).into()).collect()
+        };
+        assert_eq!(node(canvas_id.into()).children(), region_ids);
+
+        let left = node(region_ids[0]);
+        assert_eq!(left.role(), Role::GraphicsObject);
+        assert_eq!(left.name(), Some("Left"));
+        assert_eq!(
+            left.bounds(),
+            Some(accesskit::Rect::new(0.0, 0.0, 20.0, 30.0))
+        );
+        let right = node(region_ids[1]);
+        assert_eq!(right.name(), Some("Right"));
+        assert_eq!(
+            right.bounds(),
+            Some(accesskit::Rect::new(20.0, 0.0, 40.0, 30.0))
+        );
+    }
+}
@@ -0,0 +1,994 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A widget plotting series of data as line, bar or scatter charts.
+
+use accesskit::Role;
+use smallvec::SmallVec;
+use tracing::{trace, trace_span, Span};
+use vello::kurbo::{BezPath, Circle, Line, Stroke};
+use vello::peniko::{BlendMode, Color};
+use vello::Scene;
+
+use crate::paint_scene_helpers::{fill_color, fill_lin_gradient, stroke, UnitPoint};
+use crate::text::TextLayout;
+use crate::widget::WidgetMut;
+use crate::{
+    theme, AccessCtx, AccessEvent, Affine, ArcStr, BoxConstraints, EventCtx, LayoutCtx, LifeCycle,
+    LifeCycleCtx, PaintCtx, Point, PointerButton, PointerEvent, Rect, Size, StatusChange,
+    TextEvent, Widget, WidgetId,
+};
+
+const DEFAULT_SIZE: Size = Size::new(400., 300.);
+const PADDING: f64 = 8.;
+const TICK_LENGTH: f64 = 4.;
+const TICK_LABEL_GAP: f64 = 4.;
+const TICK_LABEL_SIZE: f32 = 12.;
+/// The approximate distance between the ticks of the x axis.
+const X_TICK_SPACING: f64 = 80.;
+/// The approximate distance between the ticks of the y axis.
+const Y_TICK_SPACING: f64 = 40.;
+/// The space which the tick labels of the x axis are assumed to take when choosing the
+/// ticks of the y axis.
+const X_AXIS_ESTIMATE: f64 = 30.;
+/// The space which the tick labels of the y axis are assumed to take when choosing the
+/// ticks of the x axis.
+const Y_AXIS_ESTIMATE: f64 = 50.;
+const LINE_WIDTH: f64 = 2.;
+const POINT_RADIUS: f64 = 3.;
+/// How close the pointer has to be to a point of a line or scatter chart to hover it.
+const HOVER_RADIUS: f64 = 8.;
+/// The fraction of the distance between two bars which the bars of each x value fill.
+const BAR_FILL: f64 = 0.8;
+const SWATCH_SIZE: f64 = 10.;
+const LEGEND_PADDING: f64 = 6.;
+const TOOLTIP_PADDING: f64 = 4.;
+const TOOLTIP_OFFSET: f64 = 12.;
+/// How much a line of mouse wheel scrolling zooms the chart.
+const WHEEL_ZOOM: f64 = 1.1;
+
+/// The colors of series which don't have a color set, in order.
+const SERIES_COLORS: [Color; 6] = [
+    theme::PRIMARY_LIGHT,
+    Color::rgb8(0xff, 0x9f, 0x43),
+    Color::rgb8(0x7b, 0xd8, 0x8f),
+    Color::rgb8(0xf7, 0x6e, 0x8a),
+    Color::rgb8(0xc3, 0x9b, 0xf2),
+    Color::rgb8(0xf2, 0xd7, 0x5c),
+];
+
+/// How the series of a [`Chart`] are drawn.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChartKind {
+    /// The points of each series are joined by lines.
+    #[default]
+    Line,
+    /// Each point is a bar from zero, with the bars of the series grouped side by side.
+    Bar,
+    /// Each point is drawn as a dot.
+    Scatter,
+}
+
+/// A named series of points plotted by a [`Chart`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChartSeries {
+    name: ArcStr,
+    color: Option<Color>,
+    points: Vec<Point>,
+}
+
+/// A tick of an axis, with its label.
+struct Tick {
+    value: f64,
+    label: TextLayout<ArcStr>,
+}
+
+/// A chart plotting one or more series of data, with axes, ticks and a legend.
+///
+/// Hovering a point shows a tooltip with its value. The chart can be zoomed with the mouse
+/// wheel or a pinch gesture, and panned by dragging it; double clicking it fits it to the
+/// data again.
+///
+/// Points can be [appended](WidgetMut::append_points) to a series without laying out the
+/// chart again, unless the ticks of the axes change.
+pub struct Chart {
+    kind: ChartKind,
+    series: Vec<ChartSeries>,
+    show_legend: bool,
+    /// The bounds of the points of all series, or `None` if there are no points.
+    data_bounds: Option<Rect>,
+    /// The smallest distance between the x values of two consecutive points of a series,
+    /// which decides the width of bars.
+    min_x_step: Option<f64>,
+    /// The visible range of the data, if the chart was panned or zoomed.
+    viewport: Option<Rect>,
+    /// The area the data is plotted in, in the chart's coordinate space.
+    plot_rect: Rect,
+    x_ticks: Vec<Tick>,
+    y_ticks: Vec<Tick>,
+    x_step: f64,
+    y_step: f64,
+    legend: Vec<TextLayout<ArcStr>>,
+    tooltip: TextLayout<ArcStr>,
+    /// The series and point indices of the hovered point.
+    hovered: Option<(usize, usize)>,
+    /// The last pointer position while the chart is being dragged.
+    pan_position: Option<Point>,
+}
+
+// --- MARK: SERIES ---
+impl ChartSeries {
+    /// A series with the given name, which is shown in the legend, and points.
+    pub fn new(name: impl Into<ArcStr>, points: impl Into<Vec<Point>>) -> Self {
+        Self {
+            name: name.into(),
+            color: None,
+            points: points.into(),
+        }
+    }
+
+    /// Builder-style method to set the color of the series.
+    ///
+    /// By default, the series is given a color based on its index.
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// The name of the series.
+    pub fn name(&self) -> &ArcStr {
+        &self.name
+    }
+
+    /// The color of the series, if it was set.
+    pub fn get_color(&self) -> Option<Color> {
+        self.color
+    }
+
+    /// The points of the series.
+    pub fn points(&self) -> &[Point] {
+        &self.points
+    }
+}
+
+/// The bounds of the finite points in `points`, unioned with `bounds`.
+fn union_bounds(bounds: Option<Rect>, points: &[Point]) -> Option<Rect> {
+    points
+        .iter()
+        .filter(|point| point.x.is_finite() && point.y.is_finite())
+        .fold(bounds, |bounds, point| match bounds {
+            Some(bounds) => Some(bounds.union_pt(*point)),
+            None => Some(Rect::from_points(*point, *point)),
+        })
+}
+
+/// The smallest positive distance between the x values of consecutive points, including
+/// the distance between `previous` and the first of `points`.
+fn min_step(step: Option<f64>, previous: Option<Point>, points: &[Point]) -> Option<f64> {
+    previous
+        .into_iter()
+        .chain(points.iter().copied())
+        .zip(points.iter())
+        .map(|(a, b)| (b.x - a.x).abs())
+        .filter(|step| *step > 0. && step.is_finite())
+        .fold(step, |min, step| {
+            Some(min.map_or(step, |min: f64| min.min(step)))
+        })
+}
+
+/// Choose "nice" values for the ticks of an axis spanning `min` to `max`, with about
+/// `count` ticks, and return them with the distance between them.
+fn tick_values(min: f64, max: f64, count: f64) -> (Vec<f64>, f64) {
+    let span = max - min;
+    if !(span > 0. && span.is_finite()) {
+        return (Vec::new(), 1.);
+    }
+    let raw_step = span / count.max(1.);
+    let magnitude = 10_f64.powf(raw_step.log10().floor());
+    let step = [1., 2., 5., 10.]
+        .into_iter()
+        .map(|factor| factor * magnitude)
+        .find(|step| *step >= raw_step)
+        .unwrap_or(10. * magnitude);
+    let first = (min / step).ceil() as i64;
+    let last = (max / step + 1e-9).floor() as i64;
+    let values = (first..=last)
+        .map(|index| index as f64 * step)
+        // Avoid labels like "-0".
+        .map(|value| if value.abs() < step * 1e-9 { 0. } else { value })
+        .collect();
+    (values, step)
+}
+
+/// Format `value` with enough decimals to tell apart values which are `step` apart.
+fn format_value(value: f64, step: f64) -> String {
+    let decimals = (-step.log10().floor()).max(0.) as usize;
+    format!("{value:.decimals$}")
+}
+
+// --- MARK: BUILDERS ---
+impl Chart {
+    /// Create a chart of the given kind, without any series.
+    pub fn new(kind: ChartKind) -> Self {
+        Self {
+            kind,
+            series: Vec::new(),
+            show_legend: true,
+            data_bounds: None,
+            min_x_step: None,
+            viewport: None,
+            plot_rect: Rect::ZERO,
+            x_ticks: Vec::new(),
+            y_ticks: Vec::new(),
+            x_step: 1.,
+            y_step: 1.,
+            legend: Vec::new(),
+            tooltip: TextLayout::new("".into(), TICK_LABEL_SIZE),
+            hovered: None,
+            pan_position: None,
+        }
+    }
+
+    /// Builder-style method to add a series to the chart.
+    pub fn with_series(mut self, series: ChartSeries) -> Self {
+        self.series.push(series);
+        self.update_bounds();
+        self
+    }
+
+    /// Builder-style method to set whether the legend is shown.
+    ///
+    /// The legend is shown by default.
+    pub fn with_legend(mut self, show_legend: bool) -> Self {
+        self.show_legend = show_legend;
+        self
+    }
+
+    /// How the series are drawn.
+    pub fn kind(&self) -> ChartKind {
+        self.kind
+    }
+
+    /// The series of the chart.
+    pub fn series(&self) -> &[ChartSeries] {
+        &self.series
+    }
+
+    /// The range of the data which is visible.
+    ///
+    /// Unless the chart was panned or zoomed, this fits all the data.
+    pub fn view_range(&self) -> Rect {
+        self.viewport.unwrap_or_else(|| self.fitted_range())
+    }
+
+    /// The series and point indices of the point which is hovered.
+    pub fn hovered_point(&self) -> Option<(usize, usize)> {
+        self.hovered
+    }
+
+    /// The values of the ticks of the x axis.
+    pub fn x_ticks(&self) -> Vec<f64> {
+        self.x_ticks.iter().map(|tick| tick.value).collect()
+    }
+
+    /// The values of the ticks of the y axis.
+    pub fn y_ticks(&self) -> Vec<f64> {
+        self.y_ticks.iter().map(|tick| tick.value).collect()
+    }
+
+    fn series_color(&self, index: usize) -> Color {
+        self.series[index]
+            .color
+            .unwrap_or(SERIES_COLORS[index % SERIES_COLORS.len()])
+    }
+
+    fn update_bounds(&mut self) {
+        self.data_bounds = None;
+        self.min_x_step = None;
+        for series in &self.series {
+            self.data_bounds = union_bounds(self.data_bounds, &series.points);
+            self.min_x_step = min_step(self.min_x_step, None, &series.points);
+        }
+    }
+
+    /// The width of each group of bars, in data coordinates.
+    fn bar_group_width(&self) -> f64 {
+        self.min_x_step.unwrap_or(1.) * BAR_FILL
+    }
+
+    /// The range which fits all the data, with some margin.
+    fn fitted_range(&self) -> Rect {
+        let Some(mut range) = self.data_bounds else {
+            return Rect::new(0., 0., 1., 1.);
+        };
+        if self.kind == ChartKind::Bar {
+            range = range.union_pt(Point::new(range.x0, 0.));
+            let half_bar = self.min_x_step.unwrap_or(1.) / 2.;
+            range.x0 -= half_bar;
+            range.x1 += half_bar;
+        }
+        if range.width() == 0. {
+            range.x0 -= 0.5;
+            range.x1 += 0.5;
+        }
+        if range.height() == 0. {
+            range.y0 -= 0.5;
+            range.y1 += 0.5;
+        }
+        let margin = range.height() * 0.05;
+        // Bars start at zero, so there is no margin below them.
+        if !(self.kind == ChartKind::Bar && range.y0 == 0.) {
+            range.y0 -= margin;
+        }
+        if !(self.kind == ChartKind::Bar && range.y1 == 0.) {
+            range.y1 += margin;
+        }
+        range
+    }
+
+    /// The values of the ticks of both axes, for the given range and size of the chart.
+    fn tick_values(&self, range: Rect, size: Size) -> ((Vec<f64>, f64), (Vec<f64>, f64)) {
+        let plot_width = size.width - 2. * PADDING - Y_AXIS_ESTIMATE;
+        let plot_height = size.height - 2. * PADDING - X_AXIS_ESTIMATE;
+        (
+            tick_values(range.x0, range.x1, plot_width / X_TICK_SPACING),
+            tick_values(range.y0, range.y1, plot_height / Y_TICK_SPACING),
+        )
+    }
+
+    fn ticks_changed(&self, size: Size) -> bool {
+        let ((x_values, _), (y_values, _)) = self.tick_values(self.view_range(), size);
+        !x_values
+            .iter()
+            .eq(self.x_ticks.iter().map(|tick| &tick.value))
+            || !y_values
+                .iter()
+                .eq(self.y_ticks.iter().map(|tick| &tick.value))
+    }
+
+    fn to_screen(&self, range: Rect, point: Point) -> Point {
+        let plot = self.plot_rect;
+        Point::new(
+            plot.x0 + (point.x - range.x0) / range.width() * plot.width(),
+            plot.y1 - (point.y - range.y0) / range.height() * plot.height(),
+        )
+    }
+
+    fn to_data(&self, range: Rect, point: Point) -> Point {
+        let plot = self.plot_rect;
+        Point::new(
+            range.x0 + (point.x - plot.x0) / plot.width() * range.width(),
+            range.y0 + (plot.y1 - point.y) / plot.height() * range.height(),
+        )
+    }
+
+    /// The bar of the given point, in the chart's coordinate space.
+    fn bar_rect(&self, range: Rect, series: usize, point: Point) -> Rect {
+        let group_width = self.bar_group_width();
+        let bar_width = group_width / self.series.len() as f64;
+        let x0 = point.x - group_width / 2. + series as f64 * bar_width;
+        Rect::from_points(
+            self.to_screen(range, Point::new(x0, 0.)),
+            self.to_screen(range, Point::new(x0 + bar_width, point.y)),
+        )
+    }
+
+    /// The point under `position`, in the chart's coordinate space.
+    fn point_at(&self, position: Point) -> Option<(usize, usize)> {
+        if !self.plot_rect.contains(position) {
+            return None;
+        }
+        let range = self.view_range();
+        let mut closest = None;
+        let mut closest_distance = HOVER_RADIUS;
+        for (series_idx, series) in self.series.iter().enumerate().rev() {
+            for (point_idx, point) in series.points.iter().enumerate() {
+                if self.kind == ChartKind::Bar {
+                    if self.bar_rect(range, series_idx, *point).contains(position) {
+                        return Some((series_idx, point_idx));
+                    }
+                    continue;
+                }
+                let distance = self.to_screen(range, *point).distance(position);
+                if distance <= closest_distance {
+                    closest = Some((series_idx, point_idx));
+                    closest_distance = distance;
+                }
+            }
+        }
+        closest
+    }
+
+    /// Zoom by `factor` around `position`, in the chart's coordinate space.
+    fn zoom(&mut self, position: Point, factor: f64) {
+        if !(factor > 0. && factor.is_finite()) {
+            return;
+        }
+        let range = self.view_range();
+        let center = self.to_data(range, position);
+        self.viewport = Some(Rect::new(
+            center.x + (range.x0 - center.x) / factor,
+            center.y + (range.y0 - center.y) / factor,
+            center.x + (range.x1 - center.x) / factor,
+            center.y + (range.y1 - center.y) / factor,
+        ));
+    }
+
+    /// Update the tick values and labels, reusing the labels of ticks which are unchanged.
+    fn update_ticks(ticks: &mut Vec<Tick>, values: &[f64], step: f64, ctx: &mut LayoutCtx) {
+        ticks.truncate(values.len());
+        for (index, value) in values.iter().enumerate() {
+            let text: ArcStr = format_value(*value, step).into();
+            match ticks.get_mut(index) {
+                Some(tick) => {
+                    tick.value = *value;
+                    if *tick.label.text() != text {
+                        tick.label.set_text(text);
+                    }
+                }
+                None => ticks.push(Tick {
+                    value: *value,
+                    label: TextLayout::new(text, TICK_LABEL_SIZE),
+                }),
+            }
+            let label = &mut ticks[index].label;
+            label.set_scale(ctx.scale_factor() as f32);
+            if label.needs_rebuild() {
+                let (font_ctx, layout_ctx) = ctx.text_contexts();
+                label.rebuild(font_ctx, layout_ctx);
+            }
+        }
+    }
+}
+
+// --- MARK: WIDGETMUT ---
+impl WidgetMut<'_, Chart> {
+    /// Set how the series are drawn.
+    pub fn set_kind(&mut self, kind: ChartKind) {
+        self.widget.kind = kind;
+        self.widget.hovered = None;
+        self.ctx.request_layout();
+    }
+
+    /// Replace all series of the chart.
+    pub fn set_series(&mut self, series: Vec<ChartSeries>) {
+        self.widget.series = series;
+        self.widget.update_bounds();
+        self.widget.hovered = None;
+        self.ctx.request_layout();
+    }
+
+    /// Replace the series at `index`.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    pub fn set_series_at(&mut self, index: usize, series: ChartSeries) {
+        let old = std::mem::replace(&mut self.widget.series[index], series);
+        self.widget.update_bounds();
+        self.widget.hovered = None;
+        if old.name != self.widget.series[index].name {
+            // The legend has to be laid out again.
+            self.ctx.request_layout();
+        } else {
+            self.update_after_data_change();
+        }
+    }
+
+    /// Append `points` to the series at `index`.
+    ///
+    /// Unlike replacing the series, this doesn't need to look at the existing points of the
+    /// series, and only lays out the chart again if the ticks of the axes change.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    pub fn append_points(&mut self, index: usize, points: &[Point]) {
+        let chart = &mut *self.widget;
+        let series = &mut chart.series[index];
+        chart.data_bounds = union_bounds(chart.data_bounds, points);
+        chart.min_x_step = min_step(chart.min_x_step, series.points.last().copied(), points);
+        series.points.extend_from_slice(points);
+        self.update_after_data_change();
+    }
+
+    /// Set whether the legend is shown.
+    pub fn set_legend(&mut self, show_legend: bool) {
+        self.widget.show_legend = show_legend;
+        self.ctx.request_paint();
+    }
+
+    /// Fit the chart to the data again, after it was panned or zoomed.
+    pub fn reset_view(&mut self) {
+        self.widget.viewport = None;
+        self.ctx.request_layout();
+    }
+
+    fn update_after_data_change(&mut self) {
+        if self.widget.ticks_changed(self.ctx.size()) {
+            self.ctx.request_layout();
+        } else {
+            self.ctx.request_paint();
+        }
+        self.ctx.request_accessibility_update();
+    }
+}
+
+// --- MARK: IMPL WIDGET ---
+impl Widget for Chart {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        let state = event.pointer_state();
+        let position = ctx.to_local(Point::new(state.position.x, state.position.y));
+        match event {
+            PointerEvent::PointerDown(PointerButton::Primary, state) if !ctx.is_disabled() => {
+                if state.count == 2 {
+                    self.viewport = None;
+                    ctx.request_layout();
+                } else if self.plot_rect.contains(position) {
+                    ctx.capture_pointer();
+                    self.pan_position = Some(position);
+                }
+            }
+            PointerEvent::PointerMove(_) => {
+                if let Some(last) = self.pan_position {
+                    let range = self.view_range();
+                    let delta = self.to_data(range, last) - self.to_data(range, position);
+                    self.viewport = Some(range + delta);
+                    self.pan_position = Some(position);
+                    ctx.request_layout();
+                    return;
+                }
+                let hovered = self.point_at(position);
+                if hovered != self.hovered {
+                    self.hovered = hovered;
+                    ctx.request_paint();
+                }
+            }
+            PointerEvent::PointerUp(PointerButton::Primary, _) if self.pan_position.is_some() => {
+                self.pan_position = None;
+                ctx.release_pointer();
+            }
+            PointerEvent::PointerLeave(_) if self.hovered.is_some() => {
+                self.hovered = None;
+                ctx.request_paint();
+            }
+            PointerEvent::MouseWheel(delta, _) if !ctx.is_disabled() => {
+                ctx.set_handled();
+                self.zoom(position, WHEEL_ZOOM.powf(delta.y));
+                ctx.request_layout();
+            }
+            PointerEvent::Pinch(delta, _) if !ctx.is_disabled() => {
+                ctx.set_handled();
+                self.zoom(position, 1. + delta);
+                ctx.request_layout();
+            }
+            _ => {}
+        }
+    }
+
+    fn on_text_event(&mut self, _ctx: &mut EventCtx, _event: &TextEvent) {}
+
+    fn on_access_event(&mut self, _ctx: &mut EventCtx, _event: &AccessEvent) {}
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle) {}
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        // A chart takes all the space it is given, with a default size if it is unbounded.
+        let size = if bc.is_width_bounded() && bc.is_height_bounded() {
+            bc.max()
+        } else {
+            bc.constrain(DEFAULT_SIZE)
+        };
+
+        let ((x_values, x_step), (y_values, y_step)) = self.tick_values(self.view_range(), size);
+        self.x_step = x_step;
+        self.y_step = y_step;
+        Self::update_ticks(&mut self.x_ticks, &x_values, x_step, ctx);
+        Self::update_ticks(&mut self.y_ticks, &y_values, y_step, ctx);
+
+        let y_label_width = self
+            .y_ticks
+            .iter()
+            .map(|tick| tick.label.size().width)
+            .fold(0., f64::max);
+        let x_label_height = self
+            .x_ticks
+            .iter()
+            .map(|tick| tick.label.size().height)
+            .fold(0., f64::max);
+        let left = PADDING + y_label_width + TICK_LABEL_GAP + TICK_LENGTH;
+        let bottom = PADDING + x_label_height + TICK_LABEL_GAP + TICK_LENGTH;
+        self.plot_rect = Rect::new(
+            left,
+            PADDING,
+            (size.width - PADDING).max(left + 1.),
+            (size.height - bottom).max(PADDING + 1.),
+        );
+
+        self.legend.truncate(self.series.len());
+        for (index, series) in self.series.iter().enumerate() {
+            match self.legend.get_mut(index) {
+                Some(label) if *label.text() != series.name => label.set_text(series.name.clone()),
+                Some(_) => {}
+                None => self
+                    .legend
+                    .push(TextLayout::new(series.name.clone(), TICK_LABEL_SIZE)),
+            }
+            let label = &mut self.legend[index];
+            label.set_scale(ctx.scale_factor() as f32);
+            if label.needs_rebuild() {
+                let (font_ctx, layout_ctx) = ctx.text_contexts();
+                label.rebuild(font_ctx, layout_ctx);
+            }
+        }
+
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let range = self.view_range();
+        let plot = self.plot_rect;
+
+        // Grid lines and ticks.
+        for tick in &self.x_ticks {
+            let x = self.to_screen(range, Point::new(tick.value, range.y0)).x;
+            stroke(
+                scene,
+                &Line::new((x, plot.y0), (x, plot.y1)),
+                theme::BORDER_DARK,
+                1.,
+            );
+            stroke(
+                scene,
+                &Line::new((x, plot.y1), (x, plot.y1 + TICK_LENGTH)),
+                theme::BORDER_LIGHT,
+                1.,
+            );
+        }
+        for tick in &self.y_ticks {
+            let y = self.to_screen(range, Point::new(range.x0, tick.value)).y;
+            stroke(
+                scene,
+                &Line::new((plot.x0, y), (plot.x1, y)),
+                theme::BORDER_DARK,
+                1.,
+            );
+            stroke(
+                scene,
+                &Line::new((plot.x0 - TICK_LENGTH, y), (plot.x0, y)),
+                theme::BORDER_LIGHT,
+                1.,
+            );
+        }
+
+        // The series, clipped to the plot area.
+        scene.push_layer(BlendMode::default(), 1., Affine::IDENTITY, &plot);
+        for (index, series) in self.series.iter().enumerate() {
+            let color = self.series_color(index);
+            let points = series
+                .points
+                .iter()
+                .filter(|point| point.x.is_finite() && point.y.is_finite());
+            match self.kind {
+                ChartKind::Line => {
+                    let mut path = BezPath::new();
+                    for point in points {
+                        let point = self.to_screen(range, *point);
+                        if path.elements().is_empty() {
+                            path.move_to(point);
+                        } else {
+                            path.line_to(point);
+                        }
+                    }
+                    scene.stroke(
+                        &Stroke::new(LINE_WIDTH),
+                        Affine::IDENTITY,
+                        color,
+                        None,
+                        &path,
+                    );
+                }
+                ChartKind::Scatter => {
+                    for point in points {
+                        let circle = Circle::new(self.to_screen(range, *point), POINT_RADIUS);
+                        fill_color(scene, &circle, color);
+                    }
+                }
+                ChartKind::Bar => {
+                    for point in points {
+                        let bar = self.bar_rect(range, index, *point);
+                        fill_lin_gradient(
+                            scene,
+                            &bar,
+                            [color, color.with_alpha_factor(0.6)],
+                            UnitPoint::TOP,
+                            UnitPoint::BOTTOM,
+                        );
+                    }
+                }
+            }
+        }
+        if let Some((series, point)) = self.hovered {
+            let point = self.series[series].points[point];
+            if self.kind == ChartKind::Bar {
+                stroke(
+                    scene,
+                    &self.bar_rect(range, series, point),
+                    theme::FOREGROUND_LIGHT,
+                    1.,
+                );
+            } else {
+                let circle = Circle::new(self.to_screen(range, point), POINT_RADIUS + 2.);
+                stroke(scene, &circle, theme::FOREGROUND_LIGHT, 2.);
+            }
+        }
+        scene.pop_layer();
+
+        // Axes and tick labels.
+        stroke(
+            scene,
+            &Line::new((plot.x0, plot.y0), (plot.x0, plot.y1)),
+            theme::BORDER_LIGHT,
+            1.,
+        );
+        stroke(
+            scene,
+            &Line::new((plot.x0, plot.y1), (plot.x1, plot.y1)),
+            theme::BORDER_LIGHT,
+            1.,
+        );
+        for index in 0..self.x_ticks.len() {
+            let x = self
+                .to_screen(range, Point::new(self.x_ticks[index].value, range.y0))
+                .x;
+            let label = &mut self.x_ticks[index].label;
+            let label_size = label.size();
+            label.draw(
+                scene,
+                (
+                    x - label_size.width / 2.,
+                    plot.y1 + TICK_LENGTH + TICK_LABEL_GAP,
+                ),
+            );
+        }
+        for index in 0..self.y_ticks.len() {
+            let y = self
+                .to_screen(range, Point::new(range.x0, self.y_ticks[index].value))
+                .y;
+            let label = &mut self.y_ticks[index].label;
+            let label_size = label.size();
+            label.draw(
+                scene,
+                (
+                    plot.x0 - TICK_LENGTH - TICK_LABEL_GAP - label_size.width,
+                    y - label_size.height / 2.,
+                ),
+            );
+        }
+
+        // The legend, in the top right corner of the plot area.
+        if self.show_legend && !self.series.is_empty() {
+            let row_height = self
+                .legend
+                .iter()
+                .map(|label| label.size().height)
+                .fold(SWATCH_SIZE, f64::max);
+            let label_width = self
+                .legend
+                .iter()
+                .map(|label| label.size().width)
+                .fold(0., f64::max);
+            let width = SWATCH_SIZE + LEGEND_PADDING + label_width + 2. * LEGEND_PADDING;
+            let height = row_height * self.legend.len() as f64 + 2. * LEGEND_PADDING;
+            let origin = Point::new(plot.x1 - LEGEND_PADDING - width, plot.y0 + LEGEND_PADDING);
+            let legend_rect = Rect::from_origin_size(origin, (width, height));
+            fill_color(
+                scene,
+                &legend_rect,
+                theme::BACKGROUND_DARK.with_alpha_factor(0.8),
+            );
+            stroke(scene, &legend_rect, theme::BORDER_LIGHT, 1.);
+            for index in 0..self.legend.len() {
+                let row_y = origin.y + LEGEND_PADDING + index as f64 * row_height;
+                let swatch = Rect::from_origin_size(
+                    (
+                        origin.x + LEGEND_PADDING,
+                        row_y + (row_height - SWATCH_SIZE) / 2.,
+                    ),
+                    (SWATCH_SIZE, SWATCH_SIZE),
+                );
+                fill_color(scene, &swatch, self.series_color(index));
+                let label = &mut self.legend[index];
+                let label_y = row_y + (row_height - label.size().height) / 2.;
+                label.draw(
+                    scene,
+                    (origin.x + 2. * LEGEND_PADDING + SWATCH_SIZE, label_y),
+                );
+            }
+        }
+
+        // The tooltip of the hovered point.
+        if let Some((series, point)) = self.hovered {
+            let series = &self.series[series];
+            let point = series.points[point];
+            let text = format!(
+                "{}: {}, {}",
+                series.name,
+                format_value(point.x, self.x_step / 10.),
+                format_value(point.y, self.y_step / 10.)
+            );
+            self.tooltip.set_text(text.into());
+            self.tooltip.set_scale(ctx.scale_factor() as f32);
+            let (font_ctx, layout_ctx) = ctx.text_contexts();
+            self.tooltip.rebuild(font_ctx, layout_ctx);
+            let size = ctx.size();
+            let text_size = self.tooltip.size();
+            let box_size = Size::new(
+                text_size.width + 2. * TOOLTIP_PADDING,
+                text_size.height + 2. * TOOLTIP_PADDING,
+            );
+            let anchor = self.to_screen(range, point);
+            let origin = Point::new(
+                (anchor.x + TOOLTIP_OFFSET)
+                    .min(size.width - box_size.width)
+                    .max(0.),
+                (anchor.y - TOOLTIP_OFFSET - box_size.height).max(0.),
+            );
+            let tooltip_rect = Rect::from_origin_size(origin, box_size).to_rounded_rect(2.);
+            fill_color(scene, &tooltip_rect, theme::BACKGROUND_LIGHT);
+            stroke(scene, &tooltip_rect, theme::BORDER_LIGHT, 1.);
+            self.tooltip.draw(
+                scene,
+                (origin.x + TOOLTIP_PADDING, origin.y + TOOLTIP_PADDING),
+            );
+        }
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::Figure
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        let kind = match self.kind {
+            ChartKind::Line => "Line chart",
+            ChartKind::Bar => "Bar chart",
+            ChartKind::Scatter => "Scatter plot",
+        };
+        let series = self
+            .series
+            .iter()
+            .map(|series| format!("{} ({} points)", series.name, series.points.len()))
+            .collect::<Vec<_>>()
+            .join(", ");
+        ctx.current_node()
+            .set_description(format!("{kind} of {series}"));
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        SmallVec::new()
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("Chart")
+    }
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use vello::kurbo::Vec2;
+
+    use super::*;
+    use crate::testing::TestHarness;
+
+    fn ramp(count: usize) -> Vec<Point> {
+        (0..count)
+            .map(|index| Point::new(index as f64, index as f64))
+            .collect()
+    }
+
+    #[test]
+    fn nice_ticks() {
+        assert_eq!(
+            tick_values(0., 10., 5.),
+            (vec![0., 2., 4., 6., 8., 10.], 2.)
+        );
+        assert_eq!(tick_values(-0.3, 0.25, 5.).0, vec![-0.2, 0., 0.2]);
+        assert_eq!(tick_values(1., 1., 5.).0, Vec::<f64>::new());
+        assert_eq!(format_value(0.30000000000000004, 0.1), "0.3");
+        assert_eq!(format_value(1500., 500.), "1500");
+    }
+
+    #[test]
+    fn fits_data() {
+        let chart = Chart::new(ChartKind::Line).with_series(ChartSeries::new("a", ramp(11)));
+        let harness = TestHarness::create_with_size(chart, Size::new(400., 300.));
+        let root = harness.root_widget();
+        let chart = root.downcast::<Chart>().unwrap();
+        let range = chart.view_range();
+        assert_eq!((range.x0, range.x1), (0., 10.));
+        assert!(range.y0 < 0. && range.y1 > 10.);
+        assert_eq!(chart.x_ticks().first(), Some(&0.));
+        assert_eq!(chart.x_ticks().last(), Some(&10.));
+    }
+
+    #[test]
+    fn bars_start_at_zero() {
+        let chart = Chart::new(ChartKind::Bar).with_series(ChartSeries::new(
+            "a",
+            vec![Point::new(1., 5.), Point::new(2., 8.)],
+        ));
+        assert_eq!(chart.view_range().y0, 0.);
+        assert_eq!(chart.view_range().x0, 0.5);
+    }
+
+    #[test]
+    fn append_without_layout() {
+        let chart = Chart::new(ChartKind::Line).with_series(ChartSeries::new("a", ramp(11)));
+        let mut harness = TestHarness::create_with_size(chart, Size::new(400., 300.));
+
+        // Appending a point within the current ticks only needs a repaint.
+        harness.edit_root_widget(|mut root| {
+            let mut chart = root.downcast::<Chart>();
+            chart.append_points(0, &[Point::new(10.2, 5.)]);
+            assert!(!chart.ctx.widget_state.needs_layout);
+        });
+        let ticks = harness.root_widget().downcast::<Chart>().unwrap().x_ticks();
+        assert_eq!(ticks.last(), Some(&10.));
+
+        // Appending a point outside of the ticks changes them, which needs a layout.
+        harness.edit_root_widget(|mut root| {
+            let mut chart = root.downcast::<Chart>();
+            chart.append_points(0, &[Point::new(11., 20.)]);
+            assert!(chart.ctx.widget_state.needs_layout);
+        });
+        let root = harness.root_widget();
+        let chart = root.downcast::<Chart>().unwrap();
+        assert_eq!(chart.series()[0].points().len(), 13);
+        assert_eq!(chart.y_ticks().last(), Some(&20.));
+    }
+
+    #[test]
+    fn zoom_around_pointer() {
+        let mut chart = Chart::new(ChartKind::Line).with_series(ChartSeries::new("a", ramp(11)));
+        chart.plot_rect = Rect::new(50., 10., 390., 270.);
+        let target = chart.to_screen(chart.view_range(), Point::new(5., 5.));
+        chart.zoom(target, 2.);
+        let range = chart.view_range();
+        assert!((range.width() - 5.).abs() < 1e-9);
+        let moved = chart.to_screen(range, Point::new(5., 5.));
+        assert!((moved - target).hypot() < 1e-9);
+    }
+
+    #[test]
+    fn hover_and_wheel() {
+        let chart = Chart::new(ChartKind::Scatter).with_series(ChartSeries::new("a", ramp(11)));
+        let mut harness = TestHarness::create_with_size(chart, Size::new(400., 300.));
+
+        let root = harness.root_widget();
+        let chart = root.downcast::<Chart>().unwrap();
+        let target = chart.to_screen(chart.view_range(), Point::new(5., 5.));
+        let before = chart.view_range();
+        harness.mouse_move(target + Vec2::new(2., 2.));
+        let root = harness.root_widget();
+        let chart = root.downcast::<Chart>().unwrap();
+        assert_eq!(chart.hovered_point(), Some((0, 5)));
+
+        harness.mouse_wheel(Vec2::new(0., 1.));
+        let root = harness.root_widget();
+        let chart = root.downcast::<Chart>().unwrap();
+        let after = chart.view_range();
+        assert!(after.width() < before.width());
+        assert!(after.contains(Point::new(5., 5.)));
+
+        harness.edit_root_widget(|mut root| {
+            root.downcast::<Chart>().reset_view();
+        });
+        let root = harness.root_widget();
+        let chart = root.downcast::<Chart>().unwrap();
+        assert_eq!(chart.view_range(), before);
+    }
+}
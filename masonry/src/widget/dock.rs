@@ -0,0 +1,1247 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A docking area, which arranges panels in nested splits and stacks of tabs.
+
+use accesskit::Role;
+use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
+use tracing::{trace, trace_span, Span};
+use vello::kurbo::Line;
+use vello::Scene;
+
+use crate::action::Action;
+use crate::dpi::LogicalPosition;
+use crate::event::PointerButton;
+use crate::paint_scene_helpers::{fill_color, stroke};
+use crate::widget::split::BarDrag;
+use crate::widget::{Axis, Label, WidgetMut};
+use crate::{
+    theme, AccessCtx, AccessEvent, ArcStr, BoxConstraints, CursorIcon, EventCtx, LayoutCtx,
+    LifeCycle, LifeCycleCtx, PaintCtx, Point, PointerEvent, Rect, Size, StatusChange, TextEvent,
+    Widget, WidgetId, WidgetPod,
+};
+
+const TAB_HEIGHT: f64 = theme::BORDERED_WIDGET_HEIGHT;
+const TAB_PADDING: f64 = 8.;
+const SELECTED_INDICATOR_WIDTH: f64 = 2.;
+const BAR_SIZE: f64 = 6.;
+/// The smallest size either side of a split is given, if there is enough space.
+const MIN_PANE_SIZE: f64 = 40.;
+/// How far the pointer has to move before pressing a tab turns into dragging it.
+const DRAG_THRESHOLD: f64 = 4.;
+/// The fraction of a panel's content area at each edge which drops a panel beside it.
+const EDGE_DROP_FRACTION: f64 = 0.25;
+
+/// The arrangement of the panels of a [`Dock`], which are identified by their id.
+///
+/// Layouts can be serialized, so that they can be saved and restored.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum DockLayout {
+    /// Panels stacked on top of each other, with a tab for each of them.
+    Stack {
+        /// The ids of the panels, in the order of their tabs.
+        panels: Vec<String>,
+        /// The index of the panel which is shown.
+        selected: usize,
+    },
+    /// An area split in two, with a bar between them which can be dragged.
+    Split {
+        /// The axis which is split in two.
+        axis: Axis,
+        /// The position of the bar, as a fraction of the split axis.
+        split_point: f64,
+        first: Box<DockLayout>,
+        second: Box<DockLayout>,
+    },
+}
+
+/// Where a panel is inserted relative to the stack of another panel, see [`DockLayout::insert_panel`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DockPlacement {
+    /// Into the stack, as the tab at the given index.
+    Tab(usize),
+    /// Into a new stack to the left of the stack.
+    Left,
+    /// Into a new stack to the right of the stack.
+    Right,
+    /// Into a new stack above the stack.
+    Top,
+    /// Into a new stack below the stack.
+    Bottom,
+}
+
+// --- MARK: DOCK LAYOUT ---
+impl DockLayout {
+    /// A stack of the given panels, with the first one shown.
+    pub fn stack(panels: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        DockLayout::Stack {
+            panels: panels.into_iter().map(Into::into).collect(),
+            selected: 0,
+        }
+    }
+
+    /// An area split along `axis` at `split_point`, with `first` on the left or top.
+    pub fn split(axis: Axis, split_point: f64, first: DockLayout, second: DockLayout) -> Self {
+        DockLayout::Split {
+            axis,
+            split_point,
+            first: Box::new(first),
+            second: Box::new(second),
+        }
+    }
+
+    /// The ids of all panels, from the top left to the bottom right.
+    pub fn panels(&self) -> Vec<&str> {
+        let mut panels = Vec::new();
+        self.visit_stacks(&mut |ids, _| panels.extend(ids.iter().map(String::as_str)));
+        panels
+    }
+
+    /// Whether the panel `id` is part of this layout.
+    pub fn contains(&self, id: &str) -> bool {
+        self.panels().contains(&id)
+    }
+
+    /// The ids of the panels which are shown, one for each stack.
+    pub fn selected_panels(&self) -> Vec<&str> {
+        let mut panels = Vec::new();
+        self.visit_stacks(&mut |ids, selected| {
+            if let Some(id) = ids.get(selected) {
+                panels.push(id.as_str());
+            }
+        });
+        panels
+    }
+
+    /// Show the panel `id` in its stack.
+    ///
+    /// Returns `false` if the panel isn't part of this layout.
+    pub fn select_panel(&mut self, id: &str) -> bool {
+        match self.stack_of_mut(id) {
+            Some(DockLayout::Stack { panels, selected }) => {
+                *selected = panels.iter().position(|panel| panel == id).unwrap();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Remove the panel `id`, removing its stack if it was the only panel in it.
+    ///
+    /// Returns `false` if the panel isn't part of this layout.
+    pub fn remove_panel(&mut self, id: &str) -> bool {
+        let mut removed = false;
+        self.retain_panels(&mut |panel| {
+            let keep = panel != id;
+            removed |= !keep;
+            keep
+        });
+        removed
+    }
+
+    /// Insert the panel `id` relative to the stack of the panel `target`.
+    ///
+    /// Returns `false` if `target` isn't part of this layout.
+    pub fn insert_panel(
+        &mut self,
+        id: impl Into<String>,
+        target: &str,
+        placement: DockPlacement,
+    ) -> bool {
+        let Some(stack) = self.stack_of_mut(target) else {
+            return false;
+        };
+        let id = id.into();
+        let (axis, new_first) = match placement {
+            DockPlacement::Tab(index) => {
+                if let DockLayout::Stack { panels, selected } = stack {
+                    let index = index.min(panels.len());
+                    panels.insert(index, id);
+                    *selected = index;
+                }
+                return true;
+            }
+            DockPlacement::Left => (Axis::Horizontal, true),
+            DockPlacement::Right => (Axis::Horizontal, false),
+            DockPlacement::Top => (Axis::Vertical, true),
+            DockPlacement::Bottom => (Axis::Vertical, false),
+        };
+        let old = std::mem::replace(stack, DockLayout::stack(Vec::<String>::new()));
+        let new = DockLayout::stack([id]);
+        *stack = if new_first {
+            DockLayout::split(axis, 0.5, new, old)
+        } else {
+            DockLayout::split(axis, 0.5, old, new)
+        };
+        true
+    }
+
+    fn visit_stacks<'a>(&'a self, f: &mut impl FnMut(&'a [String], usize)) {
+        match self {
+            DockLayout::Stack { panels, selected } => f(panels, *selected),
+            DockLayout::Split { first, second, .. } => {
+                first.visit_stacks(f);
+                second.visit_stacks(f);
+            }
+        }
+    }
+
+    /// The stack which contains the panel `id`.
+    fn stack_of_mut(&mut self, id: &str) -> Option<&mut DockLayout> {
+        match self {
+            DockLayout::Stack { panels, .. } => {
+                if panels.iter().any(|panel| panel == id) {
+                    Some(self)
+                } else {
+                    None
+                }
+            }
+            DockLayout::Split { first, second, .. } => first
+                .stack_of_mut(id)
+                .or_else(move || second.stack_of_mut(id)),
+        }
+    }
+
+    /// The first stack, from the top left.
+    fn first_stack_mut(&mut self) -> &mut DockLayout {
+        match self {
+            DockLayout::Stack { .. } => self,
+            DockLayout::Split { first, .. } => first.first_stack_mut(),
+        }
+    }
+
+    /// The split point of the `index`th split, in depth-first order.
+    fn split_point_mut(&mut self, index: usize) -> Option<&mut f64> {
+        match self {
+            DockLayout::Stack { .. } => None,
+            DockLayout::Split {
+                split_point,
+                first,
+                second,
+                ..
+            } => {
+                let Some(index) = index.checked_sub(1) else {
+                    return Some(split_point);
+                };
+                let first_splits = first.split_count();
+                if index < first_splits {
+                    first.split_point_mut(index)
+                } else {
+                    second.split_point_mut(index - first_splits)
+                }
+            }
+        }
+    }
+
+    /// The number of splits in this layout.
+    fn split_count(&self) -> usize {
+        match self {
+            DockLayout::Stack { .. } => 0,
+            DockLayout::Split { first, second, .. } => {
+                1 + first.split_count() + second.split_count()
+            }
+        }
+    }
+
+    fn is_empty_stack(&self) -> bool {
+        matches!(self, DockLayout::Stack { panels, .. } if panels.is_empty())
+    }
+
+    /// Remove the panels for which `keep` returns false, then remove the stacks which are empty.
+    fn retain_panels(&mut self, keep: &mut impl FnMut(&str) -> bool) {
+        match self {
+            DockLayout::Stack { panels, selected } => {
+                let shown = panels.get(*selected).cloned();
+                panels.retain(|panel| keep(panel));
+                *selected = shown
+                    .and_then(|shown| panels.iter().position(|panel| *panel == shown))
+                    .unwrap_or((*selected).min(panels.len().saturating_sub(1)));
+            }
+            DockLayout::Split { first, second, .. } => {
+                first.retain_panels(keep);
+                second.retain_panels(keep);
+                if first.is_empty_stack() {
+                    *self = std::mem::replace(second, DockLayout::stack(Vec::<String>::new()));
+                } else if second.is_empty_stack() {
+                    *self = std::mem::replace(first, DockLayout::stack(Vec::<String>::new()));
+                }
+            }
+        }
+    }
+
+    /// Make this layout contain exactly the panels `ids`, once each.
+    ///
+    /// Panels which aren't part of the layout are added to its first stack.
+    fn normalize(&mut self, ids: &[String]) {
+        let mut seen = Vec::new();
+        self.retain_panels(&mut |panel| {
+            let keep = ids.iter().any(|id| id == panel) && !seen.contains(&panel.to_string());
+            if keep {
+                seen.push(panel.to_string());
+            }
+            keep
+        });
+        if let DockLayout::Stack { panels, .. } = self.first_stack_mut() {
+            panels.extend(ids.iter().filter(|id| !seen.contains(id)).cloned());
+        }
+        self.clamp();
+    }
+
+    fn clamp(&mut self) {
+        match self {
+            DockLayout::Stack { panels, selected } => {
+                *selected = (*selected).min(panels.len().saturating_sub(1));
+            }
+            DockLayout::Split {
+                split_point,
+                first,
+                second,
+                ..
+            } => {
+                *split_point = split_point.clamp(0.0, 1.0);
+                first.clamp();
+                second.clamp();
+            }
+        }
+    }
+}
+
+// --- MARK: DOCK ---
+/// A docking area, which arranges panels in nested horizontal and vertical splits,
+/// and stacks of tabs.
+///
+/// Each panel has an id and a title. How the panels are arranged is described by a
+/// [`DockLayout`]; panels which aren't part of the layout are added to its first stack,
+/// and ids which don't belong to a panel are removed from it. When this changes the layout,
+/// the dock emits an [`Action::DockLayoutChanged`].
+///
+/// The user can change the layout by dragging the bars between splits, by clicking
+/// on the tab of a panel to show it, and by dragging a tab to another place: onto the
+/// tabs of a stack to add it to that stack, or onto the edge of a panel to split it.
+/// The dock changes its own layout, and emits an [`Action::DockLayoutChanged`] with
+/// the new layout.
+pub struct Dock {
+    layout: DockLayout,
+    panels: Vec<DockPanel>,
+    drop_indicator: WidgetPod<DropIndicator>,
+    /// The stacks of the layout, as of the last layout pass.
+    stacks: Vec<StackRegion>,
+    /// The bars of the splits of the layout, in depth-first order, as of the last layout pass.
+    bars: Vec<BarRegion>,
+    /// The bar which is hovered or dragged, by index in `bars`.
+    bar: Option<(usize, BarDrag)>,
+    press: Option<TabPress>,
+    drop_target: Option<DropTarget>,
+}
+
+struct DockPanel {
+    id: String,
+    title: WidgetPod<Label>,
+    content: WidgetPod<Box<dyn Widget>>,
+}
+
+/// Highlights where a dragged tab will be dropped.
+struct DropIndicator;
+
+struct StackRegion {
+    /// The index in `Dock::panels` of each panel of the stack, in tab order.
+    panels: Vec<usize>,
+    /// The rect of each tab.
+    tabs: Vec<Rect>,
+    /// The index of the tab of the panel which is shown.
+    selected: Option<usize>,
+    strip: Rect,
+    content: Rect,
+}
+
+struct BarRegion {
+    axis: Axis,
+    /// The rect of the bar itself.
+    bar: Rect,
+    /// The rect of the whole split.
+    area: Rect,
+}
+
+/// A tab which was pressed.
+struct TabPress {
+    panel: usize,
+    start: Point,
+    dragging: bool,
+}
+
+struct DropTarget {
+    /// A panel in the stack the dragged panel is dropped on, other than the dragged one.
+    anchor: String,
+    placement: DockPlacement,
+    indicator: Rect,
+}
+
+// --- MARK: BUILDERS ---
+impl Dock {
+    /// Create a new dock, with panels arranged by `layout`.
+    pub fn new(layout: DockLayout) -> Self {
+        Dock {
+            layout,
+            panels: Vec::new(),
+            drop_indicator: WidgetPod::new(DropIndicator),
+            stacks: Vec::new(),
+            bars: Vec::new(),
+            bar: None,
+            press: None,
+            drop_target: None,
+        }
+    }
+
+    /// Builder-style method to add a panel with the given id and title.
+    pub fn with_panel(
+        self,
+        id: impl Into<String>,
+        title: impl Into<ArcStr>,
+        content: impl Widget,
+    ) -> Self {
+        self.with_panel_pod(id, title, WidgetPod::new(Box::new(content)))
+    }
+
+    /// Builder-style method to add a panel with the given id and title, from a widget pod.
+    pub fn with_panel_pod(
+        mut self,
+        id: impl Into<String>,
+        title: impl Into<ArcStr>,
+        content: WidgetPod<Box<dyn Widget>>,
+    ) -> Self {
+        self.panels.push(DockPanel {
+            id: id.into(),
+            title: WidgetPod::new(Label::new(title)),
+            content,
+        });
+        self
+    }
+
+    /// The current arrangement of the panels.
+    ///
+    /// Panels which aren't part of the layout are added to it once the dock is added to the
+    /// widget tree or has been mutated, and ids which don't belong to a panel are removed
+    /// from it.
+    pub fn get_layout(&self) -> &DockLayout {
+        &self.layout
+    }
+
+    /// The number of panels.
+    pub fn panel_count(&self) -> usize {
+        self.panels.len()
+    }
+
+    /// The index of the panel with the given id.
+    pub fn panel_index(&self, id: &str) -> Option<usize> {
+        self.panels.iter().position(|panel| panel.id == id)
+    }
+}
+
+// --- MARK: WIDGETMUT ---
+impl WidgetMut<'_, Dock> {
+    /// Set the arrangement of the panels.
+    pub fn set_layout(&mut self, layout: DockLayout) {
+        self.widget.layout = layout;
+        self.normalize_layout_later();
+        self.ctx.request_layout();
+    }
+
+    /// Add a panel with the given id and title after the existing panels.
+    pub fn add_panel(
+        &mut self,
+        id: impl Into<String>,
+        title: impl Into<ArcStr>,
+        content: impl Widget,
+    ) {
+        let index = self.widget.panels.len();
+        self.insert_panel_pod(index, id, title, WidgetPod::new(Box::new(content)));
+    }
+
+    /// Insert a panel with the given id and title at `index`, from a widget pod.
+    ///
+    /// If the panel isn't part of the layout, it is added to its first stack.
+    pub fn insert_panel_pod(
+        &mut self,
+        index: usize,
+        id: impl Into<String>,
+        title: impl Into<ArcStr>,
+        content: WidgetPod<Box<dyn Widget>>,
+    ) {
+        self.widget.panels.insert(
+            index,
+            DockPanel {
+                id: id.into(),
+                title: WidgetPod::new(Label::new(title)),
+                content,
+            },
+        );
+        self.normalize_layout_later();
+        self.ctx.children_changed();
+    }
+
+    /// Remove the panel at `index`.
+    ///
+    /// The panel is removed from the layout once the current mutations are done, unless
+    /// a panel with the same id is added before then.
+    pub fn remove_panel(&mut self, index: usize) {
+        let panel = self.widget.panels.remove(index);
+        self.ctx.remove_child(panel.title);
+        self.ctx.remove_child(panel.content);
+        self.widget.press = None;
+        self.widget.drop_target = None;
+        self.normalize_layout_later();
+        self.ctx.request_layout();
+    }
+
+    /// Change the id of the panel at `index`, keeping its place in the layout.
+    pub fn set_panel_id(&mut self, index: usize, id: impl Into<String>) {
+        let id = id.into();
+        let old = std::mem::replace(&mut self.widget.panels[index].id, id.clone());
+        rename_panel(&mut self.widget.layout, &old, &id);
+        self.normalize_layout_later();
+        self.ctx.request_layout();
+    }
+
+    /// Set the title of the panel at `index`.
+    pub fn set_title(&mut self, index: usize, title: impl Into<ArcStr>) {
+        let panel = &mut self.widget.panels[index];
+        if panel.title.created_widget_mut().is_some() {
+            // The title hasn't been added to the widget tree yet, so it can be replaced.
+            panel.title = WidgetPod::new(Label::new(title));
+            return;
+        }
+        self.ctx.get_mut(&mut panel.title).set_text(title);
+    }
+
+    /// The content of the panel at `index`.
+    pub fn panel_mut(&mut self, index: usize) -> WidgetMut<'_, Box<dyn Widget>> {
+        self.ctx.get_mut(&mut self.widget.panels[index].content)
+    }
+
+    /// Normalize the layout after the current mutations, as panels can be removed and added
+    /// back with the same id in the meantime.
+    fn normalize_layout_later(&mut self) {
+        self.ctx.mutate_self_later(|mut dock| {
+            let mut dock = dock.downcast::<Dock>();
+            if dock.widget.normalize_layout() {
+                dock.ctx
+                    .submit_action(Action::DockLayoutChanged(dock.widget.layout.clone()));
+            }
+        });
+    }
+}
+
+fn rename_panel(layout: &mut DockLayout, old: &str, new: &str) {
+    match layout {
+        DockLayout::Stack { panels, .. } => {
+            for panel in panels.iter_mut().filter(|panel| *panel == old) {
+                *panel = new.to_string();
+            }
+        }
+        DockLayout::Split { first, second, .. } => {
+            rename_panel(first, old, new);
+            rename_panel(second, old, new);
+        }
+    }
+}
+
+// --- MARK: INTERNALS ---
+impl Dock {
+    /// Make the layout match the panels, and return whether that changed it.
+    fn normalize_layout(&mut self) -> bool {
+        let ids: Vec<String> = self.panels.iter().map(|panel| panel.id.clone()).collect();
+        let prev = self.layout.clone();
+        self.layout.normalize(&ids);
+        self.layout != prev
+    }
+
+    /// Change the layout in response to user input.
+    fn change_layout(&mut self, ctx: &mut EventCtx, layout: DockLayout) {
+        if layout == self.layout {
+            return;
+        }
+        trace!("Dock {:?} changed its layout", ctx.widget_id());
+        self.layout = layout;
+        ctx.submit_action(Action::DockLayoutChanged(self.layout.clone()));
+        ctx.request_layout();
+    }
+
+    /// The panel whose tab is at `pos`.
+    fn tab_at(&self, pos: Point) -> Option<usize> {
+        self.stacks.iter().find_map(|stack| {
+            if !stack.strip.contains(pos) {
+                return None;
+            }
+            let tab = stack.tabs.iter().position(|tab| tab.contains(pos))?;
+            Some(stack.panels[tab])
+        })
+    }
+
+    fn bar_at(&self, pos: Point) -> Option<usize> {
+        self.bars.iter().position(|bar| bar.bar.contains(pos))
+    }
+
+    /// Where the panel at `dragged` would be dropped if it was released at `pos`.
+    fn drop_target_at(&self, dragged: usize, pos: Point) -> Option<DropTarget> {
+        let stack = self
+            .stacks
+            .iter()
+            .find(|stack| stack.strip.contains(pos) || stack.content.contains(pos))?;
+        let anchor = stack.panels.iter().find(|panel| **panel != dragged)?;
+        let anchor = self.panels[*anchor].id.clone();
+        let tabs: Vec<Rect> = stack
+            .panels
+            .iter()
+            .zip(&stack.tabs)
+            .filter(|(panel, _)| **panel != dragged)
+            .map(|(_, tab)| *tab)
+            .collect();
+
+        if stack.strip.contains(pos) {
+            let index = tabs.iter().filter(|tab| tab.center().x < pos.x).count();
+            let x = match tabs.get(index) {
+                Some(tab) => tab.x0,
+                None => tabs.last().map_or(stack.strip.x0, |tab| tab.x1),
+            };
+            let indicator = Rect::new(
+                x - SELECTED_INDICATOR_WIDTH / 2.,
+                stack.strip.y0,
+                x + SELECTED_INDICATOR_WIDTH / 2.,
+                stack.strip.y1,
+            );
+            return Some(DropTarget {
+                anchor,
+                placement: DockPlacement::Tab(index),
+                indicator,
+            });
+        }
+
+        let content = stack.content;
+        let fx = (pos.x - content.x0) / content.width();
+        let fy = (pos.y - content.y0) / content.height();
+        let edges = [
+            (fx, DockPlacement::Left),
+            (1.0 - fx, DockPlacement::Right),
+            (fy, DockPlacement::Top),
+            (1.0 - fy, DockPlacement::Bottom),
+        ];
+        let (distance, placement) = edges
+            .into_iter()
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .unwrap();
+        let (placement, indicator) = if distance < EDGE_DROP_FRACTION {
+            let (mid_x, mid_y) = (content.center().x, content.center().y);
+            let indicator = match placement {
+                DockPlacement::Left => content.with_size((content.width() / 2., content.height())),
+                DockPlacement::Right => Rect::new(mid_x, content.y0, content.x1, content.y1),
+                DockPlacement::Top => content.with_size((content.width(), content.height() / 2.)),
+                _ => Rect::new(content.x0, mid_y, content.x1, content.y1),
+            };
+            (placement, indicator)
+        } else {
+            (DockPlacement::Tab(tabs.len()), content)
+        };
+        Some(DropTarget {
+            anchor,
+            placement,
+            indicator,
+        })
+    }
+
+    /// Lay out the node `layout` of the layout tree in `rect`.
+    fn layout_node(
+        layout: &DockLayout,
+        rect: Rect,
+        panels: &mut [DockPanel],
+        stacks: &mut Vec<StackRegion>,
+        bars: &mut Vec<BarRegion>,
+        ctx: &mut LayoutCtx,
+    ) {
+        match layout {
+            DockLayout::Stack {
+                panels: ids,
+                selected,
+            } => {
+                let strip = rect.with_size((rect.width(), TAB_HEIGHT.min(rect.height())));
+                let content = Rect::new(rect.x0, strip.y1, rect.x1, rect.y1);
+                let mut region = StackRegion {
+                    panels: Vec::new(),
+                    tabs: Vec::new(),
+                    selected: None,
+                    strip,
+                    content,
+                };
+                let mut x = strip.x0;
+                for (tab_index, id) in ids.iter().enumerate() {
+                    let Some(index) = panels.iter().position(|panel| panel.id == *id) else {
+                        continue;
+                    };
+                    let panel = &mut panels[index];
+                    let label_bc =
+                        BoxConstraints::new(Size::ZERO, Size::new(f64::INFINITY, TAB_HEIGHT));
+                    let label_size = panel.title.layout(ctx, &label_bc);
+                    let tab = Rect::new(
+                        x,
+                        strip.y0,
+                        x + label_size.width + 2. * TAB_PADDING,
+                        strip.y1,
+                    );
+                    // Tabs which don't fit in the strip are hidden.
+                    ctx.set_stashed(&mut panel.title, tab.x1 > strip.x1);
+                    ctx.place_child(
+                        &mut panel.title,
+                        Point::new(
+                            tab.x0 + TAB_PADDING,
+                            tab.y0 + (tab.height() - label_size.height) / 2.,
+                        ),
+                    );
+                    x = tab.x1;
+
+                    let shown = tab_index == *selected;
+                    ctx.set_stashed(&mut panel.content, !shown);
+                    if shown {
+                        region.selected = Some(region.tabs.len());
+                        let content_bc = BoxConstraints::tight(content.size());
+                        panel.content.layout(ctx, &content_bc);
+                        ctx.place_child(&mut panel.content, content.origin());
+                    } else {
+                        ctx.skip_layout(&mut panel.content);
+                    }
+                    region.panels.push(index);
+                    region.tabs.push(tab);
+                }
+                stacks.push(region);
+            }
+            DockLayout::Split {
+                axis,
+                split_point,
+                first,
+                second,
+            } => {
+                let major = axis.major(rect.size());
+                let available = (major - BAR_SIZE).max(0.0);
+                let min_pane = MIN_PANE_SIZE.min(available / 2.);
+                let first_size = (split_point * available).clamp(min_pane, available - min_pane);
+                let (first_rect, bar, second_rect) = match axis {
+                    Axis::Horizontal => {
+                        let bar_x = rect.x0 + first_size;
+                        (
+                            Rect::new(rect.x0, rect.y0, bar_x, rect.y1),
+                            Rect::new(bar_x, rect.y0, bar_x + BAR_SIZE, rect.y1),
+                            Rect::new(bar_x + BAR_SIZE, rect.y0, rect.x1, rect.y1),
+                        )
+                    }
+                    Axis::Vertical => {
+                        let bar_y = rect.y0 + first_size;
+                        (
+                            Rect::new(rect.x0, rect.y0, rect.x1, bar_y),
+                            Rect::new(rect.x0, bar_y, rect.x1, bar_y + BAR_SIZE),
+                            Rect::new(rect.x0, bar_y + BAR_SIZE, rect.x1, rect.y1),
+                        )
+                    }
+                };
+                bars.push(BarRegion {
+                    axis: *axis,
+                    bar,
+                    area: rect,
+                });
+                Self::layout_node(first, first_rect, panels, stacks, bars, ctx);
+                Self::layout_node(second, second_rect, panels, stacks, bars, ctx);
+            }
+        }
+    }
+}
+
+// --- MARK: IMPL WIDGET ---
+impl Widget for Dock {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        match event {
+            PointerEvent::PointerDown(PointerButton::Primary, state) => {
+                if ctx.is_disabled() {
+                    return;
+                }
+                let pos = ctx.to_local(Point::new(state.position.x, state.position.y));
+                if let Some(index) = self.bar_at(pos) {
+                    ctx.set_handled();
+                    let region = &self.bars[index];
+                    let bar_position = region.axis.major_pos(region.bar.origin());
+                    let mut bar_drag = match self.bar.take() {
+                        Some((hovered, bar_drag)) if hovered == index => bar_drag,
+                        _ => BarDrag::new(region.axis),
+                    };
+                    bar_drag.start(ctx, LogicalPosition::new(pos.x, pos.y), bar_position);
+                    self.bar = Some((index, bar_drag));
+                } else if let Some(panel) = self.tab_at(pos) {
+                    ctx.set_handled();
+                    ctx.set_active(true);
+                    let mut layout = self.layout.clone();
+                    layout.select_panel(&self.panels[panel].id);
+                    self.change_layout(ctx, layout);
+                    self.press = Some(TabPress {
+                        panel,
+                        start: pos,
+                        dragging: false,
+                    });
+                }
+            }
+            PointerEvent::PointerMove(state) => {
+                let pos = ctx.to_local(Point::new(state.position.x, state.position.y));
+                let mouse_pos = LogicalPosition::new(pos.x, pos.y);
+                if let Some(press) = &mut self.press {
+                    if !press.dragging && (pos - press.start).hypot() > DRAG_THRESHOLD {
+                        press.dragging = true;
+                        ctx.set_cursor(&CursorIcon::Grabbing);
+                    }
+                    if press.dragging {
+                        let panel = press.panel;
+                        self.drop_target = self.drop_target_at(panel, pos);
+                        ctx.request_layout();
+                    }
+                    return;
+                }
+
+                let hovered = self.bar_at(pos);
+                if let Some((index, bar_drag)) = &mut self.bar {
+                    let index = *index;
+                    if let Some(bar_position) =
+                        bar_drag.drag(ctx, mouse_pos, hovered == Some(index))
+                    {
+                        let region = &self.bars[index];
+                        let available = (region.axis.major(region.area.size()) - BAR_SIZE).max(1.0);
+                        let offset = bar_position - region.axis.major_pos(region.area.origin());
+                        if let Some(split_point) = self.layout.split_point_mut(index) {
+                            *split_point = (offset / available).clamp(0.0, 1.0);
+                        }
+                        ctx.request_layout();
+                        return;
+                    }
+                    if hovered == Some(index) {
+                        return;
+                    }
+                    self.bar = None;
+                }
+                if let Some(index) = hovered {
+                    let mut bar_drag = BarDrag::new(self.bars[index].axis);
+                    bar_drag.drag(ctx, mouse_pos, true);
+                    self.bar = Some((index, bar_drag));
+                }
+            }
+            PointerEvent::PointerUp(PointerButton::Primary, state) => {
+                let pos = ctx.to_local(Point::new(state.position.x, state.position.y));
+                if let Some(press) = self.press.take() {
+                    ctx.set_handled();
+                    ctx.set_active(false);
+                    if !press.dragging {
+                        return;
+                    }
+                    ctx.clear_cursor();
+                    ctx.request_layout();
+                    let Some(target) = self.drop_target.take() else {
+                        return;
+                    };
+                    let id = self.panels[press.panel].id.clone();
+                    let mut layout = self.layout.clone();
+                    layout.remove_panel(&id);
+                    layout.insert_panel(id, &target.anchor, target.placement);
+                    self.change_layout(ctx, layout);
+                    return;
+                }
+                let hovered = self.bar_at(pos);
+                if let Some((index, bar_drag)) = &mut self.bar {
+                    let hit = hovered == Some(*index);
+                    if bar_drag.end(ctx, hit) {
+                        ctx.set_handled();
+                        ctx.submit_action(Action::DockLayoutChanged(self.layout.clone()));
+                    }
+                    if !hit {
+                        self.bar = None;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn on_text_event(&mut self, _ctx: &mut EventCtx, _event: &TextEvent) {}
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        if !matches!(event.action, accesskit::Action::Default) {
+            return;
+        }
+        let panel = self
+            .panels
+            .iter()
+            .find(|panel| panel.title.id() == event.target);
+        if let Some(panel) = panel {
+            ctx.set_handled();
+            let mut layout = self.layout.clone();
+            layout.select_panel(&panel.id);
+            self.change_layout(ctx, layout);
+        }
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        if let LifeCycle::WidgetAdded = event {
+            if self.normalize_layout() {
+                ctx.submit_action(Action::DockLayoutChanged(self.layout.clone()));
+            }
+        }
+        for panel in &mut self.panels {
+            panel.title.lifecycle(ctx, event);
+            panel.content.lifecycle(ctx, event);
+        }
+        self.drop_indicator.lifecycle(ctx, event);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let size = bc.max();
+        self.stacks.clear();
+        self.bars.clear();
+        Self::layout_node(
+            &self.layout,
+            size.to_rect(),
+            &mut self.panels,
+            &mut self.stacks,
+            &mut self.bars,
+            ctx,
+        );
+
+        match &self.drop_target {
+            Some(target) => {
+                ctx.set_stashed(&mut self.drop_indicator, false);
+                let indicator_bc = BoxConstraints::tight(target.indicator.size());
+                self.drop_indicator.layout(ctx, &indicator_bc);
+                ctx.place_child(&mut self.drop_indicator, target.indicator.origin());
+            }
+            None => {
+                ctx.set_stashed(&mut self.drop_indicator, true);
+                ctx.skip_layout(&mut self.drop_indicator);
+            }
+        }
+
+        trace!("Computed layout: size={}", size);
+        size
+    }
+
+    fn paint(&mut self, _ctx: &mut PaintCtx, scene: &mut Scene) {
+        for stack in &self.stacks {
+            fill_color(scene, &stack.strip, theme::BACKGROUND_DARK);
+            let bottom = Line::new(
+                (stack.strip.x0, stack.strip.y1 - 0.5),
+                (stack.strip.x1, stack.strip.y1 - 0.5),
+            );
+            stroke(scene, &bottom, theme::BORDER_DARK, 1.0);
+            if let Some(tab) = stack.selected.map(|index| stack.tabs[index]) {
+                fill_color(scene, &tab, theme::BACKGROUND_LIGHT);
+                let indicator =
+                    Rect::new(tab.x0, tab.y1 - SELECTED_INDICATOR_WIDTH, tab.x1, tab.y1);
+                fill_color(scene, &indicator, theme::PRIMARY_LIGHT);
+            }
+        }
+        for (index, region) in self.bars.iter().enumerate() {
+            let color = if self.bar.as_ref().is_some_and(|(bar, _)| *bar == index) {
+                theme::BORDER_LIGHT
+            } else {
+                theme::BORDER_DARK
+            };
+            fill_color(scene, &region.bar, color);
+        }
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::GenericContainer
+    }
+
+    fn accessibility(&mut self, _ctx: &mut AccessCtx) {}
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        let mut ids: SmallVec<_> = self.panels.iter().map(|panel| panel.content.id()).collect();
+        ids.extend(self.panels.iter().map(|panel| panel.title.id()));
+        ids.push(self.drop_indicator.id());
+        ids
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("Dock")
+    }
+}
+
+impl Widget for DropIndicator {
+    fn on_pointer_event(&mut self, _ctx: &mut EventCtx, _event: &PointerEvent) {}
+
+    fn on_text_event(&mut self, _ctx: &mut EventCtx, _event: &TextEvent) {}
+
+    fn on_access_event(&mut self, _ctx: &mut EventCtx, _event: &AccessEvent) {}
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle) {}
+
+    fn layout(&mut self, _ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        bc.max()
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let rect = ctx.size().to_rect();
+        fill_color(scene, &rect, theme::PRIMARY_LIGHT.with_alpha_factor(0.3));
+        stroke(scene, &rect.inset(-1.0), theme::PRIMARY_LIGHT, 2.0);
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::GenericContainer
+    }
+
+    fn accessibility(&mut self, _ctx: &mut AccessCtx) {}
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        SmallVec::new()
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("DropIndicator")
+    }
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestHarness;
+
+    fn two_columns() -> DockLayout {
+        DockLayout::split(
+            Axis::Horizontal,
+            0.5,
+            DockLayout::stack(["a", "b"]),
+            DockLayout::stack(["c"]),
+        )
+    }
+
+    fn dock(layout: DockLayout) -> Dock {
+        Dock::new(layout)
+            .with_panel("a", "A", Label::new("Panel A"))
+            .with_panel("b", "B", Label::new("Panel B"))
+            .with_panel("c", "C", Label::new("Panel C"))
+    }
+
+    #[test]
+    fn layout_operations() {
+        let mut layout = two_columns();
+        assert_eq!(layout.panels(), ["a", "b", "c"]);
+        assert_eq!(layout.selected_panels(), ["a", "c"]);
+
+        assert!(layout.select_panel("b"));
+        assert_eq!(layout.selected_panels(), ["b", "c"]);
+
+        // Removing the only panel of a stack removes the stack.
+        assert!(layout.remove_panel("c"));
+        assert_eq!(
+            layout,
+            DockLayout::Stack {
+                panels: vec!["a".into(), "b".into()],
+                selected: 1,
+            }
+        );
+        assert!(!layout.remove_panel("c"));
+
+        assert!(layout.insert_panel("c", "a", DockPlacement::Bottom));
+        assert_eq!(
+            layout,
+            DockLayout::split(
+                Axis::Vertical,
+                0.5,
+                DockLayout::Stack {
+                    panels: vec!["a".into(), "b".into()],
+                    selected: 1,
+                },
+                DockLayout::stack(["c"]),
+            )
+        );
+        assert!(layout.insert_panel("d", "c", DockPlacement::Tab(0)));
+        assert_eq!(layout.panels(), ["a", "b", "d", "c"]);
+        assert_eq!(layout.selected_panels(), ["b", "d"]);
+        assert!(!layout.insert_panel("e", "f", DockPlacement::Left));
+    }
+
+    #[test]
+    fn split_points() {
+        let mut layout = DockLayout::split(
+            Axis::Horizontal,
+            0.5,
+            DockLayout::split(
+                Axis::Vertical,
+                0.5,
+                DockLayout::stack(["a"]),
+                DockLayout::stack(["b"]),
+            ),
+            DockLayout::split(
+                Axis::Vertical,
+                0.5,
+                DockLayout::stack(["c"]),
+                DockLayout::stack(["d"]),
+            ),
+        );
+        for (index, split_point) in [0.1, 0.2, 0.3].into_iter().enumerate() {
+            *layout.split_point_mut(index).unwrap() = split_point;
+        }
+        assert_eq!(layout.split_point_mut(3), None);
+        let DockLayout::Split {
+            split_point,
+            first,
+            second,
+            ..
+        } = layout
+        else {
+            unreachable!()
+        };
+        assert_eq!(split_point, 0.1);
+        assert!(matches!(*first, DockLayout::Split { split_point, .. } if split_point == 0.2));
+        assert!(matches!(*second, DockLayout::Split { split_point, .. } if split_point == 0.3));
+    }
+
+    #[test]
+    fn layout_serialization() {
+        let layout = two_columns();
+        let serialized = serde_json::to_string(&layout).unwrap();
+        let deserialized: DockLayout = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized, layout);
+    }
+
+    #[test]
+    fn missing_panels_are_added_to_first_stack() {
+        let mut harness = TestHarness::create_with_size(
+            dock(DockLayout::stack(["c", "unknown"])),
+            Size::new(400.0, 300.0),
+        );
+        let dock_id = harness.root_widget().id();
+
+        let expected = DockLayout::stack(["c", "a", "b"]);
+        let dock = harness.root_widget();
+        let dock = dock.downcast::<Dock>().unwrap();
+        assert_eq!(dock.get_layout(), &expected);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::DockLayoutChanged(expected), dock_id))
+        );
+    }
+
+    #[test]
+    fn removed_panels_are_removed_from_layout() {
+        let mut harness =
+            TestHarness::create_with_size(dock(two_columns()), Size::new(400.0, 300.0));
+        let dock_id = harness.root_widget().id();
+        assert_eq!(harness.pop_action(), None);
+
+        // A panel which is added back with the same id keeps its place.
+        harness.edit_root_widget(|mut dock| {
+            let mut dock = dock.downcast::<Dock>();
+            dock.remove_panel(2);
+            dock.add_panel("c", "C", Label::new("New panel C"));
+        });
+        assert_eq!(harness.pop_action(), None);
+
+        harness.edit_root_widget(|mut dock| {
+            dock.downcast::<Dock>().remove_panel(2);
+        });
+        let expected = DockLayout::Stack {
+            panels: vec!["a".into(), "b".into()],
+            selected: 0,
+        };
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::DockLayoutChanged(expected.clone()), dock_id))
+        );
+        let dock = harness.root_widget();
+        assert_eq!(dock.downcast::<Dock>().unwrap().get_layout(), &expected);
+    }
+
+    #[test]
+    fn drag_bar() {
+        let mut harness =
+            TestHarness::create_with_size(dock(two_columns()), Size::new(400.0, 300.0));
+        let dock_id = harness.root_widget().id();
+
+        // The bar starts at (400 - 6) / 2 = 197.
+        harness.mouse_move((200.0, 150.0));
+        harness.mouse_button_press(PointerButton::Primary);
+        harness.mouse_move((250.0, 150.0));
+        assert_eq!(harness.pop_action(), None);
+        harness.mouse_button_release(PointerButton::Primary);
+
+        let DockLayout::Split { first, second, .. } = two_columns() else {
+            unreachable!()
+        };
+        let expected = DockLayout::Split {
+            axis: Axis::Horizontal,
+            split_point: 247.0 / 394.0,
+            first,
+            second,
+        };
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::DockLayoutChanged(expected), dock_id))
+        );
+    }
+
+    #[test]
+    fn drag_tab_to_edge() {
+        let mut harness =
+            TestHarness::create_with_size(dock(two_columns()), Size::new(400.0, 300.0));
+        let dock_id = harness.root_widget().id();
+
+        // Drag the tab of "a" to the right edge of "c".
+        harness.mouse_move((10.0, 12.0));
+        harness.mouse_button_press(PointerButton::Primary);
+        harness.mouse_move((200.0, 100.0));
+        harness.mouse_move((390.0, 150.0));
+        harness.mouse_button_release(PointerButton::Primary);
+
+        let expected = DockLayout::split(
+            Axis::Horizontal,
+            0.5,
+            DockLayout::stack(["b"]),
+            DockLayout::split(
+                Axis::Horizontal,
+                0.5,
+                DockLayout::stack(["c"]),
+                DockLayout::stack(["a"]),
+            ),
+        );
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::DockLayoutChanged(expected.clone()), dock_id))
+        );
+        assert_eq!(harness.pop_action(), None);
+        let dock = harness.root_widget();
+        assert_eq!(dock.downcast::<Dock>().unwrap().get_layout(), &expected);
+    }
+
+    #[test]
+    fn drag_tab_into_stack() {
+        let mut harness =
+            TestHarness::create_with_size(dock(two_columns()), Size::new(400.0, 300.0));
+
+        // Drag the tab of "a" onto the middle of "c".
+        harness.mouse_move((10.0, 12.0));
+        harness.mouse_button_press(PointerButton::Primary);
+        harness.mouse_move((300.0, 150.0));
+        harness.mouse_button_release(PointerButton::Primary);
+
+        let dock = harness.root_widget();
+        assert_eq!(
+            dock.downcast::<Dock>().unwrap().get_layout(),
+            &DockLayout::split(
+                Axis::Horizontal,
+                0.5,
+                DockLayout::stack(["b"]),
+                DockLayout::Stack {
+                    panels: vec!["c".into(), "a".into()],
+                    selected: 1,
+                },
+            )
+        );
+    }
+}
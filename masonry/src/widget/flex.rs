@@ -4,6 +4,7 @@
 //! A widget that arranges its children in a one-dimensional array.
 
 use accesskit::Role;
+use serde::{Deserialize, Serialize};
 use smallvec::SmallVec;
 use tracing::{trace, trace_span, Span};
 use vello::kurbo::{common::FloatExt, Affine, Line, Stroke, Vec2};
@@ -47,7 +48,7 @@ pub struct FlexParams {
 /// Most often used by widgets to describe
 /// the direction in which they grow as their number of children increases.
 /// Has some methods for manipulating geometry with respect to the axis.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum Axis {
     /// The x axis
     Horizontal,
@@ -0,0 +1,140 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A widget that groups the focusable widgets of its child.
+
+use accesskit::Role;
+use smallvec::{smallvec, SmallVec};
+use tracing::{trace_span, Span};
+use vello::kurbo::Point;
+use vello::Scene;
+
+use crate::widget::{WidgetMut, WidgetPod};
+use crate::{
+    AccessCtx, AccessEvent, BoxConstraints, EventCtx, FocusScopeOptions, LayoutCtx, LifeCycle,
+    LifeCycleCtx, PaintCtx, PointerEvent, Size, StatusChange, TextEvent, Widget, WidgetId,
+};
+
+/// A widget that makes its child a focus scope.
+///
+/// The focusable widgets inside the scope are kept together in tab order.
+/// The scope can also trap focus, like a modal dialog, or let arrow keys move focus
+/// between its widgets, like in a grid. See [`FocusScopeOptions`] for details.
+pub struct FocusScope {
+    child: WidgetPod<Box<dyn Widget>>,
+    options: FocusScopeOptions,
+    tab_index: u32,
+}
+
+// --- MARK: BUILDERS ---
+impl FocusScope {
+    /// Create a focus scope around a widget.
+    pub fn new(child: impl Widget + 'static) -> Self {
+        Self::new_pod(WidgetPod::new(child).boxed())
+    }
+
+    /// Create a focus scope around a widget pod.
+    pub fn new_pod(child: WidgetPod<Box<dyn Widget>>) -> Self {
+        Self {
+            child,
+            options: FocusScopeOptions::default(),
+            tab_index: 0,
+        }
+    }
+
+    /// Builder-style method to trap focus inside the scope.
+    pub fn trap(mut self, trap: bool) -> Self {
+        self.options.trap = trap;
+        self
+    }
+
+    /// Builder-style method to move focus with arrow keys inside the scope.
+    pub fn directional(mut self, directional: bool) -> Self {
+        self.options.directional = directional;
+        self
+    }
+
+    /// Builder-style method to set the position of the scope in tab order.
+    ///
+    /// See [`EventCtx::set_tab_index`] for details.
+    pub fn tab_index(mut self, tab_index: u32) -> Self {
+        self.tab_index = tab_index;
+        self
+    }
+}
+
+// --- MARK: WIDGETMUT ---
+impl WidgetMut<'_, FocusScope> {
+    /// Set whether focus is trapped inside the scope.
+    pub fn set_trap(&mut self, trap: bool) {
+        self.widget.options.trap = trap;
+        self.ctx.set_focus_scope(Some(self.widget.options));
+    }
+
+    /// Set whether arrow keys move focus inside the scope.
+    pub fn set_directional(&mut self, directional: bool) {
+        self.widget.options.directional = directional;
+        self.ctx.set_focus_scope(Some(self.widget.options));
+    }
+
+    /// Set the position of the scope in tab order.
+    pub fn set_tab_index(&mut self, tab_index: u32) {
+        self.widget.tab_index = tab_index;
+        self.ctx.set_tab_index(tab_index);
+    }
+
+    pub fn child_mut(&mut self) -> WidgetMut<'_, Box<dyn Widget>> {
+        self.ctx.get_mut(&mut self.widget.child)
+    }
+}
+
+// --- MARK: IMPL WIDGET ---
+impl Widget for FocusScope {
+    fn on_pointer_event(&mut self, _ctx: &mut EventCtx, _event: &PointerEvent) {}
+
+    fn on_text_event(&mut self, _ctx: &mut EventCtx, _event: &TextEvent) {}
+
+    fn on_access_event(&mut self, _ctx: &mut EventCtx, _event: &AccessEvent) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        if let LifeCycle::WidgetAdded = event {
+            ctx.set_focus_scope(Some(self.options));
+            ctx.set_tab_index(self.tab_index);
+        }
+        self.child.lifecycle(ctx, event);
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let size = self.child.layout(ctx, bc);
+        ctx.place_child(&mut self.child, Point::ORIGIN);
+        let insets = ctx.compute_insets_from_child(&self.child, size);
+        ctx.set_paint_insets(insets);
+        size
+    }
+
+    fn paint(&mut self, _ctx: &mut PaintCtx, _scene: &mut Scene) {}
+
+    fn accessibility_role(&self) -> Role {
+        if self.options.trap {
+            Role::Dialog
+        } else {
+            Role::Group
+        }
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        if self.options.trap {
+            ctx.current_node().set_modal();
+        }
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        smallvec![self.child.id()]
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("FocusScope")
+    }
+}
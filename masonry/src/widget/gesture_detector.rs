@@ -0,0 +1,217 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A widget that recognizes pointer gestures on its child.
+
+use accesskit::Role;
+use smallvec::{smallvec, SmallVec};
+use tracing::{trace, trace_span, Span};
+use vello::kurbo::Point;
+use vello::Scene;
+
+use crate::gesture::{
+    ClickRecognizer, DragPhase, DragRecognizer, Gesture, LongPressRecognizer, PanZoomRecognizer,
+};
+use crate::widget::{WidgetMut, WidgetPod};
+use crate::{
+    AccessCtx, AccessEvent, Action, BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx,
+    PaintCtx, PointerEvent, Size, StatusChange, TextEvent, TimerToken, Widget, WidgetId,
+};
+
+/// A widget that recognizes pointer gestures on its child.
+///
+/// Only the gestures which were enabled are recognized. Each recognized gesture
+/// is emitted as an [`Action::Gesture`].
+///
+/// Once a drag starts, the current press no longer counts as a click or a long press,
+/// and a long press means the button release isn't a click.
+/// Descendants that capture the pointer, such as buttons, keep working as usual.
+pub struct GestureDetector {
+    child: WidgetPod<Box<dyn Widget>>,
+    click: Option<ClickRecognizer>,
+    long_press: Option<LongPressRecognizer>,
+    drag: Option<DragRecognizer>,
+    pan_zoom: Option<PanZoomRecognizer>,
+}
+
+// --- MARK: BUILDERS ---
+impl GestureDetector {
+    /// Create a gesture detector around a widget, with no gesture enabled.
+    pub fn new(child: impl Widget + 'static) -> Self {
+        Self::new_pod(WidgetPod::new(child).boxed())
+    }
+
+    /// Create a gesture detector around a widget pod, with no gesture enabled.
+    pub fn new_pod(child: WidgetPod<Box<dyn Widget>>) -> Self {
+        Self {
+            child,
+            click: None,
+            long_press: None,
+            drag: None,
+            pan_zoom: None,
+        }
+    }
+
+    /// Builder-style method to recognize clicks, including double-clicks.
+    pub fn clicks(mut self, enabled: bool) -> Self {
+        self.click = enabled.then(ClickRecognizer::new);
+        self
+    }
+
+    /// Builder-style method to recognize long presses.
+    pub fn long_press(mut self, enabled: bool) -> Self {
+        self.long_press = enabled.then(LongPressRecognizer::new);
+        self
+    }
+
+    /// Builder-style method to recognize drags.
+    pub fn drag(mut self, enabled: bool) -> Self {
+        self.drag = enabled.then(DragRecognizer::new);
+        self
+    }
+
+    /// Builder-style method to recognize two-finger pans and pinches.
+    pub fn pan_zoom(mut self, enabled: bool) -> Self {
+        self.pan_zoom = enabled.then(PanZoomRecognizer::new);
+        self
+    }
+}
+
+// --- MARK: WIDGETMUT ---
+impl WidgetMut<'_, GestureDetector> {
+    /// Set whether clicks are recognized.
+    pub fn set_clicks(&mut self, enabled: bool) {
+        if enabled != self.widget.click.is_some() {
+            self.widget.click = enabled.then(ClickRecognizer::new);
+        }
+    }
+
+    /// Set whether long presses are recognized.
+    pub fn set_long_press(&mut self, enabled: bool) {
+        if enabled != self.widget.long_press.is_some() {
+            self.widget.long_press = enabled.then(LongPressRecognizer::new);
+        }
+    }
+
+    /// Set whether drags are recognized.
+    pub fn set_drag(&mut self, enabled: bool) {
+        if enabled != self.widget.drag.is_some() {
+            self.widget.drag = enabled.then(DragRecognizer::new);
+        }
+    }
+
+    /// Set whether two-finger pans and pinches are recognized.
+    pub fn set_pan_zoom(&mut self, enabled: bool) {
+        if enabled != self.widget.pan_zoom.is_some() {
+            self.widget.pan_zoom = enabled.then(PanZoomRecognizer::new);
+        }
+    }
+
+    pub fn child_mut(&mut self) -> WidgetMut<'_, Box<dyn Widget>> {
+        self.ctx.get_mut(&mut self.widget.child)
+    }
+}
+
+// --- MARK: IMPL WIDGET ---
+impl Widget for GestureDetector {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        if let Some(pan_zoom) = &mut self.pan_zoom {
+            let was_pinching = pan_zoom.is_pinching();
+            if let Some(pan_zoom) = pan_zoom.on_pointer_event(ctx, event) {
+                ctx.submit_action(Action::Gesture(Gesture::PanZoom(pan_zoom)));
+                ctx.set_handled();
+            }
+            if self.pan_zoom.as_ref().is_some_and(|p| p.is_pinching()) {
+                if !was_pinching {
+                    // A second finger turns the touch into a pinch.
+                    if let Some(click) = &mut self.click {
+                        click.cancel();
+                    }
+                    if let Some(long_press) = &mut self.long_press {
+                        long_press.cancel();
+                    }
+                    if let Some(drag) = self.drag.as_mut().and_then(|drag| drag.finish(ctx)) {
+                        ctx.submit_action(Action::Gesture(Gesture::Drag(drag)));
+                    }
+                }
+                // Fingers don't start other gestures while pinching.
+                return;
+            }
+        }
+        if let Some(drag) = &mut self.drag {
+            if let Some(drag) = drag.on_pointer_event(ctx, event) {
+                if drag.phase == DragPhase::Start {
+                    if let Some(click) = &mut self.click {
+                        click.cancel();
+                    }
+                    if let Some(long_press) = &mut self.long_press {
+                        long_press.cancel();
+                    }
+                }
+                trace!("GestureDetector {:?} drag {:?}", ctx.widget_id(), drag);
+                ctx.submit_action(Action::Gesture(Gesture::Drag(drag)));
+            }
+        }
+        if let Some(long_press) = &mut self.long_press {
+            long_press.on_pointer_event(ctx, event);
+        }
+        if let Some(click) = &mut self.click {
+            if let Some(click) = click.on_pointer_event(ctx, event) {
+                trace!("GestureDetector {:?} click {:?}", ctx.widget_id(), click);
+                ctx.submit_action(Action::Gesture(Gesture::Click(click)));
+            }
+        }
+    }
+
+    fn on_text_event(&mut self, _ctx: &mut EventCtx, _event: &TextEvent) {}
+
+    fn on_access_event(&mut self, _ctx: &mut EventCtx, _event: &AccessEvent) {}
+
+    fn on_timer(&mut self, ctx: &mut EventCtx, token: TimerToken) {
+        let Some(long_press) = &mut self.long_press else {
+            return;
+        };
+        if let Some(long_press) = long_press.on_timer(ctx, token) {
+            if let Some(click) = &mut self.click {
+                click.cancel();
+            }
+            trace!("GestureDetector {:?} long press", ctx.widget_id());
+            ctx.submit_action(Action::Gesture(Gesture::LongPress(long_press)));
+        }
+    }
+
+    fn accepts_multi_pointer(&self) -> bool {
+        // Pinching on touch screens needs the second finger.
+        self.pan_zoom.is_some()
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        self.child.lifecycle(ctx, event);
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let size = self.child.layout(ctx, bc);
+        ctx.place_child(&mut self.child, Point::ORIGIN);
+        let insets = ctx.compute_insets_from_child(&self.child, size);
+        ctx.set_paint_insets(insets);
+        size
+    }
+
+    fn paint(&mut self, _ctx: &mut PaintCtx, _scene: &mut Scene) {}
+
+    fn accessibility_role(&self) -> Role {
+        Role::GenericContainer
+    }
+
+    fn accessibility(&mut self, _ctx: &mut AccessCtx) {}
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        smallvec![self.child.id()]
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("GestureDetector")
+    }
+}
@@ -2,33 +2,69 @@
 // SPDX-License-Identifier: Apache-2.0
 
 //! An Image widget.
-//! Please consider using SVG and the SVG widget as it scales much better.
+//! Please consider using SVG and the [`Svg`](super::Svg) widget as it scales much better.
+
+use std::sync::Arc;
+use std::time::Duration;
 
 use accesskit::Role;
 use smallvec::SmallVec;
 use tracing::{trace, trace_span, Span};
-use vello::kurbo::Affine;
+use vello::kurbo::{Affine, Line, Stroke};
 use vello::peniko::{BlendMode, Image as ImageBuf};
 use vello::Scene;
 
+use crate::image_loading::{DecodedImage, ImageCache, ImageError, ImageRequest, ImageSource};
+use crate::paint_scene_helpers::fill_color;
 use crate::widget::{FillStrat, WidgetMut};
 use crate::{
-    AccessCtx, AccessEvent, BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
-    PointerEvent, Size, StatusChange, TextEvent, Widget, WidgetId,
+    theme, AccessCtx, AccessEvent, BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx,
+    PaintCtx, PointerEvent, Size, StatusChange, TextEvent, TimerToken, Widget, WidgetId,
 };
 
 // TODO - Resolve name collision between masonry::Image and peniko::Image
 
 /// A widget that renders a bitmap Image.
 ///
+/// The image is either an already decoded image buffer, or an [`ImageSource`] which is
+/// loaded and decoded in the background. While the image is loading a placeholder is shown,
+/// and if loading fails an error placeholder is shown instead.
+/// Decoded images are shared through the [global image cache](ImageCache::global),
+/// unless another cache is given with [`with_cache`](Self::with_cache).
+///
+/// Images from a source are downscaled to the size they are shown at, and animated
+/// GIF and PNG images are played.
+///
 /// The underlying image uses `Arc` for buffer data, making it cheap to clone.
 ///
 /// This currently uses bilinear interpolation, which falls down when the image is
 /// larger than its layout size (e.g. it is in a [sized box](super::SizedBox) smaller
-/// than the image size).
+/// than the image size) and isn't loaded from a source.
 pub struct Image {
-    image_data: ImageBuf,
+    source: Option<ImageSource>,
+    cache: ImageCache,
+    /// The image which is shown, if it has been decoded.
+    image: Option<Arc<DecodedImage>>,
+    /// The size of the full image, which `image` may be a downscaled version of.
+    natural_size: Size,
+    /// The size `image` was decoded to fit in, or `None` if it is the full image.
+    decoded_size: Option<(u32, u32)>,
+    load_state: LoadState,
     fill: FillStrat,
+    /// The index of the frame of an animated image which is shown.
+    frame: usize,
+    /// How long the current frame has been shown for.
+    frame_time: Duration,
+}
+
+enum LoadState {
+    /// The image is shown, or the widget has no source.
+    Loaded,
+    /// Loading starts in the first layout pass, when the widget is in the tree
+    /// and can be woken once the image is decoded.
+    NotStarted,
+    Loading(ImageRequest),
+    Failed(ImageError),
 }
 
 // --- MARK: BUILDERS ---
@@ -39,17 +75,79 @@ impl Image {
     #[inline]
     pub fn new(image_data: ImageBuf) -> Self {
         Image {
-            image_data,
+            source: None,
+            cache: ImageCache::global().clone(),
+            natural_size: Size::new(image_data.width as f64, image_data.height as f64),
+            image: Some(Arc::new(DecodedImage::still(image_data))),
+            decoded_size: None,
+            load_state: LoadState::Loaded,
+            fill: FillStrat::default(),
+            frame: 0,
+            frame_time: Duration::ZERO,
+        }
+    }
+
+    /// Create an image drawing widget which loads its image from `source`.
+    ///
+    /// The image is decoded in the background once the widget is laid out,
+    /// and a placeholder is shown until it is.
+    pub fn from_source(source: ImageSource) -> Self {
+        Image {
+            source: Some(source),
+            cache: ImageCache::global().clone(),
+            natural_size: Size::ZERO,
+            image: None,
+            decoded_size: None,
+            load_state: LoadState::NotStarted,
             fill: FillStrat::default(),
+            frame: 0,
+            frame_time: Duration::ZERO,
         }
     }
 
+    /// Builder-style method for specifying the cache the image is loaded through.
+    pub fn with_cache(mut self, cache: ImageCache) -> Self {
+        self.cache = cache;
+        self
+    }
+
     /// Builder-style method for specifying the fill strategy.
     #[inline]
     pub fn fill_mode(mut self, mode: FillStrat) -> Self {
         self.fill = mode;
         self
     }
+
+    /// The source the image is loaded from, if it has one.
+    pub fn source(&self) -> Option<&ImageSource> {
+        self.source.as_ref()
+    }
+
+    /// The decoded image which is shown, which may be downscaled.
+    pub fn decoded_image(&self) -> Option<&DecodedImage> {
+        self.image.as_deref()
+    }
+
+    /// Whether the image (or a version of it at a different size) is being loaded.
+    pub fn is_loading(&self) -> bool {
+        matches!(
+            self.load_state,
+            LoadState::NotStarted | LoadState::Loading(_)
+        )
+    }
+
+    /// The error which occurred while loading the image, if loading failed.
+    pub fn error(&self) -> Option<&ImageError> {
+        match &self.load_state {
+            LoadState::Failed(err) => Some(err),
+            _ => None,
+        }
+    }
+
+    /// The index of the frame which is shown, for animated images.
+    pub fn current_frame(&self) -> usize {
+        self.frame
+    }
 }
 
 // --- MARK: WIDGETMUT ---
@@ -58,15 +156,165 @@ impl<'a> WidgetMut<'a, Image> {
     #[inline]
     pub fn set_fill_mode(&mut self, newfil: FillStrat) {
         self.widget.fill = newfil;
+        // The fill strategy changes the size images from a source are decoded at.
+        self.ctx.request_layout();
         self.ctx.request_paint();
     }
 
     /// Set new `ImageBuf`.
     #[inline]
     pub fn set_image_data(&mut self, image_data: ImageBuf) {
-        self.widget.image_data = image_data;
+        let fill = self.widget.fill;
+        let cache = self.widget.cache.clone();
+        *self.widget = Image::new(image_data).with_cache(cache).fill_mode(fill);
         self.ctx.request_layout();
     }
+
+    /// Set the source the image is loaded from.
+    ///
+    /// This does nothing if `source` is the current source.
+    pub fn set_source(&mut self, source: ImageSource) {
+        if self.widget.source.as_ref() == Some(&source) {
+            return;
+        }
+        let fill = self.widget.fill;
+        let cache = self.widget.cache.clone();
+        *self.widget = Image::from_source(source).with_cache(cache).fill_mode(fill);
+        // The new image starts loading in the layout pass.
+        self.ctx.request_layout();
+    }
+}
+
+// --- MARK: INTERNALS ---
+impl Image {
+    fn is_animated(&self) -> bool {
+        self.image.as_ref().is_some_and(|image| image.is_animated())
+    }
+
+    /// Start loading the image from its source, decoded to fit within `max_size`.
+    ///
+    /// If the image isn't decoded right away, the widget is woken once it is.
+    fn start_load(&mut self, ctx: &mut LayoutCtx, max_size: Option<(u32, u32)>) {
+        let Some(source) = &self.source else {
+            return;
+        };
+        let request = self.cache.load(source, max_size);
+        if !request.is_finished() {
+            let waker = ctx.request_waker();
+            request.on_finish(move || waker.wake());
+        }
+        self.load_state = LoadState::Loading(request);
+        // Widgets are painted again after layout, so a finished load only needs to start
+        // the animation.
+        if self.poll_load() && self.is_animated() {
+            ctx.request_anim_frame();
+        }
+    }
+
+    /// Take the result of the load, returning whether it had finished.
+    ///
+    /// Once it has, the widget should be painted again, and laid out again in case the
+    /// natural size of the image changed.
+    fn poll_load(&mut self) -> bool {
+        let LoadState::Loading(request) = &self.load_state else {
+            return false;
+        };
+        let Some(result) = request.poll() else {
+            return false;
+        };
+        match result {
+            Ok(image) => {
+                if self.decoded_size.is_none() {
+                    self.natural_size = Size::new(image.width() as f64, image.height() as f64);
+                }
+                let frame_count = self.image.as_ref().map(|image| image.frames().len());
+                if frame_count != Some(image.frames().len()) {
+                    self.frame = 0;
+                    self.frame_time = Duration::ZERO;
+                }
+                self.image = Some(image);
+                self.load_state = LoadState::Loaded;
+            }
+            // If a downscaled version of the image failed to load, keep showing the current one.
+            Err(err) if self.image.is_some() => {
+                tracing::warn!("Resizing image failed: {err}");
+                self.load_state = LoadState::Loaded;
+            }
+            Err(err) => {
+                self.load_state = LoadState::Failed(err);
+            }
+        }
+        true
+    }
+
+    fn advance_frames(&mut self, ctx: &mut LifeCycleCtx, interval: Duration) {
+        let Some(image) = &self.image else {
+            return;
+        };
+        let frames = image.frames();
+        let total: Duration = frames.iter().map(|frame| frame.duration).sum();
+        if frames.len() < 2 || total.is_zero() {
+            return;
+        }
+        self.frame_time += interval;
+        if self.frame_time >= total {
+            self.frame_time =
+                Duration::from_secs_f64(self.frame_time.as_secs_f64() % total.as_secs_f64());
+        }
+        let previous_frame = self.frame;
+        while self.frame_time >= frames[self.frame].duration {
+            self.frame_time -= frames[self.frame].duration;
+            self.frame = (self.frame + 1) % frames.len();
+        }
+        if self.frame != previous_frame {
+            ctx.request_paint();
+        }
+    }
+
+    /// The size an image from a source should be decoded to fit in, to be shown at `size`
+    /// on a screen with the given scale factor.
+    ///
+    /// Images are never upscaled, so this is `None` if the full image is needed.
+    fn decode_size_for(&self, size: Size, scale_factor: f64) -> Option<(u32, u32)> {
+        let transform = self.fill.affine_to_fill(size, self.natural_size);
+        let [scale_x, _, _, scale_y, _, _] = transform.as_coeffs();
+        // The image is rendered in physical pixels.
+        let scale = scale_x.abs().max(scale_y.abs()) * scale_factor;
+        // Round the scale up to a multiple of 1/16, so that resizing the widget
+        // doesn't decode the image again at every size.
+        let scale = (scale * 16.0).ceil() / 16.0;
+        if scale >= 1.0 {
+            return None;
+        }
+        Some((
+            (self.natural_size.width * scale).ceil() as u32,
+            (self.natural_size.height * scale).ceil() as u32,
+        ))
+    }
+
+    fn paint_placeholder(&self, size: Size, scene: &mut Scene) {
+        let rect = size.to_rect();
+        match self.load_state {
+            LoadState::Failed(_) => {
+                fill_color(scene, &rect, theme::BACKGROUND_DARK);
+                let stroke = Stroke::new(1.0);
+                let inset = rect.inset(-size.min_side() / 4.0);
+                for line in [
+                    Line::new(inset.origin(), (inset.x1, inset.y1)),
+                    Line::new((inset.x1, inset.y0), (inset.x0, inset.y1)),
+                ] {
+                    scene.stroke(
+                        &stroke,
+                        Affine::IDENTITY,
+                        theme::PLACEHOLDER_COLOR,
+                        None,
+                        &line,
+                    );
+                }
+            }
+            _ => fill_color(scene, &rect, theme::BACKGROUND_LIGHT),
+        }
+    }
 }
 
 // --- MARK: IMPL WIDGET ---
@@ -77,17 +325,49 @@ impl Widget for Image {
 
     fn on_access_event(&mut self, _ctx: &mut EventCtx, _event: &AccessEvent) {}
 
+    fn on_timer(&mut self, ctx: &mut EventCtx, _token: TimerToken) {
+        // The widget is only woken by the loads it started. Wakers of earlier loads
+        // just poll the current one.
+        if self.poll_load() {
+            ctx.request_layout();
+            ctx.request_paint();
+            if self.is_animated() {
+                ctx.request_anim_frame();
+            }
+        }
+    }
+
     fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
 
-    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle) {}
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        if let LifeCycle::AnimFrame(interval) = event {
+            self.advance_frames(ctx, Duration::from_nanos(*interval));
+            if self.is_animated() {
+                ctx.request_anim_frame();
+            }
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        if matches!(self.load_state, LoadState::NotStarted) {
+            // The natural size isn't known yet, so the full image is loaded first.
+            self.start_load(ctx, None);
+        }
 
-    fn layout(&mut self, _ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
         // If either the width or height is constrained calculate a value so that the image fits
         // in the size exactly. If it is unconstrained by both width and height take the size of
         // the image.
-        let image_size = Size::new(self.image_data.width as f64, self.image_data.height as f64);
+        let image_size = self.natural_size;
         if image_size.is_empty() {
-            let size = bc.min();
+            let size = if self.image.is_none() {
+                // The placeholder, while loading or after loading failed.
+                bc.constrain(Size::new(
+                    theme::WIDE_WIDGET_WIDTH,
+                    theme::WIDE_WIDGET_WIDTH,
+                ))
+            } else {
+                bc.min()
+            };
             trace!("Computed size: {}", size);
             return size;
         }
@@ -96,16 +376,39 @@ impl Widget for Image {
         let size =
             bc.constrain_aspect_ratio(image_size.height / image_size.width, image_size.width);
         trace!("Computed size: {}", size);
+
+        // Decode images from a source again if they are shown at a different size.
+        if self.source.is_some() && matches!(self.load_state, LoadState::Loaded) {
+            let decode_size = self.decode_size_for(size, ctx.scale_factor());
+            if decode_size != self.decoded_size {
+                self.decoded_size = decode_size;
+                self.start_load(ctx, decode_size);
+            }
+        }
         size
     }
 
     fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
-        let image_size = Size::new(self.image_data.width as f64, self.image_data.height as f64);
-        let transform = self.fill.affine_to_fill(ctx.size(), image_size);
+        let Some(frame) = self
+            .image
+            .as_ref()
+            .and_then(|image| image.frames().get(self.frame))
+        else {
+            self.paint_placeholder(ctx.size(), scene);
+            return;
+        };
+        let image_size = self.natural_size;
+        let mut transform = self.fill.affine_to_fill(ctx.size(), image_size);
+        let (width, height) = (frame.image.width as f64, frame.image.height as f64);
+        if (width, height) != (image_size.width, image_size.height) {
+            // The image was downscaled, so scale it back up to its natural size.
+            transform *=
+                Affine::scale_non_uniform(image_size.width / width, image_size.height / height);
+        }
 
         let clip_rect = ctx.size().to_rect();
         scene.push_layer(BlendMode::default(), 1., Affine::IDENTITY, &clip_rect);
-        scene.draw_image(&self.image_data, transform);
+        scene.draw_image(&frame.image, transform);
         scene.pop_layer();
     }
 
@@ -131,12 +434,32 @@ impl Widget for Image {
 // --- MARK: TESTS ---
 #[cfg(test)]
 mod tests {
+    use std::io::Cursor;
+    use std::sync::Mutex;
+
+    use image::codecs::gif::GifEncoder;
+    use image::{Delay, Frame, ImageFormat, Rgba, RgbaImage};
     use vello::peniko::Format;
 
     use super::*;
     use crate::assert_render_snapshot;
+    use crate::image_loading::{DecodeJob, DEFAULT_CACHE_MEMORY_LIMIT};
     use crate::testing::TestHarness;
 
+    fn encode_png(width: u32, height: u32) -> Vec<u8> {
+        let image = RgbaImage::from_pixel(width, height, Rgba([255, 0, 0, 255]));
+        let mut data = Vec::new();
+        image
+            .write_to(&mut Cursor::new(&mut data), ImageFormat::Png)
+            .unwrap();
+        data
+    }
+
+    /// A cache which loads images as soon as they are requested.
+    fn sync_cache() -> ImageCache {
+        ImageCache::with_executor(DEFAULT_CACHE_MEMORY_LIMIT, |job| job())
+    }
+
     /// Painting an empty image shouldn't crash.
     #[test]
     fn empty_paint() {
@@ -198,4 +521,148 @@ mod tests {
         // We don't use assert_eq because we don't want rich assert
         assert!(render_1 == render_2);
     }
+
+    #[test]
+    fn load_from_bytes() {
+        let image_widget =
+            Image::from_source(ImageSource::bytes(encode_png(4, 2))).with_cache(sync_cache());
+        // Loading starts once the widget is laid out.
+        assert!(image_widget.is_loading());
+        assert!(image_widget.decoded_image().is_none());
+
+        let harness = TestHarness::create_with_size(image_widget, Size::new(40.0, 60.0));
+        let root = harness.root_widget();
+        let image = root.downcast::<Image>().unwrap();
+        let decoded = image.decoded_image().unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (4, 2));
+        assert!(image.error().is_none());
+        assert!(!image.is_loading());
+    }
+
+    #[test]
+    fn woken_when_loaded() {
+        let jobs = Arc::new(Mutex::new(Vec::<DecodeJob>::new()));
+        let queue = jobs.clone();
+        let cache = ImageCache::with_executor(DEFAULT_CACHE_MEMORY_LIMIT, move |job| {
+            queue.lock().unwrap().push(job);
+        });
+        let image_widget = Image::from_source(ImageSource::bytes(encode_png(4, 2)))
+            .with_cache(cache)
+            .fill_mode(FillStrat::None);
+        let mut harness = TestHarness::create(image_widget);
+        assert!(harness
+            .root_widget()
+            .downcast::<Image>()
+            .unwrap()
+            .is_loading());
+
+        // Nothing happens until the image is decoded.
+        harness.move_timers_forward(Duration::from_secs(1));
+        assert!(harness
+            .root_widget()
+            .downcast::<Image>()
+            .unwrap()
+            .is_loading());
+
+        let pending: Vec<_> = jobs.lock().unwrap().drain(..).collect();
+        for job in pending {
+            job();
+        }
+        // Finishing the load woke the widget, which is handled like an expired timer.
+        harness.move_timers_forward(Duration::ZERO);
+        let root = harness.root_widget();
+        let image = root.downcast::<Image>().unwrap();
+        assert!(!image.is_loading());
+        let decoded = image.decoded_image().unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (4, 2));
+        assert!(jobs.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn downscale_to_layout_size() {
+        let image_widget =
+            Image::from_source(ImageSource::bytes(encode_png(64, 64))).with_cache(sync_cache());
+        // First the full image is loaded, then a version downscaled to the widget's size.
+        let harness = TestHarness::create_with_size(image_widget, Size::new(16.0, 16.0));
+        let root = harness.root_widget();
+        let image = root.downcast::<Image>().unwrap();
+        let decoded = image.decoded_image().unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (16, 16));
+    }
+
+    #[test]
+    fn invalid_data_fails() {
+        let image_widget = Image::from_source(ImageSource::bytes(b"not an image".to_vec()))
+            .with_cache(sync_cache());
+        let harness = TestHarness::create(image_widget);
+        let root = harness.root_widget();
+        let image = root.downcast::<Image>().unwrap();
+        assert!(image.decoded_image().is_none());
+        assert!(matches!(image.error(), Some(ImageError::Decode(_))));
+    }
+
+    #[test]
+    fn missing_file_fails() {
+        let image_widget =
+            Image::from_source(ImageSource::path("does/not/exist.png")).with_cache(sync_cache());
+        let harness = TestHarness::create(image_widget);
+        let root = harness.root_widget();
+        let image = root.downcast::<Image>().unwrap();
+        assert!(matches!(image.error(), Some(ImageError::Io(_))));
+    }
+
+    #[test]
+    fn play_animated_gif() {
+        let mut data = Vec::new();
+        {
+            let mut encoder = GifEncoder::new(&mut data);
+            for color in [[255, 0, 0, 255], [0, 0, 255, 255]] {
+                let buffer = RgbaImage::from_pixel(2, 2, Rgba(color));
+                let delay = Delay::from_numer_denom_ms(50, 1);
+                encoder
+                    .encode_frame(Frame::from_parts(buffer, 0, 0, delay))
+                    .unwrap();
+            }
+        }
+        let image_widget = Image::from_source(ImageSource::bytes(data)).with_cache(sync_cache());
+        let mut harness = TestHarness::create(image_widget);
+        {
+            let root = harness.root_widget();
+            let image = root.downcast::<Image>().unwrap();
+            let decoded = image.decoded_image().unwrap();
+            assert_eq!(decoded.frames().len(), 2);
+            assert_eq!(decoded.frames()[0].duration, Duration::from_millis(50));
+            assert_eq!(image.current_frame(), 0);
+        }
+
+        // The first animation frame starts the animation's clock, so the 50ms of the
+        // first frame are over by the frame at 83ms.
+        harness.move_timers_forward(Duration::from_millis(90));
+        let root = harness.root_widget();
+        assert_eq!(root.downcast::<Image>().unwrap().current_frame(), 1);
+    }
+
+    #[test]
+    fn set_source() {
+        let source = ImageSource::bytes(encode_png(4, 4));
+        let image_widget = Image::from_source(source.clone()).with_cache(sync_cache());
+        let mut harness = TestHarness::create(image_widget);
+
+        // Setting the same source again keeps the loaded image.
+        harness.edit_root_widget(|mut image| {
+            let mut image = image.downcast::<Image>();
+            image.set_source(source);
+            assert!(!image.widget.is_loading());
+        });
+
+        harness.edit_root_widget(|mut image| {
+            let mut image = image.downcast::<Image>();
+            image.set_source(ImageSource::bytes(encode_png(2, 3)));
+            assert!(image.widget.decoded_image().is_none());
+        });
+        let root = harness.root_widget();
+        let image = root.downcast::<Image>().unwrap();
+        let decoded = image.decoded_image().unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (2, 3));
+    }
 }
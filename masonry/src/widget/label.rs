@@ -224,6 +224,7 @@ impl Widget for Label {
             None
         };
         self.text_layout.set_max_advance(max_advance);
+        self.text_layout.set_scale(ctx.scale_factor() as f32);
         if self.text_layout.needs_rebuild() {
             let (font_ctx, layout_ctx) = ctx.text_contexts();
             self.text_layout.rebuild(font_ctx, layout_ctx);
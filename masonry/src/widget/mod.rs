@@ -15,8 +15,13 @@ mod tests;
 
 mod align;
 mod button;
+mod canvas;
+mod chart;
 mod checkbox;
+mod dock;
 mod flex;
+mod focus_scope;
+mod gesture_detector;
 mod image;
 mod label;
 mod portal;
@@ -27,17 +32,26 @@ mod scroll_bar;
 mod sized_box;
 mod spinner;
 mod split;
+mod svg;
+mod table;
+mod tabs;
 mod textbox;
+mod tree;
 mod variable_label;
 mod widget_arena;
 
 pub use self::image::Image;
 pub use align::Align;
 pub use button::Button;
+pub use canvas::{Canvas, CanvasPointerEvent, CanvasPointerKind, HitRegion};
+pub use chart::{Chart, ChartKind, ChartSeries};
 pub use checkbox::Checkbox;
+pub use dock::{Dock, DockLayout, DockPlacement};
 pub use flex::{Axis, CrossAxisAlignment, Flex, FlexParams, MainAxisAlignment};
+pub use focus_scope::FocusScope;
+pub use gesture_detector::GestureDetector;
 pub use label::{Label, LineBreaking};
-pub use portal::Portal;
+pub use portal::{OverscrollBehavior, Portal};
 pub use progress_bar::ProgressBar;
 pub use prose::Prose;
 pub use root_widget::RootWidget;
@@ -45,7 +59,11 @@ pub use scroll_bar::ScrollBar;
 pub use sized_box::SizedBox;
 pub use spinner::Spinner;
 pub use split::Split;
+pub use svg::{Svg, SvgData, SvgError};
+pub use table::{SortDirection, Table, TableColumn, TableRow};
+pub use tabs::{TabPanel, Tabs};
 pub use textbox::Textbox;
+pub use tree::{Tree, TreeItem};
 pub use variable_label::VariableLabel;
 pub use widget_mut::WidgetMut;
 pub use widget_pod::WidgetPod;
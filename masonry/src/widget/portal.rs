@@ -4,23 +4,157 @@
 #![allow(missing_docs)]
 
 use std::ops::Range;
+use std::time::Duration;
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+#[cfg(target_arch = "wasm32")]
+use web_time::Instant;
 
 use accesskit::Role;
 use smallvec::{smallvec, SmallVec};
 use tracing::{trace_span, Span};
 use vello::kurbo::{Point, Rect, Size, Vec2};
 use vello::Scene;
+use winit::event::TouchPhase;
+use winit::keyboard::{KeyCode, PhysicalKey};
 
+use crate::gesture::DRAG_SLOP;
 use crate::widget::{Axis, ScrollBar, WidgetMut};
 use crate::{
     AccessCtx, AccessEvent, BoxConstraints, ComposeCtx, EventCtx, LayoutCtx, LifeCycle,
-    LifeCycleCtx, PaintCtx, PointerEvent, StatusChange, TextEvent, Widget, WidgetId, WidgetPod,
+    LifeCycleCtx, PaintCtx, PointerEvent, PointerId, PointerType, ScrollUnit, StatusChange,
+    TextEvent, Widget, WidgetId, WidgetPod,
 };
 
+/// How far a mouse wheel scrolls per line, in logical pixels.
+const SCROLL_LINE_HEIGHT: f64 = 10.0;
+
+/// How long animated scrolls take, e.g. for mouse wheel lines and pages.
+pub const SMOOTH_SCROLL_DURATION: Duration = Duration::from_millis(150);
+
+/// How quickly kinetic scrolling slows down: the speed is divided by `e` every
+/// `1 / KINETIC_FRICTION` seconds.
+const KINETIC_FRICTION: f64 = 4.0;
+
+/// Kinetic scrolling stops below this speed, in logical pixels per second.
+const KINETIC_MIN_VELOCITY: f64 = 20.0;
+
+/// Touches and touchpad scrolls released slower than this, in logical pixels per second,
+/// don't start kinetic scrolling.
+const FLING_MIN_VELOCITY: f64 = 100.0;
+
+/// Touches and touchpad scrolls which paused for longer than this before being released
+/// don't start kinetic scrolling.
+const FLING_MAX_PAUSE: Duration = Duration::from_millis(100);
+
+/// How much of the movement past the edges of the content moves the content.
+const OVERSCROLL_RESISTANCE: f64 = 0.5;
+
+/// How far the content can be pulled past its edges, as a fraction of the portal size.
+const OVERSCROLL_MAX_FRACTION: f64 = 0.25;
+
+/// How much of its speed a fling turns into overscroll when it hits an edge,
+/// in seconds.
+const OVERSCROLL_BOUNCE: f64 = 0.05;
+
+/// How quickly the content springs back after overscrolling: the overscroll is
+/// divided by `e` every `1 / OVERSCROLL_SPRING` seconds.
+const OVERSCROLL_SPRING: f64 = 12.0;
+
+/// What a [`Portal`] does when scrolled past the edges of its content.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OverscrollBehavior {
+    /// Scrolling stops at the edges.
+    #[default]
+    Clamp,
+    /// Touches and touchpads can pull the content past its edges, and flings bounce
+    /// off them. The content springs back once released.
+    Bounce,
+}
+
+/// A running viewport animation.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ScrollAnimation {
+    /// Easing towards a target position.
+    Smooth {
+        start: Point,
+        target: Point,
+        elapsed: f64,
+    },
+    /// Slowing down after a fling, with a velocity in logical pixels per second.
+    Kinetic { velocity: Vec2 },
+}
+
+/// A primary touch which may scroll the portal.
+#[derive(Clone, Copy, Debug)]
+struct TouchScroll {
+    pointer: PointerId,
+    start: Point,
+    last: Point,
+    /// Whether the touch moved far enough to scroll, rather than being a tap.
+    scrolling: bool,
+}
+
+/// Estimates how fast a touch or touchpad scroll moves, to fling the content when it ends.
+#[derive(Clone, Copy, Debug, Default)]
+struct VelocityTracker {
+    last_time: Option<Instant>,
+    /// Movement at `last_time` which isn't part of the velocity yet.
+    pending: Vec2,
+    velocity: Vec2,
+}
+
+impl VelocityTracker {
+    fn start(&mut self, now: Instant) {
+        *self = Self {
+            last_time: Some(now),
+            ..Self::default()
+        };
+    }
+
+    fn add(&mut self, now: Instant, delta: Vec2) {
+        let Some(last_time) = self.last_time else {
+            self.start(now);
+            return;
+        };
+        self.pending += delta;
+        let elapsed = now.duration_since(last_time);
+        if elapsed.is_zero() {
+            return;
+        }
+        let sample = self.pending / elapsed.as_secs_f64();
+        self.velocity = if elapsed > FLING_MAX_PAUSE {
+            sample
+        } else {
+            // Smooth out the jitter of individual events.
+            sample.lerp(self.velocity, 0.4)
+        };
+        self.pending = Vec2::ZERO;
+        self.last_time = Some(now);
+    }
+
+    /// The velocity when the touch is released.
+    fn release(&self, now: Instant) -> Vec2 {
+        match self.last_time {
+            Some(last_time) if now.duration_since(last_time) <= FLING_MAX_PAUSE => self.velocity,
+            _ => Vec2::ZERO,
+        }
+    }
+}
+
 // TODO - refactor - see https://github.com/linebender/xilem/issues/366
 // TODO - rename "Portal" to "ScrollPortal"?
 // Conceptually, a Portal is a Widget giving a restricted view of a child widget
 // Imagine a very large widget, and a rect that represents the part of the widget we see
+/// A widget which shows part of its child, and can be scrolled to show the rest.
+///
+/// Mouse wheels and [`pan_viewport_to`](WidgetMut::pan_viewport_to) can scroll smoothly,
+/// and touchpads and touch screens keep scrolling after a fling. All the animations run
+/// on [`LifeCycle::AnimFrame`].
+///
+/// Descendants can declare points the viewport snaps to once a scroll comes to rest,
+/// with [`LayoutCtx::add_scroll_snap_point`].
 pub struct Portal<W: Widget> {
     child: WidgetPod<W>,
     // TODO - differentiate between the "explicit" viewport pos determined
@@ -32,6 +166,16 @@ pub struct Portal<W: Widget> {
     constrain_horizontal: bool,
     constrain_vertical: bool,
     must_fill: bool,
+    overscroll_behavior: OverscrollBehavior,
+    /// How far the content is pulled past its edges, on top of `viewport_pos`.
+    overscroll: Vec2,
+    animation: Option<ScrollAnimation>,
+    touch_scroll: Option<TouchScroll>,
+    /// Whether fingers are scrolling on a touchpad.
+    touchpad_scroll: bool,
+    velocity: VelocityTracker,
+    /// The snap points of the child, in its own coordinates.
+    snap_points: Vec<Point>,
     scrollbar_horizontal: WidgetPod<ScrollBar>,
     scrollbar_horizontal_visible: bool,
     scrollbar_vertical: WidgetPod<ScrollBar>,
@@ -51,6 +195,13 @@ impl<W: Widget> Portal<W> {
             constrain_horizontal: false,
             constrain_vertical: false,
             must_fill: false,
+            overscroll_behavior: OverscrollBehavior::default(),
+            overscroll: Vec2::ZERO,
+            animation: None,
+            touch_scroll: None,
+            touchpad_scroll: false,
+            velocity: VelocityTracker::default(),
+            snap_points: Vec::new(),
             // TODO - remove (TODO: why?)
             scrollbar_horizontal: WidgetPod::new(ScrollBar::new(Axis::Horizontal, 1.0, 1.0)),
             scrollbar_horizontal_visible: false,
@@ -63,6 +214,19 @@ impl<W: Widget> Portal<W> {
         self.viewport_pos
     }
 
+    /// Whether the viewport is moving on its own, e.g. after a fling.
+    pub fn is_animating(&self) -> bool {
+        self.animation.is_some() || (self.overscroll != Vec2::ZERO && !self.is_dragged())
+    }
+
+    /// Builder-style method to set the initial position of the viewport, e.g. to restore it.
+    ///
+    /// The position will be clamped to the size of the content once it is laid out.
+    pub fn viewport_pos(mut self, position: Point) -> Self {
+        self.viewport_pos = position;
+        self
+    }
+
     // TODO - rewrite doc
     /// Builder-style method for deciding whether to constrain the child vertically.
     ///
@@ -100,6 +264,14 @@ impl<W: Widget> Portal<W> {
         self.must_fill = must_fill;
         self
     }
+
+    /// Builder-style method to set what happens when scrolling past the edges of the content.
+    ///
+    /// The default is [`OverscrollBehavior::Clamp`].
+    pub fn overscroll_behavior(mut self, behavior: OverscrollBehavior) -> Self {
+        self.overscroll_behavior = behavior;
+        self
+    }
 }
 
 fn compute_pan_range(mut viewport: Range<f64>, target: Range<f64>) -> Range<f64> {
@@ -128,15 +300,38 @@ fn compute_pan_range(mut viewport: Range<f64>, target: Range<f64>) -> Range<f64>
     viewport
 }
 
+/// The snap point on one axis for a scroll from `from`, which would stop at `projected`
+/// without snapping.
+///
+/// This is the nearest snap point to `projected`, unless that is `from` itself: then the
+/// scroll moves on to the next snap point in its direction, so that scrolling by less than
+/// half the distance between snap points still moves.
+fn snap_axis(points: &[f64], from: f64, projected: f64) -> f64 {
+    const EPSILON: f64 = 1e-6;
+    let nearest_to = |target: f64, points: &mut dyn Iterator<Item = f64>| {
+        points.min_by(|a, b| (a - target).abs().total_cmp(&(b - target).abs()))
+    };
+    let Some(nearest) = nearest_to(projected, &mut points.iter().copied()) else {
+        return projected;
+    };
+    let direction = projected - from;
+    if (nearest - from).abs() > EPSILON || direction.abs() < EPSILON {
+        return nearest;
+    }
+    nearest_to(
+        from,
+        &mut points
+            .iter()
+            .copied()
+            .filter(|point| (point - from) * direction.signum() > EPSILON),
+    )
+    .unwrap_or(nearest)
+}
+
 impl<W: Widget> Portal<W> {
     // TODO - rename
     fn set_viewport_pos_raw(&mut self, portal_size: Size, content_size: Size, pos: Point) -> bool {
-        let viewport_max_pos =
-            (content_size - portal_size).clamp(Size::ZERO, Size::new(f64::INFINITY, f64::INFINITY));
-        let pos = Point::new(
-            pos.x.clamp(0.0, viewport_max_pos.width),
-            pos.y.clamp(0.0, viewport_max_pos.height),
-        );
+        let pos = Self::clamp_viewport_pos(portal_size, content_size, pos);
 
         if (pos - self.viewport_pos).hypot2() > 1e-12 {
             self.viewport_pos = pos;
@@ -145,6 +340,174 @@ impl<W: Widget> Portal<W> {
             false
         }
     }
+
+    fn clamp_viewport_pos(portal_size: Size, content_size: Size, pos: Point) -> Point {
+        let viewport_max_pos =
+            (content_size - portal_size).clamp(Size::ZERO, Size::new(f64::INFINITY, f64::INFINITY));
+        Point::new(
+            pos.x.clamp(0.0, viewport_max_pos.width),
+            pos.y.clamp(0.0, viewport_max_pos.height),
+        )
+    }
+
+    /// How far the scrollbars are along the content, from 0 to 1.
+    fn scroll_progress(&self, portal_size: Size, content_size: Size) -> Vec2 {
+        let scroll_range = content_size - portal_size;
+        let progress = |pos: f64, range: f64| if range > 0.0 { pos / range } else { 0.0 };
+        Vec2::new(
+            progress(self.viewport_pos.x, scroll_range.width),
+            progress(self.viewport_pos.y, scroll_range.height),
+        )
+    }
+
+    /// Whether a touch or touchpad is moving the content.
+    fn is_dragged(&self) -> bool {
+        self.touchpad_scroll || self.touch_scroll.is_some_and(|touch| touch.scrolling)
+    }
+
+    /// Where a scroll from `from` which would stop at `projected` should stop,
+    /// taking snap points into account.
+    fn snap_target(
+        &self,
+        portal_size: Size,
+        content_size: Size,
+        from: Point,
+        projected: Point,
+    ) -> Point {
+        let projected = Self::clamp_viewport_pos(portal_size, content_size, projected);
+        if self.snap_points.is_empty() {
+            return projected;
+        }
+        let points: Vec<Point> = self
+            .snap_points
+            .iter()
+            .map(|point| Self::clamp_viewport_pos(portal_size, content_size, *point))
+            .collect();
+        let xs: Vec<f64> = points.iter().map(|point| point.x).collect();
+        let ys: Vec<f64> = points.iter().map(|point| point.y).collect();
+        Point::new(
+            snap_axis(&xs, from.x, projected.x),
+            snap_axis(&ys, from.y, projected.y),
+        )
+    }
+
+    /// Animate the viewport towards `target`, from where it currently is.
+    fn animate_to(&mut self, target: Point) {
+        self.animation = if (target - self.viewport_pos).hypot2() > 1e-12 {
+            Some(ScrollAnimation::Smooth {
+                start: self.viewport_pos,
+                target,
+                elapsed: 0.0,
+            })
+        } else {
+            None
+        };
+    }
+
+    /// Scroll by `delta` with an animation, e.g. for mouse wheel lines and pages.
+    fn scroll_smoothly_by(&mut self, portal_size: Size, content_size: Size, delta: Vec2) {
+        // Successive scrolls add up, instead of restarting from wherever the viewport is.
+        let from = match self.animation {
+            Some(ScrollAnimation::Smooth { target, .. }) => target,
+            _ => self.viewport_pos,
+        };
+        let target = self.snap_target(portal_size, content_size, from, from + delta);
+        self.animate_to(target);
+    }
+
+    /// Move the content along with a finger or touchpad, pulling it past its edges
+    /// if overscrolling is allowed.
+    fn drag_by(&mut self, portal_size: Size, content_size: Size, delta: Vec2) {
+        let wanted = self.viewport_pos + self.overscroll / OVERSCROLL_RESISTANCE + delta;
+        self.set_viewport_pos_raw(portal_size, content_size, wanted);
+        self.overscroll = match self.overscroll_behavior {
+            OverscrollBehavior::Clamp => Vec2::ZERO,
+            OverscrollBehavior::Bounce => {
+                let max = portal_size.to_vec2() * OVERSCROLL_MAX_FRACTION;
+                let overscroll = (wanted - self.viewport_pos) * OVERSCROLL_RESISTANCE;
+                Vec2::new(
+                    overscroll.x.clamp(-max.x, max.x),
+                    overscroll.y.clamp(-max.y, max.y),
+                )
+            }
+        };
+    }
+
+    /// Start kinetic scrolling after a touch or touchpad scroll ends.
+    fn fling(&mut self, portal_size: Size, content_size: Size, velocity: Vec2) {
+        if velocity.hypot() >= FLING_MIN_VELOCITY {
+            self.animation = Some(ScrollAnimation::Kinetic { velocity });
+        } else {
+            self.settle(portal_size, content_size);
+        }
+    }
+
+    /// Move to the nearest snap point once a scroll comes to rest.
+    fn settle(&mut self, portal_size: Size, content_size: Size) {
+        let pos = self.viewport_pos;
+        let target = self.snap_target(portal_size, content_size, pos, pos);
+        self.animate_to(target);
+    }
+
+    /// Move the animations forward by `elapsed` seconds.
+    fn advance_animation(&mut self, portal_size: Size, content_size: Size, elapsed: f64) {
+        match self.animation {
+            Some(ScrollAnimation::Smooth {
+                start,
+                target,
+                elapsed: previous,
+            }) => {
+                let elapsed = previous + elapsed;
+                let t = (elapsed / SMOOTH_SCROLL_DURATION.as_secs_f64()).min(1.0);
+                // Ease out, so that the scroll responds immediately.
+                let eased = 1.0 - (1.0 - t).powi(3);
+                self.set_viewport_pos_raw(portal_size, content_size, start.lerp(target, eased));
+                self.animation = (t < 1.0).then_some(ScrollAnimation::Smooth {
+                    start,
+                    target,
+                    elapsed,
+                });
+            }
+            Some(ScrollAnimation::Kinetic { velocity }) => {
+                // Integrate the exponential decay exactly, so that the distance doesn't
+                // depend on the frame rate.
+                let decay = (-KINETIC_FRICTION * elapsed).exp();
+                let wanted = self.viewport_pos + velocity * (1.0 - decay) / KINETIC_FRICTION;
+                self.set_viewport_pos_raw(portal_size, content_size, wanted);
+                let mut velocity = velocity * decay;
+
+                // Flings stop at the edges, and may bounce off them.
+                let blocked = wanted - self.viewport_pos;
+                if blocked.x.abs() > 1e-9 {
+                    if self.overscroll_behavior == OverscrollBehavior::Bounce {
+                        self.overscroll.x += velocity.x * OVERSCROLL_BOUNCE;
+                    }
+                    velocity.x = 0.0;
+                }
+                if blocked.y.abs() > 1e-9 {
+                    if self.overscroll_behavior == OverscrollBehavior::Bounce {
+                        self.overscroll.y += velocity.y * OVERSCROLL_BOUNCE;
+                    }
+                    velocity.y = 0.0;
+                }
+
+                if velocity.hypot() >= KINETIC_MIN_VELOCITY {
+                    self.animation = Some(ScrollAnimation::Kinetic { velocity });
+                } else {
+                    self.animation = None;
+                    self.settle(portal_size, content_size);
+                }
+            }
+            None => {}
+        }
+
+        if !self.is_dragged() {
+            self.overscroll *= (-OVERSCROLL_SPRING * elapsed).exp();
+            if self.overscroll.hypot() < 0.5 {
+                self.overscroll = Vec2::ZERO;
+            }
+        }
+    }
 }
 
 // --- MARK: WIDGETMUT ---
@@ -193,7 +556,14 @@ impl<W: Widget> WidgetMut<'_, Portal<W>> {
         self.ctx.request_layout();
     }
 
+    /// Set what happens when scrolling past the edges of the content.
+    pub fn set_overscroll_behavior(&mut self, behavior: OverscrollBehavior) {
+        self.widget.overscroll_behavior = behavior;
+    }
+
+    /// Move the viewport to `position` immediately, stopping any scroll animation.
     pub fn set_viewport_pos(&mut self, position: Point) -> bool {
+        self.widget.animation = None;
         let portal_size = self.state().layout_rect().size();
         let content_size = self
             .ctx
@@ -206,11 +576,10 @@ impl<W: Widget> WidgetMut<'_, Portal<W>> {
             .widget
             .set_viewport_pos_raw(portal_size, content_size, position);
         if pos_changed {
-            let progress_x = self.widget.viewport_pos.x / (content_size - portal_size).width;
-            self.horizontal_scrollbar_mut().widget.cursor_progress = progress_x;
+            let progress = self.widget.scroll_progress(portal_size, content_size);
+            self.horizontal_scrollbar_mut().widget.cursor_progress = progress.x;
             self.horizontal_scrollbar_mut().ctx.request_paint();
-            let progress_y = self.widget.viewport_pos.y / (content_size - portal_size).height;
-            self.vertical_scrollbar_mut().widget.cursor_progress = progress_y;
+            self.vertical_scrollbar_mut().widget.cursor_progress = progress.y;
             self.vertical_scrollbar_mut().ctx.request_paint();
             self.ctx.request_layout();
         }
@@ -221,8 +590,13 @@ impl<W: Widget> WidgetMut<'_, Portal<W>> {
         self.set_viewport_pos(self.widget.viewport_pos + translation)
     }
 
-    // Note - Rect is in child coordinates
-    pub fn pan_viewport_to(&mut self, target: Rect) -> bool {
+    /// Move the viewport so that `target` is visible, moving it as little as possible.
+    ///
+    /// `target` is in the child's coordinates. If `animated` is true, the viewport
+    /// scrolls there smoothly instead of jumping.
+    ///
+    /// Returns whether the viewport will move.
+    pub fn pan_viewport_to(&mut self, target: Rect, animated: bool) -> bool {
         let viewport = Rect::from_origin_size(self.widget.viewport_pos, self.ctx.widget_state.size);
 
         let new_pos_x = compute_pan_range(
@@ -235,32 +609,145 @@ impl<W: Widget> WidgetMut<'_, Portal<W>> {
             target.min_y()..target.max_y(),
         )
         .start;
+        let new_pos = Point::new(new_pos_x, new_pos_y);
 
-        self.set_viewport_pos(Point::new(new_pos_x, new_pos_y))
+        if !animated {
+            return self.set_viewport_pos(new_pos);
+        }
+        let portal_size = self.state().layout_rect().size();
+        let content_size = self
+            .ctx
+            .get_mut(&mut self.widget.child)
+            .state()
+            .layout_rect()
+            .size();
+        let new_pos = Portal::<W>::clamp_viewport_pos(portal_size, content_size, new_pos);
+        self.widget.animate_to(new_pos);
+        if self.widget.animation.is_some() {
+            self.ctx.request_anim_frame();
+            true
+        } else {
+            false
+        }
     }
 }
 
 // --- MARK: IMPL WIDGET ---
 impl<W: Widget> Widget for Portal<W> {
     fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
-        const SCROLLING_SPEED: f64 = 10.0;
-
         let portal_size = ctx.size();
         let content_size = ctx.get_raw_ref(&mut self.child).ctx().layout_rect().size();
+        let was_animating = self.is_animating();
+        let old_viewport_pos = self.viewport_pos;
 
         match event {
-            PointerEvent::MouseWheel(delta, _) => {
-                let delta = Vec2::new(delta.x * -SCROLLING_SPEED, delta.y * -SCROLLING_SPEED);
-                self.set_viewport_pos_raw(portal_size, content_size, self.viewport_pos + delta);
-                ctx.request_compose();
+            PointerEvent::MouseWheel(delta, state) => {
+                // Wheels scroll the content down when the wheel moves up.
+                let delta = -Vec2::new(delta.x, delta.y);
+                match state.scroll_unit {
+                    ScrollUnit::Line => {
+                        self.scroll_smoothly_by(
+                            portal_size,
+                            content_size,
+                            delta * SCROLL_LINE_HEIGHT,
+                        );
+                    }
+                    ScrollUnit::Pixel => {
+                        let now = ctx.now();
+                        match state.scroll_phase {
+                            TouchPhase::Started => {
+                                self.animation = None;
+                                self.touchpad_scroll = true;
+                                self.velocity.start(now);
+                                self.drag_by(portal_size, content_size, delta);
+                            }
+                            TouchPhase::Moved => {
+                                if !self.touchpad_scroll {
+                                    self.animation = None;
+                                    self.touchpad_scroll = true;
+                                    self.velocity.start(now);
+                                }
+                                self.drag_by(portal_size, content_size, delta);
+                                self.velocity.add(now, delta);
+                            }
+                            TouchPhase::Ended | TouchPhase::Cancelled => {
+                                self.drag_by(portal_size, content_size, delta);
+                                self.velocity.add(now, delta);
+                                self.touchpad_scroll = false;
+                                let velocity = self.velocity.release(now);
+                                self.fling(portal_size, content_size, velocity);
+                            }
+                        }
+                    }
+                }
+            }
+            PointerEvent::PointerDown(_, state) if state.pointer_type != PointerType::Mouse => {
+                // Touching the content stops it, like grabbing a spinning wheel.
+                if matches!(self.animation, Some(ScrollAnimation::Kinetic { .. })) {
+                    self.animation = None;
+                }
+                let position = Point::new(state.position.x, state.position.y);
+                self.touch_scroll = Some(TouchScroll {
+                    pointer: state.pointer_id,
+                    start: position,
+                    last: position,
+                    scrolling: false,
+                });
+                self.velocity.start(ctx.now());
+            }
+            PointerEvent::PointerMove(state) => {
+                if let Some(mut touch) = self
+                    .touch_scroll
+                    .filter(|touch| touch.pointer == state.pointer_id)
+                {
+                    let position = Point::new(state.position.x, state.position.y);
+                    if !touch.scrolling && touch.start.distance(position) > DRAG_SLOP {
+                        // Scrolling takes over from whichever child was pressed.
+                        touch.scrolling = true;
+                        self.animation = None;
+                        ctx.capture_pointer();
+                    }
+                    if touch.scrolling {
+                        // The content follows the finger.
+                        let delta = touch.last - position;
+                        touch.last = position;
+                        self.drag_by(portal_size, content_size, delta);
+                        self.velocity.add(ctx.now(), delta);
+                        ctx.set_handled();
+                    }
+                    self.touch_scroll = Some(touch);
+                }
+            }
+            PointerEvent::PointerUp(_, state) | PointerEvent::PointerLeave(state) => {
+                if let Some(touch) = self.touch_scroll {
+                    if touch.pointer == state.pointer_id {
+                        self.touch_scroll = None;
+                        if touch.scrolling {
+                            ctx.release_pointer();
+                            ctx.set_handled();
+                            let velocity = self.velocity.release(ctx.now());
+                            self.fling(portal_size, content_size, velocity);
+                        }
+                    }
+                }
+            }
+            _ => (),
+        }
 
-                // TODO - horizontal scrolling?
-                let mut scrollbar = ctx.get_raw_mut(&mut self.scrollbar_vertical);
-                scrollbar.widget().cursor_progress =
-                    self.viewport_pos.y / (content_size - portal_size).height;
+        if self.viewport_pos != old_viewport_pos || self.overscroll != Vec2::ZERO {
+            ctx.request_compose();
+            let progress = self.scroll_progress(portal_size, content_size);
+            for (scrollbar, progress) in [
+                (&mut self.scrollbar_horizontal, progress.x),
+                (&mut self.scrollbar_vertical, progress.y),
+            ] {
+                let mut scrollbar = ctx.get_raw_mut(scrollbar);
+                scrollbar.widget().cursor_progress = progress;
                 scrollbar.ctx().request_paint();
             }
-            _ => (),
+        }
+        if self.is_animating() && !was_animating {
+            ctx.request_anim_frame();
         }
 
         // This section works because events are propagated up. So if the scrollbar got
@@ -298,12 +785,34 @@ impl<W: Widget> Widget for Portal<W> {
         }
 
         if scrollbar_moved {
+            self.animation = None;
             ctx.request_compose();
         }
     }
 
-    // TODO - handle Home/End keys, etc
-    fn on_text_event(&mut self, _ctx: &mut EventCtx, _event: &TextEvent) {}
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        let TextEvent::KeyboardKey(key, _) = event else {
+            return;
+        };
+        if !key.state.is_pressed() {
+            return;
+        }
+        let portal_size = ctx.size();
+        let content_size = ctx.get_raw_ref(&mut self.child).ctx().layout_rect().size();
+        // Keep a line of the previous page visible.
+        let page = (portal_size.height - SCROLL_LINE_HEIGHT).max(SCROLL_LINE_HEIGHT);
+        let delta = match key.physical_key {
+            PhysicalKey::Code(KeyCode::PageDown) => page,
+            PhysicalKey::Code(KeyCode::PageUp) => -page,
+            _ => return,
+        };
+        let was_animating = self.is_animating();
+        self.scroll_smoothly_by(portal_size, content_size, Vec2::new(0.0, delta));
+        ctx.set_handled();
+        if self.is_animating() && !was_animating {
+            ctx.request_anim_frame();
+        }
+    }
 
     // TODO - Handle scroll-related events?
     fn on_access_event(&mut self, _ctx: &mut EventCtx, _event: &AccessEvent) {}
@@ -315,6 +824,25 @@ impl<W: Widget> Widget for Portal<W> {
             LifeCycle::WidgetAdded => {
                 ctx.register_as_portal();
             }
+            LifeCycle::AnimFrame(interval) if self.is_animating() => {
+                let portal_size = ctx.size();
+                let content_size = ctx.get_raw_ref(&mut self.child).ctx().layout_rect().size();
+                self.advance_animation(portal_size, content_size, *interval as f64 * 1e-9);
+
+                ctx.request_compose();
+                let progress = self.scroll_progress(portal_size, content_size);
+                for (scrollbar, progress) in [
+                    (&mut self.scrollbar_horizontal, progress.x),
+                    (&mut self.scrollbar_vertical, progress.y),
+                ] {
+                    let mut scrollbar = ctx.get_raw_mut(scrollbar);
+                    scrollbar.widget().cursor_progress = progress;
+                    scrollbar.ctx().request_paint();
+                }
+                if self.is_animating() {
+                    ctx.request_anim_frame();
+                }
+            }
             //TODO
             //LifeCycle::RequestPanToChild(target_rect) => {}
             _ => {}
@@ -338,11 +866,11 @@ impl<W: Widget> Widget for Portal<W> {
         // TODO - document better
         // Recompute the portal offset for the new layout
         self.set_viewport_pos_raw(portal_size, content_size, self.viewport_pos);
-        // TODO - recompute portal progress
 
         ctx.set_clip_path(portal_size.to_rect());
 
         ctx.place_child(&mut self.child, Point::ZERO);
+        self.snap_points = ctx.child_scroll_snap_points(&self.child).to_vec();
 
         self.scrollbar_horizontal_visible =
             !self.constrain_horizontal && portal_size.width < content_size.width;
@@ -362,6 +890,8 @@ impl<W: Widget> Widget for Portal<W> {
             let mut scrollbar = ctx.get_raw_mut(&mut self.scrollbar_horizontal);
             scrollbar.widget().portal_size = portal_size.width;
             scrollbar.widget().content_size = content_size.width;
+            scrollbar.widget().cursor_progress =
+                self.viewport_pos.x / (content_size - portal_size).width;
             // TODO - request paint for scrollbar?
             std::mem::drop(scrollbar);
 
@@ -377,6 +907,8 @@ impl<W: Widget> Widget for Portal<W> {
             let mut scrollbar = ctx.get_raw_mut(&mut self.scrollbar_vertical);
             scrollbar.widget().portal_size = portal_size.height;
             scrollbar.widget().content_size = content_size.height;
+            scrollbar.widget().cursor_progress =
+                self.viewport_pos.y / (content_size - portal_size).height;
             // TODO - request paint for scrollbar?
             std::mem::drop(scrollbar);
 
@@ -393,7 +925,10 @@ impl<W: Widget> Widget for Portal<W> {
     }
 
     fn compose(&mut self, ctx: &mut ComposeCtx) {
-        ctx.set_child_translation(&mut self.child, Vec2::new(0.0, -self.viewport_pos.y));
+        ctx.set_child_translation(
+            &mut self.child,
+            -(self.viewport_pos.to_vec2() + self.overscroll),
+        );
     }
 
     fn paint(&mut self, _ctx: &mut PaintCtx, _scene: &mut Scene) {}
@@ -450,8 +985,8 @@ mod tests {
 
     use super::*;
     use crate::assert_render_snapshot;
-    use crate::testing::{widget_ids, TestHarness};
-    use crate::widget::{Button, Flex, SizedBox};
+    use crate::testing::{widget_ids, ModularWidget, TestHarness};
+    use crate::widget::{Button, Flex, SizedBox, WidgetRef};
 
     fn button(text: &'static str) -> impl Widget {
         SizedBox::new(Button::new(text)).width(70.0).height(40.0)
@@ -510,7 +1045,7 @@ mod tests {
         let item_3_rect = harness.get_widget(item_3_id).state().layout_rect();
         harness.edit_root_widget(|mut portal| {
             let mut portal = portal.downcast::<Portal<Flex>>();
-            portal.pan_viewport_to(item_3_rect);
+            portal.pan_viewport_to(item_3_rect, false);
         });
 
         assert_render_snapshot!(harness, "button_list_scroll_to_item_3");
@@ -518,12 +1053,227 @@ mod tests {
         let item_13_rect = harness.get_widget(item_13_id).state().layout_rect();
         harness.edit_root_widget(|mut portal| {
             let mut portal = portal.downcast::<Portal<Flex>>();
-            portal.pan_viewport_to(item_13_rect);
+            portal.pan_viewport_to(item_13_rect, false);
         });
 
         assert_render_snapshot!(harness, "button_list_scroll_to_item_13");
     }
 
+    #[test]
+    fn initial_viewport_pos() {
+        let widget = Portal::new(
+            Flex::column()
+                .with_child(button("Item 1"))
+                .with_spacer(500.0)
+                .with_child(button("Item 2")),
+        )
+        .viewport_pos(Point::new(50.0, 1000.0));
+
+        let harness = TestHarness::create_with_size(widget, Size::new(400., 400.));
+
+        // The initial position should be clamped to the content size
+        let portal = harness.root_widget();
+        let portal = portal.downcast::<Portal<Flex>>().unwrap();
+        let content_height = portal.children()[0].state().layout_rect().height();
+        assert_eq!(
+            portal.get_viewport_pos(),
+            Point::new(0.0, content_height - 400.0)
+        );
+    }
+
+    /// A portal showing 400 out of 1000 pixels of a column of items 100 pixels high.
+    fn list_harness(snap: bool, overscroll_behavior: OverscrollBehavior) -> TestHarness {
+        let mut column = Flex::column().gap(0.);
+        for _ in 0..10 {
+            column = column.with_child(ModularWidget::new(()).layout_fn(move |_, ctx, _| {
+                if snap {
+                    ctx.add_scroll_snap_point(Point::ORIGIN);
+                }
+                Size::new(100., 100.)
+            }));
+        }
+        let widget = Portal::new(column).overscroll_behavior(overscroll_behavior);
+        let mut harness = TestHarness::create_with_size(widget, Size::new(400., 400.));
+        harness.mouse_move(Point::new(50., 50.));
+        harness
+    }
+
+    fn portal(harness: &TestHarness) -> &Portal<Flex> {
+        WidgetRef::deref(harness.root_widget().downcast::<Portal<Flex>>().unwrap())
+    }
+
+    fn viewport_y(harness: &TestHarness) -> f64 {
+        portal(harness).get_viewport_pos().y
+    }
+
+    /// Drag a finger by `distance`, in steps of 20 pixels every 16ms.
+    fn touch_drag(harness: &mut TestHarness, start: Point, distance: f64) {
+        harness.touch_down(1, start);
+        let steps = (distance.abs() / 20.).ceil() as usize;
+        for step in 1..=steps {
+            harness.move_timers_forward(Duration::from_millis(16));
+            let moved = (step as f64 * 20.).min(distance.abs()) * distance.signum();
+            harness.touch_move(1, start + Vec2::new(0., moved));
+        }
+    }
+
+    #[test]
+    fn wheel_scrolls_smoothly() {
+        let mut harness = list_harness(false, OverscrollBehavior::Clamp);
+
+        harness.mouse_wheel(Vec2::new(0., -3.));
+        assert_eq!(viewport_y(&harness), 0.);
+        assert!(portal(&harness).is_animating());
+
+        harness.move_timers_forward(SMOOTH_SCROLL_DURATION / 2);
+        let halfway = viewport_y(&harness);
+        assert!(0. < halfway && halfway < 30.);
+
+        // Scrolling again while animating adds up.
+        harness.mouse_wheel(Vec2::new(0., -3.));
+        harness.move_timers_forward(Duration::from_secs(1));
+        assert_eq!(viewport_y(&harness), 60.);
+        assert!(!portal(&harness).is_animating());
+    }
+
+    #[test]
+    fn touch_fling_keeps_scrolling() {
+        let mut harness = list_harness(false, OverscrollBehavior::Clamp);
+
+        touch_drag(&mut harness, Point::new(50., 300.), -100.);
+        assert_eq!(viewport_y(&harness), 100.);
+        harness.touch_up(1);
+
+        harness.move_timers_forward(Duration::from_millis(100));
+        let moving = viewport_y(&harness);
+        assert!(moving > 100.);
+        harness.move_timers_forward(Duration::from_secs(5));
+        assert!(viewport_y(&harness) > moving);
+        assert!(!portal(&harness).is_animating());
+
+        // A finger which stopped before being lifted doesn't fling.
+        let resting = viewport_y(&harness);
+        touch_drag(&mut harness, Point::new(50., 300.), -40.);
+        harness.move_timers_forward(Duration::from_millis(200));
+        harness.touch_up(1);
+        harness.move_timers_forward(Duration::from_secs(5));
+        assert_eq!(viewport_y(&harness), resting + 40.);
+    }
+
+    #[test]
+    fn touchpad_fling_stops_at_edge() {
+        let mut harness = list_harness(false, OverscrollBehavior::Clamp);
+
+        harness.touchpad_scroll(Vec2::ZERO, TouchPhase::Started);
+        for _ in 0..10 {
+            harness.move_timers_forward(Duration::from_millis(16));
+            harness.touchpad_scroll(Vec2::new(0., -40.), TouchPhase::Moved);
+        }
+        assert_eq!(viewport_y(&harness), 400.);
+        harness.touchpad_scroll(Vec2::ZERO, TouchPhase::Ended);
+
+        harness.move_timers_forward(Duration::from_secs(5));
+        assert_eq!(viewport_y(&harness), 600.);
+        assert!(!portal(&harness).is_animating());
+    }
+
+    #[test]
+    fn overscroll_springs_back() {
+        let mut harness = list_harness(false, OverscrollBehavior::Bounce);
+
+        touch_drag(&mut harness, Point::new(50., 100.), 100.);
+        assert_eq!(viewport_y(&harness), 0.);
+        assert_eq!(portal(&harness).overscroll, Vec2::new(0., -50.));
+
+        harness.touch_up(1);
+        harness.move_timers_forward(Duration::from_secs(1));
+        assert_eq!(portal(&harness).overscroll, Vec2::ZERO);
+        assert!(!portal(&harness).is_animating());
+
+        // Without overscrolling, the content stops at the edge.
+        let mut harness = list_harness(false, OverscrollBehavior::Clamp);
+        touch_drag(&mut harness, Point::new(50., 100.), 100.);
+        assert_eq!(portal(&harness).overscroll, Vec2::ZERO);
+    }
+
+    #[test]
+    fn scrolls_come_to_rest_on_snap_points() {
+        let mut harness = list_harness(true, OverscrollBehavior::Clamp);
+        assert_eq!(portal(&harness).snap_points.len(), 10);
+
+        // A single line moves to the next item.
+        harness.mouse_wheel(Vec2::new(0., -1.));
+        harness.move_timers_forward(Duration::from_secs(1));
+        assert_eq!(viewport_y(&harness), 100.);
+
+        // Short drags snap back, longer ones snap forward.
+        touch_drag(&mut harness, Point::new(50., 300.), -30.);
+        harness.move_timers_forward(Duration::from_millis(200));
+        harness.touch_up(1);
+        harness.move_timers_forward(Duration::from_secs(1));
+        assert_eq!(viewport_y(&harness), 100.);
+
+        touch_drag(&mut harness, Point::new(50., 300.), -70.);
+        harness.move_timers_forward(Duration::from_millis(200));
+        harness.touch_up(1);
+        harness.move_timers_forward(Duration::from_secs(1));
+        assert_eq!(viewport_y(&harness), 200.);
+    }
+
+    #[test]
+    fn touch_scroll_takes_over_from_button() {
+        let [button_id] = widget_ids();
+        let widget = Portal::new(
+            Flex::column()
+                .with_child_id(button("Item 1"), button_id)
+                .with_spacer(1000.0),
+        );
+        let mut harness = TestHarness::create_with_size(widget, Size::new(400., 400.));
+        let center = harness
+            .get_widget(button_id)
+            .state()
+            .window_layout_rect()
+            .center();
+
+        // A tap still presses the button.
+        harness.touch_down(1, center);
+        harness.touch_up(1);
+        assert!(harness.pop_action().is_some());
+
+        touch_drag(&mut harness, center, -60.);
+        assert_eq!(
+            harness.capture_target_id_of(PointerId::Touch(1)),
+            Some(harness.root_widget().id())
+        );
+        harness.touch_up(1);
+        assert_eq!(harness.pop_action(), None);
+        assert_eq!(viewport_y(&harness), 60.);
+    }
+
+    #[test]
+    fn pan_viewport_to_animated() {
+        let mut harness = list_harness(false, OverscrollBehavior::Clamp);
+
+        let moved = harness.edit_root_widget(|mut portal| {
+            let mut portal = portal.downcast::<Portal<Flex>>();
+            portal.pan_viewport_to(Rect::new(0., 500., 100., 600.), true)
+        });
+        assert!(moved);
+        assert_eq!(viewport_y(&harness), 0.);
+
+        harness.move_timers_forward(Duration::from_secs(1));
+        assert_eq!(viewport_y(&harness), 200.);
+
+        // Setting the position directly stops the animation.
+        harness.edit_root_widget(|mut portal| {
+            let mut portal = portal.downcast::<Portal<Flex>>();
+            portal.pan_viewport_to(Rect::new(0., 0., 100., 100.), true);
+            portal.set_viewport_pos(Point::new(0., 300.));
+        });
+        harness.move_timers_forward(Duration::from_secs(1));
+        assert_eq!(viewport_y(&harness), 300.);
+    }
+
     // Helper function for panning tests
     fn make_range(repr: &str) -> Range<f64> {
         let repr = &repr[repr.find('_').unwrap()..];
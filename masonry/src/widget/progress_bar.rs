@@ -119,6 +119,7 @@ impl Widget for ProgressBar {
     fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
         const DEFAULT_WIDTH: f64 = 400.;
 
+        self.label.set_scale(ctx.scale_factor() as f32);
         if self.label.needs_rebuild() {
             let (font_ctx, layout_ctx) = ctx.text_contexts();
             self.label.rebuild(font_ctx, layout_ctx);
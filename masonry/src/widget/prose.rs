@@ -9,7 +9,7 @@ use parley::{
 use smallvec::SmallVec;
 use tracing::{trace, trace_span, Span};
 use vello::{
-    kurbo::{Affine, Point, Size},
+    kurbo::{Affine, Point, Size, Vec2},
     peniko::BlendMode,
     Scene,
 };
@@ -143,13 +143,15 @@ impl WidgetMut<'_, Prose> {
 // --- MARK: IMPL WIDGET ---
 impl Widget for Prose {
     fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
-        let window_origin = ctx.widget_state.window_origin();
-        let inner_origin = Point::new(window_origin.x + LABEL_X_PADDING, window_origin.y);
+        // The text is inset by the padding.
+        let text_offset = Vec2::new(LABEL_X_PADDING, 0.0);
         match event {
             PointerEvent::PointerDown(button, state) => {
                 if !ctx.is_disabled() {
                     // TODO: Start tracking currently pressed link?
-                    let made_change = self.text_layout.pointer_down(inner_origin, state, *button);
+                    let position =
+                        ctx.to_local(Point::new(state.position.x, state.position.y)) - text_offset;
+                    let made_change = self.text_layout.pointer_down(position, state, *button);
                     if made_change {
                         ctx.request_layout();
                         ctx.request_paint();
@@ -162,7 +164,9 @@ impl Widget for Prose {
                 if !ctx.is_disabled() {
                     // TODO: Set cursor if over link
                     ctx.set_cursor(&CursorIcon::Text);
-                    if ctx.is_active() && self.text_layout.pointer_move(inner_origin, state) {
+                    let position =
+                        ctx.to_local(Point::new(state.position.x, state.position.y)) - text_offset;
+                    if ctx.is_active() && self.text_layout.pointer_move(position, state) {
                         // We might have changed text colours, so we need to re-request a layout
                         ctx.request_layout();
                         ctx.request_paint();
@@ -172,7 +176,9 @@ impl Widget for Prose {
             PointerEvent::PointerUp(button, state) => {
                 // TODO: Follow link (if not now dragging ?)
                 if !ctx.is_disabled() && ctx.is_active() {
-                    self.text_layout.pointer_up(inner_origin, state, *button);
+                    let position =
+                        ctx.to_local(Point::new(state.position.x, state.position.y)) - text_offset;
+                    self.text_layout.pointer_up(position, state, *button);
                 }
                 ctx.set_active(false);
             }
@@ -250,6 +256,7 @@ impl Widget for Prose {
             None
         };
         self.text_layout.set_max_advance(max_advance);
+        self.text_layout.set_scale(ctx.scale_factor() as f32);
         if self.text_layout.needs_rebuild() {
             let (font_ctx, layout_ctx) = ctx.text_contexts();
             self.text_layout.rebuild(font_ctx, layout_ctx);
@@ -133,8 +133,7 @@ impl Widget for ScrollBar {
                 let cursor_min_length = theme::SCROLLBAR_MIN_SIZE;
                 let cursor_rect = self.get_cursor_rect(ctx.size(), cursor_min_length);
 
-                let mouse_pos =
-                    Point::new(state.position.x, state.position.y) - ctx.window_origin().to_vec2();
+                let mouse_pos = ctx.to_local(Point::new(state.position.x, state.position.y));
                 if cursor_rect.contains(mouse_pos) {
                     let (z0, z1) = self.axis.major_span(cursor_rect);
                     let mouse_major = self.axis.major_pos(mouse_pos);
@@ -148,8 +147,7 @@ impl Widget for ScrollBar {
                 ctx.request_paint();
             }
             PointerEvent::PointerMove(state) => {
-                let mouse_pos =
-                    Point::new(state.position.x, state.position.y) - ctx.window_origin().to_vec2();
+                let mouse_pos = ctx.to_local(Point::new(state.position.x, state.position.y));
                 if let Some(grab_anchor) = self.grab_anchor {
                     let cursor_min_length = theme::SCROLLBAR_MIN_SIZE;
                     self.cursor_progress = self.progress_from_mouse_pos(
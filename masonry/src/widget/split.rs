@@ -15,7 +15,7 @@ use crate::paint_scene_helpers::{fill_color, stroke};
 use crate::widget::flex::Axis;
 use crate::widget::{WidgetMut, WidgetPod};
 use crate::{
-    theme, AccessCtx, AccessEvent, BoxConstraints, Color, CursorIcon, EventCtx, LayoutCtx,
+    theme, AccessCtx, AccessEvent, Action, BoxConstraints, Color, CursorIcon, EventCtx, LayoutCtx,
     LifeCycle, LifeCycleCtx, PaintCtx, Point, PointerEvent, Rect, Size, StatusChange, TextEvent,
     Widget, WidgetId,
 };
@@ -32,15 +32,7 @@ pub struct Split {
     min_bar_area: f64,    // Integers only
     solid: bool,
     draggable: bool,
-    /// The split bar is hovered by the mouse. This state is locked to `true` if the
-    /// widget is active (the bar is being dragged) to avoid cursor and painting jitter
-    /// if the mouse moves faster than the layout and temporarily gets outside of the
-    /// bar area while still being dragged.
-    is_bar_hover: bool,
-    /// Offset from the split point (bar center) to the actual mouse position when the
-    /// bar was clicked. This is used to ensure a click without mouse move is a no-op,
-    /// instead of re-centering the bar on the mouse.
-    click_offset: f64,
+    bar_drag: BarDrag,
     child1: WidgetPod<Box<dyn Widget>>,
     child2: WidgetPod<Box<dyn Widget>>,
 }
@@ -52,6 +44,19 @@ impl Split {
     /// Horizontal split axis means that the children are left and right.
     /// Vertical split axis means that the children are up and down.
     fn new(split_axis: Axis, child1: impl Widget + 'static, child2: impl Widget + 'static) -> Self {
+        Self::new_pod(
+            split_axis,
+            WidgetPod::new(child1).boxed(),
+            WidgetPod::new(child2).boxed(),
+        )
+    }
+
+    /// Create a new split panel from two widget pods, with the specified axis being split in two.
+    pub fn new_pod(
+        split_axis: Axis,
+        child1: WidgetPod<Box<dyn Widget>>,
+        child2: WidgetPod<Box<dyn Widget>>,
+    ) -> Self {
         Split {
             split_axis,
             split_point_chosen: 0.5,
@@ -61,10 +66,9 @@ impl Split {
             min_bar_area: 6.0,
             solid: false,
             draggable: false,
-            is_bar_hover: false,
-            click_offset: 0.0,
-            child1: WidgetPod::new(child1).boxed(),
-            child2: WidgetPod::new(child2).boxed(),
+            bar_drag: BarDrag::new(split_axis),
+            child1,
+            child2,
         }
     }
 
@@ -146,6 +150,13 @@ impl Split {
         self.solid = solid;
         self
     }
+
+    /// The split point chosen by the user or the app, as a fraction of the split axis.
+    ///
+    /// The effective split point may differ from this, to respect the minimum sizes of the children.
+    pub fn get_split_point(&self) -> f64 {
+        self.split_point_chosen
+    }
 }
 
 // --- MARK: INTERNALS ---
@@ -295,10 +306,119 @@ impl Split {
     }
 }
 
+// --- MARK: BAR DRAG ---
+/// The pointer handling of a bar which can be dragged along an axis.
+///
+/// This is used by [`Split`] for its splitter bar, by [`Table`](super::Table) for the
+/// dividers between its column headers, and by [`Dock`](super::Dock) for the bars between its splits.
+/// The owner does the hit testing, and decides what dragging the bar does.
+pub(crate) struct BarDrag {
+    axis: Axis,
+    is_dragging: bool,
+    /// The bar is hovered by the mouse. This state is locked to `true` while the bar
+    /// is being dragged to avoid cursor and painting jitter if the mouse moves faster
+    /// than the layout and temporarily gets outside of the bar area.
+    is_hover: bool,
+    /// Offset from the bar position to the actual mouse position when the bar was clicked.
+    /// This is used to ensure a click without mouse move is a no-op,
+    /// instead of re-centering the bar on the mouse.
+    click_offset: f64,
+}
+
+impl BarDrag {
+    pub(crate) fn new(axis: Axis) -> Self {
+        BarDrag {
+            axis,
+            is_dragging: false,
+            is_hover: false,
+            click_offset: 0.0,
+        }
+    }
+
+    fn set_cursor(&self, ctx: &mut EventCtx) {
+        match self.axis {
+            Axis::Horizontal => ctx.set_cursor(&CursorIcon::EwResize),
+            Axis::Vertical => ctx.set_cursor(&CursorIcon::NsResize),
+        };
+    }
+
+    /// Start dragging the bar at `bar_position`, which was clicked at `mouse_pos`.
+    pub(crate) fn start(
+        &mut self,
+        ctx: &mut EventCtx,
+        mouse_pos: LogicalPosition<f64>,
+        bar_position: f64,
+    ) {
+        ctx.set_active(true);
+        self.is_dragging = true;
+        // Save the delta between the mouse click position and the bar position
+        self.click_offset =
+            self.axis.major_pos(Point::new(mouse_pos.x, mouse_pos.y)) - bar_position;
+        // If not already hovering, force and change cursor appropriately
+        if !self.is_hover {
+            self.is_hover = true;
+            self.set_cursor(ctx);
+        }
+    }
+
+    /// Handle a pointer move, where `hit` is whether the mouse is over the bar.
+    ///
+    /// Returns the position the bar is dragged to, if it is being dragged.
+    pub(crate) fn drag(
+        &mut self,
+        ctx: &mut EventCtx,
+        mouse_pos: LogicalPosition<f64>,
+        hit: bool,
+    ) -> Option<f64> {
+        if self.is_dragging {
+            // If dragging, assume always hovered
+            return Some(
+                self.axis.major_pos(Point::new(mouse_pos.x, mouse_pos.y)) - self.click_offset,
+            );
+        }
+        // If not dragging, set cursor when hovering state changes
+        let hover = ctx.is_hot() && hit;
+        if self.is_hover != hover {
+            self.is_hover = hover;
+            if hover {
+                self.set_cursor(ctx);
+            } else {
+                ctx.clear_cursor();
+            }
+        }
+        None
+    }
+
+    /// Stop dragging, where `hit` is whether the mouse is over the bar.
+    ///
+    /// Returns whether the bar was being dragged.
+    pub(crate) fn end(&mut self, ctx: &mut EventCtx, hit: bool) -> bool {
+        if !self.is_dragging {
+            return false;
+        }
+        ctx.set_active(false);
+        self.is_dragging = false;
+        // Dependending on where the mouse cursor is when the button is released,
+        // the cursor might or might not need to be changed
+        self.is_hover = ctx.is_hot() && hit;
+        if !self.is_hover {
+            ctx.clear_cursor();
+        }
+        true
+    }
+}
+
 // FIXME - Add unit tests for WidgetMut<Split>
 
 // --- MARK: WIDGETMUT ---
 impl WidgetMut<'_, Split> {
+    /// Set the axis which is split in two.
+    pub fn set_split_axis(&mut self, split_axis: Axis) {
+        self.widget.split_axis = split_axis;
+        self.widget.bar_drag = BarDrag::new(split_axis);
+        self.ctx.request_layout();
+    }
+
     /// Set the split point as a fraction of the split axis.
     ///
     /// The value must be between `0.0` and `1.0`, inclusive.
@@ -365,6 +485,14 @@ impl WidgetMut<'_, Split> {
         self.widget.solid = solid;
         self.ctx.request_paint();
     }
+
+    pub fn child1_mut(&mut self) -> WidgetMut<'_, Box<dyn Widget>> {
+        self.ctx.get_mut(&mut self.widget.child1)
+    }
+
+    pub fn child2_mut(&mut self) -> WidgetMut<'_, Box<dyn Widget>> {
+        self.ctx.get_mut(&mut self.widget.child2)
+    }
 }
 
 // --- MARK: IMPL WIDGET ---
@@ -375,62 +503,29 @@ impl Widget for Split {
                 PointerEvent::PointerDown(PointerButton::Primary, state) => {
                     if self.bar_hit_test(ctx.size(), state.position) {
                         ctx.set_handled();
-                        ctx.set_active(true);
-                        // Save the delta between the mouse click position and the split point
-                        self.click_offset = match self.split_axis {
-                            Axis::Horizontal => state.position.x,
-                            Axis::Vertical => state.position.y,
-                        } - self.bar_position(ctx.size());
-                        // If not already hovering, force and change cursor appropriately
-                        if !self.is_bar_hover {
-                            self.is_bar_hover = true;
-                            match self.split_axis {
-                                Axis::Horizontal => ctx.set_cursor(&CursorIcon::EwResize),
-                                Axis::Vertical => ctx.set_cursor(&CursorIcon::NsResize),
-                            };
-                        }
+                        self.bar_drag
+                            .start(ctx, state.position, self.bar_position(ctx.size()));
                     }
                 }
                 PointerEvent::PointerUp(PointerButton::Primary, state) => {
-                    if ctx.is_active() {
+                    let hit = self.bar_hit_test(ctx.size(), state.position);
+                    if self.bar_drag.end(ctx, hit) {
                         ctx.set_handled();
-                        ctx.set_active(false);
-                        // Dependending on where the mouse cursor is when the button is released,
-                        // the cursor might or might not need to be changed
-                        self.is_bar_hover =
-                            ctx.is_hot() && self.bar_hit_test(ctx.size(), state.position);
-                        if !self.is_bar_hover {
-                            ctx.clear_cursor();
-                        }
                     }
                 }
                 PointerEvent::PointerMove(state) => {
-                    if ctx.is_active() {
-                        // If active, assume always hover/hot
+                    let hit = self.bar_hit_test(ctx.size(), state.position);
+                    if let Some(bar_position) = self.bar_drag.drag(ctx, state.position, hit) {
                         let effective_pos = match self.split_axis {
-                            Axis::Horizontal => {
-                                Point::new(state.position.x - self.click_offset, state.position.y)
-                            }
-                            Axis::Vertical => {
-                                Point::new(state.position.x, state.position.y - self.click_offset)
-                            }
+                            Axis::Horizontal => Point::new(bar_position, state.position.y),
+                            Axis::Vertical => Point::new(state.position.x, bar_position),
                         };
+                        let prev_split_point = self.split_point_chosen;
                         self.update_split_point(ctx.size(), effective_pos);
-                        ctx.request_layout();
-                    } else {
-                        // If not active, set cursor when hovering state changes
-                        let hover = ctx.is_hot() && self.bar_hit_test(ctx.size(), state.position);
-                        if self.is_bar_hover != hover {
-                            self.is_bar_hover = hover;
-                            if hover {
-                                match self.split_axis {
-                                    Axis::Horizontal => ctx.set_cursor(&CursorIcon::EwResize),
-                                    Axis::Vertical => ctx.set_cursor(&CursorIcon::NsResize),
-                                };
-                            } else {
-                                ctx.clear_cursor();
-                            }
+                        if self.split_point_chosen != prev_split_point {
+                            ctx.submit_action(Action::SplitPointChanged(self.split_point_chosen));
                         }
+                        ctx.request_layout();
                     }
                 }
                 _ => {}
@@ -610,8 +705,43 @@ mod tests {
         assert_render_snapshot!(harness, "rows");
     }
 
-    // FIXME - test moving the split point by mouse
-    // test draggable and min_bar_area
+    // FIXME - test min_bar_area
+
+    #[test]
+    fn drag_splitter() {
+        let widget = Split::columns(Label::new("Hello"), Label::new("World")).draggable(true);
+
+        let mut harness = TestHarness::create_with_size(widget, Size::new(100.0, 100.0));
+        let split_id = harness.root_widget().id();
+
+        harness.mouse_move((50.0, 50.0));
+        harness.mouse_button_press(PointerButton::Primary);
+        harness.mouse_move((70.0, 50.0));
+        harness.mouse_button_release(PointerButton::Primary);
+
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::SplitPointChanged(0.7), split_id))
+        );
+        assert_eq!(harness.pop_action(), None);
+        let split_point = harness
+            .root_widget()
+            .downcast::<Split>()
+            .unwrap()
+            .get_split_point();
+        assert_eq!(split_point, 0.7);
+
+        // The split point doesn't change when the splitter isn't draggable.
+        harness.edit_root_widget(|mut splitter| {
+            splitter.downcast::<Split>().set_draggable(false);
+        });
+        harness.mouse_move((70.0, 50.0));
+        harness.mouse_button_press(PointerButton::Primary);
+        harness.mouse_move((30.0, 50.0));
+        harness.mouse_button_release(PointerButton::Primary);
+
+        assert_eq!(harness.pop_action(), None);
+    }
 
     #[test]
     fn edit_splitter() {
@@ -0,0 +1,597 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A widget which displays an SVG document.
+
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+
+use accesskit::Role;
+use smallvec::SmallVec;
+use tracing::{trace, trace_span, warn, Span};
+use usvg::tiny_skia_path::PathSegment;
+use vello::kurbo::{Affine, BezPath, Cap, Join, Point, Rect, Shape, Stroke};
+use vello::peniko::{BlendMode, Brush, Color, ColorStop, Extend, Fill, Gradient, Mix};
+use vello::Scene;
+
+use crate::image_loading::decode;
+use crate::widget::{FillStrat, WidgetMut};
+use crate::{
+    AccessCtx, AccessEvent, BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
+    PointerEvent, Size, StatusChange, TextEvent, Widget, WidgetId,
+};
+
+/// A parsed SVG document, ready to be painted by an [`Svg`] widget.
+///
+/// The document is converted to a vello [`Scene`] when it is parsed, so painting it
+/// is cheap. `SvgData` uses `Arc` for the scene, making it cheap to clone.
+///
+/// Paths, fills and strokes (including gradients), embedded images, clip paths,
+/// group opacity and blend modes are supported.
+///
+/// Text, patterns, masks and filters are not. Text is dropped when the document is
+/// parsed. Fills and strokes with a pattern aren't painted, and groups with a mask or
+/// filters are painted without them; a warning is logged for each of those.
+#[derive(Clone)]
+pub struct SvgData {
+    scene: Arc<Scene>,
+    size: Size,
+}
+
+/// An error which occurred while loading an SVG document.
+#[derive(Debug)]
+pub enum SvgError {
+    /// The file couldn't be read.
+    Io(std::io::Error),
+    /// The document couldn't be parsed.
+    Parse(usvg::Error),
+}
+
+/// A widget that renders an SVG document.
+///
+/// The document is scaled to fit the widget according to its [`FillStrat`],
+/// and stays sharp at any size.
+pub struct Svg {
+    data: SvgData,
+    fill: FillStrat,
+}
+
+// --- MARK: SVG DATA ---
+impl SvgData {
+    /// Parse the SVG document in `data`.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, SvgError> {
+        let tree =
+            usvg::Tree::from_data(data, &usvg::Options::default()).map_err(SvgError::Parse)?;
+        Ok(Self::from_tree(&tree))
+    }
+
+    /// Read and parse the SVG document in the file at `path`.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, SvgError> {
+        let data = std::fs::read(path).map_err(SvgError::Io)?;
+        Self::from_bytes(&data)
+    }
+
+    fn from_tree(tree: &usvg::Tree) -> Self {
+        let mut scene = Scene::new();
+        render_group(&mut scene, tree.root(), Affine::IDENTITY);
+        let size = tree.size();
+        Self {
+            scene: Arc::new(scene),
+            size: Size::new(size.width() as f64, size.height() as f64),
+        }
+    }
+
+    /// The size of the document, in SVG user units.
+    pub fn size(&self) -> Size {
+        self.size
+    }
+}
+
+impl PartialEq for SvgData {
+    /// Whether both are the same parsed document.
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.scene, &other.scene)
+    }
+}
+
+impl fmt::Debug for SvgData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SvgData")
+            .field("size", &self.size)
+            .finish_non_exhaustive()
+    }
+}
+
+impl fmt::Display for SvgError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "failed to read SVG document: {err}"),
+            Self::Parse(err) => write!(f, "failed to parse SVG document: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for SvgError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Parse(err) => Some(err),
+        }
+    }
+}
+
+// --- MARK: CONVERSION ---
+fn to_affine(transform: usvg::Transform) -> Affine {
+    let usvg::Transform {
+        sx,
+        kx,
+        ky,
+        sy,
+        tx,
+        ty,
+    } = transform;
+    Affine::new([sx, ky, kx, sy, tx, ty].map(f64::from))
+}
+
+fn to_point(point: usvg::tiny_skia_path::Point) -> Point {
+    Point::new(point.x as f64, point.y as f64)
+}
+
+fn to_bez_path(path: &usvg::tiny_skia_path::Path) -> BezPath {
+    let mut bez_path = BezPath::new();
+    for segment in path.segments() {
+        match segment {
+            PathSegment::MoveTo(p) => bez_path.move_to(to_point(p)),
+            PathSegment::LineTo(p) => bez_path.line_to(to_point(p)),
+            PathSegment::QuadTo(p1, p2) => bez_path.quad_to(to_point(p1), to_point(p2)),
+            PathSegment::CubicTo(p1, p2, p3) => {
+                bez_path.curve_to(to_point(p1), to_point(p2), to_point(p3));
+            }
+            PathSegment::Close => bez_path.close_path(),
+        }
+    }
+    bez_path
+}
+
+fn to_color(color: usvg::Color, opacity: usvg::Opacity) -> Color {
+    Color::rgba8(
+        color.red,
+        color.green,
+        color.blue,
+        (opacity.get() * 255.0).round() as u8,
+    )
+}
+
+fn to_mix(blend_mode: usvg::BlendMode) -> Mix {
+    match blend_mode {
+        usvg::BlendMode::Normal => Mix::Normal,
+        usvg::BlendMode::Multiply => Mix::Multiply,
+        usvg::BlendMode::Screen => Mix::Screen,
+        usvg::BlendMode::Overlay => Mix::Overlay,
+        usvg::BlendMode::Darken => Mix::Darken,
+        usvg::BlendMode::Lighten => Mix::Lighten,
+        usvg::BlendMode::ColorDodge => Mix::ColorDodge,
+        usvg::BlendMode::ColorBurn => Mix::ColorBurn,
+        usvg::BlendMode::HardLight => Mix::HardLight,
+        usvg::BlendMode::SoftLight => Mix::SoftLight,
+        usvg::BlendMode::Difference => Mix::Difference,
+        usvg::BlendMode::Exclusion => Mix::Exclusion,
+        usvg::BlendMode::Hue => Mix::Hue,
+        usvg::BlendMode::Saturation => Mix::Saturation,
+        usvg::BlendMode::Color => Mix::Color,
+        usvg::BlendMode::Luminosity => Mix::Luminosity,
+    }
+}
+
+fn to_stops(stops: &[usvg::Stop], opacity: usvg::Opacity) -> Vec<ColorStop> {
+    stops
+        .iter()
+        .map(|stop| ColorStop {
+            offset: stop.offset().get(),
+            color: to_color(stop.color(), stop.opacity() * opacity),
+        })
+        .collect()
+}
+
+fn to_extend(spread_method: usvg::SpreadMethod) -> Extend {
+    match spread_method {
+        usvg::SpreadMethod::Pad => Extend::Pad,
+        usvg::SpreadMethod::Reflect => Extend::Reflect,
+        usvg::SpreadMethod::Repeat => Extend::Repeat,
+    }
+}
+
+/// Convert `paint` to a brush and its transform, or `None` if it isn't supported.
+fn to_brush(paint: &usvg::Paint, opacity: usvg::Opacity) -> Option<(Brush, Affine)> {
+    match paint {
+        usvg::Paint::Color(color) => {
+            Some((Brush::Solid(to_color(*color, opacity)), Affine::IDENTITY))
+        }
+        usvg::Paint::LinearGradient(gradient) => {
+            let brush = Gradient::new_linear(
+                (gradient.x1() as f64, gradient.y1() as f64),
+                (gradient.x2() as f64, gradient.y2() as f64),
+            )
+            .with_stops(to_stops(gradient.stops(), opacity).as_slice())
+            .with_extend(to_extend(gradient.spread_method()));
+            Some((Brush::Gradient(brush), to_affine(gradient.transform())))
+        }
+        usvg::Paint::RadialGradient(gradient) => {
+            let brush = Gradient::new_two_point_radial(
+                (gradient.fx() as f64, gradient.fy() as f64),
+                0.0,
+                (gradient.cx() as f64, gradient.cy() as f64),
+                gradient.r().get(),
+            )
+            .with_stops(to_stops(gradient.stops(), opacity).as_slice())
+            .with_extend(to_extend(gradient.spread_method()));
+            Some((Brush::Gradient(brush), to_affine(gradient.transform())))
+        }
+        usvg::Paint::Pattern(_) => {
+            warn!("SVG patterns are not supported, the fill or stroke isn't painted");
+            None
+        }
+    }
+}
+
+fn to_stroke(stroke: &usvg::Stroke) -> Stroke {
+    let join = match stroke.linejoin() {
+        usvg::LineJoin::Miter | usvg::LineJoin::MiterClip => Join::Miter,
+        usvg::LineJoin::Round => Join::Round,
+        usvg::LineJoin::Bevel => Join::Bevel,
+    };
+    let cap = match stroke.linecap() {
+        usvg::LineCap::Butt => Cap::Butt,
+        usvg::LineCap::Round => Cap::Round,
+        usvg::LineCap::Square => Cap::Square,
+    };
+    let mut kurbo_stroke = Stroke::new(stroke.width().get() as f64)
+        .with_join(join)
+        .with_caps(cap)
+        .with_miter_limit(stroke.miterlimit().get() as f64);
+    if let Some(dash_array) = stroke.dasharray() {
+        kurbo_stroke = kurbo_stroke.with_dashes(
+            stroke.dashoffset() as f64,
+            dash_array.iter().map(|dash| *dash as f64),
+        );
+    }
+    kurbo_stroke
+}
+
+/// Collect the paths of a clip path's `group` into `clip`.
+fn collect_clip_paths(clip: &mut BezPath, group: &usvg::Group, transform: Affine) {
+    for node in group.children() {
+        match node {
+            usvg::Node::Group(group) => {
+                collect_clip_paths(clip, group, transform * to_affine(group.transform()));
+            }
+            usvg::Node::Path(path) if path.is_visible() => {
+                let mut path = to_bez_path(path.data());
+                path.apply_affine(transform);
+                clip.extend(path);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Paint the children of `group` into `scene`, with `transform` applied on top of
+/// their absolute transforms.
+fn render_group(scene: &mut Scene, group: &usvg::Group, transform: Affine) {
+    for node in group.children() {
+        match node {
+            usvg::Node::Group(group) => {
+                if group.mask().is_some() {
+                    warn!("SVG masks are not supported, the group is painted without its mask");
+                }
+                if !group.filters().is_empty() {
+                    warn!("SVG filters are not supported, the group is painted without them");
+                }
+                let alpha = group.opacity().get();
+                let mix = to_mix(group.blend_mode());
+                let clip = group.clip_path().map(|clip_path| {
+                    let mut clip = BezPath::new();
+                    let clip_transform =
+                        to_affine(group.abs_transform()) * to_affine(clip_path.transform());
+                    collect_clip_paths(&mut clip, clip_path.root(), clip_transform);
+                    clip
+                });
+                let needs_layer = alpha < 1.0 || mix != Mix::Normal || clip.is_some();
+                if needs_layer {
+                    let clip = clip.unwrap_or_else(|| {
+                        let bounds = group.abs_layer_bounding_box();
+                        Rect::new(
+                            bounds.left() as f64,
+                            bounds.top() as f64,
+                            bounds.right() as f64,
+                            bounds.bottom() as f64,
+                        )
+                        .to_path(0.1)
+                    });
+                    scene.push_layer(BlendMode::from(mix), alpha, transform, &clip);
+                }
+                render_group(scene, group, transform);
+                if needs_layer {
+                    scene.pop_layer();
+                }
+            }
+            usvg::Node::Path(path) if path.is_visible() => {
+                let shape = to_bez_path(path.data());
+                let path_transform = transform * to_affine(path.abs_transform());
+                let fill = |scene: &mut Scene| {
+                    let Some(fill) = path.fill() else {
+                        return;
+                    };
+                    let Some((brush, brush_transform)) = to_brush(fill.paint(), fill.opacity())
+                    else {
+                        return;
+                    };
+                    let style = match fill.rule() {
+                        usvg::FillRule::NonZero => Fill::NonZero,
+                        usvg::FillRule::EvenOdd => Fill::EvenOdd,
+                    };
+                    scene.fill(style, path_transform, &brush, Some(brush_transform), &shape);
+                };
+                let stroke = |scene: &mut Scene| {
+                    let Some(stroke) = path.stroke() else {
+                        return;
+                    };
+                    let Some((brush, brush_transform)) = to_brush(stroke.paint(), stroke.opacity())
+                    else {
+                        return;
+                    };
+                    scene.stroke(
+                        &to_stroke(stroke),
+                        path_transform,
+                        &brush,
+                        Some(brush_transform),
+                        &shape,
+                    );
+                };
+                match path.paint_order() {
+                    usvg::PaintOrder::FillAndStroke => {
+                        fill(scene);
+                        stroke(scene);
+                    }
+                    usvg::PaintOrder::StrokeAndFill => {
+                        stroke(scene);
+                        fill(scene);
+                    }
+                }
+            }
+            usvg::Node::Image(image) if image.is_visible() => {
+                let image_transform = transform * to_affine(image.abs_transform());
+                let size = image.size();
+                match image.kind() {
+                    usvg::ImageKind::JPEG(data)
+                    | usvg::ImageKind::PNG(data)
+                    | usvg::ImageKind::GIF(data) => {
+                        let decoded = match decode(data) {
+                            Ok(decoded) => decoded,
+                            Err(err) => {
+                                warn!("Failed to decode image in SVG document: {err}");
+                                continue;
+                            }
+                        };
+                        let Some(frame) = decoded.frames().first() else {
+                            continue;
+                        };
+                        let scale = Affine::scale_non_uniform(
+                            size.width() as f64 / frame.image.width as f64,
+                            size.height() as f64 / frame.image.height as f64,
+                        );
+                        scene.draw_image(&frame.image, image_transform * scale);
+                    }
+                    usvg::ImageKind::SVG(tree) => {
+                        let tree_size = tree.size();
+                        let scale = Affine::scale_non_uniform(
+                            size.width() as f64 / tree_size.width() as f64,
+                            size.height() as f64 / tree_size.height() as f64,
+                        );
+                        render_group(scene, tree.root(), image_transform * scale);
+                    }
+                }
+            }
+            // Text isn't parsed, as that needs fonts, and other nodes are invisible.
+            _ => {}
+        }
+    }
+}
+
+// --- MARK: BUILDERS ---
+impl Svg {
+    /// Create a widget which displays the SVG document `data`.
+    ///
+    /// By default, the document is scaled to fit the widget ([`FillStrat::Contain`]).
+    pub fn new(data: SvgData) -> Self {
+        Svg {
+            data,
+            fill: FillStrat::default(),
+        }
+    }
+
+    /// Builder-style method for specifying the fill strategy.
+    pub fn fill_mode(mut self, mode: FillStrat) -> Self {
+        self.fill = mode;
+        self
+    }
+
+    /// The displayed document.
+    pub fn data(&self) -> &SvgData {
+        &self.data
+    }
+}
+
+// --- MARK: WIDGETMUT ---
+impl WidgetMut<'_, Svg> {
+    /// Modify the widget's fill strategy.
+    pub fn set_fill_mode(&mut self, fill: FillStrat) {
+        self.widget.fill = fill;
+        self.ctx.request_paint();
+    }
+
+    /// Set the displayed document.
+    pub fn set_data(&mut self, data: SvgData) {
+        self.widget.data = data;
+        self.ctx.request_layout();
+    }
+}
+
+// --- MARK: IMPL WIDGET ---
+impl Widget for Svg {
+    fn on_pointer_event(&mut self, _ctx: &mut EventCtx, _event: &PointerEvent) {}
+
+    fn on_text_event(&mut self, _ctx: &mut EventCtx, _event: &TextEvent) {}
+
+    fn on_access_event(&mut self, _ctx: &mut EventCtx, _event: &AccessEvent) {}
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle) {}
+
+    fn layout(&mut self, _ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        // Like the Image widget, keep the document's aspect ratio if possible,
+        // and take its size if unconstrained.
+        let svg_size = self.data.size;
+        let size = if svg_size.is_empty() {
+            bc.min()
+        } else {
+            bc.constrain_aspect_ratio(svg_size.height / svg_size.width, svg_size.width)
+        };
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let transform = self.fill.affine_to_fill(ctx.size(), self.data.size);
+
+        let clip_rect = ctx.size().to_rect();
+        scene.push_layer(BlendMode::default(), 1., Affine::IDENTITY, &clip_rect);
+        scene.append(&self.data.scene, Some(transform));
+        scene.pop_layer();
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::Image
+    }
+
+    fn accessibility(&mut self, _ctx: &mut AccessCtx) {}
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        SmallVec::new()
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("Svg")
+    }
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_render_snapshot;
+    use crate::testing::TestHarness;
+
+    const ICON: &str = r##"<svg xmlns="http://www.w3.org/2000/svg" width="24" height="12" viewBox="0 0 24 12">
+        <defs>
+            <linearGradient id="g" x1="0" y1="0" x2="1" y2="0">
+                <stop offset="0" stop-color="red"/>
+                <stop offset="1" stop-color="blue" stop-opacity="0.5"/>
+            </linearGradient>
+            <clipPath id="c"><circle cx="6" cy="6" r="5"/></clipPath>
+        </defs>
+        <rect width="24" height="12" fill="url(#g)"/>
+        <g opacity="0.5" clip-path="url(#c)">
+            <path d="M0 0 L12 12" stroke="#00ff00" stroke-width="2" stroke-dasharray="2 1"/>
+        </g>
+        <text x="0" y="10">Text is skipped</text>
+    </svg>"##;
+
+    /// The icon, shown with `fill` in a square larger than the icon.
+    fn fill_harness(fill: FillStrat) -> TestHarness {
+        let data = SvgData::from_bytes(ICON.as_bytes()).unwrap();
+        TestHarness::create_with_size(Svg::new(data).fill_mode(fill), Size::new(40.0, 40.0))
+    }
+
+    #[test]
+    fn parse_document() {
+        let data = SvgData::from_bytes(ICON.as_bytes()).unwrap();
+        assert_eq!(data.size(), Size::new(24.0, 12.0));
+        assert_eq!(data.clone(), data);
+        assert_ne!(data, SvgData::from_bytes(ICON.as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn invalid_document() {
+        assert!(matches!(
+            SvgData::from_bytes(b"<not svg"),
+            Err(SvgError::Parse(_))
+        ));
+        assert!(matches!(
+            SvgData::from_path("does/not/exist.svg"),
+            Err(SvgError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn edit_svg() {
+        let data = SvgData::from_bytes(ICON.as_bytes()).unwrap();
+        let mut harness = TestHarness::create(Svg::new(data));
+
+        let square = SvgData::from_bytes(
+            br#"<svg xmlns="http://www.w3.org/2000/svg" width="10" height="10"><rect width="10" height="10"/></svg>"#,
+        )
+        .unwrap();
+        harness.edit_root_widget(|mut svg| {
+            let mut svg = svg.downcast::<Svg>();
+            svg.set_data(square.clone());
+        });
+        let root = harness.root_widget();
+        let svg = root.downcast::<Svg>().unwrap();
+        assert_eq!(svg.data(), &square);
+    }
+
+    #[test]
+    fn fill_contain() {
+        let mut harness = fill_harness(FillStrat::Contain);
+        assert_render_snapshot!(harness, "fill_contain");
+    }
+
+    #[test]
+    fn fill_cover() {
+        let mut harness = fill_harness(FillStrat::Cover);
+        assert_render_snapshot!(harness, "fill_cover");
+    }
+
+    #[test]
+    fn fill_fill() {
+        let mut harness = fill_harness(FillStrat::Fill);
+        assert_render_snapshot!(harness, "fill_fill");
+    }
+
+    #[test]
+    fn fill_none() {
+        let mut harness = fill_harness(FillStrat::None);
+        assert_render_snapshot!(harness, "fill_none");
+    }
+
+    #[test]
+    fn unsupported_features_are_skipped() {
+        let data = SvgData::from_bytes(
+            br##"<svg xmlns="http://www.w3.org/2000/svg" width="10" height="10">
+                <defs>
+                    <mask id="m"><rect width="5" height="10" fill="white"/></mask>
+                    <pattern id="p" width="2" height="2" patternUnits="userSpaceOnUse">
+                        <rect width="1" height="1"/>
+                    </pattern>
+                </defs>
+                <g mask="url(#m)"><rect width="10" height="10" fill="red"/></g>
+                <rect width="10" height="10" fill="url(#p)"/>
+            </svg>"##,
+        )
+        .unwrap();
+        assert_eq!(data.size(), Size::new(10.0, 10.0));
+    }
+}
@@ -0,0 +1,1277 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A table of rows of widgets, laid out in columns.
+
+use std::ops::Range;
+
+use accesskit::Role;
+use smallvec::SmallVec;
+use tracing::{trace, trace_span, Span};
+use vello::kurbo::{BezPath, Line};
+use vello::Scene;
+use winit::keyboard::{KeyCode, PhysicalKey};
+
+use crate::action::Action;
+use crate::event::PointerButton;
+use crate::paint_scene_helpers::{fill_color, stroke};
+use crate::widget::flex::Axis;
+use crate::widget::split::BarDrag;
+use crate::widget::{Label, WidgetMut};
+use crate::{
+    theme, AccessCtx, AccessEvent, AllowRawMut, ArcStr, BoxConstraints, CursorIcon, EventCtx,
+    LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx, Point, PointerEvent, Rect, Size, StatusChange,
+    TextEvent, Widget, WidgetId, WidgetPod,
+};
+
+const DEFAULT_COLUMN_WIDTH: f64 = 100.;
+const DEFAULT_MIN_COLUMN_WIDTH: f64 = 24.;
+/// The horizontal padding on each side of a cell.
+const CELL_PADDING: f64 = 4.;
+/// The width of the area around the edge of a column header which can be dragged to resize it.
+const DIVIDER_AREA: f64 = 6.;
+const SORT_INDICATOR_SIZE: f64 = 8.;
+/// How far the mouse has to move after pressing a header before it's dragged.
+const DRAG_THRESHOLD: f64 = 4.;
+const SCROLLING_SPEED: f64 = 10.;
+/// The number of rows which are loaded above and below the ones in view.
+const OVERSCAN: usize = 8;
+
+/// A table, with a row of column headers and rows of widgets.
+///
+/// All rows have the same height, and only the rows which are scrolled into view are laid out
+/// and painted. The others are stashed, which means they keep their state.
+/// The column headers stay at the top while the rows are scrolled.
+///
+/// A table can also have rows which aren't loaded, which means they have no widgets yet,
+/// so that tables with many rows only need widgets for the part of them which is in view.
+/// These are added with [`with_unloaded_rows`](Self::with_unloaded_rows), and when rows which
+/// aren't loaded are about to be scrolled into view, the table emits
+/// [`Action::TableRowsRequested`] with the rows it should have loaded.
+///
+/// The user can resize columns by dragging the edges of their headers, and select a row by
+/// clicking it or with the arrow keys. If a column is [sortable](TableColumn::sortable),
+/// clicking its header sorts the table by it, and clicking it again reverses the order.
+/// If the table is [reorderable](Self::reorderable), headers can be dragged to move columns.
+///
+/// The table doesn't sort or reorder rows and columns itself, as their contents belong to the
+/// app. Instead it emits [`Action::TableSortChanged`] and [`Action::TableColumnMoved`], which
+/// the app should handle by updating the rows or columns.
+/// It also emits [`Action::TableColumnResized`] when the user finishes resizing a column,
+/// and [`Action::TableRowSelected`] when a row is selected.
+pub struct Table {
+    columns: Vec<TableColumn>,
+    headers: Vec<WidgetPod<TableHeader>>,
+    /// The rows which are loaded, starting with row `first_loaded`.
+    rows: Vec<WidgetPod<TableRow>>,
+    first_loaded: usize,
+    /// The number of rows, including the ones which aren't loaded.
+    row_count: usize,
+    /// The rows of the last [`Action::TableRowsRequested`], until they are loaded.
+    requested: Option<Range<usize>>,
+    row_height: f64,
+    header_height: f64,
+    /// The height of the area the rows are shown in, as of the last layout.
+    viewport_height: f64,
+    scroll_offset: f64,
+    sort: Option<(usize, SortDirection)>,
+    selected: Option<usize>,
+    reorderable: bool,
+    divider_drag: BarDrag,
+    /// The column whose right edge is being dragged.
+    resizing: Option<usize>,
+    press: Option<HeaderPress>,
+}
+
+/// The definition of a column of a [`Table`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableColumn {
+    title: ArcStr,
+    width: f64,
+    min_width: f64,
+    sortable: bool,
+}
+
+/// The order a [`Table`] is sorted in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SortDirection {
+    Ascending,
+    Descending,
+}
+
+/// A row of a [`Table`], with one cell per column.
+pub struct TableRow {
+    cells: Vec<WidgetPod<Box<dyn Widget>>>,
+    selected: bool,
+    /// The widths of the columns, set by the table before layout.
+    widths: Vec<f64>,
+    /// How much of the top of the row is hidden under the column headers.
+    hidden_top: f64,
+}
+
+/// The header of a column of a [`Table`].
+struct TableHeader {
+    label: WidgetPod<Label>,
+    sort: Option<SortDirection>,
+}
+
+/// A header which was pressed.
+struct HeaderPress {
+    column: usize,
+    start_x: f64,
+    dragging: bool,
+}
+
+// --- MARK: BUILDERS ---
+impl TableColumn {
+    /// Create a new column with the given title.
+    pub fn new(title: impl Into<ArcStr>) -> Self {
+        TableColumn {
+            title: title.into(),
+            width: DEFAULT_COLUMN_WIDTH,
+            min_width: DEFAULT_MIN_COLUMN_WIDTH,
+            sortable: false,
+        }
+    }
+
+    /// Builder-style method to set the initial width of the column.
+    ///
+    /// The default width is `100.0`.
+    pub fn width(mut self, width: f64) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Builder-style method to set the width the user can't resize the column below.
+    pub fn min_width(mut self, min_width: f64) -> Self {
+        self.min_width = min_width;
+        self
+    }
+
+    /// Builder-style method to set whether the table can be sorted by this column.
+    pub fn sortable(mut self, sortable: bool) -> Self {
+        self.sortable = sortable;
+        self
+    }
+
+    pub fn title(&self) -> &ArcStr {
+        &self.title
+    }
+
+    pub fn get_width(&self) -> f64 {
+        self.width.max(self.min_width)
+    }
+
+    pub fn is_sortable(&self) -> bool {
+        self.sortable
+    }
+}
+
+impl SortDirection {
+    /// The opposite direction.
+    pub fn reversed(self) -> Self {
+        match self {
+            SortDirection::Ascending => SortDirection::Descending,
+            SortDirection::Descending => SortDirection::Ascending,
+        }
+    }
+}
+
+impl Table {
+    /// Create a new table with the given columns, and no rows.
+    pub fn new(columns: impl IntoIterator<Item = TableColumn>) -> Self {
+        let columns: Vec<_> = columns.into_iter().collect();
+        let headers = columns.iter().map(TableHeader::pod).collect();
+        Table {
+            columns,
+            headers,
+            rows: Vec::new(),
+            first_loaded: 0,
+            row_count: 0,
+            requested: None,
+            row_height: theme::BORDERED_WIDGET_HEIGHT,
+            header_height: 0.0,
+            viewport_height: 0.0,
+            scroll_offset: 0.0,
+            sort: None,
+            selected: None,
+            reorderable: false,
+            divider_drag: BarDrag::new(Axis::Horizontal),
+            resizing: None,
+            press: None,
+        }
+    }
+
+    /// Builder-style method to add a row.
+    pub fn with_row(self, row: TableRow) -> Self {
+        self.with_row_pod(WidgetPod::new(row))
+    }
+
+    /// Builder-style method to add a row which is already in a [`WidgetPod`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`with_unloaded_rows`](Self::with_unloaded_rows) was called before.
+    pub fn with_row_pod(mut self, mut row: WidgetPod<TableRow>) -> Self {
+        assert_eq!(
+            self.first_loaded + self.rows.len(),
+            self.row_count,
+            "Loaded rows can't be added after unloaded rows"
+        );
+        row.created_widget_mut().unwrap().selected = self.selected == Some(self.row_count);
+        self.rows.push(row);
+        self.row_count += 1;
+        self
+    }
+
+    /// Builder-style method to add `count` rows which aren't loaded.
+    ///
+    /// See the [type-level documentation](Table) for how they are loaded.
+    pub fn with_unloaded_rows(mut self, count: usize) -> Self {
+        self.row_count += count;
+        self
+    }
+
+    /// Builder-style method to set the height of all rows.
+    ///
+    /// The default height is [`theme::BORDERED_WIDGET_HEIGHT`].
+    pub fn row_height(mut self, row_height: f64) -> Self {
+        self.row_height = row_height;
+        self
+    }
+
+    /// Builder-style method to set the column the table is sorted by, which shows an indicator
+    /// in its header.
+    pub fn sort(mut self, sort: Option<(usize, SortDirection)>) -> Self {
+        self.sort = sort;
+        for (idx, header) in self.headers.iter_mut().enumerate() {
+            let header = header.created_widget_mut().unwrap();
+            header.sort = sort
+                .filter(|(column, _)| *column == idx)
+                .map(|(_, dir)| dir);
+        }
+        self
+    }
+
+    /// Builder-style method to set the selected row.
+    pub fn selected(mut self, selected: Option<usize>) -> Self {
+        self.selected = selected;
+        for (idx, row) in (self.first_loaded..).zip(&mut self.rows) {
+            row.created_widget_mut().unwrap().selected = selected == Some(idx);
+        }
+        self
+    }
+
+    /// Builder-style method to set whether the columns can be reordered by dragging their headers.
+    pub fn reorderable(mut self, reorderable: bool) -> Self {
+        self.reorderable = reorderable;
+        self
+    }
+
+    pub fn columns(&self) -> &[TableColumn] {
+        &self.columns
+    }
+
+    /// The number of rows, including the ones which aren't loaded.
+    pub fn row_count(&self) -> usize {
+        self.row_count
+    }
+
+    /// The indices of the rows which are loaded.
+    pub fn loaded_rows(&self) -> Range<usize> {
+        self.first_loaded..self.first_loaded + self.rows.len()
+    }
+
+    /// The rows which should be loaded if the table had `row_count` rows, which are the ones
+    /// in view as of the last layout and a few around them.
+    pub fn rows_to_load(&self, row_count: usize) -> Range<usize> {
+        let max_scroll_offset =
+            (row_count as f64 * self.row_height - self.viewport_height).max(0.0);
+        let visible = self.visible_rows(self.scroll_offset.min(max_scroll_offset));
+        visible.start.saturating_sub(OVERSCAN).min(row_count)
+            ..(visible.end + OVERSCAN).min(row_count)
+    }
+
+    /// The column the table is sorted by.
+    pub fn sort_column(&self) -> Option<(usize, SortDirection)> {
+        self.sort
+    }
+
+    pub fn selected_row(&self) -> Option<usize> {
+        self.selected
+    }
+}
+
+impl TableRow {
+    /// Create a new row with the given cells.
+    pub fn new(cells: impl IntoIterator<Item = WidgetPod<Box<dyn Widget>>>) -> Self {
+        TableRow {
+            cells: cells.into_iter().collect(),
+            selected: false,
+            widths: Vec::new(),
+            hidden_top: 0.0,
+        }
+    }
+
+    /// Builder-style method to add a cell.
+    pub fn with_cell(mut self, cell: impl Widget) -> Self {
+        self.cells.push(WidgetPod::new(Box::new(cell)));
+        self
+    }
+
+    pub fn is_selected(&self) -> bool {
+        self.selected
+    }
+}
+
+impl TableHeader {
+    fn pod(column: &TableColumn) -> WidgetPod<Self> {
+        WidgetPod::new(TableHeader {
+            label: WidgetPod::new(Label::new(column.title.clone())),
+            sort: None,
+        })
+    }
+}
+
+// --- MARK: WIDGETMUT ---
+impl WidgetMut<'_, Table> {
+    /// Replace the columns of the table.
+    ///
+    /// The rows should have a cell for each of the new columns.
+    pub fn set_columns(&mut self, columns: impl IntoIterator<Item = TableColumn>) {
+        for header in std::mem::take(&mut self.widget.headers) {
+            self.ctx.remove_child(header);
+        }
+        self.widget.columns = columns.into_iter().collect();
+        self.widget.headers = self.widget.columns.iter().map(TableHeader::pod).collect();
+        let columns = self.widget.columns.len();
+        self.widget.sort = self.widget.sort.filter(|(column, _)| *column < columns);
+        if let Some((column, direction)) = self.widget.sort {
+            let header = self.widget.headers[column].created_widget_mut().unwrap();
+            header.sort = Some(direction);
+        }
+        self.ctx.children_changed();
+    }
+
+    /// Set the width of the column at `idx`.
+    pub fn set_column_width(&mut self, idx: usize, width: f64) {
+        self.widget.columns[idx].width = width;
+        self.ctx.request_layout();
+    }
+
+    /// Set the column the table is sorted by, which shows an indicator in its header.
+    ///
+    /// This doesn't emit an [`Action::TableSortChanged`].
+    pub fn set_sort(&mut self, sort: Option<(usize, SortDirection)>) {
+        let prev = std::mem::replace(&mut self.widget.sort, sort);
+        for (column, _) in prev.into_iter().chain(sort) {
+            let mut header = self.ctx.get_mut(&mut self.widget.headers[column]);
+            header.widget.sort = sort.filter(|(c, _)| *c == column).map(|(_, dir)| dir);
+            header.ctx.request_paint();
+            header.ctx.request_accessibility_update();
+        }
+    }
+
+    /// Set the selected row.
+    ///
+    /// This doesn't emit an [`Action::TableRowSelected`].
+    pub fn set_selected(&mut self, selected: Option<usize>) {
+        let prev = std::mem::replace(&mut self.widget.selected, selected);
+        for idx in prev.into_iter().chain(selected) {
+            if let Some(pos) = self.widget.loaded_position(idx) {
+                self.set_row_selected(pos, selected == Some(idx));
+            }
+        }
+    }
+
+    /// Set whether the columns can be reordered by dragging their headers.
+    pub fn set_reorderable(&mut self, reorderable: bool) {
+        self.widget.reorderable = reorderable;
+    }
+
+    /// Set the height of all rows.
+    pub fn set_row_height(&mut self, row_height: f64) {
+        self.widget.row_height = row_height;
+        self.ctx.request_layout();
+    }
+
+    /// Insert a row at `idx`, which must be loaded or directly after the loaded rows.
+    ///
+    /// The selected row stays the same.
+    pub fn insert_row_pod(&mut self, idx: usize, mut row: WidgetPod<TableRow>) {
+        if self.widget.rows.is_empty() {
+            self.widget.first_loaded = idx;
+        }
+        let pos = idx
+            .checked_sub(self.widget.first_loaded)
+            .filter(|pos| *pos <= self.widget.rows.len())
+            .unwrap_or_else(|| panic!("Row {idx} isn't next to the loaded rows"));
+        if let Some(selected) = &mut self.widget.selected {
+            if *selected >= idx {
+                *selected += 1;
+            }
+        }
+        row.created_widget_mut().unwrap().selected = self.widget.selected == Some(idx);
+        self.widget.rows.insert(pos, row);
+        self.widget.row_count += 1;
+        self.ctx.children_changed();
+    }
+
+    /// Remove the row at `idx`, which must be loaded.
+    ///
+    /// If it's the selected row, no row will be selected.
+    pub fn remove_row(&mut self, idx: usize) {
+        let pos = self.widget.expect_loaded(idx);
+        let row = self.widget.rows.remove(pos);
+        self.ctx.remove_child(row);
+        self.widget.row_count -= 1;
+        self.widget.selected = match self.widget.selected {
+            Some(selected) if selected == idx => None,
+            Some(selected) if selected > idx => Some(selected - 1),
+            selected => selected,
+        };
+        self.ctx.request_layout();
+    }
+
+    /// Move the row at `from` so that it is at `to`, which must both be loaded.
+    ///
+    /// The selected row moves with it.
+    pub fn move_row(&mut self, from: usize, to: usize) {
+        let (pos_from, pos_to) = (
+            self.widget.expect_loaded(from),
+            self.widget.expect_loaded(to),
+        );
+        if from == to {
+            return;
+        }
+        let row = self.widget.rows.remove(pos_from);
+        self.widget.rows.insert(pos_to, row);
+        self.widget.selected = self.widget.selected.map(|selected| {
+            if selected == from {
+                to
+            } else if from < selected && selected <= to {
+                selected - 1
+            } else if to <= selected && selected < from {
+                selected + 1
+            } else {
+                selected
+            }
+        });
+        self.ctx.children_changed();
+    }
+
+    /// Swap the rows at `a` and `b`, which must both be loaded.
+    ///
+    /// The selected row moves with them.
+    pub fn swap_rows(&mut self, a: usize, b: usize) {
+        let (pos_a, pos_b) = (self.widget.expect_loaded(a), self.widget.expect_loaded(b));
+        if a == b {
+            return;
+        }
+        self.widget.rows.swap(pos_a, pos_b);
+        self.widget.selected = self.widget.selected.map(|selected| {
+            if selected == a {
+                b
+            } else if selected == b {
+                a
+            } else {
+                selected
+            }
+        });
+        self.ctx.children_changed();
+    }
+
+    /// Set the number of rows, including the ones which aren't loaded.
+    ///
+    /// The loaded rows have to be before `count`. If the selected row is after it,
+    /// no row will be selected.
+    pub fn set_row_count(&mut self, count: usize) {
+        assert!(
+            self.widget.loaded_rows().end <= count,
+            "The loaded rows must be within the rows of the table"
+        );
+        self.widget.row_count = count;
+        self.widget.selected = self.widget.selected.filter(|selected| *selected < count);
+        self.ctx.request_layout();
+        self.ctx.request_accessibility_update();
+    }
+
+    /// Set the index of the first loaded row, which changes the indices of all loaded rows.
+    ///
+    /// This is used together with [`set_row_count`](Self::set_row_count) to change which
+    /// rows are loaded, after [`Action::TableRowsRequested`].
+    pub fn set_first_loaded_row(&mut self, idx: usize) {
+        self.widget.first_loaded = idx;
+        for pos in 0..self.widget.rows.len() {
+            self.set_row_selected(pos, self.widget.selected == Some(idx + pos));
+        }
+        self.ctx.request_layout();
+    }
+
+    /// Set whether the loaded row at `pos` in `rows` is selected.
+    fn set_row_selected(&mut self, pos: usize, selected: bool) {
+        let row = &mut self.widget.rows[pos];
+        // Rows which were just inserted aren't in the widget tree yet.
+        if let Some(row) = row.created_widget_mut() {
+            row.selected = selected;
+            return;
+        }
+        let mut row = self.ctx.get_mut(row);
+        if row.widget.selected != selected {
+            row.widget.selected = selected;
+            row.ctx.request_paint();
+            row.ctx.request_accessibility_update();
+        }
+    }
+
+    /// Get a mutable reference to the row at `idx`, which must be loaded.
+    pub fn row_mut(&mut self, idx: usize) -> WidgetMut<'_, TableRow> {
+        let pos = self.widget.expect_loaded(idx);
+        self.ctx.get_mut(&mut self.widget.rows[pos])
+    }
+
+    /// Scroll the table so that the row at `idx` is visible.
+    pub fn scroll_to_row(&mut self, idx: usize) {
+        self.widget.scroll_to_row(idx);
+        self.ctx.request_layout();
+    }
+}
+
+impl WidgetMut<'_, TableRow> {
+    /// Insert a cell at `idx`.
+    pub fn insert_cell_pod(&mut self, idx: usize, cell: WidgetPod<Box<dyn Widget>>) {
+        self.widget.cells.insert(idx, cell);
+        self.ctx.children_changed();
+    }
+
+    /// Remove the cell at `idx`.
+    pub fn remove_cell(&mut self, idx: usize) {
+        let cell = self.widget.cells.remove(idx);
+        self.ctx.remove_child(cell);
+        self.ctx.request_layout();
+    }
+
+    /// Get a mutable reference to the cell at `idx`.
+    pub fn cell_mut(&mut self, idx: usize) -> WidgetMut<'_, Box<dyn Widget>> {
+        self.ctx.get_mut(&mut self.widget.cells[idx])
+    }
+}
+
+// The table sets the column widths and selection of rows directly.
+impl AllowRawMut for TableRow {}
+impl AllowRawMut for TableHeader {}
+
+// --- MARK: INTERNALS ---
+impl Table {
+    /// The x coordinates of the right edges of the columns.
+    fn column_edges(&self) -> impl Iterator<Item = f64> + '_ {
+        self.columns.iter().scan(0.0, |x, column| {
+            *x += column.get_width();
+            Some(*x)
+        })
+    }
+
+    /// The column whose right edge is at `x`, in the header.
+    fn divider_at(&self, pos: Point) -> Option<usize> {
+        if pos.y >= self.header_height {
+            return None;
+        }
+        self.column_edges()
+            .position(|edge| (pos.x - edge).abs() <= DIVIDER_AREA / 2.0)
+    }
+
+    /// The column at `x`, or the last column if `x` is after it.
+    fn column_at(&self, x: f64) -> Option<usize> {
+        let last = self.columns.len().checked_sub(1)?;
+        Some(
+            self.column_edges()
+                .position(|edge| x < edge)
+                .unwrap_or(last),
+        )
+    }
+
+    fn row_at(&self, pos: Point) -> Option<usize> {
+        if pos.y < self.header_height {
+            return None;
+        }
+        let idx = ((pos.y - self.header_height + self.scroll_offset) / self.row_height).floor();
+        Some(idx as usize).filter(|idx| *idx < self.row_count)
+    }
+
+    /// The index in `rows` of the row at `idx`, if it's loaded.
+    fn loaded_position(&self, idx: usize) -> Option<usize> {
+        idx.checked_sub(self.first_loaded)
+            .filter(|pos| *pos < self.rows.len())
+    }
+
+    #[track_caller]
+    fn expect_loaded(&self, idx: usize) -> usize {
+        self.loaded_position(idx)
+            .unwrap_or_else(|| panic!("Row {idx} isn't loaded"))
+    }
+
+    /// The rows which are in view at the given scroll offset, with the last viewport height.
+    fn visible_rows(&self, scroll_offset: f64) -> Range<usize> {
+        let first = (scroll_offset / self.row_height).floor() as usize;
+        let last = ((scroll_offset + self.viewport_height) / self.row_height).ceil() as usize;
+        first..last
+    }
+
+    fn viewport_height(&self, size: Size) -> f64 {
+        (size.height - self.header_height).max(0.0)
+    }
+
+    fn max_scroll_offset(&self, size: Size) -> f64 {
+        (self.row_count as f64 * self.row_height - self.viewport_height(size)).max(0.0)
+    }
+
+    fn scroll_to_row(&mut self, idx: usize) {
+        let top = idx as f64 * self.row_height;
+        // Before the first layout, the row is scrolled to the top.
+        let viewport_height = self.viewport_height;
+        if top < self.scroll_offset {
+            self.scroll_offset = top;
+        } else if top + self.row_height > self.scroll_offset + viewport_height {
+            self.scroll_offset = top + self.row_height - viewport_height;
+        }
+    }
+
+    fn select_row(&mut self, ctx: &mut EventCtx, idx: usize) {
+        if self.selected == Some(idx) {
+            return;
+        }
+        trace!("Table {:?} selected row {}", ctx.widget_id(), idx);
+        let prev = self.selected.replace(idx);
+        for idx in prev.into_iter().chain(Some(idx)) {
+            let Some(pos) = self.loaded_position(idx) else {
+                continue;
+            };
+            let mut row = ctx.get_raw_mut(&mut self.rows[pos]);
+            row.widget().selected = self.selected == Some(idx);
+            row.ctx().request_paint();
+            row.ctx().request_accessibility_update();
+        }
+        ctx.submit_action(Action::TableRowSelected(idx));
+    }
+
+    fn sort_by(&mut self, ctx: &mut EventCtx, column: usize) {
+        let direction = match self.sort {
+            Some((sorted, direction)) if sorted == column => direction.reversed(),
+            _ => SortDirection::Ascending,
+        };
+        trace!(
+            "Table {:?} sorted by column {} {:?}",
+            ctx.widget_id(),
+            column,
+            direction
+        );
+        let prev = self.sort.replace((column, direction));
+        for idx in prev.map(|(idx, _)| idx).into_iter().chain(Some(column)) {
+            let mut header = ctx.get_raw_mut(&mut self.headers[idx]);
+            header.widget().sort = Some(direction).filter(|_| idx == column);
+            header.ctx().request_paint();
+            header.ctx().request_accessibility_update();
+        }
+        ctx.submit_action(Action::TableSortChanged(column, direction));
+    }
+}
+
+// --- MARK: IMPL WIDGET ---
+impl Widget for Table {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        match event {
+            PointerEvent::PointerDown(PointerButton::Primary, state) => {
+                if ctx.is_disabled() {
+                    return;
+                }
+                let pos = ctx.to_local(Point::new(state.position.x, state.position.y));
+                if let Some(column) = self.divider_at(pos) {
+                    ctx.set_handled();
+                    let edge = self.column_edges().nth(column).unwrap();
+                    let local = crate::dpi::LogicalPosition::new(pos.x, pos.y);
+                    self.divider_drag.start(ctx, local, edge);
+                    self.resizing = Some(column);
+                } else if pos.y < self.header_height {
+                    if let Some(column) = self.column_at(pos.x) {
+                        ctx.set_handled();
+                        ctx.set_active(true);
+                        self.press = Some(HeaderPress {
+                            column,
+                            start_x: pos.x,
+                            dragging: false,
+                        });
+                    }
+                } else if let Some(row) = self.row_at(pos) {
+                    ctx.set_handled();
+                    ctx.request_focus();
+                    self.select_row(ctx, row);
+                }
+            }
+            PointerEvent::PointerMove(state) => {
+                let pos = ctx.to_local(Point::new(state.position.x, state.position.y));
+                if let Some(press) = &mut self.press {
+                    if self.reorderable
+                        && !press.dragging
+                        && (pos.x - press.start_x).abs() > DRAG_THRESHOLD
+                    {
+                        press.dragging = true;
+                        ctx.set_cursor(&CursorIcon::Grabbing);
+                    }
+                    return;
+                }
+                let local = crate::dpi::LogicalPosition::new(pos.x, pos.y);
+                let hit = self.divider_at(pos).is_some();
+                if let Some(edge) = self.divider_drag.drag(ctx, local, hit) {
+                    let Some(column) = self.resizing else {
+                        return;
+                    };
+                    let start: f64 = self.columns[..column].iter().map(|c| c.get_width()).sum();
+                    let column = &mut self.columns[column];
+                    column.width = (edge - start).max(column.min_width);
+                    ctx.request_layout();
+                }
+            }
+            PointerEvent::PointerUp(PointerButton::Primary, state) => {
+                let pos = ctx.to_local(Point::new(state.position.x, state.position.y));
+                if let Some(press) = self.press.take() {
+                    ctx.set_handled();
+                    ctx.set_active(false);
+                    if press.dragging {
+                        ctx.clear_cursor();
+                        let to = self.column_at(pos.x).unwrap_or(press.column);
+                        if to != press.column {
+                            trace!(
+                                "Table {:?} moved column {} to {}",
+                                ctx.widget_id(),
+                                press.column,
+                                to
+                            );
+                            ctx.submit_action(Action::TableColumnMoved(press.column, to));
+                        }
+                    } else if ctx.is_hot()
+                        && self.column_at(pos.x) == Some(press.column)
+                        && self.columns[press.column].sortable
+                    {
+                        self.sort_by(ctx, press.column);
+                    }
+                    return;
+                }
+                let hit = self.divider_at(pos).is_some();
+                if self.divider_drag.end(ctx, hit) {
+                    ctx.set_handled();
+                    if let Some(column) = self.resizing.take() {
+                        let width = self.columns[column].get_width();
+                        ctx.submit_action(Action::TableColumnResized(column, width));
+                    }
+                }
+            }
+            PointerEvent::MouseWheel(delta, _) => {
+                let max = self.max_scroll_offset(ctx.size());
+                let offset = (self.scroll_offset - delta.y * SCROLLING_SPEED).clamp(0.0, max);
+                if offset != self.scroll_offset {
+                    ctx.set_handled();
+                    self.scroll_offset = offset;
+                    ctx.request_layout();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        let TextEvent::KeyboardKey(key, _) = event else {
+            return;
+        };
+        if !key.state.is_pressed() || !ctx.is_focused() || self.row_count == 0 {
+            return;
+        }
+        let last = self.row_count - 1;
+        let page = (self.viewport_height(ctx.size()) / self.row_height).floor() as usize;
+        let row = match (key.physical_key, self.selected) {
+            (PhysicalKey::Code(KeyCode::ArrowDown), Some(idx)) => (idx + 1).min(last),
+            (PhysicalKey::Code(KeyCode::ArrowUp), Some(idx)) => idx.saturating_sub(1),
+            (PhysicalKey::Code(KeyCode::PageDown), Some(idx)) => (idx + page.max(1)).min(last),
+            (PhysicalKey::Code(KeyCode::PageUp), Some(idx)) => idx.saturating_sub(page.max(1)),
+            (
+                PhysicalKey::Code(
+                    KeyCode::ArrowDown | KeyCode::ArrowUp | KeyCode::PageDown | KeyCode::PageUp,
+                ),
+                None,
+            ) => 0,
+            (PhysicalKey::Code(KeyCode::Home), _) => 0,
+            (PhysicalKey::Code(KeyCode::End), _) => last,
+            _ => return,
+        };
+        ctx.set_handled();
+        self.select_row(ctx, row);
+        self.scroll_to_row(row);
+        ctx.request_layout();
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        if event.action != accesskit::Action::Default {
+            return;
+        }
+        if let Some(pos) = self.rows.iter().position(|row| row.id() == event.target) {
+            ctx.set_handled();
+            self.select_row(ctx, self.first_loaded + pos);
+        } else if let Some(column) = self
+            .headers
+            .iter()
+            .position(|header| header.id() == event.target)
+        {
+            if self.columns[column].sortable {
+                ctx.set_handled();
+                self.sort_by(ctx, column);
+            }
+        }
+    }
+
+    fn on_status_change(&mut self, ctx: &mut LifeCycleCtx, event: &StatusChange) {
+        if let StatusChange::FocusChanged(_) = event {
+            ctx.request_paint();
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        match event {
+            LifeCycle::WidgetAdded => ctx.register_as_portal(),
+            LifeCycle::BuildFocusChain => ctx.register_for_focus(),
+            _ => {}
+        }
+        for row in &mut self.rows {
+            row.lifecycle(ctx, event);
+        }
+        for header in &mut self.headers {
+            header.lifecycle(ctx, event);
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let widths: Vec<f64> = self.columns.iter().map(|c| c.get_width()).collect();
+        let total_width: f64 = widths.iter().sum();
+
+        let mut header_height: f64 = 0.0;
+        let mut x = 0.0;
+        for (header, width) in self.headers.iter_mut().zip(&widths) {
+            let header_bc =
+                BoxConstraints::new(Size::new(*width, 0.0), Size::new(*width, f64::INFINITY));
+            let size = header.layout(ctx, &header_bc);
+            ctx.place_child(header, Point::new(x, 0.0));
+            header_height = header_height.max(size.height);
+            x += width;
+        }
+        self.header_height = header_height;
+
+        let content_height = header_height + self.row_count as f64 * self.row_height;
+        let size = bc.constrain(Size::new(total_width, content_height));
+        self.viewport_height = self.viewport_height(size);
+        self.scroll_offset = self.scroll_offset.clamp(0.0, self.max_scroll_offset(size));
+
+        // Rows are requested before they are scrolled into view, once fewer than half of the
+        // overscan rows are loaded on either side of the visible ones.
+        let visible = self.visible_rows(self.scroll_offset);
+        let needed = visible
+            .start
+            .saturating_sub(OVERSCAN / 2)
+            .min(self.row_count)
+            ..(visible.end + OVERSCAN / 2).min(self.row_count);
+        let loaded = self.loaded_rows();
+        if needed.is_empty() || (needed.start >= loaded.start && needed.end <= loaded.end) {
+            self.requested = None;
+        } else {
+            let wanted = self.rows_to_load(self.row_count);
+            if self.requested.as_ref() != Some(&wanted) {
+                trace!("Table {:?} requested rows {:?}", ctx.widget_id(), wanted);
+                self.requested = Some(wanted.clone());
+                ctx.submit_action(Action::TableRowsRequested(wanted));
+            }
+        }
+
+        // Only the rows which are scrolled into view are laid out.
+        let row_bc = BoxConstraints::tight(Size::new(total_width, self.row_height));
+        for (idx, row) in (self.first_loaded..).zip(&mut self.rows) {
+            let visible = visible.contains(&idx);
+            ctx.set_stashed(row, !visible);
+            if !visible {
+                ctx.skip_layout(row);
+                continue;
+            }
+            let y = header_height + idx as f64 * self.row_height - self.scroll_offset;
+            {
+                // The row is always laid out below, so it doesn't need to request layout.
+                let mut row = ctx.get_raw_mut(row);
+                row.widget().widths.clone_from(&widths);
+                row.widget().hidden_top = (header_height - y).max(0.0);
+            }
+            row.layout(ctx, &row_bc);
+            ctx.place_child(row, Point::new(0.0, y));
+        }
+
+        ctx.set_clip_path(size.to_rect());
+        trace!("Computed layout: size={}", size);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let size = ctx.size();
+        let header_rect = Rect::new(0.0, 0.0, size.width, self.header_height);
+        fill_color(scene, &header_rect, theme::BACKGROUND_LIGHT);
+        stroke(
+            scene,
+            &Line::new(
+                (0.0, self.header_height - 0.5),
+                (size.width, self.header_height - 0.5),
+            ),
+            theme::BORDER_DARK,
+            1.0,
+        );
+        for edge in self.column_edges() {
+            let edge = edge - 0.5;
+            stroke(
+                scene,
+                &Line::new((edge, 0.0), (edge, self.header_height)),
+                theme::BORDER_DARK,
+                1.0,
+            );
+        }
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::Table
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        ctx.current_node().set_row_count(self.row_count);
+        ctx.current_node().set_column_count(self.columns.len());
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        // The headers are after the rows, so that they are painted on top of them.
+        self.rows
+            .iter()
+            .map(|row| row.id())
+            .chain(self.headers.iter().map(|header| header.id()))
+            .collect()
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("Table")
+    }
+}
+
+impl Widget for TableRow {
+    fn on_pointer_event(&mut self, _ctx: &mut EventCtx, _event: &PointerEvent) {}
+
+    fn on_text_event(&mut self, _ctx: &mut EventCtx, _event: &TextEvent) {}
+
+    fn on_access_event(&mut self, _ctx: &mut EventCtx, _event: &AccessEvent) {}
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        for cell in &mut self.cells {
+            cell.lifecycle(ctx, event);
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let size = bc.max();
+        let mut x = 0.0;
+        for (idx, cell) in self.cells.iter_mut().enumerate() {
+            // Cells without a column are still laid out, but they have no space.
+            let width = self.widths.get(idx).copied().unwrap_or(0.0);
+            let cell_bc = BoxConstraints::new(
+                Size::ZERO,
+                Size::new((width - 2.0 * CELL_PADDING).max(0.0), size.height),
+            );
+            let cell_size = cell.layout(ctx, &cell_bc);
+            let y = (size.height - cell_size.height) / 2.0;
+            ctx.place_child(cell, Point::new(x + CELL_PADDING, y));
+            x += width;
+        }
+        // The part of the row which is under the column headers is hidden.
+        ctx.set_clip_path(Rect::new(0.0, self.hidden_top, size.width, size.height));
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        if self.selected {
+            let size = ctx.size();
+            let rect = Rect::new(0.0, self.hidden_top, size.width, size.height);
+            fill_color(scene, &rect, theme::SELECTED_TEXT_BACKGROUND_COLOR);
+        }
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::Row
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        ctx.current_node().set_selected(self.selected);
+        ctx.current_node()
+            .set_default_action_verb(accesskit::DefaultActionVerb::Click);
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        self.cells.iter().map(|cell| cell.id()).collect()
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("TableRow")
+    }
+}
+
+impl Widget for TableHeader {
+    fn on_pointer_event(&mut self, _ctx: &mut EventCtx, _event: &PointerEvent) {}
+
+    fn on_text_event(&mut self, _ctx: &mut EventCtx, _event: &TextEvent) {}
+
+    fn on_access_event(&mut self, _ctx: &mut EventCtx, _event: &AccessEvent) {}
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        self.label.lifecycle(ctx, event);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let width = bc.max().width;
+        let label_width = (width - 3.0 * CELL_PADDING - SORT_INDICATOR_SIZE).max(0.0);
+        let label_bc = BoxConstraints::new(Size::ZERO, Size::new(label_width, f64::INFINITY));
+        let label_size = self.label.layout(ctx, &label_bc);
+        let size = bc.constrain(Size::new(width, label_size.height + 2.0 * CELL_PADDING));
+        ctx.place_child(&mut self.label, Point::new(CELL_PADDING, CELL_PADDING));
+        // Long titles are cut off.
+        ctx.set_clip_path(size.to_rect());
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let Some(sort) = self.sort else {
+            return;
+        };
+        let size = ctx.size();
+        let x = size.width - CELL_PADDING - SORT_INDICATOR_SIZE;
+        let y = (size.height - SORT_INDICATOR_SIZE / 2.0) / 2.0;
+        let (tip, base) = match sort {
+            SortDirection::Ascending => (y, y + SORT_INDICATOR_SIZE / 2.0),
+            SortDirection::Descending => (y + SORT_INDICATOR_SIZE / 2.0, y),
+        };
+        let mut triangle = BezPath::new();
+        triangle.move_to((x, base));
+        triangle.line_to((x + SORT_INDICATOR_SIZE / 2.0, tip));
+        triangle.line_to((x + SORT_INDICATOR_SIZE, base));
+        triangle.close_path();
+        fill_color(scene, &triangle, theme::FOREGROUND_DARK);
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::ColumnHeader
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        if let Some(sort) = self.sort {
+            ctx.current_node().set_sort_direction(match sort {
+                SortDirection::Ascending => accesskit::SortDirection::Ascending,
+                SortDirection::Descending => accesskit::SortDirection::Descending,
+            });
+        }
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        smallvec::smallvec![self.label.id()]
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("TableHeader")
+    }
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{widget_ids, TestHarness};
+    use crate::Vec2;
+
+    fn table(rows: usize) -> Table {
+        let mut table = Table::new([
+            TableColumn::new("Name").sortable(true),
+            TableColumn::new("Size"),
+        ]);
+        for idx in 0..rows {
+            let row = TableRow::new([])
+                .with_cell(Label::new(format!("file{idx}")))
+                .with_cell(Label::new("4 KiB"));
+            table = table.with_row(row);
+        }
+        table
+    }
+
+    fn click(harness: &mut TestHarness, pos: impl Into<Point>) {
+        harness.mouse_move(pos);
+        harness.mouse_button_press(PointerButton::Primary);
+        harness.mouse_button_release(PointerButton::Primary);
+    }
+
+    #[test]
+    fn select_row() {
+        let [row_id] = widget_ids();
+        let widget = table(2).with_row_pod(WidgetPod::new_with_id(
+            TableRow::new([]).with_cell(Label::new("file2")),
+            row_id,
+        ));
+        let mut harness = TestHarness::create(widget);
+
+        harness.mouse_click_on(row_id);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::TableRowSelected(2), harness.root_widget().id()))
+        );
+        let row = harness.get_widget(row_id);
+        assert!(row.downcast::<TableRow>().unwrap().is_selected());
+        assert_eq!(
+            harness
+                .root_widget()
+                .downcast::<Table>()
+                .unwrap()
+                .selected_row(),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn sort_by_header() {
+        let mut harness = TestHarness::create(table(2));
+        let table_id = harness.root_widget().id();
+
+        click(&mut harness, (20.0, 4.0));
+        assert_eq!(
+            harness.pop_action(),
+            Some((
+                Action::TableSortChanged(0, SortDirection::Ascending),
+                table_id
+            ))
+        );
+        click(&mut harness, (20.0, 4.0));
+        assert_eq!(
+            harness.pop_action(),
+            Some((
+                Action::TableSortChanged(0, SortDirection::Descending),
+                table_id
+            ))
+        );
+
+        // The second column isn't sortable.
+        click(&mut harness, (120.0, 4.0));
+        assert_eq!(harness.pop_action(), None);
+    }
+
+    #[test]
+    fn resize_column() {
+        let mut harness = TestHarness::create(table(2));
+        let table_id = harness.root_widget().id();
+
+        harness.mouse_move((100.0, 4.0));
+        harness.mouse_button_press(PointerButton::Primary);
+        harness.mouse_move((150.0, 4.0));
+        harness.mouse_button_release(PointerButton::Primary);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::TableColumnResized(0, 150.0), table_id))
+        );
+        let table = harness.root_widget();
+        let table = table.downcast::<Table>().unwrap();
+        assert_eq!(table.columns()[0].get_width(), 150.0);
+    }
+
+    #[test]
+    fn move_column() {
+        let mut harness = TestHarness::create(table(2).reorderable(true));
+        let table_id = harness.root_widget().id();
+
+        harness.mouse_move((20.0, 4.0));
+        harness.mouse_button_press(PointerButton::Primary);
+        harness.mouse_move((150.0, 4.0));
+        harness.mouse_button_release(PointerButton::Primary);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::TableColumnMoved(0, 1), table_id))
+        );
+        assert_eq!(harness.pop_action(), None);
+    }
+
+    #[test]
+    fn rows_outside_viewport_are_stashed() {
+        let [first, last] = widget_ids();
+        let widget = table(0)
+            .with_row_pod(WidgetPod::new_with_id(TableRow::new([]), first))
+            .with_row(TableRow::new([]));
+        let widget = (0..100).fold(widget, |table, _| table.with_row(TableRow::new([])));
+        let widget = widget.with_row_pod(WidgetPod::new_with_id(TableRow::new([]), last));
+        let mut harness = TestHarness::create_with_size(widget, Size::new(200.0, 200.0));
+
+        assert!(!harness.get_widget(first).state().is_stashed);
+        assert!(harness.get_widget(last).state().is_stashed);
+
+        harness.mouse_move((50.0, 100.0));
+        harness.mouse_wheel(Vec2::new(0.0, -1000.0));
+        assert!(harness.get_widget(first).state().is_stashed);
+        assert!(!harness.get_widget(last).state().is_stashed);
+    }
+
+    #[test]
+    fn unloaded_rows_are_requested() {
+        let widget = table(0).with_unloaded_rows(1000);
+        let mut harness = TestHarness::create_with_size(widget, Size::new(200.0, 200.0));
+        let table_id = harness.root_widget().id();
+        let rows_to_load = |harness: &TestHarness| {
+            let table = harness.root_widget();
+            table.downcast::<Table>().unwrap().rows_to_load(1000)
+        };
+
+        let wanted = rows_to_load(&harness);
+        assert_eq!(wanted.start, 0);
+        assert!(wanted.end > 8 && wanted.end < 20);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::TableRowsRequested(wanted.clone()), table_id))
+        );
+        assert_eq!(harness.pop_action(), None);
+
+        let load = |harness: &mut TestHarness, rows: Range<usize>| {
+            harness.edit_widget(table_id, |mut table| {
+                let mut table = table.downcast::<Table>();
+                for idx in table.widget.loaded_rows().rev() {
+                    table.remove_row(idx);
+                }
+                for idx in rows {
+                    table.insert_row_pod(idx, WidgetPod::new(TableRow::new([])));
+                }
+                table.set_row_count(1000);
+            });
+        };
+        load(&mut harness, wanted.clone());
+        assert_eq!(harness.pop_action(), None);
+
+        // Scrolling a few rows uses the overscan rows.
+        harness.mouse_move((50.0, 100.0));
+        harness.mouse_wheel(Vec2::new(0.0, -5.0));
+        assert_eq!(harness.pop_action(), None);
+
+        harness.mouse_wheel(Vec2::new(0.0, -100.0));
+        let wanted = rows_to_load(&harness);
+        assert!(wanted.start > 0);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::TableRowsRequested(wanted.clone()), table_id))
+        );
+        load(&mut harness, wanted.clone());
+        let table = harness.root_widget();
+        let table = table.downcast::<Table>().unwrap();
+        assert_eq!(table.loaded_rows(), wanted);
+        assert_eq!(table.row_count(), 1000);
+        assert_eq!(harness.pop_action(), None);
+    }
+}
@@ -0,0 +1,1041 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A container which shows one of several pages, selected using a strip of tabs.
+
+use accesskit::{DefaultActionVerb, Role};
+use smallvec::{smallvec, SmallVec};
+use tracing::{trace, trace_span, Span};
+use vello::kurbo::{Line, Vec2};
+use vello::Scene;
+use winit::keyboard::{KeyCode, ModifiersState, PhysicalKey};
+
+use crate::action::Action;
+use crate::event::PointerButton;
+use crate::paint_scene_helpers::{fill_color, stroke};
+use crate::widget::{Label, WidgetMut};
+use crate::{
+    theme, AccessCtx, AccessEvent, ArcStr, BoxConstraints, CursorIcon, EventCtx, Insets, LayoutCtx,
+    LifeCycle, LifeCycleCtx, PaintCtx, Point, PointerEvent, Rect, Size, StatusChange, TextEvent,
+    Widget, WidgetId, WidgetPod,
+};
+
+const TAB_INSETS: Insets = Insets::uniform_xy(8., 4.);
+const CLOSE_BUTTON_SIZE: f64 = 12.;
+const SELECTED_INDICATOR_WIDTH: f64 = 2.;
+/// How far the pointer has to move before pressing a tab turns into dragging it.
+const DRAG_THRESHOLD: f64 = 4.;
+
+/// A container with a strip of tabs, which shows the page of the selected tab.
+///
+/// Only the selected page is laid out and painted. The other pages are stashed,
+/// which means they keep their state until they are selected again.
+///
+/// The selected tab can be changed by clicking on a tab, by pressing Ctrl+Tab and
+/// Ctrl+Shift+Tab, or with the arrow keys whilst the tabs are focused.
+/// This emits an [`Action::TabSelected`].
+///
+/// Closing and reordering tabs is left to the owner of the widget: clicking on the close button
+/// of a closable tab (or middle-clicking it) emits an [`Action::TabCloseRequested`], and
+/// dragging a tab of a reorderable `Tabs` onto another emits an [`Action::TabMoved`].
+/// These can be applied using [`WidgetMut::remove_tab`](WidgetMut#method.remove_tab)
+/// and [`WidgetMut::move_tab`](WidgetMut#method.move_tab).
+pub struct Tabs {
+    strip: WidgetPod<TabStrip>,
+    pages: Vec<WidgetPod<TabPanel>>,
+    selected: usize,
+    reorderable: bool,
+    press: Option<TabPress>,
+}
+
+/// A page of [`Tabs`].
+///
+/// This wraps the widget passed to `Tabs`, to expose it as a tab panel to accessibility.
+pub struct TabPanel {
+    child: WidgetPod<Box<dyn Widget>>,
+}
+
+/// The row of tabs at the top of [`Tabs`].
+struct TabStrip {
+    tabs: Vec<WidgetPod<TabButton>>,
+    /// The layout rect of each tab, which is also its rect in the parent `Tabs`.
+    tab_rects: Vec<Rect>,
+    close_rects: Vec<Option<Rect>>,
+}
+
+struct TabButton {
+    label: WidgetPod<Label>,
+    selected: bool,
+    closable: bool,
+}
+
+/// A pointer button which was pressed on a tab.
+struct TabPress {
+    index: usize,
+    button: PointerButton,
+    on_close_button: bool,
+    start_x: f64,
+    dragging: bool,
+}
+
+// --- MARK: BUILDERS ---
+impl Tabs {
+    /// Create a new `Tabs` without any tabs.
+    pub fn new() -> Self {
+        Tabs {
+            strip: WidgetPod::new(TabStrip {
+                tabs: Vec::new(),
+                tab_rects: Vec::new(),
+                close_rects: Vec::new(),
+            }),
+            pages: Vec::new(),
+            selected: 0,
+            reorderable: false,
+            press: None,
+        }
+    }
+
+    /// Builder-style method to add a tab.
+    pub fn with_tab(self, title: impl Into<ArcStr>, page: impl Widget) -> Self {
+        self.with_tab_pod(title, WidgetPod::new(Box::new(page)), false)
+    }
+
+    /// Builder-style method to add a tab which can be closed by the user.
+    pub fn with_closable_tab(self, title: impl Into<ArcStr>, page: impl Widget) -> Self {
+        self.with_tab_pod(title, WidgetPod::new(Box::new(page)), true)
+    }
+
+    /// Builder-style method to add a tab with a page which is already in a [`WidgetPod`].
+    pub fn with_tab_pod(
+        mut self,
+        title: impl Into<ArcStr>,
+        page: WidgetPod<Box<dyn Widget>>,
+        closable: bool,
+    ) -> Self {
+        let index = self.pages.len();
+        let tab = TabButton::new(title.into(), index == self.selected, closable);
+        self.strip_widget().tabs.push(WidgetPod::new(tab));
+        self.pages.push(WidgetPod::new(TabPanel { child: page }));
+        self
+    }
+
+    /// Builder-style method to set which tab is selected.
+    pub fn selected(mut self, index: usize) -> Self {
+        self.selected = index;
+        for (i, tab) in self.strip_widget().tabs.iter_mut().enumerate() {
+            tab.created_widget_mut().unwrap().selected = i == index;
+        }
+        self
+    }
+
+    /// Builder-style method to set whether tabs can be reordered by dragging them.
+    ///
+    /// This is `false` by default.
+    pub fn reorderable(mut self, reorderable: bool) -> Self {
+        self.reorderable = reorderable;
+        self
+    }
+
+    /// The index of the selected tab.
+    pub fn selected_index(&self) -> usize {
+        self.selected
+    }
+
+    /// The number of tabs.
+    pub fn tab_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    fn strip_widget(&mut self) -> &mut TabStrip {
+        self.strip
+            .created_widget_mut()
+            .expect("Tabs builder methods can't be used after the widget was added")
+    }
+}
+
+impl Default for Tabs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TabButton {
+    fn new(title: ArcStr, selected: bool, closable: bool) -> Self {
+        TabButton {
+            label: WidgetPod::new(Label::new(title).with_skip_pointer(true)),
+            selected,
+            closable,
+        }
+    }
+
+    /// The rect of the close button, in the tab's coordinate space.
+    fn close_rect(&self, size: Size) -> Option<Rect> {
+        if !self.closable {
+            return None;
+        }
+        let origin = Point::new(
+            size.width - TAB_INSETS.x1 - CLOSE_BUTTON_SIZE,
+            (size.height - CLOSE_BUTTON_SIZE) / 2.,
+        );
+        Some(Rect::from_origin_size(
+            origin,
+            Size::new(CLOSE_BUTTON_SIZE, CLOSE_BUTTON_SIZE),
+        ))
+    }
+}
+
+impl TabStrip {
+    /// The index of the tab at `pos`, and whether `pos` is on its close button.
+    fn hit_test(&self, pos: Point) -> Option<(usize, bool)> {
+        let index = self.tab_rects.iter().position(|rect| rect.contains(pos))?;
+        let on_close_button = self.close_rects[index].is_some_and(|rect| rect.contains(pos));
+        Some((index, on_close_button))
+    }
+
+    /// The index a tab dropped at `x` should be moved to.
+    fn drop_index(&self, x: f64) -> usize {
+        self.tab_rects
+            .iter()
+            .position(|rect| x < rect.x1)
+            .unwrap_or(self.tab_rects.len().saturating_sub(1))
+    }
+}
+
+// --- MARK: WIDGETMUT ---
+impl WidgetMut<'_, Tabs> {
+    /// Add a tab at the end.
+    pub fn add_tab(&mut self, title: impl Into<ArcStr>, page: impl Widget) {
+        let index = self.widget.pages.len();
+        self.insert_tab_pod(index, title, WidgetPod::new(Box::new(page)), false);
+    }
+
+    /// Insert a tab at `index`.
+    ///
+    /// The selected page stays the same, so the selected index is increased
+    /// if the tab is inserted before the selected tab.
+    pub fn insert_tab(&mut self, index: usize, title: impl Into<ArcStr>, page: impl Widget) {
+        self.insert_tab_pod(index, title, WidgetPod::new(Box::new(page)), false);
+    }
+
+    /// Insert a tab with a page which is already in a [`WidgetPod`] at `index`.
+    ///
+    /// See [`insert_tab`](Self::insert_tab) for details.
+    pub fn insert_tab_pod(
+        &mut self,
+        index: usize,
+        title: impl Into<ArcStr>,
+        page: WidgetPod<Box<dyn Widget>>,
+        closable: bool,
+    ) {
+        let had_tabs = !self.widget.pages.is_empty();
+        if had_tabs && index <= self.widget.selected {
+            self.widget.selected += 1;
+        }
+        self.widget
+            .pages
+            .insert(index, WidgetPod::new(TabPanel { child: page }));
+        let tab = TabButton::new(title.into(), !had_tabs, closable);
+        self.strip_mut().insert_tab(index, tab);
+        self.ctx.children_changed();
+    }
+
+    /// Remove the tab at `index`.
+    ///
+    /// If the selected tab is removed, the next tab (or the previous one, if it was the last tab)
+    /// is selected.
+    pub fn remove_tab(&mut self, index: usize) {
+        let page = self.widget.pages.remove(index);
+        self.ctx.remove_child(page);
+        self.strip_mut().remove_tab(index);
+        self.ctx.children_changed();
+
+        let count = self.widget.pages.len();
+        if index < self.widget.selected {
+            self.widget.selected -= 1;
+        } else if index == self.widget.selected && count > 0 {
+            let selected = self.widget.selected.min(count - 1);
+            self.widget.selected = selected;
+            self.strip_mut().set_tab_selected(selected, true);
+        }
+    }
+
+    /// Move the tab at index `from` to index `to`.
+    ///
+    /// The selected page stays the same.
+    pub fn move_tab(&mut self, from: usize, to: usize) {
+        let page = self.widget.pages.remove(from);
+        self.widget.pages.insert(to, page);
+        self.strip_mut().move_tab(from, to);
+        self.ctx.children_changed();
+
+        let selected = self.widget.selected;
+        self.widget.selected = if selected == from {
+            to
+        } else if from < selected && selected <= to {
+            selected - 1
+        } else if to <= selected && selected < from {
+            selected + 1
+        } else {
+            selected
+        };
+    }
+
+    /// Select the tab at `index`, and show its page.
+    ///
+    /// If `index` is past the last tab, the last tab is selected.
+    /// This doesn't emit an [`Action::TabSelected`].
+    pub fn set_selected(&mut self, index: usize) {
+        let count = self.widget.pages.len();
+        let index = index.min(count.saturating_sub(1));
+        let previous = self.widget.selected;
+        if index == previous {
+            return;
+        }
+        self.widget.selected = index;
+        let mut strip = self.strip_mut();
+        if previous < count {
+            strip.set_tab_selected(previous, false);
+        }
+        if index < count {
+            strip.set_tab_selected(index, true);
+        }
+        drop(strip);
+        self.ctx.request_layout();
+    }
+
+    /// Set the title of the tab at `index`.
+    pub fn set_title(&mut self, index: usize, title: impl Into<ArcStr>) {
+        self.strip_mut()
+            .tab_mut(index)
+            .label_mut()
+            .set_text(title.into());
+    }
+
+    /// Set whether the tab at `index` can be closed by the user.
+    pub fn set_closable(&mut self, index: usize, closable: bool) {
+        self.strip_mut().tab_mut(index).set_closable(closable);
+    }
+
+    /// Set whether tabs can be reordered by dragging them.
+    pub fn set_reorderable(&mut self, reorderable: bool) {
+        self.widget.reorderable = reorderable;
+    }
+
+    /// Get a mutable reference to the page of the tab at `index`.
+    ///
+    /// The page widget itself can be accessed using [`WidgetMut<TabPanel>::child_mut`](WidgetMut#method.child_mut-2).
+    pub fn page_mut(&mut self, index: usize) -> WidgetMut<'_, TabPanel> {
+        self.ctx.get_mut(&mut self.widget.pages[index])
+    }
+
+    fn strip_mut(&mut self) -> WidgetMut<'_, TabStrip> {
+        self.ctx.get_mut(&mut self.widget.strip)
+    }
+}
+
+impl WidgetMut<'_, TabPanel> {
+    pub fn child_mut(&mut self) -> WidgetMut<'_, Box<dyn Widget>> {
+        self.ctx.get_mut(&mut self.widget.child)
+    }
+}
+
+impl WidgetMut<'_, TabStrip> {
+    fn tab_mut(&mut self, index: usize) -> WidgetMut<'_, TabButton> {
+        self.ctx.get_mut(&mut self.widget.tabs[index])
+    }
+
+    /// Set whether the tab at `index` is selected, even if it was only just inserted.
+    fn set_tab_selected(&mut self, index: usize, selected: bool) {
+        if let Some(tab) = self.widget.tabs[index].created_widget_mut() {
+            tab.selected = selected;
+            return;
+        }
+        self.tab_mut(index).set_selected(selected);
+    }
+
+    fn insert_tab(&mut self, index: usize, tab: TabButton) {
+        self.widget.tabs.insert(index, WidgetPod::new(tab));
+        self.ctx.children_changed();
+    }
+
+    fn remove_tab(&mut self, index: usize) {
+        let tab = self.widget.tabs.remove(index);
+        self.ctx.remove_child(tab);
+        self.ctx.request_layout();
+    }
+
+    fn move_tab(&mut self, from: usize, to: usize) {
+        let tab = self.widget.tabs.remove(from);
+        self.widget.tabs.insert(to, tab);
+        self.ctx.children_changed();
+    }
+}
+
+impl WidgetMut<'_, TabButton> {
+    fn set_selected(&mut self, selected: bool) {
+        if self.widget.selected != selected {
+            self.widget.selected = selected;
+            self.ctx.request_paint();
+            self.ctx.request_accessibility_update();
+        }
+    }
+
+    fn set_closable(&mut self, closable: bool) {
+        if self.widget.closable != closable {
+            self.widget.closable = closable;
+            self.ctx.request_layout();
+        }
+    }
+
+    fn label_mut(&mut self) -> WidgetMut<'_, Label> {
+        self.ctx.get_mut(&mut self.widget.label)
+    }
+}
+
+// --- MARK: IMPL TABS ---
+impl Tabs {
+    /// Select the tab at `index` in response to user input.
+    fn select(&mut self, ctx: &mut EventCtx, index: usize) {
+        if index == self.selected {
+            return;
+        }
+        trace!("Tabs {:?} selected tab {}", ctx.widget_id(), index);
+        ctx.submit_action(Action::TabSelected(index));
+        ctx.mutate_self_later(move |mut tabs| {
+            tabs.downcast::<Tabs>().set_selected(index);
+        });
+    }
+
+    /// The index of the tab which pressing the key `code` selects.
+    ///
+    /// Ctrl+Tab works anywhere inside the tabs, the other keys only when the tab strip is focused.
+    fn key_index(&self, code: KeyCode, mods: ModifiersState, focused: bool) -> Option<usize> {
+        let count = self.pages.len();
+        if count == 0 {
+            return None;
+        }
+        let next = (self.selected + 1) % count;
+        let previous = (self.selected + count - 1) % count;
+        Some(match code {
+            KeyCode::Tab if mods.control_key() && mods.shift_key() => previous,
+            KeyCode::Tab if mods.control_key() => next,
+            KeyCode::ArrowRight if focused => next,
+            KeyCode::ArrowLeft if focused => previous,
+            KeyCode::Home if focused => 0,
+            KeyCode::End if focused => count - 1,
+            _ => return None,
+        })
+    }
+
+    fn strip_hit_test(&mut self, ctx: &mut EventCtx, pos: Point) -> Option<(usize, bool)> {
+        ctx.get_raw_ref(&mut self.strip).widget().hit_test(pos)
+    }
+}
+
+// --- MARK: IMPL WIDGET ---
+impl Widget for Tabs {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        match event {
+            PointerEvent::PointerDown(button, state) => {
+                if ctx.is_disabled() || self.press.is_some() {
+                    return;
+                }
+                let pos = ctx.to_local(Point::new(state.position.x, state.position.y));
+                let Some((index, on_close_button)) = self.strip_hit_test(ctx, pos) else {
+                    return;
+                };
+                match button {
+                    PointerButton::Primary => {
+                        if !on_close_button {
+                            self.select(ctx, index);
+                        }
+                        ctx.request_focus();
+                    }
+                    PointerButton::Auxiliary => {}
+                    _ => return,
+                }
+                ctx.set_handled();
+                ctx.set_active(true);
+                self.press = Some(TabPress {
+                    index,
+                    button: *button,
+                    on_close_button,
+                    start_x: pos.x,
+                    dragging: false,
+                });
+            }
+            PointerEvent::PointerMove(state) => {
+                let Some(press) = &mut self.press else {
+                    return;
+                };
+                let x = ctx
+                    .to_local(Point::new(state.position.x, state.position.y))
+                    .x;
+                if self.reorderable
+                    && press.button == PointerButton::Primary
+                    && !press.on_close_button
+                    && !press.dragging
+                    && (x - press.start_x).abs() > DRAG_THRESHOLD
+                {
+                    press.dragging = true;
+                    ctx.set_cursor(&CursorIcon::Grabbing);
+                }
+            }
+            PointerEvent::PointerUp(button, state) => {
+                if !self
+                    .press
+                    .as_ref()
+                    .is_some_and(|press| press.button == *button)
+                {
+                    return;
+                }
+                let Some(press) = self.press.take() else {
+                    return;
+                };
+                ctx.set_handled();
+                ctx.set_active(false);
+                let pos = ctx.to_local(Point::new(state.position.x, state.position.y));
+                if press.dragging {
+                    ctx.clear_cursor();
+                    let to = ctx.get_raw_ref(&mut self.strip).widget().drop_index(pos.x);
+                    if to != press.index {
+                        trace!(
+                            "Tabs {:?} moved tab {} to {}",
+                            ctx.widget_id(),
+                            press.index,
+                            to
+                        );
+                        ctx.submit_action(Action::TabMoved(press.index, to));
+                    }
+                    return;
+                }
+                let Some((index, on_close_button)) = self.strip_hit_test(ctx, pos) else {
+                    return;
+                };
+                let closable =
+                    ctx.get_raw_ref(&mut self.strip).widget().close_rects[index].is_some();
+                let close = match press.button {
+                    PointerButton::Primary => press.on_close_button && on_close_button,
+                    _ => closable,
+                };
+                if close && index == press.index {
+                    trace!("Tabs {:?} requested closing tab {}", ctx.widget_id(), index);
+                    ctx.submit_action(Action::TabCloseRequested(index));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        let TextEvent::KeyboardKey(key, mods) = event else {
+            return;
+        };
+        if !key.state.is_pressed() {
+            return;
+        }
+        let PhysicalKey::Code(code) = key.physical_key else {
+            return;
+        };
+        let Some(index) = self.key_index(code, *mods, ctx.is_focused()) else {
+            return;
+        };
+        ctx.set_handled();
+        self.select(ctx, index);
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        if !matches!(event.action, accesskit::Action::Default) {
+            return;
+        }
+        let index = ctx
+            .get_raw_ref(&mut self.strip)
+            .widget()
+            .tabs
+            .iter()
+            .position(|tab| tab.id() == event.target);
+        if let Some(index) = index {
+            ctx.set_handled();
+            self.select(ctx, index);
+        }
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        if let LifeCycle::BuildFocusChain = event {
+            ctx.register_for_focus();
+        }
+        self.strip.lifecycle(ctx, event);
+        for page in &mut self.pages {
+            page.lifecycle(ctx, event);
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let strip_bc = BoxConstraints::new(
+            Size::new(bc.min().width, 0.0),
+            Size::new(bc.max().width, bc.max().height),
+        );
+        let strip_size = self.strip.layout(ctx, &strip_bc);
+        ctx.place_child(&mut self.strip, Point::ZERO);
+
+        let page_bc = BoxConstraints::new(
+            Size::new(
+                bc.min().width,
+                (bc.min().height - strip_size.height).max(0.0),
+            ),
+            Size::new(
+                bc.max().width,
+                (bc.max().height - strip_size.height).max(0.0),
+            ),
+        );
+        let mut page_size = Size::ZERO;
+        for (index, page) in self.pages.iter_mut().enumerate() {
+            let selected = index == self.selected;
+            ctx.set_stashed(page, !selected);
+            if selected {
+                page_size = page.layout(ctx, &page_bc);
+                ctx.place_child(page, Point::new(0.0, strip_size.height));
+            } else {
+                ctx.skip_layout(page);
+            }
+        }
+
+        let size = bc.constrain(Size::new(
+            strip_size.width.max(page_size.width),
+            strip_size.height + page_size.height,
+        ));
+        trace!("Computed layout: size={}", size);
+        size
+    }
+
+    fn paint(&mut self, _ctx: &mut PaintCtx, _scene: &mut Scene) {}
+
+    fn accessibility_role(&self) -> Role {
+        Role::GenericContainer
+    }
+
+    fn accessibility(&mut self, _ctx: &mut AccessCtx) {}
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        let mut ids: SmallVec<_> = smallvec![self.strip.id()];
+        ids.extend(self.pages.iter().map(|page| page.id()));
+        ids
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("Tabs")
+    }
+}
+
+impl Widget for TabPanel {
+    fn on_pointer_event(&mut self, _ctx: &mut EventCtx, _event: &PointerEvent) {}
+
+    fn on_text_event(&mut self, _ctx: &mut EventCtx, _event: &TextEvent) {}
+
+    fn on_access_event(&mut self, _ctx: &mut EventCtx, _event: &AccessEvent) {}
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        self.child.lifecycle(ctx, event);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let size = self.child.layout(ctx, bc);
+        ctx.place_child(&mut self.child, Point::ZERO);
+        bc.constrain(size)
+    }
+
+    fn paint(&mut self, _ctx: &mut PaintCtx, _scene: &mut Scene) {}
+
+    fn accessibility_role(&self) -> Role {
+        Role::TabPanel
+    }
+
+    fn accessibility(&mut self, _ctx: &mut AccessCtx) {}
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        smallvec![self.child.id()]
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("TabPanel")
+    }
+}
+
+impl Widget for TabStrip {
+    fn on_pointer_event(&mut self, _ctx: &mut EventCtx, _event: &PointerEvent) {}
+
+    fn on_text_event(&mut self, _ctx: &mut EventCtx, _event: &TextEvent) {}
+
+    fn on_access_event(&mut self, _ctx: &mut EventCtx, _event: &AccessEvent) {}
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        for tab in &mut self.tabs {
+            tab.lifecycle(ctx, event);
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let tab_bc = BoxConstraints::new(Size::ZERO, Size::new(f64::INFINITY, bc.max().height));
+        self.tab_rects.clear();
+        self.close_rects.clear();
+        let mut x = 0.0;
+        let mut height: f64 = 0.0;
+        for tab in &mut self.tabs {
+            let size = tab.layout(ctx, &tab_bc);
+            ctx.place_child(tab, Point::new(x, 0.0));
+            let close_rect = ctx.get_raw_ref(tab).widget().close_rect(size);
+            self.tab_rects
+                .push(Rect::from_origin_size(Point::new(x, 0.0), size));
+            self.close_rects
+                .push(close_rect.map(|rect| rect + Vec2::new(x, 0.0)));
+            x += size.width;
+            height = height.max(size.height);
+        }
+
+        let width = if bc.is_width_bounded() {
+            bc.max().width
+        } else {
+            x
+        };
+        let size = bc.constrain(Size::new(width, height));
+        ctx.set_clip_path(size.to_rect());
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let size = ctx.size();
+        fill_color(scene, &size.to_rect(), theme::BACKGROUND_DARK);
+        let bottom = Line::new((0.0, size.height - 0.5), (size.width, size.height - 0.5));
+        stroke(scene, &bottom, theme::BORDER_DARK, 1.0);
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::TabList
+    }
+
+    fn accessibility(&mut self, _ctx: &mut AccessCtx) {}
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        self.tabs.iter().map(|tab| tab.id()).collect()
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("TabStrip")
+    }
+}
+
+impl Widget for TabButton {
+    fn on_pointer_event(&mut self, _ctx: &mut EventCtx, _event: &PointerEvent) {}
+
+    fn on_text_event(&mut self, _ctx: &mut EventCtx, _event: &TextEvent) {}
+
+    fn on_access_event(&mut self, _ctx: &mut EventCtx, _event: &AccessEvent) {}
+
+    fn on_status_change(&mut self, ctx: &mut LifeCycleCtx, _event: &StatusChange) {
+        ctx.request_paint();
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        self.label.lifecycle(ctx, event);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let label_size = self.label.layout(ctx, &bc.loosen());
+        let close_width = if self.closable {
+            theme::WIDGET_CONTROL_COMPONENT_PADDING + CLOSE_BUTTON_SIZE
+        } else {
+            0.0
+        };
+        let content_height = label_size.height.max(CLOSE_BUTTON_SIZE);
+        let size = bc.constrain(Size::new(
+            label_size.width + close_width + TAB_INSETS.x_value(),
+            content_height + TAB_INSETS.y_value(),
+        ));
+        let label_y = (size.height - label_size.height) / 2.0;
+        ctx.place_child(&mut self.label, Point::new(TAB_INSETS.x0, label_y));
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let size = ctx.size();
+        if self.selected {
+            fill_color(scene, &size.to_rect(), theme::BACKGROUND_LIGHT);
+            let indicator = Rect::new(
+                0.0,
+                size.height - SELECTED_INDICATOR_WIDTH,
+                size.width,
+                size.height,
+            );
+            fill_color(scene, &indicator, theme::PRIMARY_LIGHT);
+        } else if ctx.is_hot() && !ctx.is_disabled() {
+            fill_color(scene, &size.to_rect(), theme::BUTTON_LIGHT);
+        }
+
+        if let Some(close_rect) = self.close_rect(size) {
+            let cross = close_rect.inset(-3.0);
+            let color = if ctx.is_disabled() {
+                theme::DISABLED_TEXT_COLOR
+            } else {
+                theme::FOREGROUND_DARK
+            };
+            stroke(
+                scene,
+                &Line::new((cross.x0, cross.y0), (cross.x1, cross.y1)),
+                color,
+                1.5,
+            );
+            stroke(
+                scene,
+                &Line::new((cross.x0, cross.y1), (cross.x1, cross.y0)),
+                color,
+                1.5,
+            );
+        }
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::Tab
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        ctx.current_node().set_selected(self.selected);
+        ctx.current_node()
+            .set_default_action_verb(DefaultActionVerb::Click);
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        smallvec![self.label.id()]
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("TabButton")
+    }
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{widget_ids, TestHarness, TestWidgetExt};
+
+    fn tab_ids(harness: &TestHarness) -> Vec<WidgetId> {
+        let tabs = harness.root_widget();
+        let strip = tabs.children()[0];
+        strip.children().iter().map(|tab| tab.id()).collect()
+    }
+
+    /// Pages are stashed through their parent `TabPanel`.
+    fn is_page_stashed(harness: &TestHarness, page: WidgetId) -> bool {
+        harness
+            .root_widget()
+            .children()
+            .into_iter()
+            .find(|panel| panel.find_widget_by_id(page).is_some())
+            .unwrap()
+            .state()
+            .is_stashed
+    }
+
+    #[test]
+    fn only_selected_page_is_visible() {
+        let [page_1, page_2] = widget_ids();
+        let widget = Tabs::new()
+            .with_tab("One", Label::new("First page").with_id(page_1))
+            .with_tab("Two", Label::new("Second page").with_id(page_2));
+
+        let mut harness = TestHarness::create(widget);
+        assert!(!is_page_stashed(&harness, page_1));
+        assert!(is_page_stashed(&harness, page_2));
+
+        harness.edit_root_widget(|mut tabs| {
+            tabs.downcast::<Tabs>().set_selected(1);
+        });
+        assert!(is_page_stashed(&harness, page_1));
+        assert!(!is_page_stashed(&harness, page_2));
+    }
+
+    #[test]
+    fn click_selects_tab() {
+        let [page_2] = widget_ids();
+        let widget = Tabs::new()
+            .with_tab("One", Label::new("First page"))
+            .with_tab("Two", Label::new("Second page").with_id(page_2));
+
+        let mut harness = TestHarness::create(widget);
+        let tabs = tab_ids(&harness);
+
+        harness.mouse_click_on(tabs[1]);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::TabSelected(1), harness.root_widget().id()))
+        );
+        assert!(!is_page_stashed(&harness, page_2));
+        let selected = harness
+            .root_widget()
+            .downcast::<Tabs>()
+            .unwrap()
+            .selected_index();
+        assert_eq!(selected, 1);
+
+        // Clicking on the selected tab doesn't emit an action.
+        harness.mouse_click_on(tabs[1]);
+        assert_eq!(harness.pop_action(), None);
+    }
+
+    #[test]
+    fn close_and_move_requests() {
+        let widget = Tabs::new()
+            .with_closable_tab("One", Label::new("First page"))
+            .with_tab("Two", Label::new("Second page"))
+            .reorderable(true);
+
+        let mut harness = TestHarness::create(widget);
+        let tabs = tab_ids(&harness);
+        let tabs_id = harness.root_widget().id();
+
+        // Middle-clicking a tab which isn't closable does nothing.
+        harness.mouse_move_to(tabs[1]);
+        harness.mouse_button_press(PointerButton::Auxiliary);
+        harness.mouse_button_release(PointerButton::Auxiliary);
+        assert_eq!(harness.pop_action(), None);
+
+        harness.mouse_move_to(tabs[0]);
+        harness.mouse_button_press(PointerButton::Auxiliary);
+        harness.mouse_button_release(PointerButton::Auxiliary);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::TabCloseRequested(0), tabs_id))
+        );
+
+        // Drag the first tab onto the second one.
+        harness.mouse_move_to(tabs[0]);
+        harness.mouse_button_press(PointerButton::Primary);
+        harness.mouse_move_to(tabs[1]);
+        harness.mouse_button_release(PointerButton::Primary);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::TabMoved(0, 1), tabs_id))
+        );
+
+        // The widget doesn't apply the requests itself.
+        assert_eq!(tab_ids(&harness), tabs);
+    }
+
+    #[test]
+    fn edit_tabs() {
+        let [page_1, page_3] = widget_ids();
+        let widget = Tabs::new()
+            .with_tab("One", Label::new("First page").with_id(page_1))
+            .with_tab("Two", Label::new("Second page"));
+
+        let mut harness = TestHarness::create(widget);
+
+        harness.edit_root_widget(|mut tabs| {
+            let mut tabs = tabs.downcast::<Tabs>();
+            tabs.insert_tab(0, "Three", Label::new("Third page").with_id(page_3));
+            // The previously selected page stays selected.
+            assert_eq!(tabs.widget.selected_index(), 1);
+            tabs.move_tab(1, 2);
+            assert_eq!(tabs.widget.selected_index(), 2);
+            tabs.remove_tab(2);
+            assert_eq!(tabs.widget.selected_index(), 1);
+            assert_eq!(tabs.widget.tab_count(), 2);
+            // A new tab can be selected straight away.
+            tabs.add_tab("Four", Label::new("Fourth page"));
+            tabs.set_selected(2);
+        });
+
+        assert!(harness.try_get_widget(page_1).is_none());
+        assert_eq!(tab_ids(&harness).len(), 3);
+        let selected = harness
+            .root_widget()
+            .downcast::<Tabs>()
+            .unwrap()
+            .selected_index();
+        assert_eq!(selected, 2);
+        assert!(is_page_stashed(&harness, page_3));
+    }
+
+    #[test]
+    fn selecting_past_the_last_tab() {
+        let [page_2] = widget_ids();
+        let widget = Tabs::new()
+            .with_tab("One", Label::new("First page"))
+            .with_tab("Two", Label::new("Second page").with_id(page_2))
+            .with_tab("Three", Label::new("Third page"))
+            .selected(2);
+
+        let mut harness = TestHarness::create(widget);
+        // The owner of the widget removes the selected last tab, but still selects its index.
+        harness.edit_root_widget(|mut tabs| {
+            let mut tabs = tabs.downcast::<Tabs>();
+            tabs.remove_tab(2);
+            tabs.set_selected(2);
+            assert_eq!(tabs.widget.selected_index(), 1);
+        });
+        assert!(!is_page_stashed(&harness, page_2));
+
+        harness.edit_root_widget(|mut tabs| {
+            let mut tabs = tabs.downcast::<Tabs>();
+            tabs.remove_tab(1);
+            tabs.remove_tab(0);
+            tabs.set_selected(3);
+            assert_eq!(tabs.widget.selected_index(), 0);
+        });
+        assert!(tab_ids(&harness).is_empty());
+    }
+
+    fn key_index(harness: &TestHarness, code: KeyCode, mods: ModifiersState) -> Option<usize> {
+        let focused =
+            harness.focused_widget().map(|widget| widget.id()) == Some(harness.root_widget().id());
+        harness
+            .root_widget()
+            .downcast::<Tabs>()
+            .unwrap()
+            .key_index(code, mods, focused)
+    }
+
+    #[test]
+    fn keyboard_navigation() {
+        let widget = Tabs::new()
+            .with_tab("One", Label::new("First page"))
+            .with_tab("Two", Label::new("Second page"))
+            .with_tab("Three", Label::new("Third page"));
+
+        let mut harness = TestHarness::create(widget);
+        let ctrl = ModifiersState::CONTROL;
+        let ctrl_shift = ModifiersState::CONTROL | ModifiersState::SHIFT;
+        let none = ModifiersState::empty();
+
+        // Ctrl+Tab cycles through the tabs even when the strip isn't focused.
+        assert_eq!(key_index(&harness, KeyCode::Tab, ctrl), Some(1));
+        assert_eq!(key_index(&harness, KeyCode::Tab, ctrl_shift), Some(2));
+        assert_eq!(key_index(&harness, KeyCode::Tab, none), None);
+        assert_eq!(key_index(&harness, KeyCode::ArrowRight, none), None);
+        assert_eq!(key_index(&harness, KeyCode::End, none), None);
+
+        let tabs = tab_ids(&harness);
+        harness.mouse_click_on(tabs[1]);
+        assert_eq!(harness.pop_action().unwrap().0, Action::TabSelected(1));
+        assert_eq!(key_index(&harness, KeyCode::ArrowRight, none), Some(2));
+        assert_eq!(key_index(&harness, KeyCode::ArrowLeft, none), Some(0));
+        assert_eq!(key_index(&harness, KeyCode::Home, none), Some(0));
+        assert_eq!(key_index(&harness, KeyCode::End, none), Some(2));
+        assert_eq!(key_index(&harness, KeyCode::Tab, ctrl), Some(2));
+        assert_eq!(key_index(&harness, KeyCode::Space, none), None);
+
+        // The keys wrap around.
+        harness.edit_root_widget(|mut tabs| {
+            tabs.downcast::<Tabs>().set_selected(2);
+        });
+        assert_eq!(key_index(&harness, KeyCode::ArrowRight, none), Some(0));
+        assert_eq!(key_index(&harness, KeyCode::Tab, ctrl), Some(0));
+        assert_eq!(key_index(&harness, KeyCode::Tab, ctrl_shift), Some(1));
+    }
+}
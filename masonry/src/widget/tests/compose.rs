@@ -0,0 +1,100 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tests related to the compose pass and layers.
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use smallvec::smallvec;
+use vello::kurbo::Affine;
+
+use crate::testing::{widget_ids, ModularWidget, TestHarness, TestWidgetExt as _};
+use crate::widget::SizedBox;
+use crate::*;
+
+#[derive(Clone, Default)]
+struct Layer {
+    transform: Rc<Cell<Affine>>,
+    opacity: Rc<Cell<f32>>,
+}
+
+fn make_layer_parent<W: Widget>(child: W, layer: Layer) -> impl Widget {
+    ModularWidget::new((WidgetPod::new(child), layer))
+        .lifecycle_fn(|(child, _), ctx, event| child.lifecycle(ctx, event))
+        .layout_fn(|(child, _), ctx, bc| {
+            child.layout(ctx, &bc.loosen());
+            ctx.place_child(child, Point::ZERO);
+            bc.max()
+        })
+        .compose_fn(|(child, layer), ctx| {
+            ctx.set_child_transform(child, layer.transform.get());
+            ctx.set_child_opacity(child, layer.opacity.get());
+        })
+        .children_fn(|(child, _)| smallvec![child.id()])
+}
+
+#[test]
+fn hit_test_transformed_child() {
+    let [child_id] = widget_ids();
+    let layer = Layer::default();
+    layer.transform.set(Affine::scale(2.0));
+    layer.opacity.set(1.0);
+    let child = SizedBox::empty().width(50.).height(50.).with_id(child_id);
+    let widget = make_layer_parent(child, layer.clone());
+    let mut harness = TestHarness::create_with_size(widget, Size::new(200., 200.));
+
+    let child_rect = harness.get_widget(child_id).state().window_layout_rect();
+    assert_eq!(child_rect, Rect::new(0., 0., 100., 100.));
+
+    // Outside of the child's layout rect, but inside its scaled rect.
+    harness.mouse_move(Point::new(75., 75.));
+    assert!(harness.get_widget(child_id).state().is_hot);
+
+    harness.mouse_move(Point::new(125., 75.));
+    assert!(!harness.get_widget(child_id).state().is_hot);
+
+    // Rotating the child by half a turn moves it to the left of its origin.
+    layer
+        .transform
+        .set(Affine::translate((50., 50.)) * Affine::rotate(std::f64::consts::PI));
+    harness.edit_root_widget(|mut parent| parent.ctx.request_compose());
+    harness.mouse_move(Point::new(10., 10.));
+    assert!(harness.get_widget(child_id).state().is_hot);
+    harness.mouse_move(Point::new(60., 10.));
+    assert!(!harness.get_widget(child_id).state().is_hot);
+}
+
+#[test]
+fn change_layer_without_repaint() {
+    let [child_id] = widget_ids();
+    let paint_count = Rc::new(Cell::new(0));
+    let child = ModularWidget::new(paint_count.clone())
+        .layout_fn(|_, _, _| Size::new(50., 50.))
+        .paint_fn(|paint_count, _, _| paint_count.set(paint_count.get() + 1))
+        .with_id(child_id);
+    let layer = Layer::default();
+    layer.opacity.set(1.0);
+    let widget = make_layer_parent(child, layer.clone());
+    let mut harness = TestHarness::create_with_size(widget, Size::new(200., 200.));
+    harness.paint();
+    assert_eq!(paint_count.get(), 1);
+
+    layer.opacity.set(0.5);
+    harness.edit_root_widget(|mut parent| parent.ctx.request_compose());
+    let damage = harness.paint();
+    assert_eq!(damage.rects(), &[Rect::new(0., 0., 50., 50.)]);
+    assert_eq!(paint_count.get(), 1);
+
+    // Moving the child damages both where it was and where it is now.
+    layer.transform.set(Affine::translate((100., 0.)));
+    harness.edit_root_widget(|mut parent| parent.ctx.request_compose());
+    let damage = harness.paint();
+    assert!(damage.intersects(Rect::new(0., 0., 50., 50.)));
+    assert!(damage.intersects(Rect::new(100., 0., 150., 50.)));
+    assert_eq!(paint_count.get(), 1);
+    assert_eq!(
+        harness.get_widget(child_id).state().window_layout_rect(),
+        Rect::new(100., 0., 150., 50.)
+    );
+}
@@ -0,0 +1,172 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tests related to keyboard focus navigation and the focus ring.
+
+use crate::testing::{widget_ids, ModularWidget, TestHarness};
+use crate::widget::{Flex, FocusScope};
+use crate::*;
+
+fn focusable(tab_index: u32) -> ModularWidget<u32> {
+    ModularWidget::new(tab_index)
+        .lifecycle_fn(|tab_index, ctx, event| match event {
+            LifeCycle::WidgetAdded => ctx.set_tab_index(*tab_index),
+            LifeCycle::BuildFocusChain => ctx.register_for_focus(),
+            _ => {}
+        })
+        .layout_fn(|_, _, _| Size::new(20., 20.))
+}
+
+fn focused_id(harness: &TestHarness) -> Option<WidgetId> {
+    harness.focused_widget().map(|widget| widget.id())
+}
+
+#[test]
+fn tab_order_follows_tab_indices() {
+    let [a, b, c, d] = widget_ids();
+    let widget = Flex::row()
+        .with_child_id(focusable(0), a)
+        .with_child_id(focusable(2), b)
+        .with_child_id(focusable(1), c)
+        .with_child_id(focusable(0), d);
+    let mut harness = TestHarness::create(widget);
+    assert_eq!(focused_id(&harness), None);
+
+    let mut order = Vec::new();
+    for _ in 0..5 {
+        assert!(harness.move_focus(FocusDirection::Next));
+        order.push(focused_id(&harness).unwrap());
+    }
+    assert_eq!(order, [c, b, a, d, c]);
+
+    harness.move_focus(FocusDirection::Previous);
+    assert_eq!(focused_id(&harness), Some(d));
+}
+
+#[test]
+fn focus_scope_keeps_widgets_together() {
+    let [a, b, c, d] = widget_ids();
+    let scope = FocusScope::new(
+        Flex::column()
+            .with_child_id(focusable(2), b)
+            .with_child_id(focusable(1), c),
+    );
+    let widget = Flex::row()
+        .with_child_id(focusable(0), a)
+        .with_child(scope)
+        .with_child_id(focusable(1), d);
+    let mut harness = TestHarness::create(widget);
+
+    let mut order = Vec::new();
+    for _ in 0..4 {
+        harness.move_focus(FocusDirection::Next);
+        order.push(focused_id(&harness).unwrap());
+    }
+    assert_eq!(order, [d, a, c, b]);
+}
+
+#[test]
+fn focus_trap_restores_focus() {
+    let [a, b, c, scope_id] = widget_ids();
+    let scope = FocusScope::new(
+        Flex::column()
+            .with_child_id(focusable(0), b)
+            .with_child_id(focusable(0), c),
+    );
+    let widget = Flex::row()
+        .with_child_id(focusable(0), a)
+        .with_child_id(scope, scope_id);
+    let mut harness = TestHarness::create(widget);
+
+    harness.move_focus(FocusDirection::Next);
+    assert_eq!(focused_id(&harness), Some(a));
+
+    // Opening the trap moves focus into it, and Tab stays inside it.
+    harness.edit_widget(scope_id, |mut scope| {
+        scope.downcast::<FocusScope>().set_trap(true);
+    });
+    assert_eq!(focused_id(&harness), Some(b));
+    harness.move_focus(FocusDirection::Next);
+    assert_eq!(focused_id(&harness), Some(c));
+    harness.move_focus(FocusDirection::Next);
+    assert_eq!(focused_id(&harness), Some(b));
+
+    // Disabling the trap makes its widgets unfocusable, so focus goes back to where it was.
+    harness.edit_widget(scope_id, |mut scope| {
+        scope.ctx.set_disabled(true);
+    });
+    assert_eq!(focused_id(&harness), Some(a));
+    harness.move_focus(FocusDirection::Next);
+    assert_eq!(focused_id(&harness), Some(a));
+}
+
+#[test]
+fn arrow_keys_move_focus_in_directional_scope() {
+    let [top_left, top_right, bottom_left, bottom_right] = widget_ids();
+    let grid = Flex::column()
+        .with_child(
+            Flex::row()
+                .with_child_id(focusable(0), top_left)
+                .with_child_id(focusable(0), top_right),
+        )
+        .with_child(
+            Flex::row()
+                .with_child_id(focusable(0), bottom_left)
+                .with_child_id(focusable(0), bottom_right),
+        );
+    let [scope_id] = widget_ids();
+    let widget = Flex::row().with_child_id(FocusScope::new(grid).directional(true), scope_id);
+    let mut harness = TestHarness::create(widget);
+
+    harness.move_focus(FocusDirection::Next);
+    assert_eq!(focused_id(&harness), Some(top_left));
+
+    assert!(!harness.move_focus(FocusDirection::Left));
+    assert!(harness.move_focus(FocusDirection::Right));
+    assert_eq!(focused_id(&harness), Some(top_right));
+    harness.move_focus(FocusDirection::Down);
+    assert_eq!(focused_id(&harness), Some(bottom_right));
+    harness.move_focus(FocusDirection::Left);
+    assert_eq!(focused_id(&harness), Some(bottom_left));
+    harness.move_focus(FocusDirection::Up);
+    assert_eq!(focused_id(&harness), Some(top_left));
+
+    // Arrow keys don't move focus outside of directional scopes.
+    harness.edit_widget(scope_id, |mut scope| {
+        scope.downcast::<FocusScope>().set_directional(false);
+    });
+    assert!(!harness.move_focus(FocusDirection::Right));
+    assert_eq!(focused_id(&harness), Some(top_left));
+}
+
+#[test]
+fn focus_ring_follows_keyboard_focus() {
+    let [a, b] = widget_ids();
+    let widget = Flex::row()
+        .with_child_id(focusable(0), a)
+        .with_child_id(focusable(0), b);
+    let mut harness = TestHarness::create(widget);
+    harness.paint();
+
+    let ring_extent = theme::FOCUS_RING_OFFSET + 1.5 * theme::FOCUS_RING_WIDTH;
+    let ring_rect = |harness: &TestHarness, id| {
+        let rect = harness.get_widget(id).state().window_layout_rect();
+        rect.inflate(ring_extent, ring_extent)
+    };
+
+    harness.move_focus(FocusDirection::Next);
+    assert_eq!(harness.paint().rects(), &[ring_rect(&harness, a)]);
+
+    // Moving the ring damages both where it was and where it is now.
+    harness.move_focus(FocusDirection::Next);
+    let damage = harness.paint();
+    assert!(damage.intersects(ring_rect(&harness, a)));
+    assert!(damage.intersects(ring_rect(&harness, b)));
+
+    // The ring is hidden as soon as the pointer is used.
+    harness.mouse_move(Point::new(200., 200.));
+    harness.mouse_button_press(PointerButton::Primary);
+    assert_eq!(focused_id(&harness), Some(b));
+    assert_eq!(harness.paint().rects(), &[ring_rect(&harness, b)]);
+    assert!(harness.paint().is_empty());
+}
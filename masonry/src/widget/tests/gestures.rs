@@ -0,0 +1,197 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tests related to timers and pointer gesture recognizers.
+
+use std::time::Duration;
+
+use crate::gesture::{
+    Click, Drag, DragPhase, Gesture, LongPress, PanZoom, LONG_PRESS_DELAY, MULTI_CLICK_INTERVAL,
+};
+use crate::testing::{widget_ids, ModularWidget, TestHarness};
+use crate::widget::{Flex, GestureDetector, SizedBox};
+use crate::*;
+
+/// A harness with a 100x100 gesture detector, and the position of its origin in the window.
+fn detector_harness(
+    detector: impl FnOnce(GestureDetector) -> GestureDetector,
+) -> (TestHarness, WidgetId, Vec2) {
+    let [detector_id] = widget_ids();
+    let detector = detector(GestureDetector::new(
+        SizedBox::empty().width(100.).height(100.),
+    ));
+    let widget = Flex::row()
+        .with_spacer(10.)
+        .with_child_id(detector, detector_id);
+    let harness = TestHarness::create(widget);
+    let origin = harness
+        .get_widget(detector_id)
+        .state()
+        .window_layout_rect()
+        .origin()
+        .to_vec2();
+    (harness, detector_id, origin)
+}
+
+fn gestures(harness: &mut TestHarness) -> Vec<Gesture> {
+    let mut gestures = Vec::new();
+    while let Some((action, _)) = harness.pop_action() {
+        if let Action::Gesture(gesture) = action {
+            gestures.push(gesture);
+        }
+    }
+    gestures
+}
+
+fn clicks(harness: &mut TestHarness) -> Vec<u8> {
+    gestures(harness)
+        .into_iter()
+        .filter_map(|gesture| match gesture {
+            Gesture::Click(Click { count, .. }) => Some(count),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn timers_fire_in_order() {
+    let [widget_id] = widget_ids();
+    let widget = ModularWidget::new(Vec::new())
+        .pointer_event_fn(|tokens, ctx, event| {
+            if let PointerEvent::PointerDown(_, _) = event {
+                tokens.push(ctx.request_timer(Duration::from_millis(200)));
+                tokens.push(ctx.request_timer(Duration::from_millis(100)));
+            }
+        })
+        .timer_fn(|tokens, ctx, token| {
+            // The timer requested last expires first.
+            assert_eq!(Some(token), tokens.pop());
+            ctx.submit_action(Action::Other(Box::new(())));
+        })
+        .layout_fn(|_, _, _| Size::new(20., 20.));
+    let mut harness = TestHarness::create(Flex::row().with_child_id(widget, widget_id));
+
+    harness.mouse_click_on(widget_id);
+    harness.move_timers_forward(Duration::from_millis(99));
+    assert!(harness.pop_action().is_none());
+    harness.move_timers_forward(Duration::from_millis(1));
+    assert!(harness.pop_action().is_some());
+    harness.move_timers_forward(Duration::from_millis(100));
+    assert!(harness.pop_action().is_some());
+    harness.move_timers_forward(Duration::from_secs(10));
+    assert!(harness.pop_action().is_none());
+}
+
+#[test]
+fn click_count_resets_after_interval() {
+    let (mut harness, detector_id, origin) = detector_harness(|detector| detector.clicks(true));
+
+    harness.mouse_double_click_on(detector_id);
+    harness.mouse_click_on(detector_id);
+    assert_eq!(clicks(&mut harness), [1, 2, 3]);
+
+    harness.move_timers_forward(MULTI_CLICK_INTERVAL + Duration::from_millis(1));
+    harness.mouse_click_on(detector_id);
+    assert_eq!(clicks(&mut harness), [1]);
+
+    // Moving between clicks starts a new sequence.
+    harness.mouse_move(origin.to_point() + Vec2::new(20., 20.));
+    harness.mouse_button_press(PointerButton::Primary);
+    harness.mouse_button_release(PointerButton::Primary);
+    assert_eq!(clicks(&mut harness), [1]);
+}
+
+#[test]
+fn click_requires_release_over_widget() {
+    let (mut harness, _, origin) = detector_harness(|detector| detector.clicks(true));
+
+    harness.mouse_move(origin.to_point() + Vec2::new(50., 50.));
+    harness.mouse_button_press(PointerButton::Primary);
+    harness.mouse_move(origin.to_point() + Vec2::new(500., 50.));
+    harness.mouse_button_release(PointerButton::Primary);
+    assert!(clicks(&mut harness).is_empty());
+    assert_eq!(harness.pointer_capture_target_id(), None);
+}
+
+#[test]
+fn long_press_needs_timer() {
+    let (mut harness, _, origin) =
+        detector_harness(|detector| detector.clicks(true).long_press(true));
+
+    harness.mouse_move(origin.to_point() + Vec2::new(50., 50.));
+    harness.mouse_button_press(PointerButton::Primary);
+    harness.move_timers_forward(LONG_PRESS_DELAY - Duration::from_millis(1));
+    assert!(gestures(&mut harness).is_empty());
+
+    harness.move_timers_forward(Duration::from_millis(1));
+    harness.mouse_button_release(PointerButton::Primary);
+    assert_eq!(
+        gestures(&mut harness),
+        [Gesture::LongPress(LongPress {
+            position: Point::new(50., 50.)
+        })]
+    );
+
+    // Releasing early is a click instead.
+    harness.mouse_button_press(PointerButton::Primary);
+    harness.mouse_button_release(PointerButton::Primary);
+    harness.move_timers_forward(LONG_PRESS_DELAY);
+    assert!(matches!(gestures(&mut harness)[..], [Gesture::Click(_)]));
+}
+
+#[test]
+fn drag_starts_after_slop() {
+    let (mut harness, _, origin) = detector_harness(|detector| detector.clicks(true).drag(true));
+    let drag = |phase, position: (f64, f64), delta: (f64, f64)| {
+        Gesture::Drag(Drag {
+            phase,
+            button: PointerButton::Primary,
+            start: Point::new(50., 50.),
+            position: position.into(),
+            delta: delta.into(),
+        })
+    };
+
+    harness.mouse_move(origin.to_point() + Vec2::new(50., 50.));
+    harness.mouse_button_press(PointerButton::Primary);
+    harness.mouse_move(origin.to_point() + Vec2::new(52., 52.));
+    assert!(gestures(&mut harness).is_empty());
+
+    // The drag keeps going outside of the widget.
+    harness.mouse_move(origin.to_point() + Vec2::new(60., 50.));
+    harness.mouse_move(origin.to_point() + Vec2::new(200., 60.));
+    harness.mouse_button_release(PointerButton::Primary);
+    assert_eq!(
+        gestures(&mut harness),
+        [
+            drag(DragPhase::Start, (60., 50.), (10., 0.)),
+            drag(DragPhase::Move, (200., 60.), (140., 10.)),
+            drag(DragPhase::End, (200., 60.), (0., 0.)),
+        ]
+    );
+    assert_eq!(harness.pointer_capture_target_id(), None);
+}
+
+#[test]
+fn pinch_and_pan() {
+    let (mut harness, _, origin) = detector_harness(|detector| detector.pan_zoom(true));
+
+    harness.mouse_move(origin.to_point() + Vec2::new(50., 50.));
+    harness.pinch(0.25);
+    harness.mouse_wheel(Vec2::new(0., -30.));
+    assert_eq!(
+        gestures(&mut harness),
+        [
+            Gesture::PanZoom(PanZoom {
+                pan: Vec2::ZERO,
+                zoom: 1.25,
+                center: Point::new(50., 50.),
+            }),
+            Gesture::PanZoom(PanZoom {
+                pan: Vec2::new(0., -30.),
+                zoom: 1.,
+                center: Point::new(50., 50.),
+            }),
+        ]
+    );
+}
@@ -5,10 +5,16 @@
 
 #![allow(clippy::print_stdout, clippy::print_stderr, clippy::dbg_macro)]
 
+mod compose;
+mod focus_navigation;
+mod gestures;
 mod layout;
 mod lifecycle_basic;
 mod lifecycle_disable;
 mod lifecycle_focus;
+mod paint;
 mod safety_rails;
+mod scale;
 mod status_change;
+mod touch;
 mod widget_tree;
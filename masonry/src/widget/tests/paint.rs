@@ -0,0 +1,87 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tests related to painting and damage tracking.
+
+use std::time::Duration;
+
+use vello::kurbo::{Point, Rect, Size, Vec2};
+
+use crate::render_root::Damage;
+use crate::testing::{widget_ids, TestHarness};
+use crate::widget::{Flex, Portal, SizedBox};
+
+#[test]
+fn damage_merges_overlapping_rects() {
+    let mut damage = Damage::new();
+    assert!(damage.is_empty());
+
+    damage.add_rect(Rect::new(0., 0., 10., 10.));
+    damage.add_rect(Rect::new(20., 0., 30., 10.));
+    assert_eq!(damage.rects().len(), 2);
+
+    // This overlaps both rects, so all three are merged.
+    damage.add_rect(Rect::new(5., 5., 25., 8.));
+    assert_eq!(damage.rects(), &[Rect::new(0., 0., 30., 10.)]);
+
+    // Empty rects don't damage anything.
+    damage.add_rect(Rect::new(50., 50., 50., 60.));
+    assert_eq!(damage.rects().len(), 1);
+    assert!(!damage.intersects(Rect::new(40., 40., 60., 60.)));
+
+    // Past the maximum number of areas, they are merged into their bounding box.
+    for i in 0..8 {
+        let x = 100. + 20. * i as f64;
+        damage.add_rect(Rect::new(x, 0., x + 10., 10.));
+    }
+    assert_eq!(damage.rects(), &[Rect::new(0., 0., 250., 10.)]);
+}
+
+#[test]
+fn repaint_damages_widget() {
+    let [box_id] = widget_ids();
+    let widget = Flex::column()
+        .with_child_id(SizedBox::empty().width(50.).height(20.), box_id)
+        .with_flex_spacer(1.0);
+    let mut harness = TestHarness::create_with_size(widget, Size::new(200., 200.));
+
+    let damage = harness.paint();
+    assert_eq!(damage.bounds(), Some(Rect::new(0., 0., 200., 200.)));
+
+    // Nothing changed, so nothing needs to be redrawn.
+    assert!(harness.paint().is_empty());
+
+    harness.edit_widget(box_id, |mut sized_box| {
+        sized_box.ctx.request_paint();
+    });
+    let box_rect = harness.get_widget(box_id).state().window_layout_rect();
+    assert_eq!(harness.paint().rects(), &[box_rect]);
+    assert!(harness.paint().is_empty());
+}
+
+#[test]
+fn cull_children_outside_clip() {
+    let [top_id, bottom_id] = widget_ids();
+    let widget = Portal::new(
+        Flex::column()
+            .with_child_id(SizedBox::empty().width(50.).height(50.), top_id)
+            .with_spacer(500.)
+            .with_child_id(SizedBox::empty().width(50.).height(50.), bottom_id),
+    );
+    let mut harness = TestHarness::create_with_size(widget, Size::new(200., 200.));
+    harness.paint();
+
+    assert!(harness.get_widget(top_id).state().painted_rect.is_some());
+    assert!(harness.get_widget(bottom_id).state().painted_rect.is_none());
+
+    // Scrolling to the bottom paints the bottom box, and damages the area where the top
+    // box was painted.
+    harness.mouse_move(Point::new(100., 100.));
+    harness.mouse_wheel(Vec2::new(0., -100.));
+    harness.move_timers_forward(Duration::from_secs(1));
+    let damage = harness.paint();
+    assert!(damage.intersects(Rect::new(0., 0., 50., 50.)));
+    assert!(harness.get_widget(top_id).state().painted_rect.is_none());
+    let painted = harness.get_widget(bottom_id).state().painted_rect;
+    assert_eq!(painted, Some(Rect::new(0., 150., 50., 200.)));
+}
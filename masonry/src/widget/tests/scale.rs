@@ -0,0 +1,110 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tests related to the scale factor of the window.
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use crate::assert_render_snapshot;
+use crate::testing::{widget_ids, ModularWidget, TestHarness, TestWidgetExt as _};
+use crate::widget::{Button, Flex, Label, SizedBox};
+use crate::*;
+
+/// A harness with text, a border and a button, rendered at `scale_factor`.
+fn scaled_harness(scale_factor: f64) -> TestHarness {
+    let widget = Flex::column()
+        .with_child(Label::new("Hello world"))
+        .with_spacer(4.)
+        .with_child(
+            SizedBox::empty()
+                .width(40.)
+                .height(10.)
+                .border(Color::WHITE, 1.),
+        )
+        .with_spacer(4.)
+        .with_child(Button::new("Button"));
+    TestHarness::create_with_scale(widget, Size::new(120., 120.), scale_factor)
+}
+
+#[test]
+fn render_at_scale_1() {
+    let mut harness = scaled_harness(1.0);
+    assert_render_snapshot!(harness, "render_at_scale_1");
+}
+
+#[test]
+fn render_at_scale_1_5() {
+    let mut harness = scaled_harness(1.5);
+    assert_render_snapshot!(harness, "render_at_scale_1_5");
+}
+
+#[test]
+fn render_at_scale_2() {
+    let mut harness = scaled_harness(2.0);
+    assert_render_snapshot!(harness, "render_at_scale_2");
+}
+
+#[test]
+fn text_size_is_logical() {
+    let [label_id] = widget_ids();
+    let widget = Flex::column().with_child_id(Label::new("Hello world"), label_id);
+
+    let mut harness = TestHarness::create_with_size(widget, Size::new(400., 400.));
+    let size = harness.get_widget(label_id).state().size;
+
+    for scale_factor in [1.5, 2.0] {
+        harness.set_scale_factor(scale_factor);
+        let scaled_size = harness.get_widget(label_id).state().size;
+        // Hinting at a different size can change the advances slightly.
+        assert!((scaled_size.width - size.width).abs() < 2.0);
+        assert!((scaled_size.height - size.height).abs() < 1.0);
+    }
+}
+
+#[test]
+fn rescale_relayouts_every_widget() {
+    let [leaf_id] = widget_ids();
+    let layout_count = Rc::new(Cell::new(0));
+    let leaf = ModularWidget::new(layout_count.clone())
+        .layout_fn(|layout_count, ctx, _| {
+            layout_count.set(layout_count.get() + 1);
+            Size::new(10. * ctx.scale_factor(), 10.)
+        })
+        .with_id(leaf_id);
+    let widget = Flex::row().with_child(SizedBox::new(leaf));
+
+    let mut harness = TestHarness::create_with_scale(widget, Size::new(400., 400.), 2.0);
+    let count = layout_count.get();
+    assert_eq!(
+        harness.get_widget(leaf_id).state().size,
+        Size::new(20., 10.)
+    );
+
+    // Setting the same scale factor again doesn't do anything.
+    harness.set_scale_factor(2.0);
+    assert_eq!(layout_count.get(), count);
+
+    harness.set_scale_factor(1.0);
+    assert!(layout_count.get() > count);
+    assert_eq!(
+        harness.get_widget(leaf_id).state().size,
+        Size::new(10., 10.)
+    );
+}
+
+#[test]
+fn pointer_position_is_logical() {
+    let [box_id] = widget_ids();
+    let widget = Flex::row()
+        .with_spacer(100.)
+        .with_child_id(SizedBox::empty().width(50.).height(50.), box_id);
+
+    let mut harness = TestHarness::create_with_scale(widget, Size::new(400., 400.), 2.0);
+    let box_rect = harness.get_widget(box_id).state().window_layout_rect();
+    assert!(box_rect.x0 >= 100.);
+    assert!(box_rect.x1 < 200.);
+
+    harness.mouse_move(box_rect.center());
+    assert!(harness.get_widget(box_id).state().is_hot);
+}
@@ -145,6 +145,45 @@ fn update_hot_on_mouse_leave() {
     assert_eq!(next_hot_changed(&label_rec), Some(false));
 }
 
+#[test]
+fn capture_target_hot_over_descendants() {
+    let [button_id, icon_id, other_id] = widget_ids();
+
+    let content = Flex::row()
+        .with_child_id(SizedBox::empty().width(20.0).height(20.0), icon_id)
+        .with_child(Label::new("Add"));
+    let widget = Flex::row()
+        .with_child_id(Button::from_child(content), button_id)
+        .with_child_id(SizedBox::empty().width(20.0).height(20.0), other_id);
+
+    let mut harness = TestHarness::create(widget);
+
+    harness.mouse_move_to(icon_id);
+    assert!(is_hot(&harness, button_id));
+    assert!(is_hot(&harness, icon_id));
+
+    harness.mouse_button_press(PointerButton::Primary);
+    assert_eq!(harness.pointer_capture_target_id(), Some(button_id));
+    // Whilst the pointer is captured, only the capture target can be hovered,
+    // but hovering its descendants still counts as hovering it.
+    assert!(is_hot(&harness, button_id));
+    assert!(!is_hot(&harness, icon_id));
+
+    harness.mouse_move_to(other_id);
+    assert!(!is_hot(&harness, button_id));
+    assert!(!is_hot(&harness, other_id));
+
+    harness.mouse_move_to(icon_id);
+    assert!(is_hot(&harness, button_id));
+    assert!(!is_hot(&harness, icon_id));
+
+    harness.mouse_button_release(PointerButton::Primary);
+    assert_matches!(
+        harness.pop_action(),
+        Some((Action::ButtonPressed(PointerButton::Primary), id)) if id == button_id
+    );
+}
+
 // TODO - https://github.com/PoignardAzur/masonry-rs/issues/58
 #[cfg(FALSE)]
 #[test]
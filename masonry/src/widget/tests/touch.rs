@@ -0,0 +1,154 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tests related to touch input and multiple pointers.
+
+use assert_matches::assert_matches;
+
+use crate::gesture::{Gesture, PanZoom};
+use crate::testing::{widget_ids, TestHarness};
+use crate::widget::{Button, Flex, GestureDetector, SizedBox};
+use crate::*;
+
+fn center_of(harness: &TestHarness, id: WidgetId) -> Point {
+    harness.get_widget(id).state().window_layout_rect().center()
+}
+
+fn detector() -> GestureDetector {
+    GestureDetector::new(SizedBox::empty().width(100.).height(100.))
+        .clicks(true)
+        .pan_zoom(true)
+}
+
+#[test]
+fn primary_touch_acts_like_mouse() {
+    let [button_id] = widget_ids();
+    let widget = Flex::row().with_child_id(Button::new("Hello"), button_id);
+    let mut harness = TestHarness::create(widget);
+    let center = center_of(&harness, button_id);
+
+    harness.touch_down(1, center);
+    assert!(harness.get_widget(button_id).state().is_hot);
+    assert_eq!(
+        harness.capture_target_id_of(PointerId::Touch(1)),
+        Some(button_id)
+    );
+    assert_eq!(harness.pointer_capture_target_id(), None);
+
+    harness.touch_up(1);
+    assert_eq!(
+        harness.pop_action(),
+        Some((Action::ButtonPressed(PointerButton::Primary), button_id))
+    );
+    // Touches don't hover once lifted.
+    assert!(!harness.get_widget(button_id).state().is_hot);
+    assert_eq!(harness.capture_target_id_of(PointerId::Touch(1)), None);
+}
+
+#[test]
+fn secondary_touches_skip_single_pointer_widgets() {
+    let [first_id, second_id] = widget_ids();
+    let widget = Flex::row()
+        .with_child_id(Button::new("First"), first_id)
+        .with_child_id(Button::new("Second"), second_id);
+    let mut harness = TestHarness::create(widget);
+
+    harness.touch_down(1, center_of(&harness, first_id));
+    harness.touch_down(2, center_of(&harness, second_id));
+    harness.touch_up(2);
+    assert_eq!(harness.pop_action(), None);
+
+    // The second finger is hovering the button, even though the button ignores it.
+    harness.touch_down(3, center_of(&harness, second_id));
+    assert!(harness.get_widget(second_id).state().is_hot);
+    harness.touch_up(3);
+
+    harness.touch_up(1);
+    assert_eq!(
+        harness.pop_action(),
+        Some((Action::ButtonPressed(PointerButton::Primary), first_id))
+    );
+
+    // Once every finger is lifted, the next touch is primary again.
+    harness.touch_down(4, center_of(&harness, second_id));
+    harness.touch_up(4);
+    assert_eq!(
+        harness.pop_action(),
+        Some((Action::ButtonPressed(PointerButton::Primary), second_id))
+    );
+}
+
+#[test]
+fn each_touch_has_its_own_capture() {
+    let [first_id, second_id] = widget_ids();
+    let widget = Flex::row()
+        .with_child_id(detector(), first_id)
+        .with_child_id(detector(), second_id);
+    let mut harness = TestHarness::create(widget);
+
+    harness.touch_down(1, center_of(&harness, first_id));
+    harness.touch_down(2, center_of(&harness, second_id));
+    assert_eq!(
+        harness.capture_target_id_of(PointerId::Touch(1)),
+        Some(first_id)
+    );
+    assert_eq!(
+        harness.capture_target_id_of(PointerId::Touch(2)),
+        Some(second_id)
+    );
+    assert!(harness.get_widget(first_id).state().is_hot);
+    assert!(harness.get_widget(second_id).state().is_hot);
+
+    harness.touch_up(2);
+    assert_matches!(
+        harness.pop_action(),
+        Some((Action::Gesture(Gesture::Click(_)), id)) if id == second_id
+    );
+    assert_eq!(harness.capture_target_id_of(PointerId::Touch(2)), None);
+    assert!(!harness.get_widget(second_id).state().is_hot);
+
+    // A cancelled touch isn't a click, and loses its capture.
+    harness.touch_cancel(1);
+    assert_eq!(harness.pop_action(), None);
+    assert_eq!(harness.capture_target_id_of(PointerId::Touch(1)), None);
+}
+
+#[test]
+fn two_fingers_pinch() {
+    let [detector_id] = widget_ids();
+    let widget = Flex::row().with_child_id(detector().drag(true), detector_id);
+    let mut harness = TestHarness::create_with_size(widget, Size::new(400., 400.));
+    let origin = harness
+        .get_widget(detector_id)
+        .state()
+        .window_layout_rect()
+        .origin();
+
+    harness.touch_down(1, origin + Vec2::new(40., 50.));
+    harness.touch_down(2, origin + Vec2::new(60., 50.));
+    harness.touch_move(2, origin + Vec2::new(80., 50.));
+    harness.touch_move(1, origin + Vec2::new(40., 70.));
+    harness.touch_up(1);
+    harness.touch_up(2);
+
+    let gestures: Vec<_> = std::iter::from_fn(|| harness.pop_action())
+        .map(|(action, _)| action)
+        .collect();
+    assert_eq!(
+        gestures,
+        [
+            Action::Gesture(Gesture::PanZoom(PanZoom {
+                pan: Vec2::new(10., 0.),
+                zoom: 2.,
+                center: Point::new(60., 50.),
+            })),
+            Action::Gesture(Gesture::PanZoom(PanZoom {
+                pan: Vec2::new(0., 10.),
+                zoom: (40_f64.powi(2) + 20_f64.powi(2)).sqrt() / 40.,
+                center: Point::new(60., 60.),
+            })),
+        ]
+    );
+    assert_eq!(harness.capture_target_id_of(PointerId::Touch(1)), None);
+    assert_eq!(harness.capture_target_id_of(PointerId::Touch(2)), None);
+}
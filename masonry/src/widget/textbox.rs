@@ -1,6 +1,8 @@
 // Copyright 2018 the Xilem Authors and the Druid Authors
 // SPDX-License-Identifier: Apache-2.0
 
+use std::ops::Range;
+
 use accesskit::Role;
 use parley::{
     layout::Alignment,
@@ -9,17 +11,23 @@ use parley::{
 use smallvec::SmallVec;
 use tracing::{trace, trace_span, Span};
 use vello::{
-    kurbo::{Affine, Point, Size, Stroke},
+    kurbo::{Affine, Insets, Point, Rect, Size, Stroke, Vec2},
     peniko::{BlendMode, Color},
     Scene,
 };
+use winit::keyboard::{Key, NamedKey};
 
+use crate::paint_scene_helpers::{fill_color, stroke};
+use crate::text::{
+    Affinity, Misspelling, Selection, Spellchecker, TextBrush, TextEditor, TextLayout, TextStorage,
+    TextWithSelection,
+};
+use crate::text_helpers::ArcStr;
 use crate::widget::{LineBreaking, WidgetMut};
 use crate::{
     dpi::{LogicalPosition, LogicalSize},
-    text::{TextBrush, TextEditor, TextStorage, TextWithSelection},
     AccessCtx, AccessEvent, BoxConstraints, CursorIcon, EventCtx, LayoutCtx, LifeCycle,
-    LifeCycleCtx, PaintCtx, PointerEvent, StatusChange, TextEvent, Widget, WidgetId,
+    LifeCycleCtx, PaintCtx, PointerButton, PointerEvent, StatusChange, TextEvent, Widget, WidgetId,
 };
 
 const TEXTBOX_PADDING: f64 = 3.0;
@@ -34,9 +42,14 @@ const TEXTBOX_PADDING: f64 = 3.0;
 const TEXTBOX_MARGIN: f64 = 8.0;
 /// The fallback minimum width for a textbox with infinite provided maximum width.
 const INFINITE_TEXTBOX_WIDTH: f64 = 400.0;
+/// The padding around the items of the menu of spelling suggestions.
+const MENU_PADDING: f64 = 4.0;
 
 /// The textbox widget is a widget which shows text which can be edited by the user
 ///
+/// With a [`Spellchecker`], misspelled words are underlined, and right-clicking one
+/// of them shows a menu of suggestions.
+///
 /// For immutable text [`Prose`](super::Prose) should be preferred
 // TODO: RichTextBox 👀
 pub struct Textbox {
@@ -48,6 +61,24 @@ pub struct Textbox {
     line_break_mode: LineBreaking,
     show_disabled: bool,
     brush: TextBrush,
+    /// The area last reported to the input method, in window coordinates.
+    ime_cursor_area: Option<Rect>,
+    spelling_menu: Option<SpellingMenu>,
+}
+
+/// The menu of suggestions for a misspelled word.
+// TODO: Draw this above the other widgets once Masonry supports popups.
+struct SpellingMenu {
+    /// The misspelled range of the text.
+    range: Range<usize>,
+    suggestions: Vec<String>,
+    /// The text of each item, which is a placeholder if there are no suggestions.
+    items: Vec<TextLayout<ArcStr>>,
+    /// The top left corner of the menu, relative to the textbox.
+    origin: Point,
+    /// The area of each item, relative to the textbox. This is computed in layout.
+    item_rects: Vec<Rect>,
+    hovered: Option<usize>,
 }
 
 // --- MARK: BUILDERS ---
@@ -58,14 +89,33 @@ impl Textbox {
             line_break_mode: LineBreaking::WordWrap,
             show_disabled: true,
             brush: crate::theme::TEXT_COLOR.into(),
+            ime_cursor_area: None,
+            spelling_menu: None,
         }
     }
 
     // TODO: Can we reduce code duplication with `Label` widget somehow?
+    /// The text of the textbox, including the text being composed with an input method.
     pub fn text(&self) -> &str {
         self.editor.text()
     }
 
+    /// Whether the user is composing text with an input method.
+    pub fn is_composing(&self) -> bool {
+        self.editor.is_composing()
+    }
+
+    /// The misspelled ranges of the text, found by the spellchecker.
+    pub fn misspellings(&self) -> &[Misspelling] {
+        self.editor.misspellings()
+    }
+
+    /// Builder-style method to check the spelling of the text.
+    pub fn with_spellchecker(mut self, spellchecker: impl Spellchecker + 'static) -> Self {
+        self.editor.set_spellchecker(Some(Box::new(spellchecker)));
+        self
+    }
+
     #[doc(alias = "with_text_color")]
     pub fn with_text_brush(mut self, brush: impl Into<TextBrush>) -> Self {
         self.brush = brush.into();
@@ -97,12 +147,164 @@ impl Textbox {
     }
 }
 
+// --- MARK: SPELLING MENU ---
+impl Textbox {
+    /// Open the menu of suggestions if there is a misspelled word under the pointer.
+    fn open_spelling_menu(&mut self, position: Point) -> bool {
+        let text_origin = Vec2::new(TEXTBOX_PADDING, TEXTBOX_PADDING);
+        let (text_pos, _) = self.editor.layout.caret_for_point(position - text_origin);
+        let Some(misspelling) = self.editor.misspelling_at(text_pos).cloned() else {
+            return false;
+        };
+        let Some(word_rect) = self
+            .editor
+            .layout
+            .rects_for_range(misspelling.range.clone())
+            .first()
+            .copied()
+        else {
+            return false;
+        };
+        // Finding suggestions can be slow, so it is only done when the menu is opened.
+        let suggestions = self.editor.spelling_suggestions(misspelling.range.clone());
+        let placeholder = [("No suggestions", crate::theme::DISABLED_TEXT_COLOR)];
+        let items = suggestions
+            .iter()
+            .map(|suggestion| (suggestion.as_str(), crate::theme::TEXT_COLOR))
+            .chain(suggestions.is_empty().then_some(placeholder[0]))
+            .map(|(text, color)| {
+                let mut layout =
+                    TextLayout::new(text.into(), crate::theme::TEXT_SIZE_NORMAL as f32);
+                layout.set_brush(color);
+                layout
+            })
+            .collect();
+        // The menu is shown below the word, which is selected.
+        self.editor.selection = Some(Selection::new(
+            misspelling.range.start,
+            misspelling.range.end,
+            Affinity::Upstream,
+        ));
+        self.spelling_menu = Some(SpellingMenu {
+            range: misspelling.range,
+            suggestions,
+            items,
+            origin: Point::new(word_rect.x0, word_rect.y1) + text_origin,
+            item_rects: Vec::new(),
+            hovered: None,
+        });
+        true
+    }
+
+    fn close_spelling_menu(&mut self, ctx: &mut EventCtx) {
+        self.spelling_menu = None;
+        ctx.set_active(false);
+        ctx.request_layout();
+    }
+
+    /// Replace the misspelled word by one of the suggestions of the menu.
+    fn apply_suggestion(&mut self, ctx: &mut EventCtx, index: usize) {
+        let Some(menu) = self.spelling_menu.as_ref() else {
+            return;
+        };
+        if let Some(suggestion) = menu.suggestions.get(index) {
+            self.editor.apply_suggestion(menu.range.clone(), suggestion);
+            let contents = self.editor.text().as_str().to_string();
+            ctx.submit_action(crate::Action::TextChanged(contents));
+        }
+        self.close_spelling_menu(ctx);
+    }
+
+    fn on_spelling_menu_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        let Some(menu) = self.spelling_menu.as_mut() else {
+            return;
+        };
+        let item = event
+            .position()
+            .and_then(|position| menu.item_at(ctx.to_local(Point::new(position.x, position.y))));
+        match event {
+            PointerEvent::PointerMove(_) => {
+                if menu.hovered != item {
+                    menu.hovered = item;
+                    ctx.request_paint();
+                }
+            }
+            PointerEvent::PointerDown(..) => match item {
+                Some(index) => self.apply_suggestion(ctx, index),
+                // Clicking anywhere else closes the menu.
+                None => self.close_spelling_menu(ctx),
+            },
+            _ => {}
+        }
+        ctx.set_handled();
+    }
+
+    fn on_spelling_menu_key(&mut self, ctx: &mut EventCtx, key: &Key) -> bool {
+        let Some(menu) = self.spelling_menu.as_mut() else {
+            return false;
+        };
+        let count = menu.suggestions.len();
+        match key {
+            Key::Named(NamedKey::ArrowDown) if count > 0 => {
+                menu.hovered = Some(menu.hovered.map_or(0, |i| (i + 1) % count));
+                ctx.request_paint();
+            }
+            Key::Named(NamedKey::ArrowUp) if count > 0 => {
+                menu.hovered = Some(menu.hovered.map_or(count - 1, |i| (i + count - 1) % count));
+                ctx.request_paint();
+            }
+            Key::Named(NamedKey::Enter) => match menu.hovered {
+                Some(index) => self.apply_suggestion(ctx, index),
+                None => self.close_spelling_menu(ctx),
+            },
+            Key::Named(NamedKey::Escape) => self.close_spelling_menu(ctx),
+            _ => {
+                // Other keys go to the text.
+                self.close_spelling_menu(ctx);
+                return false;
+            }
+        }
+        true
+    }
+}
+
+impl SpellingMenu {
+    /// The index of the suggestion at a position relative to the textbox.
+    fn item_at(&self, position: Point) -> Option<usize> {
+        self.item_rects
+            .iter()
+            .take(self.suggestions.len())
+            .position(|rect| rect.contains(position))
+    }
+
+    fn bounds(&self) -> Rect {
+        let items = self.item_rects.iter().fold(
+            Rect::from_origin_size(self.origin, Size::ZERO),
+            |bounds, rect| bounds.union(*rect),
+        );
+        items.inflate(0., MENU_PADDING)
+    }
+}
+
 // --- MARK: WIDGETMUT ---
 impl WidgetMut<'_, Textbox> {
+    /// The text of the textbox, including the text being composed with an input method.
     pub fn text(&self) -> &str {
         self.widget.editor.text()
     }
 
+    /// Whether the user is composing text with an input method.
+    pub fn is_composing(&self) -> bool {
+        self.widget.editor.is_composing()
+    }
+
+    /// Set the spellchecker, or remove it with `None`.
+    pub fn set_spellchecker(&mut self, spellchecker: Option<Box<dyn Spellchecker>>) {
+        self.widget.editor.set_spellchecker(spellchecker);
+        self.widget.spelling_menu = None;
+        self.ctx.request_layout();
+    }
+
     pub fn set_text_properties<R>(
         &mut self,
         f: impl FnOnce(&mut TextWithSelection<String>) -> R,
@@ -128,7 +330,9 @@ impl WidgetMut<'_, Textbox> {
             );
         }
         self.widget.editor.reset_preedit();
+        self.widget.spelling_menu = None;
         self.set_text_properties(|layout| layout.set_text(new_text));
+        self.widget.editor.recheck_spelling();
     }
 
     #[doc(alias = "set_text_color")]
@@ -161,16 +365,29 @@ impl WidgetMut<'_, Textbox> {
 // --- MARK: IMPL WIDGET ---
 impl Widget for Textbox {
     fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
-        let window_origin = ctx.widget_state.window_origin();
-        let inner_origin = Point::new(
-            window_origin.x + TEXTBOX_PADDING,
-            window_origin.y + TEXTBOX_PADDING,
-        );
+        // The text is inset by the padding.
+        let text_offset = Vec2::new(TEXTBOX_PADDING, TEXTBOX_PADDING);
+        if self.spelling_menu.is_some() {
+            self.on_spelling_menu_pointer_event(ctx, event);
+            return;
+        }
         match event {
+            PointerEvent::PointerDown(PointerButton::Secondary, state) => {
+                let position = ctx.to_local(Point::new(state.position.x, state.position.y));
+                if !ctx.is_disabled() && self.open_spelling_menu(position) {
+                    // The menu keeps the pointer until it is closed.
+                    ctx.set_active(true);
+                    ctx.request_focus();
+                    ctx.request_layout();
+                    ctx.set_handled();
+                }
+            }
             PointerEvent::PointerDown(button, state) => {
                 if !ctx.is_disabled() {
                     // TODO: Start tracking currently pressed link?
-                    let made_change = self.editor.pointer_down(inner_origin, state, *button);
+                    let position =
+                        ctx.to_local(Point::new(state.position.x, state.position.y)) - text_offset;
+                    let made_change = self.editor.pointer_down(position, state, *button);
                     if made_change {
                         ctx.request_layout();
                         ctx.request_paint();
@@ -180,9 +397,11 @@ impl Widget for Textbox {
                 }
             }
             PointerEvent::PointerMove(state) => {
+                let position =
+                    ctx.to_local(Point::new(state.position.x, state.position.y)) - text_offset;
                 if !ctx.is_disabled()
                     && ctx.is_active()
-                    && self.editor.pointer_move(inner_origin, state)
+                    && self.editor.pointer_move(position, state)
                 {
                     // We might have changed text colours, so we need to re-request a layout
                     ctx.request_layout();
@@ -192,7 +411,9 @@ impl Widget for Textbox {
             PointerEvent::PointerUp(button, state) => {
                 // TODO: Follow link (if not now dragging ?)
                 if !ctx.is_disabled() && ctx.is_active() {
-                    self.editor.pointer_up(inner_origin, state, *button);
+                    let position =
+                        ctx.to_local(Point::new(state.position.x, state.position.y)) - text_offset;
+                    self.editor.pointer_up(position, state, *button);
                 }
                 ctx.set_active(false);
             }
@@ -204,6 +425,18 @@ impl Widget for Textbox {
     }
 
     fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        if self.spelling_menu.is_some() {
+            match event {
+                TextEvent::KeyboardKey(key, _) if key.state.is_pressed() => {
+                    if self.on_spelling_menu_key(ctx, &key.logical_key) {
+                        ctx.set_handled();
+                        return;
+                    }
+                }
+                TextEvent::Ime(_) => self.close_spelling_menu(ctx),
+                _ => {}
+            }
+        }
         let result = self.editor.text_event(ctx, event);
         // If focused on a link and enter pressed, follow it?
         if result.is_handled() {
@@ -224,11 +457,19 @@ impl Widget for Textbox {
     fn on_status_change(&mut self, ctx: &mut LifeCycleCtx, event: &StatusChange) {
         match event {
             StatusChange::FocusChanged(false) => {
-                self.editor.focus_lost();
+                self.spelling_menu = None;
+                if self.editor.focus_lost() {
+                    // The text being composed is kept.
+                    let contents = self.editor.text().as_str().to_string();
+                    ctx.submit_action(crate::Action::TextChanged(contents));
+                }
                 ctx.request_layout();
                 // TODO: Stop focusing on any links
             }
             StatusChange::FocusChanged(true) => {
+                // The input method needs to know where the caret is.
+                self.ime_cursor_area = None;
+                ctx.request_paint();
                 // TODO: Focus on first link
             }
             _ => {}
@@ -270,6 +511,7 @@ impl Widget for Textbox {
             None
         };
         self.editor.set_max_advance(max_advance);
+        self.editor.set_scale(ctx.scale_factor() as f32);
         if self.editor.needs_rebuild() {
             let (font_ctx, layout_ctx) = ctx.text_contexts();
             self.editor.rebuild(font_ctx, layout_ctx);
@@ -288,6 +530,46 @@ impl Widget for Textbox {
             width,
         };
         let size = bc.constrain(label_size);
+
+        // The menu of suggestions overflows the textbox.
+        let mut paint_insets = Insets::ZERO;
+        if let Some(menu) = &mut self.spelling_menu {
+            let scale = ctx.scale_factor() as f32;
+            let (font_ctx, layout_ctx) = ctx.text_contexts();
+            let mut y = menu.origin.y + MENU_PADDING;
+            for item in &mut menu.items {
+                item.set_scale(scale);
+                item.rebuild(font_ctx, layout_ctx);
+            }
+            let width = menu
+                .items
+                .iter()
+                .map(|item| item.size().width)
+                .fold(0., f64::max)
+                + 2. * MENU_PADDING;
+            menu.item_rects = menu
+                .items
+                .iter()
+                .map(|item| {
+                    let rect = Rect::new(
+                        menu.origin.x,
+                        y,
+                        menu.origin.x + width,
+                        y + item.size().height + MENU_PADDING,
+                    );
+                    y = rect.y1;
+                    rect
+                })
+                .collect();
+            let bounds = menu.bounds();
+            paint_insets = Insets::new(
+                -bounds.x0,
+                -bounds.y0,
+                bounds.x1 - size.width,
+                bounds.y1 - size.height,
+            );
+        }
+        ctx.set_paint_insets(paint_insets);
         trace!(
             "Computed layout: max={:?}. w={}, h={}",
             max_advance,
@@ -321,18 +603,41 @@ impl Widget for Textbox {
             None,
             &outline_rect,
         );
-        let origin = ctx.widget_state.window_origin();
+        if let Some(menu) = &mut self.spelling_menu {
+            let menu_rect = menu.bounds().to_rounded_rect(2.);
+            fill_color(scene, &menu_rect, crate::theme::BACKGROUND_LIGHT);
+            stroke(scene, &menu_rect, crate::theme::BORDER_LIGHT, 1.);
+            if let Some(rect) = menu.hovered.and_then(|i| menu.item_rects.get(i)) {
+                fill_color(scene, rect, crate::theme::PRIMARY_DARK);
+            }
+            for (item, rect) in menu.items.iter_mut().zip(&menu.item_rects) {
+                item.draw(scene, (rect.x0 + MENU_PADDING, rect.y0 + MENU_PADDING / 2.));
+            }
+        }
         if ctx.widget_state.has_focus {
-            ctx.signal(crate::render_root::RenderRootSignal::ImeMoved(
-                LogicalPosition {
-                    x: origin.x,
-                    y: origin.y + size.height,
-                },
-                LogicalSize {
-                    width: size.width,
-                    height: size.height,
-                },
-            ));
+            // The candidate window of the input method is placed next to the caret.
+            let caret_area = self
+                .editor
+                .ime_cursor_area()
+                .unwrap_or(Rect::from_origin_size(
+                    Point::ORIGIN,
+                    (0., self.editor.size().height),
+                ))
+                + Vec2::new(TEXTBOX_PADDING, TEXTBOX_PADDING);
+            let area = ctx.window_transform().transform_rect_bbox(caret_area);
+            if self.ime_cursor_area != Some(area) {
+                self.ime_cursor_area = Some(area);
+                ctx.signal(crate::render_root::RenderRootSignal::ImeMoved(
+                    LogicalPosition {
+                        x: area.x0,
+                        y: area.y0,
+                    },
+                    LogicalSize {
+                        width: area.width(),
+                        height: area.height(),
+                    },
+                ));
+            }
         }
     }
 
@@ -360,3 +665,332 @@ impl Widget for Textbox {
         Some(self.editor.text().as_str().chars().take(100).collect())
     }
 }
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use smallvec::smallvec;
+
+    use super::*;
+    use crate::testing::{widget_ids, ModularWidget, TestHarness};
+    use crate::text::TextChange;
+    use crate::widget::{Flex, WidgetRef};
+    use crate::{Action, WidgetPod};
+
+    fn textbox(harness: &TestHarness, id: WidgetId) -> &Textbox {
+        WidgetRef::deref(harness.get_widget(id).downcast::<Textbox>().unwrap())
+    }
+
+    fn focused_textbox(text: &str) -> (TestHarness, WidgetId) {
+        let [textbox_id] = widget_ids();
+        let widget = Flex::column().with_child_id(Textbox::new(text), textbox_id);
+        let mut harness = TestHarness::create(widget);
+        harness.mouse_click_on(textbox_id);
+        assert_eq!(harness.focused_widget().map(|w| w.id()), Some(textbox_id));
+        (harness, textbox_id)
+    }
+
+    #[test]
+    fn ime_composition_commits() {
+        let (mut harness, textbox_id) = focused_textbox("");
+        harness.keyboard_type_chars("ab");
+        while harness.pop_action().is_some() {}
+
+        harness.ime_preedit("に", Some((3, 3)));
+        harness.ime_preedit("にほん", Some((9, 9)));
+        assert_eq!(textbox(&harness, textbox_id).text(), "abにほん");
+        assert!(textbox(&harness, textbox_id).is_composing());
+        // The composed text isn't final yet.
+        assert_eq!(harness.pop_action(), None);
+
+        harness.ime_preedit("日本", Some((0, 6)));
+        harness.ime_commit("日本");
+        assert_eq!(textbox(&harness, textbox_id).text(), "ab日本");
+        assert!(!textbox(&harness, textbox_id).is_composing());
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::TextChanged("ab日本".to_string()), textbox_id))
+        );
+
+        // Typing continues after the committed text.
+        harness.keyboard_type_chars("c");
+        assert_eq!(textbox(&harness, textbox_id).text(), "ab日本c");
+    }
+
+    #[test]
+    fn ime_cancel_removes_preedit() {
+        let (mut harness, textbox_id) = focused_textbox("");
+        harness.keyboard_type_chars("ab");
+        while harness.pop_action().is_some() {}
+
+        harness.ime_preedit("かな", Some((6, 6)));
+        harness.ime_preedit("", None);
+        assert_eq!(textbox(&harness, textbox_id).text(), "ab");
+        assert!(!textbox(&harness, textbox_id).is_composing());
+
+        harness.ime_preedit("かな", None);
+        harness.process_text_event(TextEvent::Ime(winit::event::Ime::Disabled));
+        assert_eq!(textbox(&harness, textbox_id).text(), "ab");
+        assert_eq!(harness.pop_action(), None);
+
+        harness.keyboard_type_chars("c");
+        assert_eq!(textbox(&harness, textbox_id).text(), "abc");
+    }
+
+    #[test]
+    fn ime_preedit_is_kept_on_focus_loss() {
+        let [first_id, second_id] = widget_ids();
+        let widget = Flex::column()
+            .with_child_id(Textbox::new(""), first_id)
+            .with_child_id(Textbox::new(""), second_id);
+        let mut harness = TestHarness::create(widget);
+        harness.mouse_click_on(first_id);
+        // Input methods often show the romanized text before converting it.
+        harness.ime_preedit("ka", Some((2, 2)));
+
+        harness.mouse_click_on(second_id);
+        assert_eq!(textbox(&harness, first_id).text(), "ka");
+        assert!(!textbox(&harness, first_id).is_composing());
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::TextChanged("ka".to_string()), first_id))
+        );
+    }
+
+    #[test]
+    fn ime_cursor_area_follows_caret() {
+        let (mut harness, textbox_id) = focused_textbox("");
+        harness.paint();
+        let empty_area = harness.ime_cursor_area().unwrap();
+        let window_rect = harness.get_widget(textbox_id).state().window_layout_rect();
+        assert!(window_rect.contains(empty_area.origin()));
+        assert!(empty_area.height() > 0.);
+
+        harness.keyboard_type_chars("ab");
+        harness.paint();
+        let typed_area = harness.ime_cursor_area().unwrap();
+        assert!(typed_area.x0 > empty_area.x0);
+        assert_eq!(typed_area.y0, empty_area.y0);
+
+        // The candidate window follows the part of the preedit being converted.
+        harness.ime_preedit("nihon", Some((0, 2)));
+        harness.paint();
+        assert_eq!(harness.ime_cursor_area(), Some(typed_area));
+        harness.ime_preedit("nihon", Some((2, 5)));
+        harness.paint();
+        assert!(harness.ime_cursor_area().unwrap().x0 > typed_area.x0);
+    }
+
+    /// A spellchecker which only knows that "teh" is misspelled.
+    #[derive(Clone, Default)]
+    struct FakeChecker {
+        changes: Arc<Mutex<Vec<TextChange>>>,
+    }
+
+    impl Spellchecker for FakeChecker {
+        fn text_changed(&mut self, text: &str, change: &TextChange) -> Vec<Misspelling> {
+            self.changes.lock().unwrap().push(change.clone());
+            text.match_indices("teh")
+                .map(|(i, word)| Misspelling::new(i..i + word.len()))
+                .collect()
+        }
+
+        fn suggest(&mut self, _word: &str) -> Vec<String> {
+            vec!["the".into(), "ten".into()]
+        }
+    }
+
+    fn spellchecked_textbox(text: &str) -> (TestHarness, WidgetId, FakeChecker) {
+        let [textbox_id] = widget_ids();
+        let checker = FakeChecker::default();
+        let widget = Flex::column().with_child_id(
+            Textbox::new(text).with_spellchecker(checker.clone()),
+            textbox_id,
+        );
+        let harness = TestHarness::create(widget);
+        (harness, textbox_id, checker)
+    }
+
+    /// The window position of a text position of the textbox.
+    fn text_point(harness: &TestHarness, id: WidgetId, text_pos: usize) -> Point {
+        let textbox = textbox(harness, id);
+        let line = textbox
+            .editor
+            .layout
+            .cursor_line_for_text_position(text_pos);
+        harness.get_widget(id).state().window_transform
+            * Point::new(
+                TEXTBOX_PADDING + line.p0.x,
+                TEXTBOX_PADDING + line.p0.y - 5.,
+            )
+    }
+
+    /// A textbox whose parent scales and moves it with a layer transform.
+    fn transformed_textbox(textbox: Textbox) -> (TestHarness, WidgetId) {
+        let [textbox_id] = widget_ids();
+        let widget = ModularWidget::new(WidgetPod::new_with_id(textbox, textbox_id))
+            .lifecycle_fn(|child, ctx, event| child.lifecycle(ctx, event))
+            .layout_fn(|child, ctx, bc| {
+                child.layout(ctx, &bc.loosen());
+                ctx.place_child(child, Point::ZERO);
+                bc.max()
+            })
+            .compose_fn(|child, ctx| {
+                ctx.set_child_transform(child, Affine::translate((30., 20.)) * Affine::scale(2.));
+            })
+            .children_fn(|child| smallvec![child.id()]);
+        (TestHarness::create(widget), textbox_id)
+    }
+
+    #[test]
+    fn click_in_transformed_parent() {
+        let (mut harness, textbox_id) = transformed_textbox(Textbox::new("hello world"));
+        harness.mouse_move(text_point(&harness, textbox_id, 6));
+        harness.mouse_button_press(PointerButton::Primary);
+        harness.mouse_button_release(PointerButton::Primary);
+        let selection = textbox(&harness, textbox_id).editor.selection.unwrap();
+        assert_eq!(selection.range(), 6..6);
+    }
+
+    fn misspelled_ranges(harness: &TestHarness, id: WidgetId) -> Vec<Range<usize>> {
+        textbox(harness, id)
+            .misspellings()
+            .iter()
+            .map(|misspelling| misspelling.range.clone())
+            .collect()
+    }
+
+    /// Right-click the middle of a word.
+    fn open_menu(harness: &mut TestHarness, id: WidgetId, text_pos: usize) {
+        harness.mouse_move(text_point(harness, id, text_pos));
+        harness.mouse_button_press(PointerButton::Secondary);
+        harness.mouse_button_release(PointerButton::Secondary);
+    }
+
+    #[test]
+    fn spellchecker_receives_changes() {
+        let (mut harness, textbox_id, checker) = spellchecked_textbox("a ");
+        assert!(textbox(&harness, textbox_id).misspellings().is_empty());
+
+        harness.mouse_click_on(textbox_id);
+        harness.keyboard_type_chars("teh");
+        let misspellings = textbox(&harness, textbox_id).misspellings();
+        assert_eq!(misspellings.len(), 1);
+        assert_eq!(misspellings[0].range, 2..5);
+        assert_eq!(
+            *checker.changes.lock().unwrap(),
+            [
+                TextChange::replace_all(0, 2),
+                TextChange {
+                    old_range: 2..2,
+                    new_range: 2..3
+                },
+                TextChange {
+                    old_range: 3..3,
+                    new_range: 3..4
+                },
+                TextChange {
+                    old_range: 4..4,
+                    new_range: 4..5
+                },
+            ]
+        );
+
+        harness.edit_widget(textbox_id, |mut textbox| {
+            let mut textbox = textbox.downcast::<Textbox>();
+            textbox.reset_text("teh teh".into());
+        });
+        assert_eq!(misspelled_ranges(&harness, textbox_id), [0..3, 4..7]);
+        assert_eq!(
+            checker.changes.lock().unwrap().last(),
+            Some(&TextChange::replace_all(5, 7))
+        );
+        harness.paint();
+    }
+
+    #[test]
+    fn suggestion_replaces_misspelling() {
+        let (mut harness, textbox_id, _) = spellchecked_textbox("a teh cat");
+        open_menu(&mut harness, textbox_id, 3);
+        let textbox_ref = textbox(&harness, textbox_id);
+        let menu = textbox_ref.spelling_menu.as_ref().unwrap();
+        assert_eq!(menu.suggestions, ["the", "ten"]);
+        assert_eq!(textbox_ref.editor.selection.unwrap().range(), 2..5);
+        // The menu gets every click, even outside of the textbox.
+        assert_eq!(harness.pointer_capture_target_id(), Some(textbox_id));
+        assert_eq!(
+            harness.focused_widget().map(|widget| widget.id()),
+            Some(textbox_id)
+        );
+        let item_center =
+            harness.get_widget(textbox_id).state().window_transform * menu.item_rects[1].center();
+        harness.paint();
+
+        harness.mouse_move(item_center);
+        assert_eq!(
+            textbox(&harness, textbox_id)
+                .spelling_menu
+                .as_ref()
+                .unwrap()
+                .hovered,
+            Some(1)
+        );
+        harness.mouse_button_press(PointerButton::Primary);
+        assert_eq!(textbox(&harness, textbox_id).text(), "a ten cat");
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::TextChanged("a ten cat".to_string()), textbox_id))
+        );
+        assert!(textbox(&harness, textbox_id).spelling_menu.is_none());
+        assert_eq!(harness.pointer_capture_target_id(), None);
+        assert!(textbox(&harness, textbox_id).misspellings().is_empty());
+    }
+
+    #[test]
+    fn spelling_menu_in_transformed_parent() {
+        let (mut harness, textbox_id) = transformed_textbox(
+            Textbox::new("a teh cat").with_spellchecker(FakeChecker::default()),
+        );
+        open_menu(&mut harness, textbox_id, 3);
+        let textbox_ref = textbox(&harness, textbox_id);
+        let menu = textbox_ref.spelling_menu.as_ref().unwrap();
+        let item_center =
+            harness.get_widget(textbox_id).state().window_transform * menu.item_rects[0].center();
+
+        harness.mouse_move(item_center);
+        harness.mouse_button_press(PointerButton::Primary);
+        assert_eq!(textbox(&harness, textbox_id).text(), "a the cat");
+        assert!(textbox(&harness, textbox_id).spelling_menu.is_none());
+    }
+
+    #[test]
+    fn preedit_is_not_spellchecked() {
+        let (mut harness, textbox_id, _) = spellchecked_textbox("a ");
+        harness.mouse_click_on(textbox_id);
+        harness.ime_preedit("teh", Some((3, 3)));
+        assert_eq!(textbox(&harness, textbox_id).text(), "a teh");
+        assert!(textbox(&harness, textbox_id).misspellings().is_empty());
+
+        harness.ime_commit("teh");
+        assert_eq!(textbox(&harness, textbox_id).text(), "a teh");
+        assert_eq!(misspelled_ranges(&harness, textbox_id), vec![2..5]);
+    }
+
+    #[test]
+    fn click_outside_closes_spelling_menu() {
+        let (mut harness, textbox_id, _) = spellchecked_textbox("a teh cat");
+        // Correctly spelled words have no menu.
+        open_menu(&mut harness, textbox_id, 7);
+        assert!(textbox(&harness, textbox_id).spelling_menu.is_none());
+
+        open_menu(&mut harness, textbox_id, 3);
+        assert!(textbox(&harness, textbox_id).spelling_menu.is_some());
+        harness.mouse_move(Point::new(390., 390.));
+        harness.mouse_button_press(PointerButton::Primary);
+        assert!(textbox(&harness, textbox_id).spelling_menu.is_none());
+        assert_eq!(textbox(&harness, textbox_id).text(), "a teh cat");
+        assert_eq!(harness.pop_action(), None);
+    }
+}
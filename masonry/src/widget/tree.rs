@@ -0,0 +1,935 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A hierarchical list of items, which can be expanded and collapsed.
+
+use accesskit::{DefaultActionVerb, Role};
+use smallvec::{smallvec, SmallVec};
+use tracing::{trace, trace_span, Span};
+use vello::kurbo::BezPath;
+use vello::Scene;
+use winit::keyboard::{KeyCode, PhysicalKey};
+
+use crate::action::Action;
+use crate::event::PointerButton;
+use crate::paint_scene_helpers::{fill_color, stroke};
+use crate::widget::WidgetMut;
+use crate::{
+    theme, AccessCtx, AccessEvent, BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx,
+    PaintCtx, Point, PointerEvent, Rect, Size, StatusChange, TextEvent, Widget, WidgetId,
+    WidgetPod,
+};
+
+/// The width of the area for the disclosure triangle at the start of each row.
+const DISCLOSURE_WIDTH: f64 = 16.;
+const DISCLOSURE_TRIANGLE_SIZE: f64 = 8.;
+/// How far the children of an item are indented.
+const INDENT: f64 = 16.;
+
+/// A tree of [`TreeItem`]s.
+///
+/// The user can select items by clicking them, and expand or collapse them by clicking
+/// their disclosure triangle (or double-clicking them).
+/// When the tree is focused, the arrow keys move between the visible items, and expand or
+/// collapse them.
+///
+/// If [`multi_select`](Self::multi_select) is enabled, Ctrl+click toggles the selection of an item,
+/// and Shift+click (or Shift with the arrow keys) selects a range of items.
+///
+/// The tree emits actions on behalf of its items: [`Action::TreeItemSelected`] when an item's
+/// selection changes and [`Action::TreeItemExpanded`] when it is expanded or collapsed.
+/// Items which are collapsed keep their selection.
+pub struct Tree {
+    items: Vec<WidgetPod<TreeItem>>,
+    multi_select: bool,
+    /// The visible rows, in order, as of the last layout.
+    rows: Vec<TreeRow>,
+    /// The item the keyboard is acting on.
+    cursor: Option<WidgetId>,
+    /// The item ranges are selected from.
+    anchor: Option<WidgetId>,
+}
+
+/// An item in a [`Tree`], with some content and an optional list of child items.
+///
+/// The children are only shown if the item is expanded.
+/// An item can be marked as [`expandable`](Self::expandable) without having children,
+/// so that they can be loaded lazily when it is first expanded.
+pub struct TreeItem {
+    content: WidgetPod<Box<dyn Widget>>,
+    children: Vec<WidgetPod<TreeItem>>,
+    expandable: bool,
+    expanded: bool,
+    selected: bool,
+    /// The row of this item, followed by those of its visible descendants.
+    rows: Vec<TreeRow>,
+}
+
+/// What a key press does in a [`Tree`], with the index of the row it acts on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum KeyCommand {
+    /// Move the cursor to the row.
+    MoveTo(usize),
+    /// Expand or collapse the row.
+    SetExpanded(usize, bool),
+    /// Select the row, or toggle its selection if Ctrl is held.
+    Select(usize),
+}
+
+/// A visible row of a [`Tree`].
+#[derive(Debug, Clone, Copy)]
+struct TreeRow {
+    id: WidgetId,
+    parent: Option<WidgetId>,
+    /// The rect of the disclosure triangle and content of the item,
+    /// in the coordinate space of the widget which stores the row.
+    rect: Rect,
+    expandable: bool,
+    expanded: bool,
+    selected: bool,
+}
+
+// --- MARK: BUILDERS ---
+impl Tree {
+    /// Create a new tree without any items.
+    pub fn new() -> Self {
+        Tree {
+            items: Vec::new(),
+            multi_select: false,
+            rows: Vec::new(),
+            cursor: None,
+            anchor: None,
+        }
+    }
+
+    /// Builder-style method to add a top-level item.
+    pub fn with_item(self, item: TreeItem) -> Self {
+        self.with_item_pod(WidgetPod::new(item))
+    }
+
+    /// Builder-style method to add a top-level item which is already in a [`WidgetPod`].
+    pub fn with_item_pod(mut self, item: WidgetPod<TreeItem>) -> Self {
+        self.items.push(item);
+        self
+    }
+
+    /// Builder-style method to set whether several items can be selected at once.
+    ///
+    /// This is `false` by default.
+    pub fn multi_select(mut self, multi_select: bool) -> Self {
+        self.multi_select = multi_select;
+        self
+    }
+}
+
+impl Default for Tree {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TreeItem {
+    /// Create a new item showing `content`.
+    pub fn new(content: impl Widget) -> Self {
+        Self::from_content_pod(WidgetPod::new(Box::new(content)))
+    }
+
+    /// Create a new item with content which is already in a [`WidgetPod`].
+    pub fn from_content_pod(content: WidgetPod<Box<dyn Widget>>) -> Self {
+        TreeItem {
+            content,
+            children: Vec::new(),
+            expandable: false,
+            expanded: false,
+            selected: false,
+            rows: Vec::new(),
+        }
+    }
+
+    /// Builder-style method to add a child item.
+    ///
+    /// This also makes the item [`expandable`](Self::expandable).
+    pub fn with_child(self, child: TreeItem) -> Self {
+        self.with_child_pod(WidgetPod::new(child))
+    }
+
+    /// Builder-style method to add a child item which is already in a [`WidgetPod`].
+    ///
+    /// This also makes the item [`expandable`](Self::expandable).
+    pub fn with_child_pod(mut self, child: WidgetPod<TreeItem>) -> Self {
+        self.children.push(child);
+        self.expandable = true;
+        self
+    }
+
+    /// Builder-style method to set whether the item can be expanded, even if it has no children.
+    pub fn expandable(mut self, expandable: bool) -> Self {
+        self.expandable = expandable;
+        self
+    }
+
+    /// Builder-style method to set whether the item is expanded.
+    pub fn expanded(mut self, expanded: bool) -> Self {
+        self.expanded = expanded;
+        self
+    }
+
+    /// Builder-style method to set whether the item is selected.
+    pub fn selected(mut self, selected: bool) -> Self {
+        self.selected = selected;
+        self
+    }
+
+    /// Whether the item can be expanded.
+    pub fn is_expandable(&self) -> bool {
+        self.expandable
+    }
+
+    /// Whether the item is expanded.
+    pub fn is_expanded(&self) -> bool {
+        self.expanded
+    }
+
+    /// Whether the item is selected.
+    pub fn is_selected(&self) -> bool {
+        self.selected
+    }
+
+    /// The number of child items.
+    pub fn child_count(&self) -> usize {
+        self.children.len()
+    }
+}
+
+// --- MARK: WIDGETMUT ---
+impl WidgetMut<'_, Tree> {
+    /// Add a top-level item at the end.
+    pub fn add_item(&mut self, item: TreeItem) {
+        let idx = self.widget.items.len();
+        self.insert_item_pod(idx, WidgetPod::new(item));
+    }
+
+    /// Insert a top-level item at `idx`.
+    pub fn insert_item_pod(&mut self, idx: usize, item: WidgetPod<TreeItem>) {
+        self.widget.items.insert(idx, item);
+        self.ctx.children_changed();
+    }
+
+    /// Remove the top-level item at `idx`.
+    pub fn remove_item(&mut self, idx: usize) {
+        let item = self.widget.items.remove(idx);
+        self.ctx.remove_child(item);
+        self.ctx.request_layout();
+    }
+
+    /// Get a mutable reference to the top-level item at `idx`.
+    pub fn item_mut(&mut self, idx: usize) -> WidgetMut<'_, TreeItem> {
+        self.ctx.get_mut(&mut self.widget.items[idx])
+    }
+
+    /// Set whether several items can be selected at once.
+    pub fn set_multi_select(&mut self, multi_select: bool) {
+        self.widget.multi_select = multi_select;
+        self.ctx.request_accessibility_update();
+    }
+}
+
+impl WidgetMut<'_, TreeItem> {
+    /// Add a child item at the end.
+    pub fn add_child(&mut self, child: TreeItem) {
+        let idx = self.widget.children.len();
+        self.insert_child_pod(idx, WidgetPod::new(child));
+    }
+
+    /// Insert a child item at `idx`.
+    pub fn insert_child_pod(&mut self, idx: usize, child: WidgetPod<TreeItem>) {
+        self.widget.children.insert(idx, child);
+        self.ctx.children_changed();
+    }
+
+    /// Remove the child item at `idx`.
+    pub fn remove_child(&mut self, idx: usize) {
+        let child = self.widget.children.remove(idx);
+        self.ctx.remove_child(child);
+        self.ctx.request_layout();
+    }
+
+    /// Get a mutable reference to the child item at `idx`.
+    pub fn child_mut(&mut self, idx: usize) -> WidgetMut<'_, TreeItem> {
+        self.ctx.get_mut(&mut self.widget.children[idx])
+    }
+
+    /// Get a mutable reference to the content of the item.
+    pub fn content_mut(&mut self) -> WidgetMut<'_, Box<dyn Widget>> {
+        self.ctx.get_mut(&mut self.widget.content)
+    }
+
+    /// Set whether the item can be expanded, even if it has no children.
+    pub fn set_expandable(&mut self, expandable: bool) {
+        self.widget.expandable = expandable;
+        self.ctx.request_layout();
+    }
+
+    /// Expand or collapse the item.
+    ///
+    /// This doesn't emit an [`Action::TreeItemExpanded`].
+    pub fn set_expanded(&mut self, expanded: bool) {
+        self.widget.expanded = expanded;
+        self.ctx.request_layout();
+        self.ctx.request_accessibility_update();
+    }
+
+    /// Select or deselect the item.
+    ///
+    /// This doesn't emit an [`Action::TreeItemSelected`].
+    pub fn set_selected(&mut self, selected: bool) {
+        self.widget.selected = selected;
+        // The tree paints the selection, using the rows collected during layout.
+        self.ctx.request_layout();
+        self.ctx.request_accessibility_update();
+    }
+}
+
+// --- MARK: IMPL TREE ---
+impl Tree {
+    fn row_index(&self, id: WidgetId) -> Option<usize> {
+        self.rows.iter().position(|row| row.id == id)
+    }
+
+    fn row_at(&self, pos: Point) -> Option<usize> {
+        self.rows
+            .iter()
+            .position(|row| row.rect.y0 <= pos.y && pos.y < row.rect.y1)
+    }
+
+    /// Select the rows for which `selected` returns true, and deselect the others.
+    fn select_rows(&mut self, ctx: &mut EventCtx, selected: impl Fn(usize) -> bool) {
+        for (idx, row) in self.rows.iter_mut().enumerate() {
+            let selected = selected(idx);
+            if row.selected == selected {
+                continue;
+            }
+            trace!(
+                "Tree {:?} set item {:?} selected={}",
+                ctx.widget_id(),
+                row.id,
+                selected
+            );
+            row.selected = selected;
+            ctx.submit_descendant_action(row.id, Action::TreeItemSelected(selected));
+            ctx.mutate_descendant_later(row.id, move |mut item| {
+                item.downcast::<TreeItem>().set_selected(selected);
+            });
+        }
+        ctx.request_paint();
+    }
+
+    fn set_expanded(&mut self, ctx: &mut EventCtx, idx: usize, expanded: bool) {
+        let row = &mut self.rows[idx];
+        if !row.expandable || row.expanded == expanded {
+            return;
+        }
+        trace!(
+            "Tree {:?} set item {:?} expanded={}",
+            ctx.widget_id(),
+            row.id,
+            expanded
+        );
+        row.expanded = expanded;
+        ctx.submit_descendant_action(row.id, Action::TreeItemExpanded(expanded));
+        ctx.mutate_descendant_later(row.id, move |mut item| {
+            item.downcast::<TreeItem>().set_expanded(expanded);
+        });
+    }
+
+    /// Move the cursor to the row at `idx`, selecting it, or the range from the anchor to it if `extend` is true.
+    fn move_cursor(&mut self, ctx: &mut EventCtx, idx: usize, extend: bool) {
+        let id = self.rows[idx].id;
+        self.cursor = Some(id);
+        let anchor = self.anchor.and_then(|anchor| self.row_index(anchor));
+        match anchor {
+            Some(anchor) if extend && self.multi_select => {
+                let range = anchor.min(idx)..=anchor.max(idx);
+                self.select_rows(ctx, |row| range.contains(&row));
+            }
+            _ => {
+                self.anchor = Some(id);
+                self.select_rows(ctx, |row| row == idx);
+            }
+        }
+        ctx.request_accessibility_update();
+    }
+
+    /// What pressing the key `code` does, given the current cursor.
+    fn key_command(&self, code: KeyCode) -> Option<KeyCommand> {
+        let last = self.rows.len().checked_sub(1)?;
+        let cursor = self.cursor.and_then(|cursor| self.row_index(cursor));
+        Some(match (code, cursor) {
+            (KeyCode::ArrowDown, Some(idx)) => KeyCommand::MoveTo((idx + 1).min(last)),
+            (KeyCode::ArrowUp, Some(idx)) => KeyCommand::MoveTo(idx.saturating_sub(1)),
+            (KeyCode::ArrowDown | KeyCode::ArrowUp | KeyCode::Home, _) => KeyCommand::MoveTo(0),
+            (KeyCode::End, _) => KeyCommand::MoveTo(last),
+            (KeyCode::ArrowRight, Some(idx)) => {
+                let row = self.rows[idx];
+                if row.expandable && !row.expanded {
+                    return Some(KeyCommand::SetExpanded(idx, true));
+                }
+                match self.rows.get(idx + 1) {
+                    Some(child) if child.parent == Some(row.id) => KeyCommand::MoveTo(idx + 1),
+                    _ => return None,
+                }
+            }
+            (KeyCode::ArrowLeft, Some(idx)) => {
+                let row = self.rows[idx];
+                if row.expanded {
+                    return Some(KeyCommand::SetExpanded(idx, false));
+                }
+                KeyCommand::MoveTo(row.parent.and_then(|parent| self.row_index(parent))?)
+            }
+            (KeyCode::Space, Some(idx)) => KeyCommand::Select(idx),
+            _ => return None,
+        })
+    }
+
+    fn toggle_selected(&mut self, ctx: &mut EventCtx, idx: usize) {
+        let id = self.rows[idx].id;
+        self.cursor = Some(id);
+        self.anchor = Some(id);
+        let selected: Vec<bool> = self.rows.iter().map(|row| row.selected).collect();
+        self.select_rows(ctx, |row| selected[row] != (row == idx));
+        ctx.request_accessibility_update();
+    }
+}
+
+// --- MARK: IMPL WIDGET ---
+impl Widget for Tree {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        let PointerEvent::PointerDown(PointerButton::Primary, state) = event else {
+            return;
+        };
+        if ctx.is_disabled() {
+            return;
+        }
+        let pos = ctx.to_local(Point::new(state.position.x, state.position.y));
+        let Some(idx) = self.row_at(pos) else {
+            return;
+        };
+        ctx.set_handled();
+        ctx.request_focus();
+
+        let row = self.rows[idx];
+        let on_disclosure = pos.x >= row.rect.x0 && pos.x < row.rect.x0 + DISCLOSURE_WIDTH;
+        if on_disclosure || state.count == 2 {
+            self.set_expanded(ctx, idx, !row.expanded);
+        }
+        if on_disclosure {
+            return;
+        }
+        let mods = state.mods.state();
+        if self.multi_select && (mods.control_key() || mods.super_key()) {
+            self.toggle_selected(ctx, idx);
+        } else {
+            self.move_cursor(ctx, idx, mods.shift_key());
+        }
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        let TextEvent::KeyboardKey(key, mods) = event else {
+            return;
+        };
+        if !key.state.is_pressed() || !ctx.is_focused() || self.rows.is_empty() {
+            return;
+        }
+        let PhysicalKey::Code(code) = key.physical_key else {
+            return;
+        };
+        let Some(command) = self.key_command(code) else {
+            return;
+        };
+        ctx.set_handled();
+        match command {
+            KeyCommand::SetExpanded(idx, expanded) => self.set_expanded(ctx, idx, expanded),
+            KeyCommand::Select(idx) if self.multi_select && mods.control_key() => {
+                self.toggle_selected(ctx, idx);
+            }
+            KeyCommand::Select(idx) => self.move_cursor(ctx, idx, false),
+            KeyCommand::MoveTo(idx) if self.multi_select && mods.control_key() => {
+                // Move without changing the selection, so that Ctrl+Space can toggle other items.
+                self.cursor = Some(self.rows[idx].id);
+                ctx.request_paint();
+                ctx.request_accessibility_update();
+            }
+            KeyCommand::MoveTo(idx) => self.move_cursor(ctx, idx, mods.shift_key()),
+        }
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        let Some(idx) = self.row_index(event.target) else {
+            return;
+        };
+        match event.action {
+            accesskit::Action::Default => self.move_cursor(ctx, idx, false),
+            accesskit::Action::Expand => self.set_expanded(ctx, idx, true),
+            accesskit::Action::Collapse => self.set_expanded(ctx, idx, false),
+            _ => return,
+        }
+        ctx.set_handled();
+    }
+
+    fn on_status_change(&mut self, ctx: &mut LifeCycleCtx, event: &StatusChange) {
+        if let StatusChange::FocusChanged(_) = event {
+            ctx.request_paint();
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        if let LifeCycle::BuildFocusChain = event {
+            ctx.register_for_focus();
+        }
+        for item in &mut self.items {
+            item.lifecycle(ctx, event);
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let item_bc = BoxConstraints::new(Size::ZERO, Size::new(bc.max().width, f64::INFINITY));
+        self.rows.clear();
+        let mut width: f64 = 0.0;
+        let mut y = 0.0;
+        for item in &mut self.items {
+            let size = item.layout(ctx, &item_bc);
+            let origin = Point::new(0.0, y);
+            ctx.place_child(item, origin);
+            let item = ctx.get_raw_ref(item);
+            self.rows
+                .extend(item.widget().rows.iter().map(|row| TreeRow {
+                    rect: row.rect + origin.to_vec2(),
+                    ..*row
+                }));
+            width = width.max(size.width);
+            y += size.height;
+        }
+        if self
+            .cursor
+            .is_some_and(|cursor| !self.rows.iter().any(|row| row.id == cursor))
+        {
+            self.cursor = None;
+        }
+
+        let size = bc.constrain(Size::new(width, y));
+        trace!("Computed layout: size={}", size);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let width = ctx.size().width;
+        for row in &self.rows {
+            let rect = Rect::new(0.0, row.rect.y0, width, row.rect.y1);
+            if row.selected {
+                let color = if ctx.has_focus() {
+                    theme::SELECTED_TEXT_BACKGROUND_COLOR
+                } else {
+                    theme::SELECTED_TEXT_INACTIVE_BACKGROUND_COLOR
+                };
+                fill_color(scene, &rect, color);
+            }
+            if ctx.is_focused() && self.cursor == Some(row.id) {
+                stroke(scene, &rect.inset(-0.5), theme::PRIMARY_LIGHT, 1.0);
+            }
+        }
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::Tree
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        if self.multi_select {
+            ctx.current_node().set_multiselectable();
+        }
+        if let Some(cursor) = self.cursor {
+            ctx.current_node().set_active_descendant(cursor.into());
+        }
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        self.items.iter().map(|item| item.id()).collect()
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("Tree")
+    }
+}
+
+impl Widget for TreeItem {
+    fn on_pointer_event(&mut self, _ctx: &mut EventCtx, _event: &PointerEvent) {}
+
+    fn on_text_event(&mut self, _ctx: &mut EventCtx, _event: &TextEvent) {}
+
+    fn on_access_event(&mut self, _ctx: &mut EventCtx, _event: &AccessEvent) {}
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        self.content.lifecycle(ctx, event);
+        for child in &mut self.children {
+            child.lifecycle(ctx, event);
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let content_bc = BoxConstraints::new(
+            Size::ZERO,
+            Size::new((bc.max().width - DISCLOSURE_WIDTH).max(0.0), f64::INFINITY),
+        );
+        let content_size = self.content.layout(ctx, &content_bc);
+        let row_height = content_size.height.max(DISCLOSURE_WIDTH);
+        ctx.place_child(
+            &mut self.content,
+            Point::new(DISCLOSURE_WIDTH, (row_height - content_size.height) / 2.0),
+        );
+
+        let id = ctx.widget_id();
+        let mut width = DISCLOSURE_WIDTH + content_size.width;
+        self.rows.clear();
+        self.rows.push(TreeRow {
+            id,
+            parent: None,
+            rect: Rect::new(0.0, 0.0, width, row_height),
+            expandable: self.expandable,
+            expanded: self.expanded,
+            selected: self.selected,
+        });
+
+        let child_bc = BoxConstraints::new(
+            Size::ZERO,
+            Size::new((bc.max().width - INDENT).max(0.0), f64::INFINITY),
+        );
+        let mut y = row_height;
+        for child in &mut self.children {
+            ctx.set_stashed(child, !self.expanded);
+            if !self.expanded {
+                ctx.skip_layout(child);
+                continue;
+            }
+            let size = child.layout(ctx, &child_bc);
+            let origin = Point::new(INDENT, y);
+            ctx.place_child(child, origin);
+            let child = ctx.get_raw_ref(child);
+            self.rows
+                .extend(child.widget().rows.iter().map(|row| TreeRow {
+                    parent: row.parent.or(Some(id)),
+                    rect: row.rect + origin.to_vec2(),
+                    ..*row
+                }));
+            width = width.max(INDENT + size.width);
+            y += size.height;
+        }
+
+        bc.constrain(Size::new(width, y))
+    }
+
+    fn paint(&mut self, _ctx: &mut PaintCtx, scene: &mut Scene) {
+        if !self.expandable {
+            return;
+        }
+        let row_height = self
+            .rows
+            .first()
+            .map_or(DISCLOSURE_WIDTH, |row| row.rect.height());
+        let center = Point::new(DISCLOSURE_WIDTH / 2.0, row_height / 2.0);
+        let half = DISCLOSURE_TRIANGLE_SIZE / 2.0;
+        let mut triangle = BezPath::new();
+        if self.expanded {
+            triangle.move_to((center.x - half, center.y - half / 2.0));
+            triangle.line_to((center.x + half, center.y - half / 2.0));
+            triangle.line_to((center.x, center.y + half / 2.0 + 1.0));
+        } else {
+            triangle.move_to((center.x - half / 2.0, center.y - half));
+            triangle.line_to((center.x - half / 2.0, center.y + half));
+            triangle.line_to((center.x + half / 2.0 + 1.0, center.y));
+        }
+        triangle.close_path();
+        fill_color(scene, &triangle, theme::FOREGROUND_DARK);
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::TreeItem
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        let node = ctx.current_node();
+        node.set_selected(self.selected);
+        node.set_default_action_verb(DefaultActionVerb::Click);
+        if self.expandable {
+            node.set_expanded(self.expanded);
+            node.add_action(if self.expanded {
+                accesskit::Action::Collapse
+            } else {
+                accesskit::Action::Expand
+            });
+        }
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        let mut ids: SmallVec<_> = smallvec![self.content.id()];
+        ids.extend(self.children.iter().map(|child| child.id()));
+        ids
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("TreeItem")
+    }
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{widget_ids, TestHarness};
+    use crate::widget::Label;
+    use crate::PointerState;
+    use winit::dpi::LogicalPosition;
+    use winit::keyboard::ModifiersState;
+
+    fn item(id: WidgetId, text: &str) -> WidgetPod<TreeItem> {
+        WidgetPod::new_with_id(TreeItem::new(Label::new(text)), id)
+    }
+
+    fn click(harness: &mut TestHarness, pos: Point) {
+        harness.mouse_move(pos);
+        harness.mouse_button_press(PointerButton::Primary);
+        harness.mouse_button_release(PointerButton::Primary);
+    }
+
+    fn content_pos(harness: &TestHarness, id: WidgetId) -> Point {
+        harness.get_widget(id).state().window_origin() + (DISCLOSURE_WIDTH + 4.0, 4.0)
+    }
+
+    #[test]
+    fn expand_and_collapse() {
+        let [folder, file] = widget_ids();
+        let widget = Tree::new().with_item_pod(WidgetPod::new_with_id(
+            TreeItem::new(Label::new("src")).with_child_pod(item(file, "main.rs")),
+            folder,
+        ));
+        let mut harness = TestHarness::create(widget);
+        assert!(harness.get_widget(file).state().is_stashed);
+
+        // Click on the disclosure triangle.
+        let triangle = harness.get_widget(folder).state().window_origin() + (4.0, 4.0);
+        click(&mut harness, triangle);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::TreeItemExpanded(true), folder))
+        );
+        assert_eq!(harness.pop_action(), None);
+        assert!(!harness.get_widget(file).state().is_stashed);
+
+        click(&mut harness, triangle);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::TreeItemExpanded(false), folder))
+        );
+        assert!(harness.get_widget(file).state().is_stashed);
+    }
+
+    #[test]
+    fn lazy_children() {
+        let [folder, file] = widget_ids();
+        let widget = Tree::new().with_item_pod(WidgetPod::new_with_id(
+            TreeItem::new(Label::new("src")).expandable(true),
+            folder,
+        ));
+        let mut harness = TestHarness::create(widget);
+
+        let triangle = harness.get_widget(folder).state().window_origin() + (4.0, 4.0);
+        click(&mut harness, triangle);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::TreeItemExpanded(true), folder))
+        );
+        harness.edit_widget(folder, |mut folder| {
+            folder
+                .downcast::<TreeItem>()
+                .insert_child_pod(0, item(file, "main.rs"));
+        });
+        assert!(!harness.get_widget(file).state().is_stashed);
+    }
+
+    #[test]
+    fn click_selects() {
+        let [a, b] = widget_ids();
+        let widget = Tree::new()
+            .with_item_pod(item(a, "a"))
+            .with_item_pod(item(b, "b"));
+        let mut harness = TestHarness::create(widget);
+
+        let pos = content_pos(&harness, a);
+        click(&mut harness, pos);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::TreeItemSelected(true), a))
+        );
+        assert_eq!(harness.pop_action(), None);
+
+        let pos = content_pos(&harness, b);
+        click(&mut harness, pos);
+        let mut actions = [harness.pop_action(), harness.pop_action()];
+        actions.sort_by_key(|action| action.as_ref().map(|(_, id)| *id != a));
+        assert_eq!(
+            actions,
+            [
+                Some((Action::TreeItemSelected(false), a)),
+                Some((Action::TreeItemSelected(true), b))
+            ]
+        );
+        let item_b = harness.get_widget(b);
+        assert!(item_b.downcast::<TreeItem>().unwrap().is_selected());
+    }
+
+    #[test]
+    fn ctrl_click_multi_select() {
+        let [a, b] = widget_ids();
+        let widget = Tree::new()
+            .multi_select(true)
+            .with_item_pod(item(a, "a"))
+            .with_item_pod(item(b, "b"));
+        let mut harness = TestHarness::create(widget);
+
+        let pos = content_pos(&harness, a);
+        click(&mut harness, pos);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::TreeItemSelected(true), a))
+        );
+
+        // The harness has no modifier state, so send the event ourselves.
+        let pos = content_pos(&harness, b);
+        let mut state = PointerState::empty();
+        state.position = LogicalPosition::new(pos.x, pos.y);
+        state.mods = ModifiersState::CONTROL.into();
+        state.count = 1;
+        harness.process_pointer_event(PointerEvent::PointerDown(PointerButton::Primary, state));
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::TreeItemSelected(true), b))
+        );
+        assert_eq!(harness.pop_action(), None);
+    }
+
+    fn key_command(harness: &TestHarness, code: KeyCode) -> Option<KeyCommand> {
+        harness
+            .root_widget()
+            .downcast::<Tree>()
+            .unwrap()
+            .key_command(code)
+    }
+
+    #[test]
+    fn keyboard_navigation() {
+        let [folder, file, b] = widget_ids();
+        let widget = Tree::new()
+            .with_item_pod(WidgetPod::new_with_id(
+                TreeItem::new(Label::new("src")).with_child_pod(item(file, "main.rs")),
+                folder,
+            ))
+            .with_item_pod(item(b, "b"));
+        let mut harness = TestHarness::create(widget);
+        assert_eq!(
+            key_command(&harness, KeyCode::ArrowDown),
+            Some(KeyCommand::MoveTo(0))
+        );
+        assert_eq!(
+            key_command(&harness, KeyCode::End),
+            Some(KeyCommand::MoveTo(1))
+        );
+
+        let pos = content_pos(&harness, folder);
+        click(&mut harness, pos);
+        assert_eq!(
+            key_command(&harness, KeyCode::ArrowRight),
+            Some(KeyCommand::SetExpanded(0, true))
+        );
+        assert_eq!(key_command(&harness, KeyCode::ArrowLeft), None);
+        assert_eq!(
+            key_command(&harness, KeyCode::ArrowUp),
+            Some(KeyCommand::MoveTo(0))
+        );
+        assert_eq!(
+            key_command(&harness, KeyCode::ArrowDown),
+            Some(KeyCommand::MoveTo(1))
+        );
+        assert_eq!(
+            key_command(&harness, KeyCode::Space),
+            Some(KeyCommand::Select(0))
+        );
+
+        harness.edit_widget(folder, |mut folder| {
+            folder.downcast::<TreeItem>().set_expanded(true);
+        });
+        // Right moves into an expanded folder, and Left collapses it.
+        assert_eq!(
+            key_command(&harness, KeyCode::ArrowRight),
+            Some(KeyCommand::MoveTo(1))
+        );
+        assert_eq!(
+            key_command(&harness, KeyCode::ArrowLeft),
+            Some(KeyCommand::SetExpanded(0, false))
+        );
+
+        // Left moves from a child to its parent.
+        let pos = content_pos(&harness, file);
+        click(&mut harness, pos);
+        assert_eq!(
+            key_command(&harness, KeyCode::ArrowLeft),
+            Some(KeyCommand::MoveTo(0))
+        );
+        assert_eq!(key_command(&harness, KeyCode::ArrowRight), None);
+        assert_eq!(
+            key_command(&harness, KeyCode::ArrowUp),
+            Some(KeyCommand::MoveTo(0))
+        );
+        assert_eq!(
+            key_command(&harness, KeyCode::ArrowDown),
+            Some(KeyCommand::MoveTo(2))
+        );
+
+        // Up and Down stop at the first and last rows.
+        let pos = content_pos(&harness, b);
+        click(&mut harness, pos);
+        assert_eq!(
+            key_command(&harness, KeyCode::ArrowDown),
+            Some(KeyCommand::MoveTo(2))
+        );
+        assert_eq!(key_command(&harness, KeyCode::ArrowLeft), None);
+        assert_eq!(
+            key_command(&harness, KeyCode::Home),
+            Some(KeyCommand::MoveTo(0))
+        );
+    }
+
+    #[test]
+    fn double_click_expands() {
+        let [folder, file] = widget_ids();
+        let widget = Tree::new().with_item_pod(WidgetPod::new_with_id(
+            TreeItem::new(Label::new("src")).with_child_pod(item(file, "main.rs")),
+            folder,
+        ));
+        let mut harness = TestHarness::create(widget);
+
+        let pos = content_pos(&harness, folder);
+        click(&mut harness, pos);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::TreeItemSelected(true), folder))
+        );
+        click(&mut harness, pos);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::TreeItemExpanded(true), folder))
+        );
+        assert_eq!(harness.pop_action(), None);
+        assert!(!harness.get_widget(file).state().is_stashed);
+    }
+}
@@ -361,6 +361,7 @@ impl Widget for VariableLabel {
             None
         };
         self.text_layout.set_max_advance(max_advance);
+        self.text_layout.set_scale(ctx.scale_factor() as f32);
         if self.text_layout.needs_rebuild() {
             self.text_layout
                 .set_brush(self.brush(ctx.widget_state.is_disabled));
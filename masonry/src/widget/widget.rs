@@ -16,6 +16,7 @@ use crate::contexts::ComposeCtx;
 use crate::event::{AccessEvent, PointerEvent, StatusChange, TextEvent};
 use crate::{
     AccessCtx, AsAny, BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx, Size,
+    TimerToken,
 };
 
 /// A unique identifier for a single [`Widget`].
@@ -76,6 +77,9 @@ pub trait Widget: AsAny {
     /// Handle an event from the platform's accessibility API.
     fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {}
 
+    /// Handle a timer requested with [`EventCtx::request_timer`].
+    fn on_timer(&mut self, ctx: &mut EventCtx, token: TimerToken) {}
+
     #[allow(missing_docs)]
     fn on_status_change(&mut self, ctx: &mut LifeCycleCtx, event: &StatusChange);
 
@@ -138,6 +142,16 @@ pub trait Widget: AsAny {
         false
     }
 
+    /// Whether the widget receives the events of every pointer, rather than only those
+    /// of the primary pointer.
+    ///
+    /// Most widgets only handle a single pointer, and treat touches like mouse clicks.
+    /// Widgets which handle multi-touch, e.g. to pinch and zoom, should return `true`,
+    /// and tell pointers apart with [`PointerState::pointer_id`](crate::PointerState::pointer_id).
+    fn accepts_multi_pointer(&self) -> bool {
+        false
+    }
+
     /// Return a span for tracing.
     ///
     /// As methods recurse through the widget tree, trace spans are added for each child
@@ -298,6 +312,10 @@ impl Widget for Box<dyn Widget> {
         self.deref_mut().on_access_event(ctx, event);
     }
 
+    fn on_timer(&mut self, ctx: &mut EventCtx, token: TimerToken) {
+        self.deref_mut().on_timer(ctx, token);
+    }
+
     fn on_status_change(&mut self, ctx: &mut LifeCycleCtx, event: &StatusChange) {
         self.deref_mut().on_status_change(ctx, event);
     }
@@ -342,6 +360,10 @@ impl Widget for Box<dyn Widget> {
         self.deref().skip_pointer()
     }
 
+    fn accepts_multi_pointer(&self) -> bool {
+        self.deref().accepts_multi_pointer()
+    }
+
     fn make_trace_span(&self) -> Span {
         self.deref().make_trace_span()
     }
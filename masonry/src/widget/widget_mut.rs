@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::contexts::MutateCtx;
+use crate::widget::WidgetRef;
 use crate::{Widget, WidgetState};
 
 // TODO - Document extension trait workaround.
@@ -56,6 +57,16 @@ impl<'w, W: Widget> WidgetMut<'w, W> {
 }
 
 impl<'a> WidgetMut<'a, Box<dyn Widget>> {
+    /// Get a [`WidgetRef`] to the same underlying widget, e.g. to inspect its descendants.
+    pub fn as_widget_ref(&mut self) -> WidgetRef<'_, dyn Widget> {
+        WidgetRef {
+            widget_state_children: self.ctx.widget_state_children.reborrow(),
+            widget_children: self.ctx.widget_children.reborrow(),
+            widget_state: self.ctx.widget_state,
+            widget: &**self.widget,
+        }
+    }
+
     /// Attempt to downcast to `WidgetMut` of concrete Widget type.
     pub fn try_downcast<W2: Widget>(&mut self) -> Option<WidgetMut<'_, W2>> {
         Some(WidgetMut {
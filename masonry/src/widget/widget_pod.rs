@@ -61,6 +61,16 @@ impl<W: Widget> WidgetPod<W> {
     pub fn id(&self) -> WidgetId {
         self.id
     }
+
+    /// Get the contained widget, if it hasn't been inserted into the widget graph yet.
+    ///
+    /// This lets builder methods of container widgets configure their children.
+    pub(crate) fn created_widget_mut(&mut self) -> Option<&mut W> {
+        match &mut self.inner {
+            WidgetPodInner::Created(widget) => Some(widget),
+            WidgetPodInner::Inserted => None,
+        }
+    }
 }
 
 impl<W: Widget + 'static> WidgetPod<W> {
@@ -350,6 +360,7 @@ impl<W: Widget> WidgetPod<W> {
                     state.has_focus = is_focused;
 
                     state.focus_chain.clear();
+                    state.focus_traps.clear();
                     true
                 } else {
                     false
@@ -405,11 +416,16 @@ impl<W: Widget> WidgetPod<W> {
                 }
                 state.has_focus = had_focus;
 
+                state.sort_focus_scope();
                 if !state.is_disabled {
                     parent_ctx
                         .widget_state
                         .focus_chain
                         .extend(&state.focus_chain);
+                    parent_ctx
+                        .widget_state
+                        .focus_traps
+                        .extend(&state.focus_traps);
                 }
             }
             _ => (),
@@ -480,6 +496,7 @@ impl<W: Widget> WidgetPod<W> {
         trace!("Computing layout with constraints {:?}", bc);
 
         state.local_paint_rect = Rect::ZERO;
+        state.scroll_snap_points.clear();
 
         state.request_layout = false;
         let new_size = {
@@ -542,8 +559,12 @@ impl<W: Widget> WidgetPod<W> {
                 }
 
                 // TODO - This check might be redundant with the code updating local_paint_rect
+                // Stashed children aren't painted, so they can keep a stale layout.
                 let child_rect = child_state.paint_rect();
-                if !rect_contains(&state.local_paint_rect, &child_rect) && !state.is_portal {
+                if !rect_contains(&state.local_paint_rect, &child_rect)
+                    && !state.is_portal
+                    && !child_state.is_stashed
+                {
                     debug_panic!(
                         "Error in '{}' #{}: paint_rect {:?} doesn't contain paint_rect {:?} of child widget '{}' #{}",
                         widget.short_type_name(),
@@ -185,16 +185,18 @@ impl<'w> WidgetRef<'w, dyn Widget> {
         // TODO - Rewrite more elegantly
         loop {
             if let Some(clip) = innermost_widget.state().clip {
-                let relative_pos = pos.to_vec2() - innermost_widget.state().window_origin.to_vec2();
+                let relative_pos = innermost_widget.state().window_transform.inverse() * pos;
                 // If the widget has a clip, the point must be inside
                 // else we don't iterate over children.
-                if !clip.contains(relative_pos.to_point()) {
+                if !clip.contains(relative_pos) {
                     break;
                 }
             }
             // TODO - Use Widget::get_child_at_pos method
             if let Some(child) = innermost_widget.children().into_iter().rev().find(|child| {
-                !child.widget.skip_pointer() && child.state().window_layout_rect().contains(pos)
+                !child.widget.skip_pointer()
+                    && !child.state().is_stashed
+                    && child.state().window_contains(pos)
             }) {
                 innermost_widget = child;
             } else {
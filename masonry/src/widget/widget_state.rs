@@ -4,10 +4,12 @@
 #![cfg(not(tarpaulin_include))]
 
 use std::sync::atomic::{AtomicBool, Ordering};
-use vello::kurbo::{Insets, Point, Rect, Size, Vec2};
+use vello::kurbo::{Affine, Insets, Point, Rect, Size, Vec2};
+use vello::peniko::{BlendMode, Mix};
 
+use crate::focus::{sort_focus_chain, FocusEntry};
 use crate::text_helpers::TextFieldRegistration;
-use crate::{CursorIcon, WidgetId};
+use crate::{CursorIcon, FocusScopeOptions, WidgetId};
 
 // TODO - Sort out names of widget state flags in two categories:
 // - request_xxx: means this widget needs the xxx pass to run on it
@@ -48,6 +50,9 @@ pub struct WidgetState {
     pub(crate) origin: Point,
     /// The origin of the widget in the window coordinate space;
     pub(crate) window_origin: Point,
+    /// The transform from the widget's coordinate space to the window's, including
+    /// the layer transforms of the widget and its ancestors.
+    pub(crate) window_transform: Affine,
     /// The insets applied to the layout rect to generate the paint rect.
     /// In general, these will be zero; the exception is for things like
     /// drop shadows or overflowing text.
@@ -61,6 +66,9 @@ pub struct WidgetState {
     /// the baseline. Widgets that contain text or controls that expect to be
     /// laid out alongside text can set this as appropriate.
     pub(crate) baseline_offset: f64,
+    /// The points the viewport of an enclosing portal can snap to, declared by this
+    /// widget and its descendants, in local coordinates.
+    pub(crate) scroll_snap_points: Vec<Point>,
     // TODO - Document
     pub(crate) is_portal: bool,
 
@@ -69,9 +77,22 @@ pub struct WidgetState {
     // efficiently hold an arbitrary shape.
     pub(crate) clip: Option<Rect>,
 
-    // TODO - Handle matrix transforms
+    /// The visible area of the window this widget and its children were painted in
+    /// during the last paint pass, or `None` if it wasn't painted.
+    pub(crate) painted_rect: Option<Rect>,
+    /// The window transform of the widget during the last paint pass.
+    pub(crate) painted_transform: Affine,
+
     pub(crate) translation: Vec2,
-    pub(crate) translation_changed: bool,
+    /// The transform of the widget's layer, applied in its own coordinate space.
+    pub(crate) transform: Affine,
+    /// The opacity the widget and its children are composited with.
+    pub(crate) opacity: f32,
+    /// The blend mode the widget and its children are composited with.
+    pub(crate) blend_mode: BlendMode,
+    /// Whether the position or the layer of the widget changed since the last
+    /// compose pass.
+    pub(crate) layer_changed: bool,
 
     // --- PASSES ---
 
@@ -107,7 +128,16 @@ pub struct WidgetState {
 
     pub(crate) update_focus_chain: bool,
 
-    pub(crate) focus_chain: Vec<WidgetId>,
+    /// The focusable widgets of this subtree, in tab order once sorted by the
+    /// enclosing focus scope.
+    pub(crate) focus_chain: Vec<FocusEntry>,
+    /// The focus traps of this subtree, innermost first.
+    pub(crate) focus_traps: Vec<WidgetId>,
+    /// The position of this widget, or of its focus scope, in tab order.
+    ///
+    /// Zero means the widget is reached in tree order, after widgets with a positive index.
+    pub(crate) tab_index: u32,
+    pub(crate) focus_scope: Option<FocusScopeOptions>,
 
     pub(crate) children_changed: bool,
 
@@ -152,17 +182,24 @@ impl WidgetState {
             id,
             origin: Point::ORIGIN,
             window_origin: Point::ORIGIN,
+            window_transform: Affine::IDENTITY,
             size: Size::ZERO,
             is_expecting_place_child_call: false,
             paint_insets: Insets::ZERO,
             local_paint_rect: Rect::ZERO,
             is_portal: false,
             clip: Default::default(),
+            painted_rect: None,
+            painted_transform: Affine::IDENTITY,
             translation: Vec2::ZERO,
-            translation_changed: false,
+            transform: Affine::IDENTITY,
+            opacity: 1.0,
+            blend_mode: Mix::Normal.into(),
+            layer_changed: false,
             is_explicitly_disabled: false,
             is_disabled: false,
             baseline_offset: 0.0,
+            scroll_snap_points: Vec::new(),
             is_hot: false,
             request_layout: true,
             needs_layout: true,
@@ -176,6 +213,9 @@ impl WidgetState {
             request_anim: true,
             needs_update_disabled: true,
             focus_chain: Vec::new(),
+            focus_traps: Vec::new(),
+            tab_index: 0,
+            focus_scope: None,
             children_changed: true,
             cursor: None,
             text_registrations: Vec::new(),
@@ -241,6 +281,22 @@ impl WidgetState {
         self.update_focus_chain |= child_state.update_focus_chain;
     }
 
+    /// Sort the focus chain of this widget's subtree, if the widget is a focus scope.
+    ///
+    /// The whole scope is then reached at the position of its own tab index.
+    pub(crate) fn sort_focus_scope(&mut self) {
+        let Some(scope) = self.focus_scope else {
+            return;
+        };
+        sort_focus_chain(&mut self.focus_chain);
+        for entry in &mut self.focus_chain {
+            entry.tab_index = self.tab_index;
+        }
+        if scope.trap && !self.focus_traps.contains(&self.id) {
+            self.focus_traps.push(self.id);
+        }
+    }
+
     #[inline]
     pub(crate) fn size(&self) -> Size {
         self.size
@@ -250,7 +306,8 @@ impl WidgetState {
     ///
     /// For more information, see [`WidgetPod::paint_rect`](crate::WidgetPod::paint_rect).
     pub fn paint_rect(&self) -> Rect {
-        self.local_paint_rect + self.origin.to_vec2()
+        (Affine::translate(self.origin.to_vec2()) * self.transform)
+            .transform_rect_bbox(self.local_paint_rect)
     }
 
     /// The rectangle used when calculating layout with other widgets
@@ -264,13 +321,34 @@ impl WidgetState {
     ///
     /// This might not map to a visible area of the screen, eg if the widget is scrolled
     /// away.
+    ///
+    /// If the widget or one of its ancestors has a layer transform, this is the
+    /// bounding box of the transformed layout rect.
     pub fn window_layout_rect(&self) -> Rect {
-        Rect::from_origin_size(self.window_origin(), self.size)
+        self.window_transform
+            .transform_rect_bbox(self.size.to_rect())
     }
 
     pub(crate) fn window_origin(&self) -> Point {
         self.window_origin
     }
+
+    /// Whether `window_pos`, in window coordinates, is inside the layout rect of the
+    /// widget, taking its window transform into account.
+    pub(crate) fn window_contains(&self, window_pos: Point) -> bool {
+        // A widget scaled down to nothing can't be hit.
+        if self.window_transform.determinant() == 0.0 {
+            return false;
+        }
+        let local_pos = self.window_transform.inverse() * window_pos;
+        self.size.to_rect().contains(local_pos)
+    }
+
+    /// The layer properties differ from the defaults, so the widget must be composited
+    /// separately.
+    pub(crate) fn has_layer(&self) -> bool {
+        self.opacity != 1.0 || self.blend_mode != Mix::Normal.into()
+    }
 }
 
 impl Clone for VisitBool {
@@ -63,7 +63,8 @@ impl ApplicationHandler<MasonryUserEvent> for ExternalApp {
     }
 
     fn about_to_wait(&mut self, event_loop: &winit::event_loop::ActiveEventLoop) {
-        self.masonry_state.handle_about_to_wait(event_loop);
+        self.masonry_state
+            .handle_about_to_wait(event_loop, self.app_driver.as_mut());
     }
 
     fn window_event(
@@ -4,18 +4,15 @@
 //! An example demonstrating the use of Async web requests in Xilem to access the <https://http.cat/> API.
 //! This also demonstrates image loading.
 
-use std::sync::Arc;
-
-use vello::peniko::{Blob, Image};
 use winit::{dpi::LogicalSize, error::EventLoopError, window::Window};
 use xilem::{
     view::{
-        button, flex, image, portal, prose, sized_box, spinner, worker, Axis, CrossAxisAlignment,
-        FlexExt, FlexSpacer,
+        button, flex, image_source, portal, prose, sized_box, Axis, CrossAxisAlignment, FlexExt,
+        FlexSpacer, ImageSource,
     },
     Color, EventLoop, EventLoopBuilder, TextAlignment, WidgetView, Xilem,
 };
-use xilem_core::{fork, one_of::OneOf3};
+use xilem_core::one_of::OneOf3;
 
 /// The main state of the application.
 struct HttpCats {
@@ -28,16 +25,6 @@ struct HttpCats {
 struct Status {
     code: u32,
     message: &'static str,
-    image: ImageState,
-}
-
-#[derive(Debug)]
-/// The state of the download of an image from a URL
-enum ImageState {
-    NotRequested,
-    Pending,
-    // Error,
-    Available(Image),
 }
 
 impl HttpCats {
@@ -49,113 +36,56 @@ impl HttpCats {
                 .map(Status::list_view)
                 .collect::<Vec<_>>(),
         )));
-        let (info_area, worker_value) = if let Some(selected_code) = self.selected_code {
+        let info_area = if let Some(selected_code) = self.selected_code {
             if let Some(selected_status) =
                 self.statuses.iter_mut().find(|it| it.code == selected_code)
             {
-                // If we haven't requested the image yet, make sure we do so.
-                let value = match selected_status.image {
-                    ImageState::NotRequested => {
-                        // TODO: Should a view_function be editing `self`?
-                        // This feels too imperative.
-                        selected_status.image = ImageState::Pending;
-                        Some(selected_code)
-                    }
-                    // If the image is pending, that means that worker already knows about it.
-                    // We don't set the requested code to `selected_code` here because we could have been on
-                    // a different view in-between, so we don't want to request the same image twice.
-                    ImageState::Pending => None,
-                    ImageState::Available(_) => None,
-                };
-                (OneOf3::A(selected_status.details_view()), value)
+                OneOf3::A(selected_status.details_view())
             } else {
-                (
-                    OneOf3::B(
-                        prose(format!(
-                            "Status code {selected_code} selected, but this was not found."
-                        ))
-                        .alignment(TextAlignment::Middle)
-                        .brush(Color::YELLOW),
-                    ),
-                    None,
+                OneOf3::B(
+                    prose(format!(
+                        "Status code {selected_code} selected, but this was not found."
+                    ))
+                    .alignment(TextAlignment::Middle)
+                    .brush(Color::YELLOW),
                 )
             }
         } else {
-            (
-                OneOf3::C(
-                    prose("No selection yet made. Select an item from the sidebar to continue.")
-                        .alignment(TextAlignment::Middle),
-                ),
-                None,
+            OneOf3::C(
+                prose("No selection yet made. Select an item from the sidebar to continue.")
+                    .alignment(TextAlignment::Middle),
             )
         };
 
-        // TODO: Should `web_image` be a built-in component?
-
-        fork(
+        flex((
+            // Add padding to the top for Android. Still a horrible hack
+            FlexSpacer::Fixed(40.),
             flex((
-                // Add padding to the top for Android. Still a horrible hack
-                FlexSpacer::Fixed(40.),
-                flex((
-                    left_column.flex(1.),
-                    portal(sized_box(info_area).expand_width()).flex(1.),
-                ))
-                .direction(Axis::Horizontal)
-                .cross_axis_alignment(CrossAxisAlignment::Fill)
-                .must_fill_major_axis(true)
-                .flex(1.),
+                left_column.flex(1.),
+                portal(sized_box(info_area).expand_width()).flex(1.),
             ))
+            .direction(Axis::Horizontal)
+            .cross_axis_alignment(CrossAxisAlignment::Fill)
             .must_fill_major_axis(true)
-            .cross_axis_alignment(CrossAxisAlignment::Fill),
-            worker(
-                worker_value,
-                |proxy, mut rx| async move {
-                    while let Some(request) = rx.recv().await {
-                        if let Some(code) = request {
-                            let proxy = proxy.clone();
-                            tokio::task::spawn(async move {
-                                let url = format!("https://http.cat/{code}");
-                                let result = image_from_url(&url).await;
-                                match result {
-                                    // We choose not to handle the case where the event loop has ended
-                                    Ok(image) => drop(proxy.message((code, image))),
-                                    // TODO: Report in the frontend
-                                    Err(err) => {
-                                        tracing::warn!(
-                                            "Loading image for HTTP status code {code} from {url} failed: {err:?}"
-                                        );
-                                    }
-                                }
-                            });
-                        }
-                    }
-                },
-                |state: &mut HttpCats, (code, image): (u32, Image)| {
-                    if let Some(status) = state.statuses.iter_mut().find(|it| it.code == code) {
-                        status.image = ImageState::Available(image);
-                    } else {
-                        // TODO: Error handling?
-                    }
-                },
-            ),
-        )
+            .flex(1.),
+        ))
+        .must_fill_major_axis(true)
+        .cross_axis_alignment(CrossAxisAlignment::Fill)
     }
 }
 
-/// Load a [`vello::peniko::Image`] from the given url.
-async fn image_from_url(url: &str) -> anyhow::Result<Image> {
-    let response = reqwest::get(url).await?;
-    let bytes = response.bytes().await?;
-    let image = image::load_from_memory(&bytes)?.into_rgba8();
-    let width = image.width();
-    let height = image.height();
-    let data = image.into_vec();
-    Ok(Image::new(
-        Blob::new(Arc::new(data)),
-        vello::peniko::Format::Rgba8,
-        width,
-        height,
-    ))
+/// The source of the image at the given url.
+///
+/// The image is downloaded when it is first shown, and decoded and cached by Xilem.
+fn image_from_url(url: String) -> ImageSource {
+    ImageSource::loader(url.clone(), move || {
+        let url = url.clone();
+        async move {
+            let response = reqwest::get(&url).await?.error_for_status()?;
+            let bytes = response.bytes().await?;
+            Ok(bytes.to_vec())
+        }
+    })
 }
 
 impl Status {
@@ -166,8 +96,6 @@ impl Status {
             prose(self.code.to_string()),
             prose(self.message),
             FlexSpacer::Flex(1.),
-            // TODO: Spinner if image pending?
-            // TODO: Tick if image loaded?
             button("Select", move |state: &mut HttpCats| {
                 state.selected_code = Some(code);
             }),
@@ -177,15 +105,8 @@ impl Status {
     }
 
     fn details_view(&mut self) -> impl WidgetView<HttpCats> {
-        let image = match &self.image {
-            ImageState::NotRequested => OneOf3::A(
-                prose("Failed to start fetching image. This is a bug!")
-                    .alignment(TextAlignment::Middle),
-            ),
-            ImageState::Pending => OneOf3::B(sized_box(spinner()).width(80.).height(80.)),
-            // TODO: Alt text?
-            ImageState::Available(image_data) => OneOf3::C(image(image_data)),
-        };
+        // TODO: Alt text?
+        let image = image_source(image_from_url(format!("https://http.cat/{}", self.code)));
         flex((
             prose(format!("HTTP Status Code: {}", self.code)).alignment(TextAlignment::Middle),
             prose(self.message)
@@ -233,7 +154,6 @@ impl Status {
         Some(Self {
             code: code.parse().ok()?,
             message: message.trim(),
-            image: ImageState::NotRequested,
         })
     }
 }
@@ -8,11 +8,10 @@ use masonry::event_loop_runner::{EventLoop, EventLoopBuilder};
 use masonry::widget::{Axis, CrossAxisAlignment, MainAxisAlignment};
 use std::ops::{Add, Sub};
 use std::time::{Duration, SystemTime};
-use tokio::time;
 use tracing::warn;
 use winit::error::EventLoopError;
 use winit::window::Window;
-use xilem::view::{button, flex, label, task, FlexSequence, FlexSpacer};
+use xilem::view::{button, flex, label, stream, ticks, FlexSequence, FlexSpacer};
 use xilem::{WidgetView, Xilem};
 use xilem_core::fork;
 use xilem_core::one_of::Either;
@@ -117,19 +116,10 @@ fn app_logic(data: &mut Stopwatch) -> impl WidgetView<Stopwatch> {
         )),
         data.active.then(|| {
             // Only update while active.
-            task(
-                |proxy| async move {
-                    let mut interval = time::interval(Duration::from_millis(50));
-                    loop {
-                        interval.tick().await;
-                        let Ok(()) = proxy.message(()) else {
-                            break;
-                        };
-                    }
-                },
-                |data: &mut Stopwatch, ()| {
-                    data.update_display();
-                },
+            stream(
+                Duration::from_millis(50),
+                ticks,
+                |data: &mut Stopwatch, ()| data.update_display(),
             )
         }),
     )
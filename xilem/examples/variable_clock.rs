@@ -13,8 +13,8 @@ use time::{error::IndeterminateOffset, macros::format_description, OffsetDateTim
 use winit::error::EventLoopError;
 use xilem::{
     view::{
-        button, flex, label, portal, prose, sized_box, task, variable_label, Axis, FlexExt,
-        FlexSpacer,
+        button, flex, label, portal, prose, sized_box, stream, ticks, variable_label, Axis,
+        FlexExt, FlexSpacer,
     },
     Color, EventLoop, EventLoopBuilder, WidgetView, Xilem,
 };
@@ -52,18 +52,11 @@ fn app_logic(data: &mut Clocks) -> impl WidgetView<Clocks> {
     ));
     fork(
         view,
-        task(
-            |proxy| async move {
-                // TODO: Synchronise with the actual "second" interval. This is expected to show the wrong second
-                // ~50% of the time.
-                let mut interval = tokio::time::interval(Duration::from_secs(1));
-                loop {
-                    interval.tick().await;
-                    let Ok(()) = proxy.message(()) else {
-                        break;
-                    };
-                }
-            },
+        stream(
+            // TODO: Synchronise with the actual "second" interval. This is expected to show the wrong second
+            // ~50% of the time.
+            Duration::from_secs(1),
+            ticks,
             |data: &mut Clocks, ()| data.now_utc = OffsetDateTime::now_utc(),
         ),
     )
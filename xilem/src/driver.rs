@@ -4,14 +4,18 @@
 use std::sync::Arc;
 
 use masonry::{
-    app_driver::AppDriver,
-    event_loop_runner::{self, EventLoopProxy, MasonryUserEvent},
+    app_driver::{AppDriver, DriverCtx},
+    event_loop_runner::{self, EventLoopProxy, MasonryUserEvent, WindowState},
     widget::RootWidget,
     WidgetId,
 };
-use xilem_core::{DynMessage, Message, MessageResult, ProxyError, RawProxy, ViewId};
+use xilem_core::{DynMessage, Message, MessageCodec, MessageResult, ProxyError, RawProxy, ViewId};
 
-use crate::{ViewCtx, WidgetView};
+use crate::{
+    persist::{Persister, SaveState},
+    record::{MessageSource, Recorder, Recording},
+    ViewCtx, WidgetView,
+};
 
 pub struct MasonryDriver<State, Logic, View, ViewState> {
     pub(crate) state: State,
@@ -21,6 +25,10 @@ pub struct MasonryDriver<State, Logic, View, ViewState> {
     pub(crate) view_state: ViewState,
     // Fonts which will be registered on startup.
     pub(crate) fonts: Vec<Vec<u8>>,
+    pub(crate) recorder: Option<Recorder>,
+    pub(crate) persister: Option<Persister<State>>,
+    /// Whether the app logic is only run once, see [`Xilem::fine_grained`](crate::Xilem::fine_grained).
+    pub(crate) fine_grained: bool,
 }
 
 /// The `WidgetId` which async events should be sent to.
@@ -65,36 +73,59 @@ impl MasonryProxy {
     }
 }
 
-impl<State, Logic, View> AppDriver for MasonryDriver<State, Logic, View, View::ViewState>
+impl<State, Logic, View> MasonryDriver<State, Logic, View, View::ViewState>
 where
     Logic: FnMut(&mut State) -> View,
     View: WidgetView<State>,
 {
-    fn on_action(
+    /// The current app state.
+    pub fn state(&self) -> &State {
+        &self.state
+    }
+
+    /// Replay the messages in `recording`, as if they had been sent to this app.
+    ///
+    /// The messages are decoded using `codec`, which should have the same message types registered
+    /// as the codec used to create the recording.
+    /// Messages which can't be decoded, or which have become stale, are skipped.
+    ///
+    /// Async tasks started by the app still run during the replay, so for deterministic results,
+    /// the driver should be created with a [`RawProxy`] which discards their messages.
+    /// The replayed messages are not recorded again.
+    ///
+    /// In a Masonry [`TestHarness`](masonry::testing::TestHarness), the `DriverCtx` can be
+    /// obtained using [`with_driver_ctx`](masonry::testing::TestHarness::with_driver_ctx):
+    ///
+    /// ```ignore
+    /// harness.with_driver_ctx(|ctx| driver.replay(ctx, &recording, &codec));
+    /// ```
+    pub fn replay(
         &mut self,
-        masonry_ctx: &mut masonry::app_driver::DriverCtx<'_>,
-        widget_id: masonry::WidgetId,
-        action: masonry::Action,
+        masonry_ctx: &mut DriverCtx<'_>,
+        recording: &Recording,
+        codec: &MessageCodec,
     ) {
-        let message_result = if widget_id == ASYNC_MARKER_WIDGET {
-            let masonry::Action::Other(action) = action else {
-                panic!();
+        for recorded in &recording.messages {
+            let Some(message) = codec.decode(&recorded.message) else {
+                tracing::error!(
+                    "Could not decode recorded message of type {:?}, skipping it",
+                    recorded.message.type_name
+                );
+                continue;
             };
-            let (path, message) = *action.downcast::<MessagePackage>().unwrap();
-            // Handle an async path
+            self.handle_message(masonry_ctx, &recorded.view_path(), message);
+        }
+    }
+
+    fn handle_message(
+        &mut self,
+        masonry_ctx: &mut DriverCtx<'_>,
+        id_path: &[ViewId],
+        message: DynMessage,
+    ) {
+        let message_result =
             self.current_view
-                .message(&mut self.view_state, &path, message, &mut self.state)
-        } else if let Some(id_path) = self.ctx.widget_map.get(&widget_id) {
-            self.current_view.message(
-                &mut self.view_state,
-                id_path.as_slice(),
-                Box::new(action),
-                &mut self.state,
-            )
-        } else {
-            tracing::error!("Got action {action:?} for unknown widget. Did you forget to use `with_action_widget`?");
-            return;
-        };
+                .message(&mut self.view_state, id_path, message, &mut self.state);
         let rebuild = match message_result {
             MessageResult::Action(()) => {
                 // It's not entirely clear what to do here
@@ -107,7 +138,16 @@ where
                 false
             }
         };
-        if rebuild {
+        if rebuild && self.fine_grained {
+            let mut root = masonry_ctx.get_root::<RootWidget<View::Widget>>();
+            self.current_view.rebuild(
+                &self.current_view,
+                &mut self.view_state,
+                &mut self.ctx,
+                root.get_element(),
+            );
+            self.schedule_save();
+        } else if rebuild {
             let next_view = (self.logic)(&mut self.state);
 
             let mut root = masonry_ctx.get_root::<RootWidget<View::Widget>>();
@@ -122,8 +162,83 @@ where
                 tracing::debug!("Nothing changed as result of action");
             }
             self.current_view = next_view;
+            self.schedule_save();
         }
     }
+
+    /// Save the state once the debounce time has elapsed, unless it changes again before that.
+    fn schedule_save(&mut self) {
+        let Some(persister) = &mut self.persister else {
+            return;
+        };
+        persister.generation += 1;
+        let message = SaveState {
+            generation: persister.generation,
+        };
+        let debounce = persister.debounce();
+        let proxy = self.ctx.proxy.clone();
+        self.ctx.runtime.spawn(async move {
+            tokio::time::sleep(debounce).await;
+            // If the app has already exited, it was saved then.
+            drop(proxy.send_message(Arc::from([]), Box::new(message)));
+        });
+    }
+
+    fn save(&mut self, masonry_ctx: &mut DriverCtx<'_>) {
+        let Some(persister) = &mut self.persister else {
+            return;
+        };
+        persister.save(
+            &self.state,
+            &self.ctx.persisted_widgets,
+            masonry_ctx.main_root_widget.as_widget_ref(),
+        );
+    }
+}
+
+impl<State, Logic, View> AppDriver for MasonryDriver<State, Logic, View, View::ViewState>
+where
+    Logic: FnMut(&mut State) -> View,
+    View: WidgetView<State>,
+{
+    fn on_action(
+        &mut self,
+        masonry_ctx: &mut masonry::app_driver::DriverCtx<'_>,
+        widget_id: masonry::WidgetId,
+        action: masonry::Action,
+    ) {
+        let (source, path, message) = if widget_id == ASYNC_MARKER_WIDGET {
+            let masonry::Action::Other(action) = action else {
+                panic!();
+            };
+            let (path, message) = *action.downcast::<MessagePackage>().unwrap();
+            let message = match message.downcast::<SaveState>() {
+                Ok(save) => {
+                    let is_latest = self
+                        .persister
+                        .as_ref()
+                        .is_some_and(|persister| persister.generation == save.generation);
+                    if is_latest {
+                        self.save(masonry_ctx);
+                    }
+                    return;
+                }
+                Err(message) => message,
+            };
+            // Handle an async path
+            (MessageSource::Async, path, message)
+        } else if let Some(id_path) = self.ctx.widget_map.get(&widget_id) {
+            let message: DynMessage = Box::new(action);
+            (MessageSource::Widget, id_path.as_slice().into(), message)
+        } else {
+            tracing::error!("Got action {action:?} for unknown widget. Did you forget to use `with_action_widget`?");
+            return;
+        };
+        if let Some(recorder) = &self.recorder {
+            recorder.record(source, &path, &*message);
+        }
+        self.handle_message(masonry_ctx, &path, message);
+    }
     fn on_start(&mut self, state: &mut event_loop_runner::MasonryState) {
         let root = state.get_root();
         // Register all provided fonts
@@ -134,4 +249,82 @@ where
             drop(root.register_fonts(font));
         }
     }
+    fn on_exit(&mut self, state: &mut event_loop_runner::MasonryState) {
+        let Some(persister) = &mut self.persister else {
+            return;
+        };
+        if let WindowState::Rendering { window, .. } | WindowState::Suspended { window, .. } =
+            state.get_window_state()
+        {
+            let size = window.inner_size().to_logical(window.scale_factor());
+            persister.ui.window_size = Some((size.width, size.height));
+        }
+        persister.save(
+            &self.state,
+            &self.ctx.persisted_widgets,
+            state.get_root().get_root_widget(),
+        );
+    }
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use xilem_core::{reactive, Signal};
+
+    use crate::testing::TestApp;
+    use crate::view::{button, flex, label};
+    use crate::{WidgetView, Xilem};
+
+    struct AppState {
+        count: Signal<u32>,
+        logic_runs: u32,
+    }
+
+    fn app_logic(state: &mut AppState) -> impl WidgetView<AppState> {
+        state.logic_runs += 1;
+        let count = state.count.clone();
+        flex((
+            button("Increment", |state: &mut AppState| {
+                state.count.update(|count| *count += 1);
+            }),
+            reactive(move || label(format!("Count: {}", count.get()))),
+        ))
+    }
+
+    fn increment<Logic, View>(app: &mut TestApp<AppState, Logic, View>)
+    where
+        Logic: FnMut(&mut AppState) -> View,
+        View: WidgetView<AppState>,
+    {
+        let button_id = app.harness.root_widget().children()[0].children()[0].id();
+        app.harness.mouse_click_on(button_id);
+        app.handle_actions();
+    }
+
+    fn state() -> AppState {
+        AppState {
+            count: Signal::new(0),
+            logic_runs: 0,
+        }
+    }
+
+    #[test]
+    fn fine_grained_skips_app_logic() {
+        let mut app = TestApp::new(Xilem::new(state(), app_logic).fine_grained());
+        assert_eq!(app.label_texts(), ["Increment", "Count: 0"]);
+
+        increment(&mut app);
+        increment(&mut app);
+        assert_eq!(app.label_texts(), ["Increment", "Count: 2"]);
+        assert_eq!(app.state().logic_runs, 1);
+    }
+
+    #[test]
+    fn app_logic_runs_after_each_message() {
+        let mut app = TestApp::new(Xilem::new(state(), app_logic));
+        increment(&mut app);
+        assert_eq!(app.label_texts(), ["Increment", "Count: 1"]);
+        assert_eq!(app.state().logic_runs, 2);
+    }
 }
@@ -37,8 +37,15 @@ pub use any_view::AnyWidgetView;
 mod driver;
 pub use driver::{async_action, MasonryDriver, MasonryProxy, ASYNC_MARKER_WIDGET};
 
+pub mod persist;
+
+pub mod record;
+
 pub mod view;
 
+#[cfg(test)]
+mod testing;
+
 /// Tokio is the async runner used with Xilem.
 pub use tokio;
 
@@ -49,6 +56,9 @@ pub struct Xilem<State, Logic> {
     background_color: Color,
     // Font data to include in loading.
     fonts: Vec<Vec<u8>>,
+    recorder: Option<record::Recorder>,
+    persister: Option<persist::Persister<State>>,
+    fine_grained: bool,
 }
 
 impl<State, Logic, View> Xilem<State, Logic>
@@ -64,6 +74,9 @@ where
             runtime,
             background_color: Color::BLACK,
             fonts: Vec::new(),
+            recorder: None,
+            persister: None,
+            fine_grained: false,
         }
     }
 
@@ -75,6 +88,38 @@ where
         self
     }
 
+    /// Record the messages handled by this app using `recorder`.
+    ///
+    /// See the [`record`] module for details.
+    pub fn with_recorder(mut self, recorder: record::Recorder) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    /// Persist the app state using `persistence`.
+    ///
+    /// The state previously saved is restored immediately, replacing the initial state,
+    /// and will be saved again when the app exits and after it changes.
+    /// See the [`persist`] module for details.
+    pub fn persist(mut self, persistence: persist::Persistence) -> Self
+    where
+        State: serde::Serialize + serde::de::DeserializeOwned,
+    {
+        self.persister = Some(persist::Persister::restore(persistence, &mut self.state));
+        self
+    }
+
+    /// Only run the app logic once, when the app starts, rather than after every message.
+    ///
+    /// After each message, the initial view tree is instead rebuilt against itself, which
+    /// recreates only the [`reactive`](core::reactive) views whose [`Signal`](core::Signal)s
+    /// have changed, and skips all other views.
+    /// All data which the views show and which can change must therefore be stored in signals.
+    pub fn fine_grained(mut self) -> Self {
+        self.fine_grained = true;
+        self
+    }
+
     /// Sets main window background color.
     pub fn background_color(mut self, color: Color) -> Self {
         self.background_color = color;
@@ -106,7 +151,7 @@ where
     pub fn run_windowed_in(
         self,
         mut event_loop: EventLoopBuilder,
-        window_attributes: WindowAttributes,
+        mut window_attributes: WindowAttributes,
     ) -> Result<(), EventLoopError>
     where
         State: 'static,
@@ -116,6 +161,13 @@ where
         let event_loop = event_loop.build()?;
         let proxy = event_loop.create_proxy();
         let bg_color = self.background_color;
+        if let Some((width, height)) = self
+            .persister
+            .as_ref()
+            .and_then(|persister| persister.ui.window_size)
+        {
+            window_attributes = window_attributes.with_inner_size(LogicalSize::new(width, height));
+        }
         let (root_widget, driver) = self.into_driver(Arc::new(MasonryProxy(proxy)));
         event_loop_runner::run_with(event_loop, window_attributes, root_widget, driver, bg_color)
     }
@@ -128,12 +180,17 @@ where
         MasonryDriver<State, Logic, View, View::ViewState>,
     ) {
         let first_view = (self.logic)(&mut self.state);
+        let mut persisted_widgets = persist::PersistedWidgets::default();
+        if let Some(persister) = &self.persister {
+            persisted_widgets.restored = persister.ui.widgets.clone();
+        }
         let mut ctx = ViewCtx {
             widget_map: WidgetMap::default(),
             id_path: Vec::new(),
             view_tree_changed: false,
             proxy,
             runtime: self.runtime,
+            persisted_widgets,
         };
         let (pod, view_state) = first_view.build(&mut ctx);
         let root_widget = RootWidget::from_pod(pod.inner);
@@ -144,6 +201,9 @@ where
             ctx,
             view_state,
             fonts: self.fonts,
+            recorder: self.recorder,
+            persister: self.persister,
+            fine_grained: self.fine_grained,
         };
         (root_widget, driver)
     }
@@ -212,6 +272,103 @@ pub trait WidgetView<State, Action = ()>:
     {
         Box::new(self)
     }
+
+    /// Aligns this view within the space given to it, see [`align`](view::align).
+    ///
+    /// # Examples
+    /// ```
+    /// use xilem::{view::{label, UnitPoint}, WidgetView};
+    ///
+    /// # fn view<State: 'static>() -> impl WidgetView<State> {
+    /// label("a label").align(UnitPoint::TOP_RIGHT)
+    /// # }
+    ///
+    /// ```
+    fn align(self, align: view::UnitPoint) -> view::Align<Self, State, Action>
+    where
+        Self: Sized,
+    {
+        view::align(align, self)
+    }
+
+    /// Centers this view within the space given to it.
+    ///
+    /// This is equivalent to `.align(UnitPoint::CENTER)`.
+    fn centered(self) -> view::Align<Self, State, Action>
+    where
+        Self: Sized,
+    {
+        view::align(view::UnitPoint::CENTER, self)
+    }
+
+    /// Calls `on_click` when this view is clicked, see [`gestures`](view::gestures).
+    fn on_click<F>(self, on_click: F) -> view::Gestures<Self, State, Action>
+    where
+        Self: Sized,
+        F: Fn(&mut State, &view::Click) -> Action + Send + Sync + 'static,
+    {
+        view::gestures(self).on_click(on_click)
+    }
+
+    /// Calls `on_double_click` when this view is double-clicked, see [`gestures`](view::gestures).
+    ///
+    /// # Examples
+    /// ```
+    /// use xilem::{view::label, WidgetView};
+    ///
+    /// # fn view() -> impl WidgetView<bool> {
+    /// label("Double-click me").on_double_click(|expanded: &mut bool, _| *expanded = !*expanded)
+    /// # }
+    ///
+    /// ```
+    fn on_double_click<F>(self, on_double_click: F) -> view::Gestures<Self, State, Action>
+    where
+        Self: Sized,
+        F: Fn(&mut State, &view::Click) -> Action + Send + Sync + 'static,
+    {
+        view::gestures(self).on_double_click(on_double_click)
+    }
+
+    /// Calls `on_long_press` when this view is pressed for a while, see [`gestures`](view::gestures).
+    fn on_long_press<F>(self, on_long_press: F) -> view::Gestures<Self, State, Action>
+    where
+        Self: Sized,
+        F: Fn(&mut State, &view::LongPress) -> Action + Send + Sync + 'static,
+    {
+        view::gestures(self).on_long_press(on_long_press)
+    }
+
+    /// Calls `on_drag` when this view is dragged, see [`gestures`](view::gestures).
+    ///
+    /// # Examples
+    /// ```
+    /// use xilem::{view::{label, DragPhase}, WidgetView};
+    ///
+    /// # fn view() -> impl WidgetView<f64> {
+    /// label("Drag me").on_drag(|offset: &mut f64, drag| {
+    ///     if drag.phase != DragPhase::End {
+    ///         *offset += drag.delta.x;
+    ///     }
+    /// })
+    /// # }
+    ///
+    /// ```
+    fn on_drag<F>(self, on_drag: F) -> view::Gestures<Self, State, Action>
+    where
+        Self: Sized,
+        F: Fn(&mut State, &view::Drag) -> Action + Send + Sync + 'static,
+    {
+        view::gestures(self).on_drag(on_drag)
+    }
+
+    /// Calls `on_pan_zoom` on touchpad and two-finger touch pans and pinches over this view, see [`gestures`](view::gestures).
+    fn on_pan_zoom<F>(self, on_pan_zoom: F) -> view::Gestures<Self, State, Action>
+    where
+        Self: Sized,
+        F: Fn(&mut State, &view::PanZoom) -> Action + Send + Sync + 'static,
+    {
+        view::gestures(self).on_pan_zoom(on_pan_zoom)
+    }
 }
 
 impl<V, State, Action, W> WidgetView<State, Action> for V
@@ -257,6 +414,7 @@ pub struct ViewCtx {
     view_tree_changed: bool,
     proxy: Arc<dyn RawProxy>,
     runtime: tokio::runtime::Runtime,
+    persisted_widgets: persist::PersistedWidgets,
 }
 
 impl ViewPathTracker for ViewCtx {
@@ -302,6 +460,40 @@ impl ViewCtx {
     pub fn runtime(&self) -> &tokio::runtime::Runtime {
         &self.runtime
     }
+
+    /// Take the restored UI state persisted under `key`, if the app is [persisted](Xilem::persist).
+    ///
+    /// This returns `None` for subsequent calls with the same key, so that only the
+    /// first view built with that key is restored.
+    pub fn take_restored_ui_state(&mut self, key: &str) -> Option<persist::WidgetUiState> {
+        self.persisted_widgets.restored.remove(key)
+    }
+
+    /// Persist the UI state of the widget `id` under `key`, by reading it using `read` whenever the app is saved.
+    ///
+    /// This replaces any other widget registered with the same key.
+    pub fn register_ui_state(
+        &mut self,
+        key: impl Into<String>,
+        id: WidgetId,
+        read: persist::ReadUiState,
+    ) {
+        self.persisted_widgets
+            .registered
+            .insert(key.into(), (id, read));
+    }
+
+    /// Stop persisting the UI state registered under `key`, if it is still registered for the widget `id`.
+    pub fn unregister_ui_state(&mut self, key: &str, id: WidgetId) {
+        if self
+            .persisted_widgets
+            .registered
+            .get(key)
+            .is_some_and(|(registered, _)| *registered == id)
+        {
+            self.persisted_widgets.registered.remove(key);
+        }
+    }
 }
 
 impl AsyncCtx for ViewCtx {
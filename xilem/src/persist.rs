@@ -0,0 +1,405 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Saving the state of a Xilem app, and restoring it on startup.
+//!
+//! Persistence can be enabled using [`Xilem::persist`](crate::Xilem::persist), for app states which
+//! implement [`Serialize`] and [`DeserializeOwned`].
+//! The state is then restored when the app is created, and saved whenever the app exits,
+//! as well as a configurable time after it was last changed.
+//!
+//! Alongside the app state, some UI state is persisted:
+//! - The size of the window.
+//! - The viewport position of [`portal`](crate::view::portal)s with a
+//!   [`persist_as`](crate::view::Portal::persist_as) key.
+//! - The split point of [`split`](crate::view::split)s with a
+//!   [`persist_as`](crate::view::Split::persist_as) key.
+//!
+//! Where the data is stored is determined by a [`Storage`].
+//! [`FileStorage`] stores it as JSON files in a directory, whilst [`MemoryStorage`] can be used in tests.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    io,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use masonry::{widget::WidgetRef, Widget, WidgetId};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+/// Where persisted data is stored.
+pub trait Storage: 'static {
+    /// Load the data previously saved under `key`, if any.
+    ///
+    /// # Errors
+    ///
+    /// If the data exists, but couldn't be loaded.
+    fn load(&self, key: &str) -> io::Result<Option<String>>;
+
+    /// Save `data` under `key`, replacing any previously saved data.
+    ///
+    /// # Errors
+    ///
+    /// If the data couldn't be saved.
+    fn save(&self, key: &str, data: &str) -> io::Result<()>;
+}
+
+/// A [`Storage`] which saves each key as a JSON file in a directory.
+#[derive(Debug, Clone)]
+pub struct FileStorage {
+    dir: PathBuf,
+}
+
+impl FileStorage {
+    /// Store files in `dir`, which will be created if it doesn't exist.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{key}.json"))
+    }
+}
+
+impl Storage for FileStorage {
+    fn load(&self, key: &str) -> io::Result<Option<String>> {
+        match std::fs::read_to_string(self.path(key)) {
+            Ok(data) => Ok(Some(data)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn save(&self, key: &str, data: &str) -> io::Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        // Write to a temporary file first, so that the previous data isn't lost
+        // if the app crashes whilst saving.
+        let path = self.path(key);
+        let temp_path = path.with_extension("json.tmp");
+        std::fs::write(&temp_path, data)?;
+        std::fs::rename(temp_path, path)
+    }
+}
+
+/// A [`Storage`] which keeps the data in memory, for use in tests.
+///
+/// This is a cheaply clonable handle, so that the saved data can be inspected
+/// whilst (or after) the app is running.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryStorage {
+    entries: Arc<Mutex<HashMap<String, String>>>,
+}
+
+impl MemoryStorage {
+    /// Create an empty storage.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The data saved under `key`, if any.
+    pub fn get(&self, key: &str) -> Option<String> {
+        self.entries.lock().unwrap().get(key).cloned()
+    }
+
+    /// Save `data` under `key`, e.g. to test restoring an app.
+    pub fn insert(&self, key: impl Into<String>, data: impl Into<String>) {
+        self.entries.lock().unwrap().insert(key.into(), data.into());
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn load(&self, key: &str) -> io::Result<Option<String>> {
+        Ok(self.get(key))
+    }
+
+    fn save(&self, key: &str, data: &str) -> io::Result<()> {
+        self.insert(key, data);
+        Ok(())
+    }
+}
+
+/// The configuration of how an app is persisted.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use xilem::persist::{FileStorage, Persistence};
+///
+/// let persistence = Persistence::new(FileStorage::new("app_data"))
+///     .key("todos")
+///     .debounce(Duration::from_secs(5));
+/// ```
+pub struct Persistence {
+    storage: Box<dyn Storage>,
+    key: String,
+    debounce: Duration,
+}
+
+impl Persistence {
+    /// Persist the app in `storage`.
+    ///
+    /// By default, the data is saved under the key `"app"`, one second after the state was last changed.
+    pub fn new(storage: impl Storage) -> Self {
+        Self {
+            storage: Box::new(storage),
+            key: "app".into(),
+            debounce: Duration::from_secs(1),
+        }
+    }
+
+    /// Set the key the data is saved under.
+    pub fn key(mut self, key: impl Into<String>) -> Self {
+        self.key = key.into();
+        self
+    }
+
+    /// Set how long after the last change to the app state it should be saved.
+    ///
+    /// The state is always saved when the app exits.
+    pub fn debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+}
+
+/// The state of a widget which is persisted alongside the app state.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum WidgetUiState {
+    /// The viewport position of a scrollable region.
+    Viewport { x: f64, y: f64 },
+    /// The split point of a split container.
+    SplitPoint(f64),
+}
+
+/// The UI state which is persisted alongside the app state.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UiState {
+    /// The logical size of the window.
+    pub window_size: Option<(f64, f64)>,
+    /// The state of the widgets with a persistence key, by key.
+    pub widgets: BTreeMap<String, WidgetUiState>,
+}
+
+/// Read the [`WidgetUiState`] of a widget, if it has the expected type.
+pub type ReadUiState = fn(WidgetRef<'_, dyn Widget>) -> Option<WidgetUiState>;
+
+/// The widgets whose state is persisted, see [`ViewCtx::register_ui_state`](crate::ViewCtx::register_ui_state).
+#[derive(Default)]
+pub(crate) struct PersistedWidgets {
+    /// The restored states which haven't been used by a view yet.
+    pub(crate) restored: BTreeMap<String, WidgetUiState>,
+    pub(crate) registered: BTreeMap<String, (WidgetId, ReadUiState)>,
+}
+
+impl PersistedWidgets {
+    /// The current state of all persisted widgets, including those which haven't been built since restoring.
+    fn snapshot(&self, root: WidgetRef<'_, dyn Widget>) -> BTreeMap<String, WidgetUiState> {
+        let mut widgets = self.restored.clone();
+        for (key, (id, read)) in &self.registered {
+            let Some(widget) = root.find_widget_by_id(*id) else {
+                continue;
+            };
+            if let Some(state) = read(widget) {
+                widgets.insert(key.clone(), state);
+            }
+        }
+        widgets
+    }
+}
+
+#[derive(Serialize)]
+struct Saved<'a> {
+    state: serde_json::Value,
+    ui: &'a UiState,
+}
+
+#[derive(Deserialize)]
+struct Restored<State> {
+    state: State,
+    #[serde(default)]
+    ui: UiState,
+}
+
+/// The message sent to the driver once the debounce time after a change has elapsed.
+#[derive(Debug)]
+pub(crate) struct SaveState {
+    pub(crate) generation: u64,
+}
+
+/// Type erased persistence for a specific app state.
+pub(crate) struct Persister<State> {
+    persistence: Persistence,
+    serialize: fn(&State) -> serde_json::Result<serde_json::Value>,
+    pub(crate) ui: UiState,
+    pub(crate) generation: u64,
+}
+
+impl<State> Persister<State> {
+    /// Restore the previously saved state into `state`, if there is any.
+    pub(crate) fn restore(persistence: Persistence, state: &mut State) -> Self
+    where
+        State: Serialize + DeserializeOwned,
+    {
+        let mut ui = UiState::default();
+        match persistence.storage.load(&persistence.key) {
+            Ok(Some(data)) => match serde_json::from_str::<Restored<State>>(&data) {
+                Ok(restored) => {
+                    *state = restored.state;
+                    ui = restored.ui;
+                }
+                Err(err) => {
+                    tracing::error!(
+                        "Could not restore the app state, using the initial state: {err}"
+                    );
+                }
+            },
+            Ok(None) => {}
+            Err(err) => {
+                tracing::error!("Could not load the app state, using the initial state: {err}");
+            }
+        }
+        Self {
+            persistence,
+            serialize: |state| serde_json::to_value(state),
+            ui,
+            generation: 0,
+        }
+    }
+
+    pub(crate) fn debounce(&self) -> Duration {
+        self.persistence.debounce
+    }
+
+    /// Save `state`, together with the current state of the persisted widgets.
+    pub(crate) fn save(
+        &mut self,
+        state: &State,
+        widgets: &PersistedWidgets,
+        root: WidgetRef<'_, dyn Widget>,
+    ) {
+        self.ui.widgets = widgets.snapshot(root);
+        let state = match (self.serialize)(state) {
+            Ok(state) => state,
+            Err(err) => {
+                tracing::error!("Could not serialize the app state: {err}");
+                return;
+            }
+        };
+        let saved = Saved {
+            state,
+            ui: &self.ui,
+        };
+        let data = match serde_json::to_string(&saved) {
+            Ok(data) => data,
+            Err(err) => {
+                tracing::error!("Could not serialize the app state: {err}");
+                return;
+            }
+        };
+        if let Err(err) = self.persistence.storage.save(&self.persistence.key, &data) {
+            tracing::error!("Could not save the app state: {err}");
+        }
+    }
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use masonry::widget;
+
+    use super::*;
+    use crate::testing::TestApp;
+    use crate::view::{button, label, split};
+    use crate::{WidgetView, Xilem};
+
+    #[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+    struct AppState {
+        count: u32,
+    }
+
+    fn app_logic(state: &mut AppState) -> impl WidgetView<AppState> {
+        split(
+            button("Increment", |state: &mut AppState| state.count += 1),
+            label(state.count.to_string()),
+        )
+        .persist_as("split")
+    }
+
+    fn persistence(storage: &MemoryStorage) -> Persistence {
+        Persistence::new(storage.clone()).debounce(Duration::ZERO)
+    }
+
+    fn split_point<Logic, View: WidgetView<AppState>>(app: &TestApp<AppState, Logic, View>) -> f64 {
+        let root = app.harness.root_widget();
+        let split = root.children()[0];
+        split.downcast::<widget::Split>().unwrap().get_split_point()
+    }
+
+    #[test]
+    fn save_and_restore() {
+        let storage = MemoryStorage::new();
+        let mut app =
+            TestApp::new(Xilem::new(AppState::default(), app_logic).persist(persistence(&storage)));
+        let button_id = app.harness.root_widget().children()[0].children()[0].id();
+        app.harness.mouse_click_on(button_id);
+        app.handle_actions();
+        assert!(storage.get("app").is_none());
+
+        // The save is sent as a message once the debounce time has elapsed.
+        app.handle_async_message();
+        let saved: serde_json::Value = serde_json::from_str(&storage.get("app").unwrap()).unwrap();
+        assert_eq!(saved["state"]["count"], 1);
+        assert_eq!(saved["ui"]["widgets"]["split"]["SplitPoint"], 0.5);
+
+        let restored =
+            TestApp::new(Xilem::new(AppState::default(), app_logic).persist(persistence(&storage)));
+        assert_eq!(*restored.state(), AppState { count: 1 });
+        assert_eq!(restored.label_texts(), ["Increment", "1"]);
+    }
+
+    #[test]
+    fn restore_widget_state() {
+        let storage = MemoryStorage::new();
+        storage.insert(
+            "app",
+            r#"{"state":{"count":3},"ui":{"window_size":null,"widgets":{"split":{"SplitPoint":0.25}}}}"#,
+        );
+        let app =
+            TestApp::new(Xilem::new(AppState::default(), app_logic).persist(persistence(&storage)));
+        assert_eq!(*app.state(), AppState { count: 3 });
+        assert_eq!(split_point(&app), 0.25);
+    }
+
+    #[test]
+    fn missing_ui_state_uses_defaults() {
+        let storage = MemoryStorage::new();
+        storage.insert("app", r#"{"state":{"count":3}}"#);
+        let app =
+            TestApp::new(Xilem::new(AppState::default(), app_logic).persist(persistence(&storage)));
+        assert_eq!(*app.state(), AppState { count: 3 });
+        assert_eq!(split_point(&app), 0.5);
+    }
+
+    #[test]
+    fn corrupted_data_uses_initial_state() {
+        let storage = MemoryStorage::new();
+        storage.insert("app", r#"{"state":{"count":"#);
+        let app =
+            TestApp::new(Xilem::new(AppState::default(), app_logic).persist(persistence(&storage)));
+        assert_eq!(*app.state(), AppState::default());
+        assert_eq!(split_point(&app), 0.5);
+    }
+
+    #[test]
+    fn missing_key_uses_initial_state() {
+        let storage = MemoryStorage::new();
+        storage.insert("other", r#"{"state":{"count":3}}"#);
+        let app =
+            TestApp::new(Xilem::new(AppState::default(), app_logic).persist(persistence(&storage)));
+        assert_eq!(*app.state(), AppState::default());
+        assert!(storage.get("app").is_none());
+    }
+}
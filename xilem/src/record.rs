@@ -0,0 +1,442 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Recording and replaying the messages sent to a Xilem app, for deterministic debugging.
+//!
+//! A [`Recorder`] can be attached to an app using [`Xilem::with_recorder`](crate::Xilem::with_recorder).
+//! It then logs every message routed through the view tree, i.e. the actions of Masonry widgets
+//! and the results of async tasks, together with the path of the view they were sent to.
+//! The resulting [`Recording`] can be saved as text, and later be replayed using
+//! [`MasonryDriver::replay`](crate::MasonryDriver::replay), for example in a Masonry
+//! [`TestHarness`](masonry::testing::TestHarness).
+//!
+//! Only messages whose type is registered in the [`MessageCodec`] can be recorded.
+//! Use [`default_codec`] to get a codec which supports Masonry's [`Action`]s,
+//! except for [`Action::Other`], which is skipped.
+
+use std::sync::{Arc, Mutex};
+
+use masonry::widget::{CanvasPointerEvent, CanvasPointerKind, SortDirection};
+use masonry::{Action, Point, PointerButton};
+use xilem_core::{EncodedMessage, Message, MessageCodec, ViewId};
+
+/// Where a recorded message originated from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageSource {
+    /// An action from a Masonry widget.
+    Widget,
+    /// A message sent from an async task, using a [`MessageProxy`](crate::core::MessageProxy).
+    Async,
+}
+
+/// A message recorded by a [`Recorder`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedMessage {
+    /// Where the message originated from.
+    pub source: MessageSource,
+    /// The path of the view the message was sent to.
+    pub path: Vec<u64>,
+    /// The serialized message.
+    pub message: EncodedMessage,
+}
+
+impl RecordedMessage {
+    /// The path of the view the message was sent to.
+    pub fn view_path(&self) -> Vec<ViewId> {
+        self.path.iter().copied().map(ViewId::new).collect()
+    }
+}
+
+/// The error returned when parsing a [`Recording`] fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseRecordingError {
+    /// The (1-based) line number of the invalid line.
+    pub line: usize,
+}
+
+impl std::fmt::Display for ParseRecordingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid recorded message on line {}", self.line)
+    }
+}
+
+impl std::error::Error for ParseRecordingError {}
+
+/// A sequence of messages recorded by a [`Recorder`].
+///
+/// Recordings can be saved using [`to_text`](Self::to_text), which uses one line per message.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Recording {
+    /// The recorded messages, in the order they were handled.
+    pub messages: Vec<RecordedMessage>,
+}
+
+impl Recording {
+    /// Serialize this recording into a line-based text format.
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        for message in &self.messages {
+            let source = match message.source {
+                MessageSource::Widget => "widget",
+                MessageSource::Async => "async",
+            };
+            let path = message
+                .path
+                .iter()
+                .map(u64::to_string)
+                .collect::<Vec<_>>()
+                .join(".");
+            text.push_str(&format!(
+                "{source}\t{path}\t{}\t{}\n",
+                escape(&message.message.type_name),
+                escape(&message.message.data)
+            ));
+        }
+        text
+    }
+
+    /// Parse a recording previously serialized using [`to_text`](Self::to_text).
+    ///
+    /// # Errors
+    ///
+    /// If any line of `text` is not a valid recorded message.
+    pub fn from_text(text: &str) -> Result<Self, ParseRecordingError> {
+        let mut messages = Vec::new();
+        for (idx, line) in text.lines().enumerate() {
+            if line.is_empty() {
+                continue;
+            }
+            let error = ParseRecordingError { line: idx + 1 };
+            let mut parts = line.split('\t');
+            let (Some(source), Some(path), Some(type_name), Some(data), None) = (
+                parts.next(),
+                parts.next(),
+                parts.next(),
+                parts.next(),
+                parts.next(),
+            ) else {
+                return Err(error);
+            };
+            let source = match source {
+                "widget" => MessageSource::Widget,
+                "async" => MessageSource::Async,
+                _ => return Err(error),
+            };
+            let path = if path.is_empty() {
+                Vec::new()
+            } else {
+                path.split('.')
+                    .map(str::parse)
+                    .collect::<Result<_, _>>()
+                    .map_err(|_| error.clone())?
+            };
+            messages.push(RecordedMessage {
+                source,
+                path,
+                message: EncodedMessage {
+                    type_name: unescape(type_name).ok_or(error.clone())?,
+                    data: unescape(data).ok_or(error)?,
+                },
+            });
+        }
+        Ok(Self { messages })
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn unescape(text: &str) -> Option<String> {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            unescaped.push(match chars.next()? {
+                '\\' => '\\',
+                't' => '\t',
+                'n' => '\n',
+                'r' => '\r',
+                _ => return None,
+            });
+        } else {
+            unescaped.push(c);
+        }
+    }
+    Some(unescaped)
+}
+
+struct RecorderInner {
+    codec: MessageCodec,
+    recording: Recording,
+}
+
+/// Records the messages handled by a Xilem app.
+///
+/// This is a cheaply clonable handle, so that the recording can be accessed
+/// whilst (or after) the app is running.
+#[derive(Clone)]
+pub struct Recorder {
+    inner: Arc<Mutex<RecorderInner>>,
+}
+
+impl Recorder {
+    /// Create a recorder which serializes messages using `codec`.
+    pub fn new(codec: MessageCodec) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(RecorderInner {
+                codec,
+                recording: Recording::default(),
+            })),
+        }
+    }
+
+    /// A copy of the messages recorded so far.
+    pub fn recording(&self) -> Recording {
+        self.inner.lock().unwrap().recording.clone()
+    }
+
+    /// Remove and return the messages recorded so far.
+    pub fn take_recording(&self) -> Recording {
+        std::mem::take(&mut self.inner.lock().unwrap().recording)
+    }
+
+    pub(crate) fn record(&self, source: MessageSource, path: &[ViewId], message: &dyn Message) {
+        let mut inner = self.inner.lock().unwrap();
+        let Some(message) = inner.codec.encode(message) else {
+            tracing::warn!(
+                "Could not record message {:?}, as the codec can't encode it",
+                message.dyn_debug()
+            );
+            return;
+        };
+        inner.recording.messages.push(RecordedMessage {
+            source,
+            path: path.iter().map(|id| id.routing_id()).collect(),
+            message,
+        });
+    }
+}
+
+/// A [`MessageCodec`] which can serialize the Masonry [`Action`]s used by the built-in views.
+pub fn default_codec() -> MessageCodec {
+    let mut codec = MessageCodec::new();
+    codec.register("masonry::Action", encode_action, decode_action);
+    codec
+}
+
+fn encode_action(action: &Action) -> Option<String> {
+    Some(match action {
+        Action::ButtonPressed(button) => format!("ButtonPressed:{}", encode_button(button)),
+        Action::TextChanged(text) => format!("TextChanged:{text}"),
+        Action::TextEntered(text) => format!("TextEntered:{text}"),
+        Action::CheckboxChecked(checked) => format!("CheckboxChecked:{checked}"),
+        Action::SplitPointChanged(split_point) => format!("SplitPointChanged:{split_point}"),
+        Action::TabSelected(index) => format!("TabSelected:{index}"),
+        Action::TabCloseRequested(index) => format!("TabCloseRequested:{index}"),
+        Action::TabMoved(from, to) => format!("TabMoved:{from},{to}"),
+        Action::TreeItemExpanded(expanded) => format!("TreeItemExpanded:{expanded}"),
+        Action::TreeItemSelected(selected) => format!("TreeItemSelected:{selected}"),
+        Action::TableSortChanged(column, direction) => {
+            let direction = match direction {
+                SortDirection::Ascending => "Ascending",
+                SortDirection::Descending => "Descending",
+            };
+            format!("TableSortChanged:{column},{direction}")
+        }
+        Action::TableColumnResized(column, width) => format!("TableColumnResized:{column},{width}"),
+        Action::TableColumnMoved(from, to) => format!("TableColumnMoved:{from},{to}"),
+        Action::TableRowSelected(row) => format!("TableRowSelected:{row}"),
+        // Tables request rows again when they are laid out during the replay.
+        Action::TableRowsRequested(_) => return None,
+        Action::DockLayoutChanged(layout) => format!(
+            "DockLayoutChanged:{}",
+            serde_json::to_string(layout).expect("DockLayout can be serialized")
+        ),
+        Action::CanvasPointer(event) => {
+            let kind = match event.kind {
+                CanvasPointerKind::Down(button) => format!("Down {}", encode_button(&button)),
+                CanvasPointerKind::Up(button) => format!("Up {}", encode_button(&button)),
+                CanvasPointerKind::Move => "Move".into(),
+                CanvasPointerKind::Leave => "Leave".into(),
+            };
+            let region = match event.region {
+                Some(region) => region.to_string(),
+                None => "-".into(),
+            };
+            let Point { x, y } = event.position;
+            format!("CanvasPointer:{kind},{x},{y},{region}")
+        }
+        // We can't know what the type of other actions is, so they can't be recorded.
+        _ => return None,
+    })
+}
+
+fn decode_action(data: &str) -> Option<Action> {
+    let (kind, value) = data.split_once(':')?;
+    Some(match kind {
+        "ButtonPressed" => Action::ButtonPressed(decode_button(value)?),
+        "TextChanged" => Action::TextChanged(value.into()),
+        "TextEntered" => Action::TextEntered(value.into()),
+        "CheckboxChecked" => Action::CheckboxChecked(value.parse().ok()?),
+        "SplitPointChanged" => Action::SplitPointChanged(value.parse().ok()?),
+        "TabSelected" => Action::TabSelected(value.parse().ok()?),
+        "TabCloseRequested" => Action::TabCloseRequested(value.parse().ok()?),
+        "TabMoved" => {
+            let (from, to) = value.split_once(',')?;
+            Action::TabMoved(from.parse().ok()?, to.parse().ok()?)
+        }
+        "TreeItemExpanded" => Action::TreeItemExpanded(value.parse().ok()?),
+        "TreeItemSelected" => Action::TreeItemSelected(value.parse().ok()?),
+        "TableSortChanged" => {
+            let (column, direction) = value.split_once(',')?;
+            let direction = match direction {
+                "Ascending" => SortDirection::Ascending,
+                "Descending" => SortDirection::Descending,
+                _ => return None,
+            };
+            Action::TableSortChanged(column.parse().ok()?, direction)
+        }
+        "TableColumnResized" => {
+            let (column, width) = value.split_once(',')?;
+            Action::TableColumnResized(column.parse().ok()?, width.parse().ok()?)
+        }
+        "TableColumnMoved" => {
+            let (from, to) = value.split_once(',')?;
+            Action::TableColumnMoved(from.parse().ok()?, to.parse().ok()?)
+        }
+        "TableRowSelected" => Action::TableRowSelected(value.parse().ok()?),
+        "DockLayoutChanged" => Action::DockLayoutChanged(serde_json::from_str(value).ok()?),
+        "CanvasPointer" => {
+            let mut parts = value.split(',');
+            let kind = match parts.next()? {
+                "Move" => CanvasPointerKind::Move,
+                "Leave" => CanvasPointerKind::Leave,
+                kind => match kind.split_once(' ')? {
+                    ("Down", button) => CanvasPointerKind::Down(decode_button(button)?),
+                    ("Up", button) => CanvasPointerKind::Up(decode_button(button)?),
+                    _ => return None,
+                },
+            };
+            let x = parts.next()?.parse().ok()?;
+            let y = parts.next()?.parse().ok()?;
+            let region = match parts.next()? {
+                "-" => None,
+                region => Some(region.parse().ok()?),
+            };
+            Action::CanvasPointer(CanvasPointerEvent {
+                kind,
+                position: Point::new(x, y),
+                region,
+            })
+        }
+        _ => return None,
+    })
+}
+
+fn encode_button(button: &PointerButton) -> &'static str {
+    match button {
+        PointerButton::None => "None",
+        PointerButton::Primary => "Primary",
+        PointerButton::Secondary => "Secondary",
+        PointerButton::Auxiliary => "Auxiliary",
+        PointerButton::X1 => "X1",
+        PointerButton::X2 => "X2",
+        PointerButton::Other => "Other",
+    }
+}
+
+fn decode_button(value: &str) -> Option<PointerButton> {
+    Some(match value {
+        "None" => PointerButton::None,
+        "Primary" => PointerButton::Primary,
+        "Secondary" => PointerButton::Secondary,
+        "Auxiliary" => PointerButton::Auxiliary,
+        "X1" => PointerButton::X1,
+        "X2" => PointerButton::X2,
+        "Other" => PointerButton::Other,
+        _ => return None,
+    })
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestApp;
+    use crate::view::{button, checkbox, flex};
+    use crate::{WidgetView, Xilem};
+
+    #[derive(Debug, Default, PartialEq)]
+    struct AppState {
+        count: u32,
+        checked: bool,
+    }
+
+    fn app_logic(state: &mut AppState) -> impl WidgetView<AppState> {
+        flex((
+            button("Increment", |state: &mut AppState| state.count += 1),
+            checkbox("Check", state.checked, |state: &mut AppState, checked| {
+                state.checked = checked;
+            }),
+        ))
+    }
+
+    #[test]
+    fn record_and_replay() {
+        let recorder = Recorder::new(default_codec());
+        let mut app = TestApp::new(
+            Xilem::new(AppState::default(), app_logic).with_recorder(recorder.clone()),
+        );
+        let flex = app.harness.root_widget().children()[0].id();
+        let button_id = app.harness.get_widget(flex).children()[0].id();
+        let checkbox_id = app.harness.get_widget(flex).children()[1].id();
+        app.harness.mouse_click_on(button_id);
+        app.harness.mouse_click_on(button_id);
+        app.harness.mouse_click_on(checkbox_id);
+        app.handle_actions();
+        assert_eq!(
+            *app.state(),
+            AppState {
+                count: 2,
+                checked: true
+            }
+        );
+
+        let recording = Recording::from_text(&recorder.recording().to_text()).unwrap();
+        assert_eq!(recording, recorder.recording());
+        assert_eq!(recording.messages.len(), 3);
+        assert!(recording
+            .messages
+            .iter()
+            .all(|message| message.source == MessageSource::Widget));
+
+        let mut replayed = TestApp::new(Xilem::new(AppState::default(), app_logic));
+        let codec = default_codec();
+        replayed
+            .harness
+            .with_driver_ctx(|ctx| replayed.driver.replay(ctx, &recording, &codec));
+        assert_eq!(replayed.state(), app.state());
+    }
+
+    #[test]
+    fn unknown_actions_are_not_encoded() {
+        let codec = default_codec();
+        let action: Box<dyn Message> = Box::new(Action::Other(Box::new(42)));
+        assert!(codec.encode(&*action).is_none());
+
+        let recorder = Recorder::new(codec);
+        recorder.record(MessageSource::Widget, &[ViewId::new(0)], &*action);
+        assert!(recorder.recording().messages.is_empty());
+    }
+}
@@ -0,0 +1,88 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Helpers for testing views, by driving a [`Xilem`] app in a Masonry [`TestHarness`].
+
+use std::sync::{mpsc, Arc};
+use std::time::Duration;
+
+use masonry::app_driver::AppDriver;
+use masonry::testing::TestHarness;
+use masonry::widget::{Label, WidgetRef};
+use masonry::Widget;
+use xilem_core::{DynMessage, ProxyError, RawProxy, ViewId};
+
+use crate::{async_action, MasonryDriver, WidgetView, Xilem, ASYNC_MARKER_WIDGET};
+
+/// A [`RawProxy`] which queues the messages of async tasks, until they are handled by a [`TestApp`].
+struct ChannelProxy(mpsc::Sender<(Arc<[ViewId]>, DynMessage)>);
+
+impl RawProxy for ChannelProxy {
+    fn send_message(&self, path: Arc<[ViewId]>, message: DynMessage) -> Result<(), ProxyError> {
+        self.0
+            .send((path, message))
+            .map_err(|err| ProxyError::DriverFinished(err.0 .1))
+    }
+}
+
+/// A Xilem app running in a [`TestHarness`].
+pub(crate) struct TestApp<State, Logic, View: WidgetView<State>> {
+    pub(crate) harness: TestHarness,
+    pub(crate) driver: MasonryDriver<State, Logic, View, View::ViewState>,
+    messages: mpsc::Receiver<(Arc<[ViewId]>, DynMessage)>,
+}
+
+impl<State: 'static, Logic, View> TestApp<State, Logic, View>
+where
+    Logic: FnMut(&mut State) -> View,
+    View: WidgetView<State>,
+{
+    pub(crate) fn new(app: Xilem<State, Logic>) -> Self {
+        let (sender, messages) = mpsc::channel();
+        let (root_widget, driver) = app.into_driver(Arc::new(ChannelProxy(sender)));
+        TestApp {
+            harness: TestHarness::create(root_widget),
+            driver,
+            messages,
+        }
+    }
+
+    /// The current app state.
+    pub(crate) fn state(&self) -> &State {
+        self.driver.state()
+    }
+
+    /// Give the actions emitted by widgets to the app.
+    pub(crate) fn handle_actions(&mut self) {
+        self.harness.handle_actions(&mut self.driver);
+    }
+
+    /// Wait for the next message sent by an async task, and give it to the app.
+    ///
+    /// This panics if no message is sent within a few seconds.
+    pub(crate) fn handle_async_message(&mut self) {
+        let (path, message) = self
+            .messages
+            .recv_timeout(Duration::from_secs(5))
+            .expect("an async task should have sent a message");
+        let driver = &mut self.driver;
+        self.harness.with_driver_ctx(|ctx| {
+            driver.on_action(ctx, ASYNC_MARKER_WIDGET, async_action(path, message));
+        });
+    }
+
+    /// The texts of all labels in the app, in depth-first order.
+    pub(crate) fn label_texts(&self) -> Vec<String> {
+        fn collect(widget: WidgetRef<'_, dyn Widget>, texts: &mut Vec<String>) {
+            if let Some(label) = widget.downcast::<Label>() {
+                texts.push(label.text().to_string());
+            }
+            for child in widget.children() {
+                collect(child, texts);
+            }
+        }
+        let mut texts = Vec::new();
+        collect(self.harness.root_widget(), &mut texts);
+        texts
+    }
+}
@@ -0,0 +1,99 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::marker::PhantomData;
+
+use masonry::widget;
+use xilem_core::{Mut, ViewMarker};
+
+use crate::{MessageResult, Pod, View, ViewCtx, ViewId, WidgetView};
+
+pub use masonry::paint_scene_helpers::UnitPoint;
+
+/// A view which aligns `child` within the space given to it, for example centering it.
+///
+/// This can also be used as a modifier, with [`WidgetView::align`] or [`WidgetView::centered`].
+///
+/// This corresponds to the Masonry [`Align`](masonry::widget::Align) widget.
+///
+/// # Examples
+///
+/// ```
+/// use xilem::view::{align, label, UnitPoint};
+/// use xilem::WidgetView;
+///
+/// # fn view<State: 'static>() -> impl WidgetView<State> {
+/// align(UnitPoint::BOTTOM_RIGHT, label("In the corner"))
+/// # }
+/// ```
+pub fn align<Child, State, Action>(align: UnitPoint, child: Child) -> Align<Child, State, Action>
+where
+    Child: WidgetView<State, Action>,
+{
+    Align {
+        child,
+        align,
+        phantom: PhantomData,
+    }
+}
+
+pub struct Align<V, State, Action = ()> {
+    child: V,
+    align: UnitPoint,
+    phantom: PhantomData<fn() -> (State, Action)>,
+}
+
+impl<V, State, Action> ViewMarker for Align<V, State, Action> {}
+impl<V, State, Action> View<State, Action, ViewCtx> for Align<V, State, Action>
+where
+    V: WidgetView<State, Action>,
+    State: 'static,
+    Action: 'static,
+{
+    type Element = Pod<widget::Align>;
+    type ViewState = V::ViewState;
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        let (child, child_state) = self.child.build(ctx);
+        let widget = widget::Align::new_pod(self.align, child.inner.boxed());
+        (Pod::new(widget), child_state)
+    }
+
+    fn rebuild<'el>(
+        &self,
+        prev: &Self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<'el, Self::Element>,
+    ) -> Mut<'el, Self::Element> {
+        if self.align != prev.align {
+            element.set_align(self.align);
+        }
+        {
+            let mut child = element.child_mut();
+            self.child
+                .rebuild(&prev.child, view_state, ctx, child.downcast());
+        }
+        element
+    }
+
+    fn teardown(
+        &self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<'_, Self::Element>,
+    ) {
+        let mut child = element.child_mut();
+        self.child.teardown(view_state, ctx, child.downcast());
+    }
+
+    fn message(
+        &self,
+        view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: xilem_core::DynMessage,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        self.child.message(view_state, id_path, message, app_state)
+    }
+}
@@ -1,13 +1,15 @@
 // Copyright 2024 the Xilem Authors
 // SPDX-License-Identifier: Apache-2.0
 
+use std::marker::PhantomData;
+
 use crate::{core::View, Pod};
 use masonry::{widget, ArcStr};
-use xilem_core::{Mut, ViewMarker};
+use xilem_core::{Mut, ViewMarker, ViewPathTracker};
 
 pub use masonry::PointerButton;
 
-use crate::{MessageResult, ViewCtx, ViewId};
+use crate::{MessageResult, ViewCtx, ViewId, WidgetView};
 
 /// A button which calls `callback` when the primary mouse button (normally left) is pressed.
 pub fn button<State, Action>(
@@ -36,6 +38,46 @@ pub fn button_any_pointer<State, Action>(
     }
 }
 
+/// A button with arbitrary content, e.g. an icon and a label,
+/// which calls `callback` when the primary mouse button (normally left) is pressed.
+///
+/// The button keeps its usual styling, drawing `child` on top of its background.
+///
+/// # Examples
+///
+/// ```
+/// use xilem::view::{button_with, flex, label, Axis};
+/// # use xilem::WidgetView;
+///
+/// # fn view() -> impl WidgetView<u32> {
+/// button_with(
+///     flex((label("+"), label("Increment"))).direction(Axis::Horizontal),
+///     |count: &mut u32| *count += 1,
+/// )
+/// # }
+/// ```
+pub fn button_with<Child, State, Action>(
+    child: Child,
+    callback: impl Fn(&mut State) -> Action + Send + Sync + 'static,
+) -> ButtonWith<
+    Child,
+    impl for<'a> Fn(&'a mut State, PointerButton) -> MessageResult<Action> + Send + Sync + 'static,
+    State,
+    Action,
+>
+where
+    Child: WidgetView<State, Action>,
+{
+    ButtonWith {
+        child,
+        callback: move |state: &mut State, button| match button {
+            PointerButton::Primary => MessageResult::Action(callback(state)),
+            _ => MessageResult::Nop,
+        },
+        phantom: PhantomData,
+    }
+}
+
 pub struct Button<F> {
     label: ArcStr,
     callback: F,
@@ -103,3 +145,91 @@ where
         }
     }
 }
+
+/// The view id of the child of a [`ButtonWith`].
+///
+/// The button itself uses the path of the `ButtonWith` view, so that its actions
+/// can be distinguished from those of the child.
+const CHILD_VIEW_ID: ViewId = ViewId::new(0);
+
+pub struct ButtonWith<V, F, State, Action> {
+    child: V,
+    callback: F,
+    phantom: PhantomData<fn() -> (State, Action)>,
+}
+
+impl<V, F, State, Action> ViewMarker for ButtonWith<V, F, State, Action> {}
+impl<V, F, State, Action> View<State, Action, ViewCtx> for ButtonWith<V, F, State, Action>
+where
+    V: WidgetView<State, Action>,
+    F: Fn(&mut State, PointerButton) -> MessageResult<Action> + Send + Sync + 'static,
+    State: 'static,
+    Action: 'static,
+{
+    type Element = Pod<widget::Button<V::Widget>>;
+    type ViewState = V::ViewState;
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        let (child, child_state) = ctx.with_id(CHILD_VIEW_ID, |ctx| self.child.build(ctx));
+        let pod = ctx.with_action_widget(|_| Pod::new(widget::Button::from_pod(child.inner)));
+        (pod, child_state)
+    }
+
+    fn rebuild<'el>(
+        &self,
+        prev: &Self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<'el, Self::Element>,
+    ) -> Mut<'el, Self::Element> {
+        ctx.with_id(CHILD_VIEW_ID, |ctx| {
+            self.child
+                .rebuild(&prev.child, view_state, ctx, element.child_mut());
+        });
+        element
+    }
+
+    fn teardown(
+        &self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<'_, Self::Element>,
+    ) {
+        ctx.with_id(CHILD_VIEW_ID, |ctx| {
+            self.child.teardown(view_state, ctx, element.child_mut());
+        });
+        ctx.teardown_leaf(element);
+    }
+
+    fn message(
+        &self,
+        view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: xilem_core::DynMessage,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        if let Some((first, remainder)) = id_path.split_first() {
+            if *first != CHILD_VIEW_ID {
+                tracing::error!("Invalid id path in ButtonWith::message: {id_path:?}");
+                return MessageResult::Stale(message);
+            }
+            return self
+                .child
+                .message(view_state, remainder, message, app_state);
+        }
+        match message.downcast::<masonry::Action>() {
+            Ok(action) => {
+                if let masonry::Action::ButtonPressed(button) = *action {
+                    (self.callback)(app_state, button)
+                } else {
+                    tracing::error!("Wrong action type in ButtonWith::message: {action:?}");
+                    MessageResult::Stale(action)
+                }
+            }
+            Err(message) => {
+                tracing::error!("Wrong message type in ButtonWith::message: {message:?}");
+                MessageResult::Stale(message)
+            }
+        }
+    }
+}
@@ -0,0 +1,340 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The custom drawing widget.
+
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use masonry::widget;
+use masonry::Size;
+use vello::Scene;
+use xilem_core::{Mut, ViewMarker};
+
+use crate::{MessageResult, Pod, View, ViewCtx, ViewId};
+
+pub use masonry::widget::{CanvasPointerEvent, CanvasPointerKind, HitRegion};
+
+type PaintFn<Data> = Arc<dyn Fn(&Data, &mut Scene, Size) + Send + Sync + 'static>;
+type HitRegionsFn<Data> = Arc<dyn Fn(&Data, Size) -> Vec<HitRegion> + Send + Sync + 'static>;
+type PointerCallback<State, Action> =
+    Box<dyn Fn(&mut State, &CanvasPointerEvent) -> Action + Send + Sync + 'static>;
+
+/// A canvas which draws custom 2D graphics of `data` with `paint`.
+///
+/// `paint` is called with the data, the scene to draw into and the size of the canvas,
+/// with the origin at the top left of the canvas. The canvas takes all the space it is given.
+///
+/// As closures can't be compared, the drawing should only depend on `data`: the canvas
+/// is only repainted when the view is rebuilt with data which isn't equal to the previous
+/// data. Hit regions, which report which part of the drawing the pointer is over and
+/// describe it to accessibility, can be set with [`hit_regions`](Canvas::hit_regions).
+///
+/// Corresponds to the [`Canvas`](widget::Canvas) widget.
+///
+/// # Examples
+///
+/// ```
+/// use vello::kurbo::{Affine, Rect};
+/// use vello::peniko::{Color, Fill};
+/// use xilem::view::{canvas, HitRegion};
+/// use xilem::WidgetView;
+///
+/// struct Chart {
+///     values: Vec<f64>,
+///     hovered: Option<usize>,
+/// }
+///
+/// fn bar(index: usize, count: usize, value: f64, size: masonry::Size) -> Rect {
+///     let width = size.width / count as f64;
+///     let x = index as f64 * width;
+///     Rect::new(x, size.height * (1.0 - value), x + width, size.height)
+/// }
+///
+/// fn chart(state: &mut Chart) -> impl WidgetView<Chart> {
+///     let data = (state.values.clone(), state.hovered);
+///     canvas(data, |(values, hovered), scene, size| {
+///         for (index, value) in values.iter().enumerate() {
+///             let color = if *hovered == Some(index) {
+///                 Color::ORANGE
+///             } else {
+///                 Color::STEEL_BLUE
+///             };
+///             let rect = bar(index, values.len(), *value, size);
+///             scene.fill(Fill::NonZero, Affine::IDENTITY, color, None, &rect);
+///         }
+///     })
+///     .hit_regions(|(values, _), size| {
+///         values
+///             .iter()
+///             .enumerate()
+///             .map(|(index, value)| {
+///                 HitRegion::new(bar(index, values.len(), *value, size))
+///                     .label(format!("Bar {index}: {value}"))
+///             })
+///             .collect()
+///     })
+///     .on_pointer_move(|state: &mut Chart, event| state.hovered = event.region)
+///     .on_pointer_leave(|state: &mut Chart, _| state.hovered = None)
+/// }
+/// ```
+pub fn canvas<Data, State, Action>(
+    data: Data,
+    paint: impl Fn(&Data, &mut Scene, Size) + Send + Sync + 'static,
+) -> Canvas<Data, State, Action>
+where
+    Data: PartialEq + Send + Sync + 'static,
+{
+    Canvas {
+        data: Arc::new(data),
+        paint: Arc::new(paint),
+        hit_regions: None,
+        on_pointer_down: None,
+        on_pointer_up: None,
+        on_pointer_move: None,
+        on_pointer_leave: None,
+        phantom: PhantomData,
+    }
+}
+
+/// The [`View`] created by [`canvas`].
+///
+/// See `canvas`'s docs for more details.
+pub struct Canvas<Data, State, Action = ()> {
+    data: Arc<Data>,
+    paint: PaintFn<Data>,
+    hit_regions: Option<HitRegionsFn<Data>>,
+    on_pointer_down: Option<PointerCallback<State, Action>>,
+    on_pointer_up: Option<PointerCallback<State, Action>>,
+    on_pointer_move: Option<PointerCallback<State, Action>>,
+    on_pointer_leave: Option<PointerCallback<State, Action>>,
+    phantom: PhantomData<fn() -> (State, Action)>,
+}
+
+impl<Data, State, Action> Canvas<Data, State, Action> {
+    /// Set the function computing the [`HitRegion`]s of the canvas from its data and size.
+    ///
+    /// Regions which come later are on top of earlier regions. Like the paint function,
+    /// this is only called again when the data changes or the canvas is resized.
+    pub fn hit_regions(
+        mut self,
+        hit_regions: impl Fn(&Data, Size) -> Vec<HitRegion> + Send + Sync + 'static,
+    ) -> Self {
+        self.hit_regions = Some(Arc::new(hit_regions));
+        self
+    }
+
+    /// Set the callback for when a pointer button is pressed on the canvas.
+    pub fn on_pointer_down<F>(mut self, on_pointer_down: F) -> Self
+    where
+        F: Fn(&mut State, &CanvasPointerEvent) -> Action + Send + Sync + 'static,
+    {
+        self.on_pointer_down = Some(Box::new(on_pointer_down));
+        self
+    }
+
+    /// Set the callback for when a pointer button pressed on the canvas is released,
+    /// which might be outside of the canvas.
+    pub fn on_pointer_up<F>(mut self, on_pointer_up: F) -> Self
+    where
+        F: Fn(&mut State, &CanvasPointerEvent) -> Action + Send + Sync + 'static,
+    {
+        self.on_pointer_up = Some(Box::new(on_pointer_up));
+        self
+    }
+
+    /// Set the callback for when the pointer moves over the canvas, or anywhere while
+    /// a button pressed on it is held.
+    pub fn on_pointer_move<F>(mut self, on_pointer_move: F) -> Self
+    where
+        F: Fn(&mut State, &CanvasPointerEvent) -> Action + Send + Sync + 'static,
+    {
+        self.on_pointer_move = Some(Box::new(on_pointer_move));
+        self
+    }
+
+    /// Set the callback for when the pointer leaves the canvas.
+    pub fn on_pointer_leave<F>(mut self, on_pointer_leave: F) -> Self
+    where
+        F: Fn(&mut State, &CanvasPointerEvent) -> Action + Send + Sync + 'static,
+    {
+        self.on_pointer_leave = Some(Box::new(on_pointer_leave));
+        self
+    }
+
+    fn report_buttons(&self) -> bool {
+        self.on_pointer_down.is_some() || self.on_pointer_up.is_some()
+    }
+
+    fn report_moves(&self) -> bool {
+        self.on_pointer_move.is_some() || self.on_pointer_leave.is_some()
+    }
+}
+
+fn widget_paint<Data: 'static>(
+    data: &Arc<Data>,
+    paint: &PaintFn<Data>,
+) -> impl Fn(&mut Scene, Size) + 'static {
+    let data = data.clone();
+    let paint = paint.clone();
+    move |scene, size| paint(&data, scene, size)
+}
+
+fn widget_hit_regions<Data: 'static>(
+    data: &Arc<Data>,
+    hit_regions: &HitRegionsFn<Data>,
+) -> impl Fn(Size) -> Vec<HitRegion> + 'static {
+    let data = data.clone();
+    let hit_regions = hit_regions.clone();
+    move |size| hit_regions(&data, size)
+}
+
+impl<Data, State, Action> ViewMarker for Canvas<Data, State, Action> {}
+impl<Data, State, Action> View<State, Action, ViewCtx> for Canvas<Data, State, Action>
+where
+    Data: PartialEq + Send + Sync + 'static,
+    State: 'static,
+    Action: 'static,
+{
+    type Element = Pod<widget::Canvas>;
+    type ViewState = ();
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        ctx.with_leaf_action_widget(|_| {
+            let mut widget = widget::Canvas::new(widget_paint(&self.data, &self.paint))
+                .with_pointer_events(self.report_buttons(), self.report_moves());
+            if let Some(hit_regions) = &self.hit_regions {
+                widget = widget.with_hit_regions(widget_hit_regions(&self.data, hit_regions));
+            }
+            Pod::new(widget)
+        })
+    }
+
+    fn rebuild<'el>(
+        &self,
+        prev: &Self,
+        (): &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<'el, Self::Element>,
+    ) -> Mut<'el, Self::Element> {
+        // The closures can't be compared, so they are only replaced when the data changes.
+        let data_changed = prev.data != self.data;
+        if data_changed {
+            element.set_paint(widget_paint(&self.data, &self.paint));
+            ctx.mark_changed();
+        }
+        if self.hit_regions.is_some() != prev.hit_regions.is_some()
+            || (data_changed && self.hit_regions.is_some())
+        {
+            element.set_hit_regions(
+                self.hit_regions
+                    .as_ref()
+                    .map(|hit_regions| Box::new(widget_hit_regions(&self.data, hit_regions)) as _),
+            );
+            ctx.mark_changed();
+        }
+        if prev.report_buttons() != self.report_buttons()
+            || prev.report_moves() != self.report_moves()
+        {
+            element.set_pointer_events(self.report_buttons(), self.report_moves());
+        }
+        element
+    }
+
+    fn teardown(
+        &self,
+        (): &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        element: Mut<'_, Self::Element>,
+    ) {
+        ctx.teardown_leaf(element);
+    }
+
+    fn message(
+        &self,
+        (): &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: xilem_core::DynMessage,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        debug_assert!(
+            id_path.is_empty(),
+            "id path should be empty in Canvas::message"
+        );
+        let action = match message.downcast::<masonry::Action>() {
+            Ok(action) => action,
+            Err(message) => {
+                tracing::error!("Wrong message type in Canvas::message");
+                return MessageResult::Stale(message);
+            }
+        };
+        let masonry::Action::CanvasPointer(event) = *action else {
+            tracing::error!("Wrong action type in Canvas::message: {action:?}");
+            return MessageResult::Stale(action);
+        };
+        let callback = match event.kind {
+            CanvasPointerKind::Down(_) => &self.on_pointer_down,
+            CanvasPointerKind::Up(_) => &self.on_pointer_up,
+            CanvasPointerKind::Move => &self.on_pointer_move,
+            CanvasPointerKind::Leave => &self.on_pointer_leave,
+        };
+        match callback {
+            Some(callback) => MessageResult::Action(callback(app_state, &event)),
+            None => MessageResult::Nop,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::testing::TestApp;
+    use crate::{WidgetView, Xilem};
+
+    struct AppState {
+        value: u32,
+        clicks: u32,
+    }
+
+    static PAINTS: AtomicUsize = AtomicUsize::new(0);
+
+    fn app_logic(state: &mut AppState) -> impl WidgetView<AppState> {
+        canvas(state.value, |_, _, _| {
+            PAINTS.fetch_add(1, Ordering::Relaxed);
+        })
+        .on_pointer_down(|state: &mut AppState, _| {
+            // Every other click changes the painted data.
+            state.clicks += 1;
+            if state.clicks % 2 == 0 {
+                state.value += 1;
+            }
+        })
+    }
+
+    #[test]
+    fn repaint_when_data_changes() {
+        let state = AppState {
+            value: 0,
+            clicks: 0,
+        };
+        let mut app = TestApp::new(Xilem::new(state, app_logic));
+        let canvas_id = app.harness.root_widget().children()[0].id();
+        app.harness.paint();
+        let paints = PAINTS.load(Ordering::Relaxed);
+
+        // The app is rebuilt with the same data, so the canvas isn't repainted.
+        app.harness.mouse_click_on(canvas_id);
+        app.handle_actions();
+        assert_eq!(app.state().clicks, 1);
+        app.harness.paint();
+        assert_eq!(PAINTS.load(Ordering::Relaxed), paints);
+
+        app.harness.mouse_click_on(canvas_id);
+        app.handle_actions();
+        assert_eq!(app.state().value, 1);
+        app.harness.paint();
+        assert_eq!(PAINTS.load(Ordering::Relaxed), paints + 1);
+    }
+}
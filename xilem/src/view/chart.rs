@@ -0,0 +1,150 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The line, bar and scatter chart widgets.
+
+use masonry::widget;
+use xilem_core::{Mut, ViewMarker};
+
+use crate::{MessageResult, Pod, View, ViewCtx, ViewId};
+
+pub use masonry::widget::{ChartKind, ChartSeries};
+
+/// A chart of the given `kind`, plotting `series`.
+///
+/// The chart has axes with ticks, a legend and tooltips, and can be zoomed and panned by
+/// the user. When it is rebuilt, only the series which changed are updated, and points
+/// appended to the end of a series are added without laying out the whole chart again,
+/// so that data can be streamed into it.
+///
+/// Corresponds to the [`Chart`](widget::Chart) widget.
+///
+/// # Examples
+///
+/// ```
+/// use masonry::Point;
+/// use xilem::view::{line_chart, ChartSeries};
+/// use xilem::WidgetView;
+///
+/// struct Dashboard {
+///     cpu: Vec<Point>,
+///     memory: Vec<Point>,
+/// }
+///
+/// fn usage(state: &mut Dashboard) -> impl WidgetView<Dashboard> {
+///     line_chart(vec![
+///         ChartSeries::new("CPU", state.cpu.clone()),
+///         ChartSeries::new("Memory", state.memory.clone()),
+///     ])
+/// }
+/// ```
+pub fn chart(kind: ChartKind, series: Vec<ChartSeries>) -> Chart {
+    Chart {
+        kind,
+        series,
+        show_legend: true,
+    }
+}
+
+/// A chart joining the points of each of `series` with lines.
+///
+/// See [`chart`] for more details.
+pub fn line_chart(series: Vec<ChartSeries>) -> Chart {
+    chart(ChartKind::Line, series)
+}
+
+/// A chart drawing the points of `series` as bars.
+///
+/// See [`chart`] for more details.
+pub fn bar_chart(series: Vec<ChartSeries>) -> Chart {
+    chart(ChartKind::Bar, series)
+}
+
+/// A chart drawing the points of `series` as dots.
+///
+/// See [`chart`] for more details.
+pub fn scatter_plot(series: Vec<ChartSeries>) -> Chart {
+    chart(ChartKind::Scatter, series)
+}
+
+/// The [`View`] created by [`chart`], [`line_chart`], [`bar_chart`] or [`scatter_plot`].
+///
+/// See `chart`'s docs for more details.
+pub struct Chart {
+    kind: ChartKind,
+    series: Vec<ChartSeries>,
+    show_legend: bool,
+}
+
+impl Chart {
+    /// Set whether the legend is shown.
+    pub fn legend(mut self, show_legend: bool) -> Self {
+        self.show_legend = show_legend;
+        self
+    }
+}
+
+impl ViewMarker for Chart {}
+impl<State, Action> View<State, Action, ViewCtx> for Chart {
+    type Element = Pod<widget::Chart>;
+    type ViewState = ();
+
+    fn build(&self, _: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        let widget = self
+            .series
+            .iter()
+            .cloned()
+            .fold(widget::Chart::new(self.kind), widget::Chart::with_series)
+            .with_legend(self.show_legend);
+        (Pod::new(widget), ())
+    }
+
+    fn rebuild<'el>(
+        &self,
+        prev: &Self,
+        (): &mut Self::ViewState,
+        _: &mut ViewCtx,
+        mut element: Mut<'el, Self::Element>,
+    ) -> Mut<'el, Self::Element> {
+        if prev.kind != self.kind {
+            element.set_kind(self.kind);
+        }
+        if prev.show_legend != self.show_legend {
+            element.set_legend(self.show_legend);
+        }
+        if prev.series.len() != self.series.len() {
+            element.set_series(self.series.clone());
+            return element;
+        }
+        for (index, (prev, series)) in prev.series.iter().zip(&self.series).enumerate() {
+            if prev == series {
+                continue;
+            }
+            let prev_points = prev.points();
+            let appended = prev.name() == series.name()
+                && prev.get_color() == series.get_color()
+                && series.points().starts_with(prev_points);
+            if appended {
+                element.append_points(index, &series.points()[prev_points.len()..]);
+            } else {
+                element.set_series_at(index, series.clone());
+            }
+        }
+        element
+    }
+
+    fn teardown(&self, (): &mut Self::ViewState, _: &mut ViewCtx, _: Mut<'_, Self::Element>) {}
+
+    fn message(
+        &self,
+        (): &mut Self::ViewState,
+        _: &[ViewId],
+        message: xilem_core::DynMessage,
+        _: &mut State,
+    ) -> MessageResult<Action> {
+        tracing::error!(
+            "Message arrived in Chart::message, but Chart doesn't consume any messages, this is a bug"
+        );
+        MessageResult::Stale(message)
+    }
+}
@@ -0,0 +1,464 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::{HashMap, HashSet};
+use std::marker::PhantomData;
+
+use masonry::{widget, ArcStr, WidgetPod};
+use xilem_core::{DynMessage, MessageResult, Mut, View, ViewId, ViewMarker, ViewPathTracker};
+
+use crate::{Pod, ViewCtx, WidgetView};
+
+pub use masonry::widget::{DockLayout, DockPlacement};
+
+type LayoutCallback<State, Action> =
+    Box<dyn Fn(&mut State, DockLayout) -> Action + Send + Sync + 'static>;
+
+/// A docking area, which arranges [`dock_panel`]s according to `layout`.
+///
+/// Panels are identified by their id in the layout, which must be unique within the dock.
+/// When the dock is rebuilt, panels are matched up by their id, so that panels which are
+/// moved in `panels` keep their widgets and state. Panels which aren't part of the layout
+/// are added to its first stack, and ids in the layout which don't belong to a panel are ignored.
+/// Panels with different types of content can be [boxed](WidgetView::boxed).
+///
+/// The user can resize the splits of the layout, and drag panels between stacks or to the
+/// edge of another panel to split it. `on_layout_change` is called with the new layout, and
+/// should update the state so that `layout` is the new layout. Layouts can be serialized,
+/// so they can be saved as part of the app state.
+///
+/// # Examples
+///
+/// ```
+/// use xilem::view::{dock, dock_panel, label, Axis, DockLayout};
+/// # use xilem::WidgetView;
+///
+/// struct Ide {
+///     layout: DockLayout,
+/// }
+///
+/// fn ide(state: &mut Ide) -> impl WidgetView<Ide> {
+///     dock(
+///         state.layout.clone(),
+///         vec![
+///             dock_panel("files", "Files", label("The files")),
+///             dock_panel("editor", "Editor", label("The editor")),
+///             dock_panel("terminal", "Terminal", label("The terminal")),
+///         ],
+///         |state: &mut Ide, layout| state.layout = layout,
+///     )
+/// }
+///
+/// let state = Ide {
+///     layout: DockLayout::split(
+///         Axis::Horizontal,
+///         0.25,
+///         DockLayout::stack(["files"]),
+///         DockLayout::split(
+///             Axis::Vertical,
+///             0.75,
+///             DockLayout::stack(["editor"]),
+///             DockLayout::stack(["terminal"]),
+///         ),
+///     ),
+/// };
+/// ```
+pub fn dock<State, Action, V, F>(
+    layout: DockLayout,
+    panels: Vec<DockPanel<V, State, Action>>,
+    on_layout_change: F,
+) -> Dock<V, State, Action>
+where
+    V: WidgetView<State, Action>,
+    F: Fn(&mut State, DockLayout) -> Action + Send + Sync + 'static,
+{
+    Dock {
+        panels,
+        layout,
+        on_layout_change: Box::new(on_layout_change),
+        phantom: PhantomData,
+    }
+}
+
+pub struct Dock<V, State, Action = ()> {
+    panels: Vec<DockPanel<V, State, Action>>,
+    layout: DockLayout,
+    on_layout_change: LayoutCallback<State, Action>,
+    phantom: PhantomData<fn() -> (State, Action)>,
+}
+
+/// A panel with an `id` and a `title`, which shows `content`, for use in a [`Dock`] view.
+///
+/// The id should be unique within the dock.
+pub fn dock_panel<V, State, Action>(
+    id: impl Into<String>,
+    title: impl Into<ArcStr>,
+    content: V,
+) -> DockPanel<V, State, Action>
+where
+    V: WidgetView<State, Action>,
+{
+    DockPanel {
+        id: id.into(),
+        title: title.into(),
+        content,
+        phantom: PhantomData,
+    }
+}
+
+pub struct DockPanel<V, State, Action> {
+    id: String,
+    title: ArcStr,
+    content: V,
+    phantom: PhantomData<fn() -> (State, Action)>,
+}
+
+/// The view state of a [`Dock`].
+#[doc(hidden)] // Implementation detail, public because of trait visibility rules
+pub struct DockState<VState> {
+    /// The state of each panel, in the order of the panels of the widget.
+    panels: Vec<PanelState<VState>>,
+    /// The view id of the next panel to be built.
+    next_id: u64,
+}
+
+struct PanelState<VState> {
+    id: String,
+    view_id: ViewId,
+    content: VState,
+}
+
+impl<V, State, Action> Dock<V, State, Action>
+where
+    State: 'static,
+    Action: 'static,
+    V: WidgetView<State, Action>,
+{
+    fn build_panel(
+        panel: &DockPanel<V, State, Action>,
+        next_id: &mut u64,
+        ctx: &mut ViewCtx,
+    ) -> (
+        WidgetPod<Box<dyn masonry::Widget>>,
+        PanelState<V::ViewState>,
+    ) {
+        let view_id = ViewId::new(*next_id);
+        *next_id += 1;
+        let (pod, content) = ctx.with_id(view_id, |ctx| panel.content.build(ctx));
+        let state = PanelState {
+            id: panel.id.clone(),
+            view_id,
+            content,
+        };
+        (pod.inner.boxed(), state)
+    }
+
+    /// The panel with the given id.
+    fn panel(&self, id: &str) -> Option<&DockPanel<V, State, Action>> {
+        self.panels.iter().find(|panel| panel.id == id)
+    }
+}
+
+impl<V, State, Action> ViewMarker for Dock<V, State, Action> {}
+impl<State, Action, V> View<State, Action, ViewCtx> for Dock<V, State, Action>
+where
+    State: 'static,
+    Action: 'static,
+    V: WidgetView<State, Action>,
+{
+    type Element = Pod<widget::Dock>;
+
+    type ViewState = DockState<V::ViewState>;
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        let mut next_id = 0;
+        let mut widget = widget::Dock::new(self.layout.clone());
+        let mut panels = Vec::with_capacity(self.panels.len());
+        for panel in &self.panels {
+            let (pod, state) = Self::build_panel(panel, &mut next_id, ctx);
+            widget = widget.with_panel_pod(panel.id.clone(), panel.title.clone(), pod);
+            panels.push(state);
+        }
+        debug_assert_eq!(
+            self.panels
+                .iter()
+                .map(|panel| &panel.id)
+                .collect::<HashSet<_>>()
+                .len(),
+            self.panels.len(),
+            "The ids of the panels of a dock must be unique"
+        );
+        let pod = ctx.with_action_widget(|_| Pod::new(widget));
+        (pod, DockState { panels, next_id })
+    }
+
+    fn rebuild<'el>(
+        &self,
+        prev: &Self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<'el, Self::Element>,
+    ) -> Mut<'el, Self::Element> {
+        // The panel of `self` and of `prev` for each id.
+        let panels: HashMap<&str, &DockPanel<V, State, Action>> = self
+            .panels
+            .iter()
+            .map(|panel| (panel.id.as_str(), panel))
+            .collect();
+        debug_assert_eq!(
+            panels.len(),
+            self.panels.len(),
+            "The ids of the panels of a dock must be unique"
+        );
+        let prev_panels: HashMap<&str, &DockPanel<V, State, Action>> = prev
+            .panels
+            .iter()
+            .map(|panel| (panel.id.as_str(), panel))
+            .collect();
+
+        // Tear down the panels whose ids aren't used anymore.
+        for pos in (0..view_state.panels.len()).rev() {
+            if panels.contains_key(view_state.panels[pos].id.as_str()) {
+                continue;
+            }
+            let mut state = view_state.panels.remove(pos);
+            {
+                let mut content = element.panel_mut(pos);
+                ctx.with_id(state.view_id, |ctx| {
+                    prev_panels[state.id.as_str()].content.teardown(
+                        &mut state.content,
+                        ctx,
+                        content.downcast(),
+                    );
+                });
+            }
+            element.remove_panel(pos);
+            ctx.mark_changed();
+        }
+
+        // Rebuild the other panels, wherever they are in `panels`.
+        for (pos, state) in view_state.panels.iter_mut().enumerate() {
+            let panel = panels[state.id.as_str()];
+            let prev_panel = prev_panels[state.id.as_str()];
+            if prev_panel.title != panel.title {
+                element.set_title(pos, panel.title.clone());
+                ctx.mark_changed();
+            }
+            let mut content = element.panel_mut(pos);
+            ctx.with_id(state.view_id, |ctx| {
+                panel.content.rebuild(
+                    &prev_panel.content,
+                    &mut state.content,
+                    ctx,
+                    content.downcast(),
+                );
+            });
+        }
+
+        // Build the new panels after the others.
+        let kept: HashSet<String> = view_state
+            .panels
+            .iter()
+            .map(|state| state.id.clone())
+            .collect();
+        for panel in self.panels.iter().filter(|panel| !kept.contains(&panel.id)) {
+            let (pod, state) = Self::build_panel(panel, &mut view_state.next_id, ctx);
+            let index = view_state.panels.len();
+            element.insert_panel_pod(index, panel.id.clone(), panel.title.clone(), pod);
+            view_state.panels.push(state);
+            ctx.mark_changed();
+        }
+
+        // The widget changes its layout itself when the user drags panels, so compare to the widget.
+        if element.widget.get_layout() != &self.layout {
+            element.set_layout(self.layout.clone());
+            ctx.mark_changed();
+        }
+        element
+    }
+
+    fn teardown(
+        &self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<'_, Self::Element>,
+    ) {
+        for (pos, state) in view_state.panels.iter_mut().enumerate() {
+            let panel = self.panel(&state.id).unwrap();
+            let mut content = element.panel_mut(pos);
+            ctx.with_id(state.view_id, |ctx| {
+                panel
+                    .content
+                    .teardown(&mut state.content, ctx, content.downcast());
+            });
+        }
+        ctx.teardown_leaf(element);
+    }
+
+    fn message(
+        &self,
+        view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: DynMessage,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        if let Some((first, remainder)) = id_path.split_first() {
+            let Some(state) = view_state
+                .panels
+                .iter_mut()
+                .find(|state| state.view_id == *first)
+            else {
+                // The panel was removed.
+                return MessageResult::Stale(message);
+            };
+            let panel = self.panel(&state.id).unwrap();
+            return panel
+                .content
+                .message(&mut state.content, remainder, message, app_state);
+        }
+        match message.downcast::<masonry::Action>() {
+            Ok(action) => {
+                if let masonry::Action::DockLayoutChanged(layout) = *action {
+                    MessageResult::Action((self.on_layout_change)(app_state, layout))
+                } else {
+                    tracing::error!("Wrong action type in Dock::message: {action:?}");
+                    MessageResult::Stale(action)
+                }
+            }
+            Err(message) => {
+                tracing::error!("Wrong message type in Dock::message");
+                MessageResult::Stale(message)
+            }
+        }
+    }
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use masonry::testing::TestHarness;
+    use masonry::WidgetId;
+
+    use super::*;
+    use crate::testing::TestApp;
+    use crate::view::{button, flex};
+    use crate::Xilem;
+
+    struct Panels {
+        ids: Vec<String>,
+        layout: DockLayout,
+        clicked: Option<String>,
+    }
+
+    fn app_logic(state: &mut Panels) -> impl WidgetView<Panels> {
+        let panels = state
+            .ids
+            .iter()
+            .map(|id| {
+                let clicked = id.clone();
+                let content = button(format!("Panel {id}"), move |state: &mut Panels| {
+                    state.clicked = Some(clicked.clone());
+                });
+                dock_panel(id.clone(), id.to_uppercase(), content)
+            })
+            .collect();
+        flex((
+            button("Remove first", |state: &mut Panels| {
+                state.ids.remove(0);
+            }),
+            button("Reverse", |state: &mut Panels| state.ids.reverse()),
+            button("Insert", |state: &mut Panels| {
+                state.ids.insert(0, "d".to_string());
+            }),
+            dock(
+                state.layout.clone(),
+                panels,
+                |state: &mut Panels, layout| state.layout = layout,
+            ),
+        ))
+    }
+
+    fn click_button<Logic, V>(app: &mut TestApp<Panels, Logic, V>, index: usize)
+    where
+        Logic: FnMut(&mut Panels) -> V,
+        V: WidgetView<Panels>,
+    {
+        let id = app.harness.root_widget().children()[0].children()[index].id();
+        app.harness.mouse_click_on(id);
+        app.handle_actions();
+    }
+
+    /// The id of the content widget of the panel `id`.
+    fn content_id(harness: &TestHarness, id: &str) -> Option<WidgetId> {
+        let dock = harness.root_widget().children()[0].children()[3];
+        let index = dock.downcast::<widget::Dock>().unwrap().panel_index(id)?;
+        Some(dock.children()[index].id())
+    }
+
+    /// Click the content of the panel which is shown, and check that its view got the click.
+    fn click_shown_panel<Logic, V>(app: &mut TestApp<Panels, Logic, V>)
+    where
+        Logic: FnMut(&mut Panels) -> V,
+        V: WidgetView<Panels>,
+    {
+        let shown = app.state().layout.selected_panels()[0].to_string();
+        let id = content_id(&app.harness, &shown).unwrap();
+        app.harness.mouse_click_on(id);
+        app.handle_actions();
+        assert_eq!(app.state().clicked.as_deref(), Some(shown.as_str()));
+    }
+
+    fn panels(ids: &[&str]) -> Panels {
+        Panels {
+            ids: ids.iter().map(|id| id.to_string()).collect(),
+            layout: DockLayout::stack(ids.iter().copied()),
+            clicked: None,
+        }
+    }
+
+    #[test]
+    fn remove_panel() {
+        let mut app = TestApp::new(Xilem::new(panels(&["a", "b", "c"]), app_logic));
+        let b = content_id(&app.harness, "b").unwrap();
+        let c = content_id(&app.harness, "c").unwrap();
+        click_shown_panel(&mut app);
+
+        click_button(&mut app, 0);
+        assert_eq!(content_id(&app.harness, "a"), None);
+        // The other panels keep their widgets, instead of taking the place of the removed one.
+        assert_eq!(content_id(&app.harness, "b"), Some(b));
+        assert_eq!(content_id(&app.harness, "c"), Some(c));
+        assert_eq!(app.state().layout.panels(), ["b", "c"]);
+        click_shown_panel(&mut app);
+    }
+
+    #[test]
+    fn reorder_panels() {
+        let mut app = TestApp::new(Xilem::new(panels(&["a", "b", "c"]), app_logic));
+        let a = content_id(&app.harness, "a").unwrap();
+        let c = content_id(&app.harness, "c").unwrap();
+
+        click_button(&mut app, 1);
+        assert_eq!(app.state().ids, ["c", "b", "a"]);
+        assert_eq!(content_id(&app.harness, "a"), Some(a));
+        assert_eq!(content_id(&app.harness, "c"), Some(c));
+        assert_eq!(app.state().layout.panels(), ["a", "b", "c"]);
+        click_shown_panel(&mut app);
+    }
+
+    #[test]
+    fn insert_panel() {
+        let mut app = TestApp::new(Xilem::new(panels(&["a", "b"]), app_logic));
+        let a = content_id(&app.harness, "a").unwrap();
+        let b = content_id(&app.harness, "b").unwrap();
+
+        click_button(&mut app, 2);
+        assert_eq!(app.state().ids, ["d", "a", "b"]);
+        assert!(content_id(&app.harness, "d").is_some());
+        assert_eq!(content_id(&app.harness, "a"), Some(a));
+        assert_eq!(content_id(&app.harness, "b"), Some(b));
+        // The new panel is added to the layout, which is reported to the app.
+        assert!(app.state().layout.contains("d"));
+        click_shown_panel(&mut app);
+    }
+}
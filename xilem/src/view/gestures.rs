@@ -0,0 +1,247 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::marker::PhantomData;
+
+use masonry::gesture::Gesture;
+use masonry::widget;
+use xilem_core::{DynMessage, Mut, ViewMarker, ViewPathTracker};
+
+use crate::{MessageResult, Pod, View, ViewCtx, ViewId, WidgetView};
+
+pub use masonry::gesture::{Click, Drag, DragPhase, LongPress, PanZoom};
+
+type GestureCallback<State, Action, G> =
+    Box<dyn Fn(&mut State, &G) -> Action + Send + Sync + 'static>;
+
+/// A view which recognizes pointer gestures on `child`, such as double-clicks and drags.
+///
+/// This is usually used as a modifier, with [`WidgetView::on_click`],
+/// [`WidgetView::on_double_click`], [`WidgetView::on_long_press`], [`WidgetView::on_drag`]
+/// or [`WidgetView::on_pan_zoom`]. Only the gestures with a callback are recognized.
+///
+/// Positions in the gestures are relative to the child's top left corner.
+///
+/// This corresponds to the Masonry [`GestureDetector`](masonry::widget::GestureDetector) widget.
+///
+/// # Examples
+///
+/// ```
+/// use xilem::view::{label, sized_box, DragPhase};
+/// use xilem::WidgetView;
+///
+/// struct Photo {
+///     zoom: f64,
+///     offset: (f64, f64),
+/// }
+///
+/// fn photo(state: &mut Photo) -> impl WidgetView<Photo> {
+///     sized_box(label(format!("Zoom: {:.0}%", state.zoom * 100.)))
+///         .on_double_click(|state: &mut Photo, _| state.zoom = 1.)
+///         .on_drag(|state: &mut Photo, drag| {
+///             if drag.phase != DragPhase::End {
+///                 state.offset.0 += drag.delta.x;
+///                 state.offset.1 += drag.delta.y;
+///             }
+///         })
+///         .on_pan_zoom(|state: &mut Photo, pan_zoom| state.zoom *= pan_zoom.zoom)
+/// }
+/// ```
+pub fn gestures<Child, State, Action>(child: Child) -> Gestures<Child, State, Action>
+where
+    Child: WidgetView<State, Action>,
+{
+    Gestures {
+        child,
+        on_click: None,
+        on_double_click: None,
+        on_long_press: None,
+        on_drag: None,
+        on_pan_zoom: None,
+        phantom: PhantomData,
+    }
+}
+
+/// The [`View`] created by [`gestures`].
+///
+/// See `gestures`'s docs for more details.
+pub struct Gestures<V, State, Action = ()> {
+    child: V,
+    on_click: Option<GestureCallback<State, Action, Click>>,
+    on_double_click: Option<GestureCallback<State, Action, Click>>,
+    on_long_press: Option<GestureCallback<State, Action, LongPress>>,
+    on_drag: Option<GestureCallback<State, Action, Drag>>,
+    on_pan_zoom: Option<GestureCallback<State, Action, PanZoom>>,
+    phantom: PhantomData<fn() -> (State, Action)>,
+}
+
+impl<V, State, Action> Gestures<V, State, Action> {
+    /// Set the callback for clicks with any button.
+    ///
+    /// If there is also a double-click callback, the second click of a double-click
+    /// only calls that one.
+    pub fn on_click<F>(mut self, on_click: F) -> Self
+    where
+        F: Fn(&mut State, &Click) -> Action + Send + Sync + 'static,
+    {
+        self.on_click = Some(Box::new(on_click));
+        self
+    }
+
+    /// Set the callback for double-clicks with any button.
+    pub fn on_double_click<F>(mut self, on_double_click: F) -> Self
+    where
+        F: Fn(&mut State, &Click) -> Action + Send + Sync + 'static,
+    {
+        self.on_double_click = Some(Box::new(on_double_click));
+        self
+    }
+
+    /// Set the callback for long presses of the primary button.
+    pub fn on_long_press<F>(mut self, on_long_press: F) -> Self
+    where
+        F: Fn(&mut State, &LongPress) -> Action + Send + Sync + 'static,
+    {
+        self.on_long_press = Some(Box::new(on_long_press));
+        self
+    }
+
+    /// Set the callback for every phase of drags.
+    pub fn on_drag<F>(mut self, on_drag: F) -> Self
+    where
+        F: Fn(&mut State, &Drag) -> Action + Send + Sync + 'static,
+    {
+        self.on_drag = Some(Box::new(on_drag));
+        self
+    }
+
+    /// Set the callback for touchpad and two-finger touch pans and pinches.
+    ///
+    /// Mouse wheel events are reported as pans.
+    pub fn on_pan_zoom<F>(mut self, on_pan_zoom: F) -> Self
+    where
+        F: Fn(&mut State, &PanZoom) -> Action + Send + Sync + 'static,
+    {
+        self.on_pan_zoom = Some(Box::new(on_pan_zoom));
+        self
+    }
+
+    fn recognize_clicks(&self) -> bool {
+        self.on_click.is_some() || self.on_double_click.is_some()
+    }
+}
+
+/// The id of the child view, to tell its messages apart from the gestures.
+const CHILD_VIEW_ID: ViewId = ViewId::new(0);
+
+impl<V, State, Action> ViewMarker for Gestures<V, State, Action> {}
+impl<V, State, Action> View<State, Action, ViewCtx> for Gestures<V, State, Action>
+where
+    V: WidgetView<State, Action>,
+    State: 'static,
+    Action: 'static,
+{
+    type Element = Pod<widget::GestureDetector>;
+    type ViewState = V::ViewState;
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        let (child, child_state) = ctx.with_id(CHILD_VIEW_ID, |ctx| self.child.build(ctx));
+        let widget = widget::GestureDetector::new_pod(child.inner.boxed())
+            .clicks(self.recognize_clicks())
+            .long_press(self.on_long_press.is_some())
+            .drag(self.on_drag.is_some())
+            .pan_zoom(self.on_pan_zoom.is_some());
+        let pod = ctx.with_action_widget(|_| Pod::new(widget));
+        (pod, child_state)
+    }
+
+    fn rebuild<'el>(
+        &self,
+        prev: &Self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<'el, Self::Element>,
+    ) -> Mut<'el, Self::Element> {
+        element.set_clicks(self.recognize_clicks());
+        element.set_long_press(self.on_long_press.is_some());
+        element.set_drag(self.on_drag.is_some());
+        element.set_pan_zoom(self.on_pan_zoom.is_some());
+        {
+            let mut child = element.child_mut();
+            ctx.with_id(CHILD_VIEW_ID, |ctx| {
+                self.child
+                    .rebuild(&prev.child, view_state, ctx, child.downcast());
+            });
+        }
+        element
+    }
+
+    fn teardown(
+        &self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<'_, Self::Element>,
+    ) {
+        {
+            let mut child = element.child_mut();
+            ctx.with_id(CHILD_VIEW_ID, |ctx| {
+                self.child.teardown(view_state, ctx, child.downcast());
+            });
+        }
+        ctx.teardown_leaf(element);
+    }
+
+    fn message(
+        &self,
+        view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: DynMessage,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        if let Some((first, remainder)) = id_path.split_first() {
+            if *first != CHILD_VIEW_ID {
+                tracing::error!("Invalid id path in Gestures::message: {id_path:?}");
+                return MessageResult::Stale(message);
+            }
+            return self
+                .child
+                .message(view_state, remainder, message, app_state);
+        }
+        let action = match message.downcast::<masonry::Action>() {
+            Ok(action) => action,
+            Err(message) => {
+                tracing::error!("Wrong message type in Gestures::message");
+                return MessageResult::Stale(message);
+            }
+        };
+        let masonry::Action::Gesture(gesture) = *action else {
+            tracing::error!("Wrong action type in Gestures::message: {action:?}");
+            return MessageResult::Stale(action);
+        };
+        let action = match &gesture {
+            Gesture::Click(click) => match (&self.on_double_click, &self.on_click) {
+                (Some(on_double_click), _) if click.count == 2 => {
+                    Some(on_double_click(app_state, click))
+                }
+                (_, Some(on_click)) => Some(on_click(app_state, click)),
+                _ => None,
+            },
+            Gesture::LongPress(long_press) => self
+                .on_long_press
+                .as_ref()
+                .map(|on_long_press| on_long_press(app_state, long_press)),
+            Gesture::Drag(drag) => self
+                .on_drag
+                .as_ref()
+                .map(|on_drag| on_drag(app_state, drag)),
+            Gesture::PanZoom(pan_zoom) => self
+                .on_pan_zoom
+                .as_ref()
+                .map(|on_pan_zoom| on_pan_zoom(app_state, pan_zoom)),
+        };
+        match action {
+            Some(action) => MessageResult::Action(action),
+            None => MessageResult::Nop,
+        }
+    }
+}
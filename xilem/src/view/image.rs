@@ -8,6 +8,8 @@ use xilem_core::{Mut, ViewMarker};
 
 use crate::{MessageResult, Pod, View, ViewCtx, ViewId};
 
+pub use masonry::image_loading::{ImageCache, ImageError, ImageLoader, ImageSource};
+
 /// Displays the bitmap `image`.
 ///
 /// By default, the Image will scale to fit its box constraints ([`FillStrat::Fill`]).
@@ -23,19 +25,50 @@ pub fn image(image: &vello::peniko::Image) -> Image {
         // Image only contains a `Blob` and Copy fields, and so is cheap to clone.
         // We take by reference as we expect all users of this API will need to clone, and it's
         // easier than documenting that cloning is cheap.
-        image: image.clone(),
+        content: ImageContent::Buffer(image.clone()),
         fill: FillStrat::default(),
     }
 }
 
-/// The [`View`] created by [`image`].
+/// Displays the image loaded from `source`.
+///
+/// The image is decoded in the background, with a placeholder shown until it is loaded,
+/// or if loading fails. The futures of [`ImageSource::Loader`]s are run on the app's
+/// async runtime, so they can make network requests.
+///
+/// Like [`image`], the Image will scale to fit its box constraints by default.
+///
+/// # Examples
+///
+/// ```
+/// use xilem::view::{image_source, ImageSource};
+/// # use xilem::WidgetView;
+///
+/// # fn view<State: 'static>() -> impl WidgetView<State> {
+/// image_source(ImageSource::path("resources/images/cat.png"))
+/// # }
+/// ```
+pub fn image_source(source: ImageSource) -> Image {
+    Image {
+        content: ImageContent::Source(source),
+        fill: FillStrat::default(),
+    }
+}
+
+/// The [`View`] created by [`image`] or [`image_source`].
 ///
 /// See `image`'s docs for more details.
 pub struct Image {
-    image: vello::peniko::Image,
+    content: ImageContent,
     fill: FillStrat,
 }
 
+#[derive(PartialEq)]
+enum ImageContent {
+    Buffer(vello::peniko::Image),
+    Source(ImageSource),
+}
+
 impl Image {
     /// Specify the fill strategy.
     pub fn fill(mut self, fill: FillStrat) -> Self {
@@ -44,27 +77,48 @@ impl Image {
     }
 }
 
+/// Make the future of a loader source run on the app's runtime, rather than on the
+/// decoding thread, which doesn't have a runtime.
+fn on_runtime(source: &ImageSource, ctx: &ViewCtx) -> ImageSource {
+    let ImageSource::Loader(loader) = source else {
+        return source.clone();
+    };
+    let loader = loader.clone();
+    let runtime = ctx.runtime().handle().clone();
+    ImageSource::loader(loader.key().to_owned(), move || {
+        let task = runtime.spawn(loader.load());
+        async move { task.await? }
+    })
+}
+
 impl ViewMarker for Image {}
 impl<State, Action> View<State, Action, ViewCtx> for Image {
     type Element = Pod<widget::Image>;
     type ViewState = ();
 
-    fn build(&self, _: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
-        (Pod::new(widget::Image::new(self.image.clone())), ())
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        let widget = match &self.content {
+            ImageContent::Buffer(image) => widget::Image::new(image.clone()),
+            ImageContent::Source(source) => widget::Image::from_source(on_runtime(source, ctx)),
+        };
+        (Pod::new(widget.fill_mode(self.fill)), ())
     }
 
     fn rebuild<'el>(
         &self,
         prev: &Self,
         (): &mut Self::ViewState,
-        _: &mut ViewCtx,
+        ctx: &mut ViewCtx,
         mut element: Mut<'el, Self::Element>,
     ) -> Mut<'el, Self::Element> {
         if prev.fill != self.fill {
             element.set_fill_mode(self.fill);
         }
-        if prev.image != self.image {
-            element.set_image_data(self.image.clone());
+        if prev.content != self.content {
+            match &self.content {
+                ImageContent::Buffer(image) => element.set_image_data(image.clone()),
+                ImageContent::Source(source) => element.set_source(on_runtime(source, ctx)),
+            }
         }
         element
     }
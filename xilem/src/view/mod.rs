@@ -7,24 +7,51 @@ pub use task::*;
 mod worker;
 pub use worker::*;
 
+mod resource;
+pub use resource::*;
+
+mod stream;
+pub use stream::*;
+
 mod button;
 pub use button::*;
 
+mod canvas;
+pub use canvas::*;
+
+mod chart;
+pub use chart::*;
+
 mod checkbox;
 pub use checkbox::*;
 
+mod dock;
+pub use dock::*;
+
 mod flex;
 pub use flex::*;
 
+mod gestures;
+pub use gestures::*;
+
 mod sized_box;
 pub use sized_box::*;
 
+mod align;
+pub use align::*;
+
+mod split;
+pub use split::*;
+
 mod spinner;
 pub use spinner::*;
 
 mod image;
 pub use image::*;
 
+mod svg_image;
+pub use svg_image::*;
+
 mod label;
 pub use label::*;
 
@@ -42,3 +69,12 @@ pub use textbox::*;
 
 mod portal;
 pub use portal::*;
+
+mod table;
+pub use table::*;
+
+mod tabs;
+pub use tabs::*;
+
+mod tree;
+pub use tree::*;
@@ -3,10 +3,15 @@
 
 use std::marker::PhantomData;
 
-use masonry::widget;
+use masonry::{
+    widget::{self, WidgetRef},
+    Point, Widget,
+};
 use xilem_core::{Mut, ViewMarker};
 
-use crate::{Pod, View, ViewCtx, ViewId, WidgetView};
+use crate::{persist::WidgetUiState, Pod, View, ViewCtx, ViewId, WidgetView};
+
+pub use masonry::widget::OverscrollBehavior;
 
 /// A view which puts `child` into a scrollable region.
 ///
@@ -17,15 +22,44 @@ where
 {
     Portal {
         child,
+        persist_key: None,
+        overscroll_behavior: OverscrollBehavior::default(),
         phantom: PhantomData,
     }
 }
 
 pub struct Portal<V, State, Action> {
     child: V,
+    persist_key: Option<String>,
+    overscroll_behavior: OverscrollBehavior,
     phantom: PhantomData<(State, Action)>,
 }
 
+impl<V, State, Action> Portal<V, State, Action> {
+    /// Persist the viewport position of this portal under `key`, if the app is [persisted](crate::Xilem::persist).
+    ///
+    /// The key should be unique within the app.
+    pub fn persist_as(mut self, key: impl Into<String>) -> Self {
+        self.persist_key = Some(key.into());
+        self
+    }
+
+    /// Set what happens when scrolling past the edges of the content with a touch screen
+    /// or a touchpad.
+    ///
+    /// The default is [`OverscrollBehavior::Clamp`].
+    pub fn overscroll_behavior(mut self, behavior: OverscrollBehavior) -> Self {
+        self.overscroll_behavior = behavior;
+        self
+    }
+}
+
+fn read_viewport_pos<W: Widget>(widget: WidgetRef<'_, dyn Widget>) -> Option<WidgetUiState> {
+    let portal = widget.downcast::<widget::Portal<W>>()?;
+    let Point { x, y } = portal.get_viewport_pos();
+    Some(WidgetUiState::Viewport { x, y })
+}
+
 impl<V, State, Action> ViewMarker for Portal<V, State, Action> {}
 impl<Child, State, Action> View<State, Action, ViewCtx> for Portal<Child, State, Action>
 where
@@ -40,7 +74,21 @@ where
         // The Portal `View` doesn't get any messages directly (yet - scroll events?), so doesn't need to
         // use ctx.with_id.
         let (child, child_state) = self.child.build(ctx);
-        let widget_pod = Pod::new(widget::Portal::new_pod(child.inner));
+        let mut widget =
+            widget::Portal::new_pod(child.inner).overscroll_behavior(self.overscroll_behavior);
+        if let Some(key) = &self.persist_key {
+            if let Some(WidgetUiState::Viewport { x, y }) = ctx.take_restored_ui_state(key) {
+                widget = widget.viewport_pos(Point::new(x, y));
+            }
+        }
+        let widget_pod = Pod::new(widget);
+        if let Some(key) = &self.persist_key {
+            ctx.register_ui_state(
+                key.clone(),
+                widget_pod.inner.id(),
+                read_viewport_pos::<Child::Widget>,
+            );
+        }
         (widget_pod, child_state)
     }
 
@@ -51,6 +99,18 @@ where
         ctx: &mut ViewCtx,
         mut element: Mut<'el, Self::Element>,
     ) -> Mut<'el, Self::Element> {
+        if self.persist_key != prev.persist_key {
+            let id = element.ctx.widget_id();
+            if let Some(key) = &prev.persist_key {
+                ctx.unregister_ui_state(key, id);
+            }
+            if let Some(key) = &self.persist_key {
+                ctx.register_ui_state(key.clone(), id, read_viewport_pos::<Child::Widget>);
+            }
+        }
+        if self.overscroll_behavior != prev.overscroll_behavior {
+            element.set_overscroll_behavior(self.overscroll_behavior);
+        }
         let child_element = element.child_mut();
         self.child
             .rebuild(&prev.child, view_state, ctx, child_element);
@@ -63,6 +123,9 @@ where
         ctx: &mut ViewCtx,
         mut element: Mut<'_, Self::Element>,
     ) {
+        if let Some(key) = &self.persist_key {
+            ctx.unregister_ui_state(key, element.ctx.widget_id());
+        }
         let child_element = element.child_mut();
         self.child.teardown(view_state, ctx, child_element);
     }
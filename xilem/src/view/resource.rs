@@ -0,0 +1,493 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{fmt::Debug, future::Future, marker::PhantomData, sync::Arc, time::Duration};
+
+use tokio::task::JoinHandle;
+use xilem_core::{
+    DynMessage, MessageProxy, MessageResult, Mut, View, ViewId, ViewMarker, ViewPathTracker,
+};
+
+use crate::{ViewCtx, WidgetView};
+
+/// The status of the future driven by a [`resource`] view.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResourceStatus<T, E> {
+    /// The future for the current key has not completed yet.
+    ///
+    /// This is also the status while a debounced future is waiting to start.
+    Loading,
+    /// The future for the current key resolved successfully.
+    Ready(T),
+    /// The future for the current key resolved with an error.
+    Failed(E),
+}
+
+impl<T, E> ResourceStatus<T, E> {
+    /// Whether the future for the current key is still running.
+    pub fn is_loading(&self) -> bool {
+        matches!(self, Self::Loading)
+    }
+
+    /// The successful output of the future, if it has completed.
+    pub fn ready(&self) -> Option<&T> {
+        match self {
+            Self::Ready(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// The error returned by the future, if it has failed.
+    pub fn failed(&self) -> Option<&E> {
+        match self {
+            Self::Failed(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// Load an asynchronous resource identified by `key`, and show a view of its status.
+///
+/// `init_future` is called with `key` to create a future, which is run on the Tokio runtime
+/// of the app. Whilst the future is pending, `view` is called with
+/// [`ResourceStatus::Loading`], and once it has completed, with its result as
+/// [`ResourceStatus::Ready`] or [`ResourceStatus::Failed`].
+/// Data from the app state which `view` needs can be captured when creating this view.
+///
+/// When `key` changes, the running future is aborted, the status is reset to `Loading`,
+/// and `init_future` is called again with the new key.
+/// Results from futures for outdated keys are never shown.
+/// Use [`debounce`](Resource::debounce) to avoid starting a future for every intermediate key,
+/// for example whilst the user is typing into a search field.
+///
+/// This is the native equivalent of `memoized_await` in Xilem Web.
+///
+/// # Examples
+///
+/// ```
+/// use xilem::{view::{label, resource, ResourceStatus}, core::one_of::OneOf3, WidgetView};
+///
+/// async fn fetch_name(id: u32) -> Result<String, String> {
+///     Ok(format!("User #{id}"))
+/// }
+///
+/// struct AppState {
+///     user_id: u32,
+///     greeting: String,
+/// }
+///
+/// fn user_name(state: &mut AppState) -> impl WidgetView<AppState> {
+///     let greeting = state.greeting.clone();
+///     resource(
+///         state.user_id,
+///         |id| fetch_name(*id),
+///         move |status| match status {
+///             ResourceStatus::Loading => OneOf3::A(label("Loading...")),
+///             ResourceStatus::Ready(name) => OneOf3::B(label(format!("{greeting} {name}"))),
+///             ResourceStatus::Failed(error) => OneOf3::C(label(format!("Error: {error}"))),
+///         },
+///     )
+/// }
+/// ```
+pub fn resource<State, Action, Key, F, Fut, T, E, B, V>(
+    key: Key,
+    init_future: F,
+    view: B,
+) -> Resource<Key, F, B, State, Action, T, E>
+where
+    Key: PartialEq + 'static,
+    F: Fn(&Key) -> Fut + 'static,
+    Fut: Future<Output = Result<T, E>> + Send + 'static,
+    T: Debug + Send + 'static,
+    E: Debug + Send + 'static,
+    B: Fn(&ResourceStatus<T, E>) -> V + 'static,
+    V: WidgetView<State, Action>,
+{
+    Resource {
+        key,
+        init_future,
+        view,
+        debounce: Duration::ZERO,
+        phantom: PhantomData,
+    }
+}
+
+/// The view type for [`resource`].
+pub struct Resource<Key, F, B, State, Action, T, E> {
+    key: Key,
+    init_future: F,
+    view: B,
+    debounce: Duration,
+    #[allow(clippy::type_complexity)]
+    phantom: PhantomData<fn() -> (State, Action, T, E)>,
+}
+
+impl<Key, F, B, State, Action, T, E> Resource<Key, F, B, State, Action, T, E> {
+    /// Wait for `duration` before creating and starting the future, restarting the wait
+    /// whenever the key changes in the meantime.
+    ///
+    /// The default for this is [`Duration::ZERO`], i.e. the future is started immediately.
+    pub fn debounce(mut self, duration: Duration) -> Self {
+        self.debounce = duration;
+        self
+    }
+}
+
+impl<Key, F, Fut, B, State, Action, T, E> Resource<Key, F, B, State, Action, T, E>
+where
+    F: Fn(&Key) -> Fut,
+    Fut: Future<Output = Result<T, E>> + Send + 'static,
+    T: Debug + Send + 'static,
+    E: Debug + Send + 'static,
+{
+    /// Start the future for the current key, or wait for the debounce duration first.
+    fn spawn(&self, ctx: &mut ViewCtx, generation: u64) -> JoinHandle<()> {
+        if self.debounce.is_zero() {
+            return self.start(ctx, generation);
+        }
+        ctx.with_id(ViewId::new(generation), |ctx| {
+            let path: Arc<[ViewId]> = ctx.view_path().into();
+            let proxy = MessageProxy::<ResourceMessage<T, E>>::new(ctx.proxy.clone(), path);
+            let debounce = self.debounce;
+            ctx.runtime().spawn(async move {
+                tokio::time::sleep(debounce).await;
+                // The future is created when the view handles this message,
+                // so that it isn't created for keys which are replaced whilst waiting.
+                drop(proxy.message(ResourceMessage::Start));
+            })
+        })
+    }
+
+    /// Create and start the future for the current key.
+    fn start(&self, ctx: &mut ViewCtx, generation: u64) -> JoinHandle<()> {
+        ctx.with_id(ViewId::new(generation), |ctx| {
+            let path: Arc<[ViewId]> = ctx.view_path().into();
+            let proxy = MessageProxy::<ResourceMessage<T, E>>::new(ctx.proxy.clone(), path);
+            let future = (self.init_future)(&self.key);
+            ctx.runtime().spawn(async move {
+                // We choose not to handle the case where the event loop has ended
+                drop(proxy.message(ResourceMessage::Output(future.await)));
+            })
+        })
+    }
+}
+
+#[derive(Debug)]
+enum ResourceMessage<T, E> {
+    /// The debounce duration has elapsed, so the future should be started.
+    Start,
+    Output(Result<T, E>),
+}
+
+/// The id used for the child view. The futures use the ids `1..`, based on their generation.
+const CHILD_VIEW_ID: ViewId = ViewId::new(0);
+
+#[doc(hidden)] // Implementation detail, public because of trait visibility rules
+pub struct ResourceState<T, E, V, VState> {
+    generation: u64,
+    handle: JoinHandle<()>,
+    /// Whether the debounce duration has elapsed, so the future is started in the next rebuild.
+    start: bool,
+    status: ResourceStatus<T, E>,
+    view: V,
+    view_state: VState,
+}
+
+impl<Key, F, B, State, Action, T, E> ViewMarker for Resource<Key, F, B, State, Action, T, E> {}
+impl<Key, F, Fut, B, V, State, Action, T, E> View<State, Action, ViewCtx>
+    for Resource<Key, F, B, State, Action, T, E>
+where
+    State: 'static,
+    Action: 'static,
+    Key: PartialEq + 'static,
+    F: Fn(&Key) -> Fut + 'static,
+    Fut: Future<Output = Result<T, E>> + Send + 'static,
+    T: Debug + Send + 'static,
+    E: Debug + Send + 'static,
+    B: Fn(&ResourceStatus<T, E>) -> V + 'static,
+    V: WidgetView<State, Action>,
+{
+    type Element = V::Element;
+
+    type ViewState = ResourceState<T, E, V, V::ViewState>;
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        let generation = 1;
+        let handle = self.spawn(ctx, generation);
+        let status = ResourceStatus::Loading;
+        let view = (self.view)(&status);
+        let (element, view_state) = ctx.with_id(CHILD_VIEW_ID, |ctx| view.build(ctx));
+        let state = ResourceState {
+            generation,
+            handle,
+            start: false,
+            status,
+            view,
+            view_state,
+        };
+        (element, state)
+    }
+
+    fn rebuild<'el>(
+        &self,
+        prev: &Self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        element: Mut<'el, Self::Element>,
+    ) -> Mut<'el, Self::Element> {
+        if prev.key != self.key {
+            view_state.handle.abort();
+            view_state.generation += 1;
+            view_state.handle = self.spawn(ctx, view_state.generation);
+            view_state.start = false;
+            view_state.status = ResourceStatus::Loading;
+        } else if view_state.start {
+            view_state.handle = self.start(ctx, view_state.generation);
+            view_state.start = false;
+        }
+        // The view function can capture arbitrary data, so we always diff the child.
+        let view = (self.view)(&view_state.status);
+        let element = ctx.with_id(CHILD_VIEW_ID, |ctx| {
+            view.rebuild(&view_state.view, &mut view_state.view_state, ctx, element)
+        });
+        view_state.view = view;
+        element
+    }
+
+    fn teardown(
+        &self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        element: Mut<'_, Self::Element>,
+    ) {
+        view_state.handle.abort();
+        ctx.with_id(CHILD_VIEW_ID, |ctx| {
+            view_state
+                .view
+                .teardown(&mut view_state.view_state, ctx, element);
+        });
+    }
+
+    fn message(
+        &self,
+        view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: DynMessage,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        let Some((first, remainder)) = id_path.split_first() else {
+            unreachable!("Resource::message should always have an id in the path");
+        };
+        if *first == CHILD_VIEW_ID {
+            return view_state.view.message(
+                &mut view_state.view_state,
+                remainder,
+                message,
+                app_state,
+            );
+        }
+        if first.routing_id() != view_state.generation {
+            return MessageResult::Stale(message);
+        }
+        match *message.downcast::<ResourceMessage<T, E>>().unwrap() {
+            ResourceMessage::Start => view_state.start = true,
+            ResourceMessage::Output(Ok(value)) => {
+                view_state.status = ResourceStatus::Ready(value);
+            }
+            ResourceMessage::Output(Err(error)) => {
+                view_state.status = ResourceStatus::Failed(error);
+            }
+        }
+        MessageResult::RequestRebuild
+    }
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::time::Instant;
+
+    use masonry::widget::Button;
+
+    use super::*;
+    use crate::testing::TestApp;
+    use crate::view::{button, flex, label, Label};
+    use crate::Xilem;
+
+    struct AppState {
+        key: u32,
+        prefix: &'static str,
+    }
+
+    /// Even keys load successfully, odd keys fail.
+    async fn load(key: u32) -> Result<u32, String> {
+        if key % 2 == 0 {
+            Ok(key * 10)
+        } else {
+            Err(format!("odd key {key}"))
+        }
+    }
+
+    fn app_logic(state: &mut AppState) -> impl WidgetView<AppState> {
+        flex((
+            button("Next", |state: &mut AppState| state.key += 1),
+            resource(state.key, |key| load(*key), status_label(state.prefix)),
+        ))
+    }
+
+    fn status_label(prefix: &'static str) -> impl Fn(&ResourceStatus<u32, String>) -> Label {
+        move |status| {
+            label(match status {
+                ResourceStatus::Loading => format!("{prefix}loading"),
+                ResourceStatus::Ready(value) => format!("{prefix}ready {value}"),
+                ResourceStatus::Failed(error) => format!("{prefix}failed: {error}"),
+            })
+        }
+    }
+
+    fn state(key: u32) -> AppState {
+        AppState {
+            key,
+            prefix: "Item: ",
+        }
+    }
+
+    #[test]
+    fn loading_then_ready() {
+        let mut app = TestApp::new(Xilem::new(state(2), app_logic));
+        assert_eq!(app.label_texts(), ["Next", "Item: loading"]);
+
+        app.handle_async_message();
+        assert_eq!(app.label_texts(), ["Next", "Item: ready 20"]);
+    }
+
+    #[test]
+    fn loading_then_failed() {
+        let mut app = TestApp::new(Xilem::new(state(1), app_logic));
+        assert_eq!(app.label_texts(), ["Next", "Item: loading"]);
+
+        app.handle_async_message();
+        assert_eq!(app.label_texts(), ["Next", "Item: failed: odd key 1"]);
+    }
+
+    #[test]
+    fn key_change_restarts_loading() {
+        let mut app = TestApp::new(Xilem::new(state(0), app_logic));
+        app.handle_async_message();
+        assert_eq!(app.label_texts(), ["Next", "Item: ready 0"]);
+
+        let button_id = app.harness.root_widget().children()[0].children()[0].id();
+        assert!(app
+            .harness
+            .get_widget(button_id)
+            .downcast::<Button>()
+            .is_some());
+        app.harness.mouse_click_on(button_id);
+        app.handle_actions();
+        assert_eq!(app.state().key, 1);
+        assert_eq!(app.label_texts(), ["Next", "Item: loading"]);
+
+        app.handle_async_message();
+        assert_eq!(app.label_texts(), ["Next", "Item: failed: odd key 1"]);
+    }
+
+    fn click_next<Logic, V>(app: &mut TestApp<AppState, Logic, V>)
+    where
+        Logic: FnMut(&mut AppState) -> V,
+        V: WidgetView<AppState>,
+    {
+        let button_id = app.harness.root_widget().children()[0].children()[0].id();
+        app.harness.mouse_click_on(button_id);
+        app.handle_actions();
+    }
+
+    static DEBOUNCED_LOADS: AtomicUsize = AtomicUsize::new(0);
+
+    fn debounced_logic(state: &mut AppState) -> impl WidgetView<AppState> {
+        flex((
+            button("Next", |state: &mut AppState| state.key += 1),
+            resource(
+                state.key,
+                |key| {
+                    DEBOUNCED_LOADS.fetch_add(1, Ordering::Relaxed);
+                    load(*key)
+                },
+                status_label(state.prefix),
+            )
+            .debounce(Duration::from_millis(200)),
+        ))
+    }
+
+    #[test]
+    fn debounce_creates_one_future() {
+        let mut app = TestApp::new(Xilem::new(state(0), debounced_logic));
+        click_next(&mut app);
+        click_next(&mut app);
+        assert_eq!(app.state().key, 2);
+        assert_eq!(app.label_texts(), ["Next", "Item: loading"]);
+        // The future isn't created until the debounce duration has elapsed.
+        assert_eq!(DEBOUNCED_LOADS.load(Ordering::Relaxed), 0);
+
+        // The end of the wait for the last key.
+        app.handle_async_message();
+        assert_eq!(DEBOUNCED_LOADS.load(Ordering::Relaxed), 1);
+        assert_eq!(app.label_texts(), ["Next", "Item: loading"]);
+
+        app.handle_async_message();
+        assert_eq!(app.label_texts(), ["Next", "Item: ready 20"]);
+        assert_eq!(DEBOUNCED_LOADS.load(Ordering::Relaxed), 1);
+    }
+
+    static PENDING_DROPPED: AtomicBool = AtomicBool::new(false);
+
+    /// Sets [`PENDING_DROPPED`] when the future which owns it is dropped.
+    struct DropFlag;
+
+    impl Drop for DropFlag {
+        fn drop(&mut self) {
+            PENDING_DROPPED.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// Key 0 never finishes loading.
+    fn pending_logic(state: &mut AppState) -> impl WidgetView<AppState> {
+        flex((
+            button("Next", |state: &mut AppState| state.key += 1),
+            resource(
+                state.key,
+                |key| {
+                    let key = *key;
+                    let flag = (key == 0).then_some(DropFlag);
+                    async move {
+                        if flag.is_some() {
+                            std::future::pending::<()>().await;
+                        }
+                        load(key).await
+                    }
+                },
+                status_label(state.prefix),
+            ),
+        ))
+    }
+
+    #[test]
+    fn key_change_aborts_future() {
+        let mut app = TestApp::new(Xilem::new(state(0), pending_logic));
+        click_next(&mut app);
+        assert_eq!(app.label_texts(), ["Next", "Item: loading"]);
+
+        // Aborted tasks are dropped by the runtime.
+        let start = Instant::now();
+        while !PENDING_DROPPED.load(Ordering::Relaxed) {
+            assert!(
+                start.elapsed() < Duration::from_secs(5),
+                "the future for the old key should have been aborted"
+            );
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        app.handle_async_message();
+        assert_eq!(app.label_texts(), ["Next", "Item: failed: odd key 1"]);
+    }
+}
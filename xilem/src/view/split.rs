@@ -0,0 +1,326 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::marker::PhantomData;
+
+use masonry::widget::{self, Axis, WidgetRef};
+use masonry::Widget;
+use xilem_core::{DynMessage, Mut, ViewMarker, ViewPathTracker};
+
+use crate::{persist::WidgetUiState, MessageResult, Pod, View, ViewCtx, ViewId, WidgetView};
+
+type SplitPointCallback<State, Action> = Box<dyn Fn(&mut State, f64) -> Action + Send + Sync>;
+
+/// The view id of the first child of a [`Split`].
+const CHILD1_VIEW_ID: ViewId = ViewId::new(0);
+/// The view id of the second child of a [`Split`].
+const CHILD2_VIEW_ID: ViewId = ViewId::new(1);
+
+/// A view which splits its area in two, with `child1` on the left and `child2` on the right.
+///
+/// The split point can be dragged by the user, which is reported by
+/// [`on_split_point_change`](Split::on_split_point_change).
+///
+/// This corresponds to the Masonry [`Split`](masonry::widget::Split) widget.
+///
+/// # Examples
+///
+/// ```
+/// use xilem::view::{label, split};
+/// use xilem::WidgetView;
+///
+/// struct Editor {
+///     split_point: f64,
+/// }
+///
+/// fn editor(state: &mut Editor) -> impl WidgetView<Editor> {
+///     split(label("Files"), label("Contents"))
+///         .split_point(state.split_point)
+///         .on_split_point_change(|state: &mut Editor, split_point| {
+///             state.split_point = split_point;
+///         })
+/// }
+/// ```
+pub fn split<State, Action, ChildA, ChildB>(
+    child1: ChildA,
+    child2: ChildB,
+) -> Split<ChildA, ChildB, State, Action>
+where
+    ChildA: WidgetView<State, Action>,
+    ChildB: WidgetView<State, Action>,
+{
+    Split {
+        child1,
+        child2,
+        split_axis: Axis::Horizontal,
+        split_point: None,
+        min_size: (0.0, 0.0),
+        bar_size: 6.0,
+        min_bar_area: 6.0,
+        solid: false,
+        draggable: true,
+        on_split_point_change: None,
+        persist_key: None,
+        phantom: PhantomData,
+    }
+}
+
+pub struct Split<ChildA, ChildB, State, Action = ()> {
+    child1: ChildA,
+    child2: ChildB,
+    split_axis: Axis,
+    split_point: Option<f64>,
+    min_size: (f64, f64),
+    bar_size: f64,
+    min_bar_area: f64,
+    solid: bool,
+    draggable: bool,
+    on_split_point_change: Option<SplitPointCallback<State, Action>>,
+    persist_key: Option<String>,
+    phantom: PhantomData<fn() -> (State, Action)>,
+}
+
+impl<ChildA, ChildB, State, Action> Split<ChildA, ChildB, State, Action> {
+    /// Set the axis which is split in two.
+    ///
+    /// With [`Axis::Vertical`], the first child is on top and the second child below it.
+    /// The default is [`Axis::Horizontal`].
+    pub fn split_axis(mut self, split_axis: Axis) -> Self {
+        self.split_axis = split_axis;
+        self
+    }
+
+    /// Set the split point as a fraction of the split axis.
+    ///
+    /// The value must be between `0.0` and `1.0`, inclusive.
+    /// If this isn't set, the split point starts at `0.5` and the widget keeps track of it itself.
+    pub fn split_point(mut self, split_point: f64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&split_point),
+            "split_point must be in the range [0.0-1.0]!"
+        );
+        self.split_point = Some(split_point);
+        self
+    }
+
+    /// Set the minimum size for both sides of the split axis.
+    pub fn min_size(mut self, first: f64, second: f64) -> Self {
+        assert!(first >= 0.0);
+        assert!(second >= 0.0);
+        self.min_size = (first.ceil(), second.ceil());
+        self
+    }
+
+    /// Set the size of the splitter bar.
+    ///
+    /// The default splitter bar size is `6.0`.
+    pub fn bar_size(mut self, bar_size: f64) -> Self {
+        assert!(bar_size >= 0.0, "bar_size must be 0.0 or greater!");
+        self.bar_size = bar_size.ceil();
+        self
+    }
+
+    /// Set the minimum size of the area where the splitter bar can be clicked.
+    ///
+    /// The default minimum splitter bar area is `6.0`.
+    pub fn min_bar_area(mut self, min_bar_area: f64) -> Self {
+        assert!(min_bar_area >= 0.0, "min_bar_area must be 0.0 or greater!");
+        self.min_bar_area = min_bar_area.ceil();
+        self
+    }
+
+    /// Set whether the split point can be changed by dragging the splitter bar.
+    ///
+    /// The default is `true`.
+    pub fn draggable(mut self, draggable: bool) -> Self {
+        self.draggable = draggable;
+        self
+    }
+
+    /// Set whether the splitter bar is drawn as a solid rectangle, instead of two parallel lines.
+    pub fn solid_bar(mut self, solid: bool) -> Self {
+        self.solid = solid;
+        self
+    }
+
+    /// Set the callback for when the user drags the splitter bar, which is called with the new split point.
+    ///
+    /// If [`split_point`](Self::split_point) is set, this should update the state it is set from.
+    pub fn on_split_point_change<F>(mut self, on_split_point_change: F) -> Self
+    where
+        F: Fn(&mut State, f64) -> Action + Send + Sync + 'static,
+    {
+        self.on_split_point_change = Some(Box::new(on_split_point_change));
+        self
+    }
+
+    /// Persist the split point under `key`, if the app is [persisted](crate::Xilem::persist).
+    ///
+    /// The key should be unique within the app.
+    /// This has no effect on the initial split point if [`split_point`](Self::split_point) is set.
+    pub fn persist_as(mut self, key: impl Into<String>) -> Self {
+        self.persist_key = Some(key.into());
+        self
+    }
+}
+
+fn read_split_point(widget: WidgetRef<'_, dyn Widget>) -> Option<WidgetUiState> {
+    let split = widget.downcast::<widget::Split>()?;
+    Some(WidgetUiState::SplitPoint(split.get_split_point()))
+}
+
+impl<ChildA, ChildB, State, Action> ViewMarker for Split<ChildA, ChildB, State, Action> {}
+impl<ChildA, ChildB, State, Action> View<State, Action, ViewCtx>
+    for Split<ChildA, ChildB, State, Action>
+where
+    ChildA: WidgetView<State, Action>,
+    ChildB: WidgetView<State, Action>,
+    State: 'static,
+    Action: 'static,
+{
+    type Element = Pod<widget::Split>;
+    type ViewState = (ChildA::ViewState, ChildB::ViewState);
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        let (child1, child1_state) = ctx.with_id(CHILD1_VIEW_ID, |ctx| self.child1.build(ctx));
+        let (child2, child2_state) = ctx.with_id(CHILD2_VIEW_ID, |ctx| self.child2.build(ctx));
+        let mut widget =
+            widget::Split::new_pod(self.split_axis, child1.inner.boxed(), child2.inner.boxed())
+                .min_size(self.min_size.0, self.min_size.1)
+                .bar_size(self.bar_size)
+                .min_bar_area(self.min_bar_area)
+                .draggable(self.draggable)
+                .solid_bar(self.solid);
+        if let Some(key) = &self.persist_key {
+            if let Some(WidgetUiState::SplitPoint(split_point)) = ctx.take_restored_ui_state(key) {
+                widget = widget.split_point(split_point);
+            }
+        }
+        if let Some(split_point) = self.split_point {
+            widget = widget.split_point(split_point);
+        }
+        let pod = ctx.with_action_widget(|_| Pod::new(widget));
+        if let Some(key) = &self.persist_key {
+            ctx.register_ui_state(key.clone(), pod.inner.id(), read_split_point);
+        }
+        (pod, (child1_state, child2_state))
+    }
+
+    fn rebuild<'el>(
+        &self,
+        prev: &Self,
+        (child1_state, child2_state): &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<'el, Self::Element>,
+    ) -> Mut<'el, Self::Element> {
+        if self.split_axis != prev.split_axis {
+            element.set_split_axis(self.split_axis);
+        }
+        if let Some(split_point) = self.split_point {
+            // The widget changes its split point when it is dragged, so compare with it.
+            if element.widget.get_split_point() != split_point {
+                element.set_split_point(split_point);
+            }
+        }
+        if self.min_size != prev.min_size {
+            element.set_min_size(self.min_size.0, self.min_size.1);
+        }
+        if self.bar_size != prev.bar_size {
+            element.set_bar_size(self.bar_size);
+        }
+        if self.min_bar_area != prev.min_bar_area {
+            element.set_min_bar_area(self.min_bar_area);
+        }
+        if self.draggable != prev.draggable {
+            element.set_draggable(self.draggable);
+        }
+        if self.solid != prev.solid {
+            element.set_bar_solid(self.solid);
+        }
+        if self.persist_key != prev.persist_key {
+            let id = element.ctx.widget_id();
+            if let Some(key) = &prev.persist_key {
+                ctx.unregister_ui_state(key, id);
+            }
+            if let Some(key) = &self.persist_key {
+                ctx.register_ui_state(key.clone(), id, read_split_point);
+            }
+        }
+        ctx.with_id(CHILD1_VIEW_ID, |ctx| {
+            let mut child = element.child1_mut();
+            self.child1
+                .rebuild(&prev.child1, child1_state, ctx, child.downcast());
+        });
+        ctx.with_id(CHILD2_VIEW_ID, |ctx| {
+            let mut child = element.child2_mut();
+            self.child2
+                .rebuild(&prev.child2, child2_state, ctx, child.downcast());
+        });
+        element
+    }
+
+    fn teardown(
+        &self,
+        (child1_state, child2_state): &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<'_, Self::Element>,
+    ) {
+        if let Some(key) = &self.persist_key {
+            ctx.unregister_ui_state(key, element.ctx.widget_id());
+        }
+        ctx.with_id(CHILD1_VIEW_ID, |ctx| {
+            let mut child = element.child1_mut();
+            self.child1.teardown(child1_state, ctx, child.downcast());
+        });
+        ctx.with_id(CHILD2_VIEW_ID, |ctx| {
+            let mut child = element.child2_mut();
+            self.child2.teardown(child2_state, ctx, child.downcast());
+        });
+        ctx.teardown_leaf(element);
+    }
+
+    fn message(
+        &self,
+        (child1_state, child2_state): &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: DynMessage,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        match id_path.split_first() {
+            Some((&CHILD1_VIEW_ID, remainder)) => {
+                return self
+                    .child1
+                    .message(child1_state, remainder, message, app_state);
+            }
+            Some((&CHILD2_VIEW_ID, remainder)) => {
+                return self
+                    .child2
+                    .message(child2_state, remainder, message, app_state);
+            }
+            Some(_) => {
+                tracing::error!("Invalid id path in Split::message: {id_path:?}");
+                return MessageResult::Stale(message);
+            }
+            None => {}
+        }
+        match message.downcast::<masonry::Action>() {
+            Ok(action) => {
+                if let masonry::Action::SplitPointChanged(split_point) = *action {
+                    match &self.on_split_point_change {
+                        Some(on_split_point_change) => {
+                            MessageResult::Action(on_split_point_change(app_state, split_point))
+                        }
+                        None => MessageResult::Nop,
+                    }
+                } else {
+                    tracing::error!("Wrong action type in Split::message: {action:?}");
+                    MessageResult::Stale(action)
+                }
+            }
+            Err(message) => {
+                tracing::error!("Wrong message type in Split::message: {message:?}");
+                MessageResult::Stale(message)
+            }
+        }
+    }
+}
@@ -0,0 +1,283 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{marker::PhantomData, sync::Arc, time::Duration};
+
+use futures::{Stream, StreamExt};
+use tokio::task::JoinHandle;
+use xilem_core::{
+    DynMessage, Message, MessageProxy, MessageResult, Mut, NoElement, View, ViewId, ViewMarker,
+    ViewPathTracker,
+};
+
+use crate::ViewCtx;
+
+/// Subscribe to the [`Stream`] returned by `make_stream` whilst this view is in the tree.
+///
+/// Each item the stream produces is passed to `on_item`, which can then update the app's state.
+/// When `key` changes, the current subscription is dropped, and `make_stream` is called
+/// again with the new key. Items produced by a previous subscription are discarded.
+/// The subscription is also dropped once the view is removed from the tree.
+///
+/// The stream is polled on the Tokio runtime of the app.
+/// For periodic timers, use [`ticks`] as `make_stream`.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use xilem::{core::fork, view::{label, stream, ticks}, WidgetView};
+///
+/// fn ticker(count: &mut u32) -> impl WidgetView<u32> {
+///     fork(
+///         label(format!("{count} ticks have passed")),
+///         // When the period changes, the timer is restarted
+///         stream(Duration::from_millis(100), ticks, |count: &mut u32, ()| *count += 1),
+///     )
+/// }
+/// ```
+pub fn stream<State, Action, Key, F, S, H>(
+    key: Key,
+    make_stream: F,
+    on_item: H,
+) -> StreamView<Key, F, H, S::Item>
+where
+    Key: PartialEq + 'static,
+    F: Fn(&Key) -> S + 'static,
+    S: Stream + Send + 'static,
+    S::Item: Message,
+    H: Fn(&mut State, S::Item) -> Action + 'static,
+{
+    StreamView {
+        key,
+        make_stream,
+        on_item,
+        message: PhantomData,
+    }
+}
+
+/// A stream which yields `()` every `period`, starting immediately.
+///
+/// This takes the period by reference so that it can be passed directly to [`stream`],
+/// with the period as the key.
+/// It must be called within a Tokio runtime, which is the case for `make_stream`.
+pub fn ticks(period: &Duration) -> impl Stream<Item = ()> + Send + 'static {
+    futures::stream::unfold(tokio::time::interval(*period), |mut interval| async move {
+        interval.tick().await;
+        Some(((), interval))
+    })
+}
+
+/// The view type for [`stream`].
+pub struct StreamView<Key, F, H, M> {
+    key: Key,
+    make_stream: F,
+    on_item: H,
+    message: PhantomData<fn() -> M>,
+}
+
+impl<Key, F, H, M, S> StreamView<Key, F, H, M>
+where
+    F: Fn(&Key) -> S,
+    S: Stream<Item = M> + Send + 'static,
+    M: Message,
+{
+    fn subscribe(&self, ctx: &mut ViewCtx, generation: u64) -> JoinHandle<()> {
+        ctx.with_id(ViewId::new(generation), |ctx| {
+            let path: Arc<[ViewId]> = ctx.view_path().into();
+            let proxy = MessageProxy::<M>::new(ctx.proxy.clone(), path);
+            // Streams such as Tokio's timers need to be created within the runtime.
+            let _guard = ctx.runtime().enter();
+            let stream = (self.make_stream)(&self.key);
+            ctx.runtime().spawn(async move {
+                let mut stream = std::pin::pin!(stream);
+                while let Some(item) = stream.next().await {
+                    if proxy.message(item).is_err() {
+                        // The event loop has ended, so there's no point in polling further.
+                        break;
+                    }
+                }
+            })
+        })
+    }
+}
+
+#[doc(hidden)] // Implementation detail, public because of trait visibility rules
+pub struct StreamState {
+    generation: u64,
+    handle: JoinHandle<()>,
+}
+
+impl<Key, F, H, M> ViewMarker for StreamView<Key, F, H, M> {}
+impl<State, Action, Key, F, H, M, S> View<State, Action, ViewCtx> for StreamView<Key, F, H, M>
+where
+    Key: PartialEq + 'static,
+    F: Fn(&Key) -> S + 'static,
+    S: Stream<Item = M> + Send + 'static,
+    H: Fn(&mut State, M) -> Action + 'static,
+    M: Message,
+{
+    type Element = NoElement;
+
+    type ViewState = StreamState;
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        let generation = 0;
+        let handle = self.subscribe(ctx, generation);
+        (NoElement, StreamState { generation, handle })
+    }
+
+    fn rebuild<'el>(
+        &self,
+        prev: &Self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        (): Mut<'el, Self::Element>,
+    ) -> Mut<'el, Self::Element> {
+        if prev.key != self.key {
+            view_state.handle.abort();
+            view_state.generation += 1;
+            view_state.handle = self.subscribe(ctx, view_state.generation);
+        }
+    }
+
+    fn teardown(
+        &self,
+        view_state: &mut Self::ViewState,
+        _: &mut ViewCtx,
+        _: Mut<'_, Self::Element>,
+    ) {
+        view_state.handle.abort();
+    }
+
+    fn message(
+        &self,
+        view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: DynMessage,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        debug_assert_eq!(
+            id_path.len(),
+            1,
+            "id path should contain only the generation in StreamView::message"
+        );
+        if id_path[0].routing_id() != view_state.generation {
+            return MessageResult::Stale(message);
+        }
+        let item = message.downcast::<M>().unwrap();
+        MessageResult::Action((self.on_item)(app_state, *item))
+    }
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::*;
+    use crate::testing::TestApp;
+    use crate::view::button;
+    use crate::{WidgetView, Xilem};
+    use xilem_core::fork;
+
+    struct AppState {
+        key: u32,
+        subscribed: bool,
+        items: Vec<u32>,
+        /// A token held by the stream, to check when the subscription is dropped.
+        token: Arc<()>,
+    }
+
+    impl AppState {
+        fn new() -> Self {
+            AppState {
+                key: 1,
+                subscribed: true,
+                items: Vec::new(),
+                token: Arc::new(()),
+            }
+        }
+    }
+
+    fn app_logic(state: &mut AppState) -> impl WidgetView<AppState> {
+        let token = state.token.clone();
+        fork(
+            button("Next", |state: &mut AppState| {
+                state.key += 1;
+                state.subscribed = state.key < 3;
+            }),
+            state.subscribed.then(|| {
+                stream(
+                    state.key,
+                    move |key| {
+                        let token = token.clone();
+                        // Yields `key` and `key * 10`, then stays subscribed.
+                        futures::stream::iter([*key, *key * 10]).chain(futures::stream::unfold(
+                            token,
+                            |token| async move {
+                                let _token = token;
+                                std::future::pending::<Option<(u32, Arc<()>)>>().await
+                            },
+                        ))
+                    },
+                    |state: &mut AppState, item| state.items.push(item),
+                )
+            }),
+        )
+    }
+
+    fn click_next<State: 'static, Logic, View>(app: &mut TestApp<State, Logic, View>)
+    where
+        Logic: FnMut(&mut State) -> View,
+        View: WidgetView<State>,
+    {
+        let button_id = app.harness.root_widget().children()[0].id();
+        app.harness.mouse_click_on(button_id);
+        app.handle_actions();
+    }
+
+    #[test]
+    fn items_update_state() {
+        let mut app = TestApp::new(Xilem::new(AppState::new(), app_logic));
+        app.handle_async_message();
+        app.handle_async_message();
+        assert_eq!(app.state().items, [1, 10]);
+    }
+
+    #[test]
+    fn key_change_resubscribes() {
+        let mut app = TestApp::new(Xilem::new(AppState::new(), app_logic));
+        app.handle_async_message();
+        app.handle_async_message();
+
+        click_next(&mut app);
+        assert_eq!(app.state().key, 2);
+        app.handle_async_message();
+        app.handle_async_message();
+        assert_eq!(app.state().items, [1, 10, 2, 20]);
+    }
+
+    #[test]
+    fn teardown_drops_subscription() {
+        let mut app = TestApp::new(Xilem::new(AppState::new(), app_logic));
+        app.handle_async_message();
+        app.handle_async_message();
+        let token = app.state().token.clone();
+        // The state, this handle, the `make_stream` closure and the running stream.
+        assert_eq!(Arc::strong_count(&token), 4);
+
+        click_next(&mut app);
+        click_next(&mut app);
+        assert!(!app.state().subscribed);
+        // Aborted tasks are dropped by the runtime asynchronously.
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while Arc::strong_count(&token) > 2 {
+            assert!(
+                Instant::now() < deadline,
+                "the stream should be dropped on teardown"
+            );
+            std::thread::yield_now();
+        }
+    }
+}
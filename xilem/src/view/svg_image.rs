@@ -0,0 +1,104 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The SVG document widget.
+
+use masonry::widget::{self, FillStrat};
+use xilem_core::{Mut, ViewMarker};
+
+use crate::{MessageResult, Pod, View, ViewCtx, ViewId};
+
+pub use masonry::widget::{SvgData, SvgError};
+
+/// Displays the SVG document `data`, for example an icon.
+///
+/// By default, the document scales to fit its box constraints ([`FillStrat::Contain`]).
+/// To configure this, call [`fill`](SvgImage::fill) on the returned value.
+///
+/// Corresponds to the [`Svg`](widget::Svg) widget.
+///
+/// # Examples
+///
+/// ```
+/// use xilem::view::{svg_image, SvgData};
+/// use xilem::WidgetView;
+///
+/// const ICON: &str = r#"<svg xmlns="http://www.w3.org/2000/svg" width="16" height="16">
+///     <circle cx="8" cy="8" r="6" fill="tomato"/>
+/// </svg>"#;
+///
+/// struct App {
+///     icon: SvgData,
+/// }
+///
+/// fn app(state: &mut App) -> impl WidgetView<App> {
+///     svg_image(&state.icon)
+/// }
+///
+/// let state = App {
+///     icon: SvgData::from_bytes(ICON.as_bytes()).unwrap(),
+/// };
+/// ```
+pub fn svg_image(data: &SvgData) -> SvgImage {
+    SvgImage {
+        // SvgData is an `Arc`, and so is cheap to clone.
+        data: data.clone(),
+        fill: FillStrat::default(),
+    }
+}
+
+/// The [`View`] created by [`svg_image`].
+///
+/// See `svg_image`'s docs for more details.
+pub struct SvgImage {
+    data: SvgData,
+    fill: FillStrat,
+}
+
+impl SvgImage {
+    /// Specify the fill strategy.
+    pub fn fill(mut self, fill: FillStrat) -> Self {
+        self.fill = fill;
+        self
+    }
+}
+
+impl ViewMarker for SvgImage {}
+impl<State, Action> View<State, Action, ViewCtx> for SvgImage {
+    type Element = Pod<widget::Svg>;
+    type ViewState = ();
+
+    fn build(&self, _: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        let widget = widget::Svg::new(self.data.clone()).fill_mode(self.fill);
+        (Pod::new(widget), ())
+    }
+
+    fn rebuild<'el>(
+        &self,
+        prev: &Self,
+        (): &mut Self::ViewState,
+        _: &mut ViewCtx,
+        mut element: Mut<'el, Self::Element>,
+    ) -> Mut<'el, Self::Element> {
+        if prev.fill != self.fill {
+            element.set_fill_mode(self.fill);
+        }
+        if prev.data != self.data {
+            element.set_data(self.data.clone());
+        }
+        element
+    }
+
+    fn teardown(&self, (): &mut Self::ViewState, _: &mut ViewCtx, _: Mut<'_, Self::Element>) {}
+
+    fn message(
+        &self,
+        (): &mut Self::ViewState,
+        _: &[ViewId],
+        message: xilem_core::DynMessage,
+        _: &mut State,
+    ) -> MessageResult<Action> {
+        tracing::error!("Message arrived in SvgImage::message, but SvgImage doesn't consume any messages, this is a bug");
+        MessageResult::Stale(message)
+    }
+}
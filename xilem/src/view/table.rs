@@ -0,0 +1,702 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+use masonry::widget::{self, WidgetMut};
+use masonry::{theme, Widget, WidgetPod};
+use xilem_core::{
+    AppendVec, DynMessage, ElementSplice, MessageResult, Mut, View, ViewId, ViewMarker,
+    ViewPathTracker, ViewSequence,
+};
+
+use crate::{Pod, ViewCtx};
+
+pub use masonry::widget::{SortDirection, TableColumn};
+
+type SortCallback<State, Action> =
+    Box<dyn Fn(&mut State, usize, SortDirection) -> Action + Send + Sync + 'static>;
+type SelectCallback<K, State, Action> =
+    Box<dyn Fn(&mut State, &K) -> Action + Send + Sync + 'static>;
+type ResizeCallback<State, Action> =
+    Box<dyn Fn(&mut State, usize, f64) -> Action + Send + Sync + 'static>;
+type ReorderCallback<State, Action> =
+    Box<dyn Fn(&mut State, usize, usize) -> Action + Send + Sync + 'static>;
+
+/// A table with the given `columns`, showing `rows` of cells.
+///
+/// Each row has a key, which must be unique within the table. When the table is rebuilt,
+/// rows are matched up by their key, so that rows which are moved (for example, because the
+/// table was sorted) keep their widgets and state.
+///
+/// The table only builds widgets for the rows which are scrolled into view and a few rows
+/// around them, so it should be given a bounded height. Rows which are scrolled far enough
+/// out of view are torn down, and lose their state.
+///
+/// # Examples
+///
+/// ```
+/// use xilem::view::{label, table, table_row, SortDirection, TableColumn};
+/// use xilem::WidgetView;
+///
+/// struct File {
+///     id: u64,
+///     name: String,
+///     size: u64,
+/// }
+///
+/// struct Files {
+///     files: Vec<File>,
+///     sort: Option<(usize, SortDirection)>,
+///     selected: Option<u64>,
+/// }
+///
+/// fn files(state: &mut Files) -> impl WidgetView<Files> {
+///     let columns = vec![
+///         TableColumn::new("Name").width(200.).sortable(true),
+///         TableColumn::new("Size").sortable(true),
+///     ];
+///     let rows = state
+///         .files
+///         .iter()
+///         .map(|file| table_row(file.id, (label(file.name.clone()), label(format!("{} B", file.size)))))
+///         .collect();
+///     table(columns, rows)
+///         .sort(state.sort)
+///         .on_sort(|state: &mut Files, column, direction| {
+///             match column {
+///                 0 => state.files.sort_by(|a, b| a.name.cmp(&b.name)),
+///                 _ => state.files.sort_by_key(|file| file.size),
+///             }
+///             if direction == SortDirection::Descending {
+///                 state.files.reverse();
+///             }
+///             state.sort = Some((column, direction));
+///         })
+///         .selected(state.selected)
+///         .on_select(|state: &mut Files, id| state.selected = Some(*id))
+/// }
+/// ```
+pub fn table<K, State, Action, Seq>(
+    columns: Vec<TableColumn>,
+    rows: Vec<TableRow<K, Seq>>,
+) -> Table<K, Seq, State, Action>
+where
+    K: Hash + Eq + Clone + Send + Sync + 'static,
+    Seq: TableCells<State, Action>,
+{
+    Table {
+        columns,
+        rows,
+        row_height: None,
+        sort: None,
+        selected: None,
+        on_sort: None,
+        on_select: None,
+        on_resize: None,
+        on_reorder: None,
+        phantom: PhantomData,
+    }
+}
+
+/// A row of a [`Table`] view, with a key which identifies it and its `cells`.
+pub fn table_row<K, Seq>(key: K, cells: Seq) -> TableRow<K, Seq> {
+    TableRow { key, cells }
+}
+
+pub struct TableRow<K, Seq> {
+    key: K,
+    cells: Seq,
+}
+
+pub struct Table<K, Seq, State, Action = ()> {
+    columns: Vec<TableColumn>,
+    rows: Vec<TableRow<K, Seq>>,
+    row_height: Option<f64>,
+    sort: Option<(usize, SortDirection)>,
+    /// The key of the selected row, if the selection is controlled by the app.
+    selected: Option<Option<K>>,
+    on_sort: Option<SortCallback<State, Action>>,
+    on_select: Option<SelectCallback<K, State, Action>>,
+    on_resize: Option<ResizeCallback<State, Action>>,
+    on_reorder: Option<ReorderCallback<State, Action>>,
+    phantom: PhantomData<fn() -> (State, Action)>,
+}
+
+impl<K, Seq, State, Action> Table<K, Seq, State, Action> {
+    /// Set the height of all rows.
+    pub fn row_height(mut self, row_height: f64) -> Self {
+        self.row_height = Some(row_height);
+        self
+    }
+
+    /// Set the column the rows are sorted by, which is shown in its header.
+    pub fn sort(mut self, sort: Option<(usize, SortDirection)>) -> Self {
+        self.sort = sort;
+        self
+    }
+
+    /// Set the key of the selected row.
+    ///
+    /// If this isn't set, the table keeps track of the selected row itself.
+    pub fn selected(mut self, selected: Option<K>) -> Self {
+        self.selected = Some(selected);
+        self
+    }
+
+    /// Set the callback for when the user clicks the header of a sortable column.
+    ///
+    /// This should sort the rows, and update the state so that [`sort`](Self::sort) is set to
+    /// the column and direction.
+    pub fn on_sort<F>(mut self, on_sort: F) -> Self
+    where
+        F: Fn(&mut State, usize, SortDirection) -> Action + Send + Sync + 'static,
+    {
+        self.on_sort = Some(Box::new(on_sort));
+        self
+    }
+
+    /// Set the callback for when the user selects a row, which is called with its key.
+    pub fn on_select<F>(mut self, on_select: F) -> Self
+    where
+        F: Fn(&mut State, &K) -> Action + Send + Sync + 'static,
+    {
+        self.on_select = Some(Box::new(on_select));
+        self
+    }
+
+    /// Set the callback for when the user finishes resizing the column at the given index.
+    ///
+    /// The table keeps the new width until its columns are changed.
+    pub fn on_resize<F>(mut self, on_resize: F) -> Self
+    where
+        F: Fn(&mut State, usize, f64) -> Action + Send + Sync + 'static,
+    {
+        self.on_resize = Some(Box::new(on_resize));
+        self
+    }
+
+    /// Set the callback for when the user drags a column header from the first index to the
+    /// second one.
+    ///
+    /// Columns can only be reordered if this is set. The callback should reorder both the
+    /// columns and the cells of each row.
+    pub fn on_reorder<F>(mut self, on_reorder: F) -> Self
+    where
+        F: Fn(&mut State, usize, usize) -> Action + Send + Sync + 'static,
+    {
+        self.on_reorder = Some(Box::new(on_reorder));
+        self
+    }
+}
+
+/// The view state of a [`Table`].
+#[doc(hidden)] // Implementation detail, public because of trait visibility rules
+pub struct TableState<K, SeqState> {
+    /// The state of each row which is loaded in the widget, in order.
+    rows: Vec<RowState<K, SeqState>>,
+    /// The index of the first loaded row.
+    first_loaded: usize,
+    /// The key of the selected row, if the selection isn't controlled by the app.
+    selected: Option<K>,
+    /// The view id of the next row to be built.
+    next_id: u64,
+}
+
+struct RowState<K, SeqState> {
+    key: K,
+    id: ViewId,
+    cells: SeqState,
+}
+
+impl<K, Seq, State, Action> Table<K, Seq, State, Action>
+where
+    K: Hash + Eq + Clone + Send + Sync + 'static,
+    State: 'static,
+    Action: 'static,
+    Seq: TableCells<State, Action>,
+{
+    fn build_row(
+        &self,
+        row: &TableRow<K, Seq>,
+        next_id: &mut u64,
+        ctx: &mut ViewCtx,
+    ) -> (WidgetPod<widget::TableRow>, RowState<K, Seq::SeqState>) {
+        let id = ViewId::new(*next_id);
+        *next_id += 1;
+        let mut elements = AppendVec::default();
+        let cells = ctx.with_id(id, |ctx| row.cells.seq_build(ctx, &mut elements));
+        let widget =
+            widget::TableRow::new(elements.into_inner().into_iter().map(|cell| cell.inner));
+        let state = RowState {
+            key: row.key.clone(),
+            id,
+            cells,
+        };
+        (WidgetPod::new(widget), state)
+    }
+
+    fn selected_index(&self, selected: &Option<K>) -> Option<usize> {
+        let key = selected.as_ref()?;
+        self.rows.iter().position(|row| row.key == *key)
+    }
+}
+
+impl<K, Seq, State, Action> ViewMarker for Table<K, Seq, State, Action> {}
+impl<K, Seq, State, Action> View<State, Action, ViewCtx> for Table<K, Seq, State, Action>
+where
+    K: Hash + Eq + Clone + Send + Sync + 'static,
+    State: 'static,
+    Action: 'static,
+    Seq: TableCells<State, Action>,
+{
+    type Element = Pod<widget::Table>;
+
+    type ViewState = TableState<K, Seq::SeqState>;
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        let mut next_id = 0;
+        let mut widget = widget::Table::new(self.columns.iter().cloned())
+            .sort(self.sort)
+            .reorderable(self.on_reorder.is_some());
+        if let Some(row_height) = self.row_height {
+            widget = widget.row_height(row_height);
+        }
+        if let Some(selected) = &self.selected {
+            widget = widget.selected(self.selected_index(selected));
+        }
+        // A new table is scrolled to the top, and it requests more rows once it's laid out.
+        let loaded = widget.rows_to_load(self.rows.len());
+        debug_assert_eq!(loaded.start, 0);
+        let mut rows = Vec::with_capacity(loaded.len());
+        for row in &self.rows[loaded.clone()] {
+            let (pod, state) = self.build_row(row, &mut next_id, ctx);
+            widget = widget.with_row_pod(pod);
+            rows.push(state);
+        }
+        widget = widget.with_unloaded_rows(self.rows.len() - loaded.end);
+        debug_assert_eq!(
+            self.rows
+                .iter()
+                .map(|row| &row.key)
+                .collect::<HashSet<_>>()
+                .len(),
+            self.rows.len(),
+            "The keys of the rows of a table must be unique"
+        );
+        let pod = ctx.with_action_widget(|_| Pod::new(widget));
+        let state = TableState {
+            rows,
+            first_loaded: 0,
+            selected: None,
+            next_id,
+        };
+        (pod, state)
+    }
+
+    fn rebuild<'el>(
+        &self,
+        prev: &Self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<'el, Self::Element>,
+    ) -> Mut<'el, Self::Element> {
+        if prev.columns != self.columns {
+            element.set_columns(self.columns.iter().cloned());
+            ctx.mark_changed();
+        }
+        if prev.row_height != self.row_height {
+            element.set_row_height(self.row_height.unwrap_or(theme::BORDERED_WIDGET_HEIGHT));
+            ctx.mark_changed();
+        }
+        if prev.on_reorder.is_some() != self.on_reorder.is_some() {
+            element.set_reorderable(self.on_reorder.is_some());
+        }
+
+        // The index of each row, by its key.
+        let indices: HashMap<&K, usize> = self
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(idx, row)| (&row.key, idx))
+            .collect();
+        debug_assert_eq!(
+            indices.len(),
+            self.rows.len(),
+            "The keys of the rows of a table must be unique"
+        );
+        let loaded = element.widget.rows_to_load(self.rows.len());
+        let first = view_state.first_loaded;
+
+        // Pair each row state with the row of `prev` it was built or rebuilt from.
+        let mut current: Vec<_> = std::mem::take(&mut view_state.rows)
+            .into_iter()
+            .zip(prev.rows[first..].iter().map(Some))
+            .collect();
+
+        // Tear down the rows whose keys aren't used anymore, or which won't be loaded.
+        for pos in (0..current.len()).rev() {
+            let idx = indices.get(&current[pos].0.key);
+            if idx.is_some_and(|idx| loaded.contains(idx)) {
+                continue;
+            }
+            let (mut state, prev_row) = current.remove(pos);
+            {
+                let mut row = element.row_mut(first + pos);
+                let mut splice = CellSplice::new(row.reborrow_mut());
+                ctx.with_id(state.id, |ctx| {
+                    prev_row
+                        .unwrap()
+                        .cells
+                        .seq_teardown(&mut state.cells, ctx, &mut splice);
+                });
+            }
+            element.remove_row(first + pos);
+            ctx.mark_changed();
+        }
+
+        // Build the rows which aren't loaded yet after the others, which are all kept.
+        let kept: HashSet<&K> = current.iter().map(|(state, _)| &state.key).collect();
+        let new_rows: Vec<_> = self.rows[loaded.clone()]
+            .iter()
+            .filter(|row| !kept.contains(&row.key))
+            .collect();
+        for row in new_rows {
+            let (pod, state) = self.build_row(row, &mut view_state.next_id, ctx);
+            element.insert_row_pod(first + current.len(), pod);
+            // The new row doesn't need to be rebuilt.
+            current.push((state, None));
+            ctx.mark_changed();
+        }
+
+        // Swap each row into place, so that every row is moved at most once.
+        for pos in 0..current.len() {
+            loop {
+                let target = indices[&current[pos].0.key] - loaded.start;
+                if target == pos {
+                    break;
+                }
+                current.swap(pos, target);
+                element.swap_rows(first + pos, first + target);
+                ctx.mark_changed();
+            }
+        }
+        element.set_first_loaded_row(loaded.start);
+        element.set_row_count(self.rows.len());
+
+        for (idx, (state, prev_row)) in loaded.clone().zip(&mut current) {
+            let Some(prev_row) = prev_row else {
+                continue;
+            };
+            let mut row_mut = element.row_mut(idx);
+            let mut splice = CellSplice::new(row_mut.reborrow_mut());
+            ctx.with_id(state.id, |ctx| {
+                self.rows[idx].cells.seq_rebuild(
+                    &prev_row.cells,
+                    &mut state.cells,
+                    ctx,
+                    &mut splice,
+                );
+            });
+            debug_assert!(splice.scratch.is_empty());
+        }
+        view_state.rows = current.into_iter().map(|(state, _)| state).collect();
+        view_state.first_loaded = loaded.start;
+
+        // The widget selects rows when the user interacts with it, and the indices of rows
+        // change when they are moved, so compare to the widget.
+        let selected = match &self.selected {
+            Some(selected) => selected.as_ref(),
+            None => view_state.selected.as_ref(),
+        };
+        let selected = selected.and_then(|key| indices.get(key).copied());
+        if element.widget.selected_row() != selected {
+            element.set_selected(selected);
+            ctx.mark_changed();
+        }
+        // The widget also sorts itself, which the app might not have handled yet, so the sort
+        // is only set when the app changes it.
+        if prev.sort != self.sort {
+            element.set_sort(self.sort);
+            ctx.mark_changed();
+        }
+        element
+    }
+
+    fn teardown(
+        &self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<'_, Self::Element>,
+    ) {
+        for (idx, state) in (view_state.first_loaded..).zip(&mut view_state.rows) {
+            let mut row_mut = element.row_mut(idx);
+            let mut splice = CellSplice::new(row_mut.reborrow_mut());
+            ctx.with_id(state.id, |ctx| {
+                self.rows[idx]
+                    .cells
+                    .seq_teardown(&mut state.cells, ctx, &mut splice);
+            });
+        }
+        ctx.teardown_leaf(element);
+    }
+
+    fn message(
+        &self,
+        view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: DynMessage,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        if let Some((first, remainder)) = id_path.split_first() {
+            let Some(pos) = view_state.rows.iter().position(|row| row.id == *first) else {
+                // The row was removed.
+                return MessageResult::Stale(message);
+            };
+            return self.rows[view_state.first_loaded + pos].cells.seq_message(
+                &mut view_state.rows[pos].cells,
+                remainder,
+                message,
+                app_state,
+            );
+        }
+        let action = match message.downcast::<masonry::Action>() {
+            Ok(action) => action,
+            Err(message) => {
+                tracing::error!("Wrong message type in Table::message");
+                return MessageResult::Stale(message);
+            }
+        };
+        let result = match *action {
+            masonry::Action::TableSortChanged(column, direction) => self
+                .on_sort
+                .as_ref()
+                .map(|on_sort| on_sort(app_state, column, direction)),
+            masonry::Action::TableRowSelected(row) => match self.rows.get(row) {
+                Some(row) => {
+                    view_state.selected = Some(row.key.clone());
+                    self.on_select
+                        .as_ref()
+                        .map(|on_select| on_select(app_state, &row.key))
+                }
+                None => return MessageResult::Stale(action),
+            },
+            masonry::Action::TableRowsRequested(_) => return MessageResult::RequestRebuild,
+            masonry::Action::TableColumnResized(column, width) => self
+                .on_resize
+                .as_ref()
+                .map(|on_resize| on_resize(app_state, column, width)),
+            masonry::Action::TableColumnMoved(from, to) => self
+                .on_reorder
+                .as_ref()
+                .map(|on_reorder| on_reorder(app_state, from, to)),
+            _ => {
+                tracing::error!("Wrong action type in Table::message: {action:?}");
+                return MessageResult::Stale(action);
+            }
+        };
+        match result {
+            Some(action) => MessageResult::Action(action),
+            None => MessageResult::Nop,
+        }
+    }
+}
+
+struct CellSplice<'w> {
+    idx: usize,
+    element: WidgetMut<'w, widget::TableRow>,
+    scratch: AppendVec<Pod<Box<dyn Widget>>>,
+}
+
+impl<'w> CellSplice<'w> {
+    fn new(element: WidgetMut<'w, widget::TableRow>) -> Self {
+        Self {
+            idx: 0,
+            element,
+            scratch: AppendVec::default(),
+        }
+    }
+}
+
+impl ElementSplice<Pod<Box<dyn Widget>>> for CellSplice<'_> {
+    fn insert(&mut self, element: Pod<Box<dyn Widget>>) {
+        self.element.insert_cell_pod(self.idx, element.inner);
+        self.idx += 1;
+    }
+
+    fn with_scratch<R>(&mut self, f: impl FnOnce(&mut AppendVec<Pod<Box<dyn Widget>>>) -> R) -> R {
+        let mut scratch = std::mem::take(&mut self.scratch);
+        let ret = f(&mut scratch);
+        for element in scratch.drain() {
+            self.insert(element);
+        }
+        self.scratch = scratch;
+        ret
+    }
+
+    fn mutate<R>(&mut self, f: impl FnOnce(Mut<'_, Pod<Box<dyn Widget>>>) -> R) -> R {
+        let child = self.element.cell_mut(self.idx);
+        let ret = f(child);
+        self.idx += 1;
+        ret
+    }
+
+    fn delete<R>(&mut self, f: impl FnOnce(Mut<'_, Pod<Box<dyn Widget>>>) -> R) -> R {
+        let ret = {
+            let child = self.element.cell_mut(self.idx);
+            f(child)
+        };
+        self.element.remove_cell(self.idx);
+        ret
+    }
+
+    fn skip(&mut self, n: usize) {
+        self.idx += n;
+    }
+}
+
+/// An ordered sequence of widget views, for the cells of a [`table_row`].
+/// See [`ViewSequence`] for more technical details.
+pub trait TableCells<State, Action = ()>:
+    ViewSequence<State, Action, ViewCtx, Pod<Box<dyn Widget>>>
+{
+}
+
+impl<Seq, State, Action> TableCells<State, Action> for Seq where
+    Seq: ViewSequence<State, Action, ViewCtx, Pod<Box<dyn Widget>>>
+{
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use masonry::{PointerButton, Vec2};
+
+    use super::*;
+    use crate::testing::TestApp;
+    use crate::view::label;
+    use crate::{WidgetView, Xilem};
+
+    struct Files {
+        files: Vec<u32>,
+        sort: Option<(usize, SortDirection)>,
+        /// Whether sorting updates `sort`, as opposed to sorting in the background.
+        update_sort: bool,
+    }
+
+    fn app_logic(state: &mut Files) -> impl WidgetView<Files> {
+        let rows = state
+            .files
+            .iter()
+            .map(|file| table_row(*file, (label(format!("file{file}")),)))
+            .collect();
+        table(vec![TableColumn::new("Name").sortable(true)], rows)
+            .sort(state.sort)
+            .on_sort(|state: &mut Files, column, direction| {
+                state.files.sort();
+                if direction == SortDirection::Descending {
+                    state.files.reverse();
+                }
+                if state.update_sort {
+                    state.sort = Some((column, direction));
+                }
+            })
+    }
+
+    fn files(count: u32, update_sort: bool) -> Files {
+        Files {
+            files: (0..count).collect(),
+            sort: None,
+            update_sort,
+        }
+    }
+
+    /// The texts of the cells which are loaded.
+    fn file_labels(texts: Vec<String>) -> Vec<String> {
+        texts
+            .into_iter()
+            .filter(|text| text.starts_with("file"))
+            .collect()
+    }
+
+    fn click_header(harness: &mut masonry::testing::TestHarness) {
+        harness.mouse_move((20.0, 4.0));
+        harness.mouse_button_press(PointerButton::Primary);
+        harness.mouse_button_release(PointerButton::Primary);
+    }
+
+    #[test]
+    fn only_rows_in_view_are_built() {
+        let mut app = TestApp::new(Xilem::new(files(1000, true), app_logic));
+        // The table requests the rows which fit in it once it's laid out.
+        app.handle_actions();
+        let labels = file_labels(app.label_texts());
+        assert_eq!(labels[0], "file0");
+        assert!(labels.len() > 16 && labels.len() < 40);
+
+        app.harness.mouse_move((20.0, 100.0));
+        app.harness.mouse_wheel(Vec2::new(0.0, -200.0));
+        app.handle_actions();
+        let table = app.harness.root_widget().children()[0];
+        let table = table.downcast::<widget::Table>().unwrap();
+        let loaded = table.loaded_rows();
+        assert_eq!(table.row_count(), 1000);
+        assert!(loaded.start > 50);
+        let expected: Vec<_> = loaded.map(|idx| format!("file{idx}")).collect();
+        assert_eq!(file_labels(app.label_texts()), expected);
+    }
+
+    #[test]
+    fn sorting_keeps_row_widgets() {
+        let mut app = TestApp::new(Xilem::new(files(5, true), app_logic));
+        app.handle_actions();
+        let row_ids = |app: &TestApp<_, _, _>| {
+            let table = app.harness.root_widget().children()[0];
+            let ids: Vec<_> = table.children()[..5].iter().map(|row| row.id()).collect();
+            ids
+        };
+        let ids = row_ids(&app);
+        app.harness.mouse_click_on(ids[1]);
+        app.handle_actions();
+
+        // Sort ascending, which doesn't change the order, and then descending.
+        click_header(&mut app.harness);
+        app.handle_actions();
+        click_header(&mut app.harness);
+        app.handle_actions();
+        assert_eq!(app.state().sort, Some((0, SortDirection::Descending)));
+        assert_eq!(
+            file_labels(app.label_texts()),
+            ["file4", "file3", "file2", "file1", "file0"]
+        );
+        let mut reversed = row_ids(&app);
+        reversed.reverse();
+        assert_eq!(reversed, ids);
+        // The selection moves with the row.
+        let table = app.harness.root_widget().children()[0];
+        let table = table.downcast::<widget::Table>().unwrap();
+        assert_eq!(table.selected_row(), Some(3));
+    }
+
+    #[test]
+    fn sort_is_kept_until_app_sets_it() {
+        let mut app = TestApp::new(Xilem::new(files(5, false), app_logic));
+        app.handle_actions();
+
+        click_header(&mut app.harness);
+        app.handle_actions();
+        let sort_column = |app: &TestApp<_, _, _>| {
+            let table = app.harness.root_widget().children()[0];
+            table.downcast::<widget::Table>().unwrap().sort_column()
+        };
+        assert_eq!(app.state().sort, None);
+        assert_eq!(sort_column(&app), Some((0, SortDirection::Ascending)));
+
+        click_header(&mut app.harness);
+        app.handle_actions();
+        assert_eq!(sort_column(&app), Some((0, SortDirection::Descending)));
+        assert_eq!(file_labels(app.label_texts())[0], "file4");
+    }
+}
@@ -0,0 +1,557 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::marker::PhantomData;
+
+use masonry::{
+    widget::{self, WidgetMut},
+    ArcStr, Widget,
+};
+use xilem_core::{
+    AppendVec, DynMessage, ElementSplice, MessageResult, Mut, SuperElement, View, ViewElement,
+    ViewId, ViewMarker, ViewPathTracker, ViewSequence,
+};
+
+use crate::{Pod, ViewCtx, WidgetView};
+
+type SelectCallback<State, Action> =
+    Box<dyn Fn(&mut State, usize) -> Action + Send + Sync + 'static>;
+type ReorderCallback<State, Action> =
+    Box<dyn Fn(&mut State, usize, usize) -> Action + Send + Sync + 'static>;
+
+/// A container with a strip of tabs, which shows the page of the `selected` tab.
+///
+/// The tabs are a sequence of [`tab`]s. `on_select` is called with the index of the tab
+/// the user selected, and should update the state so that `selected` is that index.
+/// If `selected` is past the last tab, the last tab is selected.
+///
+/// Pages of tabs which aren't selected keep their widget state until they are shown again.
+///
+/// # Examples
+///
+/// ```
+/// use xilem::view::{label, tab, tabs};
+/// # use xilem::WidgetView;
+///
+/// struct Documents {
+///     names: Vec<String>,
+///     selected: usize,
+/// }
+///
+/// fn documents(state: &mut Documents) -> impl WidgetView<Documents> {
+///     let pages = state
+///         .names
+///         .iter()
+///         .map(|name| tab(name.clone(), label(format!("The contents of {name}"))).closable(true))
+///         .collect::<Vec<_>>();
+///     tabs(state.selected, pages, |state: &mut Documents, index| {
+///         state.selected = index;
+///     })
+///     .on_close(|state: &mut Documents, index| {
+///         state.names.remove(index);
+///         state.selected = state.selected.min(state.names.len().saturating_sub(1));
+///     })
+///     .on_reorder(|state: &mut Documents, from, to| {
+///         let name = state.names.remove(from);
+///         state.names.insert(to, name);
+///         state.selected = to;
+///     })
+/// }
+/// ```
+pub fn tabs<State, Action, Seq, F>(
+    selected: usize,
+    sequence: Seq,
+    on_select: F,
+) -> Tabs<Seq, State, Action>
+where
+    Seq: TabsSequence<State, Action>,
+    F: Fn(&mut State, usize) -> Action + Send + Sync + 'static,
+{
+    Tabs {
+        sequence,
+        selected,
+        on_select: Box::new(on_select),
+        on_close: None,
+        on_reorder: None,
+        phantom: PhantomData,
+    }
+}
+
+pub struct Tabs<Seq, State, Action = ()> {
+    sequence: Seq,
+    selected: usize,
+    on_select: SelectCallback<State, Action>,
+    on_close: Option<SelectCallback<State, Action>>,
+    on_reorder: Option<ReorderCallback<State, Action>>,
+    phantom: PhantomData<fn() -> (State, Action)>,
+}
+
+impl<Seq, State, Action> Tabs<Seq, State, Action> {
+    /// Set the callback for when the user closes the tab at the given index,
+    /// either using its close button or by middle-clicking it.
+    ///
+    /// Only tabs which are [`closable`](TabItem::closable) can be closed.
+    pub fn on_close<F>(mut self, on_close: F) -> Self
+    where
+        F: Fn(&mut State, usize) -> Action + Send + Sync + 'static,
+    {
+        self.on_close = Some(Box::new(on_close));
+        self
+    }
+
+    /// Set the callback for when the user drags a tab from the first index to the second one.
+    ///
+    /// Tabs can only be reordered if this is set.
+    pub fn on_reorder<F>(mut self, on_reorder: F) -> Self
+    where
+        F: Fn(&mut State, usize, usize) -> Action + Send + Sync + 'static,
+    {
+        self.on_reorder = Some(Box::new(on_reorder));
+        self
+    }
+}
+
+/// The view id of the sequence of tabs.
+///
+/// The `Tabs` widget itself uses the path of the `Tabs` view, so that its actions
+/// can be distinguished from those of the pages.
+const SEQUENCE_VIEW_ID: ViewId = ViewId::new(0);
+
+impl<Seq, State, Action> ViewMarker for Tabs<Seq, State, Action> {}
+impl<State, Action, Seq> View<State, Action, ViewCtx> for Tabs<Seq, State, Action>
+where
+    State: 'static,
+    Action: 'static,
+    Seq: TabsSequence<State, Action>,
+{
+    type Element = Pod<widget::Tabs>;
+
+    type ViewState = Seq::SeqState;
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        let mut elements = AppendVec::default();
+        let seq_state = ctx.with_id(SEQUENCE_VIEW_ID, |ctx| {
+            self.sequence.seq_build(ctx, &mut elements)
+        });
+        let elements = elements.into_inner();
+        let selected = self.selected.min(elements.len().saturating_sub(1));
+        let mut widget = widget::Tabs::new().reorderable(self.on_reorder.is_some());
+        for element in elements {
+            widget = widget.with_tab_pod(element.title, element.page.inner, element.closable);
+        }
+        let widget = widget.selected(selected);
+        let pod = ctx.with_action_widget(|_| Pod::new(widget));
+        (pod, seq_state)
+    }
+
+    fn rebuild<'el>(
+        &self,
+        prev: &Self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<'el, Self::Element>,
+    ) -> Mut<'el, Self::Element> {
+        if prev.on_reorder.is_some() != self.on_reorder.is_some() {
+            element.set_reorderable(self.on_reorder.is_some());
+        }
+        let mut splice = TabsSplice::new(element);
+        ctx.with_id(SEQUENCE_VIEW_ID, |ctx| {
+            self.sequence
+                .seq_rebuild(&prev.sequence, view_state, ctx, &mut splice);
+        });
+        debug_assert!(splice.scratch.is_empty());
+        let mut element = splice.element;
+        // The widget selects tabs itself when the user clicks on them, and keeps the same
+        // page selected when tabs are added or removed, so compare to the widget.
+        let selected = self
+            .selected
+            .min(element.widget.tab_count().saturating_sub(1));
+        if element.widget.selected_index() != selected {
+            element.set_selected(selected);
+            ctx.mark_changed();
+        }
+        element
+    }
+
+    fn teardown(
+        &self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        element: Mut<'_, Self::Element>,
+    ) {
+        let mut splice = TabsSplice::new(element);
+        ctx.with_id(SEQUENCE_VIEW_ID, |ctx| {
+            self.sequence.seq_teardown(view_state, ctx, &mut splice);
+        });
+        debug_assert!(splice.scratch.into_inner().is_empty());
+        ctx.teardown_leaf(splice.element);
+    }
+
+    fn message(
+        &self,
+        view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: DynMessage,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        if let Some((first, remainder)) = id_path.split_first() {
+            if *first != SEQUENCE_VIEW_ID {
+                tracing::error!("Invalid id path in Tabs::message: {id_path:?}");
+                return MessageResult::Stale(message);
+            }
+            return self
+                .sequence
+                .seq_message(view_state, remainder, message, app_state);
+        }
+        match message.downcast::<masonry::Action>() {
+            Ok(action) => match *action {
+                masonry::Action::TabSelected(index) => {
+                    MessageResult::Action((self.on_select)(app_state, index))
+                }
+                masonry::Action::TabCloseRequested(index) if self.on_close.is_some() => {
+                    MessageResult::Action((self.on_close.as_ref().unwrap())(app_state, index))
+                }
+                masonry::Action::TabMoved(from, to) if self.on_reorder.is_some() => {
+                    MessageResult::Action((self.on_reorder.as_ref().unwrap())(app_state, from, to))
+                }
+                masonry::Action::TabCloseRequested(_) => {
+                    tracing::error!("Tabs::message: on_close is not set");
+                    MessageResult::Stale(action)
+                }
+                _ => {
+                    tracing::error!("Wrong action type in Tabs::message: {action:?}");
+                    MessageResult::Stale(action)
+                }
+            },
+            Err(message) => {
+                tracing::error!("Wrong message type in Tabs::message");
+                MessageResult::Stale(message)
+            }
+        }
+    }
+}
+
+/// A tab of a [`Tabs`] view, with its title and page.
+pub struct TabElement {
+    page: Pod<Box<dyn Widget>>,
+    title: ArcStr,
+    closable: bool,
+}
+
+pub struct TabElementMut<'w> {
+    parent: WidgetMut<'w, widget::Tabs>,
+    idx: usize,
+}
+
+struct TabsSplice<'w> {
+    idx: usize,
+    element: WidgetMut<'w, widget::Tabs>,
+    scratch: AppendVec<TabElement>,
+}
+
+impl<'w> TabsSplice<'w> {
+    fn new(element: WidgetMut<'w, widget::Tabs>) -> Self {
+        Self {
+            idx: 0,
+            element,
+            scratch: AppendVec::default(),
+        }
+    }
+
+    fn insert_element(&mut self, element: TabElement) {
+        self.element.insert_tab_pod(
+            self.idx,
+            element.title,
+            element.page.inner,
+            element.closable,
+        );
+        self.idx += 1;
+    }
+}
+
+impl ViewElement for TabElement {
+    type Mut<'w> = TabElementMut<'w>;
+}
+
+impl SuperElement<TabElement> for TabElement {
+    fn upcast(child: TabElement) -> Self {
+        child
+    }
+
+    fn with_downcast_val<R>(
+        mut this: Mut<'_, Self>,
+        f: impl FnOnce(Mut<'_, TabElement>) -> R,
+    ) -> (Self::Mut<'_>, R) {
+        let r = {
+            let parent = this.parent.reborrow_mut();
+            let reborrow = TabElementMut {
+                idx: this.idx,
+                parent,
+            };
+            f(reborrow)
+        };
+        (this, r)
+    }
+}
+
+impl ElementSplice<TabElement> for TabsSplice<'_> {
+    fn insert(&mut self, element: TabElement) {
+        self.insert_element(element);
+    }
+
+    fn with_scratch<R>(&mut self, f: impl FnOnce(&mut AppendVec<TabElement>) -> R) -> R {
+        let mut scratch = std::mem::take(&mut self.scratch);
+        let ret = f(&mut scratch);
+        for element in scratch.drain() {
+            self.insert_element(element);
+        }
+        self.scratch = scratch;
+        ret
+    }
+
+    fn mutate<R>(&mut self, f: impl FnOnce(Mut<'_, TabElement>) -> R) -> R {
+        let child = TabElementMut {
+            parent: self.element.reborrow_mut(),
+            idx: self.idx,
+        };
+        let ret = f(child);
+        self.idx += 1;
+        ret
+    }
+
+    fn delete<R>(&mut self, f: impl FnOnce(Mut<'_, TabElement>) -> R) -> R {
+        let ret = {
+            let child = TabElementMut {
+                parent: self.element.reborrow_mut(),
+                idx: self.idx,
+            };
+            f(child)
+        };
+        self.element.remove_tab(self.idx);
+        ret
+    }
+
+    fn skip(&mut self, n: usize) {
+        self.idx += n;
+    }
+}
+
+/// An ordered sequence of [`tab`]s for a [`Tabs`] view.
+/// See [`ViewSequence`] for more technical details.
+pub trait TabsSequence<State, Action = ()>:
+    ViewSequence<State, Action, ViewCtx, TabElement>
+{
+}
+
+impl<Seq, State, Action> TabsSequence<State, Action> for Seq where
+    Seq: ViewSequence<State, Action, ViewCtx, TabElement>
+{
+}
+
+/// A tab with a `title`, which shows `page` when it's selected, for use in a [`Tabs`] view.
+pub fn tab<V, State, Action>(title: impl Into<ArcStr>, page: V) -> TabItem<V, State, Action>
+where
+    State: 'static,
+    Action: 'static,
+    V: WidgetView<State, Action>,
+{
+    TabItem {
+        title: title.into(),
+        page,
+        closable: false,
+        phantom: PhantomData,
+    }
+}
+
+pub struct TabItem<V, State, Action> {
+    title: ArcStr,
+    page: V,
+    closable: bool,
+    phantom: PhantomData<fn() -> (State, Action)>,
+}
+
+impl<V, State, Action> TabItem<V, State, Action> {
+    /// Set whether the tab has a close button, see [`Tabs::on_close`].
+    pub fn closable(mut self, closable: bool) -> Self {
+        self.closable = closable;
+        self
+    }
+}
+
+impl<V, State, Action> ViewMarker for TabItem<V, State, Action> {}
+impl<State, Action, V> View<State, Action, ViewCtx> for TabItem<V, State, Action>
+where
+    State: 'static,
+    Action: 'static,
+    V: WidgetView<State, Action>,
+{
+    type Element = TabElement;
+
+    type ViewState = V::ViewState;
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        let (pod, state) = self.page.build(ctx);
+        let element = TabElement {
+            page: pod.inner.boxed().into(),
+            title: self.title.clone(),
+            closable: self.closable,
+        };
+        (element, state)
+    }
+
+    fn rebuild<'el>(
+        &self,
+        prev: &Self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<'el, Self::Element>,
+    ) -> Mut<'el, Self::Element> {
+        {
+            if prev.title != self.title {
+                element.parent.set_title(element.idx, self.title.clone());
+                ctx.mark_changed();
+            }
+            if prev.closable != self.closable {
+                element.parent.set_closable(element.idx, self.closable);
+                ctx.mark_changed();
+            }
+            let mut panel = element.parent.page_mut(element.idx);
+            let mut page = panel.child_mut();
+            self.page
+                .rebuild(&prev.page, view_state, ctx, page.downcast());
+        }
+        element
+    }
+
+    fn teardown(
+        &self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<'_, Self::Element>,
+    ) {
+        let mut panel = element.parent.page_mut(element.idx);
+        let mut page = panel.child_mut();
+        self.page.teardown(view_state, ctx, page.downcast());
+    }
+
+    fn message(
+        &self,
+        view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: DynMessage,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        self.page.message(view_state, id_path, message, app_state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use masonry::testing::TestHarness;
+    use masonry::widget::Label;
+    use masonry::{PointerButton, WidgetId};
+
+    use super::*;
+    use crate::testing::TestApp;
+    use crate::view::label;
+    use crate::Xilem;
+
+    struct Documents {
+        names: Vec<String>,
+        selected: usize,
+    }
+
+    fn app_logic(state: &mut Documents) -> impl WidgetView<Documents> {
+        let pages = state
+            .names
+            .iter()
+            .map(|name| tab(name.clone(), label(format!("Page {name}"))).closable(true))
+            .collect::<Vec<_>>();
+        tabs(state.selected, pages, |state: &mut Documents, index| {
+            state.selected = index;
+        })
+        // The selected index isn't updated, so it can be past the last tab.
+        .on_close(|state: &mut Documents, index| {
+            state.names.remove(index);
+        })
+        .on_reorder(|state: &mut Documents, from, to| {
+            let name = state.names.remove(from);
+            state.names.insert(to, name);
+            state.selected = to;
+        })
+    }
+
+    fn documents(names: &[&str]) -> Documents {
+        Documents {
+            names: names.iter().map(|name| name.to_string()).collect(),
+            selected: 0,
+        }
+    }
+
+    fn tab_ids(harness: &TestHarness) -> Vec<WidgetId> {
+        let tabs = harness.root_widget().children()[0];
+        let strip = tabs.children()[0];
+        strip.children().iter().map(|tab| tab.id()).collect()
+    }
+
+    fn selected_index(harness: &TestHarness) -> usize {
+        harness.root_widget().children()[0]
+            .downcast::<widget::Tabs>()
+            .unwrap()
+            .selected_index()
+    }
+
+    /// The text of the selected page.
+    fn selected_page(harness: &TestHarness) -> String {
+        let tabs = harness.root_widget().children()[0];
+        let panel = tabs.children()[1 + selected_index(harness)];
+        let page = panel.children()[0];
+        page.downcast::<Label>().unwrap().text().to_string()
+    }
+
+    #[test]
+    fn select_and_close_tabs() {
+        let mut app = TestApp::new(Xilem::new(documents(&["a", "b", "c"]), app_logic));
+        assert_eq!(selected_page(&app.harness), "Page a");
+
+        app.harness.mouse_click_on(tab_ids(&app.harness)[2]);
+        app.handle_actions();
+        assert_eq!(app.state().selected, 2);
+        assert_eq!(selected_index(&app.harness), 2);
+        assert_eq!(selected_page(&app.harness), "Page c");
+
+        // Closing the selected last tab leaves a stale index in the state.
+        app.harness.mouse_move_to(tab_ids(&app.harness)[2]);
+        app.harness.mouse_button_press(PointerButton::Auxiliary);
+        app.harness.mouse_button_release(PointerButton::Auxiliary);
+        app.handle_actions();
+        assert_eq!(app.state().names, ["a", "b"]);
+        assert_eq!(app.state().selected, 2);
+        assert_eq!(tab_ids(&app.harness).len(), 2);
+        assert_eq!(selected_index(&app.harness), 1);
+        assert_eq!(selected_page(&app.harness), "Page b");
+
+        app.harness.mouse_click_on(tab_ids(&app.harness)[0]);
+        app.handle_actions();
+        assert_eq!(app.state().selected, 0);
+        assert_eq!(selected_index(&app.harness), 0);
+        assert_eq!(selected_page(&app.harness), "Page a");
+    }
+
+    #[test]
+    fn reorder_tabs() {
+        let mut app = TestApp::new(Xilem::new(documents(&["a", "b", "c"]), app_logic));
+        let tabs = tab_ids(&app.harness);
+
+        // Drag the first tab onto the last one.
+        app.harness.mouse_move_to(tabs[0]);
+        app.harness.mouse_button_press(PointerButton::Primary);
+        app.harness.mouse_move_to(tabs[2]);
+        app.harness.mouse_button_release(PointerButton::Primary);
+        app.handle_actions();
+        assert_eq!(app.state().names, ["b", "c", "a"]);
+        assert_eq!(app.state().selected, 2);
+        assert_eq!(selected_index(&app.harness), 2);
+        assert_eq!(selected_page(&app.harness), "Page a");
+    }
+}
@@ -92,7 +92,9 @@ impl<State: 'static, Action: 'static> View<State, Action, ViewCtx> for Textbox<S
         // without calling `set_text`.
 
         // This is probably not the right behaviour, but determining what is the right behaviour is hard
-        if self.contents != element.text() {
+        // The text being composed with an input method isn't part of the contents yet,
+        // so we wait until it is committed or cancelled.
+        if !element.is_composing() && self.contents != element.text() {
             element.reset_text(self.contents.clone());
             ctx.mark_changed();
         }
@@ -0,0 +1,636 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::marker::PhantomData;
+
+use masonry::widget::{self, WidgetMut};
+use xilem_core::{
+    AppendVec, DynMessage, ElementSplice, MessageResult, Mut, SuperElement, View, ViewId,
+    ViewMarker, ViewPathTracker, ViewSequence,
+};
+
+use crate::{AnyWidgetView, Pod, ViewCtx, WidgetView};
+
+type ToggleCallback<State, Action> =
+    Box<dyn Fn(&mut State, bool) -> Action + Send + Sync + 'static>;
+
+/// A tree of [`tree_item`]s, which the user can expand, collapse and select.
+///
+/// This is typically built from recursive data, with a function which returns the item
+/// for one node and calls itself for the node's children.
+///
+/// # Examples
+///
+/// ```
+/// use xilem::view::{label, tree, tree_item, AnyTreeItem};
+/// use xilem::WidgetView;
+///
+/// struct Entry {
+///     name: String,
+///     /// `None` for files.
+///     entries: Option<Vec<Entry>>,
+/// }
+///
+/// struct Files {
+///     root: Vec<Entry>,
+///     selected: Option<String>,
+/// }
+///
+/// fn entry_view(entry: &Entry) -> AnyTreeItem<Files> {
+///     let name = entry.name.clone();
+///     let children = entry.entries.iter().flatten().map(entry_view).collect();
+///     tree_item(label(entry.name.clone()).boxed(), children)
+///         .expandable(entry.entries.is_some())
+///         .on_select(move |state: &mut Files, selected| {
+///             if selected {
+///                 state.selected = Some(name.clone());
+///             }
+///         })
+///         .into()
+/// }
+///
+/// fn files(state: &mut Files) -> impl WidgetView<Files> {
+///     tree(state.root.iter().map(entry_view).collect::<Vec<_>>())
+/// }
+/// ```
+pub fn tree<State, Action, Seq>(sequence: Seq) -> Tree<Seq, State, Action>
+where
+    Seq: TreeSequence<State, Action>,
+{
+    Tree {
+        sequence,
+        multi_select: false,
+        phantom: PhantomData,
+    }
+}
+
+pub struct Tree<Seq, State, Action = ()> {
+    sequence: Seq,
+    multi_select: bool,
+    phantom: PhantomData<fn() -> (State, Action)>,
+}
+
+impl<Seq, State, Action> Tree<Seq, State, Action> {
+    /// Set whether several items can be selected at once.
+    pub fn multi_select(mut self, multi_select: bool) -> Self {
+        self.multi_select = multi_select;
+        self
+    }
+}
+
+impl<Seq, State, Action> ViewMarker for Tree<Seq, State, Action> {}
+impl<State, Action, Seq> View<State, Action, ViewCtx> for Tree<Seq, State, Action>
+where
+    State: 'static,
+    Action: 'static,
+    Seq: TreeSequence<State, Action>,
+{
+    type Element = Pod<widget::Tree>;
+
+    type ViewState = Seq::SeqState;
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        let mut elements = AppendVec::default();
+        let seq_state = self.sequence.seq_build(ctx, &mut elements);
+        let mut widget = widget::Tree::new().multi_select(self.multi_select);
+        for element in elements.into_inner() {
+            widget = widget.with_item_pod(element.inner);
+        }
+        (Pod::new(widget), seq_state)
+    }
+
+    fn rebuild<'el>(
+        &self,
+        prev: &Self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<'el, Self::Element>,
+    ) -> Mut<'el, Self::Element> {
+        if prev.multi_select != self.multi_select {
+            element.set_multi_select(self.multi_select);
+            ctx.mark_changed();
+        }
+        let mut splice = TreeSplice::new(element);
+        self.sequence
+            .seq_rebuild(&prev.sequence, view_state, ctx, &mut splice);
+        debug_assert!(splice.scratch.is_empty());
+        splice.element
+    }
+
+    fn teardown(
+        &self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        element: Mut<'_, Self::Element>,
+    ) {
+        let mut splice = TreeSplice::new(element);
+        self.sequence.seq_teardown(view_state, ctx, &mut splice);
+        debug_assert!(splice.scratch.into_inner().is_empty());
+    }
+
+    fn message(
+        &self,
+        view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: DynMessage,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        self.sequence
+            .seq_message(view_state, id_path, message, app_state)
+    }
+}
+
+/// An item of a [`Tree`] view, showing `content`, with the items of `children` below it
+/// when it's expanded.
+///
+/// The children are only built while the item is expanded, so that large or expensive
+/// trees (such as a file system) can be loaded lazily.
+/// For this reason, the item is expandable even if `children` is empty; use
+/// [`expandable`](TreeItem::expandable) or [`tree_leaf`] for items which can't have children.
+///
+/// Unless [`expanded`](TreeItem::expanded) or [`selected`](TreeItem::selected) are set,
+/// the item keeps track of whether it's expanded and selected itself.
+pub fn tree_item<State, Action, V, Seq>(
+    content: V,
+    children: Seq,
+) -> TreeItem<V, Seq, State, Action>
+where
+    V: WidgetView<State, Action>,
+    Seq: TreeSequence<State, Action>,
+{
+    TreeItem {
+        content,
+        children,
+        expandable: true,
+        expanded: None,
+        selected: None,
+        on_expand: None,
+        on_select: None,
+        phantom: PhantomData,
+    }
+}
+
+/// An item of a [`Tree`] view without children.
+pub fn tree_leaf<State, Action, V>(content: V) -> TreeItem<V, (), State, Action>
+where
+    V: WidgetView<State, Action>,
+{
+    tree_item(content, ()).expandable(false)
+}
+
+pub struct TreeItem<V, Seq, State, Action = ()> {
+    content: V,
+    children: Seq,
+    expandable: bool,
+    expanded: Option<bool>,
+    selected: Option<bool>,
+    on_expand: Option<ToggleCallback<State, Action>>,
+    on_select: Option<ToggleCallback<State, Action>>,
+    phantom: PhantomData<fn() -> (State, Action)>,
+}
+
+impl<V, Seq, State, Action> TreeItem<V, Seq, State, Action> {
+    /// Set whether the item shows a disclosure triangle, and can be expanded.
+    pub fn expandable(mut self, expandable: bool) -> Self {
+        self.expandable = expandable;
+        self
+    }
+
+    /// Set whether the item is expanded.
+    ///
+    /// When this is set, the item goes back to this state on every rebuild,
+    /// so [`on_expand`](Self::on_expand) should update the app state to match.
+    pub fn expanded(mut self, expanded: bool) -> Self {
+        self.expanded = Some(expanded);
+        self
+    }
+
+    /// Set whether the item is selected.
+    ///
+    /// When this is set, the item goes back to this state on every rebuild,
+    /// so [`on_select`](Self::on_select) should update the app state to match.
+    pub fn selected(mut self, selected: bool) -> Self {
+        self.selected = Some(selected);
+        self
+    }
+
+    /// Set the callback for when the user expands (`true`) or collapses (`false`) the item.
+    pub fn on_expand<F>(mut self, on_expand: F) -> Self
+    where
+        F: Fn(&mut State, bool) -> Action + Send + Sync + 'static,
+    {
+        self.on_expand = Some(Box::new(on_expand));
+        self
+    }
+
+    /// Set the callback for when the user selects (`true`) or deselects (`false`) the item.
+    pub fn on_select<F>(mut self, on_select: F) -> Self
+    where
+        F: Fn(&mut State, bool) -> Action + Send + Sync + 'static,
+    {
+        self.on_select = Some(Box::new(on_select));
+        self
+    }
+}
+
+/// The view state of a [`TreeItem`].
+#[doc(hidden)] // Implementation detail, public because of trait visibility rules
+pub struct TreeItemState<ContentState, ChildrenState> {
+    content: ContentState,
+    /// The state of the children, if the item is expanded.
+    children: Option<ChildrenState>,
+}
+
+const CONTENT_VIEW_ID: ViewId = ViewId::new(0);
+const CHILDREN_VIEW_ID: ViewId = ViewId::new(1);
+
+impl<V, Seq, State, Action> TreeItem<V, Seq, State, Action>
+where
+    State: 'static,
+    Action: 'static,
+    V: WidgetView<State, Action>,
+    Seq: TreeSequence<State, Action>,
+{
+    /// Build, rebuild or tear down the children, depending on whether the item is expanded.
+    fn update_children(
+        &self,
+        prev: Option<&Self>,
+        children: &mut Option<Seq::SeqState>,
+        ctx: &mut ViewCtx,
+        element: WidgetMut<'_, widget::TreeItem>,
+    ) {
+        let expanded = element.widget.is_expanded();
+        let mut splice = TreeSplice::new(element);
+        ctx.with_id(CHILDREN_VIEW_ID, |ctx| match (children.as_mut(), prev) {
+            (Some(seq_state), Some(prev)) if expanded => {
+                self.children
+                    .seq_rebuild(&prev.children, seq_state, ctx, &mut splice);
+            }
+            (Some(seq_state), Some(prev)) => {
+                prev.children.seq_teardown(seq_state, ctx, &mut splice);
+                *children = None;
+            }
+            (None, _) if expanded => {
+                let mut elements = AppendVec::default();
+                *children = Some(self.children.seq_build(ctx, &mut elements));
+                for element in elements.into_inner() {
+                    splice.insert(element);
+                }
+            }
+            _ => {}
+        });
+        debug_assert!(splice.scratch.is_empty());
+    }
+}
+
+impl<V, Seq, State, Action> ViewMarker for TreeItem<V, Seq, State, Action> {}
+impl<State, Action, V, Seq> View<State, Action, ViewCtx> for TreeItem<V, Seq, State, Action>
+where
+    State: 'static,
+    Action: 'static,
+    V: WidgetView<State, Action>,
+    Seq: TreeSequence<State, Action>,
+{
+    type Element = Pod<widget::TreeItem>;
+
+    type ViewState = TreeItemState<V::ViewState, Seq::SeqState>;
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        let (content, content_state) = ctx.with_id(CONTENT_VIEW_ID, |ctx| self.content.build(ctx));
+        let expanded = self.expandable && self.expanded.unwrap_or(false);
+        let mut widget = widget::TreeItem::from_content_pod(content.inner.boxed())
+            .expandable(self.expandable)
+            .expanded(expanded)
+            .selected(self.selected.unwrap_or(false));
+        let mut children = None;
+        if expanded {
+            let mut elements = AppendVec::default();
+            let seq_state = ctx.with_id(CHILDREN_VIEW_ID, |ctx| {
+                self.children.seq_build(ctx, &mut elements)
+            });
+            for element in elements.into_inner() {
+                widget = widget.with_child_pod(element.inner);
+            }
+            children = Some(seq_state);
+        }
+        let pod = ctx.with_action_widget(|_| Pod::new(widget));
+        let state = TreeItemState {
+            content: content_state,
+            children,
+        };
+        (pod, state)
+    }
+
+    fn rebuild<'el>(
+        &self,
+        prev: &Self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<'el, Self::Element>,
+    ) -> Mut<'el, Self::Element> {
+        if prev.expandable != self.expandable {
+            element.set_expandable(self.expandable);
+            ctx.mark_changed();
+        }
+        // The widget expands and selects itself when the user interacts with it,
+        // so compare to the widget.
+        let expanded = self
+            .expanded
+            .map(|expanded| expanded && self.expandable)
+            .unwrap_or(element.widget.is_expanded() && self.expandable);
+        if element.widget.is_expanded() != expanded {
+            element.set_expanded(expanded);
+            ctx.mark_changed();
+        }
+        if let Some(selected) = self.selected {
+            if element.widget.is_selected() != selected {
+                element.set_selected(selected);
+                ctx.mark_changed();
+            }
+        }
+        ctx.with_id(CONTENT_VIEW_ID, |ctx| {
+            let mut content = element.content_mut();
+            self.content.rebuild(
+                &prev.content,
+                &mut view_state.content,
+                ctx,
+                content.downcast(),
+            );
+        });
+        self.update_children(
+            Some(prev),
+            &mut view_state.children,
+            ctx,
+            element.reborrow_mut(),
+        );
+        element
+    }
+
+    fn teardown(
+        &self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<'_, Self::Element>,
+    ) {
+        ctx.with_id(CONTENT_VIEW_ID, |ctx| {
+            let mut content = element.content_mut();
+            self.content
+                .teardown(&mut view_state.content, ctx, content.downcast());
+        });
+        if let Some(seq_state) = &mut view_state.children {
+            let mut splice = TreeSplice::new(element.reborrow_mut());
+            ctx.with_id(CHILDREN_VIEW_ID, |ctx| {
+                self.children.seq_teardown(seq_state, ctx, &mut splice);
+            });
+            debug_assert!(splice.scratch.into_inner().is_empty());
+        }
+        ctx.teardown_leaf(element);
+    }
+
+    fn message(
+        &self,
+        view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: DynMessage,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        match id_path.split_first() {
+            Some((&CONTENT_VIEW_ID, remainder)) => {
+                return self.content.message(
+                    &mut view_state.content,
+                    remainder,
+                    message,
+                    app_state,
+                );
+            }
+            Some((&CHILDREN_VIEW_ID, remainder)) => {
+                let Some(seq_state) = &mut view_state.children else {
+                    // The item was collapsed, and its children torn down.
+                    return MessageResult::Stale(message);
+                };
+                return self
+                    .children
+                    .seq_message(seq_state, remainder, message, app_state);
+            }
+            Some(_) => {
+                tracing::error!("Invalid id path in TreeItem::message: {id_path:?}");
+                return MessageResult::Stale(message);
+            }
+            None => {}
+        }
+        match message.downcast::<masonry::Action>() {
+            Ok(action) => match *action {
+                masonry::Action::TreeItemExpanded(expanded) => match &self.on_expand {
+                    Some(on_expand) => MessageResult::Action(on_expand(app_state, expanded)),
+                    // The children still need to be built or torn down.
+                    None => MessageResult::RequestRebuild,
+                },
+                masonry::Action::TreeItemSelected(selected) => match &self.on_select {
+                    Some(on_select) => MessageResult::Action(on_select(app_state, selected)),
+                    None => MessageResult::Nop,
+                },
+                _ => {
+                    tracing::error!("Wrong action type in TreeItem::message: {action:?}");
+                    MessageResult::Stale(action)
+                }
+            },
+            Err(message) => {
+                tracing::error!("Wrong message type in TreeItem::message");
+                MessageResult::Stale(message)
+            }
+        }
+    }
+}
+
+/// A widget which has [`widget::TreeItem`]s as children.
+trait TreeParent: masonry::Widget + Sized {
+    fn insert_item(this: &mut WidgetMut<'_, Self>, idx: usize, item: Pod<widget::TreeItem>);
+    fn remove_item(this: &mut WidgetMut<'_, Self>, idx: usize);
+    fn item_mut<'a>(
+        this: &'a mut WidgetMut<'_, Self>,
+        idx: usize,
+    ) -> WidgetMut<'a, widget::TreeItem>;
+}
+
+impl TreeParent for widget::Tree {
+    fn insert_item(this: &mut WidgetMut<'_, Self>, idx: usize, item: Pod<widget::TreeItem>) {
+        this.insert_item_pod(idx, item.inner);
+    }
+
+    fn remove_item(this: &mut WidgetMut<'_, Self>, idx: usize) {
+        this.remove_item(idx);
+    }
+
+    fn item_mut<'a>(
+        this: &'a mut WidgetMut<'_, Self>,
+        idx: usize,
+    ) -> WidgetMut<'a, widget::TreeItem> {
+        this.item_mut(idx)
+    }
+}
+
+impl TreeParent for widget::TreeItem {
+    fn insert_item(this: &mut WidgetMut<'_, Self>, idx: usize, item: Pod<widget::TreeItem>) {
+        this.insert_child_pod(idx, item.inner);
+    }
+
+    fn remove_item(this: &mut WidgetMut<'_, Self>, idx: usize) {
+        this.remove_child(idx);
+    }
+
+    fn item_mut<'a>(
+        this: &'a mut WidgetMut<'_, Self>,
+        idx: usize,
+    ) -> WidgetMut<'a, widget::TreeItem> {
+        this.child_mut(idx)
+    }
+}
+
+struct TreeSplice<'w, W: TreeParent> {
+    idx: usize,
+    element: WidgetMut<'w, W>,
+    scratch: AppendVec<Pod<widget::TreeItem>>,
+}
+
+impl<'w, W: TreeParent> TreeSplice<'w, W> {
+    fn new(element: WidgetMut<'w, W>) -> Self {
+        Self {
+            idx: 0,
+            element,
+            scratch: AppendVec::default(),
+        }
+    }
+}
+
+impl<W: TreeParent> ElementSplice<Pod<widget::TreeItem>> for TreeSplice<'_, W> {
+    fn insert(&mut self, element: Pod<widget::TreeItem>) {
+        W::insert_item(&mut self.element, self.idx, element);
+        self.idx += 1;
+    }
+
+    fn with_scratch<R>(&mut self, f: impl FnOnce(&mut AppendVec<Pod<widget::TreeItem>>) -> R) -> R {
+        let mut scratch = std::mem::take(&mut self.scratch);
+        let ret = f(&mut scratch);
+        for element in scratch.drain() {
+            self.insert(element);
+        }
+        self.scratch = scratch;
+        ret
+    }
+
+    fn mutate<R>(&mut self, f: impl FnOnce(Mut<'_, Pod<widget::TreeItem>>) -> R) -> R {
+        let child = W::item_mut(&mut self.element, self.idx);
+        let ret = f(child);
+        self.idx += 1;
+        ret
+    }
+
+    fn delete<R>(&mut self, f: impl FnOnce(Mut<'_, Pod<widget::TreeItem>>) -> R) -> R {
+        let ret = {
+            let child = W::item_mut(&mut self.element, self.idx);
+            f(child)
+        };
+        W::remove_item(&mut self.element, self.idx);
+        ret
+    }
+
+    fn skip(&mut self, n: usize) {
+        self.idx += n;
+    }
+}
+
+impl SuperElement<Pod<widget::TreeItem>> for Pod<widget::TreeItem> {
+    fn upcast(child: Pod<widget::TreeItem>) -> Self {
+        child
+    }
+
+    fn with_downcast_val<R>(
+        mut this: Mut<'_, Self>,
+        f: impl FnOnce(Mut<'_, Pod<widget::TreeItem>>) -> R,
+    ) -> (Self::Mut<'_>, R) {
+        let r = f(this.reborrow_mut());
+        (this, r)
+    }
+}
+
+/// An ordered sequence of [`tree_item`]s, for a [`Tree`] view or the children of a [`TreeItem`].
+/// See [`ViewSequence`] for more technical details.
+pub trait TreeSequence<State, Action = ()>:
+    ViewSequence<State, Action, ViewCtx, Pod<widget::TreeItem>>
+{
+}
+
+impl<Seq, State, Action> TreeSequence<State, Action> for Seq where
+    Seq: ViewSequence<State, Action, ViewCtx, Pod<widget::TreeItem>>
+{
+}
+
+type AnyTreeItemInner<State, Action> =
+    TreeItem<Box<AnyWidgetView<State, Action>>, Vec<AnyTreeItem<State, Action>>, State, Action>;
+
+/// A [`tree_item`] with type-erased content, for trees built from recursive data.
+///
+/// This can be created from a [`TreeItem`] whose content is [boxed](WidgetView::boxed),
+/// and whose children are a `Vec` of `AnyTreeItem`s. See [`tree`] for an example.
+pub struct AnyTreeItem<State, Action = ()> {
+    inner: AnyTreeItemInner<State, Action>,
+}
+
+impl<State, Action> From<AnyTreeItemInner<State, Action>> for AnyTreeItem<State, Action> {
+    fn from(inner: AnyTreeItemInner<State, Action>) -> Self {
+        Self { inner }
+    }
+}
+
+/// The view state of an [`AnyTreeItem`].
+#[doc(hidden)] // Implementation detail, public because of trait visibility rules
+pub struct AnyTreeItemState<State: 'static, Action: 'static> {
+    inner: <AnyTreeItemInner<State, Action> as View<State, Action, ViewCtx>>::ViewState,
+}
+
+impl<State, Action> ViewMarker for AnyTreeItem<State, Action> {}
+impl<State, Action> View<State, Action, ViewCtx> for AnyTreeItem<State, Action>
+where
+    State: 'static,
+    Action: 'static,
+{
+    type Element = Pod<widget::TreeItem>;
+
+    type ViewState = AnyTreeItemState<State, Action>;
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        let (element, inner) = self.inner.build(ctx);
+        (element, AnyTreeItemState { inner })
+    }
+
+    fn rebuild<'el>(
+        &self,
+        prev: &Self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        element: Mut<'el, Self::Element>,
+    ) -> Mut<'el, Self::Element> {
+        self.inner
+            .rebuild(&prev.inner, &mut view_state.inner, ctx, element)
+    }
+
+    fn teardown(
+        &self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        element: Mut<'_, Self::Element>,
+    ) {
+        self.inner.teardown(&mut view_state.inner, ctx, element);
+    }
+
+    fn message(
+        &self,
+        view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: DynMessage,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        self.inner
+            .message(&mut view_state.inner, id_path, message, app_state)
+    }
+}
@@ -0,0 +1,185 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Optional serialization of [`DynMessage`]s, for recording and replaying messages.
+
+use core::any::TypeId;
+
+use alloc::{boxed::Box, collections::BTreeMap, string::String};
+
+use crate::{DynMessage, Message};
+
+type EncodeFn = Box<dyn Fn(&dyn Message) -> Option<String> + Send + Sync>;
+type DecodeFn = Box<dyn Fn(&str) -> Option<DynMessage> + Send + Sync>;
+
+struct Codec {
+    name: &'static str,
+    encode: EncodeFn,
+}
+
+/// A message which has been serialized using a [`MessageCodec`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncodedMessage {
+    /// The name the message's type was registered with.
+    pub type_name: String,
+    /// The serialized message.
+    pub data: String,
+}
+
+/// A registry of serialization functions for message types.
+///
+/// [`Message`] is implemented for all types which are `Debug` and `Send`, so it can't
+/// provide serialization itself. Instead, each message type which should be
+/// serializable (e.g. to record and replay the messages sent to an app) is registered
+/// here, together with a stable name.
+///
+/// Messages of types which have not been registered can't be encoded.
+///
+/// # Examples
+///
+/// ```
+/// use xilem_core::{DynMessage, MessageCodec};
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Increment(u32);
+///
+/// let mut codec = MessageCodec::new();
+/// codec.register(
+///     "increment",
+///     |message: &Increment| Some(message.0.to_string()),
+///     |data| data.parse().ok().map(Increment),
+/// );
+///
+/// let message: DynMessage = Box::new(Increment(3));
+/// let encoded = codec.encode(&*message).unwrap();
+/// assert_eq!(encoded.data, "3");
+/// let decoded = codec.decode(&encoded).unwrap();
+/// assert_eq!(*decoded.downcast::<Increment>().unwrap(), Increment(3));
+/// ```
+#[derive(Default)]
+pub struct MessageCodec {
+    encoders: BTreeMap<TypeId, Codec>,
+    decoders: BTreeMap<&'static str, DecodeFn>,
+}
+
+impl MessageCodec {
+    /// Create a codec without any registered message types.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the message type `T` under `name`.
+    ///
+    /// `encode` and `decode` should roundtrip, i.e. `decode(&encode(message)?)` should be
+    /// equivalent to `message`. `encode` can return `None` for messages which can't be
+    /// serialized, which are then not encoded at all.
+    /// If `T` or `name` had been registered before, the previous registration is replaced.
+    pub fn register<T: Message>(
+        &mut self,
+        name: &'static str,
+        encode: impl Fn(&T) -> Option<String> + Send + Sync + 'static,
+        decode: impl Fn(&str) -> Option<T> + Send + Sync + 'static,
+    ) -> &mut Self {
+        let encode: EncodeFn =
+            Box::new(move |message| message.as_any().downcast_ref::<T>().and_then(&encode));
+        let decode: DecodeFn =
+            Box::new(move |data| decode(data).map(|message| Box::new(message) as DynMessage));
+        self.encoders
+            .insert(TypeId::of::<T>(), Codec { name, encode });
+        self.decoders.insert(name, decode);
+        self
+    }
+
+    /// Whether messages of type `T` can be encoded.
+    pub fn is_registered<T: Message>(&self) -> bool {
+        self.encoders.contains_key(&TypeId::of::<T>())
+    }
+
+    /// Serialize `message`, if its type has been registered and it can be serialized.
+    pub fn encode(&self, message: &dyn Message) -> Option<EncodedMessage> {
+        let codec = self.encoders.get(&message.as_any().type_id())?;
+        let data = (codec.encode)(message)?;
+        Some(EncodedMessage {
+            type_name: codec.name.into(),
+            data,
+        })
+    }
+
+    /// Deserialize `message`, if its type has been registered and the data is valid.
+    pub fn decode(&self, message: &EncodedMessage) -> Option<DynMessage> {
+        let decode = self.decoders.get(message.type_name.as_str())?;
+        decode(&message.data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::boxed::Box;
+
+    use super::{EncodedMessage, MessageCodec};
+    use crate::DynMessage;
+
+    #[derive(Debug, PartialEq)]
+    struct Registered(String);
+
+    #[derive(Debug)]
+    struct Unregistered;
+
+    fn codec() -> MessageCodec {
+        let mut codec = MessageCodec::new();
+        codec.register(
+            "registered",
+            |message: &Registered| Some(message.0.clone()),
+            |data| Some(Registered(data.into())),
+        );
+        codec
+    }
+
+    #[test]
+    /// Encoding then decoding a registered message should give back the same message
+    fn roundtrip() {
+        let codec = codec();
+        let message: DynMessage = Box::new(Registered("hello".into()));
+        let encoded = codec.encode(&*message).unwrap();
+        assert_eq!(encoded.type_name, "registered");
+        let decoded = codec.decode(&encoded).unwrap();
+        assert_eq!(
+            *decoded.downcast::<Registered>().unwrap(),
+            Registered("hello".into())
+        );
+    }
+
+    #[test]
+    /// Messages of unregistered types can't be encoded
+    fn unregistered_encode() {
+        let codec = codec();
+        let message: DynMessage = Box::new(Unregistered);
+        assert!(codec.encode(&*message).is_none());
+        assert!(!codec.is_registered::<Unregistered>());
+    }
+
+    #[test]
+    /// Messages for which the encode function returns `None` aren't encoded
+    fn unencodable_encode() {
+        let mut codec = codec();
+        codec.register(
+            "unregistered",
+            |_: &Unregistered| None,
+            |_| Some(Unregistered),
+        );
+        let message: DynMessage = Box::new(Unregistered);
+        assert!(codec.is_registered::<Unregistered>());
+        assert!(codec.encode(&*message).is_none());
+    }
+
+    #[test]
+    /// Decoding an unknown type name should fail rather than panic
+    fn unknown_decode() {
+        let codec = codec();
+        let encoded = EncodedMessage {
+            type_name: "unknown".into(),
+            data: "".into(),
+        };
+        assert!(codec.decode(&encoded).is_none());
+    }
+}
@@ -19,6 +19,8 @@
 #![doc = include_str!("../README.md")]
 
 extern crate alloc;
+#[cfg(feature = "signals")]
+extern crate std;
 
 mod deferred;
 pub use deferred::{AsyncCtx, MessageProxy, PhantomView, ProxyError, RawProxy};
@@ -31,10 +33,20 @@ pub use views::{
     adapt, fork, frozen, map_action, map_state, memoize, one_of, run_once, run_once_raw, Adapt,
     AdaptThunk, Fork, Frozen, MapAction, MapState, Memoize, OrphanView, RunOnce,
 };
+#[cfg(feature = "signals")]
+pub use views::{reactive, Reactive};
+
+#[cfg(feature = "signals")]
+pub mod signal;
+#[cfg(feature = "signals")]
+pub use signal::Signal;
 
 mod message;
 pub use message::{DynMessage, Message, MessageResult};
 
+mod codec;
+pub use codec::{EncodedMessage, MessageCodec};
+
 mod element;
 pub use element::{AnyElement, Mut, NoElement, SuperElement, ViewElement};
 
@@ -0,0 +1,289 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Observable cells, which allow fine-grained updates of the view tree.
+//!
+//! A [`Signal`] is a shared, mutable value.
+//! When a signal is read inside a [`reactive`](crate::reactive) view, that view is recorded as depending on it.
+//! On subsequent rebuilds, `reactive` views whose dependencies (including those of nested `reactive` views)
+//! have not been [`set`](Signal::set) are skipped entirely, and views whose own dependencies have not changed
+//! will not recreate their subtree.
+//!
+//! This module is only available with the `signals` feature, as it requires the standard library.
+
+use core::{
+    fmt::Debug,
+    sync::atomic::{AtomicU64, Ordering},
+};
+use std::{
+    cell::RefCell,
+    sync::{Arc, RwLock},
+};
+
+use alloc::vec::Vec;
+
+/// A shared, observable value.
+///
+/// Cloning a `Signal` creates a new handle to the same value.
+/// Reads using [`get`](Signal::get) or [`with`](Signal::with) are tracked by the
+/// enclosing [`reactive`](crate::reactive) view, if any.
+///
+/// Note that setting a signal does not by itself schedule a rebuild.
+/// Signals are expected to be updated by message handlers, after which the driver
+/// rebuilds the view tree as usual, but only the views depending on the signal are recreated.
+/// Drivers can also skip recreating the view tree, and instead rebuild the existing tree against
+/// itself, so that updating a signal only rebuilds the views depending on it.
+/// In Xilem, this is enabled using `Xilem::fine_grained`.
+pub struct Signal<T> {
+    inner: Arc<SignalInner<T>>,
+}
+
+struct SignalInner<T> {
+    version: AtomicU64,
+    value: RwLock<T>,
+}
+
+impl<T> Clone for Signal<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: Debug + Send + Sync + 'static> Debug for Signal<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.with_untracked(|value| f.debug_tuple("Signal").field(value).finish())
+    }
+}
+
+impl<T: Default + Send + Sync + 'static> Default for Signal<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: Send + Sync + 'static> Signal<T> {
+    /// Create a new signal containing `value`.
+    pub fn new(value: T) -> Self {
+        Self {
+            inner: Arc::new(SignalInner {
+                version: AtomicU64::new(0),
+                value: RwLock::new(value),
+            }),
+        }
+    }
+
+    /// Access the current value, recording this signal as a dependency
+    /// of the enclosing [`reactive`](crate::reactive) view.
+    ///
+    /// # Panics
+    ///
+    /// If the signal is already being accessed on the same thread, i.e. within [`with`](Signal::with)
+    /// or [`update`](Signal::update) of the same signal, as that could deadlock.
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        record(Dependency::new(self));
+        self.with_untracked(f)
+    }
+
+    /// Access the current value, without recording a dependency.
+    ///
+    /// # Panics
+    ///
+    /// If the signal is already being accessed on the same thread, i.e. within [`with`](Signal::with)
+    /// or [`update`](Signal::update) of the same signal, as that could deadlock.
+    pub fn with_untracked<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        let _guard = AccessGuard::new(self);
+        let value = self.inner.value.read().unwrap();
+        f(&value)
+    }
+
+    /// Get a clone of the current value, recording this signal as a dependency
+    /// of the enclosing [`reactive`](crate::reactive) view.
+    ///
+    /// # Panics
+    ///
+    /// If the signal is already being accessed on the same thread, i.e. within [`with`](Signal::with)
+    /// or [`update`](Signal::update) of the same signal, as that could deadlock.
+    pub fn get(&self) -> T
+    where
+        T: Clone,
+    {
+        self.with(T::clone)
+    }
+
+    /// Replace the current value, marking all views which depend on this signal as changed.
+    pub fn set(&self, value: T) {
+        self.update(|it| *it = value);
+    }
+
+    /// Modify the current value in place, marking all views which depend on this signal as changed.
+    ///
+    /// # Panics
+    ///
+    /// If the signal is accessed from within `f`, or if it is already being accessed on
+    /// the same thread, as that could deadlock.
+    pub fn update<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        let _guard = AccessGuard::new(self);
+        let mut value = self.inner.value.write().unwrap();
+        let ret = f(&mut value);
+        self.inner.version.fetch_add(1, Ordering::Release);
+        ret
+    }
+
+    /// Whether `self` and `other` are handles to the same value.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+std::thread_local! {
+    /// The addresses of the signals which are being accessed on this thread.
+    static ACCESSING: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Records that a signal is being accessed on this thread whilst alive, to detect
+/// re-entrant access, which could deadlock on the lock of the signal.
+struct AccessGuard(usize);
+
+impl AccessGuard {
+    fn new<T>(signal: &Signal<T>) -> Self {
+        let address = Arc::as_ptr(&signal.inner) as usize;
+        ACCESSING.with_borrow_mut(|accessing| {
+            assert!(
+                !accessing.contains(&address),
+                "a signal was accessed whilst it was already being accessed on the same thread"
+            );
+            accessing.push(address);
+        });
+        Self(address)
+    }
+}
+
+impl Drop for AccessGuard {
+    fn drop(&mut self) {
+        ACCESSING.with_borrow_mut(|accessing| {
+            if let Some(index) = accessing.iter().rposition(|it| *it == self.0) {
+                accessing.remove(index);
+            }
+        });
+    }
+}
+
+/// Type erased access to the version of a signal.
+trait Versioned: Send + Sync {
+    fn version(&self) -> u64;
+}
+
+impl<T: Send + Sync> Versioned for SignalInner<T> {
+    fn version(&self) -> u64 {
+        self.version.load(Ordering::Acquire)
+    }
+}
+
+/// A signal which was read, and the version it had at that point.
+#[derive(Clone)]
+struct Dependency {
+    source: Arc<dyn Versioned>,
+    version: u64,
+}
+
+impl Dependency {
+    fn new<T: Send + Sync + 'static>(signal: &Signal<T>) -> Self {
+        let source: Arc<dyn Versioned> = signal.inner.clone();
+        let version = source.version();
+        Self { source, version }
+    }
+
+    fn is_changed(&self) -> bool {
+        self.source.version() != self.version
+    }
+}
+
+/// The set of signals read during some computation.
+#[derive(Clone, Default)]
+pub(crate) struct Dependencies(Vec<Dependency>);
+
+impl Dependencies {
+    /// Whether any of the signals in this set has been updated since it was read.
+    pub(crate) fn is_changed(&self) -> bool {
+        self.0.iter().any(Dependency::is_changed)
+    }
+
+    fn insert(&mut self, dependency: Dependency) {
+        if !self
+            .0
+            .iter()
+            .any(|it| Arc::ptr_eq(&it.source, &dependency.source))
+        {
+            self.0.push(dependency);
+        }
+    }
+
+    /// Record all signals in this set as dependencies of the enclosing scope.
+    pub(crate) fn propagate(&self) {
+        for dependency in &self.0 {
+            record(dependency.clone());
+        }
+    }
+}
+
+std::thread_local! {
+    static SCOPES: RefCell<Vec<Dependencies>> = const { RefCell::new(Vec::new()) };
+}
+
+fn record(dependency: Dependency) {
+    SCOPES.with_borrow_mut(|scopes| {
+        if let Some(scope) = scopes.last_mut() {
+            scope.insert(dependency);
+        }
+    });
+}
+
+/// Run `f`, returning the signals which were read whilst it was running.
+pub(crate) fn track<R>(f: impl FnOnce() -> R) -> (R, Dependencies) {
+    SCOPES.with_borrow_mut(|scopes| scopes.push(Dependencies::default()));
+    let scope = ScopeGuard;
+    let ret = f();
+    (ret, scope.finish())
+}
+
+/// Pops the innermost tracking scope when dropped, so that it is also popped if the tracked function panics.
+struct ScopeGuard;
+
+impl ScopeGuard {
+    fn finish(self) -> Dependencies {
+        core::mem::forget(self);
+        pop_scope()
+    }
+}
+
+impl Drop for ScopeGuard {
+    fn drop(&mut self) {
+        pop_scope();
+    }
+}
+
+fn pop_scope() -> Dependencies {
+    SCOPES
+        .with_borrow_mut(Vec::pop)
+        .expect("Each tracking scope should be popped exactly once")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{track, Signal, SCOPES};
+
+    #[test]
+    /// The tracking scope should be popped even if the tracked function panics
+    fn track_panic_pops_scope() {
+        let result = std::panic::catch_unwind(|| track(|| panic!("the view function failed")));
+        assert!(result.is_err());
+        assert_eq!(SCOPES.with_borrow(Vec::len), 0);
+
+        let signal = Signal::new(0);
+        let ((), dependencies) = track(|| signal.with(|_| ()));
+        assert_eq!(dependencies.0.len(), 1);
+        assert_eq!(SCOPES.with_borrow(Vec::len), 0);
+    }
+}
@@ -19,6 +19,11 @@ pub use fork::{fork, Fork};
 mod memoize;
 pub use memoize::{frozen, memoize, Frozen, Memoize};
 
+#[cfg(feature = "signals")]
+mod reactive;
+#[cfg(feature = "signals")]
+pub use reactive::{reactive, Reactive};
+
 pub mod one_of;
 
 mod orphan;
@@ -0,0 +1,158 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use core::marker::PhantomData;
+
+use crate::signal::{track, Dependencies};
+use crate::{MessageResult, Mut, View, ViewId, ViewMarker, ViewPathTracker};
+
+/// A view which is only recreated when the [`Signal`](crate::Signal)s it reads change.
+///
+/// See [`reactive`] for details.
+pub struct Reactive<ViewFn, State, Action> {
+    view_fn: ViewFn,
+    phantom: PhantomData<fn() -> (State, Action)>,
+}
+
+/// Create the view returned by `view_fn`, tracking which [`Signal`](crate::Signal)s it reads.
+///
+/// On rebuild, `view_fn` is only called again if any of the signals it read have been
+/// [`set`](crate::Signal::set) since, in which case the resulting view is diffed against the previous one.
+/// If only signals read by nested `reactive` views have changed, the previous view is diffed against
+/// itself, so that only those nested views are recreated.
+/// If none of these signals have changed, rebuilding this view does nothing at all.
+///
+/// As with [`memoize`](crate::memoize), `view_fn` should not capture any other changing data,
+/// as changes to that data will not be noticed. Instead, the data should be stored in signals.
+///
+/// This view interoperates with the rest of the view tree: messages are routed to the child as usual,
+/// and a [`MessageResult::RequestRebuild`] from the child forces the child to be recreated.
+///
+/// # Examples
+///
+/// (From the Xilem implementation)
+///
+/// ```ignore
+/// fn counter(count: &Signal<u32>) -> impl WidgetView<AppState> {
+///     let count = count.clone();
+///     reactive(move || label(format!("Count: {}", count.get())))
+/// }
+/// ```
+pub fn reactive<State, Action, Context, Message, V, ViewFn>(
+    view_fn: ViewFn,
+) -> Reactive<ViewFn, State, Action>
+where
+    ViewFn: Fn() -> V + 'static,
+    V: View<State, Action, Context, Message>,
+    Context: ViewPathTracker,
+{
+    Reactive {
+        view_fn,
+        phantom: PhantomData,
+    }
+}
+
+#[doc(hidden)] // Implementation detail, public because of trait visibility rules
+pub struct ReactiveState<V, VState> {
+    view: V,
+    view_state: VState,
+    /// The signals read by `view_fn` when creating `view`.
+    own: Dependencies,
+    /// The signals read whilst building or rebuilding `view`, i.e. by nested reactive views.
+    children: Dependencies,
+    dirty: bool,
+}
+
+impl<V, VState> ReactiveState<V, VState> {
+    fn propagate(&self) {
+        self.own.propagate();
+        self.children.propagate();
+    }
+}
+
+impl<ViewFn, State, Action> ViewMarker for Reactive<ViewFn, State, Action> {}
+impl<State, Action, Context, Message, V, ViewFn> View<State, Action, Context, Message>
+    for Reactive<ViewFn, State, Action>
+where
+    State: 'static,
+    Action: 'static,
+    Context: ViewPathTracker,
+    V: View<State, Action, Context, Message>,
+    ViewFn: Fn() -> V + 'static,
+{
+    type Element = V::Element;
+
+    type ViewState = ReactiveState<V, V::ViewState>;
+
+    fn build(&self, ctx: &mut Context) -> (Self::Element, Self::ViewState) {
+        let (view, own) = track(&self.view_fn);
+        let ((element, view_state), children) = track(|| view.build(ctx));
+        let state = ReactiveState {
+            view,
+            view_state,
+            own,
+            children,
+            dirty: false,
+        };
+        state.propagate();
+        (element, state)
+    }
+
+    fn rebuild<'el>(
+        &self,
+        _prev: &Self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut Context,
+        element: Mut<'el, Self::Element>,
+    ) -> Mut<'el, Self::Element> {
+        let element = if core::mem::take(&mut view_state.dirty) || view_state.own.is_changed() {
+            let (view, own) = track(&self.view_fn);
+            let (element, children) =
+                track(|| view.rebuild(&view_state.view, &mut view_state.view_state, ctx, element));
+            view_state.view = view;
+            view_state.own = own;
+            view_state.children = children;
+            element
+        } else if view_state.children.is_changed() {
+            let (element, children) = track(|| {
+                view_state
+                    .view
+                    .rebuild(&view_state.view, &mut view_state.view_state, ctx, element)
+            });
+            view_state.children = children;
+            element
+        } else {
+            element
+        };
+        view_state.propagate();
+        element
+    }
+
+    fn teardown(
+        &self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut Context,
+        element: Mut<'_, Self::Element>,
+    ) {
+        view_state
+            .view
+            .teardown(&mut view_state.view_state, ctx, element);
+    }
+
+    fn message(
+        &self,
+        view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: Message,
+        app_state: &mut State,
+    ) -> MessageResult<Action, Message> {
+        let message_result =
+            view_state
+                .view
+                .message(&mut view_state.view_state, id_path, message, app_state);
+        if matches!(message_result, MessageResult::RequestRebuild) {
+            view_state.dirty = true;
+        }
+        message_result
+    }
+}
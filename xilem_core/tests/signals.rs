@@ -0,0 +1,216 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tests for the behaviour of [`reactive`] views depending on [`Signal`]s.
+//!
+//! This is an integration test so that it can use the infrastructure in [`common`].
+
+use xilem_core::{reactive, MessageResult, Signal, View};
+
+mod common;
+use common::*;
+
+fn record_ops(id: u32) -> OperationView<0> {
+    OperationView(id)
+}
+
+#[test]
+/// A reactive view should be recreated if a signal it read has changed
+fn changed_signal_rebuild() {
+    let signal = Signal::new(0);
+    let view = {
+        let signal = signal.clone();
+        reactive(move || record_ops(signal.get()))
+    };
+    let mut ctx = TestCtx::default();
+    let (mut element, mut state) = view.build(&mut ctx);
+    ctx.assert_empty();
+    assert_eq!(element.operations, &[Operation::Build(0)]);
+
+    signal.set(1);
+    view.rebuild(&view, &mut state, &mut ctx, &mut element);
+    ctx.assert_empty();
+    assert_eq!(
+        element.operations,
+        &[Operation::Build(0), Operation::Rebuild { from: 0, to: 1 }]
+    );
+}
+
+#[test]
+/// A reactive view shouldn't be rebuilt if none of the signals it read have changed
+fn unchanged_signal_skip_rebuild() {
+    let read = Signal::new(0);
+    let unread = Signal::new(0);
+    let view = {
+        let read = read.clone();
+        reactive(move || record_ops(read.get()))
+    };
+    let mut ctx = TestCtx::default();
+    let (mut element, mut state) = view.build(&mut ctx);
+    ctx.assert_empty();
+
+    unread.set(1);
+    view.rebuild(&view, &mut state, &mut ctx, &mut element);
+    ctx.assert_empty();
+    assert_eq!(element.operations, &[Operation::Build(0)]);
+}
+
+#[test]
+/// Signals read without tracking shouldn't cause a rebuild
+fn untracked_read_skip_rebuild() {
+    let signal = Signal::new(0);
+    let view = {
+        let signal = signal.clone();
+        reactive(move || record_ops(signal.with_untracked(|it| *it)))
+    };
+    let mut ctx = TestCtx::default();
+    let (mut element, mut state) = view.build(&mut ctx);
+
+    signal.set(1);
+    view.rebuild(&view, &mut state, &mut ctx, &mut element);
+    ctx.assert_empty();
+    assert_eq!(element.operations, &[Operation::Build(0)]);
+}
+
+#[test]
+/// When only a signal read by a nested reactive view changes, only that view should be recreated
+fn nested_signal_rebuild_only_dependent() {
+    let outer = Signal::new(0);
+    let inner = Signal::new(10);
+    let outer_calls = Signal::new(0);
+    let view = {
+        let outer = outer.clone();
+        let inner = inner.clone();
+        let outer_calls = outer_calls.clone();
+        reactive(move || {
+            outer_calls.update(|it| *it += 1);
+            let inner = inner.clone();
+            sequence(
+                outer.get(),
+                (reactive(move || record_ops(inner.get())), record_ops(20)),
+            )
+        })
+    };
+    let mut ctx = TestCtx::default();
+    let (mut element, mut state) = view.build(&mut ctx);
+    ctx.assert_empty();
+    assert_eq!(outer_calls.with_untracked(|it| *it), 1);
+
+    inner.set(11);
+    view.rebuild(&view, &mut state, &mut ctx, &mut element);
+    ctx.assert_empty();
+    // The outer view function shouldn't have been called again
+    assert_eq!(outer_calls.with_untracked(|it| *it), 1);
+    let children = &element.children.as_ref().unwrap().active;
+    assert_eq!(
+        children[0].operations,
+        &[
+            Operation::Build(10),
+            Operation::Rebuild { from: 10, to: 11 }
+        ]
+    );
+    // The sibling is "rebuilt" against itself, which is a no-op for real views
+    assert_eq!(
+        children[1].operations,
+        &[
+            Operation::Build(20),
+            Operation::Rebuild { from: 20, to: 20 }
+        ]
+    );
+
+    outer.set(1);
+    view.rebuild(&view, &mut state, &mut ctx, &mut element);
+    ctx.assert_empty();
+    assert_eq!(outer_calls.with_untracked(|it| *it), 2);
+    assert_eq!(
+        element.operations.last(),
+        Some(&Operation::Rebuild { from: 0, to: 1 })
+    );
+}
+
+#[test]
+/// A `RequestRebuild` from the child should force the view function to be called again
+fn request_rebuild_forces_rebuild() {
+    struct RequestRebuild;
+    impl xilem_core::ViewMarker for RequestRebuild {}
+    impl View<(), Action, TestCtx> for RequestRebuild {
+        type Element = TestElement;
+        type ViewState = ();
+
+        fn build(&self, ctx: &mut TestCtx) -> (Self::Element, Self::ViewState) {
+            record_ops(0).build(ctx)
+        }
+
+        fn rebuild<'el>(
+            &self,
+            _: &Self,
+            (): &mut Self::ViewState,
+            ctx: &mut TestCtx,
+            element: xilem_core::Mut<'el, Self::Element>,
+        ) -> xilem_core::Mut<'el, Self::Element> {
+            record_ops(0).rebuild(&record_ops(0), &mut (), ctx, element)
+        }
+
+        fn teardown(
+            &self,
+            (): &mut Self::ViewState,
+            _: &mut TestCtx,
+            _: xilem_core::Mut<'_, Self::Element>,
+        ) {
+        }
+
+        fn message(
+            &self,
+            (): &mut Self::ViewState,
+            _: &[xilem_core::ViewId],
+            _: xilem_core::DynMessage,
+            _: &mut (),
+        ) -> MessageResult<Action> {
+            MessageResult::RequestRebuild
+        }
+    }
+
+    let view = reactive(|| RequestRebuild);
+    let mut ctx = TestCtx::default();
+    let (mut element, mut state) = view.build(&mut ctx);
+    let result = view.message(&mut state, &[], Box::new(()), &mut ());
+    assert!(matches!(result, MessageResult::RequestRebuild));
+
+    view.rebuild(&view, &mut state, &mut ctx, &mut element);
+    ctx.assert_empty();
+    assert_eq!(
+        element.operations,
+        &[Operation::Build(0), Operation::Rebuild { from: 0, to: 0 }]
+    );
+}
+
+#[test]
+#[should_panic(expected = "already being accessed on the same thread")]
+/// Updating a signal whilst reading it would deadlock, so should panic instead
+fn update_within_with_panics() {
+    let signal = Signal::new(0);
+    signal.with(|_| signal.set(1));
+}
+
+#[test]
+#[should_panic(expected = "already being accessed on the same thread")]
+/// Reading a signal whilst updating it would deadlock, so should panic instead
+fn with_within_update_panics() {
+    let signal = Signal::new(0);
+    signal.update(|_| signal.get());
+}
+
+#[test]
+/// Accessing different signals within each other, or the same signal after an access has ended, is fine
+fn sequential_and_nested_access() {
+    let first = Signal::new(1);
+    let second = Signal::new(2);
+    first.update(|first| *first += second.get());
+    second.set(first.get());
+    assert_eq!(second.get(), 3);
+
+    // A panic within an access shouldn't leave the signal marked as being accessed.
+    let result = std::panic::catch_unwind(|| second.with(|_| panic!("the reader failed")));
+    assert!(result.is_err());
+    first.with(|_| second.with_untracked(|_| ()));
+}
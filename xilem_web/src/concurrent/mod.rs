@@ -11,3 +11,6 @@ pub use interval::{interval, Interval};
 
 mod memoized_await;
 pub use memoized_await::{memoized_await, MemoizedAwait};
+
+mod stream;
+pub use stream::{stream, StreamView};
@@ -0,0 +1,168 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::marker::PhantomData;
+
+use futures::{
+    stream::{abortable, AbortHandle},
+    Stream, StreamExt,
+};
+use wasm_bindgen::UnwrapThrowExt;
+use wasm_bindgen_futures::spawn_local;
+use xilem_core::{MessageResult, Mut, NoElement, View, ViewId, ViewMarker, ViewPathTracker};
+
+use crate::{DynMessage, Message, OptionalAction, ViewCtx};
+
+/// Subscribe to a [`Stream`] whilst this view is in the tree, use [`stream`] for construction of this [`View`]
+pub struct StreamView<State, Action, OA, Key, MakeStream, Callback, M> {
+    key: Key,
+    make_stream: MakeStream,
+    callback: Callback,
+    #[allow(clippy::type_complexity)]
+    phantom: PhantomData<fn() -> (State, Action, OA, M)>,
+}
+
+/// Subscribe to the [`Stream`] returned by `make_stream` invoked with the argument `key`, `callback` is called with every item of the stream.
+///
+/// When `key` changes, the previous subscription is dropped, and `make_stream` is invoked again.
+/// Items of a previous subscription are discarded, and the subscription is dropped when this view is removed from the tree.
+///
+/// # Examples
+///
+/// ```
+/// use xilem_web::{core::fork, concurrent::stream, elements::html::div, interfaces::Element};
+///
+/// fn app_logic(state: &mut Vec<u32>) -> impl Element<Vec<u32>> {
+///     fork(
+///         div(format!("{state:?}")),
+///         stream(
+///             3, // when this changes, the stream is recreated
+///             |count| futures::stream::iter(0..*count),
+///             |state: &mut Vec<u32>, item| state.push(item),
+///         )
+///     )
+/// }
+/// ```
+pub fn stream<State, Action, OA, Key, MakeStream, Callback, S>(
+    key: Key,
+    make_stream: MakeStream,
+    callback: Callback,
+) -> StreamView<State, Action, OA, Key, MakeStream, Callback, S::Item>
+where
+    State: 'static,
+    Action: 'static,
+    Key: PartialEq + 'static,
+    S: Stream + 'static,
+    S::Item: Message,
+    MakeStream: Fn(&Key) -> S + 'static,
+    OA: OptionalAction<Action> + 'static,
+    Callback: Fn(&mut State, S::Item) -> OA + 'static,
+{
+    StreamView {
+        key,
+        make_stream,
+        callback,
+        phantom: PhantomData,
+    }
+}
+
+/// The state of a [`StreamView`], which keeps track of the current subscription.
+pub struct StreamState {
+    generation: u64,
+    abort_handle: AbortHandle,
+}
+
+impl<State, Action, OA, Key, MakeStream, Callback, M, S>
+    StreamView<State, Action, OA, Key, MakeStream, Callback, M>
+where
+    S: Stream<Item = M> + 'static,
+    M: Message,
+    MakeStream: Fn(&Key) -> S,
+{
+    fn subscribe(&self, ctx: &mut ViewCtx, generation: u64) -> AbortHandle {
+        ctx.with_id(ViewId::new(generation), |ctx| {
+            let thunk = ctx.message_thunk();
+            let (stream, abort_handle) = abortable((self.make_stream)(&self.key));
+            spawn_local(async move {
+                let mut stream = std::pin::pin!(stream);
+                while let Some(item) = stream.next().await {
+                    thunk.push_message(item);
+                }
+            });
+            abort_handle
+        })
+    }
+}
+
+impl<State, Action, OA, Key, MakeStream, Callback, M> ViewMarker
+    for StreamView<State, Action, OA, Key, MakeStream, Callback, M>
+{
+}
+impl<State, Action, OA, Key, MakeStream, Callback, M, S> View<State, Action, ViewCtx, DynMessage>
+    for StreamView<State, Action, OA, Key, MakeStream, Callback, M>
+where
+    State: 'static,
+    Action: 'static,
+    Key: PartialEq + 'static,
+    S: Stream<Item = M> + 'static,
+    M: Message,
+    MakeStream: Fn(&Key) -> S + 'static,
+    OA: OptionalAction<Action> + 'static,
+    Callback: Fn(&mut State, M) -> OA + 'static,
+{
+    type Element = NoElement;
+
+    type ViewState = StreamState;
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        let generation = 0;
+        let abort_handle = self.subscribe(ctx, generation);
+        let state = StreamState {
+            generation,
+            abort_handle,
+        };
+        (NoElement, state)
+    }
+
+    fn rebuild<'el>(
+        &self,
+        prev: &Self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        (): Mut<'el, Self::Element>,
+    ) -> Mut<'el, Self::Element> {
+        if prev.key != self.key {
+            view_state.abort_handle.abort();
+            view_state.generation += 1;
+            view_state.abort_handle = self.subscribe(ctx, view_state.generation);
+        }
+    }
+
+    fn teardown(
+        &self,
+        view_state: &mut Self::ViewState,
+        _: &mut ViewCtx,
+        (): Mut<'_, Self::Element>,
+    ) {
+        view_state.abort_handle.abort();
+    }
+
+    fn message(
+        &self,
+        view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: DynMessage,
+        app_state: &mut State,
+    ) -> MessageResult<Action, DynMessage> {
+        assert_eq!(id_path.len(), 1);
+        if id_path[0].routing_id() == view_state.generation {
+            let item = *message.downcast::<M>().unwrap_throw();
+            match (self.callback)(app_state, item).action() {
+                Some(action) => MessageResult::Action(action),
+                None => MessageResult::Nop,
+            }
+        } else {
+            MessageResult::Stale(message)
+        }
+    }
+}